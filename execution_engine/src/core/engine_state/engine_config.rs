@@ -10,7 +10,9 @@ use num_traits::One;
 
 use casper_types::{account::AccountHash, PublicKey};
 
-use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
+use crate::shared::{
+    module_cache::ModuleCache, system_config::SystemConfig, wasm_config::WasmConfig,
+};
 
 pub use self::{fee_handling::FeeHandling, refund_handling::RefundHandling};
 
@@ -47,6 +49,10 @@ pub const DEFAULT_REFUND_HANDLING: RefundHandling = RefundHandling::Refund {
 };
 /// Default fee handling.
 pub const DEFAULT_FEE_HANDLING: FeeHandling = FeeHandling::PayToProposer;
+/// Default maximum number of cached parsed Wasm modules of stored contracts.
+pub const DEFAULT_MODULE_CACHE_SIZE: usize = 0;
+/// Default value for allowing the host functions verifying zero-knowledge proofs.
+pub const DEFAULT_ALLOW_ZK_HOST_FUNCTIONS: bool = false;
 
 /// The runtime configuration of the execution engine
 #[derive(Debug, Clone)]
@@ -81,6 +87,10 @@ pub struct EngineConfig {
     pub(crate) refund_handling: RefundHandling,
     /// Fee handling.
     pub(crate) fee_handling: FeeHandling,
+    /// Cache of parsed Wasm modules of stored contracts, shared by all clones of this config.
+    module_cache: ModuleCache,
+    /// Whether contracts may import the host functions verifying zero-knowledge proofs.
+    allow_zk_host_functions: bool,
 }
 
 impl Default for EngineConfig {
@@ -100,6 +110,8 @@ impl Default for EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            module_cache: ModuleCache::new(DEFAULT_MODULE_CACHE_SIZE),
+            allow_zk_host_functions: DEFAULT_ALLOW_ZK_HOST_FUNCTIONS,
         }
     }
 }
@@ -140,6 +152,8 @@ impl EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            module_cache: ModuleCache::new(DEFAULT_MODULE_CACHE_SIZE),
+            allow_zk_host_functions: DEFAULT_ALLOW_ZK_HOST_FUNCTIONS,
         }
     }
 
@@ -212,6 +226,24 @@ impl EngineConfig {
     pub fn fee_handling(&self) -> FeeHandling {
         self.fee_handling
     }
+
+    /// Returns the cache of parsed Wasm modules of stored contracts.
+    pub fn module_cache(&self) -> &ModuleCache {
+        &self.module_cache
+    }
+
+    /// Returns true if contracts may import the host functions verifying zero-knowledge proofs.
+    pub fn allow_zk_host_functions(&self) -> bool {
+        self.allow_zk_host_functions
+    }
+
+    /// Sets whether contracts may import the host functions verifying zero-knowledge proofs.
+    ///
+    /// Unlike the other options, this one is activated at an era boundary rather than with a
+    /// protocol upgrade, so the node sets it for each block it executes.
+    pub fn set_allow_zk_host_functions(&mut self, allow_zk_host_functions: bool) {
+        self.allow_zk_host_functions = allow_zk_host_functions;
+    }
 }
 
 /// A builder for an [`EngineConfig`].
@@ -234,6 +266,8 @@ pub struct EngineConfigBuilder {
     allow_unrestricted_transfers: Option<bool>,
     refund_handling: Option<RefundHandling>,
     fee_handling: Option<FeeHandling>,
+    module_cache_size: Option<usize>,
+    allow_zk_host_functions: Option<bool>,
 }
 
 impl EngineConfigBuilder {
@@ -348,6 +382,18 @@ impl EngineConfigBuilder {
         self
     }
 
+    /// Sets the maximum number of cached parsed Wasm modules of stored contracts.
+    pub fn with_module_cache_size(mut self, module_cache_size: usize) -> Self {
+        self.module_cache_size = Some(module_cache_size);
+        self
+    }
+
+    /// Sets the allow zk host functions config option.
+    pub fn with_allow_zk_host_functions(mut self, allow_zk_host_functions: bool) -> Self {
+        self.allow_zk_host_functions = Some(allow_zk_host_functions);
+        self
+    }
+
     /// Builds a new [`EngineConfig`] object.
     pub fn build(self) -> EngineConfig {
         let max_query_depth = self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH);
@@ -377,6 +423,11 @@ impl EngineConfigBuilder {
             .unwrap_or(DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS);
         let refund_handling = self.refund_handling.unwrap_or(DEFAULT_REFUND_HANDLING);
         let fee_handling = self.fee_handling.unwrap_or(DEFAULT_FEE_HANDLING);
+        let module_cache =
+            ModuleCache::new(self.module_cache_size.unwrap_or(DEFAULT_MODULE_CACHE_SIZE));
+        let allow_zk_host_functions = self
+            .allow_zk_host_functions
+            .unwrap_or(DEFAULT_ALLOW_ZK_HOST_FUNCTIONS);
 
         let strict_argument_checking = self
             .strict_argument_checking
@@ -398,6 +449,8 @@ impl EngineConfigBuilder {
             allow_unrestricted_transfers,
             refund_handling,
             fee_handling,
+            module_cache,
+            allow_zk_host_functions,
             strict_argument_checking,
             vesting_schedule_period_millis,
             max_delegators_per_validator,
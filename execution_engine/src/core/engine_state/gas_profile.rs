@@ -0,0 +1,118 @@
+//! A breakdown of the gas charged while executing a deploy.
+use std::collections::BTreeMap;
+
+use casper_types::Gas;
+
+/// What gas has been charged for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GasProfileItem {
+    /// Executing Wasm instructions.
+    WasmInstructions,
+    /// Calling the host function imported under the given name.
+    HostFunction(&'static str),
+    /// Storing data in global state.
+    Storage,
+    /// Calling a system contract entry point.
+    SystemContractCall,
+}
+
+/// The number of calls to a host function and the gas charged for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HostFunctionUsage {
+    /// The number of calls.
+    pub calls: u64,
+    /// The gas charged for all calls.
+    pub cost: Gas,
+}
+
+/// A breakdown of the gas charged while executing a deploy.
+///
+/// Gas charged outside of the execution of the payment and session code, e.g. the fixed cost of a
+/// native transfer, is not included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasProfile {
+    /// Gas charged for executing Wasm instructions.
+    wasm_instructions: Gas,
+    /// Calls and gas charged for each host function, keyed by the name it is imported under.
+    host_functions: BTreeMap<&'static str, HostFunctionUsage>,
+    /// Gas charged for storing data in global state.
+    storage: Gas,
+    /// Gas charged for calling system contract entry points.
+    system_contract_calls: Gas,
+}
+
+impl GasProfile {
+    /// Returns the gas charged for executing Wasm instructions.
+    pub fn wasm_instructions(&self) -> Gas {
+        self.wasm_instructions
+    }
+
+    /// Returns the calls and gas charged for each host function, keyed by the name it is imported
+    /// under.
+    pub fn host_functions(&self) -> &BTreeMap<&'static str, HostFunctionUsage> {
+        &self.host_functions
+    }
+
+    /// Returns the gas charged for storing data in global state.
+    pub fn storage(&self) -> Gas {
+        self.storage
+    }
+
+    /// Returns the gas charged for calling system contract entry points.
+    pub fn system_contract_calls(&self) -> Gas {
+        self.system_contract_calls
+    }
+
+    /// Returns the total gas charged.
+    pub fn total(&self) -> Gas {
+        self.host_functions.values().fold(
+            self.wasm_instructions + self.storage + self.system_contract_calls,
+            |total, usage| total + usage.cost,
+        )
+    }
+
+    /// Records `cost` having been charged for `item`.
+    pub(crate) fn record(&mut self, item: GasProfileItem, cost: Gas) {
+        match item {
+            GasProfileItem::WasmInstructions => self.wasm_instructions += cost,
+            GasProfileItem::HostFunction(name) => {
+                let usage = self.host_functions.entry(name).or_default();
+                usage.calls += 1;
+                usage.cost += cost;
+            }
+            GasProfileItem::Storage => self.storage += cost,
+            GasProfileItem::SystemContractCall => self.system_contract_calls += cost,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::Gas;
+
+    use super::{GasProfile, GasProfileItem};
+
+    #[test]
+    fn should_sum_up_recorded_costs() {
+        let mut gas_profile = GasProfile::default();
+        gas_profile.record(GasProfileItem::WasmInstructions, Gas::new(10.into()));
+        gas_profile.record(
+            GasProfileItem::HostFunction("casper_read_value"),
+            Gas::new(3.into()),
+        );
+        gas_profile.record(
+            GasProfileItem::HostFunction("casper_read_value"),
+            Gas::new(4.into()),
+        );
+        gas_profile.record(GasProfileItem::Storage, Gas::new(20.into()));
+        gas_profile.record(GasProfileItem::WasmInstructions, Gas::new(5.into()));
+
+        assert_eq!(gas_profile.wasm_instructions(), Gas::new(15.into()));
+        assert_eq!(gas_profile.storage(), Gas::new(20.into()));
+        assert_eq!(gas_profile.system_contract_calls(), Gas::default());
+        let usage = gas_profile.host_functions()["casper_read_value"];
+        assert_eq!(usage.calls, 2);
+        assert_eq!(usage.cost, Gas::new(7.into()));
+        assert_eq!(gas_profile.total(), Gas::new(42.into()));
+    }
+}
@@ -10,12 +10,14 @@ pub mod executable_deploy_item;
 pub mod execute_request;
 pub mod execution_effect;
 pub mod execution_result;
+pub mod gas_profile;
 pub mod genesis;
 pub mod get_bids;
 pub mod op;
 mod prune;
 pub mod query;
 pub mod run_genesis_request;
+pub mod state_diff;
 pub mod step;
 pub mod system_contract_registry;
 mod transfer;
@@ -68,11 +70,13 @@ pub use self::{
     execute_request::ExecuteRequest,
     execution::Error as ExecError,
     execution_result::{ExecutionResult, ForcedTransferResult},
+    gas_profile::{GasProfile, HostFunctionUsage},
     genesis::{ExecConfig, GenesisAccount, GenesisConfig, GenesisSuccess},
     get_bids::{GetBidsRequest, GetBidsResult},
     prune::{PruneConfig, PruneResult},
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
+    state_diff::{StateChange, StateDiffResult},
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_registry::SystemContractRegistry,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
@@ -98,7 +102,7 @@ use crate::{
             StateReader,
         },
         trie::{merkle_proof::TrieMerkleProof, TrieRaw},
-        trie_store::operations::DeleteResult,
+        trie_store::operations::{DeleteResult, DiffResult},
     },
     system::auction,
 };
@@ -167,6 +171,36 @@ impl EngineState<LmdbGlobalState> {
             .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)
             .map_err(Into::into)
     }
+
+    /// Returns the changes of global state needed to get from the state under `from_root` to the
+    /// state under `to_root`.
+    pub fn get_state_diff(
+        &self,
+        correlation_id: CorrelationId,
+        from_root: Digest,
+        to_root: Digest,
+    ) -> Result<StateDiffResult, Error> {
+        let value_changes = match self.state.diff(correlation_id, from_root, to_root)? {
+            DiffResult::Found(value_changes) => value_changes,
+            DiffResult::RootNotFound => return Ok(StateDiffResult::RootNotFound),
+        };
+        let changes = value_changes
+            .into_iter()
+            .filter_map(|(key, maybe_old_value, maybe_new_value)| {
+                match (maybe_old_value, maybe_new_value) {
+                    (None, Some(value)) => Some(StateChange::Created { key, value }),
+                    (Some(old_value), Some(new_value)) => Some(StateChange::Modified {
+                        key,
+                        old_value,
+                        new_value,
+                    }),
+                    (Some(old_value), None) => Some(StateChange::Deleted { key, old_value }),
+                    (None, None) => None,
+                }
+            })
+            .collect();
+        Ok(StateDiffResult::Success { changes })
+    }
 }
 
 impl<S> EngineState<S>
@@ -558,26 +592,8 @@ where
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
-            let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-            };
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item);
             match result {
                 Ok(result) => results.push_back(result),
                 Err(error) => {
@@ -589,6 +605,63 @@ where
         Ok(results)
     }
 
+    /// Runs a deploy execution request, recording a breakdown of the gas charged for each deploy.
+    ///
+    /// Returns the execution results along with the gas profile of each deploy ran, in the same
+    /// order.
+    pub fn run_execute_with_gas_profiles(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<(ExecutionResults, Vec<GasProfile>), Error> {
+        let executor = Executor::with_gas_profile(self.config().clone());
+
+        let deploys = exec_request.take_deploys();
+        let mut results = ExecutionResults::with_capacity(deploys.len());
+        let mut gas_profiles = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?;
+            results.push_back(result);
+            gas_profiles.push(executor.take_gas_profile().unwrap_or_default());
+        }
+
+        Ok((results, gas_profiles))
+    }
+
+    /// Executes a single deploy of a deploy execution request.
+    ///
+    /// Currently a special shortcut is taken to distinguish a native transfer, from a deploy.
+    fn execute_deploy_item(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        deploy_item: DeployItem,
+    ) -> Result<ExecutionResult, Error> {
+        match deploy_item.session {
+            ExecutableDeployItem::Transfer { .. } => self.transfer(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+            _ => self.deploy(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+        }
+    }
+
     fn get_authorized_account(
         &self,
         correlation_id: CorrelationId,
@@ -0,0 +1,54 @@
+//! Support for diffing global state under two state root hashes.
+use casper_types::{Key, StoredValue};
+
+/// A change of the value stored under a key of global state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateChange {
+    /// A value was stored under a key which had none.
+    Created {
+        /// The key.
+        key: Key,
+        /// The stored value.
+        value: StoredValue,
+    },
+    /// The value stored under a key was replaced.
+    Modified {
+        /// The key.
+        key: Key,
+        /// The value stored before.
+        old_value: StoredValue,
+        /// The value stored after.
+        new_value: StoredValue,
+    },
+    /// The value stored under a key was removed.
+    Deleted {
+        /// The key.
+        key: Key,
+        /// The value stored before.
+        old_value: StoredValue,
+    },
+}
+
+impl StateChange {
+    /// Returns the key whose value changed.
+    pub fn key(&self) -> &Key {
+        match self {
+            StateChange::Created { key, .. }
+            | StateChange::Modified { key, .. }
+            | StateChange::Deleted { key, .. } => key,
+        }
+    }
+}
+
+/// Result of diffing global state under two state root hashes.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StateDiffResult {
+    /// Either state root hash, or a part of its state, was not found.
+    RootNotFound,
+    /// Successful diff.
+    Success {
+        /// The changes needed to get from the first state to the second one, ordered by the
+        /// serialized keys.
+        changes: Vec<StateChange>,
+    },
+}
@@ -12,8 +12,8 @@ use casper_types::{
 use crate::{
     core::{
         engine_state::{
-            executable_deploy_item::ExecutionKind, execution_result::ExecutionResult, EngineConfig,
-            ExecError,
+            executable_deploy_item::ExecutionKind, execution_result::ExecutionResult,
+            gas_profile::GasProfile, EngineConfig, ExecError,
         },
         execution::{address_generator::AddressGenerator, Error},
         runtime::{Runtime, RuntimeStack},
@@ -35,12 +35,32 @@ fn try_get_amount(runtime_args: &RuntimeArgs) -> Result<U512, ExecError> {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    gas_profile: Option<Rc<RefCell<GasProfile>>>,
 }
 
 impl Executor {
     /// Creates new executor object.
     pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+        Executor {
+            config,
+            gas_profile: None,
+        }
+    }
+
+    /// Creates new executor object recording a breakdown of the gas charged while executing
+    /// payment and session code.
+    pub fn with_gas_profile(config: EngineConfig) -> Self {
+        Executor {
+            config,
+            gas_profile: Some(Rc::new(RefCell::new(GasProfile::default()))),
+        }
+    }
+
+    /// Returns the gas profile recorded since the last call, if recording, resetting it.
+    pub(crate) fn take_gas_profile(&self) -> Option<GasProfile> {
+        self.gas_profile
+            .as_ref()
+            .map(|gas_profile| gas_profile.replace(GasProfile::default()))
     }
 
     /// Executes a WASM module.
@@ -98,6 +118,7 @@ impl Executor {
             tracking_copy,
             phase,
             spending_limit,
+            self.gas_profile.clone(),
         );
 
         let mut runtime = Runtime::new(self.config.clone(), context);
@@ -184,6 +205,7 @@ impl Executor {
             Rc::clone(&tracking_copy),
             phase,
             spending_limit,
+            self.gas_profile.clone(),
         );
 
         let execution_journal = tracking_copy.borrow().execution_journal();
@@ -304,6 +326,7 @@ impl Executor {
             tracking_copy,
             phase,
             remaining_spending_limit,
+            None,
         );
 
         let mut runtime = Runtime::new(self.config.clone(), runtime_context);
@@ -362,6 +385,7 @@ impl Executor {
         tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
         phase: Phase,
         remaining_spending_limit: U512,
+        gas_profile: Option<Rc<RefCell<GasProfile>>>,
     ) -> RuntimeContext<'a, R>
     where
         R: StateReader<Key, StoredValue>,
@@ -390,6 +414,7 @@ impl Executor {
             self.config.clone(),
             transfers,
             remaining_spending_limit,
+            gas_profile,
         )
     }
 }
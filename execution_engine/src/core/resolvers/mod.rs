@@ -23,6 +23,7 @@ pub(crate) fn create_module_resolver(
     if protocol_version >= ProtocolVersion::V1_0_0 {
         return Ok(v1_resolver::RuntimeModuleImportResolver::new(
             engine_config.wasm_config().max_memory,
+            engine_config.allow_zk_host_functions(),
         ));
     }
     Err(ResolverError::UnknownProtocolVersion(protocol_version))
@@ -30,9 +31,12 @@ pub(crate) fn create_module_resolver(
 
 #[cfg(test)]
 mod tests {
+    use casper_wasmi::{Signature, ValueType};
+
     use casper_types::ProtocolVersion;
 
     use super::*;
+    use crate::core::engine_state::EngineConfigBuilder;
 
     #[test]
     fn resolve_invalid_module() {
@@ -45,4 +49,23 @@ mod tests {
     fn protocol_version_1_always_resolves() {
         assert!(create_module_resolver(ProtocolVersion::V1_0_0, &EngineConfig::default()).is_ok());
     }
+
+    #[test]
+    fn zk_host_functions_resolve_only_if_allowed() {
+        let signature = Signature::new(&[ValueType::I32; 7][..], Some(ValueType::I32));
+
+        let resolver =
+            create_module_resolver(ProtocolVersion::V1_0_0, &EngineConfig::default()).unwrap();
+        assert!(resolver
+            .resolve_func("casper_verify_groth16", &signature)
+            .is_err());
+
+        let engine_config = EngineConfigBuilder::new()
+            .with_allow_zk_host_functions(true)
+            .build();
+        let resolver = create_module_resolver(ProtocolVersion::V1_0_0, &engine_config).unwrap();
+        assert!(resolver
+            .resolve_func("casper_verify_groth16", &signature)
+            .is_ok());
+    }
 }
@@ -12,13 +12,16 @@ use super::{
 pub(crate) struct RuntimeModuleImportResolver {
     memory: RefCell<Option<MemoryRef>>,
     max_memory: u32,
+    /// Whether the host functions verifying zero-knowledge proofs are exported.
+    allow_zk_host_functions: bool,
 }
 
 impl RuntimeModuleImportResolver {
-    pub(crate) fn new(max_memory: u32) -> Self {
+    pub(crate) fn new(max_memory: u32, allow_zk_host_functions: bool) -> Self {
         Self {
             memory: RefCell::new(None),
             max_memory,
+            allow_zk_host_functions,
         }
     }
 }
@@ -245,6 +248,10 @@ impl ModuleImportResolver for RuntimeModuleImportResolver {
                 Signature::new(&[ValueType::I32; 4][..], Some(ValueType::I32)),
                 FunctionIndex::EnableContractVersion.into(),
             ),
+            "casper_verify_groth16" if self.allow_zk_host_functions => FuncInstance::alloc_host(
+                Signature::new(&[ValueType::I32; 7][..], Some(ValueType::I32)),
+                FunctionIndex::VerifyGroth16.into(),
+            ),
             _ => {
                 return Err(InterpreterError::Function(format!(
                     "host module doesn't export function with name {}",
@@ -13,9 +13,11 @@ use casper_types::{
     StoredValue, URef, U512, UREF_SERIALIZED_LENGTH,
 };
 
-use super::{args::Args, Error, Runtime};
+use super::{args::Args, groth16, Error, Runtime};
 use crate::{
-    core::resolvers::v1_function_index::FunctionIndex,
+    core::{
+        engine_state::gas_profile::GasProfileItem, resolvers::v1_function_index::FunctionIndex,
+    },
     shared::host_function_costs::{Cost, HostFunction, DEFAULT_HOST_FUNCTION_NEW_DICTIONARY},
     storage::global_state::StateReader,
 };
@@ -41,6 +43,7 @@ where
                 // args(2) = pointer to output size (output param)
                 let (key_ptr, key_size, output_size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.read_value,
                     [key_ptr, key_size, output_size_ptr],
                 )?;
@@ -53,6 +56,7 @@ where
                 // args(1) = pointer to amount of serialized bytes (output)
                 let (total_keys_ptr, result_size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.load_named_keys,
                     [total_keys_ptr, result_size_ptr],
                 )?;
@@ -67,6 +71,7 @@ where
                 // args(3) = size of value
                 let (key_ptr, key_size, value_ptr, value_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.write,
                     [key_ptr, key_size, value_ptr, value_size],
                 )?;
@@ -81,6 +86,7 @@ where
                 // args(3) = size of value
                 let (key_ptr, key_size, value_ptr, value_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.add,
                     [key_ptr, key_size, value_ptr, value_size],
                 )?;
@@ -94,6 +100,7 @@ where
                 // args(2) = size of initial value
                 let (uref_ptr, value_ptr, value_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.new_uref,
                     [uref_ptr, value_ptr, value_size],
                 )?;
@@ -105,7 +112,11 @@ where
                 // args(0) = pointer to value
                 // args(1) = size of value
                 let (value_ptr, value_size) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.ret, [value_ptr, value_size])?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.ret,
+                    [value_ptr, value_size],
+                )?;
                 Err(self.ret(value_ptr, value_size as usize))
             }
 
@@ -118,6 +129,7 @@ where
                 let (name_ptr, name_size, output_ptr, output_size, bytes_written) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.get_key,
                     [name_ptr, name_size, output_ptr, output_size, bytes_written],
                 )?;
@@ -136,6 +148,7 @@ where
                 // args(1) = size of key name
                 let (name_ptr, name_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.has_key,
                     [name_ptr, name_size],
                 )?;
@@ -150,6 +163,7 @@ where
                 // args(3) = size of key
                 let (name_ptr, name_size, key_ptr, key_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.put_key,
                     [name_ptr, name_size, key_ptr, key_size],
                 )?;
@@ -162,6 +176,7 @@ where
                 // args(1) = size of key name
                 let (name_ptr, name_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.remove_key,
                     [name_ptr, name_size],
                 )?;
@@ -172,7 +187,11 @@ where
             FunctionIndex::GetCallerIndex => {
                 // args(0) = pointer where a size of serialized bytes will be stored
                 let (output_size,) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.get_caller, [output_size])?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.get_caller,
+                    [output_size],
+                )?;
                 let ret = self.get_caller(output_size)?;
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
             }
@@ -180,7 +199,11 @@ where
             FunctionIndex::GetBlocktimeIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let (dest_ptr,) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.get_blocktime, [dest_ptr])?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.get_blocktime,
+                    [dest_ptr],
+                )?;
                 self.get_blocktime(dest_ptr)?;
                 Ok(None)
             }
@@ -189,7 +212,7 @@ where
                 let (gas_arg,): (u32,) = Args::parse(args)?;
                 // Gas is special cased internal host function and for accounting purposes it isn't
                 // represented in protocol data.
-                self.gas(Gas::new(gas_arg.into()))?;
+                self.gas_for(Gas::new(gas_arg.into()), GasProfileItem::WasmInstructions)?;
                 Ok(None)
             }
 
@@ -198,6 +221,7 @@ where
                 // args(1) = size of value
                 let (uref_ptr, uref_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.is_valid_uref,
                     [uref_ptr, uref_size],
                 )?;
@@ -209,7 +233,7 @@ where
             FunctionIndex::RevertFuncIndex => {
                 // args(0) = status u32
                 let (status,) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.revert, [status])?;
+                self.charge_host_function_call(func, &host_function_costs.revert, [status])?;
                 Err(self.revert(status))
             }
 
@@ -219,6 +243,7 @@ where
                 // args(2) = weight of the key
                 let (account_hash_ptr, account_hash_size, weight_value) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.add_associated_key,
                     [account_hash_ptr, account_hash_size, weight_value as Cost],
                 )?;
@@ -235,6 +260,7 @@ where
                 // args(1) = size of an account hash
                 let (account_hash_ptr, account_hash_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.remove_associated_key,
                     [account_hash_ptr, account_hash_size],
                 )?;
@@ -249,6 +275,7 @@ where
                 // args(2) = weight of the key
                 let (account_hash_ptr, account_hash_size, weight_value) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.update_associated_key,
                     [account_hash_ptr, account_hash_size, weight_value as Cost],
                 )?;
@@ -265,6 +292,7 @@ where
                 // args(1) = new threshold
                 let (action_type_value, threshold_value) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.set_action_threshold,
                     [action_type_value, threshold_value as Cost],
                 )?;
@@ -278,6 +306,7 @@ where
                 let (dest_ptr, dest_size) = Args::parse(args)?;
 
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.create_purse,
                     [dest_ptr, dest_size],
                 )?;
@@ -307,6 +336,7 @@ where
                 let (key_ptr, key_size, amount_ptr, amount_size, id_ptr, id_size, result_ptr) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.transfer_to_account,
                     [
                         key_ptr,
@@ -367,6 +397,7 @@ where
                     result_ptr,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.transfer_from_purse_to_account,
                     [
                         source_ptr,
@@ -435,6 +466,7 @@ where
                     id_size,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.transfer_from_purse_to_purse,
                     [
                         source_ptr,
@@ -478,6 +510,7 @@ where
                 // args(2) = pointer to output size (output)
                 let (ptr, ptr_size, output_size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.get_balance,
                     [ptr, ptr_size, output_size_ptr],
                 )?;
@@ -488,7 +521,7 @@ where
             FunctionIndex::GetPhaseIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let (dest_ptr,) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.get_phase, [dest_ptr])?;
+                self.charge_host_function_call(func, &host_function_costs.get_phase, [dest_ptr])?;
                 self.get_phase(dest_ptr)?;
                 Ok(None)
             }
@@ -499,6 +532,7 @@ where
                 // args(2) = dest pointer size
                 let (system_contract_index, dest_ptr, dest_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.get_system_contract,
                     [system_contract_index, dest_ptr, dest_size],
                 )?;
@@ -509,7 +543,11 @@ where
             FunctionIndex::GetMainPurseIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let (dest_ptr,) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.get_main_purse, [dest_ptr])?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.get_main_purse,
+                    [dest_ptr],
+                )?;
                 self.get_main_purse(dest_ptr)?;
                 Ok(None)
             }
@@ -518,6 +556,7 @@ where
                 // args(0) = pointer to Wasm memory where to write size.
                 let (dest_ptr, dest_size, bytes_written_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.read_host_buffer,
                     [dest_ptr, dest_size, bytes_written_ptr],
                 )?;
@@ -531,6 +570,7 @@ where
                 // args(2) = boolean flag to determine if the contract can be versioned
                 let (hash_dest_ptr, access_dest_ptr, is_locked) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.create_contract_package_at_hash,
                     [hash_dest_ptr, access_dest_ptr],
                 )?;
@@ -563,6 +603,7 @@ where
                     output_size_ptr,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.create_contract_user_group,
                     [
                         package_key_ptr,
@@ -615,6 +656,7 @@ where
                     bytes_written_ptr,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.add_contract_version,
                     [
                         contract_package_hash_ptr,
@@ -655,6 +697,7 @@ where
                 let (package_key_ptr, package_key_size, contract_hash_ptr, contract_hash_size) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.disable_contract_version,
                     [
                         package_key_ptr,
@@ -689,6 +732,7 @@ where
                     result_size_ptr,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.call_contract,
                     [
                         contract_hash_ptr,
@@ -741,6 +785,7 @@ where
                     result_size_ptr,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.call_versioned_contract,
                     [
                         contract_package_hash_ptr,
@@ -779,7 +824,11 @@ where
             #[cfg(feature = "test-support")]
             FunctionIndex::PrintIndex => {
                 let (text_ptr, text_size) = Args::parse(args)?;
-                self.charge_host_function_call(&host_function_costs.print, [text_ptr, text_size])?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.print,
+                    [text_ptr, text_size],
+                )?;
                 self.print(text_ptr, text_size)?;
                 Ok(None)
             }
@@ -790,6 +839,7 @@ where
                 // args(2) = pointer to a argument size (output)
                 let (name_ptr, name_size, size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.get_named_arg_size,
                     [name_ptr, name_size, size_ptr],
                 )?;
@@ -804,6 +854,7 @@ where
                 // args(3) = size of available data under output pointer
                 let (name_ptr, name_size, dest_ptr, dest_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.get_named_arg,
                     [name_ptr, name_size, dest_ptr, dest_size],
                 )?;
@@ -819,6 +870,7 @@ where
                 // args(3) = size of serialized group label
                 let (package_key_ptr, package_key_size, label_ptr, label_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.remove_contract_user_group,
                     [package_key_ptr, package_key_size, label_ptr, label_size],
                 )?;
@@ -838,6 +890,7 @@ where
                 let (package_ptr, package_size, label_ptr, label_size, value_size_ptr) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.provision_contract_user_group_uref,
                     [
                         package_ptr,
@@ -867,6 +920,7 @@ where
                 let (package_ptr, package_size, label_ptr, label_size, urefs_ptr, urefs_size) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.remove_contract_user_group_urefs,
                     [
                         package_ptr,
@@ -891,6 +945,7 @@ where
             FunctionIndex::Blake2b => {
                 let (in_ptr, in_size, out_ptr, out_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.blake2b,
                     [in_ptr, in_size, out_ptr, out_size],
                 )?;
@@ -955,6 +1010,7 @@ where
                 let (output_size_ptr,): (u32,) = Args::parse(args)?;
 
                 self.charge_host_function_call(
+                    func,
                     &DEFAULT_HOST_FUNCTION_NEW_DICTIONARY,
                     [output_size_ptr],
                 )?;
@@ -976,6 +1032,7 @@ where
                     _,
                 ) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.dictionary_get,
                     [key_bytes_ptr, key_bytes_size, output_size_ptr],
                 )?;
@@ -998,6 +1055,7 @@ where
                 // args(5) = pointer to value bytes size in Wasm memory
                 let (uref_ptr, uref_size, key_bytes_ptr, key_bytes_size, value_ptr, value_ptr_size): (_, u32, _, u32, _, u32) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.dictionary_put,
                     [key_bytes_ptr, key_bytes_size, value_ptr, value_ptr_size],
                 )?;
@@ -1018,6 +1076,7 @@ where
                 // args(2) = pointer to output size (output param)
                 let (key_ptr, key_size, output_size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.read_value,
                     [key_ptr, key_size, output_size_ptr],
                 )?;
@@ -1031,6 +1090,7 @@ where
                 let (call_stack_len_ptr, result_size_ptr) = Args::parse(args)?;
                 // TODO: add cost table entry once we can upgrade safely
                 self.charge_host_function_call(
+                    func,
                     &HostFunction::fixed(10_000),
                     [call_stack_len_ptr, result_size_ptr],
                 )?;
@@ -1043,6 +1103,7 @@ where
                 // args(1) (Output) Pointer to size in bytes of the total bytes.
                 let (len_ptr, result_size_ptr) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &HostFunction::fixed(10_000),
                     [len_ptr, result_size_ptr],
                 )?;
@@ -1053,6 +1114,7 @@ where
             FunctionIndex::RandomBytes => {
                 let (out_ptr, out_size) = Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.random_bytes,
                     [out_ptr, out_size],
                 )?;
@@ -1082,6 +1144,7 @@ where
                 let (package_key_ptr, package_key_size, contract_hash_ptr, contract_hash_size) =
                     Args::parse(args)?;
                 self.charge_host_function_call(
+                    func,
                     &host_function_costs.enable_contract_version,
                     [
                         package_key_ptr,
@@ -1097,6 +1160,61 @@ where
 
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
             }
+            FunctionIndex::VerifyGroth16 => {
+                // args(0) = pointer to verifying key in wasm memory
+                // args(1) = size of verifying key in wasm memory
+                // args(2) = pointer to proof in wasm memory
+                // args(3) = size of proof in wasm memory
+                // args(4) = pointer to public inputs in wasm memory
+                // args(5) = size of public inputs in wasm memory
+                // args(6) = pointer to output byte, set to 1 if the proof is valid and 0 otherwise
+                let (
+                    verifying_key_ptr,
+                    verifying_key_size,
+                    proof_ptr,
+                    proof_size,
+                    public_inputs_ptr,
+                    public_inputs_size,
+                    out_ptr,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    func,
+                    &host_function_costs.verify_groth16,
+                    [
+                        verifying_key_ptr,
+                        verifying_key_size,
+                        proof_ptr,
+                        proof_size,
+                        public_inputs_ptr,
+                        public_inputs_size,
+                        out_ptr,
+                    ],
+                )?;
+                // Proofs have a fixed size, which is why the cost table has no weight for it.
+                if proof_size as usize != groth16::PROOF_SIZE {
+                    return Ok(Some(RuntimeValue::I32(api_error::i32_from(Err(
+                        ApiError::Deserialize,
+                    )))));
+                }
+                let verifying_key =
+                    self.bytes_from_mem(verifying_key_ptr, verifying_key_size as usize)?;
+                let proof = self.bytes_from_mem(proof_ptr, proof_size as usize)?;
+                let public_inputs =
+                    self.bytes_from_mem(public_inputs_ptr, public_inputs_size as usize)?;
+
+                let is_valid = match groth16::verify(&verifying_key, &proof, &public_inputs) {
+                    Ok(is_valid) => is_valid,
+                    Err(error) => {
+                        return Ok(Some(RuntimeValue::I32(api_error::i32_from(Err(error)))))
+                    }
+                };
+
+                self.try_get_memory()?
+                    .set(out_ptr, &[u8::from(is_valid)])
+                    .map_err(|error| Error::Interpreter(error.into()))?;
+
+                Ok(Some(RuntimeValue::I32(0)))
+            }
         }
     }
 }
@@ -0,0 +1,131 @@
+//! Verification of Groth16 proofs for the `casper_verify_groth16` host function.
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+
+use casper_types::ApiError;
+
+/// The size of a compressed Groth16 proof over BN254: two G1 points and one G2 point.
+pub(super) const PROOF_SIZE: usize = 128;
+
+/// Verifies a Groth16 proof over the BN254 curve.
+///
+/// The verifying key, the proof and the public inputs (a vector of scalar field elements) are each
+/// in the compressed canonical serialization of arkworks, which is what the node's zk prover emits.
+///
+/// Returns whether the proof is valid, or an error if any argument is malformed, including points
+/// which are not in the prime order subgroup.
+pub(super) fn verify(
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<bool, ApiError> {
+    let verifying_key: VerifyingKey<Bn254> = deserialize(verifying_key)?;
+    let proof: Proof<Bn254> = deserialize(proof)?;
+    let public_inputs: Vec<Fr> = deserialize(public_inputs)?;
+    if public_inputs.len() + 1 != verifying_key.gamma_abc_g1.len() {
+        return Ok(false);
+    }
+    let prepared_verifying_key = ark_groth16::prepare_verifying_key(&verifying_key);
+    // With the number of inputs checked, verification can only fail on an identity pairing, which
+    // no valid proof yields.
+    Ok(
+        Groth16::<Bn254>::verify_proof(&prepared_verifying_key, &proof, &public_inputs)
+            .unwrap_or(false),
+    )
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, ApiError> {
+    let mut remainder = bytes;
+    let value = T::deserialize_compressed(&mut remainder).map_err(|_| ApiError::Deserialize)?;
+    if !remainder.is_empty() {
+        return Err(ApiError::LeftOverBytes);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+    };
+    use ark_serialize::CanonicalSerialize;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Proves knowledge of two factors of the public input.
+    struct Product {
+        x: Fr,
+        y: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Product {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = cs.new_witness_variable(|| Ok(self.x))?;
+            let y = cs.new_witness_variable(|| Ok(self.y))?;
+            let z = cs.new_input_variable(|| Ok(self.x * self.y))?;
+            cs.enforce_constraint(lc!() + x, lc!() + y, lc!() + z)
+        }
+    }
+
+    fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+        let mut bytes = vec![];
+        value.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Returns a serialized verifying key and proof of the factors 3 and 5.
+    fn verifying_key_and_proof() -> (Vec<u8>, Vec<u8>) {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let circuit = || Product {
+            x: Fr::from(3u64),
+            y: Fr::from(5u64),
+        };
+        let proving_key =
+            Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit(), &mut rng)
+                .unwrap();
+        let proof =
+            Groth16::<Bn254>::create_random_proof_with_reduction(circuit(), &proving_key, &mut rng)
+                .unwrap();
+        (serialize(&proving_key.vk), serialize(&proof))
+    }
+
+    #[test]
+    fn should_verify_groth16_proofs() {
+        let (verifying_key, proof) = verifying_key_and_proof();
+        assert_eq!(proof.len(), PROOF_SIZE);
+        let valid_inputs = serialize(&vec![Fr::from(15u64)]);
+        assert_eq!(verify(&verifying_key, &proof, &valid_inputs), Ok(true));
+
+        let invalid_inputs = serialize(&vec![Fr::from(16u64)]);
+        assert_eq!(verify(&verifying_key, &proof, &invalid_inputs), Ok(false));
+        let too_many_inputs = serialize(&vec![Fr::from(15u64), Fr::from(1u64)]);
+        assert_eq!(verify(&verifying_key, &proof, &too_many_inputs), Ok(false));
+    }
+
+    #[test]
+    fn should_reject_malformed_arguments() {
+        let (verifying_key, proof) = verifying_key_and_proof();
+        let inputs = serialize(&vec![Fr::from(15u64)]);
+
+        let truncated_proof = &proof[..proof.len() - 1];
+        assert_eq!(
+            verify(&verifying_key, truncated_proof, &inputs),
+            Err(ApiError::Deserialize)
+        );
+        let mut extended_inputs = inputs.clone();
+        extended_inputs.push(0);
+        assert_eq!(
+            verify(&verifying_key, &proof, &extended_inputs),
+            Err(ApiError::LeftOverBytes)
+        );
+        let non_canonical_inputs = serialize(&vec![[u8::MAX; 32]]);
+        assert_eq!(
+            verify(&verifying_key, &proof, &non_canonical_inputs),
+            Err(ApiError::Deserialize)
+        );
+    }
+}
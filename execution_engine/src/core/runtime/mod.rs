@@ -2,6 +2,7 @@
 mod args;
 mod auction_internal;
 mod externals;
+mod groth16;
 mod handle_payment_internal;
 mod host_function_flag;
 mod mint_internal;
@@ -48,8 +49,9 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::EngineConfig,
+        engine_state::{gas_profile::GasProfileItem, EngineConfig},
         execution::{self, Error},
+        resolvers::v1_function_index::FunctionIndex,
         runtime::host_function_flag::HostFunctionFlag,
         runtime_context::{self, RuntimeContext},
         tracking_copy::TrackingCopyExt,
@@ -166,6 +168,11 @@ where
         self.context.charge_gas(amount)
     }
 
+    /// Charge specified amount of gas for `item`, recording it in the gas profile, if any.
+    fn gas_for(&mut self, amount: Gas, item: GasProfileItem) -> Result<(), Error> {
+        self.context.charge_gas_for(amount, item)
+    }
+
     /// Returns current gas counter.
     fn gas_counter(&self) -> Gas {
         self.context.gas_counter()
@@ -1392,7 +1399,17 @@ where
                 None => return Err(Error::KeyNotFound(context_key)),
             };
 
-            casper_wasm::deserialize_buffer(contract_wasm.bytes())?
+            // The Wasm is read even if its module is cached, as the read is part of the execution
+            // journal.
+            let module_cache = self.config.module_cache();
+            match module_cache.get(contract_hash, contract.contract_wasm_hash()) {
+                Some(module) => module,
+                None => {
+                    let module = casper_wasm::deserialize_buffer(contract_wasm.bytes())?;
+                    module_cache.put(contract_hash, contract.contract_wasm_hash(), module.clone());
+                    module
+                }
+            }
         };
 
         let context = self.context.new_from_self(
@@ -2812,6 +2829,7 @@ where
     /// Calculate gas cost for a host function
     fn charge_host_function_call<T>(
         &mut self,
+        func: FunctionIndex,
         host_function: &HostFunction<T>,
         weights: T,
     ) -> Result<(), Trap>
@@ -2819,7 +2837,7 @@ where
         T: AsRef<[Cost]> + Copy,
     {
         let cost = host_function.calculate_gas_cost(weights);
-        self.gas(cost)?;
+        self.gas_for(cost, GasProfileItem::HostFunction(func.name()))?;
         Ok(())
     }
 
@@ -3046,8 +3064,7 @@ where
 #[cfg(feature = "test-support")]
 fn dump_runtime_stack_info(instance: casper_wasmi::ModuleRef, max_stack_height: u32) {
     let globals = instance.globals();
-    let Some(current_runtime_call_stack_height) = globals.last()
-    else {
+    let Some(current_runtime_call_stack_height) = globals.last() else {
         return;
     };
 
@@ -25,7 +25,11 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::{execution_effect::ExecutionEffect, EngineConfig, SystemContractRegistry},
+        engine_state::{
+            execution_effect::ExecutionEffect,
+            gas_profile::{GasProfile, GasProfileItem},
+            EngineConfig, SystemContractRegistry,
+        },
         execution::{AddressGenerator, Error},
         runtime_context::dictionary::DictionaryValue,
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
@@ -104,6 +108,8 @@ pub struct RuntimeContext<'a, R> {
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
     remaining_spending_limit: U512,
+    // Breakdown of the gas charged, if it is being recorded
+    gas_profile: Option<Rc<RefCell<GasProfile>>>,
 }
 
 impl<'a, R> RuntimeContext<'a, R>
@@ -135,6 +141,7 @@ where
         engine_config: EngineConfig,
         transfers: Vec<TransferAddr>,
         remaining_spending_limit: U512,
+        gas_profile: Option<Rc<RefCell<GasProfile>>>,
     ) -> Self {
         RuntimeContext {
             tracking_copy,
@@ -156,6 +163,7 @@ where
             engine_config,
             transfers,
             remaining_spending_limit,
+            gas_profile,
         }
     }
 
@@ -184,6 +192,7 @@ where
         let engine_config = self.engine_config.clone();
         let transfers = self.transfers.clone();
         let remaining_spending_limit = self.remaining_spending_limit();
+        let gas_profile = self.gas_profile.clone();
 
         RuntimeContext {
             tracking_copy,
@@ -205,6 +214,7 @@ where
             engine_config,
             transfers,
             remaining_spending_limit,
+            gas_profile,
         }
     }
 
@@ -864,6 +874,22 @@ where
         }
     }
 
+    /// Charges gas for `item`, recording the gas actually charged in the gas profile, if any.
+    pub(crate) fn charge_gas_for(
+        &mut self,
+        amount: Gas,
+        item: GasProfileItem,
+    ) -> Result<(), Error> {
+        let prev = self.gas_counter();
+        let result = self.charge_gas(amount);
+        if let Some(gas_profile) = &self.gas_profile {
+            // On running out of gas, only the gas left up to the limit has been charged.
+            let charged = self.gas_counter().checked_sub(prev).unwrap_or_default();
+            gas_profile.borrow_mut().record(item, charged);
+        }
+        result
+    }
+
     /// Checks if we are calling a system contract.
     pub(crate) fn is_system_contract(&self, contract_hash: &ContractHash) -> Result<bool, Error> {
         Ok(self
@@ -885,7 +911,7 @@ where
 
         let gas_cost = storage_costs.calculate_gas_cost(bytes_count);
 
-        self.charge_gas(gas_cost)
+        self.charge_gas_for(gas_cost, GasProfileItem::Storage)
     }
 
     /// Charges gas for using a host system contract's entrypoint.
@@ -900,7 +926,7 @@ where
             return Ok(());
         }
         let amount: Gas = call_cost.into();
-        self.charge_gas(amount)
+        self.charge_gas_for(amount, GasProfileItem::SystemContractCall)
     }
 
     /// Writes data to global state with a measurement.
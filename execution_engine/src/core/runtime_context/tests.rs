@@ -141,6 +141,7 @@ fn new_runtime_context<'a>(
         test_engine_config(),
         Vec::default(),
         U512::MAX,
+        None,
     )
 }
 
@@ -402,6 +403,7 @@ fn contract_key_addable_valid() {
         EngineConfig::default(),
         Vec::default(),
         U512::zero(),
+        None,
     );
 
     runtime_context
@@ -478,6 +480,7 @@ fn contract_key_addable_invalid() {
         EngineConfig::default(),
         Vec::default(),
         U512::zero(),
+        None,
     );
 
     let result = runtime_context.metered_add_gs(contract_key, named_uref_tuple);
@@ -78,6 +78,10 @@ const DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT: u32 = 520;
 
 const DEFAULT_NEW_DICTIONARY_COST: u32 = DEFAULT_NEW_UREF_COST;
 
+const DEFAULT_VERIFY_GROTH16_COST: u32 = 120_000_000;
+const DEFAULT_VERIFY_GROTH16_VERIFYING_KEY_SIZE_WEIGHT: u32 = 25_000;
+const DEFAULT_VERIFY_GROTH16_PUBLIC_INPUTS_SIZE_WEIGHT: u32 = 125_000;
+
 pub(crate) const DEFAULT_HOST_FUNCTION_NEW_DICTIONARY: HostFunction<[Cost; 1]> =
     HostFunction::new(DEFAULT_NEW_DICTIONARY_COST, [NOT_USED]);
 
@@ -290,6 +294,26 @@ pub struct HostFunctionCosts {
     pub random_bytes: HostFunction<[Cost; 2]>,
     /// Cost of calling the `enable_contract_version` host function.
     pub enable_contract_version: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `verify_groth16` host function.
+    ///
+    /// Defaults if absent, so that cost tables predating the host function still parse.
+    #[serde(default = "default_verify_groth16")]
+    pub verify_groth16: HostFunction<[Cost; 7]>,
+}
+
+fn default_verify_groth16() -> HostFunction<[Cost; 7]> {
+    HostFunction::new(
+        DEFAULT_VERIFY_GROTH16_COST,
+        [
+            NOT_USED,
+            DEFAULT_VERIFY_GROTH16_VERIFYING_KEY_SIZE_WEIGHT,
+            NOT_USED,
+            NOT_USED,
+            NOT_USED,
+            DEFAULT_VERIFY_GROTH16_PUBLIC_INPUTS_SIZE_WEIGHT,
+            NOT_USED,
+        ],
+    )
 }
 
 impl Default for HostFunctionCosts {
@@ -422,6 +446,7 @@ impl Default for HostFunctionCosts {
             blake2b: HostFunction::default(),
             random_bytes: HostFunction::default(),
             enable_contract_version: HostFunction::default(),
+            verify_groth16: default_verify_groth16(),
         }
     }
 }
@@ -473,6 +498,7 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
         ret.append(&mut self.enable_contract_version.to_bytes()?);
+        ret.append(&mut self.verify_groth16.to_bytes()?);
         Ok(ret)
     }
 
@@ -521,6 +547,7 @@ impl ToBytes for HostFunctionCosts {
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
             + self.enable_contract_version.serialized_length()
+            + self.verify_groth16.serialized_length()
     }
 }
 
@@ -570,6 +597,12 @@ impl FromBytes for HostFunctionCosts {
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
         let (enable_contract_version, rem) = FromBytes::from_bytes(rem)?;
+        // Serializations predating `verify_groth16` end here.
+        let (verify_groth16, rem) = if rem.is_empty() {
+            (default_verify_groth16(), rem)
+        } else {
+            FromBytes::from_bytes(rem)?
+        };
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -616,6 +649,7 @@ impl FromBytes for HostFunctionCosts {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                verify_groth16,
             },
             rem,
         ))
@@ -669,6 +703,7 @@ impl Distribution<HostFunctionCosts> for Standard {
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
             enable_contract_version: rng.gen(),
+            verify_groth16: rng.gen(),
         }
     }
 }
@@ -730,6 +765,7 @@ pub mod gens {
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
             enable_contract_version in host_function_cost_arb(),
+            verify_groth16 in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -776,6 +812,7 @@ pub mod gens {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                verify_groth16,
             }
         }
     }
@@ -821,6 +858,31 @@ mod tests {
 
         assert_eq!(lhs, Gas::new(rhs));
     }
+
+    #[test]
+    fn should_default_verify_groth16_if_absent() {
+        let host_function_costs = HostFunctionCosts {
+            verify_groth16: HostFunction::fixed(COST),
+            ..HostFunctionCosts::default()
+        };
+
+        let mut json = serde_json::to_value(host_function_costs).unwrap();
+        json.as_object_mut().unwrap().remove("verify_groth16");
+        let parsed: HostFunctionCosts = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.verify_groth16, default_verify_groth16());
+
+        let mut bytes = host_function_costs.to_bytes().unwrap();
+        bytes.truncate(bytes.len() - host_function_costs.verify_groth16.serialized_length());
+        let (parsed, rem) = HostFunctionCosts::from_bytes(&bytes).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(
+            parsed,
+            HostFunctionCosts {
+                verify_groth16: default_verify_groth16(),
+                ..host_function_costs
+            }
+        );
+    }
 }
 
 #[cfg(test)]
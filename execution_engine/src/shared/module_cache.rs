@@ -0,0 +1,163 @@
+//! A cache of parsed Wasm modules of stored contracts.
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use casper_wasm::elements::Module;
+use linked_hash_map::LinkedHashMap;
+
+use casper_types::{ContractHash, ContractWasmHash};
+
+/// The cached modules, least recently used first.
+type Entries = LinkedHashMap<ContractHash, (ContractWasmHash, Module)>;
+
+/// A least-recently-used cache of the parsed Wasm modules of stored contracts, keyed by contract
+/// hash.
+///
+/// The Wasm of a stored contract has already been instrumented when it was installed, so a cached
+/// module can be instantiated as is.  Clones share the cached modules.
+#[derive(Clone)]
+pub struct ModuleCache {
+    /// The cached modules along with the hash of the Wasm they were parsed from.
+    entries: Arc<Mutex<Entries>>,
+    /// The maximum number of cached modules.  Nothing is cached if `0`.
+    capacity: usize,
+    /// The number of lookups which found a module.
+    hits: Arc<AtomicU64>,
+    /// The number of lookups which didn't find a module.
+    misses: Arc<AtomicU64>,
+}
+
+impl ModuleCache {
+    /// Creates a new, empty cache holding up to `capacity` modules.
+    pub fn new(capacity: usize) -> Self {
+        ModuleCache {
+            entries: Arc::new(Mutex::new(LinkedHashMap::new())),
+            capacity,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the number of lookups which found a module so far.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups which didn't find a module so far.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the cached module of the given contract, provided it was parsed from the Wasm with
+    /// the given hash.
+    pub(crate) fn get(
+        &self,
+        contract_hash: ContractHash,
+        contract_wasm_hash: ContractWasmHash,
+    ) -> Option<Module> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let maybe_module = self
+            .entries
+            .lock()
+            .expect("module cache mutex poisoned")
+            .get_refresh(&contract_hash)
+            .filter(|(cached_wasm_hash, _)| *cached_wasm_hash == contract_wasm_hash)
+            .map(|(_, module)| module.clone());
+        let counter = if maybe_module.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        maybe_module
+    }
+
+    /// Caches the module of the given contract, parsed from the Wasm with the given hash, evicting
+    /// the least recently used modules as required to stay within the capacity.
+    pub(crate) fn put(
+        &self,
+        contract_hash: ContractHash,
+        contract_wasm_hash: ContractWasmHash,
+        module: Module,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("module cache mutex poisoned");
+        let _ = entries.insert(contract_hash, (contract_wasm_hash, module));
+        while entries.len() > self.capacity {
+            let _ = entries.pop_front();
+        }
+    }
+}
+
+impl Default for ModuleCache {
+    /// Creates a cache which never caches anything.
+    fn default() -> Self {
+        ModuleCache::new(0)
+    }
+}
+
+impl Debug for ModuleCache {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ModuleCache")
+            .field("capacity", &self.capacity)
+            .field("hits", &self.hits())
+            .field("misses", &self.misses())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_wasm::elements::Module;
+
+    use casper_types::{ContractHash, ContractWasmHash};
+
+    use super::ModuleCache;
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let contract_hashes = [
+            ContractHash::new([1; 32]),
+            ContractHash::new([2; 32]),
+            ContractHash::new([3; 32]),
+        ];
+        let wasm_hash = ContractWasmHash::new([4; 32]);
+
+        let cache = ModuleCache::new(2);
+        for contract_hash in &contract_hashes[..2] {
+            cache.put(*contract_hash, wasm_hash, Module::default());
+        }
+        // The module of the second contract is now the least recently used one.
+        assert!(cache.get(contract_hashes[0], wasm_hash).is_some());
+        cache.put(contract_hashes[2], wasm_hash, Module::default());
+        assert!(cache.get(contract_hashes[1], wasm_hash).is_none());
+        assert!(cache.get(contract_hashes[2], wasm_hash).is_some());
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn should_not_return_module_parsed_from_other_wasm() {
+        let contract_hash = ContractHash::new([1; 32]);
+
+        let cache = ModuleCache::new(1);
+        cache.put(
+            contract_hash,
+            ContractWasmHash::new([2; 32]),
+            Module::default(),
+        );
+        assert!(cache
+            .get(contract_hash, ContractWasmHash::new([3; 32]))
+            .is_none());
+    }
+}
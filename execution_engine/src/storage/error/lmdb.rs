@@ -26,6 +26,14 @@ pub enum Error {
     /// Error committing to execution engine.
     #[error(transparent)]
     CommitError(#[from] CommitError),
+
+    /// The trie store does not track the references between its tries.
+    #[error("Trie references are not tracked")]
+    TrieReferencesNotTracked,
+
+    /// Not all tries written before trie references were tracked are indexed yet.
+    #[error("Trie references are not indexed yet")]
+    TrieReferencesNotIndexed,
 }
 
 impl casper_wasmi::HostError for Error {}
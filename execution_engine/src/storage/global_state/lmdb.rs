@@ -1,4 +1,9 @@
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Deref,
+    sync::Arc,
+    time::Duration,
+};
 
 use casper_hashing::Digest;
 use casper_types::{Key, StoredValue};
@@ -17,10 +22,11 @@ use crate::{
             merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie, TrieRaw,
         },
         trie_store::{
+            gc::TrieGcOutcome,
             lmdb::{LmdbTrieStore, ScratchTrieStore},
             operations::{
-                delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult,
+                delete, diff, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
+                DeleteResult, DiffResult, ReadResult,
             },
         },
     },
@@ -127,6 +133,49 @@ impl LmdbGlobalState {
     pub fn empty_state_root_hash(&self) -> Digest {
         self.empty_root_hash
     }
+
+    /// Indexes the references held by up to `max_tries` tries written before the trie store
+    /// tracked references, returning `true` once all of them are indexed.
+    pub fn index_trie_references(&self, max_tries: usize) -> Result<bool, error::Error> {
+        self.trie_store
+            .index_references(&self.environment, max_tries)
+    }
+
+    /// Deletes the tries not reachable from any of the retained state roots or from the empty
+    /// root, other than those written within the grace period.
+    ///
+    /// A dry run only reports what would be collected, without deleting anything.
+    pub fn collect_trie_garbage(
+        &self,
+        retained_roots: &BTreeSet<Digest>,
+        grace_period: Duration,
+        dry_run: bool,
+    ) -> Result<TrieGcOutcome, error::Error> {
+        let mut retained_roots = retained_roots.clone();
+        retained_roots.insert(self.empty_root_hash);
+        self.trie_store
+            .collect_garbage(&self.environment, &retained_roots, grace_period, dry_run)
+    }
+
+    /// Returns the changes of the stored values needed to get from the state under `from_root` to
+    /// the state under `to_root`.
+    pub fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        from_root: Digest,
+        to_root: Digest,
+    ) -> Result<DiffResult<Key, StoredValue>, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result = diff::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &from_root,
+            &to_root,
+        )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
 }
 
 fn compute_empty_root_hash() -> Result<(Digest, Trie<Key, StoredValue>), error::Error> {
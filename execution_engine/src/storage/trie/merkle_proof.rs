@@ -3,9 +3,12 @@ use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
 
 use casper_hashing::Digest;
-use casper_types::bytesrepr::{self, Bytes, FromBytes, ToBytes};
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    merkle_proof::{self, TrieProofPointer, TrieProofStep},
+};
 
-use crate::storage::trie::{Pointer, Trie, RADIX};
+use crate::storage::trie::Pointer;
 
 const TRIE_MERKLE_PROOF_STEP_NODE_ID: u8 = 0;
 const TRIE_MERKLE_PROOF_STEP_EXTENSION_ID: u8 = 1;
@@ -13,14 +16,14 @@ const TRIE_MERKLE_PROOF_STEP_EXTENSION_ID: u8 = 1;
 /// A component of a proof that an entry exists in the Merkle trie.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TrieMerkleProofStep {
-    /// Corresponds to [`Trie::Node`]
+    /// Corresponds to [`Trie::Node`](crate::storage::trie::Trie::Node)
     Node {
         /// Hole index.
         hole_index: u8,
         /// Indexed pointers with hole.
         indexed_pointers_with_hole: Vec<(u8, Pointer)>,
     },
-    /// Corresponds to [`Trie::Extension`]
+    /// Corresponds to [`Trie::Extension`](crate::storage::trie::Trie::Extension)
     Extension {
         /// Affix bytes.
         affix: Bytes,
@@ -44,6 +47,35 @@ impl TrieMerkleProofStep {
     }
 }
 
+impl From<Pointer> for TrieProofPointer {
+    fn from(pointer: Pointer) -> Self {
+        match pointer {
+            Pointer::LeafPointer(hash) => TrieProofPointer::Leaf(hash.value()),
+            Pointer::NodePointer(hash) => TrieProofPointer::Node(hash.value()),
+        }
+    }
+}
+
+impl From<&TrieMerkleProofStep> for TrieProofStep {
+    fn from(step: &TrieMerkleProofStep) -> Self {
+        match step {
+            TrieMerkleProofStep::Node {
+                hole_index,
+                indexed_pointers_with_hole,
+            } => TrieProofStep::Node {
+                hole_index: *hole_index,
+                indexed_pointers_with_hole: indexed_pointers_with_hole
+                    .iter()
+                    .map(|(index, pointer)| (*index, TrieProofPointer::from(*pointer)))
+                    .collect(),
+            },
+            TrieMerkleProofStep::Extension { affix } => TrieProofStep::Extension {
+                affix: affix.clone(),
+            },
+        }
+    }
+}
+
 impl ToBytes for TrieMerkleProofStep {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut ret: Vec<u8> = bytesrepr::allocate_buffer(self)?;
@@ -160,37 +192,14 @@ where
     ///
     /// 3. When there are no more steps, we return the final hash we have computed.
     ///
-    /// The steps in this function reflect `operations::rehash`.
+    /// The steps in this function reflect `operations::rehash`.  The hashing is implemented by
+    /// [`merkle_proof::compute_trie_root_hash`], which external verifiers share.
+    ///
+    /// [`Trie::Leaf`]: crate::storage::trie::Trie::Leaf
     pub fn compute_state_hash(&self) -> Result<Digest, bytesrepr::Error> {
-        let mut hash = {
-            let leaf = Trie::leaf(self.key, self.value.to_owned());
-            leaf.trie_hash()?
-        };
-
-        for (proof_step_index, proof_step) in self.proof_steps.iter().enumerate() {
-            let pointer = if proof_step_index == 0 {
-                Pointer::LeafPointer(hash)
-            } else {
-                Pointer::NodePointer(hash)
-            };
-            let proof_step_bytes = match proof_step {
-                TrieMerkleProofStep::Node {
-                    hole_index,
-                    indexed_pointers_with_hole,
-                } => {
-                    let hole_index = *hole_index;
-                    assert!(hole_index as usize <= RADIX, "hole_index exceeded RADIX");
-                    let mut indexed_pointers = indexed_pointers_with_hole.to_owned();
-                    indexed_pointers.push((hole_index, pointer));
-                    Trie::<K, V>::node(&indexed_pointers).to_bytes()?
-                }
-                TrieMerkleProofStep::Extension { affix } => {
-                    Trie::<K, V>::extension(affix.clone().into(), pointer).to_bytes()?
-                }
-            };
-            hash = Digest::hash(&proof_step_bytes);
-        }
-        Ok(hash)
+        let proof_steps: Vec<TrieProofStep> =
+            self.proof_steps.iter().map(TrieProofStep::from).collect();
+        merkle_proof::compute_trie_root_hash(&self.key, &self.value, &proof_steps).map(Digest::from)
     }
 }
 
@@ -290,9 +299,10 @@ mod gens {
 mod tests {
     use proptest::prelude::*;
 
-    use casper_types::bytesrepr;
+    use casper_types::{bytesrepr, Key, StoredValue};
 
-    use super::gens;
+    use super::{gens, TrieMerkleProofStep};
+    use crate::storage::trie::{Pointer, Trie};
 
     proptest! {
         #[test]
@@ -308,5 +318,37 @@ mod tests {
         ) {
             bytesrepr::test_serialization_roundtrip(&proof)
         }
+
+        #[test]
+        fn trie_merkle_proof_state_hash_matches_trie_hashes(
+            proof in gens::trie_merkle_proof_arb()
+        ) {
+            // Rebuild the tries on the path from the leaf to the root, and hash them as stored.
+            let mut hash = Trie::leaf(*proof.key(), proof.value().clone())
+                .trie_hash()
+                .unwrap();
+            for (index, step) in proof.proof_steps().iter().enumerate() {
+                let pointer = if index == 0 {
+                    Pointer::LeafPointer(hash)
+                } else {
+                    Pointer::NodePointer(hash)
+                };
+                let trie = match step {
+                    TrieMerkleProofStep::Node {
+                        hole_index,
+                        indexed_pointers_with_hole,
+                    } => {
+                        let mut indexed_pointers = indexed_pointers_with_hole.clone();
+                        indexed_pointers.push((*hole_index, pointer));
+                        Trie::<Key, StoredValue>::node(&indexed_pointers)
+                    }
+                    TrieMerkleProofStep::Extension { affix } => {
+                        Trie::extension(affix.clone().into(), pointer)
+                    }
+                };
+                hash = trie.trie_hash().unwrap();
+            }
+            prop_assert_eq!(proof.compute_state_hash().unwrap(), hash);
+        }
     }
 }
@@ -0,0 +1,630 @@
+//! Reference counting of the tries held in an LMDB-backed trie store, used to garbage collect the
+//! tries which are no longer reachable from any retained state root.
+//!
+//! For every trie written to a store tracking references, an edge from each of its children to the
+//! trie is recorded, so that the number of edges of a trie is its reference count.  The trie is
+//! also recorded as a candidate for collection, along with the time it was written.
+//!
+//! Collecting garbage deletes each candidate nobody refers to, unless it is one of the retained
+//! state roots or was written within the grace period.  Deleting a trie releases its references on
+//! its children, which are deleted in turn once nothing else refers to them.  Candidates which are
+//! still referenced are dropped, as they are reached through their referrers once those are
+//! deleted.
+//!
+//! Tries written while references were not tracked, e.g. before tracking was enabled, are recorded
+//! by indexing the whole store, which has to complete before any garbage can be collected.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    mem,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lmdb::{Cursor, Database, DatabaseFlags, RwTransaction, Transaction, WriteFlags};
+use serde::Serialize;
+
+use casper_hashing::Digest;
+use casper_types::bytesrepr::{self, Bytes};
+
+use crate::storage::{
+    error,
+    transaction_source::{lmdb::LmdbEnvironment, Writable},
+    trie::{self, Trie, TrieTag},
+};
+
+const REFERRERS_NAME: &str = "TRIE_REFERRERS";
+const CANDIDATES_NAME: &str = "TRIE_GC_CANDIDATES";
+const STATE_NAME: &str = "TRIE_GC_STATE";
+
+/// Key of the hash of the last trie indexed, in the state database.
+const INDEXING_POSITION_KEY: &[u8] = b"indexing_position";
+/// Key of the marker set once all tries written before references were tracked are indexed.
+const INDEXING_COMPLETE_KEY: &[u8] = b"indexing_complete";
+
+/// Number of candidates checked per read-write transaction when collecting garbage.
+const CANDIDATES_PER_TRANSACTION: usize = 1_000;
+
+/// The outcome of a garbage collection of tries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TrieGcOutcome {
+    /// Whether this was a dry run, which only determined what would be collected.
+    pub dry_run: bool,
+    /// The number of candidates checked.
+    pub candidates_checked: u64,
+    /// The number of tries collected, or which would be collected by a dry run.
+    pub tries_collected: u64,
+    /// The total size of the tries collected, in bytes.
+    pub bytes_collected: u64,
+}
+
+impl Display for TrieGcOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run {
+            "would collect"
+        } else {
+            "collected"
+        };
+        write!(
+            f,
+            "checked {} candidates, {} {} tries of {} bytes",
+            self.candidates_checked, verb, self.tries_collected, self.bytes_collected
+        )
+    }
+}
+
+/// The databases tracking the references between the tries of a trie store.
+#[derive(Clone, Copy, Debug)]
+pub struct TrieReferences {
+    /// Holds an empty value under the hash of each child followed by the hash of a trie referring
+    /// to it.
+    referrers: Database,
+    /// Maps the hash of each trie which may be unreferenced to the time it was last written, in
+    /// milliseconds since the Unix epoch.
+    candidates: Database,
+    /// Holds the progress of indexing the tries written while references were not tracked.
+    state: Database,
+}
+
+impl TrieReferences {
+    /// Creates or opens the databases tracking the references of the trie store with the given
+    /// name.
+    pub(crate) fn new(
+        env: &LmdbEnvironment,
+        maybe_name: Option<&str>,
+    ) -> Result<Self, error::Error> {
+        let env = env.env();
+        let flags = DatabaseFlags::empty();
+        Ok(TrieReferences {
+            referrers: env.create_db(Some(&name(REFERRERS_NAME, maybe_name)), flags)?,
+            candidates: env.create_db(Some(&name(CANDIDATES_NAME, maybe_name)), flags)?,
+            state: env.create_db(Some(&name(STATE_NAME, maybe_name)), flags)?,
+        })
+    }
+
+    /// Marks the tries of the store with the given name as no longer indexed, if references were
+    /// tracked for it before.
+    ///
+    /// Called when the store is opened without tracking references, as the tries written then are
+    /// not recorded.
+    pub(crate) fn invalidate(
+        env: &LmdbEnvironment,
+        maybe_name: Option<&str>,
+    ) -> Result<(), error::Error> {
+        let state = match env.env().open_db(Some(&name(STATE_NAME, maybe_name))) {
+            Ok(state) => state,
+            Err(lmdb::Error::NotFound) => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+        let mut txn = env.env().begin_rw_txn()?;
+        delete_if_present(&mut txn, state, INDEXING_POSITION_KEY)?;
+        delete_if_present(&mut txn, state, INDEXING_COMPLETE_KEY)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Records the references held by a trie being written, and the time it was written.
+    pub(crate) fn record_put<T>(
+        &self,
+        txn: &mut T,
+        trie_hash: &Digest,
+        trie_bytes: &[u8],
+    ) -> Result<(), error::Error>
+    where
+        T: Writable<Handle = Database>,
+        error::Error: From<T::Error>,
+    {
+        for child in trie_children(trie_bytes)? {
+            txn.write(self.referrers, &referrer_key(&child, trie_hash), &[])?;
+        }
+        txn.write(
+            self.candidates,
+            trie_hash.as_ref(),
+            &now_millis().to_le_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Records the references held by up to `max_tries` tries of the given store, resuming after
+    /// the last trie indexed by the previous call.
+    ///
+    /// Returns `true` once all tries are indexed.  Tries written meanwhile are recorded as they are
+    /// written, so indexing can run alongside other writes.
+    pub(crate) fn index(
+        &self,
+        env: &LmdbEnvironment,
+        trie_store: Database,
+        max_tries: usize,
+    ) -> Result<bool, error::Error> {
+        let mut txn = env.env().begin_rw_txn()?;
+        if get(&txn, self.state, INDEXING_COMPLETE_KEY)?.is_some() {
+            return Ok(true);
+        }
+        let position = get(&txn, self.state, INDEXING_POSITION_KEY)?.map(<[u8]>::to_vec);
+
+        let mut tries = Vec::new();
+        let mut is_complete = true;
+        {
+            let mut cursor = txn.open_ro_cursor(trie_store)?;
+            let iter = match &position {
+                Some(position) => cursor.iter_from(position),
+                // `iter_start` panics on an empty database, a new cursor is at the start already.
+                None => cursor.iter(),
+            };
+            for entry in iter {
+                let (key, value) = entry?;
+                if position.as_deref() == Some(key) {
+                    continue;
+                }
+                if tries.len() == max_tries {
+                    is_complete = false;
+                    break;
+                }
+                let trie_hash: Digest = bytesrepr::deserialize_from_slice(key)?;
+                tries.push((trie_hash, trie_children(value)?));
+            }
+        }
+
+        let now = now_millis().to_le_bytes();
+        for (trie_hash, children) in &tries {
+            for child in children {
+                txn.put(
+                    self.referrers,
+                    &referrer_key(child, trie_hash),
+                    b"",
+                    WriteFlags::empty(),
+                )?;
+            }
+            // Tries written since tracking was enabled keep the time they were written.
+            match txn.put(self.candidates, trie_hash, &now, WriteFlags::NO_OVERWRITE) {
+                Ok(()) | Err(lmdb::Error::KeyExist) => (),
+                Err(error) => return Err(error.into()),
+            }
+        }
+        if let Some((last_trie_hash, _)) = tries.last() {
+            txn.put(
+                self.state,
+                &INDEXING_POSITION_KEY,
+                last_trie_hash,
+                WriteFlags::empty(),
+            )?;
+        }
+        if is_complete {
+            txn.put(
+                self.state,
+                &INDEXING_COMPLETE_KEY,
+                &[1u8],
+                WriteFlags::empty(),
+            )?;
+        }
+        txn.commit()?;
+        Ok(is_complete)
+    }
+
+    /// Deletes the tries of the given store which are not reachable from any of the retained
+    /// state roots, other than those written within the grace period.
+    ///
+    /// A dry run only determines what would be collected, within a single read transaction.
+    /// Otherwise the candidates are checked in batches, each deleting its garbage in a read-write
+    /// transaction of its own.
+    pub(crate) fn collect_garbage(
+        &self,
+        env: &LmdbEnvironment,
+        trie_store: Database,
+        retained_roots: &BTreeSet<Digest>,
+        grace_period: Duration,
+        dry_run: bool,
+    ) -> Result<TrieGcOutcome, error::Error> {
+        let grace_period_millis = u64::try_from(grace_period.as_millis()).unwrap_or(u64::MAX);
+        let mut sweep = Sweep {
+            references: *self,
+            trie_store,
+            retained_roots,
+            cutoff: now_millis().saturating_sub(grace_period_millis),
+            doomed: BTreeMap::new(),
+            stale: Vec::new(),
+            outcome: TrieGcOutcome {
+                dry_run,
+                ..TrieGcOutcome::default()
+            },
+        };
+        let mut position = None;
+
+        if dry_run {
+            let txn = env.env().begin_ro_txn()?;
+            self.ensure_indexed(&txn)?;
+            while let Some(last_checked) = sweep.check_candidates(&txn, position.as_ref())? {
+                position = Some(last_checked);
+            }
+            return Ok(sweep.outcome);
+        }
+
+        loop {
+            let mut txn = env.env().begin_rw_txn()?;
+            self.ensure_indexed(&txn)?;
+            let maybe_last_checked = sweep.check_candidates(&txn, position.as_ref())?;
+            sweep.apply(&mut txn)?;
+            txn.commit()?;
+            match maybe_last_checked {
+                Some(last_checked) => position = Some(last_checked),
+                None => return Ok(sweep.outcome),
+            }
+        }
+    }
+
+    fn ensure_indexed<T: Transaction>(&self, txn: &T) -> Result<(), error::Error> {
+        match get(txn, self.state, INDEXING_COMPLETE_KEY)? {
+            Some(_) => Ok(()),
+            None => Err(error::Error::TrieReferencesNotIndexed),
+        }
+    }
+
+    /// Returns the hashes of the tries referring to the given child.
+    fn referrers<T: Transaction>(
+        &self,
+        txn: &T,
+        child: &Digest,
+    ) -> Result<Vec<Digest>, error::Error> {
+        let mut cursor = txn.open_ro_cursor(self.referrers)?;
+        let mut referrers = Vec::new();
+        for entry in cursor.iter_from(child) {
+            let (key, _) = entry?;
+            if !key.starts_with(child.as_ref()) {
+                break;
+            }
+            referrers.push(bytesrepr::deserialize_from_slice(&key[Digest::LENGTH..])?);
+        }
+        Ok(referrers)
+    }
+}
+
+/// The state of a garbage collection.
+struct Sweep<'a> {
+    references: TrieReferences,
+    trie_store: Database,
+    retained_roots: &'a BTreeSet<Digest>,
+    /// Candidates written after this time, in milliseconds since the Unix epoch, are kept.
+    cutoff: u64,
+    /// The tries to delete which are not deleted yet, with their children.
+    doomed: BTreeMap<Digest, BTreeSet<Digest>>,
+    /// The candidates to drop, which are referenced or already deleted.
+    stale: Vec<Digest>,
+    outcome: TrieGcOutcome,
+}
+
+impl<'a> Sweep<'a> {
+    /// Checks the next batch of candidates after the given position, planning the deletion of
+    /// those which are garbage.
+    ///
+    /// Returns the last candidate checked if there are more to check.
+    fn check_candidates<T: Transaction>(
+        &mut self,
+        txn: &T,
+        position: Option<&Digest>,
+    ) -> Result<Option<Digest>, error::Error> {
+        let mut candidates = Vec::with_capacity(CANDIDATES_PER_TRANSACTION + 1);
+        {
+            let mut cursor = txn.open_ro_cursor(self.references.candidates)?;
+            let iter = match position {
+                Some(position) => cursor.iter_from(position),
+                None => cursor.iter(),
+            };
+            for entry in iter {
+                let (key, value) = entry?;
+                let candidate: Digest = bytesrepr::deserialize_from_slice(key)?;
+                if Some(&candidate) == position {
+                    continue;
+                }
+                let written_at: u64 = bytesrepr::deserialize_from_slice(value)?;
+                candidates.push((candidate, written_at));
+                if candidates.len() > CANDIDATES_PER_TRANSACTION {
+                    break;
+                }
+            }
+        }
+        let has_more = candidates.len() > CANDIDATES_PER_TRANSACTION;
+        candidates.truncate(CANDIDATES_PER_TRANSACTION);
+
+        for &(candidate, written_at) in &candidates {
+            self.outcome.candidates_checked += 1;
+            if written_at > self.cutoff
+                || self.retained_roots.contains(&candidate)
+                || self.doomed.contains_key(&candidate)
+            {
+                continue;
+            }
+            if get(txn, self.trie_store, candidate.as_ref())?.is_none()
+                || !self.references.referrers(txn, &candidate)?.is_empty()
+            {
+                if !self.outcome.dry_run {
+                    self.stale.push(candidate);
+                }
+                continue;
+            }
+            self.collect(txn, candidate)?;
+        }
+
+        Ok(if has_more {
+            candidates.last().map(|(candidate, _)| *candidate)
+        } else {
+            None
+        })
+    }
+
+    /// Plans the deletion of an unreferenced trie, and of the descendants only referred to by
+    /// tries being deleted.
+    fn collect<T: Transaction>(&mut self, txn: &T, trie_hash: Digest) -> Result<(), error::Error> {
+        let mut pending = vec![trie_hash];
+        while let Some(trie_hash) = pending.pop() {
+            if self.retained_roots.contains(&trie_hash) || self.doomed.contains_key(&trie_hash) {
+                continue;
+            }
+            let trie_bytes = match get(txn, self.trie_store, trie_hash.as_ref())? {
+                Some(trie_bytes) => trie_bytes,
+                None => continue,
+            };
+            let referrers = self.references.referrers(txn, &trie_hash)?;
+            if referrers
+                .iter()
+                .any(|referrer| !self.doomed.contains_key(referrer))
+            {
+                continue;
+            }
+            let children = trie_children(trie_bytes)?;
+            pending.extend(children.iter().copied());
+            self.outcome.tries_collected += 1;
+            self.outcome.bytes_collected += trie_bytes.len() as u64;
+            self.doomed.insert(trie_hash, children);
+        }
+        Ok(())
+    }
+
+    /// Deletes the tries planned for deletion, along with the references they hold, and drops the
+    /// stale candidates.
+    fn apply(&mut self, txn: &mut RwTransaction) -> Result<(), error::Error> {
+        for (trie_hash, children) in mem::take(&mut self.doomed) {
+            txn.del(self.trie_store, &trie_hash, None)?;
+            delete_if_present(txn, self.references.candidates, trie_hash.as_ref())?;
+            for child in children {
+                delete_if_present(
+                    txn,
+                    self.references.referrers,
+                    &referrer_key(&child, &trie_hash),
+                )?;
+            }
+        }
+        for candidate in mem::take(&mut self.stale) {
+            delete_if_present(txn, self.references.candidates, candidate.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+fn name(prefix: &str, maybe_name: Option<&str>) -> String {
+    maybe_name
+        .map(|name| format!("{}-{}", prefix, name))
+        .unwrap_or_else(|| String::from(prefix))
+}
+
+/// Returns the key of the edge from a child to a trie referring to it.
+fn referrer_key(child: &Digest, referrer: &Digest) -> Vec<u8> {
+    let mut key = Vec::with_capacity(2 * Digest::LENGTH);
+    key.extend_from_slice(child.as_ref());
+    key.extend_from_slice(referrer.as_ref());
+    key
+}
+
+/// Returns the hashes of the children of a serialized trie.
+fn trie_children(trie_bytes: &[u8]) -> Result<BTreeSet<Digest>, error::Error> {
+    // Leaves have no children, and are not deserialized as they can be large.
+    if trie::lazy_trie_tag(trie_bytes) == Some(TrieTag::Leaf) {
+        return Ok(BTreeSet::new());
+    }
+    // Nodes and extensions hold no keys or values, so their types are irrelevant.
+    let trie: Trie<Bytes, Bytes> = bytesrepr::deserialize_from_slice(trie_bytes)?;
+    Ok(trie.iter_children().collect())
+}
+
+fn get<'txn, T: Transaction>(
+    txn: &'txn T,
+    db: Database,
+    key: &[u8],
+) -> Result<Option<&'txn [u8]>, lmdb::Error> {
+    match txn.get(db, &key) {
+        Ok(value) => Ok(Some(value)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+fn delete_if_present(txn: &mut RwTransaction, db: Database, key: &[u8]) -> Result<(), lmdb::Error> {
+    match txn.del(db, &key, None) {
+        Ok(()) | Err(lmdb::Error::NotFound) => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::bytesrepr::ToBytes;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::storage::{
+        store::Store,
+        transaction_source::TransactionSource,
+        trie::{Pointer, PointerBlock},
+        trie_store::lmdb::LmdbTrieStore,
+        DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+    };
+
+    fn leaf(key: u8) -> Trie<Bytes, Bytes> {
+        Trie::Leaf {
+            key: Bytes::from(vec![key, 0, 0]),
+            value: Bytes::from(vec![key; 8]),
+        }
+    }
+
+    fn node(children: &[&Trie<Bytes, Bytes>]) -> Trie<Bytes, Bytes> {
+        let mut pointer_block = PointerBlock::new();
+        for (index, child) in children.iter().enumerate() {
+            pointer_block[index] = Some(Pointer::LeafPointer(hash(child)));
+        }
+        Trie::Node {
+            pointer_block: Box::new(pointer_block),
+        }
+    }
+
+    fn hash(trie: &Trie<Bytes, Bytes>) -> Digest {
+        Digest::hash(trie.to_bytes().unwrap())
+    }
+
+    fn put(env: &LmdbEnvironment, store: &LmdbTrieStore, tries: &[&Trie<Bytes, Bytes>]) {
+        let mut txn = env.create_read_write_txn().unwrap();
+        for trie in tries {
+            Store::<Digest, Trie<Bytes, Bytes>>::put(store, &mut txn, &hash(trie), trie).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    fn is_present(env: &LmdbEnvironment, store: &LmdbTrieStore, trie: &Trie<Bytes, Bytes>) -> bool {
+        let txn = env.create_read_txn().unwrap();
+        let maybe_trie: Option<Trie<Bytes, Bytes>> = store.get(&txn, &hash(trie)).unwrap();
+        maybe_trie.is_some()
+    }
+
+    #[test]
+    fn should_collect_tries_unreachable_from_retained_roots() {
+        let tmp_dir = tempdir().unwrap();
+        let env = LmdbEnvironment::new(
+            tmp_dir.path(),
+            DEFAULT_TEST_MAX_DB_SIZE,
+            DEFAULT_TEST_MAX_READERS,
+            true,
+        )
+        .unwrap();
+        let store =
+            LmdbTrieStore::with_reference_tracking(&env, None, DatabaseFlags::empty()).unwrap();
+
+        // The old state shares `shared` with the new one, `replaced` is only reachable from it.
+        let shared = leaf(1);
+        let replaced = leaf(2);
+        let added = leaf(3);
+        let old_root = node(&[&shared, &replaced]);
+        let new_root = node(&[&shared, &added]);
+        put(&env, &store, &[&old_root, &shared, &replaced]);
+        put(&env, &store, &[&new_root, &added]);
+
+        assert!(!store.index_references(&env, 2).unwrap());
+        assert!(!store.index_references(&env, 2).unwrap());
+        assert!(store.index_references(&env, 2).unwrap());
+
+        let retained_roots = [hash(&new_root)].iter().copied().collect();
+        let all_tries = [&old_root, &new_root, &shared, &replaced, &added];
+
+        let outcome = store
+            .collect_garbage(&env, &retained_roots, Duration::from_secs(3_600), false)
+            .unwrap();
+        assert_eq!(outcome.tries_collected, 0);
+
+        let outcome = store
+            .collect_garbage(&env, &retained_roots, Duration::ZERO, true)
+            .unwrap();
+        assert!(outcome.dry_run);
+        assert_eq!(outcome.tries_collected, 2);
+        let expected_bytes = old_root.serialized_length() + replaced.serialized_length();
+        assert_eq!(outcome.bytes_collected, expected_bytes as u64);
+        assert!(all_tries.iter().all(|trie| is_present(&env, &store, trie)));
+
+        let outcome = store
+            .collect_garbage(&env, &retained_roots, Duration::ZERO, false)
+            .unwrap();
+        assert!(!outcome.dry_run);
+        assert_eq!(outcome.tries_collected, 2);
+        assert!(!is_present(&env, &store, &old_root));
+        assert!(!is_present(&env, &store, &replaced));
+        assert!(is_present(&env, &store, &new_root));
+        assert!(is_present(&env, &store, &shared));
+        assert!(is_present(&env, &store, &added));
+
+        let outcome = store
+            .collect_garbage(&env, &retained_roots, Duration::ZERO, false)
+            .unwrap();
+        assert_eq!(outcome.tries_collected, 0);
+    }
+
+    #[test]
+    fn should_require_indexing_after_writes_without_tracking() {
+        let tmp_dir = tempdir().unwrap();
+        let env = LmdbEnvironment::new(
+            tmp_dir.path(),
+            DEFAULT_TEST_MAX_DB_SIZE,
+            DEFAULT_TEST_MAX_READERS,
+            true,
+        )
+        .unwrap();
+        let untracked = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
+        let shared = leaf(1);
+        let old_root = node(&[&shared]);
+        put(&env, &untracked, &[&old_root, &shared]);
+        assert_eq!(
+            untracked.collect_garbage(&env, &BTreeSet::new(), Duration::ZERO, false),
+            Err(error::Error::TrieReferencesNotTracked)
+        );
+
+        let store =
+            LmdbTrieStore::with_reference_tracking(&env, None, DatabaseFlags::empty()).unwrap();
+        assert_eq!(
+            store.collect_garbage(&env, &BTreeSet::new(), Duration::ZERO, false),
+            Err(error::Error::TrieReferencesNotIndexed)
+        );
+        assert!(store.index_references(&env, 10).unwrap());
+
+        // Reopening the store without tracking invalidates the index.
+        let _ = LmdbTrieStore::open(&env, None).unwrap();
+        assert_eq!(
+            store.collect_garbage(&env, &BTreeSet::new(), Duration::ZERO, false),
+            Err(error::Error::TrieReferencesNotIndexed)
+        );
+        assert!(store.index_references(&env, 10).unwrap());
+
+        // The shared leaf is still referenced by the new root, so only the old root is collected.
+        let new_root = node(&[&shared, &leaf(2)]);
+        put(&env, &store, &[&new_root]);
+        let retained_roots = [hash(&new_root)].iter().copied().collect();
+        let outcome = store
+            .collect_garbage(&env, &retained_roots, Duration::ZERO, false)
+            .unwrap();
+        assert_eq!(outcome.tries_collected, 1);
+        assert!(!is_present(&env, &store, &old_root));
+        assert!(is_present(&env, &store, &shared));
+    }
+}
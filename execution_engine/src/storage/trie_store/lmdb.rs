@@ -105,8 +105,9 @@
 //! ```
 use std::{
     borrow::Cow,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, BTreeSet, HashMap},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use casper_types::{
@@ -123,7 +124,11 @@ use crate::storage::{
     store::Store,
     transaction_source::{lmdb::LmdbEnvironment, Readable, TransactionSource, Writable},
     trie::{self, LazyTrieLeaf, Trie},
-    trie_store::{self, TrieStore},
+    trie_store::{
+        self,
+        gc::{TrieGcOutcome, TrieReferences},
+        TrieStore,
+    },
 };
 
 /// An LMDB-backed trie store.
@@ -132,6 +137,7 @@ use crate::storage::{
 #[derive(Debug, Clone)]
 pub struct LmdbTrieStore {
     db: Database,
+    references: Option<TrieReferences>,
 }
 
 impl LmdbTrieStore {
@@ -143,14 +149,42 @@ impl LmdbTrieStore {
     ) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().create_db(Some(&name), flags)?;
-        Ok(LmdbTrieStore { db })
+        TrieReferences::invalidate(env, maybe_name)?;
+        Ok(LmdbTrieStore {
+            db,
+            references: None,
+        })
+    }
+
+    /// Constructor for new `LmdbTrieStore` which tracks the references between the tries written
+    /// to it, allowing the tries no longer reachable from any retained state root to be garbage
+    /// collected.
+    ///
+    /// The tries written before are only tracked once indexed, see
+    /// [`LmdbTrieStore::index_references`].
+    pub fn with_reference_tracking(
+        env: &LmdbEnvironment,
+        maybe_name: Option<&str>,
+        flags: DatabaseFlags,
+    ) -> Result<Self, error::Error> {
+        let name = Self::name(maybe_name);
+        let db = env.env().create_db(Some(&name), flags)?;
+        let references = TrieReferences::new(env, maybe_name)?;
+        Ok(LmdbTrieStore {
+            db,
+            references: Some(references),
+        })
     }
 
     /// Constructor for `LmdbTrieStore` which opens an existing lmdb store file.
     pub fn open(env: &LmdbEnvironment, maybe_name: Option<&str>) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().open_db(Some(&name))?;
-        Ok(LmdbTrieStore { db })
+        TrieReferences::invalidate(env, maybe_name)?;
+        Ok(LmdbTrieStore {
+            db,
+            references: None,
+        })
     }
 
     fn name(maybe_name: Option<&str>) -> String {
@@ -163,6 +197,44 @@ impl LmdbTrieStore {
     pub fn get_db(&self) -> Database {
         self.db
     }
+
+    /// Returns `true` if this store tracks the references between its tries.
+    pub fn is_tracking_references(&self) -> bool {
+        self.references.is_some()
+    }
+
+    /// Records the references held by up to `max_tries` tries written before references were
+    /// tracked, resuming where the previous call stopped.
+    ///
+    /// Returns `true` once all of them are indexed, which is required to collect garbage.
+    pub fn index_references(
+        &self,
+        env: &LmdbEnvironment,
+        max_tries: usize,
+    ) -> Result<bool, error::Error> {
+        self.references()?.index(env, self.db, max_tries)
+    }
+
+    /// Deletes the tries not reachable from any of the retained state roots, other than those
+    /// written within the grace period.
+    ///
+    /// A dry run only reports what would be collected, without deleting anything.
+    pub fn collect_garbage(
+        &self,
+        env: &LmdbEnvironment,
+        retained_roots: &BTreeSet<Digest>,
+        grace_period: Duration,
+        dry_run: bool,
+    ) -> Result<TrieGcOutcome, error::Error> {
+        self.references()?
+            .collect_garbage(env, self.db, retained_roots, grace_period, dry_run)
+    }
+
+    fn references(&self) -> Result<&TrieReferences, error::Error> {
+        self.references
+            .as_ref()
+            .ok_or(error::Error::TrieReferencesNotTracked)
+    }
 }
 
 impl<K, V> Store<Digest, Trie<K, V>> for LmdbTrieStore {
@@ -173,6 +245,23 @@ impl<K, V> Store<Digest, Trie<K, V>> for LmdbTrieStore {
     fn handle(&self) -> Self::Handle {
         self.db
     }
+
+    fn put_raw<T>(
+        &self,
+        txn: &mut T,
+        key: &Digest,
+        value_bytes: Cow<'_, [u8]>,
+    ) -> Result<(), Self::Error>
+    where
+        T: Writable<Handle = Self::Handle>,
+        Self::Error: From<T::Error>,
+    {
+        if let Some(references) = &self.references {
+            references.record_put(txn, key, &value_bytes)?;
+        }
+        txn.write(self.db, key.as_ref(), &value_bytes)
+            .map_err(Into::into)
+    }
 }
 
 impl<K, V> TrieStore<K, V> for LmdbTrieStore {}
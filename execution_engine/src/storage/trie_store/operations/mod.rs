@@ -289,6 +289,286 @@ where
     })
 }
 
+/// A change of the value stored under a key, along with the value before and after the change.
+pub type ValueChange<K, V> = (K, Option<V>, Option<V>);
+
+/// The result of diffing the tries under two roots.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffResult<K, V> {
+    /// The changes of the stored values, ordered by the serialized keys.
+    Found(Vec<ValueChange<K, V>>),
+    /// Either root, or a trie below it, was not found.
+    RootNotFound,
+}
+
+/// A subtrie visited while diffing two tries.
+enum Subtrie<K, V> {
+    /// A trie in the store, not yet read.
+    Stored(Digest),
+    /// A trie which has been read, or one which is not in the store as such, i.e. the remainder of
+    /// an extension.
+    Loaded(Trie<K, V>),
+}
+
+/// Returns the changes of the values stored under `from_root` needed to arrive at the values stored
+/// under `to_root`.
+///
+/// Both tries are walked in lockstep and subtries with equal hashes are skipped, so the cost is
+/// proportional to the size of the difference rather than the size of the tries.
+pub fn diff<K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    from_root: &Digest,
+    to_root: &Digest,
+) -> Result<DiffResult<K, V>, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut changes = Vec::new();
+    let complete = diff_subtries::<K, V, T, S, E>(
+        txn,
+        store,
+        Some(Subtrie::Stored(*from_root)),
+        Some(Subtrie::Stored(*to_root)),
+        &mut changes,
+    )?;
+    if complete {
+        Ok(DiffResult::Found(changes))
+    } else {
+        Ok(DiffResult::RootNotFound)
+    }
+}
+
+/// Appends the changes between two subtries at the same path to `changes`.
+///
+/// Returns `false` if a trie was not found in the store.
+fn diff_subtries<K, V, T, S, E>(
+    txn: &T,
+    store: &S,
+    from: Option<Subtrie<K, V>>,
+    to: Option<Subtrie<K, V>>,
+    changes: &mut Vec<ValueChange<K, V>>,
+) -> Result<bool, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    if let (Some(Subtrie::Stored(from_hash)), Some(Subtrie::Stored(to_hash))) = (&from, &to) {
+        if from_hash == to_hash {
+            return Ok(true);
+        }
+    }
+
+    let from = match from
+        .map(|subtrie| load_subtrie::<K, V, T, S, E>(txn, store, subtrie))
+        .transpose()?
+    {
+        Some(None) => return Ok(false),
+        maybe_trie => maybe_trie.flatten(),
+    };
+    let to = match to
+        .map(|subtrie| load_subtrie::<K, V, T, S, E>(txn, store, subtrie))
+        .transpose()?
+    {
+        Some(None) => return Ok(false),
+        maybe_trie => maybe_trie.flatten(),
+    };
+
+    let (from_children, to_children) = match (
+        from.as_ref().map(subtrie_children),
+        to.as_ref().map(subtrie_children),
+    ) {
+        (Some(None), _) | (_, Some(None)) => {
+            // At least one side is a leaf, so the shapes of the subtries diverge: compare their
+            // leaves directly.
+            return diff_leaves::<K, V, T, S, E>(txn, store, from, to, changes);
+        }
+        (maybe_from_children, maybe_to_children) => (
+            maybe_from_children.flatten().unwrap_or_default(),
+            maybe_to_children.flatten().unwrap_or_default(),
+        ),
+    };
+
+    let mut from_children = from_children.into_iter().peekable();
+    let mut to_children = to_children.into_iter().peekable();
+    loop {
+        let (from_child, to_child) = match (from_children.peek(), to_children.peek()) {
+            (None, None) => return Ok(true),
+            (Some((from_index, _)), Some((to_index, _))) if from_index == to_index => (
+                from_children.next().map(|(_, child)| child),
+                to_children.next().map(|(_, child)| child),
+            ),
+            (Some((from_index, _)), Some((to_index, _))) if from_index < to_index => {
+                (from_children.next().map(|(_, child)| child), None)
+            }
+            (Some(_), None) => (from_children.next().map(|(_, child)| child), None),
+            (_, Some(_)) => (None, to_children.next().map(|(_, child)| child)),
+        };
+        if !diff_subtries::<K, V, T, S, E>(txn, store, from_child, to_child, changes)? {
+            return Ok(false);
+        }
+    }
+}
+
+/// Reads the trie of a subtrie from the store if required.
+///
+/// Returns `None` if the trie was not found.
+fn load_subtrie<K, V, T, S, E>(
+    txn: &T,
+    store: &S,
+    subtrie: Subtrie<K, V>,
+) -> Result<Option<Trie<K, V>>, E>
+where
+    K: FromBytes,
+    V: FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error>,
+{
+    match subtrie {
+        Subtrie::Stored(hash) => Ok(store.get(txn, &hash)?),
+        Subtrie::Loaded(trie) => Ok(Some(trie)),
+    }
+}
+
+/// Returns the children of a node or an extension, ordered by their index in the path.
+///
+/// Returns `None` for a leaf, or for an extension without an affix, neither of which can be
+/// descended into one path element at a time.
+fn subtrie_children<K, V>(trie: &Trie<K, V>) -> Option<Vec<(u8, Subtrie<K, V>)>> {
+    match trie {
+        Trie::Leaf { .. } => None,
+        Trie::Node { pointer_block } => Some(
+            pointer_block
+                .as_indexed_pointers()
+                .map(|(index, pointer)| (index, Subtrie::Stored(pointer.into_hash())))
+                .collect(),
+        ),
+        Trie::Extension { affix, pointer } => {
+            let (first, rest) = affix.split_first()?;
+            let child = if rest.is_empty() {
+                Subtrie::Stored(*pointer.hash())
+            } else {
+                Subtrie::Loaded(Trie::extension(rest.to_vec(), *pointer))
+            };
+            Some(vec![(*first, child)])
+        }
+    }
+}
+
+/// Appends the changes between the leaves of two subtries at the same path to `changes`.
+///
+/// Returns `false` if a trie was not found in the store.
+fn diff_leaves<K, V, T, S, E>(
+    txn: &T,
+    store: &S,
+    from: Option<Trie<K, V>>,
+    to: Option<Trie<K, V>>,
+    changes: &mut Vec<ValueChange<K, V>>,
+) -> Result<bool, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut from_leaves = Vec::new();
+    let mut to_leaves = Vec::new();
+    for (maybe_trie, leaves) in [(from, &mut from_leaves), (to, &mut to_leaves)] {
+        if let Some(trie) = maybe_trie {
+            if !collect_leaves::<K, V, T, S, E>(txn, store, trie, leaves)? {
+                return Ok(false);
+            }
+        }
+        leaves.sort_by(|(left, _, _), (right, _, _)| left.cmp(right));
+    }
+
+    let mut from_leaves = from_leaves.into_iter().peekable();
+    let mut to_leaves = to_leaves.into_iter().peekable();
+    loop {
+        match (from_leaves.peek(), to_leaves.peek()) {
+            (None, None) => return Ok(true),
+            (Some((from_bytes, _, _)), Some((to_bytes, _, _))) if from_bytes == to_bytes => {
+                if let (Some((_, key, from_value)), Some((_, _, to_value))) =
+                    (from_leaves.next(), to_leaves.next())
+                {
+                    if from_value != to_value {
+                        changes.push((key, Some(from_value), Some(to_value)));
+                    }
+                }
+            }
+            (Some((from_bytes, _, _)), Some((to_bytes, _, _))) if from_bytes < to_bytes => {
+                if let Some((_, key, from_value)) = from_leaves.next() {
+                    changes.push((key, Some(from_value), None));
+                }
+            }
+            (Some(_), None) => {
+                if let Some((_, key, from_value)) = from_leaves.next() {
+                    changes.push((key, Some(from_value), None));
+                }
+            }
+            (_, Some(_)) => {
+                if let Some((_, key, to_value)) = to_leaves.next() {
+                    changes.push((key, None, Some(to_value)));
+                }
+            }
+        }
+    }
+}
+
+/// Appends the serialized keys, keys and values of all leaves of the given trie to `leaves`.
+///
+/// Returns `false` if a trie was not found in the store.
+fn collect_leaves<K, V, T, S, E>(
+    txn: &T,
+    store: &S,
+    trie: Trie<K, V>,
+    leaves: &mut Vec<(Vec<u8>, K, V)>,
+) -> Result<bool, E>
+where
+    K: ToBytes + FromBytes,
+    V: FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut tries_to_visit = vec![trie];
+    while let Some(trie) = tries_to_visit.pop() {
+        let pointers = match trie {
+            Trie::Leaf { key, value } => {
+                leaves.push((key.to_bytes()?, key, value));
+                continue;
+            }
+            Trie::Node { pointer_block } => pointer_block
+                .as_indexed_pointers()
+                .map(|(_, pointer)| pointer)
+                .collect(),
+            Trie::Extension { pointer, .. } => vec![pointer],
+        };
+        for pointer in pointers {
+            match store.get(txn, pointer.hash())? {
+                Some(child) => tries_to_visit.push(child),
+                None => return Ok(false),
+            }
+        }
+    }
+    Ok(true)
+}
+
 struct TrieScan<K, V> {
     tip: Trie<K, V>,
     parents: Parents<K, V>,
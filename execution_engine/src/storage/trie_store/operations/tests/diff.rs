@@ -0,0 +1,133 @@
+//! This module contains tests for [`operations::diff`].
+
+use super::*;
+use crate::storage::{
+    error::{self, in_memory},
+    trie_store::operations::{diff, DiffResult, ValueChange},
+};
+
+fn test_pairs(leaves: &[TestTrie]) -> Vec<(TestKey, TestValue)> {
+    leaves
+        .iter()
+        .filter_map(|leaf| match leaf {
+            Trie::Leaf { key, value } => Some((*key, *value)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn diffs_between_states_had_expected_results<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root_hash: &Digest,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let pairs = test_pairs(&TEST_LEAVES);
+    let states =
+        write_pairs::<_, _, _, _, E>(correlation_id, environment, store, root_hash, &pairs)?;
+    let full_root = *states.last().unwrap();
+    let updated_pairs = test_pairs(&TEST_LEAVES_UPDATED);
+    let updated_root = *write_pairs::<_, _, _, _, E>(
+        correlation_id,
+        environment,
+        store,
+        &full_root,
+        &updated_pairs,
+    )?
+    .last()
+    .unwrap();
+
+    let sorted = |mut changes: Vec<ValueChange<TestKey, TestValue>>| {
+        changes.sort_by_key(|(key, _, _)| *key);
+        DiffResult::Found(changes)
+    };
+
+    let txn = environment.create_read_txn()?;
+
+    // Written values show up as created, and as deleted when diffing the other way round.
+    let created = pairs
+        .iter()
+        .map(|(key, value)| (*key, None, Some(*value)))
+        .collect();
+    assert_eq!(
+        diff::<_, _, _, _, E>(correlation_id, &txn, store, root_hash, &full_root)?,
+        sorted(created)
+    );
+    let deleted = pairs
+        .iter()
+        .map(|(key, value)| (*key, Some(*value), None))
+        .collect();
+    assert_eq!(
+        diff::<_, _, _, _, E>(correlation_id, &txn, store, &full_root, root_hash)?,
+        sorted(deleted)
+    );
+
+    // Each write changes a single value, regardless of how the trie was restructured.
+    for (index, window) in states.windows(2).enumerate() {
+        let (key, value) = pairs[index + 1];
+        assert_eq!(
+            diff::<_, _, _, _, E>(correlation_id, &txn, store, &window[0], &window[1])?,
+            DiffResult::Found(vec![(key, None, Some(value))])
+        );
+    }
+
+    // Overwritten values show up as modified.
+    let modified = pairs
+        .iter()
+        .zip(&updated_pairs)
+        .map(|((key, value), (_, updated_value))| (*key, Some(*value), Some(*updated_value)))
+        .collect();
+    assert_eq!(
+        diff::<_, _, _, _, E>(correlation_id, &txn, store, &full_root, &updated_root)?,
+        sorted(modified)
+    );
+
+    assert_eq!(
+        diff::<_, _, _, _, E>(correlation_id, &txn, store, &full_root, &full_root)?,
+        DiffResult::Found(vec![])
+    );
+    let missing_root = Digest::hash([1u8; 32]);
+    assert_eq!(
+        diff::<_, _, _, _, E>(correlation_id, &txn, store, &full_root, &missing_root)?,
+        DiffResult::RootNotFound
+    );
+
+    txn.commit()?;
+    Ok(())
+}
+
+#[test]
+fn lmdb_diffs_between_states_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+
+    diffs_between_states_had_expected_results::<_, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diffs_between_states_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    diffs_between_states_had_expected_results::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
@@ -161,6 +161,21 @@ impl Error {
             data,
         }
     }
+
+    /// Returns the "code" field.
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    /// Returns the "message" field.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the "data" field.
+    pub fn data(&self) -> Option<&Value> {
+        self.data.as_ref()
+    }
 }
 
 #[cfg(test)]
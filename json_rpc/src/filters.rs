@@ -7,7 +7,9 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::{stream, StreamExt};
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
 use warp::{
@@ -24,10 +26,21 @@ use crate::{
     request::{ErrorOrRejection, Request},
     request_handlers::RequestHandlers,
     response::Response,
+    BatchLimits,
 };
 
 const CONTENT_TYPE_VALUE: &str = "application/json";
 
+/// The body of a JSON-RPC reply.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ResponseBody {
+    /// The response to a single request, or to an invalid batch as a whole.
+    Single(Response),
+    /// The responses to all requests of a batch, in the same order as the requests.
+    Batch(Vec<Response>),
+}
+
 /// Returns a boxed warp filter which handles the initial setup.
 ///
 /// This includes:
@@ -66,10 +79,95 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
-/// handler, and providing a JSON-RPC response (which could be a success or failure).
+/// Validates a single JSON-RPC request, executing it using the appropriate handler, and providing a
+/// JSON-RPC response (which could be a success or failure).
 ///
 /// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
+/// specification, i.e. the request doesn't contain an "id" field.
+async fn handle_request(
+    unvalidated_request: Map<String, Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Result<Response, Rejection> {
+    match Request::new(unvalidated_request, allow_unknown_fields) {
+        Ok(request) => Ok(handlers.handle_request(request).await),
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request");
+            Ok(Response::new_failure(id, error))
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            debug!(?rejection, "rejecting an invalid request");
+            Err(rejection)
+        }
+    }
+}
+
+/// Validates and executes all JSON-RPC requests of a batch, at most
+/// `batch_limits.max_concurrent_requests` of them at a time.
+///
+/// A batch which is empty or exceeds `batch_limits.max_batch_size` is answered with a single
+/// failure response.  Since Notifications are not supported, requests without an "id" field are
+/// answered with a failure response rather than being skipped.
+async fn handle_batch(
+    unvalidated_requests: Vec<Value>,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    batch_limits: BatchLimits,
+) -> ResponseBody {
+    let batch_size = unvalidated_requests.len();
+    let error = if batch_limits.max_batch_size == 0 {
+        Some("Batch requests are not supported".to_string())
+    } else if batch_size == 0 {
+        Some("Batch request is empty".to_string())
+    } else if batch_size > batch_limits.max_batch_size as usize {
+        Some(format!(
+            "Batch request of {} requests exceeds the maximum of {}",
+            batch_size, batch_limits.max_batch_size
+        ))
+    } else {
+        None
+    };
+    if let Some(message) = error {
+        debug!(batch_size, %message, "got an invalid batch request");
+        let error = Error::new(ReservedErrorCode::InvalidRequest, message);
+        return ResponseBody::Single(Response::new_failure(Value::Null, error));
+    }
+
+    let responses = stream::iter(unvalidated_requests)
+        .map(|unvalidated_request| {
+            let handlers = handlers.clone();
+            async move {
+                let unvalidated_request = match unvalidated_request {
+                    Value::Object(unvalidated_request) => unvalidated_request,
+                    _ => {
+                        let error = Error::new(
+                            ReservedErrorCode::InvalidRequest,
+                            "Expected each request of a batch to be an Object",
+                        );
+                        return Response::new_failure(Value::Null, error);
+                    }
+                };
+                match handle_request(unvalidated_request, &handlers, allow_unknown_fields).await {
+                    Ok(response) => response,
+                    Err(_) => {
+                        let error =
+                            Error::new(ReservedErrorCode::InvalidRequest, MissingId.to_string());
+                        Response::new_failure(Value::Null, error)
+                    }
+                }
+            }
+        })
+        .buffered(batch_limits.max_concurrent_requests.max(1) as usize)
+        .collect()
+        .await;
+    ResponseBody::Batch(responses)
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests from the given HTTP body, executing
+/// them using the appropriate handlers, and providing the JSON-RPC responses (which could be
+/// successes or failures).
+///
+/// Returns an `Err(Rejection)` only if a single request is a Notification as per the JSON-RPC
 /// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
 /// response is sent to the client.
 ///
@@ -79,26 +177,58 @@ async fn handle_body(
     body: Bytes,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
-    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
-        Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
-            Ok(request) => handlers.handle_request(request).await,
-            Err(ErrorOrRejection::Error { id, error }) => {
-                debug!(?error, "got an invalid request");
-                Response::new_failure(id, error)
-            }
-            Err(ErrorOrRejection::Rejection(rejection)) => {
-                debug!(?rejection, "rejecting an invalid request");
-                return Err(rejection);
-            }
-        },
+    batch_limits: BatchLimits,
+) -> Result<ResponseBody, Rejection> {
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Object(unvalidated_request)) => {
+            handle_request(unvalidated_request, &handlers, allow_unknown_fields).await?
+        }
+        Ok(Value::Array(unvalidated_requests)) => {
+            return Ok(handle_batch(
+                unvalidated_requests,
+                handlers,
+                allow_unknown_fields,
+                batch_limits,
+            )
+            .await);
+        }
+        Ok(value) => {
+            debug!(%value, "got json which is neither a request nor a batch");
+            let error = Error::new(
+                ReservedErrorCode::InvalidRequest,
+                format!(
+                    "Expected a request Object or a batch Array, but got '{}'",
+                    value
+                ),
+            );
+            Response::new_failure(Value::Null, error)
+        }
         Err(error) => {
             debug!(%error, "got bad json");
             let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
             Response::new_failure(Value::Null, error)
         }
     };
-    Ok(response)
+    Ok(ResponseBody::Single(response))
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests received other than as an HTTP body, e.g.
+/// as a WebSocket message, executing them using the appropriate handlers, and providing the JSON-RPC
+/// reply.
+///
+/// Returns `None` only if a single request is a Notification as per the JSON-RPC specification,
+/// i.e. the request doesn't contain an "id" field.  In this case, nothing should be sent to the
+/// client.
+pub async fn handle_message(
+    message: Bytes,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    batch_limits: BatchLimits,
+) -> Option<Value> {
+    let response = handle_body(message, handlers, allow_unknown_fields, batch_limits)
+        .await
+        .ok()?;
+    Some(json!(response))
 }
 
 /// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
@@ -112,14 +242,19 @@ async fn handle_body(
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
+///
+/// The body may also hold a batch of requests, i.e. an array of them, in which case the reply is an
+/// array of the corresponding responses.  Batches exceeding `batch_limits.max_batch_size` are not
+/// executed, but answered with a single failure response.
 pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    batch_limits: BatchLimits,
 ) -> BoxedFilter<(WithStatus<reply::Json>,)> {
     body::bytes()
         .and_then(move |body| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(body, handlers, allow_unknown_fields, batch_limits).await }
         })
         .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
@@ -11,11 +11,15 @@ use warp::{filters::BoxedFilter, Filter, Reply};
 use super::ResponseBodyOnRejection;
 use crate::{
     filters::{handle_rejection, main_filter},
-    Error, Params, RequestHandlersBuilder, ReservedErrorCode, Response,
+    BatchLimits, Error, Params, RequestHandlersBuilder, ReservedErrorCode, Response,
 };
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const BATCH_LIMITS: BatchLimits = BatchLimits {
+    max_batch_size: 3,
+    max_concurrent_requests: 2,
+};
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -49,13 +53,18 @@ async fn from_http_response(response: http::Response<hyper::Body>) -> Response {
     serde_json::from_slice(&body_bytes).unwrap()
 }
 
+async fn batch_from_http_response(response: http::Response<hyper::Body>) -> Vec<Response> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
 fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
     let mut handlers = RequestHandlersBuilder::new();
     handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
     let handlers = handlers.build();
 
-    main_filter(handlers, false)
+    main_filter(handlers, false, BATCH_LIMITS)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +327,84 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // Each request of the batch should get its own response, in the order of the requests, even
+    // if the request is invalid.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","method":"get good thing","params":["two"]},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing"}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 3);
+    assert_eq!(rpc_responses[0].id(), 1);
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+    assert_eq!(rpc_responses[1].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "The request is missing the 'id' field"
+        )
+    );
+    assert_eq!(rpc_responses[2].id(), "c");
+    assert_eq!(
+        rpc_responses[2].error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidParams, "no params")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_invalid_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]}"#;
+    let oversized_batch = format!("[{}]", vec![request; 4].join(","));
+    let expected_errors = [
+        ("[]", "Batch request is empty"),
+        (
+            oversized_batch.as_str(),
+            "Batch request of 4 requests exceeds the maximum of 3",
+        ),
+    ];
+
+    // An invalid batch should not be executed, but answered with a single response.
+    for (body, message) in expected_errors {
+        let http_response = warp::test::request()
+            .body(body)
+            .filter(&filter)
+            .await
+            .unwrap()
+            .into_response();
+
+        assert_eq!(http_response.status(), StatusCode::OK);
+        let rpc_response = from_http_response(http_response).await;
+        assert_eq!(rpc_response.id(), &Value::Null);
+        assert_eq!(
+            rpc_response.error().unwrap(),
+            &Error::new(ReservedErrorCode::InvalidRequest, message)
+        );
+    }
+}
@@ -12,7 +12,7 @@
 //! # Example
 //!
 //! ```no_run
-//! use casper_json_rpc::{Error, Params, RequestHandlersBuilder};
+//! use casper_json_rpc::{BatchLimits, Error, Params, RequestHandlersBuilder};
 //! use std::{convert::Infallible, sync::Arc};
 //!
 //! # #[allow(unused)]
@@ -40,7 +40,17 @@
 //!     let path = "rpc";
 //!     let max_body_bytes = 1024;
 //!     let allow_unknown_fields = false;
-//!     let route = casper_json_rpc::route(path, max_body_bytes, handlers, allow_unknown_fields);
+//!     let batch_limits = BatchLimits {
+//!         max_batch_size: 10,
+//!         max_concurrent_requests: 2,
+//!     };
+//!     let route = casper_json_rpc::route(
+//!         path,
+//!         max_body_bytes,
+//!         handlers,
+//!         allow_unknown_fields,
+//!         batch_limits,
+//!     );
 //!
 //!     // Convert it into a `Service` and run it.
 //!     let make_svc = hyper::service::make_service_fn(move |_| {
@@ -95,6 +105,26 @@ pub use response::Response;
 
 const JSON_RPC_VERSION: &str = "2.0";
 
+/// Limits on JSON-RPC batch requests, i.e. arrays of requests sent in a single HTTP request.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct BatchLimits {
+    /// Maximum number of requests in a single batch.  Batch requests are not supported if `0`.
+    pub max_batch_size: u32,
+    /// Maximum number of requests of a single batch which are executed concurrently.  The requests
+    /// are executed one after the other if `0` or `1`.
+    pub max_concurrent_requests: u32,
+}
+
+impl BatchLimits {
+    /// Returns limits under which batch requests are not supported.
+    pub const fn disabled() -> Self {
+        BatchLimits {
+            max_batch_size: 0,
+            max_concurrent_requests: 0,
+        }
+    }
+}
+
 /// Specifies the CORS origin
 pub enum CorsOrigin {
     /// Any (*) origin is allowed.
@@ -118,15 +148,22 @@ pub enum CorsOrigin {
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// `batch_limits` restricts the size and concurrent execution of batch requests.
+///
 /// For further details, see the docs for the [`filters`] functions.
 pub fn route<P: AsRef<str>>(
     path: P,
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    batch_limits: BatchLimits,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            batch_limits,
+        ))
         .recover(filters::handle_rejection)
         .boxed()
 }
@@ -146,6 +183,8 @@ pub fn route<P: AsRef<str>>(
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// `batch_limits` restricts the size and concurrent execution of batch requests.
+///
 /// Note that this is a convenience function combining the lower-level functions in [`filters`]
 /// along with [a warp CORS filter](https://docs.rs/warp/latest/warp/filters/cors/index.html) which
 ///   * allows any origin or specified origin
@@ -158,10 +197,15 @@ pub fn route_with_cors<P: AsRef<str>>(
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    batch_limits: BatchLimits,
     cors_header: &CorsOrigin,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            batch_limits,
+        ))
         .recover(filters::handle_rejection)
         .with(match cors_header {
             CorsOrigin::Any => warp::cors()
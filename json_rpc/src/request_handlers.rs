@@ -59,7 +59,7 @@ impl RequestHandlers {
 //
 // This builder exists so the internal `HashMap` can be populated before it is made immutable behind
 // the `Arc` in the `RequestHandlers`.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct RequestHandlersBuilder(HashMap<&'static str, RequestHandler>);
 
 impl RequestHandlersBuilder {
@@ -108,6 +108,14 @@ impl RequestHandlersBuilder {
         }
     }
 
+    /// Removes the request-handler for the given "method", returning the method's name if a handler
+    /// was registered.
+    pub fn remove_handler(&mut self, method: &str) -> Option<&'static str> {
+        self.0
+            .remove_entry(method)
+            .map(|(registered_method, _)| registered_method)
+    }
+
     /// Finalize building by converting `self` to a [`RequestHandlers`].
     pub fn build(self) -> RequestHandlers {
         RequestHandlers(Arc::new(self.0))
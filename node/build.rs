@@ -13,4 +13,11 @@ fn main() {
         "cargo:rustc-env=NODE_BUILD_PROFILE={}",
         env::var("PROFILE").unwrap()
     );
+
+    // Generate the gRPC server code, using a vendored `protoc` so that none needs to be installed.
+    env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/node_api.proto"], &["proto"])
+        .unwrap();
 }
@@ -3,10 +3,14 @@
 //! Most configuration is done via config files (see [`config`](../config/index.html) for details).
 
 pub mod arglang;
+mod emergency_restart;
+mod replay;
+mod storage;
 
 use std::{
     alloc::System,
-    fs,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter},
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -20,14 +24,26 @@ use structopt::StructOpt;
 use toml::{value::Table, Value};
 use tracing::info;
 
+use casper_hashing::Digest;
+use casper_types::EraId;
+
 use crate::{
-    components::network::Identity as NetworkIdentity,
-    logging,
+    components::{
+        consensus::{EraDump, EraReplay},
+        contract_runtime::ContractRuntime,
+        network::Identity as NetworkIdentity,
+        storage::{Storage, StorageInspector},
+    },
+    config_reload, logging,
     reactor::{main_reactor, Runner},
-    setup_signal_hooks,
-    types::{Chainspec, ChainspecRawBytes, ExitCode},
+    setup_signal_hooks, telemetry,
+    types::{
+        chainspec::{GlobalStateUpdateConfig, GLOBAL_STATE_UPDATE_FILENAME},
+        Chainspec, ChainspecRawBytes, ExitCode,
+    },
     utils::{Loadable, WithDir},
 };
+use storage::StorageCommand;
 
 // We override the standard allocator to gather metrics and tune the allocator via the MALLOC_CONF
 // env var.
@@ -78,9 +94,93 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Verify the integrity of all stored blocks, reporting any inconsistencies found.
+    ///
+    /// Must not be run while the node is running; use the diagnostics port's `verify-storage`
+    /// command instead.
+    VerifyStorage {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Discard inconsistent data and mark the affected blocks as incomplete, so that they are
+        /// synchronized again when the node is next run.
+        #[structopt(long)]
+        repair: bool,
+    },
+    /// Export a range of stored blocks, along with their deploys, finality signatures and
+    /// approvals hashes, to a file from which other nodes can import them.
+    ///
+    /// Must not be run while the node is running.
+    ExportBlocks {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Height of the first block to export.
+        #[structopt(long)]
+        from: u64,
+        /// Height of the last block to export.
+        #[structopt(long)]
+        to: u64,
+        /// Path of the file to write, which must not exist yet.
+        #[structopt(long)]
+        out: PathBuf,
+    },
+    /// Import blocks from a file written by `export-blocks`.
+    ///
+    /// Imported blocks are validated and stored, but still need to be synchronized to acquire
+    /// their execution results and global state. Must not be run while the node is running.
+    ImportBlocks {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Path of the file to read.
+        #[structopt(long = "in")]
+        input: PathBuf,
+    },
+    /// Execute a stored block again on top of its parent's post-state, reporting any divergence
+    /// from the stored post-state root hash and execution results.
+    ///
+    /// Must not be run while the node is running.
+    ReplayBlock {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Hex-encoded hash of the block to replay.
+        #[structopt(parse(try_from_str = Digest::from_hex))]
+        block_hash: Digest,
+    },
+    /// Export the global state root hash and validator set from which the network is restarted at
+    /// the given activation point in an emergency restart.
+    ///
+    /// The validator set is written to a global state update file in the given directory, to be
+    /// shipped alongside the emergency restart chainspec. Must not be run while the node is
+    /// running.
+    ExportEmergencyRestart {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// ID of the era at the start of which the network is restarted.
+        #[structopt(long)]
+        activation_point: u64,
+        /// Path of the directory to write, which must not exist yet.
+        #[structopt(long)]
+        out: PathBuf,
+    },
+    /// Inspect the stored blocks and deploys, opening the databases read-only.
+    ///
+    /// Intended for debugging a node which is not running.
+    Storage {
+        /// Path to configuration file.
+        config: PathBuf,
+        #[structopt(subcommand)]
+        command: StorageCommand,
+    },
+    /// Load a consensus era dump written by the diagnostics port's `dump-consensus --file`
+    /// command, and re-run the protocol state machine on its units, reporting any unit that fails
+    /// validation and whether the resulting state matches the dumped one.
+    ReplayConsensus {
+        /// Path of the dump file to read.
+        #[structopt(long = "in")]
+        input: PathBuf,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 /// Command line extension to be applied to TOML-based config file values.
 pub struct ConfigExt {
     section: String,
@@ -148,6 +248,7 @@ impl Cli {
             Cli::Standard { config, config_ext } => {
                 // Setup UNIX signal hooks.
                 setup_signal_hooks();
+                config_reload::set_config_source(config.clone(), config_ext.clone());
 
                 let mut validator_config = Self::init(&config, config_ext)?;
 
@@ -191,6 +292,7 @@ impl Cli {
                 .await?;
 
                 let exit_code = main_runner.run(&mut rng).await;
+                telemetry::shutdown();
                 Ok(exit_code as i32)
             }
             Cli::MigrateConfig {
@@ -237,39 +339,241 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::VerifyStorage { config, repair } => {
+                info!(build_version = %crate::VERSION_STRING.as_str(), "verifying storage");
+                let mut storage = Self::open_storage(&config)?;
+                let verification = storage.verify_integrity(repair)?;
+                println!("{}", verification);
+                if !verification.issues.is_empty() && !verification.repaired {
+                    bail!(
+                        "storage verification found {} issue(s)",
+                        verification.issues.len()
+                    );
+                }
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportBlocks {
+                config,
+                from,
+                to,
+                out,
+            } => {
+                if from > to {
+                    bail!("first height {} is above last height {}", from, to);
+                }
+                info!(build_version = %crate::VERSION_STRING.as_str(), from, to, "exporting blocks");
+                let storage = Self::open_storage(&config)?;
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&out)
+                    .with_context(|| format!("could not create {}", out.display()))?;
+                let exported_count = storage.export_blocks(from, to, &mut BufWriter::new(file))?;
+                info!(exported_count, path = %out.display(), "exported blocks");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportBlocks { config, input } => {
+                info!(build_version = %crate::VERSION_STRING.as_str(), "importing blocks");
+                let mut storage = Self::open_storage(&config)?;
+                let file = File::open(&input)
+                    .with_context(|| format!("could not open {}", input.display()))?;
+                let imported_count = storage.import_blocks(&mut BufReader::new(file))?;
+                info!(imported_count, path = %input.display(), "imported blocks");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ReplayBlock { config, block_hash } => {
+                info!(build_version = %crate::VERSION_STRING.as_str(), %block_hash, "replaying block");
+                let validator_config = Self::init(&config, vec![])?;
+                let (chainspec, _) =
+                    <(Chainspec, ChainspecRawBytes)>::from_path(validator_config.dir())?;
+                let mut storage = Self::new_storage(&validator_config, &chainspec)?;
+                let contract_runtime =
+                    Self::new_contract_runtime(&validator_config, &chainspec, &storage)?;
+                let replay = replay::replay_block(
+                    &mut storage,
+                    &contract_runtime,
+                    chainspec.protocol_version(),
+                    block_hash.into(),
+                )?;
+                println!("{}", replay);
+                if !replay.divergences.is_empty() {
+                    bail!(
+                        "replaying block found {} divergence(s)",
+                        replay.divergences.len()
+                    );
+                }
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportEmergencyRestart {
+                config,
+                activation_point,
+                out,
+            } => {
+                info!(
+                    build_version = %crate::VERSION_STRING.as_str(),
+                    activation_point,
+                    "exporting emergency restart state"
+                );
+                let storage = Self::open_storage(&config)?;
+                let state = emergency_restart::read_emergency_restart_state(
+                    &storage,
+                    EraId::new(activation_point),
+                )?;
+                let global_state_update = toml::Value::try_from(
+                    GlobalStateUpdateConfig::from_validators(&state.validators),
+                )?;
+                fs::create_dir(&out)
+                    .with_context(|| format!("could not create {}", out.display()))?;
+                let path = out.join(GLOBAL_STATE_UPDATE_FILENAME);
+                fs::write(&path, toml::to_string_pretty(&global_state_update)?)
+                    .with_context(|| format!("could not write {}", path.display()))?;
+                println!("{}", state);
+                info!(path = %path.display(), "exported emergency restart state");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::Storage { config, command } => {
+                let validator_config = Self::init(&config, vec![])?;
+                let (chainspec, _) =
+                    <(Chainspec, ChainspecRawBytes)>::from_path(validator_config.dir())?;
+                let storage_config = WithDir::new(
+                    validator_config.dir(),
+                    validator_config.value().storage.clone(),
+                );
+                let inspector =
+                    StorageInspector::open(&storage_config, &chainspec.network_config.name)?;
+                storage::run(command, &inspector)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ReplayConsensus { input } => {
+                let data = fs::read(&input)
+                    .with_context(|| format!("could not read {}", input.display()))?;
+                let dump: EraDump = bincode::deserialize(&data)
+                    .with_context(|| format!("could not parse era dump {}", input.display()))?;
+                let replay = EraReplay::replay(&dump);
+                println!("{}", replay);
+                if !replay.is_consistent() {
+                    bail!(
+                        "replaying era {} did not reproduce the dumped state",
+                        replay.id
+                    );
+                }
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
+    /// Opens the storage of the node configured in the given config file, for use while the node
+    /// is not running.
+    fn open_storage(config: &Path) -> anyhow::Result<Storage> {
+        let validator_config = Self::init(config, vec![])?;
+        let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(validator_config.dir())?;
+        Self::new_storage(&validator_config, &chainspec)
+    }
+
+    /// Opens the storage of the node with the given config, for use while the node is not
+    /// running.
+    fn new_storage(
+        validator_config: &WithDir<main_reactor::Config>,
+        chainspec: &Chainspec,
+    ) -> anyhow::Result<Storage> {
+        let storage_config = WithDir::new(
+            validator_config.dir(),
+            validator_config.value().storage.clone(),
+        );
+
+        // No emergency restart is applied, so that opening storage never deletes any blocks.
+        let storage = Storage::new(
+            &storage_config,
+            None,
+            chainspec.protocol_version(),
+            chainspec.protocol_config.activation_point.era_id(),
+            &chainspec.network_config.name,
+            chainspec.deploy_config.max_ttl.into(),
+            chainspec.core_config.recent_era_count(),
+            None,
+            false,
+        )?;
+        Ok(storage)
+    }
+
+    /// Opens the global state of the node with the given config, which is kept alongside its
+    /// storage, for use while the node is not running.
+    fn new_contract_runtime(
+        validator_config: &WithDir<main_reactor::Config>,
+        chainspec: &Chainspec,
+        storage: &Storage,
+    ) -> anyhow::Result<ContractRuntime> {
+        let max_delegators_per_validator =
+            if chainspec.core_config.max_delegators_per_validator == 0 {
+                None
+            } else {
+                Some(chainspec.core_config.max_delegators_per_validator)
+            };
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_version(),
+            storage.root_path(),
+            &validator_config.value().contract_runtime,
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.core_config.max_runtime_call_stack_height,
+            chainspec.core_config.minimum_delegation_amount,
+            chainspec.protocol_config.activation_point,
+            chainspec.core_config.prune_batch_size,
+            chainspec.core_config.strict_argument_checking,
+            chainspec.core_config.vesting_schedule_period.millis(),
+            max_delegators_per_validator,
+            &Registry::new(),
+            chainspec.core_config.administrators.clone(),
+            chainspec.core_config.allow_auction_bids,
+            chainspec.core_config.allow_unrestricted_transfers,
+            chainspec.core_config.refund_handling,
+            chainspec.core_config.fee_handling,
+        )?;
+        Ok(contract_runtime)
+    }
+
     /// Parses the config file for the current version of casper-node, and initializes logging.
     fn init(
         config: &Path,
         config_ext: Vec<ConfigExt>,
     ) -> anyhow::Result<WithDir<main_reactor::Config>> {
-        // Determine the parent directory of the configuration file, if any.
-        // Otherwise, we default to `/`.
-        let root = config
-            .parent()
-            .map(|path| path.to_owned())
-            .unwrap_or_else(|| "/".into());
-
-        // The app supports running without a config file, using default values.
-        let encoded_config = fs::read_to_string(config)
-            .context("could not read configuration file")
-            .with_context(|| config.display().to_string())?;
-
-        // Get the TOML table version of the config indicated from CLI args, or from a new
-        // defaulted config instance if one is not provided.
-        let mut config_table: Value = toml::from_str(&encoded_config)?;
-
-        // If any command line overrides to the config values are passed, apply them.
-        for item in config_ext {
-            item.update_toml_table(&mut config_table)?;
-        }
+        let main_config = read_config(config, &config_ext)?;
+        logging::init_with_telemetry(&main_config.value().logging, &main_config.value().telemetry)?;
 
-        // Create main config, including any overridden values.
-        let main_config: main_reactor::Config = config_table.try_into()?;
-        logging::init_with_config(&main_config.logging)?;
+        Ok(main_config)
+    }
+}
 
-        Ok(WithDir::new(root, main_config))
+/// Parses the config file for the current version of casper-node, applying the given command line
+/// overrides.
+pub(crate) fn read_config(
+    config: &Path,
+    config_ext: &[ConfigExt],
+) -> anyhow::Result<WithDir<main_reactor::Config>> {
+    // Determine the parent directory of the configuration file, if any.
+    // Otherwise, we default to `/`.
+    let root = config
+        .parent()
+        .map(|path| path.to_owned())
+        .unwrap_or_else(|| "/".into());
+
+    // The app supports running without a config file, using default values.
+    let encoded_config = fs::read_to_string(config)
+        .context("could not read configuration file")
+        .with_context(|| config.display().to_string())?;
+
+    // Get the TOML table version of the config indicated from CLI args, or from a new
+    // defaulted config instance if one is not provided.
+    let mut config_table: Value = toml::from_str(&encoded_config)?;
+
+    // If any command line overrides to the config values are passed, apply them.
+    for item in config_ext {
+        item.update_toml_table(&mut config_table)?;
     }
+
+    // Create main config, including any overridden values.
+    let main_config: main_reactor::Config = config_table.try_into()?;
+
+    Ok(WithDir::new(root, main_config))
 }
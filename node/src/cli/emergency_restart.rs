@@ -0,0 +1,88 @@
+//! Exporting the state an emergency restart is based on.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use anyhow::{anyhow, bail};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, ProtocolVersion, PublicKey, U512};
+
+use crate::{components::storage::Storage, types::BlockHash};
+
+/// The state from which a network is restarted at the start of an era.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EmergencyRestartState {
+    /// The era at the start of which the network is restarted.
+    pub(crate) activation_point: EraId,
+    /// The hash of the switch block ending the era before the activation point.
+    pub(crate) switch_block_hash: BlockHash,
+    /// The height of the switch block.
+    pub(crate) switch_block_height: u64,
+    /// The protocol version of the switch block.
+    pub(crate) protocol_version: ProtocolVersion,
+    /// The global state root hash after the switch block, on top of which the upgrade is applied.
+    pub(crate) state_root_hash: Digest,
+    /// The validators of the era starting at the activation point, with their weights.
+    pub(crate) validators: BTreeMap<PublicKey, U512>,
+}
+
+impl Display for EmergencyRestartState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "emergency restart at the start of era {}",
+            self.activation_point
+        )?;
+        writeln!(
+            f,
+            "  switch block: {} at height {}, protocol version {}",
+            self.switch_block_hash, self.switch_block_height, self.protocol_version
+        )?;
+        writeln!(f, "  global state root hash: {}", self.state_root_hash)?;
+        writeln!(f, "  validators: {}", self.validators.len())?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "to be set in the [protocol] section of the new chainspec:"
+        )?;
+        writeln!(f, "  hard_reset = true")?;
+        write!(f, "  activation_point = {}", self.activation_point.value())
+    }
+}
+
+/// Reads the state needed to restart the network at the start of the era `activation_point` from
+/// the switch block of the era before it.
+pub(crate) fn read_emergency_restart_state(
+    storage: &Storage,
+    activation_point: EraId,
+) -> anyhow::Result<EmergencyRestartState> {
+    let switch_era = match activation_point.predecessor() {
+        Some(era_id) => era_id,
+        None => bail!("cannot restart at the start of the genesis era"),
+    };
+    let switch_block = storage
+        .read_switch_block_by_era_id(switch_era)?
+        .ok_or_else(|| anyhow!("no switch block of era {} in storage", switch_era))?;
+    let header = switch_block.header();
+    let validators = header
+        .next_era_validator_weights()
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "switch block of era {} has no validator weights",
+                switch_era
+            )
+        })?;
+
+    Ok(EmergencyRestartState {
+        activation_point,
+        switch_block_hash: *switch_block.hash(),
+        switch_block_height: header.height(),
+        protocol_version: header.protocol_version(),
+        state_root_hash: *header.state_root_hash(),
+        validators,
+    })
+}
@@ -0,0 +1,180 @@
+//! Replaying the execution of a stored block.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use anyhow::{anyhow, bail};
+use tracing::warn;
+
+use casper_hashing::Digest;
+use casper_types::ProtocolVersion;
+
+use crate::{
+    components::{
+        contract_runtime::{ContractRuntime, ExecutionPreState},
+        storage::Storage,
+    },
+    types::{BlockHash, DeployHash},
+};
+
+/// A difference between the outcome of replaying a block and that of its original execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ReplayDivergence {
+    /// The replay resulted in a different post-state root hash.
+    StateRootHash { expected: Digest, actual: Digest },
+    /// The replay resulted in a block with a different hash, although the post-state root hash is
+    /// the same.
+    BlockHash {
+        expected: BlockHash,
+        actual: BlockHash,
+    },
+    /// The replay resulted in a different execution result of a deploy.
+    ExecutionResult { deploy_hash: DeployHash },
+}
+
+impl Display for ReplayDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayDivergence::StateRootHash { expected, actual } => {
+                write!(f, "post-state root hash {} instead of {}", actual, expected)
+            }
+            ReplayDivergence::BlockHash { expected, actual } => {
+                write!(f, "block hash {} instead of {}", actual, expected)
+            }
+            ReplayDivergence::ExecutionResult { deploy_hash } => {
+                write!(f, "different execution result of deploy {}", deploy_hash)
+            }
+        }
+    }
+}
+
+/// The outcome of replaying a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BlockReplay {
+    /// The hash of the replayed block.
+    pub(crate) block_hash: BlockHash,
+    /// The height of the replayed block.
+    pub(crate) height: u64,
+    /// The number of deploys and transfers executed.
+    pub(crate) deploy_count: usize,
+    /// Whether the execution results were compared, i.e. whether the results of the original
+    /// execution of all of the block's deploys are stored.
+    pub(crate) execution_results_compared: bool,
+    /// The differences found.
+    pub(crate) divergences: Vec<ReplayDivergence>,
+}
+
+impl Display for BlockReplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "replayed block {} at height {} with {} deploy(s), found {} divergence(s)",
+            self.block_hash,
+            self.height,
+            self.deploy_count,
+            self.divergences.len()
+        )?;
+        if !self.execution_results_compared {
+            write!(f, ", execution results not stored so not compared")?;
+        }
+        for divergence in &self.divergences {
+            write!(f, "\n  {}", divergence)?;
+        }
+        Ok(())
+    }
+}
+
+/// Executes the given stored block again on top of its parent's post-state and compares the
+/// outcome with that of its original execution.
+///
+/// The block must be of the given protocol version, i.e. that of `contract_runtime`.  It must not
+/// be the first block after genesis or an upgrade, since the pre-state of those isn't its parent's
+/// post-state.
+pub(super) fn replay_block(
+    storage: &mut Storage,
+    contract_runtime: &ContractRuntime,
+    protocol_version: ProtocolVersion,
+    block_hash: BlockHash,
+) -> anyhow::Result<BlockReplay> {
+    let block = storage
+        .read_block(&block_hash)?
+        .ok_or_else(|| anyhow!("block {} is not stored", block_hash))?;
+    if block.header().protocol_version() != protocol_version {
+        bail!(
+            "block {} is of protocol version {}, not {}",
+            block_hash,
+            block.header().protocol_version(),
+            protocol_version
+        );
+    }
+    let height = block.header().height();
+    if height == 0 {
+        bail!("block {} is the first block after genesis", block_hash);
+    }
+    let parent_hash = block.header().parent_hash();
+    let parent_header = storage
+        .read_block_header(parent_hash)?
+        .ok_or_else(|| anyhow!("parent block {} is not stored", parent_hash))?;
+    if parent_header.protocol_version() != block.header().protocol_version() {
+        bail!("block {} is the first block after an upgrade", block_hash);
+    }
+
+    let (finalized_block, deploys) = storage
+        .make_executable_block(&block_hash)?
+        .ok_or_else(|| anyhow!("deploys of block {} are not stored", block_hash))?;
+    let deploy_count = deploys.len();
+    let key_block_height_for_activation_point = storage
+        .read_key_block_height_for_activation_point()?
+        .unwrap_or_else(|| {
+            warn!("key block height for current activation point unknown");
+            0
+        });
+
+    let replayed = contract_runtime.replay_finalized_block(
+        ExecutionPreState::from_block_header(&parent_header),
+        finalized_block,
+        deploys,
+        key_block_height_for_activation_point,
+    )?;
+
+    let mut divergences = vec![];
+    let expected_state_root_hash = *block.header().state_root_hash();
+    let actual_state_root_hash = *replayed.block.header().state_root_hash();
+    if expected_state_root_hash != actual_state_root_hash {
+        divergences.push(ReplayDivergence::StateRootHash {
+            expected: expected_state_root_hash,
+            actual: actual_state_root_hash,
+        });
+    } else if *replayed.block.hash() != block_hash {
+        divergences.push(ReplayDivergence::BlockHash {
+            expected: block_hash,
+            actual: *replayed.block.hash(),
+        });
+    }
+
+    let maybe_stored_results = storage.read_execution_results(&block_hash)?;
+    let execution_results_compared = maybe_stored_results.is_some();
+    if let Some(stored_results) = maybe_stored_results {
+        let stored_results: HashMap<_, _> = stored_results
+            .into_iter()
+            .map(|(deploy_hash, _, execution_result)| (deploy_hash, execution_result))
+            .collect();
+        for (deploy_hash, _, execution_result) in &replayed.execution_results {
+            if stored_results.get(deploy_hash) != Some(execution_result) {
+                divergences.push(ReplayDivergence::ExecutionResult {
+                    deploy_hash: *deploy_hash,
+                });
+            }
+        }
+    }
+
+    Ok(BlockReplay {
+        block_hash,
+        height,
+        deploy_count,
+        execution_results_compared,
+        divergences,
+    })
+}
@@ -0,0 +1,103 @@
+//! Inspecting the storage of a stopped node.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use casper_hashing::Digest;
+
+use crate::{
+    components::storage::StorageInspector,
+    types::{BlockHash, DeployHash},
+};
+
+/// A command inspecting the storage of a stopped node.
+#[derive(Debug, StructOpt)]
+pub enum StorageCommand {
+    /// Print a stored block as JSON, given its hex-encoded hash or its height.
+    ///
+    /// The body is null if it is not stored, e.g. if the block has not been synchronized yet.
+    GetBlock {
+        /// Hex-encoded hash or height of the block.
+        block: BlockId,
+    },
+    /// Print a stored deploy as JSON.
+    GetDeploy {
+        /// Hex-encoded hash of the deploy.
+        #[structopt(parse(try_from_str = Digest::from_hex))]
+        deploy_hash: Digest,
+    },
+    /// Print the stored block with the greatest height as JSON, whether complete or not.
+    GetHighestBlock,
+    /// Print the number and size of the entries in each database.
+    Stats,
+}
+
+/// A block given by its hash or its height.
+#[derive(Debug)]
+pub enum BlockId {
+    Hash(BlockHash),
+    Height(u64),
+}
+
+impl FromStr for BlockId {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Ok(height) = input.parse() {
+            return Ok(BlockId::Height(height));
+        }
+        let hash = Digest::from_hex(input)
+            .map_err(|error| anyhow!("expected a block hash or height: {}", error))?;
+        Ok(BlockId::Hash(hash.into()))
+    }
+}
+
+impl Display for BlockId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockId::Hash(block_hash) => write!(f, "{}", block_hash),
+            BlockId::Height(height) => write!(f, "at height {}", height),
+        }
+    }
+}
+
+/// Runs `command` against the storage opened by `inspector`, printing the result.
+pub(super) fn run(command: StorageCommand, inspector: &StorageInspector) -> anyhow::Result<()> {
+    match command {
+        StorageCommand::GetBlock { block } => {
+            let maybe_block = match &block {
+                BlockId::Hash(block_hash) => inspector.get_block(block_hash)?,
+                BlockId::Height(height) => inspector.get_block_by_height(*height)?,
+            };
+            match maybe_block {
+                Some(block) => print_json(&block),
+                None => bail!("no block {} in storage", block),
+            }
+        }
+        StorageCommand::GetDeploy { deploy_hash } => {
+            match inspector.get_deploy(&DeployHash::new(deploy_hash))? {
+                Some(deploy) => print_json(&deploy),
+                None => bail!("no deploy {} in storage", deploy_hash),
+            }
+        }
+        StorageCommand::GetHighestBlock => match inspector.get_highest_block()? {
+            Some(block) => print_json(&block),
+            None => bail!("no blocks in storage"),
+        },
+        StorageCommand::Stats => {
+            print!("{}", inspector.stats()?);
+            Ok(())
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -60,12 +60,16 @@ pub(crate) mod gossiper;
 pub mod in_memory_network;
 pub(crate) mod metrics;
 pub(crate) mod network;
+pub(crate) mod proof_relayer;
+pub(crate) mod randomness_beacon;
 pub(crate) mod rest_server;
 pub mod rpc_server;
 pub(crate) mod shutdown_trigger;
+pub(crate) mod speculative_executor;
 pub mod storage;
 pub(crate) mod sync_leaper;
 pub(crate) mod upgrade_watcher;
+pub(crate) mod zk_prover;
 
 use datasize::DataSize;
 use serde::Deserialize;
@@ -1,17 +1,21 @@
 mod block_acceptor;
+mod block_accumulator_dump;
+mod blocks_in_range;
 mod config;
 mod error;
 mod event;
 mod leap_instruction;
 mod local_tip_identifier;
 mod metrics;
+mod peer_quality;
+mod seen_finality_signatures;
 mod sync_identifier;
 mod sync_instruction;
 #[cfg(test)]
 mod tests;
 
 use std::{
-    collections::{btree_map, BTreeMap, VecDeque},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashSet, VecDeque},
     convert::TryInto,
     sync::Arc,
 };
@@ -20,6 +24,7 @@ use datasize::DataSize;
 use futures::FutureExt;
 use itertools::Itertools;
 use prometheus::Registry;
+use tokio::{sync::Semaphore, task};
 use tracing::{debug, error, info, warn};
 
 use casper_types::{EraId, TimeDiff, Timestamp};
@@ -31,7 +36,10 @@ use crate::{
             leap_instruction::LeapInstruction,
             local_tip_identifier::LocalTipIdentifier,
             metrics::Metrics,
+            peer_quality::PeerQuality,
+            seen_finality_signatures::SeenFinalitySignatures,
         },
+        fetcher::EmptyValidationMetadata,
         network::blocklist::BlocklistJustification,
         Component, ValidatorBoundComponent,
     },
@@ -40,22 +48,26 @@ use crate::{
             BlockAccumulatorAnnouncement, FatalAnnouncement, MetaBlockAnnouncement,
             PeerBehaviorAnnouncement,
         },
-        requests::{BlockAccumulatorRequest, MarkBlockCompletedRequest, StorageRequest},
+        requests::{
+            BlockAccumulatorRequest, FetcherRequest, MarkBlockCompletedRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     fatal,
     types::{
-        ActivationPoint, BlockHash, BlockSignatures, FinalitySignature, MetaBlock, MetaBlockState,
-        NodeId, ValidatorMatrix,
+        ActivationPoint, BlockHash, BlockSignatures, FinalitySignature, FinalitySignatureId,
+        MetaBlock, MetaBlockState, NodeId, ValidatorMatrix,
     },
     NodeRng,
 };
 
-pub(crate) use config::Config;
+pub(crate) use block_accumulator_dump::{BlockAcceptorDump, BlockAccumulatorDump};
+pub(crate) use blocks_in_range::{BlockInRange, BlocksInRange};
+pub(crate) use config::{Config, FinalityThreshold};
 pub(crate) use error::Error;
 pub(crate) use event::Event;
 pub(crate) use sync_identifier::SyncIdentifier;
-pub(crate) use sync_instruction::SyncInstruction;
+pub(crate) use sync_instruction::{HighestUsableBlock, SyncInstruction};
 
 const COMPONENT_NAME: &str = "block_accumulator";
 
@@ -99,6 +111,27 @@ pub(crate) struct BlockAccumulator {
     dead_air_interval: TimeDiff,
     /// Configured setting for how often to purge dead state.
     purge_interval: TimeDiff,
+    /// Configured setting for the maximum number of block acceptors held at once.
+    max_block_acceptors: u32,
+    /// Configured setting for how long finality signatures for the same block are batched
+    /// before being written to storage.
+    finality_signature_batch_window: TimeDiff,
+    /// Finality signatures received after their block was stored, waiting to be written to
+    /// storage in a single batch per block.
+    finality_signature_batches: BTreeMap<BlockHash, BlockSignatures>,
+    /// Configured setting for the signature weight at which a block becomes syncable.
+    sync_finality_threshold: FinalityThreshold,
+    /// Configured setting for how long a block may be held without knowing its parent before it
+    /// is announced as orphaned.
+    orphan_timeout: TimeDiff,
+    /// Configured setting for how long a block may remain with weak, but not strict, finality
+    /// before we fetch its missing finality signatures from peers.
+    missing_signatures_fetch_interval: TimeDiff,
+    /// Blocks for which fetching the missing finality signatures is scheduled.
+    missing_signatures_fetches: BTreeSet<BlockHash>,
+    /// Configured setting for whether to drop invalidated signatures from sufficiently finalized
+    /// blocks when the validator matrix is updated.
+    purge_signatures_on_validator_change: bool,
     /// Configured setting for how many eras are considered to be recent.
     recent_era_interval: u64,
     /// Tracks activity and assists with perceived tip determination.
@@ -110,6 +143,16 @@ pub(crate) struct BlockAccumulator {
     min_block_time: TimeDiff,
     /// The number of validator slots.
     validator_slots: u32,
+    /// Tracks how reliably each peer sends us valid blocks and finality signatures.
+    peer_quality: PeerQuality,
+    /// IDs of finality signatures already verified and registered, used to drop duplicates
+    /// gossiped to us by other peers.
+    seen_finality_signatures: SeenFinalitySignatures,
+    /// IDs of the received finality signatures currently waiting for or undergoing verification.
+    finality_signatures_being_verified: HashSet<FinalitySignatureId>,
+    /// Permits bounding the number of finality signatures verified in parallel.
+    #[data_size(skip)]
+    signature_verification_permits: Arc<Semaphore>,
     /// Metrics.
     #[data_size(skip)]
     metrics: Metrics,
@@ -132,12 +175,26 @@ impl BlockAccumulator {
             block_children: Default::default(),
             last_progress: Timestamp::now(),
             purge_interval: config.purge_interval,
+            max_block_acceptors: config.max_block_acceptors,
+            finality_signature_batch_window: config.finality_signature_batch_window,
+            finality_signature_batches: Default::default(),
+            sync_finality_threshold: config.sync_finality_threshold,
+            orphan_timeout: config.orphan_timeout,
+            missing_signatures_fetch_interval: config.missing_signatures_fetch_interval,
+            missing_signatures_fetches: Default::default(),
+            purge_signatures_on_validator_change: config.purge_signatures_on_validator_change,
             local_tip: None,
             activation_point: None,
             recent_era_interval,
             peer_block_timestamps: Default::default(),
             min_block_time,
             validator_slots,
+            peer_quality: PeerQuality::default(),
+            seen_finality_signatures: SeenFinalitySignatures::default(),
+            finality_signatures_being_verified: Default::default(),
+            signature_verification_permits: Arc::new(Semaphore::new(
+                config.max_concurrent_signature_verifications.max(1) as usize,
+            )),
             metrics: Metrics::new(registry)?,
         })
     }
@@ -150,13 +207,21 @@ impl BlockAccumulator {
             self.register_local_tip(block_height, era_id);
         }
         if leap_instruction.should_leap() {
-            return SyncInstruction::Leap { block_hash };
+            return SyncInstruction::Leap {
+                block_hash,
+                highest_usable_block: self.highest_usable_block(),
+            };
         }
         match sync_identifier.block_hash_to_sync(self.next_syncable_block_hash(block_hash)) {
             Some(block_hash_to_sync) => {
                 self.reset_last_progress();
+                let has_strict_finality = self
+                    .block_acceptors
+                    .get(&block_hash_to_sync)
+                    .map_or(false, BlockAcceptor::has_sufficient_finality);
                 SyncInstruction::BlockSync {
                     block_hash: block_hash_to_sync,
+                    has_strict_finality,
                 }
             }
             None => {
@@ -201,15 +266,12 @@ impl BlockAccumulator {
         maybe_sender: Option<NodeId>,
     ) {
         // If the acceptor already exists, just register the peer, if applicable.
-        let entry = match self.block_acceptors.entry(block_hash) {
-            btree_map::Entry::Occupied(entry) => {
-                if let Some(sender) = maybe_sender {
-                    entry.into_mut().register_peer(sender);
-                }
-                return;
+        if let Some(acceptor) = self.block_acceptors.get_mut(&block_hash) {
+            if let Some(sender) = maybe_sender {
+                acceptor.register_peer(sender);
             }
-            btree_map::Entry::Vacant(entry) => entry,
-        };
+            return;
+        }
 
         // The acceptor doesn't exist. Don't create it if the item's era is not
         // provided or the item's era is older than the local tip era by more
@@ -255,10 +317,43 @@ impl BlockAccumulator {
             block_timestamps.push_back((block_hash, Timestamp::now()));
         }
 
-        entry.insert(BlockAcceptor::new(block_hash, maybe_sender));
+        self.evict_block_acceptors_over_limit();
+        self.block_acceptors
+            .insert(block_hash, BlockAcceptor::new(block_hash, maybe_sender));
         self.metrics.block_acceptors.inc();
     }
 
+    /// Evicts block acceptors until there is room for a new one without exceeding
+    /// `max_block_acceptors`.
+    ///
+    /// Acceptors with the lowest block height are evicted first (those for which we don't know the
+    /// block yet count as lowest), and among those of equal height, the least recently updated.
+    fn evict_block_acceptors_over_limit(&mut self) {
+        while self.block_acceptors.len() >= self.max_block_acceptors as usize {
+            let block_hash = match self
+                .block_acceptors
+                .iter()
+                .min_by_key(|(_, acceptor)| (acceptor.block_height(), acceptor.last_progress()))
+                .map(|(block_hash, _)| *block_hash)
+            {
+                Some(block_hash) => block_hash,
+                None => break,
+            };
+            debug!(%block_hash, "evicting block acceptor");
+            self.block_acceptors.remove(&block_hash);
+            self.seen_finality_signatures.forget_blocks(&[block_hash]);
+            self.block_children
+                .retain(|_parent, child| *child != block_hash);
+            self.metrics.evicted_block_acceptors.inc();
+        }
+        self.metrics
+            .block_acceptors
+            .set(self.block_acceptors.len().try_into().unwrap_or(i64::MIN));
+        self.metrics
+            .known_child_blocks
+            .set(self.block_children.len().try_into().unwrap_or(i64::MIN));
+    }
+
     fn register_block<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -272,7 +367,7 @@ impl BlockAccumulator {
             + From<FatalAnnouncement>
             + Send,
     {
-        let block_hash = meta_block.block.hash();
+        let block_hash = *meta_block.block.hash();
         debug!(%block_hash, "registering block");
         let era_id = meta_block.block.header().era_id();
         let block_height = meta_block.block.header().height();
@@ -284,28 +379,46 @@ impl BlockAccumulator {
             debug!(%block_hash, "ignoring outdated block");
             return Effects::new();
         }
-        self.upsert_acceptor(*block_hash, Some(era_id), sender);
+        self.upsert_acceptor(block_hash, Some(era_id), sender);
 
-        let acceptor = match self.block_acceptors.get_mut(block_hash) {
+        let acceptor = match self.block_acceptors.get_mut(&block_hash) {
             None => return Effects::new(),
             Some(acceptor) => acceptor,
         };
 
+        let had_block = acceptor.block_height().is_some();
         match acceptor.register_block(meta_block, sender) {
-            Ok(_) => match self.validator_matrix.validator_weights(era_id) {
-                Some(evw) => {
-                    let (should_store, faulty_senders) = acceptor.should_store_block(&evw);
-                    self.store_block_and_finality_signatures(
-                        effect_builder,
-                        should_store,
-                        faulty_senders,
-                    )
+            Ok(_) => {
+                if let Some(peer) = sender {
+                    self.peer_quality.record_valid(peer);
                 }
-                None => Effects::new(),
-            },
+                let mut effects = match self.validator_matrix.validator_weights(era_id) {
+                    Some(evw) => {
+                        let (should_store, faulty_senders) = acceptor.should_store_block(&evw);
+                        self.store_block_and_finality_signatures(
+                            effect_builder,
+                            should_store,
+                            faulty_senders,
+                        )
+                    }
+                    None => Effects::new(),
+                };
+                // Gossiped blocks may arrive ahead of their parents; if the parent doesn't turn
+                // up in time, we announce the block as orphaned.
+                if !had_block && sender.is_some() && self.missing_parent(block_hash).is_some() {
+                    effects.extend(
+                        effect_builder
+                            .set_timeout(self.orphan_timeout.into())
+                            .event(move |_| Event::CheckOrphan { block_hash }),
+                    );
+                }
+                effects.extend(self.schedule_missing_signatures_fetch(effect_builder, block_hash));
+                effects
+            }
             Err(error) => match error {
                 Error::InvalidGossip(ref gossip_error) => {
                     warn!(%gossip_error, "received invalid block");
+                    self.peer_quality.record_invalid(gossip_error.peer());
                     effect_builder
                         .announce_block_peer_with_justification(
                             gossip_error.peer(),
@@ -323,6 +436,7 @@ impl BlockAccumulator {
                         "era mismatch from {} for {}; expected: {} and actual: {}",
                         peer, block_hash, expected, actual
                     );
+                    self.peer_quality.record_invalid(peer);
                     effect_builder
                         .announce_block_peer_with_justification(
                             peer,
@@ -351,18 +465,79 @@ impl BlockAccumulator {
                     error!(%error, "failed to merge meta blocks, this is a bug");
                     Effects::new()
                 }
-                Error::TooManySignatures { peer, limit } => effect_builder
-                    .announce_block_peer_with_justification(
-                        peer,
-                        BlocklistJustification::SentTooManyFinalitySignatures {
-                            max_allowed: limit,
-                        },
-                    )
-                    .ignore(),
+                Error::TooManySignatures { peer, limit } => {
+                    self.peer_quality.record_invalid(peer);
+                    effect_builder
+                        .announce_block_peer_with_justification(
+                            peer,
+                            BlocklistJustification::SentTooManyFinalitySignatures {
+                                max_allowed: limit,
+                            },
+                        )
+                        .ignore()
+                }
             },
         }
     }
 
+    /// Verifies a finality signature received from a peer on the blocking thread pool, so that
+    /// verifying many signatures doesn't stall event processing, then feeds it back in as
+    /// `Event::SignatureVerified`.  At most `max_concurrent_signature_verifications` signatures
+    /// are verified in parallel.
+    ///
+    /// The verification result is cached within the signature, so registering it afterwards
+    /// doesn't verify it again.  Signatures we have already registered or are already verifying,
+    /// or have no acceptor for, are dropped without being verified.
+    fn verify_finality_signature(
+        &mut self,
+        finality_signature: FinalitySignature,
+        sender: NodeId,
+    ) -> Effects<Event> {
+        let block_hash = finality_signature.block_hash;
+        let era_id = finality_signature.era_id;
+        self.upsert_acceptor(block_hash, Some(era_id), Some(sender));
+        if !self.block_acceptors.contains_key(&block_hash) {
+            debug!(%finality_signature, "no acceptor to receive finality_signature");
+            return Effects::new();
+        }
+        let finality_signature_id = FinalitySignatureId {
+            block_hash,
+            era_id,
+            public_key: finality_signature.public_key.clone(),
+        };
+        if self
+            .seen_finality_signatures
+            .contains(&finality_signature_id)
+        {
+            debug!(%finality_signature, "ignoring already registered finality signature");
+            return Effects::new();
+        }
+        if !self
+            .finality_signatures_being_verified
+            .insert(finality_signature_id)
+        {
+            debug!(%finality_signature, "ignoring finality signature already being verified");
+            return Effects::new();
+        }
+
+        let permits = Arc::clone(&self.signature_verification_permits);
+        async move {
+            // This will never panic since the semaphore is never closed.
+            let _permit = permits.acquire_owned().await.unwrap();
+            task::spawn_blocking(move || {
+                // The result is cached and acted upon when registering the signature.
+                let _ = finality_signature.is_verified();
+                finality_signature
+            })
+            .await
+            .expect("finality signature verification task panicked")
+        }
+        .event(move |finality_signature| Event::SignatureVerified {
+            finality_signature: Box::new(finality_signature),
+            sender,
+        })
+    }
+
     fn register_finality_signature<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -390,13 +565,33 @@ impl BlockAccumulator {
             }
         };
 
+        let finality_signature_id = FinalitySignatureId {
+            block_hash,
+            era_id,
+            public_key: finality_signature.public_key.clone(),
+        };
         if sender.is_none() {
             acceptor.set_our_signature(finality_signature.clone());
+        } else if self
+            .seen_finality_signatures
+            .contains(&finality_signature_id)
+        {
+            // The sender has already been registered with the acceptor above; the signature itself
+            // was verified when we first received it.
+            debug!(%finality_signature, "ignoring already registered finality signature");
+            return Effects::new();
         }
 
         debug!(%finality_signature, "registering finality signature");
-        match acceptor.register_finality_signature(finality_signature, sender, self.validator_slots)
-        {
+        let result =
+            acceptor.register_finality_signature(finality_signature, sender, self.validator_slots);
+        if result.is_ok() {
+            self.seen_finality_signatures.insert(finality_signature_id);
+        }
+        if let (Ok(_), Some(peer)) = (&result, sender) {
+            self.peer_quality.record_valid(peer);
+        }
+        match result {
             Ok(Some(finality_signature)) => self.store_block_and_finality_signatures(
                 effect_builder,
                 ShouldStore::SingleSignature(finality_signature),
@@ -405,17 +600,21 @@ impl BlockAccumulator {
             Ok(None) => match self.validator_matrix.validator_weights(era_id) {
                 Some(evw) => {
                     let (should_store, faulty_senders) = acceptor.should_store_block(&evw);
-                    self.store_block_and_finality_signatures(
+                    let mut effects = self.store_block_and_finality_signatures(
                         effect_builder,
                         should_store,
                         faulty_senders,
-                    )
+                    );
+                    effects
+                        .extend(self.schedule_missing_signatures_fetch(effect_builder, block_hash));
+                    effects
                 }
                 None => Effects::new(),
             },
             Err(error) => match error {
                 Error::InvalidGossip(ref gossip_error) => {
                     warn!(%gossip_error, "received invalid finality_signature");
+                    self.peer_quality.record_invalid(gossip_error.peer());
                     effect_builder
                         .announce_block_peer_with_justification(
                             gossip_error.peer(),
@@ -436,6 +635,7 @@ impl BlockAccumulator {
                         "era mismatch from {} for {}; expected: {} and actual: {}",
                         peer, block_hash, expected, actual
                     );
+                    self.peer_quality.record_invalid(peer);
                     effect_builder
                         .announce_block_peer_with_justification(
                             peer,
@@ -464,14 +664,17 @@ impl BlockAccumulator {
                     error!(%error, "failed to merge meta blocks, this is a bug");
                     Effects::new()
                 }
-                Error::TooManySignatures { peer, limit } => effect_builder
-                    .announce_block_peer_with_justification(
-                        peer,
-                        BlocklistJustification::SentTooManyFinalitySignatures {
-                            max_allowed: limit,
-                        },
-                    )
-                    .ignore(),
+                Error::TooManySignatures { peer, limit } => {
+                    self.peer_quality.record_invalid(peer);
+                    effect_builder
+                        .announce_block_peer_with_justification(
+                            peer,
+                            BlocklistJustification::SentTooManyFinalitySignatures {
+                                max_allowed: limit,
+                            },
+                        )
+                        .ignore()
+                }
             },
         }
     }
@@ -504,12 +707,271 @@ impl BlockAccumulator {
         effects
     }
 
+    /// Writes the finality signatures batched for the given block to storage.
+    fn store_finality_signature_batch<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_hash: BlockHash,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let block_signatures = match self.finality_signature_batches.remove(&block_hash) {
+            Some(block_signatures) => block_signatures,
+            None => {
+                debug!(%block_hash, "no batched finality signatures to store");
+                return Effects::new();
+            }
+        };
+        debug!(
+            %block_hash,
+            count = block_signatures.proofs.len(),
+            "storing batched finality signatures"
+        );
+        let cloned_signatures = block_signatures.clone();
+        effect_builder
+            .put_signatures_to_storage(cloned_signatures)
+            .event(move |_| Event::Stored {
+                maybe_meta_block: None,
+                maybe_block_signatures: Some(block_signatures),
+            })
+    }
+
+    /// Returns the parent hash of the given block if we hold the block, but neither hold its
+    /// parent nor have a local tip at or above the parent's height.
+    fn missing_parent(&self, block_hash: BlockHash) -> Option<BlockHash> {
+        let acceptor = self.block_acceptors.get(&block_hash)?;
+        let parent_hash = acceptor.parent_hash()?;
+        let block_height = acceptor.block_height()?;
+        let have_parent = self
+            .block_acceptors
+            .get(&parent_hash)
+            .map_or(false, |parent| parent.block_height().is_some());
+        let parent_at_or_below_local_tip = self.local_tip.map_or(false, |local_tip| {
+            block_height <= local_tip.height.saturating_add(1)
+        });
+        if have_parent || parent_at_or_below_local_tip {
+            None
+        } else {
+            Some(parent_hash)
+        }
+    }
+
+    /// Announces the given block as orphaned if its parent is still missing.
+    fn check_orphan<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        block_hash: BlockHash,
+    ) -> Effects<Event>
+    where
+        REv: From<BlockAccumulatorAnnouncement> + Send,
+    {
+        match self.missing_parent(block_hash) {
+            Some(parent_hash) => {
+                info!(%block_hash, %parent_hash, "block accumulator: orphaned block detected");
+                effect_builder
+                    .announce_orphan_detected(block_hash, parent_hash)
+                    .ignore()
+            }
+            None => Effects::new(),
+        }
+    }
+
+    /// Returns a snapshot of the accumulator's state, for debugging.
+    fn dump(&self) -> BlockAccumulatorDump {
+        let acceptors = self
+            .block_acceptors
+            .values()
+            .map(|acceptor| {
+                let era_id = acceptor.era_id();
+                let signed_weight_percent = era_id
+                    .and_then(|era_id| self.validator_matrix.validator_weights(era_id))
+                    .filter(|evw| !evw.get_total_weight().is_zero())
+                    .map(|evw| {
+                        let signed_weight = evw.signed_weight(acceptor.signatures().keys());
+                        (signed_weight * 100 / evw.get_total_weight()).as_u64()
+                    });
+                BlockAcceptorDump {
+                    block_hash: acceptor.block_hash(),
+                    era_id,
+                    block_height: acceptor.block_height(),
+                    signature_count: acceptor.signatures().len(),
+                    signed_weight_percent,
+                    has_weak_finality: acceptor.has_weak_finality(),
+                    has_sufficient_finality: acceptor.has_sufficient_finality(),
+                    peer_count: acceptor.peers().len(),
+                    last_progress: acceptor.last_progress(),
+                }
+            })
+            .sorted_by_key(|acceptor| acceptor.block_height)
+            .collect();
+        BlockAccumulatorDump {
+            local_tip: self
+                .local_tip
+                .map(|local_tip| (local_tip.height, local_tip.era_id)),
+            last_progress: self.last_progress,
+            acceptors,
+            known_children: self
+                .block_children
+                .iter()
+                .map(|(parent, child)| (*parent, *child))
+                .collect(),
+        }
+    }
+
+    /// Drops signatures which are no longer valid under the updated validator matrix from blocks
+    /// with sufficient finality, and announces the blocks left short of strict finality.
+    ///
+    /// Such blocks have already been stored, so they keep their sufficient finality status; the
+    /// announcement only serves to warn about the inconsistency.
+    fn purge_invalidated_signatures<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event>
+    where
+        REv: From<BlockAccumulatorAnnouncement> + Send,
+    {
+        let validator_matrix = &self.validator_matrix; // Closure can't borrow all of self.
+        self.block_acceptors
+            .values_mut()
+            .filter(|acceptor| acceptor.has_sufficient_finality())
+            .filter_map(|acceptor| {
+                let evw = validator_matrix.validator_weights(acceptor.era_id()?)?;
+                acceptor
+                    .purge_invalidated_signatures(&evw)
+                    .then(|| acceptor.block_hash())
+            })
+            .collect_vec()
+            .into_iter()
+            .flat_map(|block_hash| {
+                effect_builder
+                    .announce_sufficient_finality_lost(block_hash)
+                    .ignore()
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the given block has weak, but not yet strict, finality.
+    fn is_short_of_sufficient_finality(&self, block_hash: BlockHash) -> bool {
+        self.block_acceptors
+            .get(&block_hash)
+            .map_or(false, |acceptor| {
+                acceptor.has_weak_finality() && !acceptor.has_sufficient_finality()
+            })
+    }
+
+    /// Schedules fetching the finality signatures missing for the given block, if it is short of
+    /// sufficient finality and no fetch is scheduled for it yet.
+    fn schedule_missing_signatures_fetch<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_hash: BlockHash,
+    ) -> Effects<Event>
+    where
+        REv: Send,
+    {
+        if !self.is_short_of_sufficient_finality(block_hash)
+            || !self.missing_signatures_fetches.insert(block_hash)
+        {
+            return Effects::new();
+        }
+        effect_builder
+            .set_timeout(self.missing_signatures_fetch_interval.into())
+            .event(move |_| Event::FetchMissingSignatures { block_hash })
+    }
+
+    /// Requests the finality signatures of the validators which haven't signed the given block yet
+    /// from the peers which told us about it, rather than waiting for them to be gossiped.
+    ///
+    /// Fetched signatures reach us as regular finality signatures received from the peer.  Another
+    /// fetch is scheduled unless one is already pending.
+    fn fetch_missing_signatures<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_hash: BlockHash,
+    ) -> Effects<Event>
+    where
+        REv: From<FetcherRequest<FinalitySignature>> + Send,
+    {
+        if !self.is_short_of_sufficient_finality(block_hash) {
+            return Effects::new();
+        }
+        let acceptor = match self.block_acceptors.get(&block_hash) {
+            Some(acceptor) => acceptor,
+            None => return Effects::new(),
+        };
+        let era_validator_weights = match acceptor
+            .era_id()
+            .and_then(|era_id| self.validator_matrix.validator_weights(era_id))
+        {
+            Some(era_validator_weights) => era_validator_weights,
+            None => return Effects::new(),
+        };
+        let peers = acceptor.peers().iter().copied().collect_vec();
+        if peers.is_empty() {
+            debug!(%block_hash, "no peers to fetch missing finality signatures from");
+            return Effects::new();
+        }
+
+        let era_id = era_validator_weights.era_id();
+        let mut effects: Effects<Event> = era_validator_weights
+            .missing_validators(acceptor.signatures().keys())
+            .zip(peers.iter().cycle())
+            .flat_map(|(public_key, peer)| {
+                let id = Box::new(FinalitySignatureId {
+                    block_hash,
+                    era_id,
+                    public_key: public_key.clone(),
+                });
+                effect_builder
+                    .fetch::<FinalitySignature>(id, *peer, Box::new(EmptyValidationMetadata))
+                    .ignore()
+            })
+            .collect();
+        debug!(
+            %block_hash,
+            count = effects.len(),
+            "fetching missing finality signatures"
+        );
+        self.metrics
+            .missing_signatures_fetched
+            .inc_by(effects.len() as u64);
+        effects.extend(self.schedule_missing_signatures_fetch(effect_builder, block_hash));
+        effects
+    }
+
     fn get_peers(&self, block_hash: BlockHash) -> Option<Vec<NodeId>> {
         self.block_acceptors
             .get(&block_hash)
             .map(|acceptor| acceptor.peers().iter().cloned().collect())
     }
 
+    fn get_peer_quality(&self, peers: &[NodeId]) -> BTreeMap<NodeId, i64> {
+        self.peer_quality.scores(peers)
+    }
+
+    /// Returns the blocks held by acceptors with heights in `[start_height, end_height]`.
+    fn get_blocks_in_range(&self, start_height: u64, end_height: u64) -> BlocksInRange {
+        let blocks = self
+            .block_acceptors
+            .values()
+            .filter_map(|acceptor| {
+                let block_height = acceptor.block_height()?;
+                if block_height < start_height || block_height > end_height {
+                    return None;
+                }
+                Some(BlockInRange {
+                    block_hash: acceptor.block_hash(),
+                    block_height,
+                    has_weak_finality: acceptor.has_weak_finality(),
+                    has_strict_finality: acceptor.has_sufficient_finality(),
+                })
+            })
+            .sorted_by_key(|block| (block.block_height, block.block_hash))
+            .collect();
+        BlocksInRange(blocks)
+    }
+
     fn is_stale(&mut self) -> bool {
         // we expect to be receiving gossiped blocks from other nodes
         // if we haven't received any messages describing higher blocks
@@ -522,6 +984,24 @@ impl BlockAccumulator {
         self.last_progress = Timestamp::now();
     }
 
+    /// Writes all batched finality signatures to storage ahead of a shutdown.
+    ///
+    /// Blocks held by the acceptors are not stored, as they are not yet known to be final; they
+    /// are acquired again from peers after a restart.
+    pub(crate) fn flush_finality_signature_batches<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let block_hashes: Vec<_> = self.finality_signature_batches.keys().copied().collect();
+        block_hashes
+            .into_iter()
+            .flat_map(|block_hash| self.store_finality_signature_batch(effect_builder, block_hash))
+            .collect()
+    }
+
     fn leap_instruction(&self, sync_identifier: &SyncIdentifier) -> LeapInstruction {
         let local_tip_height = match self.local_tip {
             Some(local_tip) => local_tip.height,
@@ -548,19 +1028,12 @@ impl BlockAccumulator {
             }
         };
 
-        match self
-            .block_acceptors
-            .iter()
-            .filter(|(_, acceptor)| {
-                acceptor.has_sufficient_finality() && acceptor.block_height().is_some()
-            })
-            .max_by(|x, y| x.1.block_height().cmp(&y.1.block_height()))
-            .map(|(_, acceptor)| {
-                (
-                    acceptor.block_height().unwrap_or_default(),
-                    acceptor.is_upgrade_boundary(self.activation_point),
-                )
-            }) {
+        match self.highest_usable_acceptor().map(|acceptor| {
+            (
+                acceptor.block_height().unwrap_or_default(),
+                acceptor.is_upgrade_boundary(self.activation_point),
+            )
+        }) {
             None => LeapInstruction::NoUsableBlockAcceptors,
             Some((acceptor_height, is_upgrade_boundary)) => {
                 // the accumulator has heard about at least one usable block via gossiping
@@ -592,10 +1065,34 @@ impl BlockAccumulator {
         }
     }
 
+    /// Returns the acceptor with sufficient finality for the highest known block, if any.
+    fn highest_usable_acceptor(&self) -> Option<&BlockAcceptor> {
+        self.block_acceptors
+            .values()
+            .filter(|acceptor| {
+                acceptor.has_sufficient_finality() && acceptor.block_height().is_some()
+            })
+            .max_by(|x, y| x.block_height().cmp(&y.block_height()))
+    }
+
+    /// Returns the hash, era and height of the highest known block with sufficient finality.
+    fn highest_usable_block(&self) -> Option<HighestUsableBlock> {
+        let acceptor = self.highest_usable_acceptor()?;
+        Some(HighestUsableBlock {
+            block_hash: acceptor.block_hash(),
+            era_id: acceptor.era_id()?,
+            block_height: acceptor.block_height()?,
+        })
+    }
+
     fn next_syncable_block_hash(&self, parent_block_hash: BlockHash) -> Option<BlockHash> {
         let child_hash = self.block_children.get(&parent_block_hash)?;
         let block_acceptor = self.block_acceptors.get(child_hash)?;
-        if block_acceptor.has_sufficient_finality() {
+        let is_syncable = match self.sync_finality_threshold {
+            FinalityThreshold::Weak => block_acceptor.has_weak_finality(),
+            FinalityThreshold::Strict => block_acceptor.has_sufficient_finality(),
+        };
+        if is_syncable {
             Some(block_acceptor.block_hash())
         } else {
             None
@@ -635,6 +1132,7 @@ impl BlockAccumulator {
         });
         self.block_children
             .retain(|_parent, child| false == purged.contains(child));
+        self.seen_finality_signatures.forget_blocks(&purged);
         self.peer_block_timestamps.retain(|_, block_timestamps| {
             while block_timestamps
                 .front()
@@ -644,6 +1142,7 @@ impl BlockAccumulator {
             }
             !block_timestamps.is_empty()
         });
+        self.peer_quality.decay();
 
         self.metrics
             .block_acceptors
@@ -688,10 +1187,12 @@ impl BlockAccumulator {
                 self.update_block_children(&meta_block);
                 // The block wasn't executed yet, so we just put it to storage. An `ExecutedBlock`
                 // event will then re-trigger this flow and eventually mark it complete.
-                let cloned_signatures = block_signatures.clone();
                 effect_builder
-                    .put_block_to_storage(Arc::clone(&meta_block.block))
-                    .then(move |_| effect_builder.put_signatures_to_storage(cloned_signatures))
+                    .put_block_and_approvals_and_signatures_to_storage(
+                        Arc::clone(&meta_block.block),
+                        None,
+                        block_signatures.clone(),
+                    )
                     .event(move |_| Event::Stored {
                         maybe_meta_block: Some(meta_block),
                         maybe_block_signatures: Some(block_signatures),
@@ -728,16 +1229,25 @@ impl BlockAccumulator {
                     })
             }
             ShouldStore::SingleSignature(signature) => {
-                debug!(%signature, "storing finality signature");
-                let mut block_signatures =
-                    BlockSignatures::new(signature.block_hash, signature.era_id);
-                block_signatures.insert_proof(signature.public_key.clone(), signature.signature);
-                effect_builder
-                    .put_finality_signature_to_storage(signature)
-                    .event(move |_| Event::Stored {
-                        maybe_meta_block: None,
-                        maybe_block_signatures: Some(block_signatures),
-                    })
+                debug!(%signature, "batching finality signature");
+                let block_hash = signature.block_hash;
+                match self.finality_signature_batches.entry(block_hash) {
+                    Entry::Occupied(mut entry) => {
+                        entry
+                            .get_mut()
+                            .insert_proof(signature.public_key, signature.signature);
+                        Effects::new()
+                    }
+                    Entry::Vacant(entry) => {
+                        let mut block_signatures =
+                            BlockSignatures::new(block_hash, signature.era_id);
+                        block_signatures.insert_proof(signature.public_key, signature.signature);
+                        entry.insert(block_signatures);
+                        effect_builder
+                            .set_timeout(self.finality_signature_batch_window.into())
+                            .event(move |_| Event::StoreFinalitySignatureBatch { block_hash })
+                    }
+                }
             }
             ShouldStore::Nothing => {
                 debug!("not storing block or finality signatures");
@@ -745,6 +1255,7 @@ impl BlockAccumulator {
             }
         };
         effects.extend(faulty_senders.into_iter().flat_map(|(node_id, error)| {
+            self.peer_quality.record_invalid(node_id);
             effect_builder
                 .announce_block_peer_with_justification(
                     node_id,
@@ -758,6 +1269,7 @@ impl BlockAccumulator {
 
 pub(crate) trait ReactorEvent:
     From<StorageRequest>
+    + From<FetcherRequest<FinalitySignature>>
     + From<PeerBehaviorAnnouncement>
     + From<BlockAccumulatorAnnouncement>
     + From<MarkBlockCompletedRequest>
@@ -770,6 +1282,7 @@ pub(crate) trait ReactorEvent:
 
 impl<REv> ReactorEvent for REv where
     REv: From<StorageRequest>
+        + From<FetcherRequest<FinalitySignature>>
         + From<PeerBehaviorAnnouncement>
         + From<BlockAccumulatorAnnouncement>
         + From<MarkBlockCompletedRequest>
@@ -794,6 +1307,25 @@ impl<REv: ReactorEvent> Component<REv> for BlockAccumulator {
                 block_hash,
                 responder,
             }) => responder.respond(self.get_peers(block_hash)).ignore(),
+            Event::Request(BlockAccumulatorRequest::GetPeerQuality { peers, responder }) => {
+                responder.respond(self.get_peer_quality(&peers)).ignore()
+            }
+            Event::Request(BlockAccumulatorRequest::GetBlocksInRange {
+                start_height,
+                end_height,
+                responder,
+            }) => responder
+                .respond(self.get_blocks_in_range(start_height, end_height))
+                .ignore(),
+            Event::Request(BlockAccumulatorRequest::GetDump { responder }) => {
+                responder.respond(self.dump()).ignore()
+            }
+            Event::Request(BlockAccumulatorRequest::GetHighestUsableBlockHeight { responder }) => {
+                let block_height = self
+                    .highest_usable_block()
+                    .map(|highest_usable_block| highest_usable_block.block_height);
+                responder.respond(block_height).ignore()
+            }
             Event::RegisterPeer {
                 block_hash,
                 era_id,
@@ -813,7 +1345,17 @@ impl<REv: ReactorEvent> Component<REv> for BlockAccumulator {
             Event::ReceivedFinalitySignature {
                 finality_signature,
                 sender,
+            } => self.verify_finality_signature(*finality_signature, sender),
+            Event::SignatureVerified {
+                finality_signature,
+                sender,
             } => {
+                self.finality_signatures_being_verified
+                    .remove(&FinalitySignatureId {
+                        block_hash: finality_signature.block_hash,
+                        era_id: finality_signature.era_id,
+                        public_key: finality_signature.public_key.clone(),
+                    });
                 self.register_finality_signature(effect_builder, *finality_signature, Some(sender))
             }
             Event::ExecutedBlock { meta_block } => {
@@ -827,6 +1369,17 @@ impl<REv: ReactorEvent> Component<REv> for BlockAccumulator {
                 maybe_meta_block,
                 maybe_block_signatures,
             } => self.register_stored(effect_builder, maybe_meta_block, maybe_block_signatures),
+            Event::StoreFinalitySignatureBatch { block_hash } => {
+                self.store_finality_signature_batch(effect_builder, block_hash)
+            }
+            Event::CheckOrphan { block_hash } => self.check_orphan(effect_builder, block_hash),
+            Event::FetchMissingSignatures { block_hash } => {
+                self.missing_signatures_fetches.remove(&block_hash);
+                self.fetch_missing_signatures(effect_builder, block_hash)
+            }
+            Event::SyncingBlockWithWeakFinality { block_hash } => {
+                self.fetch_missing_signatures(effect_builder, block_hash)
+            }
         }
     }
 
@@ -842,6 +1395,10 @@ impl<REv: ReactorEvent> ValidatorBoundComponent<REv> for BlockAccumulator {
         _: &mut NodeRng,
     ) -> Effects<Self::Event> {
         info!("BlockAccumulator: handling updated validator matrix");
+        let mut effects = Effects::new();
+        if self.purge_signatures_on_validator_change {
+            effects.extend(self.purge_invalidated_signatures(effect_builder));
+        }
         let validator_matrix = &self.validator_matrix; // Closure can't borrow all of self.
         let should_stores = self
             .block_acceptors
@@ -853,15 +1410,17 @@ impl<REv: ReactorEvent> ValidatorBoundComponent<REv> for BlockAccumulator {
                 Some(acceptor.should_store_block(&evw))
             })
             .collect_vec();
-        should_stores
-            .into_iter()
-            .flat_map(|(should_store, faulty_senders)| {
-                self.store_block_and_finality_signatures(
-                    effect_builder,
-                    should_store,
-                    faulty_senders,
-                )
-            })
-            .collect()
+        effects.extend(
+            should_stores
+                .into_iter()
+                .flat_map(|(should_store, faulty_senders)| {
+                    self.store_block_and_finality_signatures(
+                        effect_builder,
+                        should_store,
+                        faulty_senders,
+                    )
+                }),
+        );
+        effects
     }
 }
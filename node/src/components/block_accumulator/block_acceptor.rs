@@ -28,6 +28,7 @@ pub(super) struct BlockAcceptor {
     peers: BTreeSet<NodeId>,
     last_progress: Timestamp,
     our_signature: Option<FinalitySignature>,
+    has_weak_finality: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -58,6 +59,7 @@ impl BlockAcceptor {
             peers: peers.into_iter().collect(),
             last_progress: Timestamp::now(),
             our_signature: None,
+            has_weak_finality: false,
         }
     }
 
@@ -246,6 +248,9 @@ impl BlockAcceptor {
 
         let faulty_senders = self.remove_bogus_validators(era_validator_weights);
         let signature_weight = era_validator_weights.signature_weight(self.signatures.keys());
+        if SignatureWeight::Insufficient != signature_weight {
+            self.has_weak_finality = true;
+        }
         if SignatureWeight::Strict == signature_weight {
             self.touch();
             if let Some(meta_block) = self.meta_block.as_mut() {
@@ -336,6 +341,34 @@ impl BlockAcceptor {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if we have the block and signatures of more than 1/3 of the era's validator
+    /// weight.
+    pub(super) fn has_weak_finality(&self) -> bool {
+        (self.has_weak_finality && self.meta_block.is_some()) || self.has_sufficient_finality()
+    }
+
+    /// Drops signatures from public keys which are not validators in the given era, e.g. after the
+    /// era's validator weights were updated.
+    ///
+    /// Returns `true` if signatures were dropped from a block with sufficient finality, and the
+    /// remaining ones no longer amount to strict finality.
+    pub(super) fn purge_invalidated_signatures(
+        &mut self,
+        era_validator_weights: &EraValidatorWeights,
+    ) -> bool {
+        let invalidated = era_validator_weights.bogus_validators(self.signatures.keys());
+        if invalidated.is_empty() {
+            return false;
+        }
+        for public_key in &invalidated {
+            debug!(block_hash = %self.block_hash, %public_key, "purging invalidated signature");
+            self.signatures.remove(public_key);
+        }
+        self.has_sufficient_finality()
+            && era_validator_weights.signature_weight(self.signatures.keys())
+                != SignatureWeight::Strict
+    }
+
     pub(super) fn era_id(&self) -> Option<EraId> {
         if let Some(meta_block) = &self.meta_block {
             return Some(meta_block.block.header().era_id());
@@ -356,6 +389,12 @@ impl BlockAcceptor {
         self.block_hash
     }
 
+    pub(super) fn parent_hash(&self) -> Option<BlockHash> {
+        self.meta_block
+            .as_ref()
+            .and_then(|meta_block| meta_block.block.parent().copied())
+    }
+
     pub(super) fn is_upgrade_boundary(
         &self,
         activation_point: Option<ActivationPoint>,
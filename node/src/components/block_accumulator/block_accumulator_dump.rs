@@ -0,0 +1,43 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+
+use casper_types::{EraId, Timestamp};
+
+use crate::types::BlockHash;
+
+/// A snapshot of the state of a single block acceptor, for debugging.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BlockAcceptorDump {
+    pub(crate) block_hash: BlockHash,
+    pub(crate) era_id: Option<EraId>,
+    /// The block's height, if we have the block.
+    pub(crate) block_height: Option<u64>,
+    pub(crate) signature_count: usize,
+    /// The signed share of the era's total validator weight in percent, if the era's validator
+    /// weights are known.
+    pub(crate) signed_weight_percent: Option<u64>,
+    pub(crate) has_weak_finality: bool,
+    pub(crate) has_sufficient_finality: bool,
+    pub(crate) peer_count: usize,
+    pub(crate) last_progress: Timestamp,
+}
+
+/// A snapshot of the state of the block accumulator, for operators debugging stuck sync.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BlockAccumulatorDump {
+    /// The height and era of the local tip, if known.
+    pub(crate) local_tip: Option<(u64, EraId)>,
+    pub(crate) last_progress: Timestamp,
+    /// The block acceptors, ordered by block height.
+    pub(crate) acceptors: Vec<BlockAcceptorDump>,
+    /// Pairs of parent and child block hashes.
+    pub(crate) known_children: Vec<(BlockHash, BlockHash)>,
+}
+
+impl Display for BlockAccumulatorDump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+
+use crate::types::BlockHash;
+
+/// The hash and finality status of a block held by the block accumulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct BlockInRange {
+    pub(crate) block_hash: BlockHash,
+    pub(crate) block_height: u64,
+    /// Whether the block has signatures of more than 1/3 of the era's validator weight.
+    pub(crate) has_weak_finality: bool,
+    /// Whether the block has signatures of more than 2/3 of the era's validator weight.
+    pub(crate) has_strict_finality: bool,
+}
+
+impl Display for BlockInRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let finality = if self.has_strict_finality {
+            "strict"
+        } else if self.has_weak_finality {
+            "weak"
+        } else {
+            "insufficient"
+        };
+        write!(
+            f,
+            "block {} at height {} with {} finality",
+            self.block_hash, self.block_height, finality
+        )
+    }
+}
+
+/// The blocks held by the block accumulator within a height window, ordered by height.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct BlocksInRange(pub(crate) Vec<BlockInRange>);
+
+impl Display for BlocksInRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "no blocks in range");
+        }
+        for block in &self.0 {
+            writeln!(f, "{}", block)?;
+        }
+        Ok(())
+    }
+}
@@ -5,12 +5,28 @@ use casper_types::TimeDiff;
 
 const DEFAULT_ATTEMPT_EXECUTION_THRESHOLD: u64 = 3;
 const DEFAULT_DEAD_AIR_INTERVAL_SECS: u32 = 180;
+const DEFAULT_MAX_BLOCK_ACCEPTORS: u32 = 2000;
+const DEFAULT_FINALITY_SIGNATURE_BATCH_WINDOW_MILLIS: u64 = 250;
+const DEFAULT_ORPHAN_TIMEOUT_SECS: u32 = 60;
+const DEFAULT_MISSING_SIGNATURES_FETCH_INTERVAL_SECS: u32 = 10;
+const DEFAULT_MAX_CONCURRENT_SIGNATURE_VERIFICATIONS: u32 = 4;
 #[cfg(test)]
 const DEFAULT_PURGE_INTERVAL_SECS: u32 = 5; // 5 seconds.
 
 #[cfg(not(test))]
 const DEFAULT_PURGE_INTERVAL_SECS: u32 = 5 * 60; // 5 minutes.
 
+/// The signature weight a block needs before it is considered syncable.
+#[derive(Copy, Clone, DataSize, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalityThreshold {
+    /// More than 1/3 of the era's validator weight, i.e. at least one honest validator signed.
+    Weak,
+    /// More than 2/3 of the era's validator weight.
+    #[default]
+    Strict,
+}
+
 /// Configuration options for the block accumulator.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
@@ -20,6 +36,25 @@ pub struct Config {
     pub dead_air_interval: TimeDiff,
     /// Purge interval.
     pub purge_interval: TimeDiff,
+    /// Maximum number of block acceptors held at any time; the oldest are evicted beyond this.
+    pub max_block_acceptors: u32,
+    /// Time window within which finality signatures for the same block are coalesced into a
+    /// single storage write.
+    pub finality_signature_batch_window: TimeDiff,
+    /// Signature weight at which a block becomes syncable.  Strict finality is always required
+    /// before a block is accepted, regardless of this setting.
+    pub sync_finality_threshold: FinalityThreshold,
+    /// Time after which a block whose parent is still unknown is announced as orphaned.
+    pub orphan_timeout: TimeDiff,
+    /// Time a block may remain with weak, but not strict, finality before the missing finality
+    /// signatures are fetched from peers, and the interval between subsequent fetch attempts.
+    pub missing_signatures_fetch_interval: TimeDiff,
+    /// Whether to drop signatures which are no longer valid from sufficiently finalized blocks
+    /// when the validator matrix is updated.
+    pub purge_signatures_on_validator_change: bool,
+    /// Maximum number of received finality signatures being verified on the blocking thread pool
+    /// at any time.  Further signatures wait for a verification to finish.
+    pub max_concurrent_signature_verifications: u32,
 }
 
 impl Default for Config {
@@ -28,6 +63,17 @@ impl Default for Config {
             attempt_execution_threshold: DEFAULT_ATTEMPT_EXECUTION_THRESHOLD,
             dead_air_interval: TimeDiff::from_seconds(DEFAULT_DEAD_AIR_INTERVAL_SECS),
             purge_interval: TimeDiff::from_seconds(DEFAULT_PURGE_INTERVAL_SECS),
+            max_block_acceptors: DEFAULT_MAX_BLOCK_ACCEPTORS,
+            finality_signature_batch_window: TimeDiff::from_millis(
+                DEFAULT_FINALITY_SIGNATURE_BATCH_WINDOW_MILLIS,
+            ),
+            sync_finality_threshold: FinalityThreshold::default(),
+            orphan_timeout: TimeDiff::from_seconds(DEFAULT_ORPHAN_TIMEOUT_SECS),
+            missing_signatures_fetch_interval: TimeDiff::from_seconds(
+                DEFAULT_MISSING_SIGNATURES_FETCH_INTERVAL_SECS,
+            ),
+            purge_signatures_on_validator_change: true,
+            max_concurrent_signature_verifications: DEFAULT_MAX_CONCURRENT_SIGNATURE_VERIFICATIONS,
         }
     }
 }
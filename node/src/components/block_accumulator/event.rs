@@ -32,6 +32,10 @@ pub(crate) enum Event {
         finality_signature: Box<FinalitySignature>,
         sender: NodeId,
     },
+    SignatureVerified {
+        finality_signature: Box<FinalitySignature>,
+        sender: NodeId,
+    },
     ExecutedBlock {
         meta_block: MetaBlock,
     },
@@ -39,6 +43,18 @@ pub(crate) enum Event {
         maybe_meta_block: Option<MetaBlock>,
         maybe_block_signatures: Option<BlockSignatures>,
     },
+    StoreFinalitySignatureBatch {
+        block_hash: BlockHash,
+    },
+    CheckOrphan {
+        block_hash: BlockHash,
+    },
+    FetchMissingSignatures {
+        block_hash: BlockHash,
+    },
+    SyncingBlockWithWeakFinality {
+        block_hash: BlockHash,
+    },
 }
 
 impl Display for Event {
@@ -51,6 +67,30 @@ impl Display for Event {
                     block_hash
                 )
             }
+            Event::Request(BlockAccumulatorRequest::GetPeerQuality { peers, .. }) => {
+                write!(
+                    f,
+                    "block accumulator peer quality request for {} peers",
+                    peers.len()
+                )
+            }
+            Event::Request(BlockAccumulatorRequest::GetBlocksInRange {
+                start_height,
+                end_height,
+                ..
+            }) => {
+                write!(
+                    f,
+                    "block accumulator blocks in range request: [{}, {}]",
+                    start_height, end_height
+                )
+            }
+            Event::Request(BlockAccumulatorRequest::GetDump { .. }) => {
+                write!(f, "block accumulator dump request")
+            }
+            Event::Request(BlockAccumulatorRequest::GetHighestUsableBlockHeight { .. }) => {
+                write!(f, "block accumulator highest usable block height request")
+            }
             Event::RegisterPeer {
                 block_hash, sender, ..
             } => {
@@ -72,6 +112,12 @@ impl Display for Event {
             } => {
                 write!(f, "received {} from {}", finality_signature, sender)
             }
+            Event::SignatureVerified {
+                finality_signature,
+                sender,
+            } => {
+                write!(f, "verified {} from {}", finality_signature, sender)
+            }
             Event::ExecutedBlock { meta_block } => {
                 write!(f, "executed block {}", meta_block.block.hash())
             }
@@ -89,6 +135,18 @@ impl Display for Event {
                         .unwrap_or_default()
                 )
             }
+            Event::StoreFinalitySignatureBatch { block_hash } => {
+                write!(f, "store batched finality signatures for {}", block_hash)
+            }
+            Event::CheckOrphan { block_hash } => {
+                write!(f, "check whether {} is orphaned", block_hash)
+            }
+            Event::FetchMissingSignatures { block_hash } => {
+                write!(f, "fetch missing finality signatures for {}", block_hash)
+            }
+            Event::SyncingBlockWithWeakFinality { block_hash } => {
+                write!(f, "syncing {} with weak finality", block_hash)
+            }
             Event::Stored {
                 maybe_meta_block: None,
                 maybe_block_signatures,
@@ -1,4 +1,4 @@
-use prometheus::{IntGauge, Registry};
+use prometheus::{IntCounter, IntGauge, Registry};
 
 use crate::unregister_metric;
 
@@ -10,6 +10,11 @@ pub(super) struct Metrics {
     /// Number of child block hashes that we know of and that will be used in order to request next
     /// blocks.
     pub(super) known_child_blocks: IntGauge,
+    /// Number of BlockAcceptors evicted because the configured maximum was reached.
+    pub(super) evicted_block_acceptors: IntCounter,
+    /// Number of finality signatures requested from peers for blocks short of sufficient
+    /// finality.
+    pub(super) missing_signatures_fetched: IntCounter,
     registry: Registry,
 }
 
@@ -24,13 +29,25 @@ impl Metrics {
             "block_accumulator_known_child_blocks".to_string(),
             "number of blocks received by the Block Accumulator for which we know the hash of the child block".to_string(),
         )?;
+        let evicted_block_acceptors = IntCounter::new(
+            "block_accumulator_evicted_block_acceptors".to_string(),
+            "number of block acceptors evicted from the Block Accumulator due to the configured limit".to_string(),
+        )?;
+        let missing_signatures_fetched = IntCounter::new(
+            "block_accumulator_missing_signatures_fetched".to_string(),
+            "number of finality signatures the Block Accumulator requested from peers for blocks short of sufficient finality".to_string(),
+        )?;
 
         registry.register(Box::new(block_acceptors.clone()))?;
         registry.register(Box::new(known_child_blocks.clone()))?;
+        registry.register(Box::new(evicted_block_acceptors.clone()))?;
+        registry.register(Box::new(missing_signatures_fetched.clone()))?;
 
         Ok(Metrics {
             block_acceptors,
             known_child_blocks,
+            evicted_block_acceptors,
+            missing_signatures_fetched,
             registry: registry.clone(),
         })
     }
@@ -40,5 +57,7 @@ impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.block_acceptors);
         unregister_metric!(self.registry, self.known_child_blocks);
+        unregister_metric!(self.registry, self.evicted_block_acceptors);
+        unregister_metric!(self.registry, self.missing_signatures_fetched);
     }
 }
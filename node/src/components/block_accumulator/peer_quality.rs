@@ -0,0 +1,81 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use datasize::DataSize;
+
+use crate::types::NodeId;
+
+/// The score deducted for every item from a peer which failed validation, relative to the score
+/// gained for every valid item.
+const INVALID_ITEM_PENALTY: i64 = 10;
+
+/// Counts of valid and invalid items received from a single peer.
+#[derive(Copy, Clone, DataSize, Debug, Default, PartialEq, Eq)]
+struct PeerRecord {
+    valid: u64,
+    invalid: u64,
+}
+
+impl PeerRecord {
+    fn score(&self) -> i64 {
+        let valid = i64::try_from(self.valid).unwrap_or(i64::MAX);
+        let invalid = i64::try_from(self.invalid).unwrap_or(i64::MAX);
+        valid.saturating_sub(invalid.saturating_mul(INVALID_ITEM_PENALTY))
+    }
+}
+
+/// Tracks the quality of peers based on the blocks and finality signatures they send us.
+///
+/// Peers which repeatedly send us items failing validation end up with a negative score and can
+/// be deprioritized by other components, even if their behavior does not warrant disconnecting.
+#[derive(DataSize, Debug, Default)]
+pub(super) struct PeerQuality {
+    records: BTreeMap<NodeId, PeerRecord>,
+}
+
+impl PeerQuality {
+    /// Records that the given peer sent us a valid block or finality signature.
+    pub(super) fn record_valid(&mut self, peer: NodeId) {
+        let record = self.records.entry(peer).or_default();
+        record.valid = record.valid.saturating_add(1);
+    }
+
+    /// Records that the given peer sent us a block or finality signature which failed validation.
+    pub(super) fn record_invalid(&mut self, peer: NodeId) {
+        let record = self.records.entry(peer).or_default();
+        record.invalid = record.invalid.saturating_add(1);
+    }
+
+    /// Returns the score of the given peer, or `0` if we have not heard from it yet.
+    pub(super) fn score(&self, peer: &NodeId) -> i64 {
+        self.records
+            .get(peer)
+            .map(PeerRecord::score)
+            .unwrap_or_default()
+    }
+
+    /// Returns the scores of the given peers.
+    pub(super) fn scores<'a, I>(&self, peers: I) -> BTreeMap<NodeId, i64>
+    where
+        I: IntoIterator<Item = &'a NodeId>,
+    {
+        peers
+            .into_iter()
+            .map(|peer| (*peer, self.score(peer)))
+            .collect()
+    }
+
+    /// Halves all recorded counts, so that old behavior is gradually forgotten, and drops peers
+    /// with nothing left on record.
+    pub(super) fn decay(&mut self) {
+        self.records.retain(|_, record| {
+            record.valid /= 2;
+            record.invalid /= 2;
+            *record != PeerRecord::default()
+        });
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.records.len()
+    }
+}
@@ -0,0 +1,69 @@
+use datasize::DataSize;
+use linked_hash_map::LinkedHashMap;
+
+use crate::types::{BlockHash, FinalitySignatureId};
+
+/// The maximum number of finality signature IDs remembered at any time.
+const MAX_SEEN_FINALITY_SIGNATURES: usize = 10_000;
+
+/// A least-recently-used cache of the IDs of finality signatures which have already been verified
+/// and registered with a block acceptor.
+///
+/// Finality signatures are gossiped by many peers, so we usually receive each one several times.
+/// Remembering which ones we've already seen lets us drop the duplicates without verifying them
+/// again.
+#[derive(DataSize, Debug)]
+pub(super) struct SeenFinalitySignatures {
+    capacity: usize,
+    #[data_size(skip)]
+    ids: LinkedHashMap<FinalitySignatureId, ()>,
+}
+
+impl SeenFinalitySignatures {
+    pub(super) fn new(capacity: usize) -> Self {
+        SeenFinalitySignatures {
+            capacity,
+            ids: LinkedHashMap::new(),
+        }
+    }
+
+    /// Returns `true` if the given ID has been seen, marking it as the most recently used.
+    pub(super) fn contains(&mut self, id: &FinalitySignatureId) -> bool {
+        self.ids.get_refresh(id).is_some()
+    }
+
+    /// Records the given ID as seen, evicting the least recently used IDs beyond capacity.
+    pub(super) fn insert(&mut self, id: FinalitySignatureId) {
+        self.ids.insert(id, ());
+        while self.ids.len() > self.capacity {
+            self.ids.pop_front();
+        }
+    }
+
+    /// Forgets all IDs of signatures for the given blocks, e.g. when their acceptors are dropped.
+    pub(super) fn forget_blocks(&mut self, block_hashes: &[BlockHash]) {
+        if block_hashes.is_empty() {
+            return;
+        }
+        let to_remove: Vec<FinalitySignatureId> = self
+            .ids
+            .keys()
+            .filter(|id| block_hashes.contains(&id.block_hash))
+            .cloned()
+            .collect();
+        for id in &to_remove {
+            self.ids.remove(id);
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+impl Default for SeenFinalitySignatures {
+    fn default() -> Self {
+        SeenFinalitySignatures::new(MAX_SEEN_FINALITY_SIGNATURES)
+    }
+}
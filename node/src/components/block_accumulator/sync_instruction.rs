@@ -1,18 +1,39 @@
+use casper_types::EraId;
+
 use crate::types::BlockHash;
 
+/// The highest block with sufficient finality known to the block accumulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct HighestUsableBlock {
+    pub(crate) block_hash: BlockHash,
+    pub(crate) era_id: EraId,
+    pub(crate) block_height: u64,
+}
+
 #[derive(Debug)]
 pub(crate) enum SyncInstruction {
-    Leap { block_hash: BlockHash },
-    BlockSync { block_hash: BlockHash },
-    CaughtUp { block_hash: BlockHash },
-    LeapIntervalElapsed { block_hash: BlockHash },
+    Leap {
+        block_hash: BlockHash,
+        highest_usable_block: Option<HighestUsableBlock>,
+    },
+    BlockSync {
+        block_hash: BlockHash,
+        /// Whether the block has strict finality, as opposed to only weak finality.
+        has_strict_finality: bool,
+    },
+    CaughtUp {
+        block_hash: BlockHash,
+    },
+    LeapIntervalElapsed {
+        block_hash: BlockHash,
+    },
 }
 
 impl SyncInstruction {
     pub(crate) fn block_hash(&self) -> BlockHash {
         match self {
-            SyncInstruction::Leap { block_hash }
-            | SyncInstruction::BlockSync { block_hash }
+            SyncInstruction::Leap { block_hash, .. }
+            | SyncInstruction::BlockSync { block_hash, .. }
             | SyncInstruction::CaughtUp { block_hash }
             | SyncInstruction::LeapIntervalElapsed { block_hash } => *block_hash,
         }
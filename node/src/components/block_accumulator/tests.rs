@@ -83,6 +83,8 @@ enum Event {
     NetworkRequest(NetworkRequest<Message>),
     #[from]
     NetworkPeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
+    #[from]
+    FinalitySignatureFetcherRequest(#[serde(skip_serializing)] FetcherRequest<FinalitySignature>),
 }
 
 impl From<MarkBlockCompletedRequest> for Event {
@@ -126,6 +128,9 @@ impl Display for Event {
             Event::NetworkPeerBehaviorAnnouncement(peer_behavior) => {
                 write!(formatter, "peer behavior announcement: {:?}", peer_behavior)
             }
+            Event::FinalitySignatureFetcherRequest(request) => {
+                write!(formatter, "finality signature fetcher request: {}", request)
+            }
         }
     }
 }
@@ -263,6 +268,9 @@ impl Reactor for MockReactor {
                 panic!("test does not handle contract runtime events")
             }
             Event::NetworkRequest(_) => panic!("test does not handle network requests"),
+            Event::FinalitySignatureFetcherRequest(_) => {
+                panic!("test does not handle fetcher requests")
+            }
             Event::NetworkPeerBehaviorAnnouncement(peer_behavior) => {
                 self.blocked_peers.push(peer_behavior);
                 Effects::new()
@@ -388,6 +396,338 @@ fn acceptor_get_peers() {
     assert_eq!(acceptor.peers(), &BTreeSet::from([first_peer, second_peer]));
 }
 
+#[tokio::test]
+async fn accumulator_tracks_peer_quality() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+
+    let block = generate_non_genesis_block(&mut rng);
+    let good_peer = NodeId::random(&mut rng);
+    let bad_peer = NodeId::random(&mut rng);
+    let unknown_peer = NodeId::random(&mut rng);
+
+    let effect_builder = runner.effect_builder();
+    let block_accumulator = &mut runner.reactor_mut().block_accumulator;
+    block_accumulator.register_local_tip(0, 0.into());
+
+    // A valid signature improves the sender's score.
+    let valid_fin_sig =
+        FinalitySignature::random_for_block(*block.hash(), block.header().era_id().into());
+    let event = super::Event::SignatureVerified {
+        finality_signature: Box::new(valid_fin_sig),
+        sender: good_peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+
+    // An invalid signature decreases the sender's score and gets them blocked.
+    let invalid_fin_sig = FinalitySignature::new(
+        *block.hash(),
+        block.header().era_id(),
+        Signature::System,
+        PublicKey::random(&mut rng),
+    );
+    let event = super::Event::SignatureVerified {
+        finality_signature: Box::new(invalid_fin_sig),
+        sender: bad_peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+
+    let scores = block_accumulator.get_peer_quality(&[good_peer, bad_peer, unknown_peer]);
+    assert_eq!(scores.len(), 3);
+    assert!(scores[&good_peer] > 0);
+    assert!(scores[&bad_peer] < 0);
+    assert_eq!(scores[&unknown_peer], 0);
+
+    // Purging decays the scores until the peers are forgotten.
+    for _ in 0..4 {
+        block_accumulator.purge();
+    }
+    assert_eq!(block_accumulator.peer_quality.len(), 0);
+    assert_eq!(block_accumulator.peer_quality.score(&bad_peer), 0);
+}
+
+#[tokio::test]
+async fn accumulator_drops_duplicate_finality_signatures() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+
+    let block = generate_non_genesis_block(&mut rng);
+    let first_peer = NodeId::random(&mut rng);
+    let second_peer = NodeId::random(&mut rng);
+
+    let effect_builder = runner.effect_builder();
+    let block_accumulator = &mut runner.reactor_mut().block_accumulator;
+    block_accumulator.register_local_tip(0, 0.into());
+
+    let fin_sig =
+        FinalitySignature::random_for_block(*block.hash(), block.header().era_id().into());
+    for sender in [first_peer, second_peer] {
+        let event = super::Event::SignatureVerified {
+            finality_signature: Box::new(fin_sig.clone()),
+            sender,
+        };
+        let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+        assert!(effects.is_empty());
+    }
+    assert_eq!(block_accumulator.seen_finality_signatures.len(), 1);
+
+    // The duplicate's sender is known to the acceptor, but the signature was only registered once.
+    let acceptor = block_accumulator.block_acceptors.get(block.hash()).unwrap();
+    assert!(acceptor.peers().contains(&second_peer));
+    let (_, senders) = acceptor.signatures().get(&fin_sig.public_key).unwrap();
+    assert_eq!(senders, &BTreeSet::from([first_peer]));
+
+    // Dropping the block's signatures from the cache lets them be registered again.
+    block_accumulator
+        .seen_finality_signatures
+        .forget_blocks(&[*block.hash()]);
+    assert_eq!(block_accumulator.seen_finality_signatures.len(), 0);
+}
+
+#[tokio::test]
+async fn accumulator_verifies_received_finality_signatures_off_thread() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+
+    let block = generate_non_genesis_block(&mut rng);
+    let peer = NodeId::random(&mut rng);
+
+    let effect_builder = runner.effect_builder();
+    let block_accumulator = &mut runner.reactor_mut().block_accumulator;
+    block_accumulator.register_local_tip(0, 0.into());
+
+    // A received signature is verified before being registered.
+    let fin_sig =
+        FinalitySignature::random_for_block(*block.hash(), block.header().era_id().into());
+    let event = super::Event::ReceivedFinalitySignature {
+        finality_signature: Box::new(fin_sig.clone()),
+        sender: peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    assert!(block_accumulator
+        .block_acceptors
+        .get(block.hash())
+        .unwrap()
+        .signatures()
+        .is_empty());
+
+    // The same signature received from another peer while it's being verified isn't verified
+    // again.
+    let event = super::Event::ReceivedFinalitySignature {
+        finality_signature: Box::new(fin_sig.clone()),
+        sender: NodeId::random(&mut rng),
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+    assert_eq!(
+        block_accumulator.finality_signatures_being_verified.len(),
+        1
+    );
+
+    let event = super::Event::SignatureVerified {
+        finality_signature: Box::new(fin_sig.clone()),
+        sender: peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+    assert!(block_accumulator
+        .block_acceptors
+        .get(block.hash())
+        .unwrap()
+        .signatures()
+        .contains_key(&fin_sig.public_key));
+    assert!(block_accumulator
+        .finality_signatures_being_verified
+        .is_empty());
+
+    // Once registered, the same signature isn't verified again.
+    let event = super::Event::ReceivedFinalitySignature {
+        finality_signature: Box::new(fin_sig),
+        sender: NodeId::random(&mut rng),
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+}
+
+#[tokio::test]
+async fn accumulator_detects_orphaned_blocks() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+
+    let parent = generate_non_genesis_block(&mut rng);
+    let block = generate_next_block(&mut rng, &parent);
+    let peer = NodeId::random(&mut rng);
+
+    let effect_builder = runner.effect_builder();
+    let block_accumulator = &mut runner.reactor_mut().block_accumulator;
+    block_accumulator.register_local_tip(0, 0.into());
+
+    // Receiving a block without its parent schedules an orphan check.
+    let event = super::Event::ReceivedBlock {
+        block: Arc::new(block.clone()),
+        sender: peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    assert_eq!(
+        block_accumulator.missing_parent(*block.hash()),
+        Some(*parent.hash())
+    );
+
+    // While the parent is still missing, the check announces the orphan.
+    let effects = block_accumulator.check_orphan(effect_builder, *block.hash());
+    assert_eq!(effects.len(), 1);
+
+    // Once the parent is known, the block is no longer orphaned.
+    let event = super::Event::ReceivedBlock {
+        block: Arc::new(parent.clone()),
+        sender: peer,
+    };
+    block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(block_accumulator.missing_parent(*block.hash()), None);
+    let effects = block_accumulator.check_orphan(effect_builder, *block.hash());
+    assert!(effects.is_empty());
+}
+
+#[tokio::test]
+async fn accumulator_fetches_missing_signatures() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+    let effect_builder = runner.effect_builder();
+
+    let block = generate_non_genesis_block(&mut rng);
+    let era_id = block.header().era_id();
+    let mut validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    validator_matrix.register_era_validator_weights(EraValidatorWeights::new(
+        era_id,
+        BTreeMap::from([
+            (ALICE_PUBLIC_KEY.clone(), U512::from(101)),
+            (BOB_PUBLIC_KEY.clone(), U512::from(100)),
+            (CAROL_PUBLIC_KEY.clone(), U512::from(100)),
+        ]),
+        Ratio::new(1, 3),
+    ));
+    let config = Config::default();
+    let block_time = config.purge_interval / 2;
+    let mut block_accumulator = BlockAccumulator::new(
+        config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &Registry::default(),
+    )
+    .unwrap();
+    let peer = NodeId::random(&mut rng);
+
+    let event = super::Event::ReceivedBlock {
+        block: Arc::new(block.clone()),
+        sender: peer,
+    };
+    block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(block_accumulator.missing_signatures_fetches.is_empty());
+
+    // Alice's signature brings the block to weak finality, which schedules a fetch.
+    let event = super::Event::SignatureVerified {
+        finality_signature: Box::new(FinalitySignature::create(
+            *block.hash(),
+            era_id,
+            &ALICE_SECRET_KEY,
+            ALICE_PUBLIC_KEY.clone(),
+        )),
+        sender: peer,
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    assert!(block_accumulator
+        .missing_signatures_fetches
+        .contains(block.hash()));
+
+    // Syncing the block fetches Bob's and Carol's signatures right away, without scheduling
+    // another fetch while one is pending.
+    let event = super::Event::SyncingBlockWithWeakFinality {
+        block_hash: *block.hash(),
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 2);
+
+    // Once the scheduled fetch is due, they are fetched again, and another fetch is scheduled.
+    let event = super::Event::FetchMissingSignatures {
+        block_hash: *block.hash(),
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 3);
+    assert!(block_accumulator
+        .missing_signatures_fetches
+        .contains(block.hash()));
+
+    // Once the block reaches strict finality, nothing more is fetched.
+    let event = super::Event::SignatureVerified {
+        finality_signature: Box::new(FinalitySignature::create(
+            *block.hash(),
+            era_id,
+            &BOB_SECRET_KEY,
+            BOB_PUBLIC_KEY.clone(),
+        )),
+        sender: peer,
+    };
+    block_accumulator.handle_event(effect_builder, &mut rng, event);
+    let event = super::Event::FetchMissingSignatures {
+        block_hash: *block.hash(),
+    };
+    let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+    assert!(block_accumulator.missing_signatures_fetches.is_empty());
+}
+
 #[test]
 fn acceptor_register_finality_signature() {
     let mut rng = TestRng::new();
@@ -766,6 +1106,63 @@ fn acceptor_should_store_block() {
     assert_eq!(should_store, ShouldStore::Nothing);
 }
 
+#[test]
+fn acceptor_purges_invalidated_signatures() {
+    let mut rng = TestRng::new();
+    let block = Arc::new(Block::random(&mut rng));
+    let era_id = block.header().era_id();
+    let mut acceptor = BlockAcceptor::new(*block.hash(), vec![]);
+
+    let keys: Vec<(SecretKey, PublicKey)> = (0..5)
+        .into_iter()
+        .map(|_| generate_ed25519_keypair())
+        .collect();
+    // The first 4 validators sign the block.
+    for (secret_key, public_key) in keys.iter().take(4) {
+        let fin_sig =
+            FinalitySignature::create(*block.hash(), era_id, secret_key, public_key.clone());
+        acceptor
+            .register_finality_signature(fin_sig, None, VALIDATOR_SLOTS)
+            .unwrap();
+    }
+    let mut meta_block = meta_block_with_default_state(block.clone());
+    meta_block.state.register_has_sufficient_finality();
+    acceptor.register_block(meta_block, None).unwrap();
+    assert!(acceptor.has_sufficient_finality());
+
+    // Nothing is purged while all signers are still validators.
+    let era_validator_weights = EraValidatorWeights::new(
+        era_id,
+        keys.iter()
+            .take(4)
+            .map(|(_, public_key)| (public_key.clone(), U512::from(25)))
+            .collect(),
+        Ratio::new(1, 3),
+    );
+    assert!(!acceptor.purge_invalidated_signatures(&era_validator_weights));
+    assert_eq!(acceptor.signatures().len(), 4);
+
+    // Replace the first 2 validators with a new, heavy validator which hasn't signed.
+    let era_validator_weights = EraValidatorWeights::new(
+        era_id,
+        BTreeMap::from([
+            (keys[2].1.clone(), U512::from(10)),
+            (keys[3].1.clone(), U512::from(10)),
+            (keys[4].1.clone(), U512::from(80)),
+        ]),
+        Ratio::new(1, 3),
+    );
+    assert!(acceptor.purge_invalidated_signatures(&era_validator_weights));
+    assert_eq!(acceptor.signatures().len(), 2);
+    assert!(!acceptor.signatures().contains_key(&keys[0].1));
+    assert!(!acceptor.signatures().contains_key(&keys[1].1));
+    // The block was already stored, so it keeps its status.
+    assert!(acceptor.has_sufficient_finality());
+
+    // Purging again is a no-op.
+    assert!(!acceptor.purge_invalidated_signatures(&era_validator_weights));
+}
+
 #[test]
 fn acceptor_should_correctly_bound_the_signatures() {
     let mut rng = TestRng::new();
@@ -991,6 +1388,280 @@ fn accumulator_should_leap() {
     );
 }
 
+#[test]
+fn accumulator_leap_instruction_carries_highest_usable_block() {
+    let mut rng = TestRng::new();
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let block_accumulator_config = Config::default();
+    let block_time = block_accumulator_config.purge_interval / 2;
+    let mut block_accumulator = BlockAccumulator::new(
+        block_accumulator_config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &Registry::default(),
+    )
+    .unwrap();
+
+    let era_id = EraId::from(5);
+    block_accumulator.local_tip = Some(LocalTipIdentifier::new(1, era_id));
+    assert_eq!(block_accumulator.highest_usable_block(), None);
+
+    // An acceptor without sufficient finality is not usable.
+    let unusable_block =
+        Block::random_with_specifics(&mut rng, era_id, 200, ProtocolVersion::V1_0_0, false, None);
+    block_accumulator.block_acceptors.insert(
+        *unusable_block.hash(),
+        BlockAcceptor::new(*unusable_block.hash(), vec![]),
+    );
+
+    for height in [50, 100] {
+        let block = Block::random_with_specifics(
+            &mut rng,
+            era_id,
+            height,
+            ProtocolVersion::V1_0_0,
+            false,
+            None,
+        );
+        block_accumulator
+            .block_acceptors
+            .insert(*block.hash(), block_acceptor(block));
+    }
+    let highest_usable_block = block_accumulator
+        .highest_usable_block()
+        .expect("should have a usable block");
+    assert_eq!(highest_usable_block.era_id, era_id);
+    assert_eq!(highest_usable_block.block_height, 100);
+
+    let sync_instruction = block_accumulator.sync_instruction(
+        SyncIdentifier::SyncedBlockIdentifier(BlockHash::random(&mut rng), 1, era_id),
+    );
+    match sync_instruction {
+        SyncInstruction::Leap {
+            highest_usable_block: Some(hint),
+            ..
+        } => assert_eq!(hint, highest_usable_block),
+        other => panic!("unexpected sync instruction: {:?}", other),
+    }
+}
+
+#[test]
+fn accumulator_syncs_blocks_with_weak_finality_when_configured() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        sync_finality_threshold: FinalityThreshold::Weak,
+        ..Default::default()
+    };
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let block_time = config.purge_interval / 2;
+    let mut block_accumulator = BlockAccumulator::new(
+        config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &Registry::default(),
+    )
+    .unwrap();
+
+    let parent = generate_non_genesis_block(&mut rng);
+    let block = Arc::new(generate_next_block(&mut rng, &parent));
+    let era_validator_weights = EraValidatorWeights::new(
+        block.header().era_id(),
+        BTreeMap::from([
+            (ALICE_PUBLIC_KEY.clone(), U512::from(101)),
+            (BOB_PUBLIC_KEY.clone(), U512::from(100)),
+            (CAROL_PUBLIC_KEY.clone(), U512::from(100)),
+        ]),
+        Ratio::new(1, 3),
+    );
+
+    let mut acceptor = BlockAcceptor::new(*block.hash(), vec![]);
+    acceptor
+        .register_block(meta_block_with_default_state(block.clone()), None)
+        .unwrap();
+    let fin_sig = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &ALICE_SECRET_KEY,
+        ALICE_PUBLIC_KEY.clone(),
+    );
+    acceptor
+        .register_finality_signature(fin_sig, None, VALIDATOR_SLOTS)
+        .unwrap();
+    // Alice's signature alone brings the block to weak, but not strict, finality.
+    let (should_store, _offenders) = acceptor.should_store_block(&era_validator_weights);
+    assert_eq!(should_store, ShouldStore::Nothing);
+    assert!(acceptor.has_weak_finality());
+    assert!(!acceptor.has_sufficient_finality());
+
+    block_accumulator
+        .block_children
+        .insert(*parent.hash(), *block.hash());
+    block_accumulator
+        .block_acceptors
+        .insert(*block.hash(), acceptor);
+    assert_eq!(
+        block_accumulator.next_syncable_block_hash(*parent.hash()),
+        Some(*block.hash())
+    );
+
+    // The sync instruction reports that the block only has weak finality.
+    block_accumulator.register_local_tip(parent.header().height(), parent.header().era_id());
+    let sync_identifier = SyncIdentifier::LocalTip(
+        *parent.hash(),
+        parent.header().height(),
+        parent.header().era_id(),
+    );
+    assert!(matches!(
+        block_accumulator.sync_instruction(sync_identifier.clone()),
+        SyncInstruction::BlockSync {
+            block_hash,
+            has_strict_finality: false,
+        } if block_hash == *block.hash()
+    ));
+
+    // With the strict threshold, the block is not syncable yet.
+    block_accumulator.sync_finality_threshold = FinalityThreshold::Strict;
+    assert_eq!(
+        block_accumulator.next_syncable_block_hash(*parent.hash()),
+        None
+    );
+
+    // Bob's signature brings the block to strict finality, making it syncable either way.
+    let acceptor = block_accumulator
+        .block_acceptors
+        .get_mut(block.hash())
+        .unwrap();
+    let fin_sig = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &BOB_SECRET_KEY,
+        BOB_PUBLIC_KEY.clone(),
+    );
+    acceptor
+        .register_finality_signature(fin_sig, None, VALIDATOR_SLOTS)
+        .unwrap();
+    let (should_store, _offenders) = acceptor.should_store_block(&era_validator_weights);
+    assert!(matches!(
+        should_store,
+        ShouldStore::SufficientlySignedBlock { .. }
+    ));
+    assert!(acceptor.has_sufficient_finality());
+    assert_eq!(
+        block_accumulator.next_syncable_block_hash(*parent.hash()),
+        Some(*block.hash())
+    );
+    assert!(matches!(
+        block_accumulator.sync_instruction(sync_identifier),
+        SyncInstruction::BlockSync {
+            block_hash,
+            has_strict_finality: true,
+        } if block_hash == *block.hash()
+    ));
+}
+
+#[test]
+fn accumulator_returns_blocks_in_range() {
+    let mut rng = TestRng::new();
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let block_accumulator_config = Config::default();
+    let block_time = block_accumulator_config.purge_interval / 2;
+    let mut block_accumulator = BlockAccumulator::new(
+        block_accumulator_config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &Registry::default(),
+    )
+    .unwrap();
+
+    let era_id = EraId::from(5);
+    let mut blocks = vec![];
+    for height in [10, 11, 12, 20] {
+        let block = Block::random_with_specifics(
+            &mut rng,
+            era_id,
+            height,
+            ProtocolVersion::V1_0_0,
+            false,
+            None,
+        );
+        blocks.push(*block.hash());
+        block_accumulator
+            .block_acceptors
+            .insert(*block.hash(), block_acceptor(block));
+    }
+    // An acceptor for a block we haven't received yet has no height, so is never in range.
+    let unknown_block_hash = BlockHash::random(&mut rng);
+    block_accumulator.block_acceptors.insert(
+        unknown_block_hash,
+        BlockAcceptor::new(unknown_block_hash, vec![]),
+    );
+
+    let BlocksInRange(blocks_in_range) = block_accumulator.get_blocks_in_range(11, 19);
+    assert_eq!(blocks_in_range.len(), 2);
+    assert_eq!(blocks_in_range[0].block_hash, blocks[1]);
+    assert_eq!(blocks_in_range[0].block_height, 11);
+    assert_eq!(blocks_in_range[1].block_hash, blocks[2]);
+    assert!(blocks_in_range
+        .iter()
+        .all(|block| block.has_strict_finality && block.has_weak_finality));
+
+    assert_eq!(block_accumulator.get_blocks_in_range(10, 20).0.len(), 4);
+    assert!(block_accumulator.get_blocks_in_range(13, 19).0.is_empty());
+}
+
+#[test]
+fn accumulator_dump_reports_acceptors_and_children() {
+    let mut rng = TestRng::new();
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let block_accumulator_config = Config::default();
+    let block_time = block_accumulator_config.purge_interval / 2;
+    let mut block_accumulator = BlockAccumulator::new(
+        block_accumulator_config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &Registry::default(),
+    )
+    .unwrap();
+
+    let parent = generate_non_genesis_block(&mut rng);
+    let child = generate_next_block(&mut rng, &parent);
+    block_accumulator.local_tip = Some(LocalTipIdentifier::new(
+        parent.header().height(),
+        parent.header().era_id(),
+    ));
+    block_accumulator
+        .block_acceptors
+        .insert(*child.hash(), block_acceptor(child.clone()));
+    block_accumulator
+        .block_children
+        .insert(*parent.hash(), *child.hash());
+
+    let dump = block_accumulator.dump();
+    assert_eq!(
+        dump.local_tip,
+        Some((parent.header().height(), parent.header().era_id()))
+    );
+    assert_eq!(dump.known_children, vec![(*parent.hash(), *child.hash())]);
+    assert_eq!(dump.acceptors.len(), 1);
+    let acceptor = &dump.acceptors[0];
+    assert_eq!(acceptor.block_hash, *child.hash());
+    assert_eq!(acceptor.block_height, Some(child.header().height()));
+    assert_eq!(acceptor.signature_count, 1);
+    assert!(acceptor.has_sufficient_finality);
+
+    // The dump is rendered as JSON.
+    let json: serde_json::Value = serde_json::from_str(&dump.to_string()).unwrap();
+    assert_eq!(json["acceptors"].as_array().unwrap().len(), 1);
+}
+
 fn expected_leap_instruction(expected: LeapInstruction, actual: LeapInstruction) {
     assert!(
         expected.eq(&actual),
@@ -1474,6 +2145,133 @@ fn accumulator_purge() {
     }
 }
 
+#[test]
+fn accumulator_evicts_block_acceptors_over_limit() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        max_block_acceptors: 3,
+        ..Default::default()
+    };
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let block_time = config.purge_interval / 2;
+    let metrics_registry = Registry::new();
+    let mut accumulator = BlockAccumulator::new(
+        config,
+        validator_matrix,
+        RECENT_ERA_INTERVAL,
+        block_time,
+        VALIDATOR_SLOTS,
+        &metrics_registry,
+    )
+    .unwrap();
+
+    // Create acceptors for three consecutive blocks.
+    let block_1 = generate_non_genesis_block(&mut rng);
+    let block_2 = generate_next_block(&mut rng, &block_1);
+    let block_3 = generate_next_block(&mut rng, &block_2);
+    for block in [&block_1, &block_2, &block_3].iter().copied() {
+        accumulator.upsert_acceptor(*block.hash(), Some(block.header().era_id()), None);
+        accumulator
+            .block_acceptors
+            .get_mut(block.hash())
+            .unwrap()
+            .set_meta_block(Some(meta_block_with_default_state(Arc::new(block.clone()))));
+    }
+    assert_eq!(accumulator.block_acceptors.len(), 3);
+    assert_eq!(accumulator.metrics.evicted_block_acceptors.get(), 0);
+
+    // An acceptor for the next block evicts the one with the lowest height.
+    let block_4 = generate_next_block(&mut rng, &block_3);
+    accumulator.upsert_acceptor(*block_4.hash(), Some(block_4.header().era_id()), None);
+    assert_eq!(accumulator.block_acceptors.len(), 3);
+    assert!(!accumulator.block_acceptors.contains_key(block_1.hash()));
+    assert!(accumulator.block_acceptors.contains_key(block_4.hash()));
+    assert_eq!(accumulator.metrics.evicted_block_acceptors.get(), 1);
+
+    // The acceptor for block 4 has no block yet, so it is evicted next.
+    let block_hash = BlockHash::random(&mut rng);
+    accumulator.upsert_acceptor(block_hash, Some(block_4.header().era_id()), None);
+    assert_eq!(accumulator.block_acceptors.len(), 3);
+    assert!(!accumulator.block_acceptors.contains_key(block_4.hash()));
+    assert!(accumulator.block_acceptors.contains_key(&block_hash));
+    assert!(accumulator.block_acceptors.contains_key(block_2.hash()));
+    assert_eq!(accumulator.metrics.evicted_block_acceptors.get(), 2);
+    assert_eq!(accumulator.metrics.block_acceptors.get(), 3);
+}
+
+#[tokio::test]
+async fn accumulator_batches_late_finality_signatures() {
+    let mut rng = TestRng::new();
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut runner: Runner<MockReactor> = Runner::new(
+        (),
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+
+    let block = generate_non_genesis_block(&mut rng);
+    let fin_sig_bob = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &BOB_SECRET_KEY,
+        BOB_PUBLIC_KEY.clone(),
+    );
+    let fin_sig_carol = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &CAROL_SECRET_KEY,
+        CAROL_PUBLIC_KEY.clone(),
+    );
+
+    let effect_builder = runner.effect_builder();
+    let block_accumulator = &mut runner.reactor_mut().block_accumulator;
+
+    // The first signature starts a new batch and schedules it to be stored.
+    let effects = block_accumulator.store_block_and_finality_signatures(
+        effect_builder,
+        ShouldStore::SingleSignature(fin_sig_bob.clone()),
+        None,
+    );
+    assert_eq!(effects.len(), 1);
+
+    // The second signature for the same block joins the existing batch.
+    let effects = block_accumulator.store_block_and_finality_signatures(
+        effect_builder,
+        ShouldStore::SingleSignature(fin_sig_carol.clone()),
+        None,
+    );
+    assert!(effects.is_empty());
+    assert_eq!(
+        block_accumulator.finality_signature_batches[block.hash()],
+        signatures_for_block(&block, &vec![fin_sig_bob, fin_sig_carol])
+    );
+
+    // Once the batch window elapses, both signatures are written in a single request.
+    let effects = block_accumulator.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::StoreFinalitySignatureBatch {
+            block_hash: *block.hash(),
+        },
+    );
+    assert_eq!(effects.len(), 1);
+    assert!(block_accumulator.finality_signature_batches.is_empty());
+
+    // A repeated event is a no-op.
+    let effects = block_accumulator.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::StoreFinalitySignatureBatch {
+            block_hash: *block.hash(),
+        },
+    );
+    assert!(effects.is_empty());
+}
+
 fn register_evw_for_era(validator_matrix: &mut ValidatorMatrix, era_id: EraId) {
     let weights = EraValidatorWeights::new(
         era_id,
@@ -1578,7 +2376,7 @@ async fn block_accumulator_reactor_flow() {
         let block_accumulator = &mut reactor.block_accumulator;
         block_accumulator.register_local_tip(0, 0.into());
 
-        let event = super::Event::ReceivedFinalitySignature {
+        let event = super::Event::SignatureVerified {
             finality_signature: Box::new(fin_sig_1.clone()),
             sender: peer_1,
         };
@@ -1857,7 +2655,7 @@ async fn block_accumulator_reactor_flow() {
         let reactor = runner.reactor_mut();
 
         let block_accumulator = &mut reactor.block_accumulator;
-        let event = super::Event::ReceivedFinalitySignature {
+        let event = super::Event::SignatureVerified {
             finality_signature: Box::new(older_block_signature),
             sender: peer_2,
         };
@@ -1892,7 +2690,7 @@ async fn block_accumulator_reactor_flow() {
         let reactor = runner.reactor_mut();
 
         let block_accumulator = &mut reactor.block_accumulator;
-        let event = super::Event::ReceivedFinalitySignature {
+        let event = super::Event::SignatureVerified {
             finality_signature: Box::new(old_era_signature),
             sender: peer_2,
         };
@@ -1972,14 +2770,14 @@ async fn block_accumulator_doesnt_purge_with_delayed_block_execution() {
         let block_accumulator = &mut reactor.block_accumulator;
         block_accumulator.register_local_tip(0, 0.into());
 
-        let event = super::Event::ReceivedFinalitySignature {
+        let event = super::Event::SignatureVerified {
             finality_signature: Box::new(fin_sig_bob.clone()),
             sender: peer_1,
         };
         let effects = block_accumulator.handle_event(effect_builder, &mut rng, event);
         assert!(effects.is_empty());
 
-        let event = super::Event::ReceivedFinalitySignature {
+        let event = super::Event::SignatureVerified {
             finality_signature: Box::new(fin_sig_carol.clone()),
             sender: peer_1,
         };
@@ -1,5 +1,6 @@
 mod block_acquisition;
 mod block_acquisition_action;
+mod block_body_acquisition;
 mod block_builder;
 mod block_synchronizer_progress;
 mod config;
@@ -17,7 +18,7 @@ mod trie_accumulator;
 #[cfg(test)]
 mod tests;
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, future::Future, iter, sync::Arc};
 
 use datasize::DataSize;
 use either::Either;
@@ -26,11 +27,11 @@ use once_cell::sync::Lazy;
 use prometheus::Registry;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info, trace, warn};
+use tracing::{debug, error, info, info_span, trace, warn};
 
 use casper_execution_engine::core::engine_state;
 use casper_hashing::Digest;
-use casper_types::Timestamp;
+use casper_types::{EraId, PublicKey, TimeDiff, Timestamp, U512};
 
 use super::network::blocklist::BlocklistJustification;
 use crate::{
@@ -52,10 +53,11 @@ use crate::{
     reactor::{self, main_reactor::MainEvent},
     rpcs::docs::DocExample,
     types::{
-        sync_leap_validation_metadata::SyncLeapValidationMetaData, ApprovalsHashes, Block,
-        BlockExecutionResultsOrChunk, BlockHash, BlockHeader, BlockSignatures, Chainspec, Deploy,
-        FinalitySignature, FinalitySignatureId, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, SyncLeap, SyncLeapIdentifier, TrieOrChunk, ValidatorMatrix,
+        sync_leap_validation_metadata::SyncLeapValidationMetaData, ApprovalsHashes,
+        BlockBodyOrChunk, BlockExecutionResultsOrChunk, BlockHash, BlockHeader, BlockSignatures,
+        Chainspec, Deploy, FinalitySignature, FinalitySignatureId, FinalizedBlock, LegacyDeploy,
+        MetaBlock, MetaBlockState, NodeId, SyncLeap, SyncLeapIdentifier, TrieOrChunk,
+        ValidatorMatrix,
     },
     NodeRng,
 };
@@ -110,7 +112,7 @@ const COMPONENT_NAME: &str = "block_synchronizer";
 pub(crate) trait ReactorEvent:
     From<FetcherRequest<ApprovalsHashes>>
     + From<NetworkInfoRequest>
-    + From<FetcherRequest<Block>>
+    + From<FetcherRequest<BlockBodyOrChunk>>
     + From<FetcherRequest<BlockHeader>>
     + From<FetcherRequest<LegacyDeploy>>
     + From<FetcherRequest<Deploy>>
@@ -135,7 +137,7 @@ pub(crate) trait ReactorEvent:
 impl<REv> ReactorEvent for REv where
     REv: From<FetcherRequest<ApprovalsHashes>>
         + From<NetworkInfoRequest>
-        + From<FetcherRequest<Block>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<LegacyDeploy>>
         + From<FetcherRequest<Deploy>>
@@ -231,7 +233,12 @@ impl BlockSynchronizer {
             validator_matrix,
             forward: None,
             historical: None,
-            global_sync: GlobalStateSynchronizer::new(config.max_parallel_trie_fetches as usize),
+            global_sync: GlobalStateSynchronizer::new(
+                config.max_parallel_trie_fetches as usize,
+                config.max_parallel_chunk_fetches as usize,
+                config.max_in_flight_fetches_per_peer as usize,
+                registry,
+            )?,
             metrics: Metrics::new(registry)?,
         })
     }
@@ -287,7 +294,10 @@ impl BlockSynchronizer {
             &self.historical,
             &self.forward,
         ) {
-            if builder.block_hash() == block_hash && !builder.is_failed() {
+            if builder.block_hash() == block_hash
+                && !builder.is_failed()
+                && !builder.is_headers_only()
+            {
                 return false;
             }
         }
@@ -309,6 +319,35 @@ impl BlockSynchronizer {
         true
     }
 
+    /// Registers a historical block for synchronization of its header and finality signatures
+    /// only; its body, global state and deploys are not acquired.
+    ///
+    /// Returns `true` if the block was registered for synchronization successfully, or `false` if
+    /// the synchronizer is already working on the same block in this mode.
+    pub(crate) fn register_block_header_by_hash(&mut self, block_hash: BlockHash) -> bool {
+        if let Some(builder) = &self.historical {
+            if builder.block_hash() == block_hash
+                && !builder.is_failed()
+                && builder.is_headers_only()
+            {
+                return false;
+            }
+        }
+        let mut builder = BlockBuilder::new(
+            block_hash,
+            true,
+            self.max_simultaneous_peers,
+            self.config.peer_refresh_interval,
+            self.chainspec.core_config.legacy_required_finality,
+            self.chainspec
+                .core_config
+                .start_protocol_version_with_strict_finality_signatures_required,
+        );
+        builder.set_headers_only();
+        self.historical.replace(builder);
+        true
+    }
+
     /// Registers a sync leap result, if able.
     pub(crate) fn register_sync_leap(
         &mut self,
@@ -532,19 +571,26 @@ impl BlockSynchronizer {
         rng: &mut NodeRng,
     ) -> Effects<Event>
     where
-        REv: ReactorEvent + From<FetcherRequest<Block>> + From<MarkBlockCompletedRequest>,
+        REv:
+            ReactorEvent + From<FetcherRequest<BlockBodyOrChunk>> + From<MarkBlockCompletedRequest>,
     {
         let latch_reset_interval = self.config.latch_reset_interval;
         let need_next_interval = self.config.need_next_interval.into();
         let mut results = Effects::new();
         let max_simultaneous_peers = self.max_simultaneous_peers;
-        let mut builder_needs_next = |builder: &mut BlockBuilder, chainspec: Arc<Chainspec>| {
+        let mut builder_needs_next = |builder: &mut BlockBuilder| {
             if builder.check_latch(latch_reset_interval)
                 || builder.is_finished()
                 || builder.is_failed()
             {
                 return;
             }
+            let _span = info_span!(
+                "sync_cycle",
+                block_hash = %builder.block_hash(),
+                historical = builder.should_fetch_execution_state(),
+            )
+            .entered();
             let action = builder.block_acquisition_action(rng, max_simultaneous_peers);
             let peers = action.peers_to_ask();
             let need_next = action.need_next();
@@ -565,21 +611,32 @@ impl BlockSynchronizer {
                 NeedNext::BlockHeader(block_hash) => {
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<BlockHeader>(
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<BlockHeader>(
                                 block_hash,
                                 node_id,
                                 Box::new(EmptyValidationMetadata),
-                            )
-                            .event(Event::BlockHeaderFetched)
+                            ),
+                            Event::BlockHeaderFetched,
+                        )
                     }))
                 }
                 NeedNext::BlockBody(block_hash) => {
+                    let id = builder.block_body_or_chunk_id();
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<Block>(block_hash, node_id, Box::new(EmptyValidationMetadata))
-                            .event(Event::BlockFetched)
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<BlockBodyOrChunk>(
+                                id,
+                                node_id,
+                                Box::new(EmptyValidationMetadata),
+                            ),
+                            Event::BlockBodyOrChunkFetched,
+                        )
                     }))
                 }
                 NeedNext::FinalitySignatures(block_hash, era_id, validators) => {
@@ -599,15 +656,16 @@ impl BlockSynchronizer {
                             era_id,
                             public_key: validator,
                         });
-                        results.extend(
-                            effect_builder
-                                .fetch::<FinalitySignature>(
-                                    id,
-                                    peer,
-                                    Box::new(EmptyValidationMetadata),
-                                )
-                                .event(Event::FinalitySignatureFetched),
-                        );
+                        results.extend(timed_fetch(
+                            block_hash,
+                            peer,
+                            effect_builder.fetch::<FinalitySignature>(
+                                id,
+                                peer,
+                                Box::new(EmptyValidationMetadata),
+                            ),
+                            Event::FinalitySignatureFetched,
+                        ));
                     }
                 }
                 NeedNext::GlobalState(block_hash, global_state_root_hash) => {
@@ -633,46 +691,67 @@ impl BlockSynchronizer {
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
                         debug!("attempting to fetch BlockExecutionResultsOrChunk");
-                        effect_builder
-                            .fetch::<BlockExecutionResultsOrChunk>(id, node_id, Box::new(checksum))
-                            .event(move |result| Event::ExecutionResultsFetched {
-                                block_hash,
-                                result,
-                            })
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<BlockExecutionResultsOrChunk>(
+                                id,
+                                node_id,
+                                Box::new(checksum),
+                            ),
+                            move |result| Event::ExecutionResultsFetched { block_hash, result },
+                        )
                     }))
                 }
                 NeedNext::ApprovalsHashes(block_hash, block) => {
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<ApprovalsHashes>(block_hash, node_id, block.clone())
-                            .event(Event::ApprovalsHashesFetched)
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<ApprovalsHashes>(
+                                block_hash,
+                                node_id,
+                                block.clone(),
+                            ),
+                            Event::ApprovalsHashesFetched,
+                        )
                     }))
                 }
                 NeedNext::DeployByHash(block_hash, deploy_hash) => {
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<LegacyDeploy>(
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<LegacyDeploy>(
                                 deploy_hash,
                                 node_id,
                                 Box::new(EmptyValidationMetadata),
-                            )
-                            .event(move |result| Event::DeployFetched {
+                            ),
+                            move |result| Event::DeployFetched {
                                 block_hash,
                                 result: Either::Left(result),
-                            })
+                            },
+                        )
                     }))
                 }
                 NeedNext::DeployById(block_hash, deploy_id) => {
                     builder.latch_by(peers.len());
                     results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<Deploy>(deploy_id, node_id, Box::new(EmptyValidationMetadata))
-                            .event(move |result| Event::DeployFetched {
+                        timed_fetch(
+                            block_hash,
+                            node_id,
+                            effect_builder.fetch::<Deploy>(
+                                deploy_id,
+                                node_id,
+                                Box::new(EmptyValidationMetadata),
+                            ),
+                            move |result| Event::DeployFetched {
                                 block_hash,
                                 result: Either::Right(result),
-                            })
+                            },
+                        )
                     }))
                 }
                 NeedNext::MakeExecutableBlock(block_hash, _) => {
@@ -730,6 +809,17 @@ impl BlockSynchronizer {
                     results.extend(
                         effect_builder
                             .get_block_accumulated_peers(block_hash)
+                            .then(move |maybe_peers| async move {
+                                match maybe_peers {
+                                    Some(peers) => {
+                                        let peer_quality = effect_builder
+                                            .get_block_accumulator_peer_quality(peers.clone())
+                                            .await;
+                                        Some(prefer_high_quality_peers(peers, &peer_quality))
+                                    }
+                                    None => None,
+                                }
+                            })
                             .event(move |maybe_peers| {
                                 Event::AccumulatedPeers(block_hash, maybe_peers)
                             }),
@@ -740,18 +830,21 @@ impl BlockSynchronizer {
                         "BlockSynchronizer: does not have era_validators for era_id: {}",
                         era_id
                     );
-                    builder.latch_by(peers.len());
-                    results.extend(peers.into_iter().flat_map(|node_id| {
+                    // try to reconstruct them from our own storage before asking peers
+                    let block_hash = builder.block_hash();
+                    builder.latch();
+                    results.extend(
                         effect_builder
-                            .fetch::<SyncLeap>(
-                                SyncLeapIdentifier::sync_to_historical(builder.block_hash()),
-                                node_id,
-                                Box::new(SyncLeapValidationMetaData::from_chainspec(
-                                    chainspec.as_ref(),
-                                )),
-                            )
-                            .event(Event::SyncLeapFetched)
-                    }))
+                            .get_era_validator_weights_from_storage(era_id)
+                            .event(
+                                move |maybe_validator_weights| Event::GotStoredEraValidators {
+                                    block_hash,
+                                    era_id,
+                                    maybe_validator_weights,
+                                    peers,
+                                },
+                            ),
+                    )
                 }
                 NeedNext::SwitchToHaveStrictFinality(block_hash, _) => {
                     // Don't set the latch since this is an internal state transition
@@ -771,10 +864,10 @@ impl BlockSynchronizer {
         };
 
         if let Some(builder) = &mut self.forward {
-            builder_needs_next(builder, Arc::clone(&self.chainspec));
+            builder_needs_next(builder);
         }
         if let Some(builder) = &mut self.historical {
-            builder_needs_next(builder, Arc::clone(&self.chainspec));
+            builder_needs_next(builder);
         }
         results
     }
@@ -785,6 +878,22 @@ impl BlockSynchronizer {
         }
     }
 
+    fn peer_responded(&mut self, block_hash: BlockHash, peer: NodeId, latency: TimeDiff) {
+        self.metrics
+            .peer_latency
+            .observe(latency.millis() as f64 / 1000.0);
+        let slow_peer_latency_percentile = self.config.slow_peer_latency_percentile;
+        let rotated_out = self
+            .get_builder(block_hash, false)
+            .map_or(false, |builder| {
+                builder.register_peer_latency(peer, latency, slow_peer_latency_percentile)
+            });
+        if rotated_out {
+            info!(%peer, %block_hash, %latency, "BlockSynchronizer: rotated out slow peer");
+            self.metrics.slow_peer_rotations.inc();
+        }
+    }
+
     fn block_header_fetched(
         &mut self,
         result: Result<FetchedData<BlockHeader>, FetcherError<BlockHeader>>,
@@ -830,33 +939,36 @@ impl BlockSynchronizer {
         }
     }
 
-    fn block_fetched(&mut self, result: Result<FetchedData<Block>, FetcherError<Block>>) {
-        let (block_hash, maybe_block, maybe_peer_id): (
+    fn block_body_or_chunk_fetched(
+        &mut self,
+        result: Result<FetchedData<BlockBodyOrChunk>, FetcherError<BlockBodyOrChunk>>,
+    ) {
+        let (block_hash, maybe_body_or_chunk, maybe_peer_id): (
             BlockHash,
-            Option<Box<Block>>,
+            Option<Box<BlockBodyOrChunk>>,
             Option<NodeId>,
         ) = match result {
             Ok(FetchedData::FromPeer { item, peer }) => {
                 debug!(
-                    "BlockSynchronizer: fetched body {:?} from peer {}",
-                    item.hash(),
+                    "BlockSynchronizer: fetched {} from peer {}",
+                    item.fetch_id(),
                     peer
                 );
-                (*item.hash(), Some(item), Some(peer))
+                (*item.block_hash(), Some(item), Some(peer))
             }
-            Ok(FetchedData::FromStorage { item }) => (*item.hash(), Some(item), None),
+            Ok(FetchedData::FromStorage { item }) => (*item.block_hash(), Some(item), None),
             Err(err) => {
-                debug!(%err, "BlockSynchronizer: failed to fetch block");
+                debug!(%err, "BlockSynchronizer: failed to fetch block body or chunk");
                 if err.is_peer_fault() {
-                    (*err.id(), None, Some(*err.peer()))
+                    (*err.id().block_hash(), None, Some(*err.peer()))
                 } else {
-                    (*err.id(), None, None)
+                    (*err.id().block_hash(), None, None)
                 }
             }
         };
 
         if let Some(builder) = self.get_builder(block_hash, false) {
-            match maybe_block {
+            match maybe_body_or_chunk {
                 None => {
                     if let Some(peer_id) = maybe_peer_id {
                         builder.demote_peer(peer_id);
@@ -866,9 +978,11 @@ impl BlockSynchronizer {
                         builder.latch_decrement();
                     }
                 }
-                Some(block) => {
-                    if let Err(error) = builder.register_block(&block, maybe_peer_id) {
-                        error!(%error, "BlockSynchronizer: failed to apply block");
+                Some(body_or_chunk) => {
+                    if let Err(error) =
+                        builder.register_block_body_or_chunk(*body_or_chunk, maybe_peer_id)
+                    {
+                        error!(%error, "BlockSynchronizer: failed to apply block body or chunk");
                     }
                 }
             }
@@ -1025,6 +1139,44 @@ impl BlockSynchronizer {
         }
     }
 
+    fn got_stored_era_validators<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_hash: BlockHash,
+        era_id: EraId,
+        maybe_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+        peers: Vec<NodeId>,
+    ) -> Effects<Event>
+    where
+        REv: ReactorEvent,
+    {
+        if let Some(validator_weights) = maybe_validator_weights {
+            info!(%era_id, "BlockSynchronizer: reconstructed era validators from storage");
+            self.validator_matrix
+                .register_validator_weights(era_id, validator_weights);
+        }
+        let validator_matrix = &self.validator_matrix.clone();
+        let sync_leap_validation_metadata =
+            SyncLeapValidationMetaData::from_chainspec(self.chainspec.as_ref());
+        let mut effects = Effects::new();
+        if let Some(builder) = self.get_builder(block_hash, true) {
+            builder.register_era_validator_weights(validator_matrix);
+            if validator_matrix.validator_weights(era_id).is_none() {
+                builder.latch_by(peers.len());
+                effects.extend(peers.into_iter().flat_map(|node_id| {
+                    effect_builder
+                        .fetch::<SyncLeap>(
+                            SyncLeapIdentifier::sync_to_historical(block_hash),
+                            node_id,
+                            Box::new(sync_leap_validation_metadata.clone()),
+                        )
+                        .event(Event::SyncLeapFetched)
+                }))
+            }
+        }
+        effects
+    }
+
     fn global_state_synced(
         &mut self,
         block_hash: BlockHash,
@@ -1320,7 +1472,7 @@ impl BlockSynchronizer {
 
 impl<REv> InitializedComponent<REv> for BlockSynchronizer
 where
-    REv: ReactorEvent + From<FetcherRequest<Block>>,
+    REv: ReactorEvent + From<FetcherRequest<BlockBodyOrChunk>>,
 {
     fn state(&self) -> &ComponentState {
         &self.state
@@ -1385,16 +1537,18 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     | Event::MarkBlockExecuted(_)
                     | Event::MarkBlockCompleted { .. }
                     | Event::BlockHeaderFetched(_)
-                    | Event::BlockFetched(_)
+                    | Event::BlockBodyOrChunkFetched(_)
                     | Event::ApprovalsHashesFetched(_)
                     | Event::FinalitySignatureFetched(_)
                     | Event::SyncLeapFetched(_)
+                    | Event::GotStoredEraValidators { .. }
                     | Event::GlobalStateSynced { .. }
                     | Event::GotExecutionResultsChecksum { .. }
                     | Event::DeployFetched { .. }
                     | Event::ExecutionResultsFetched { .. }
                     | Event::ExecutionResultsStored(_)
                     | Event::AccumulatedPeers(_, _)
+                    | Event::PeerResponded { .. }
                     | Event::NetworkPeers(_, _)
                     | Event::GlobalStateSynchronizer(_) => {
                         warn!(
@@ -1531,9 +1685,10 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     self.block_header_fetched(result);
                     self.need_next(effect_builder, rng)
                 }
-                // for both historical and forward sync, the block body has been fetched
-                Event::BlockFetched(result) => {
-                    self.block_fetched(result);
+                // for both historical and forward sync, the block body or a chunk of it has been
+                // fetched
+                Event::BlockBodyOrChunkFetched(result) => {
+                    self.block_body_or_chunk_fetched(result);
                     self.need_next(effect_builder, rng)
                 }
                 // for both historical and forward sync, a finality signature has been fetched
@@ -1551,6 +1706,24 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     self.sync_leap_fetched(result);
                     self.need_next(effect_builder, rng)
                 }
+                // era validators missing from the validator matrix are first looked up in storage,
+                // and only acquired from peers if that fails
+                Event::GotStoredEraValidators {
+                    block_hash,
+                    era_id,
+                    maybe_validator_weights,
+                    peers,
+                } => {
+                    let mut effects = self.got_stored_era_validators(
+                        effect_builder,
+                        block_hash,
+                        era_id,
+                        maybe_validator_weights,
+                        peers,
+                    );
+                    effects.extend(self.need_next(effect_builder, rng));
+                    effects
+                }
                 // we use the existence of n execution results checksum as an expedient way to
                 // determine if a block is post-1.4
                 Event::GotExecutionResultsChecksum { block_hash, result } => {
@@ -1609,6 +1782,14 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     };
                     self.need_next(effect_builder, rng)
                 }
+                Event::PeerResponded {
+                    block_hash,
+                    peer,
+                    latency,
+                } => {
+                    self.peer_responded(block_hash, peer, latency);
+                    Effects::new()
+                }
                 // fresh peers to apply (random sample from network)
                 Event::NetworkPeers(block_hash, peers) => {
                     debug!(%block_hash, "BlockSynchronizer: got {} peers from network", peers.len());
@@ -1663,3 +1844,49 @@ impl<REv: ReactorEvent> ValidatorBoundComponent<REv> for BlockSynchronizer {
         self.need_next(effect_builder, rng)
     }
 }
+
+/// Turns a fetch from `peer` into an effect which, besides the event built from the fetch result,
+/// reports how long the peer took to respond.  Nothing is reported for items found in storage.
+fn timed_fetch<T, F>(
+    block_hash: BlockHash,
+    peer: NodeId,
+    fetch: impl Future<Output = FetchResult<T>> + Send + 'static,
+    to_event: F,
+) -> Effects<Event>
+where
+    T: FetchItem + 'static,
+    F: FnOnce(FetchResult<T>) -> Event + Send + 'static,
+{
+    let requested_at = Timestamp::now();
+    fetch.events(move |result| {
+        let from_peer = match &result {
+            Ok(FetchedData::FromPeer { .. }) => true,
+            Ok(FetchedData::FromStorage { .. }) => false,
+            Err(error) => error.is_peer_fault(),
+        };
+        let maybe_peer_responded = from_peer.then(|| Event::PeerResponded {
+            block_hash,
+            peer,
+            latency: Timestamp::now().saturating_diff(requested_at),
+        });
+        maybe_peer_responded
+            .into_iter()
+            .chain(iter::once(to_event(result)))
+    })
+}
+
+/// Filters out peers with a negative quality score, unless doing so would leave us with no peers
+/// at all.
+fn prefer_high_quality_peers(
+    peers: Vec<NodeId>,
+    peer_quality: &BTreeMap<NodeId, i64>,
+) -> Vec<NodeId> {
+    let (preferred, deprioritized): (Vec<_>, Vec<_>) = peers
+        .into_iter()
+        .partition(|peer| peer_quality.get(peer).copied().unwrap_or_default() >= 0);
+    if preferred.is_empty() {
+        deprioritized
+    } else {
+        preferred
+    }
+}
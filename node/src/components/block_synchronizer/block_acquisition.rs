@@ -496,6 +496,71 @@ impl BlockAcquisitionState {
         ret
     }
 
+    /// Determines what needs to be done next when only the block header and sufficient finality
+    /// signatures for it are being acquired; the block body and global state are never fetched.
+    pub(super) fn next_header_only_action(
+        &mut self,
+        peer_list: &PeerList,
+        validator_weights: &EraValidatorWeights,
+        rng: &mut NodeRng,
+        max_simultaneous_peers: u8,
+    ) -> Result<BlockAcquisitionAction, BlockAcquisitionError> {
+        if matches!(
+            self,
+            BlockAcquisitionState::Initialized(..) | BlockAcquisitionState::HaveBlockHeader(..)
+        ) {
+            return self.next_action(
+                peer_list,
+                validator_weights,
+                rng,
+                true,
+                max_simultaneous_peers,
+            );
+        }
+        match self {
+            BlockAcquisitionState::HaveWeakFinalitySignatures(header, signatures) => {
+                if signatures.has_sufficient_finality(true, true) {
+                    Ok(BlockAcquisitionAction::need_nothing(header.block_hash()))
+                } else {
+                    Ok(signatures_from_missing_validators(
+                        validator_weights,
+                        signatures,
+                        max_simultaneous_peers,
+                        peer_list,
+                        rng,
+                        header,
+                    ))
+                }
+            }
+            BlockAcquisitionState::Failed(block_hash, ..) => {
+                Ok(BlockAcquisitionAction::need_nothing(*block_hash))
+            }
+            BlockAcquisitionState::Initialized(..)
+            | BlockAcquisitionState::HaveBlockHeader(..)
+            | BlockAcquisitionState::HaveBlock(..)
+            | BlockAcquisitionState::HaveGlobalState(..)
+            | BlockAcquisitionState::HaveAllExecutionResults(..)
+            | BlockAcquisitionState::HaveApprovalsHashes(..)
+            | BlockAcquisitionState::HaveAllDeploys(..)
+            | BlockAcquisitionState::HaveStrictFinalitySignatures(..)
+            | BlockAcquisitionState::HaveFinalizedBlock(..)
+            | BlockAcquisitionState::Complete(..) => {
+                Err(BlockAcquisitionError::InvalidStateTransition)
+            }
+        }
+    }
+
+    /// Returns `true` if the block header has been acquired along with finality signatures of
+    /// strict finality weight.
+    pub(super) fn has_header_with_strict_finality(&self) -> bool {
+        match self {
+            BlockAcquisitionState::HaveWeakFinalitySignatures(_, signatures) => {
+                signatures.has_sufficient_finality(true, true)
+            }
+            _ => false,
+        }
+    }
+
     /// The block height of the current block, if available.
     pub(super) fn block_height(&self) -> Option<u64> {
         match self {
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    mem,
+};
+
+use datasize::DataSize;
+
+use casper_hashing::{ChunkWithProof, Digest};
+use casper_types::bytesrepr;
+
+use crate::types::{BlockBody, BlockHash};
+
+#[derive(Clone, Copy, PartialEq, Eq, DataSize, Debug)]
+pub(crate) enum Error {
+    ChunkCountMismatch {
+        block_hash: BlockHash,
+        expected: u64,
+        actual: u64,
+    },
+    ChunksWithDifferentRootHash {
+        block_hash: BlockHash,
+        expected: Digest,
+        actual: Digest,
+    },
+    FailedToDeserialize {
+        block_hash: BlockHash,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ChunkCountMismatch {
+                block_hash,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block body chunk count mismatch for block_hash: {}; expected {} actual: {}",
+                block_hash, expected, actual
+            ),
+            Error::ChunksWithDifferentRootHash {
+                block_hash,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block body chunks with different root hash for block_hash: {}; expected {} \
+                actual: {}",
+                block_hash, expected, actual
+            ),
+            Error::FailedToDeserialize { block_hash } => write!(
+                f,
+                "failed to deserialize block body from chunks for block_hash: {}",
+                block_hash
+            ),
+        }
+    }
+}
+
+/// The outcome of applying a single chunk of a block body.
+#[derive(Debug)]
+pub(super) enum ChunkOutcome {
+    /// The chunk had already been applied.
+    HadIt,
+    /// The chunk was applied, but others are still missing.
+    NeededIt,
+    /// The chunk was the last one missing; the chunks have been reassembled into the body.
+    Complete(Box<BlockBody>),
+}
+
+/// The chunks of a block body too large to be sent in a single message, held until all of them
+/// have been fetched.
+///
+/// Each chunk's Merkle proof is verified by the fetcher. The root hash of the chunks is not the
+/// body hash recorded in the block header though, so the reassembled body still has to be checked
+/// against the header.
+#[derive(Clone, PartialEq, Eq, DataSize, Debug)]
+pub(super) struct BlockBodyAcquisition {
+    block_hash: BlockHash,
+    chunks: BTreeMap<u64, ChunkWithProof>,
+}
+
+impl BlockBodyAcquisition {
+    pub(super) fn new(block_hash: BlockHash) -> Self {
+        BlockBodyAcquisition {
+            block_hash,
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the index of the first chunk not yet applied.
+    ///
+    /// This is 0 until the first chunk has been applied, which is also the index to request if the
+    /// body turns out to be small enough to be sent whole.
+    pub(super) fn next_chunk_index(&self) -> u64 {
+        (0..)
+            .find(|index| !self.chunks.contains_key(index))
+            .unwrap_or_default()
+    }
+
+    /// Discards all chunks applied so far.
+    pub(super) fn reset(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Applies a chunk, reassembling the body once the last missing chunk has been applied.
+    ///
+    /// All chunks are expected to be parts of the same data as the chunks applied before.
+    pub(super) fn apply_chunk(&mut self, chunk: ChunkWithProof) -> Result<ChunkOutcome, Error> {
+        let chunk_count = chunk.proof().count();
+        if let Some(applied_chunk) = self.chunks.values().next() {
+            let expected_count = applied_chunk.proof().count();
+            if expected_count != chunk_count {
+                return Err(Error::ChunkCountMismatch {
+                    block_hash: self.block_hash,
+                    expected: expected_count,
+                    actual: chunk_count,
+                });
+            }
+            let expected_root_hash = applied_chunk.proof().root_hash();
+            let actual_root_hash = chunk.proof().root_hash();
+            if expected_root_hash != actual_root_hash {
+                return Err(Error::ChunksWithDifferentRootHash {
+                    block_hash: self.block_hash,
+                    expected: expected_root_hash,
+                    actual: actual_root_hash,
+                });
+            }
+        }
+
+        let index = chunk.proof().index();
+        if self.chunks.contains_key(&index) {
+            return Ok(ChunkOutcome::HadIt);
+        }
+        self.chunks.insert(index, chunk);
+        if (self.chunks.len() as u64) < chunk_count {
+            return Ok(ChunkOutcome::NeededIt);
+        }
+
+        let serialized_body: Vec<u8> = mem::take(&mut self.chunks)
+            .into_values()
+            .flat_map(|chunk| chunk.into_chunk())
+            .collect();
+        bytesrepr::deserialize(serialized_body)
+            .map(|body| ChunkOutcome::Complete(Box::new(body)))
+            .map_err(|_| Error::FailedToDeserialize {
+                block_hash: self.block_hash,
+            })
+    }
+}
@@ -0,0 +1,108 @@
+use assert_matches::assert_matches;
+use casper_types::{bytesrepr::ToBytes, testing::TestRng, PublicKey};
+
+use super::*;
+use crate::{
+    components::block_synchronizer::tests::test_utils::chunks_with_proof_from_data,
+    types::DeployHash,
+};
+
+/// Enough deploy hashes for the serialized body to span three chunks.
+const NUM_TEST_DEPLOY_HASHES: usize = ChunkWithProof::CHUNK_SIZE_BYTES * 5 / 2 / Digest::LENGTH;
+
+fn large_block_body(rng: &mut TestRng) -> BlockBody {
+    let deploy_hashes = (0..NUM_TEST_DEPLOY_HASHES)
+        .map(|_| DeployHash::random(rng))
+        .collect();
+    BlockBody::new(PublicKey::random(rng), deploy_hashes, vec![])
+}
+
+#[test]
+fn block_body_chunks_apply_correctly() {
+    let mut rng = TestRng::new();
+    let block_hash = BlockHash::random(&mut rng);
+    let block_body = large_block_body(&mut rng);
+    let chunks = chunks_with_proof_from_data(&block_body.to_bytes().unwrap());
+    assert_eq!(chunks.len(), 3);
+
+    let mut acquisition = BlockBodyAcquisition::new(block_hash);
+    assert_eq!(acquisition.next_chunk_index(), 0);
+    for (index, chunk) in chunks.iter().take(chunks.len() - 1) {
+        assert_matches!(
+            acquisition.apply_chunk(chunk.clone()),
+            Ok(ChunkOutcome::NeededIt)
+        );
+        assert_eq!(acquisition.next_chunk_index(), index + 1);
+    }
+
+    // Applying a chunk a second time is harmless.
+    let (_, first_chunk) = chunks.first_key_value().unwrap();
+    assert_matches!(
+        acquisition.apply_chunk(first_chunk.clone()),
+        Ok(ChunkOutcome::HadIt)
+    );
+
+    let (_, last_chunk) = chunks.last_key_value().unwrap();
+    assert_matches!(
+        acquisition.apply_chunk(last_chunk.clone()),
+        Ok(ChunkOutcome::Complete(body)) if *body == block_body
+    );
+}
+
+#[test]
+fn block_body_chunks_apply_in_any_order() {
+    let mut rng = TestRng::new();
+    let block_hash = BlockHash::random(&mut rng);
+    let block_body = large_block_body(&mut rng);
+    let chunks = chunks_with_proof_from_data(&block_body.to_bytes().unwrap());
+
+    let mut acquisition = BlockBodyAcquisition::new(block_hash);
+    let (_, last_chunk) = chunks.last_key_value().unwrap();
+    assert_matches!(
+        acquisition.apply_chunk(last_chunk.clone()),
+        Ok(ChunkOutcome::NeededIt)
+    );
+    // The first missing chunk is requested next.
+    assert_eq!(acquisition.next_chunk_index(), 0);
+
+    let mut outcomes: Vec<_> = chunks
+        .values()
+        .take(chunks.len() - 1)
+        .rev()
+        .map(|chunk| acquisition.apply_chunk(chunk.clone()).unwrap())
+        .collect();
+    assert_matches!(outcomes.pop(), Some(ChunkOutcome::Complete(body)) if *body == block_body);
+    assert!(outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, ChunkOutcome::NeededIt)));
+}
+
+#[test]
+fn block_body_chunks_of_different_data_are_rejected() {
+    let mut rng = TestRng::new();
+    let block_hash = BlockHash::random(&mut rng);
+    let chunks = chunks_with_proof_from_data(&large_block_body(&mut rng).to_bytes().unwrap());
+    let other_chunks = chunks_with_proof_from_data(&large_block_body(&mut rng).to_bytes().unwrap());
+
+    let mut acquisition = BlockBodyAcquisition::new(block_hash);
+    assert_matches!(
+        acquisition.apply_chunk(chunks[&0].clone()),
+        Ok(ChunkOutcome::NeededIt)
+    );
+    assert_matches!(
+        acquisition.apply_chunk(other_chunks[&1].clone()),
+        Err(Error::ChunksWithDifferentRootHash { .. })
+    );
+    assert_eq!(acquisition.next_chunk_index(), 1);
+
+    // After a reset, chunks of the other data are accepted.
+    acquisition.reset();
+    assert_matches!(
+        acquisition.apply_chunk(other_chunks[&1].clone()),
+        Ok(ChunkOutcome::NeededIt)
+    );
+    assert_matches!(
+        acquisition.apply_chunk(chunks[&0].clone()),
+        Err(Error::ChunksWithDifferentRootHash { .. })
+    );
+}
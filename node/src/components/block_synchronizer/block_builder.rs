@@ -17,6 +17,7 @@ use casper_types::{EraId, ProtocolVersion, PublicKey, TimeDiff, Timestamp};
 use super::{
     block_acquisition::{Acceptance, BlockAcquisitionState, RegisterExecResultsOutcome},
     block_acquisition_action::BlockAcquisitionAction,
+    block_body_acquisition::{self, BlockBodyAcquisition, ChunkOutcome},
     execution_results_acquisition::{self, ExecutionResultsChecksum},
     peer_list::{PeerList, PeersStatus},
     signature_acquisition::SignatureAcquisition,
@@ -25,9 +26,10 @@ use super::{
 use crate::{
     components::block_synchronizer::block_builder::latch::Latch,
     types::{
-        chainspec::LegacyRequiredFinality, ApprovalsHashes, Block, BlockExecutionResultsOrChunk,
-        BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployId, EraValidatorWeights,
-        FinalitySignature, FinalizedBlock, NodeId, ValidatorMatrix,
+        chainspec::LegacyRequiredFinality, ApprovalsHashes, Block, BlockBodyOrChunk,
+        BlockBodyOrChunkId, BlockExecutionResultsOrChunk, BlockHash, BlockHeader, BlockSignatures,
+        Deploy, DeployHash, DeployId, EraValidatorWeights, FinalitySignature, FinalizedBlock,
+        NodeId, ValidatorMatrix, ValueOrChunk,
     },
     NodeRng,
 };
@@ -35,6 +37,8 @@ use crate::{
 #[derive(Clone, Copy, PartialEq, Eq, DataSize, Debug)]
 pub(super) enum Error {
     BlockAcquisition(BlockAcquisitionError),
+    BlockBodyAcquisition(block_body_acquisition::Error),
+    InvalidBlockBody(BlockHash),
     MissingValidatorWeights(BlockHash),
 }
 
@@ -42,6 +46,10 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::BlockAcquisition(err) => write!(f, "block acquisition error: {}", err),
+            Error::BlockBodyAcquisition(err) => write!(f, "block body acquisition error: {}", err),
+            Error::InvalidBlockBody(block_hash) => {
+                write!(f, "block body not matching header of: {}", block_hash)
+            }
             Error::MissingValidatorWeights(block_hash) => {
                 write!(f, "missing validator weights for: {}", block_hash)
             }
@@ -77,6 +85,7 @@ pub(super) struct BlockBuilder {
     // imputed
     block_hash: BlockHash,
     should_fetch_execution_state: bool,
+    headers_only: bool,
     strict_finality_protocol_version: ProtocolVersion,
     peer_list: PeerList,
 
@@ -88,6 +97,7 @@ pub(super) struct BlockBuilder {
 
     // acquired state
     acquisition_state: BlockAcquisitionState,
+    block_body_acquisition: BlockBodyAcquisition,
     era_id: Option<EraId>,
     validator_weights: Option<EraValidatorWeights>,
 }
@@ -96,8 +106,9 @@ impl Display for BlockBuilder {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "is_historical: {:?}, has_validators: {:?}, block builder: {}",
+            "is_historical: {:?}, headers_only: {:?}, has_validators: {:?}, block builder: {}",
             self.should_fetch_execution_state,
+            self.headers_only,
             self.validator_weights.is_some(),
             self.acquisition_state
         )
@@ -121,8 +132,10 @@ impl BlockBuilder {
                 block_hash,
                 SignatureAcquisition::new(vec![], legacy_required_finality),
             ),
+            block_body_acquisition: BlockBodyAcquisition::new(block_hash),
             peer_list: PeerList::new(max_simultaneous_peers, peer_refresh_interval),
             should_fetch_execution_state,
+            headers_only: false,
             strict_finality_protocol_version,
             sync_start: Instant::now(),
             execution_progress: ExecutionProgress::Idle,
@@ -167,8 +180,10 @@ impl BlockBuilder {
             era_id,
             validator_weights: Some(validator_weights),
             acquisition_state,
+            block_body_acquisition: BlockBodyAcquisition::new(block_hash),
             peer_list,
             should_fetch_execution_state,
+            headers_only: false,
             strict_finality_protocol_version,
             sync_start: Instant::now(),
             execution_progress: ExecutionProgress::Idle,
@@ -218,6 +233,16 @@ impl BlockBuilder {
         self.should_fetch_execution_state
     }
 
+    /// Restricts the builder to acquiring only the block header and finality signatures of strict
+    /// finality weight for it; the block body, global state and deploys are never fetched.
+    pub(super) fn set_headers_only(&mut self) {
+        self.headers_only = true;
+    }
+
+    pub(super) fn is_headers_only(&self) -> bool {
+        self.headers_only
+    }
+
     pub(super) fn sync_start_time(&self) -> Instant {
         self.sync_start
     }
@@ -259,6 +284,9 @@ impl BlockBuilder {
     }
 
     pub(super) fn is_finished(&self) -> bool {
+        if self.headers_only {
+            return self.acquisition_state.has_header_with_strict_finality();
+        }
         match self.acquisition_state {
             BlockAcquisitionState::Initialized(_, _)
             | BlockAcquisitionState::HaveBlockHeader(_, _)
@@ -378,6 +406,16 @@ impl BlockBuilder {
         self.peer_list.disqualify_peer(peer);
     }
 
+    pub(super) fn register_peer_latency(
+        &mut self,
+        peer: NodeId,
+        latency: TimeDiff,
+        slow_peer_latency_percentile: u8,
+    ) -> bool {
+        self.peer_list
+            .register_latency(peer, latency, slow_peer_latency_percentile)
+    }
+
     pub(super) fn promote_peer(&mut self, peer: NodeId) {
         self.peer_list.promote_peer(peer);
     }
@@ -432,13 +470,23 @@ impl BlockBuilder {
                 validator_weights
             }
         };
-        match self.acquisition_state.next_action(
-            &self.peer_list,
-            validator_weights,
-            rng,
-            self.should_fetch_execution_state,
-            max_simultaneous_peers,
-        ) {
+        let next_action = if self.headers_only {
+            self.acquisition_state.next_header_only_action(
+                &self.peer_list,
+                validator_weights,
+                rng,
+                max_simultaneous_peers,
+            )
+        } else {
+            self.acquisition_state.next_action(
+                &self.peer_list,
+                validator_weights,
+                rng,
+                self.should_fetch_execution_state,
+                max_simultaneous_peers,
+            )
+        };
+        match next_action {
             Ok(ret) => ret,
             Err(err) => {
                 error!(%err, "BlockBuilder: attempt to determine next action resulted in error.");
@@ -525,6 +573,70 @@ impl BlockBuilder {
         self.handle_acceptance(maybe_peer, acceptance, was_waiting_for_block)
     }
 
+    /// Returns the ID to fetch the block body by: the first missing chunk if the body is being
+    /// fetched piecewise, otherwise the body as a whole.
+    pub(super) fn block_body_or_chunk_id(&self) -> BlockBodyOrChunkId {
+        BlockBodyOrChunkId::new(self.block_hash)
+            .next_chunk(self.block_body_acquisition.next_chunk_index())
+    }
+
+    pub(super) fn register_block_body_or_chunk(
+        &mut self,
+        block_body_or_chunk: BlockBodyOrChunk,
+        maybe_peer: Option<NodeId>,
+    ) -> Result<(), Error> {
+        let block_header = match &self.acquisition_state {
+            BlockAcquisitionState::HaveWeakFinalitySignatures(block_header, _) => {
+                block_header.clone()
+            }
+            // late response, the body is not needed (anymore)
+            _ => return Ok(()),
+        };
+        let (block_body, is_chunked) = match block_body_or_chunk.into_value() {
+            ValueOrChunk::Value(block_body) => (block_body, false),
+            ValueOrChunk::ChunkWithProof(chunk) => {
+                match self.block_body_acquisition.apply_chunk(chunk) {
+                    Ok(ChunkOutcome::HadIt) => return Ok(()),
+                    Ok(ChunkOutcome::NeededIt) => {
+                        self.touch();
+                        if let Some(peer) = maybe_peer {
+                            self.promote_peer(peer);
+                        }
+                        return Ok(());
+                    }
+                    Ok(ChunkOutcome::Complete(block_body)) => (*block_body, true),
+                    Err(error) => {
+                        // We can't tell whether this chunk or the ones applied before it are bad,
+                        // so the peer is only demoted.
+                        if let Some(peer) = maybe_peer {
+                            self.demote_peer(peer);
+                        }
+                        self.latch_decrement();
+                        return Err(Error::BlockBodyAcquisition(error));
+                    }
+                }
+            }
+        };
+        match Block::new_from_header_and_body(*block_header, block_body) {
+            Ok(block) => self.register_block(&block, maybe_peer),
+            Err(error) => {
+                debug!(%error, "BlockBuilder: block body does not match the block header");
+                // A body reassembled from chunks may have been spoiled by any of the peers which
+                // provided them, so start over and only demote the one providing the last chunk.
+                self.block_body_acquisition.reset();
+                if let Some(peer) = maybe_peer {
+                    if is_chunked {
+                        self.demote_peer(peer);
+                    } else {
+                        self.disqualify_peer(peer);
+                    }
+                }
+                self.latch_decrement();
+                Err(Error::InvalidBlockBody(self.block_hash))
+            }
+        }
+    }
+
     pub(super) fn waiting_for_approvals_hashes(&self) -> bool {
         match &self.acquisition_state {
             BlockAcquisitionState::HaveBlock(..) if !self.should_fetch_execution_state => true,
@@ -585,6 +697,14 @@ impl BlockBuilder {
     }
 
     pub(super) fn waiting_for_signatures(&self) -> bool {
+        if self.headers_only
+            && matches!(
+                self.acquisition_state,
+                BlockAcquisitionState::HaveWeakFinalitySignatures(..)
+            )
+        {
+            return !self.is_finished();
+        }
         self.acquisition_state
             .actively_acquiring_signatures(self.should_fetch_execution_state)
     }
@@ -604,6 +724,10 @@ impl BlockBuilder {
             validator_weights,
             self.should_fetch_execution_state,
         );
+        if self.headers_only && was_waiting_for_sigs && self.is_finished() {
+            // a headers only builder finishes without a further state transition
+            self.touch();
+        }
         self.handle_acceptance(maybe_peer, acceptance, was_waiting_for_sigs)
     }
 
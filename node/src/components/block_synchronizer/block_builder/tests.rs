@@ -1,9 +1,23 @@
-use std::{collections::BTreeMap, thread, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter, thread,
+    time::Duration,
+};
 
-use casper_types::testing::TestRng;
+use casper_hashing::ChunkWithProof;
+use casper_types::{bytesrepr::ToBytes, testing::TestRng};
 use num_rational::Ratio;
+use rand::Rng;
 
-use crate::components::consensus::tests::utils::{ALICE_PUBLIC_KEY, ALICE_SECRET_KEY};
+use crate::{
+    components::{
+        block_synchronizer::{need_next::NeedNext, tests::test_utils::chunks_with_proof_from_data},
+        consensus::tests::utils::{
+            ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY, BOB_SECRET_KEY,
+        },
+    },
+    types::{BlockPayload, DeployHashWithApprovals},
+};
 
 use super::*;
 
@@ -501,3 +515,199 @@ fn register_block_marked_complete() {
     assert!(builder.is_failed());
     assert_ne!(latest_timestamp, builder.last_progress);
 }
+
+#[test]
+fn headers_only_builder_finishes_on_strict_finality() {
+    let mut rng = TestRng::new();
+    // Create a random block.
+    let block = Block::random(&mut rng);
+    // Create a historical builder for the block which only acquires its header.
+    let mut builder = BlockBuilder::new(
+        block.header().block_hash(),
+        true,
+        1,
+        TimeDiff::from_seconds(1),
+        LegacyRequiredFinality::Strict,
+        ProtocolVersion::V1_0_0,
+    );
+    builder.set_headers_only();
+    builder.register_peers(vec![NodeId::random(&mut rng)]);
+    // Create mock era weights for the block's era where Alice alone has weak finality.
+    let weights = EraValidatorWeights::new(
+        block.header().era_id(),
+        BTreeMap::from([
+            (ALICE_PUBLIC_KEY.clone(), 40.into()),
+            (BOB_PUBLIC_KEY.clone(), 60.into()),
+        ]),
+        Ratio::new(1, 3),
+    );
+    builder.era_id = Some(block.header().era_id());
+    builder.validator_weights = Some(weights.clone());
+    // Create a signature acquisition with weak finality.
+    let mut signature_acquisition = SignatureAcquisition::new(
+        vec![ALICE_PUBLIC_KEY.clone(), BOB_PUBLIC_KEY.clone()],
+        LegacyRequiredFinality::Strict,
+    );
+    let alice_sig = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &ALICE_SECRET_KEY,
+        ALICE_PUBLIC_KEY.clone(),
+    );
+    assert_eq!(
+        signature_acquisition.apply_signature(alice_sig, &weights),
+        Acceptance::NeededIt
+    );
+    // Set the builder state to `HaveWeakFinalitySignatures`.
+    builder.acquisition_state = BlockAcquisitionState::HaveWeakFinalitySignatures(
+        Box::new(block.header().clone()),
+        signature_acquisition,
+    );
+
+    // The builder should ask for more signatures rather than the block body.
+    assert!(!builder.is_finished());
+    assert!(builder.waiting_for_signatures());
+    let action = builder.block_acquisition_action(&mut rng, 1);
+    assert!(matches!(
+        action.need_next(),
+        NeedNext::FinalitySignatures(..)
+    ));
+
+    // Bob's signature brings the builder to strict finality, finishing it.
+    let bob_sig = FinalitySignature::create(
+        *block.hash(),
+        block.header().era_id(),
+        &BOB_SECRET_KEY,
+        BOB_PUBLIC_KEY.clone(),
+    );
+    assert!(builder.register_finality_signature(bob_sig, None).is_ok());
+    assert!(builder.is_finished());
+    assert!(!builder.is_failed());
+    assert!(!builder.waiting_for_signatures());
+    assert!(matches!(
+        builder.block_acquisition_action(&mut rng, 1).need_next(),
+        NeedNext::Nothing(..)
+    ));
+}
+
+#[test]
+fn register_block_body_chunks() {
+    let mut rng = TestRng::new();
+    // Create a block with a body too large to be sent in a single message.
+    let deploys = iter::repeat_with(|| {
+        DeployHashWithApprovals::new(DeployHash::random(&mut rng), BTreeSet::new())
+    })
+    .take(ChunkWithProof::CHUNK_SIZE_BYTES * 3 / 2 / Digest::LENGTH)
+    .collect();
+    let finalized_block = FinalizedBlock::new(
+        BlockPayload::new(deploys, vec![], vec![], false),
+        None,
+        Timestamp::now(),
+        EraId::from(1),
+        1,
+        ALICE_PUBLIC_KEY.clone(),
+    );
+    let block = Block::new(
+        BlockHash::random(&mut rng),
+        rng.gen(),
+        rng.gen(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .unwrap();
+    let chunks = chunks_with_proof_from_data(&block.body().to_bytes().unwrap());
+    assert_eq!(chunks.len(), 2);
+    // Create a builder for the block and set its state to `HaveWeakFinalitySignatures`.
+    let mut builder = BlockBuilder::new(
+        block.header().block_hash(),
+        false,
+        1,
+        TimeDiff::from_seconds(1),
+        LegacyRequiredFinality::Strict,
+        ProtocolVersion::V1_0_0,
+    );
+    let peer = NodeId::random(&mut rng);
+    builder.register_peers(vec![peer]);
+    builder.acquisition_state = BlockAcquisitionState::HaveWeakFinalitySignatures(
+        Box::new(block.header().clone()),
+        SignatureAcquisition::new(
+            vec![ALICE_PUBLIC_KEY.clone()],
+            LegacyRequiredFinality::Strict,
+        ),
+    );
+    let body_or_chunk = |index: u64| {
+        BlockBodyOrChunkId::new(*block.hash())
+            .next_chunk(index)
+            .response(ValueOrChunk::ChunkWithProof(chunks[&index].clone()))
+    };
+
+    // The first chunk is needed, but the body is still incomplete.
+    assert_eq!(builder.block_body_or_chunk_id().chunk_index(), 0);
+    assert!(builder
+        .register_block_body_or_chunk(body_or_chunk(0), Some(peer))
+        .is_ok());
+    assert!(matches!(
+        builder.acquisition_state,
+        BlockAcquisitionState::HaveWeakFinalitySignatures(..)
+    ));
+    assert_eq!(builder.block_body_or_chunk_id().chunk_index(), 1);
+    // A duplicate of the first chunk changes nothing.
+    assert!(builder
+        .register_block_body_or_chunk(body_or_chunk(0), Some(peer))
+        .is_ok());
+    assert_eq!(builder.block_body_or_chunk_id().chunk_index(), 1);
+
+    // The last chunk completes the body, so the builder has the whole block.
+    assert!(builder
+        .register_block_body_or_chunk(body_or_chunk(1), Some(peer))
+        .is_ok());
+    match &builder.acquisition_state {
+        BlockAcquisitionState::HaveBlock(actual_block, _, _) => {
+            assert_eq!(**actual_block, block);
+        }
+        _ => panic!("Unexpected outcome in registering block body chunks"),
+    }
+    assert!(builder.peer_list().dishonest_peers().is_empty());
+}
+
+#[test]
+fn register_block_body_not_matching_header() {
+    let mut rng = TestRng::new();
+    let block = Block::random(&mut rng);
+    let other_block = Block::random(&mut rng);
+    // Create a builder for the block and set its state to `HaveWeakFinalitySignatures`.
+    let mut builder = BlockBuilder::new(
+        block.header().block_hash(),
+        false,
+        1,
+        TimeDiff::from_seconds(1),
+        LegacyRequiredFinality::Strict,
+        ProtocolVersion::V1_0_0,
+    );
+    let dishonest_peer = NodeId::random(&mut rng);
+    builder.register_peers(vec![dishonest_peer]);
+    builder.acquisition_state = BlockAcquisitionState::HaveWeakFinalitySignatures(
+        Box::new(block.header().clone()),
+        SignatureAcquisition::new(
+            vec![ALICE_PUBLIC_KEY.clone()],
+            LegacyRequiredFinality::Strict,
+        ),
+    );
+
+    // A whole body not matching the header gets its sender disqualified.
+    let body_or_chunk = BlockBodyOrChunkId::new(*block.hash())
+        .response(ValueOrChunk::Value(other_block.body().clone()));
+    assert_eq!(
+        builder.register_block_body_or_chunk(body_or_chunk, Some(dishonest_peer)),
+        Err(Error::InvalidBlockBody(*block.hash()))
+    );
+    assert!(matches!(
+        builder.acquisition_state,
+        BlockAcquisitionState::HaveWeakFinalitySignatures(..)
+    ));
+    assert!(builder
+        .peer_list()
+        .dishonest_peers()
+        .contains(&dishonest_peer));
+}
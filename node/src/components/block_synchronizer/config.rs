@@ -6,16 +6,23 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_MAX_PARALLEL_TRIE_FETCHES: u32 = 5000;
+const DEFAULT_MAX_PARALLEL_CHUNK_FETCHES: u32 = 10;
+const DEFAULT_MAX_IN_FLIGHT_FETCHES_PER_PEER: u32 = 50;
 const DEFAULT_PEER_REFRESH_INTERVAL: &str = "90sec";
 const DEFAULT_NEED_NEXT_INTERVAL: &str = "1sec";
 const DEFAULT_DISCONNECT_DISHONEST_PEERS_INTERVAL: &str = "10sec";
 const DEFAULT_LATCH_RESET_INTERVAL: &str = "5sec";
+const DEFAULT_SLOW_PEER_LATENCY_PERCENTILE: u8 = 90;
 
 /// Configuration options for fetching.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     /// Maximum number of trie nodes to fetch in parallel.
     pub max_parallel_trie_fetches: u32,
+    /// Maximum number of chunks of a single trie to fetch in parallel.
+    pub max_parallel_chunk_fetches: u32,
+    /// Maximum number of trie or chunk fetches in flight to any single peer.
+    pub max_in_flight_fetches_per_peer: u32,
     /// Time interval for the node to ask for refreshed peers.
     pub peer_refresh_interval: TimeDiff,
     /// Time interval for the node to check what the block synchronizer needs to acquire next.
@@ -24,12 +31,17 @@ pub struct Config {
     pub disconnect_dishonest_peers_interval: TimeDiff,
     /// Time interval for resetting the latch in block builders.
     pub latch_reset_interval: TimeDiff,
+    /// Percentile of recent peer response latencies; peers responding consistently slower than
+    /// this are not asked again for the remainder of a block's sync.  100 disables rotation.
+    pub slow_peer_latency_percentile: u8,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             max_parallel_trie_fetches: DEFAULT_MAX_PARALLEL_TRIE_FETCHES,
+            max_parallel_chunk_fetches: DEFAULT_MAX_PARALLEL_CHUNK_FETCHES,
+            max_in_flight_fetches_per_peer: DEFAULT_MAX_IN_FLIGHT_FETCHES_PER_PEER,
             peer_refresh_interval: TimeDiff::from_str(DEFAULT_PEER_REFRESH_INTERVAL).unwrap(),
             need_next_interval: TimeDiff::from_str(DEFAULT_NEED_NEXT_INTERVAL).unwrap(),
             disconnect_dishonest_peers_interval: TimeDiff::from_str(
@@ -37,6 +49,7 @@ impl Default for Config {
             )
             .unwrap(),
             latch_reset_interval: TimeDiff::from_str(DEFAULT_LATCH_RESET_INTERVAL).unwrap(),
+            slow_peer_latency_percentile: DEFAULT_SLOW_PEER_LATENCY_PERCENTILE,
         }
     }
 }
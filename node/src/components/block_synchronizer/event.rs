@@ -1,4 +1,7 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
 
 use casper_hashing::Digest;
 use derive_more::From;
@@ -6,6 +9,7 @@ use either::Either;
 use serde::Serialize;
 
 use casper_execution_engine::core::engine_state;
+use casper_types::{EraId, PublicKey, TimeDiff, U512};
 
 use super::GlobalStateSynchronizerEvent;
 use crate::{
@@ -15,8 +19,8 @@ use crate::{
     },
     effect::requests::BlockSynchronizerRequest,
     types::{
-        ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHash, BlockHeader, Deploy,
-        FinalitySignature, FinalizedBlock, LegacyDeploy, NodeId, SyncLeap,
+        ApprovalsHashes, BlockBodyOrChunk, BlockExecutionResultsOrChunk, BlockHash, BlockHeader,
+        Deploy, FinalitySignature, FinalizedBlock, LegacyDeploy, NodeId, SyncLeap,
     },
 };
 
@@ -40,13 +44,19 @@ pub(crate) enum Event {
     #[from]
     BlockHeaderFetched(FetchResult<BlockHeader>),
     #[from]
-    BlockFetched(FetchResult<Block>),
+    BlockBodyOrChunkFetched(FetchResult<BlockBodyOrChunk>),
     #[from]
     ApprovalsHashesFetched(FetchResult<ApprovalsHashes>),
     #[from]
     FinalitySignatureFetched(FetchResult<FinalitySignature>),
     #[from]
     SyncLeapFetched(FetchResult<SyncLeap>),
+    GotStoredEraValidators {
+        block_hash: BlockHash,
+        era_id: EraId,
+        maybe_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+        peers: Vec<NodeId>,
+    },
     GlobalStateSynced {
         block_hash: BlockHash,
         #[serde(skip_serializing)]
@@ -67,6 +77,11 @@ pub(crate) enum Event {
     },
     ExecutionResultsStored(BlockHash),
     AccumulatedPeers(BlockHash, Option<Vec<NodeId>>),
+    PeerResponded {
+        block_hash: BlockHash,
+        peer: NodeId,
+        latency: TimeDiff,
+    },
     NetworkPeers(BlockHash, Vec<NodeId>),
     #[from]
     GlobalStateSynchronizer(GlobalStateSynchronizerEvent),
@@ -104,10 +119,10 @@ impl Display for Event {
             Event::BlockHeaderFetched(Err(fetcher_error)) => {
                 write!(f, "{}", fetcher_error)
             }
-            Event::BlockFetched(Ok(fetched_item)) => {
+            Event::BlockBodyOrChunkFetched(Ok(fetched_item)) => {
                 write!(f, "{}", fetched_item)
             }
-            Event::BlockFetched(Err(fetcher_error)) => {
+            Event::BlockBodyOrChunkFetched(Err(fetcher_error)) => {
                 write!(f, "{}", fetcher_error)
             }
             Event::ApprovalsHashesFetched(Ok(fetched_item)) => {
@@ -128,6 +143,14 @@ impl Display for Event {
             Event::SyncLeapFetched(Err(fetcher_error)) => {
                 write!(f, "{}", fetcher_error)
             }
+            Event::GotStoredEraValidators {
+                era_id,
+                maybe_validator_weights,
+                ..
+            } => match maybe_validator_weights {
+                Some(_) => write!(f, "got stored validators for era {}", era_id),
+                None => write!(f, "got no stored validators for era {}", era_id),
+            },
             Event::GlobalStateSynced {
                 block_hash: _,
                 result,
@@ -169,6 +192,9 @@ impl Display for Event {
             Event::AccumulatedPeers(..) => {
                 write!(f, "accumulated peers")
             }
+            Event::PeerResponded { peer, latency, .. } => {
+                write!(f, "peer {} responded in {}", peer, latency)
+            }
             Event::MadeFinalizedBlock { .. } => {
                 write!(f, "made finalized block")
             }
@@ -8,6 +8,7 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
+use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
 use tracing::{debug, error, warn};
@@ -22,7 +23,8 @@ use crate::{
     effect::{
         announcements::PeerBehaviorAnnouncement,
         requests::{
-            ContractRuntimeRequest, FetcherRequest, SyncGlobalStateRequest, TrieAccumulatorRequest,
+            ContractRuntimeRequest, FetcherRequest, StorageRequest, SyncGlobalStateRequest,
+            TrieAccumulatorRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
@@ -233,16 +235,25 @@ pub(super) struct GlobalStateSynchronizer {
 }
 
 impl GlobalStateSynchronizer {
-    pub(super) fn new(max_parallel_trie_fetches: usize) -> Self {
-        Self {
+    pub(super) fn new(
+        max_parallel_trie_fetches: usize,
+        max_parallel_chunk_fetches: usize,
+        max_in_flight_fetches_per_peer: usize,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(Self {
             max_parallel_trie_fetches,
-            trie_accumulator: TrieAccumulator::new(),
+            trie_accumulator: TrieAccumulator::new(
+                max_parallel_chunk_fetches,
+                max_in_flight_fetches_per_peer,
+                registry,
+            )?,
             request_state: None,
             tries_awaiting_children: Default::default(),
             fetch_queue: Default::default(),
             in_flight: Default::default(),
             last_progress: None,
-        }
+        })
     }
 
     fn touch(&mut self) {
@@ -628,6 +639,7 @@ where
         + From<ContractRuntimeRequest>
         + From<FetcherRequest<TrieOrChunk>>
         + From<PeerBehaviorAnnouncement>
+        + From<StorageRequest>
         + Send,
 {
     type Event = Event;
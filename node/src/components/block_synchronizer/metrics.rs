@@ -1,4 +1,4 @@
-use prometheus::{Histogram, Registry};
+use prometheus::{Histogram, IntCounter, Registry};
 
 use crate::{unregister_metric, utils};
 
@@ -6,6 +6,9 @@ const HIST_SYNC_DURATION_NAME: &str = "historical_block_sync_duration_seconds";
 const HIST_SYNC_DURATION_HELP: &str = "duration (in sec) to synchronize a historical block";
 const FWD_SYNC_DURATION_NAME: &str = "forward_block_sync_duration_seconds";
 const FWD_SYNC_DURATION_HELP: &str = "duration (in sec) to synchronize a forward block";
+const PEER_LATENCY_NAME: &str = "block_synchronizer_peer_latency_seconds";
+const PEER_LATENCY_HELP: &str =
+    "duration (in sec) for a peer to respond to a block synchronizer request";
 
 // We use exponential buckets to observe the time it takes to synchronize blocks.
 // Coverage is ~7.7s with higher resolution in the first buckets.
@@ -20,6 +23,10 @@ pub(super) struct Metrics {
     pub(super) historical_block_sync_duration: Histogram,
     /// Time duration for the forward synchronizer to get a block.
     pub(super) forward_block_sync_duration: Histogram,
+    /// Time taken by peers to respond to requests.
+    pub(super) peer_latency: Histogram,
+    /// Number of peers rotated out of a block's sync for responding too slowly.
+    pub(super) slow_peer_rotations: IntCounter,
     registry: Registry,
 }
 
@@ -32,6 +39,12 @@ impl Metrics {
             EXPONENTIAL_BUCKET_COUNT,
         )?;
 
+        let slow_peer_rotations = IntCounter::new(
+            "block_synchronizer_slow_peer_rotations",
+            "number of peers rotated out of a block's sync for responding too slowly",
+        )?;
+        registry.register(Box::new(slow_peer_rotations.clone()))?;
+
         Ok(Metrics {
             historical_block_sync_duration: utils::register_histogram_metric(
                 registry,
@@ -43,8 +56,15 @@ impl Metrics {
                 registry,
                 FWD_SYNC_DURATION_NAME,
                 FWD_SYNC_DURATION_HELP,
+                buckets.clone(),
+            )?,
+            peer_latency: utils::register_histogram_metric(
+                registry,
+                PEER_LATENCY_NAME,
+                PEER_LATENCY_HELP,
                 buckets,
             )?,
+            slow_peer_rotations,
             registry: registry.clone(),
         })
     }
@@ -54,5 +74,7 @@ impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.historical_block_sync_duration);
         unregister_metric!(self.registry, self.forward_block_sync_duration);
+        unregister_metric!(self.registry, self.peer_latency);
+        unregister_metric!(self.registry, self.slow_peer_rotations);
     }
 }
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests;
 
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque};
 
 use datasize::DataSize;
 use itertools::Itertools;
@@ -11,6 +11,13 @@ use tracing::debug;
 use crate::{types::NodeId, NodeRng};
 use casper_types::{TimeDiff, Timestamp};
 
+/// Number of most recent response latencies kept per peer.
+const LATENCY_SAMPLES_PER_PEER: usize = 10;
+/// Number of consecutive slow responses after which a peer is rotated out.
+const SLOW_RESPONSES_BEFORE_ROTATION: usize = 3;
+/// Minimum number of peers with recorded latencies before any of them can be deemed slow.
+const MIN_PEERS_FOR_LATENCY_COMPARISON: usize = 3;
+
 #[derive(Copy, Clone, PartialEq, Eq, DataSize, Debug, Default)]
 enum PeerQuality {
     #[default]
@@ -32,6 +39,10 @@ pub(super) struct PeerList {
     keep_fresh: Timestamp,
     max_simultaneous_peers: u8,
     peer_refresh_interval: TimeDiff,
+    /// Most recent response latencies of each peer.
+    latencies: BTreeMap<NodeId, VecDeque<TimeDiff>>,
+    /// Peers which responded consistently slowly, and are not asked again.
+    rotated_out: BTreeSet<NodeId>,
 }
 
 impl PeerList {
@@ -41,8 +52,11 @@ impl PeerList {
             keep_fresh: Timestamp::now(),
             max_simultaneous_peers,
             peer_refresh_interval,
+            latencies: BTreeMap::new(),
+            rotated_out: BTreeSet::new(),
         }
     }
+
     pub(super) fn register_peer(&mut self, peer: NodeId) {
         if self.peer_list.contains_key(&peer) {
             return;
@@ -66,10 +80,82 @@ impl PeerList {
 
     pub(super) fn flush(&mut self) {
         self.peer_list.clear();
+        self.latencies.clear();
+        self.rotated_out.clear();
     }
 
     pub(super) fn flush_dishonest_peers(&mut self) {
         self.peer_list.retain(|_, v| *v != PeerQuality::Dishonest);
+        let peer_list = &self.peer_list;
+        self.latencies
+            .retain(|peer, _| peer_list.contains_key(peer));
+    }
+
+    /// Records how long `peer` took to respond to a request.
+    ///
+    /// If the peer's last few responses were all slower than the given percentile of the recent
+    /// latencies of all peers, it is rotated out: it won't be asked again for the remainder of
+    /// this block's sync.  Returns `true` if the peer was rotated out.
+    pub(super) fn register_latency(
+        &mut self,
+        peer: NodeId,
+        latency: TimeDiff,
+        slow_percentile: u8,
+    ) -> bool {
+        if !self.peer_list.contains_key(&peer) || self.rotated_out.contains(&peer) {
+            return false;
+        }
+        let samples = self.latencies.entry(peer).or_default();
+        samples.push_back(latency);
+        if samples.len() > LATENCY_SAMPLES_PER_PEER {
+            samples.pop_front();
+        }
+
+        if self.latencies.len() < MIN_PEERS_FOR_LATENCY_COMPARISON {
+            return false;
+        }
+        let threshold = match self.latency_percentile(slow_percentile) {
+            Some(threshold) => threshold,
+            None => return false,
+        };
+        let consistently_slow = self.latencies.get(&peer).map_or(false, |samples| {
+            samples.len() >= SLOW_RESPONSES_BEFORE_ROTATION
+                && samples
+                    .iter()
+                    .rev()
+                    .take(SLOW_RESPONSES_BEFORE_ROTATION)
+                    .all(|sample| *sample > threshold)
+        });
+        if !consistently_slow {
+            return false;
+        }
+
+        // never rotate out the last usable peer
+        let other_usable_peers = self
+            .peer_list
+            .iter()
+            .filter(|(node_id, pq)| {
+                **node_id != peer
+                    && **pq != PeerQuality::Dishonest
+                    && !self.rotated_out.contains(node_id)
+            })
+            .count();
+        if other_usable_peers == 0 {
+            return false;
+        }
+        debug!(%peer, %threshold, "PeerList: rotating out slow peer");
+        self.rotated_out.insert(peer)
+    }
+
+    /// Returns the given percentile of the recent latencies of all peers.
+    fn latency_percentile(&self, percentile: u8) -> Option<TimeDiff> {
+        let mut latencies = self.latencies.values().flatten().copied().collect_vec();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort_unstable();
+        let rank = (latencies.len() * percentile.min(100) as usize + 99) / 100;
+        latencies.get(rank.saturating_sub(1)).copied()
     }
 
     pub(super) fn disqualify_peer(&mut self, peer: NodeId) {
@@ -131,7 +217,10 @@ impl PeerList {
             let count = self
                 .peer_list
                 .iter()
-                .filter(|(_, pq)| **pq == PeerQuality::Reliable || **pq == PeerQuality::Unknown)
+                .filter(|(peer, pq)| {
+                    (**pq == PeerQuality::Reliable || **pq == PeerQuality::Unknown)
+                        && !self.rotated_out.contains(peer)
+                })
                 .count();
             let reliability_goal = self.max_simultaneous_peers as usize;
             if count < reliability_goal {
@@ -151,7 +240,7 @@ impl PeerList {
     ) -> Vec<NodeId> {
         self.peer_list
             .iter()
-            .filter(|(_peer, quality)| **quality == peer_quality)
+            .filter(|(peer, quality)| **quality == peer_quality && !self.rotated_out.contains(peer))
             .choose_multiple(rng, up_to)
             .into_iter()
             .map(|(peer, _)| *peer)
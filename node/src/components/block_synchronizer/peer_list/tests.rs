@@ -130,3 +130,52 @@ fn unreliable_peer_remains_unreliable_if_demoted() {
     peer_list.demote_peer(test_peer);
     assert!(peer_list.is_peer_unreliable(&test_peer));
 }
+
+#[test]
+fn consistently_slow_peer_is_rotated_out() {
+    let mut rng = TestRng::new();
+    let mut peer_list = PeerList::new(5, TimeDiff::from_seconds(1));
+
+    let test_peers: Vec<NodeId> = random_peers(&mut rng, 4).into_iter().collect();
+    for peer in test_peers.iter() {
+        peer_list.register_peer(*peer);
+    }
+    let (slow_peer, fast_peers) = test_peers.split_last().unwrap();
+
+    for _ in 0..LATENCY_SAMPLES_PER_PEER {
+        for peer in fast_peers {
+            assert!(!peer_list.register_latency(*peer, TimeDiff::from_millis(100), 90));
+        }
+    }
+
+    // A single slow response is not enough to rotate the peer out.
+    for _ in 1..SLOW_RESPONSES_BEFORE_ROTATION {
+        assert!(!peer_list.register_latency(*slow_peer, TimeDiff::from_seconds(1), 90));
+    }
+    assert!(peer_list.register_latency(*slow_peer, TimeDiff::from_seconds(1), 90));
+
+    // The rotated out peer should not be offered again.
+    let qualified_peers = peer_list.qualified_peers(&mut rng);
+    assert_eq!(qualified_peers.len(), 3);
+    assert!(!qualified_peers.contains(slow_peer));
+}
+
+#[test]
+fn slow_peer_is_not_rotated_out_at_100th_percentile() {
+    let mut rng = TestRng::new();
+    let mut peer_list = PeerList::new(5, TimeDiff::from_seconds(1));
+
+    let test_peers: Vec<NodeId> = random_peers(&mut rng, 4).into_iter().collect();
+    for peer in test_peers.iter() {
+        peer_list.register_peer(*peer);
+    }
+    let (slow_peer, fast_peers) = test_peers.split_last().unwrap();
+
+    for _ in 0..LATENCY_SAMPLES_PER_PEER {
+        for peer in fast_peers {
+            peer_list.register_latency(*peer, TimeDiff::from_millis(100), 100);
+        }
+        assert!(!peer_list.register_latency(*slow_peer, TimeDiff::from_seconds(1), 100));
+    }
+    assert_eq!(peer_list.qualified_peers(&mut rng).len(), 4);
+}
@@ -29,8 +29,8 @@ use crate::{
     reactor::{EventQueueHandle, QueueKind, Scheduler},
     tls::KeyFingerprint,
     types::{
-        chainspec::LegacyRequiredFinality, BlockExecutionResultsOrChunkId, DeployId,
-        TestBlockBuilder, ValueOrChunk,
+        chainspec::LegacyRequiredFinality, Block, BlockBodyOrChunkId,
+        BlockExecutionResultsOrChunkId, DeployId, TestBlockBuilder, ValueOrChunk,
     },
     utils,
 };
@@ -44,7 +44,7 @@ const STRICT_FINALITY_REQUIRED_VERSION: ProtocolVersion = ProtocolVersion::from_
 #[derive(Debug, From)]
 enum MockReactorEvent {
     MarkBlockCompletedRequest(MarkBlockCompletedRequest),
-    BlockFetcherRequest(FetcherRequest<Block>),
+    BlockBodyOrChunkFetcherRequest(FetcherRequest<BlockBodyOrChunk>),
     BlockHeaderFetcherRequest(FetcherRequest<BlockHeader>),
     LegacyDeployFetcherRequest(FetcherRequest<LegacyDeploy>),
     DeployFetcherRequest(FetcherRequest<Deploy>),
@@ -151,6 +151,7 @@ impl TestEnv {
             self.validator_keys[0].clone(),
             PublicKey::from(self.validator_keys[0].as_ref()),
             1,
+            6,
         );
         validator_matrix
             .register_validator_weights(self.block.header().era_id(), validator_weights);
@@ -642,8 +643,8 @@ async fn should_not_stall_after_registering_new_era_validator_weights() {
     let effects = block_synchronizer.need_next(mock_reactor.effect_builder(), &mut rng);
     assert_eq!(
         effects.len(),
-        MAX_SIMULTANEOUS_PEERS as usize,
-        "need next should have an effect per peer when needing sync leap"
+        1,
+        "need next should first look for the era validators in storage"
     );
     latch_inner_check(
         block_synchronizer.historical.as_ref(),
@@ -659,6 +660,43 @@ async fn should_not_stall_after_registering_new_era_validator_weights() {
         "should return no effects while latched"
     );
 
+    // The era validators can't be reconstructed from storage.
+    let mut events = mock_reactor.process_effects(effects).await;
+    match events.pop().unwrap() {
+        MockReactorEvent::StorageRequest(StorageRequest::GetEraValidatorWeights {
+            era_id: actual_era_id,
+            responder,
+        }) => {
+            assert_eq!(actual_era_id, era_id);
+            responder.respond(None).await;
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+    let effects = block_synchronizer.handle_event(
+        mock_reactor.effect_builder(),
+        &mut rng,
+        Event::GotStoredEraValidators {
+            block_hash,
+            era_id,
+            maybe_validator_weights: None,
+            peers: peers
+                .iter()
+                .copied()
+                .take(MAX_SIMULTANEOUS_PEERS as usize)
+                .collect(),
+        },
+    );
+    assert_eq!(
+        effects.len(),
+        MAX_SIMULTANEOUS_PEERS as usize,
+        "should have an effect per peer when needing sync leap"
+    );
+    latch_inner_check(
+        block_synchronizer.historical.as_ref(),
+        true,
+        "while acquiring validators from peers, should be latched",
+    );
+
     // bleed off the event q, checking the expected event kind
     for effect in effects {
         tokio::spawn(async move { effect.await });
@@ -743,6 +781,75 @@ fn duplicate_register_block_not_allowed_if_builder_is_not_failed() {
     );
 }
 
+#[tokio::test]
+async fn should_use_era_validator_weights_reconstructed_from_storage() {
+    let mut rng = TestRng::new();
+    let mock_reactor = MockReactor::new();
+    let test_env = TestEnv::random(&mut rng);
+    let peers = test_env.peers();
+    let block = test_env.block();
+    let block_hash = *block.hash();
+    let era_id = block.header().era_id();
+
+    // Set up a validator matrix.
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let mut block_synchronizer =
+        BlockSynchronizer::new_initialized(&mut rng, validator_matrix.clone(), Config::default());
+
+    // Set up the synchronizer for the test block such that the next step is getting era validators.
+    block_synchronizer.register_block_by_hash(block_hash, true);
+    block_synchronizer.register_peers(block_hash, peers.clone());
+    block_synchronizer
+        .historical
+        .as_mut()
+        .expect("should have historical builder")
+        .register_block_header(block.header().clone(), None)
+        .expect("should register block header");
+    need_next_inner_check(
+        block_synchronizer.historical.as_mut(),
+        &mut rng,
+        NeedNext::EraValidators(era_id),
+        "should need era validators for era block is in",
+    );
+
+    // The era validators can be reconstructed from storage.
+    let validator_weights: BTreeMap<PublicKey, U512> =
+        iter::once((ALICE_PUBLIC_KEY.clone(), 100.into())).collect();
+    let effects = block_synchronizer.need_next(mock_reactor.effect_builder(), &mut rng);
+    let mut events = mock_reactor.process_effects(effects).await;
+    match events.pop().unwrap() {
+        MockReactorEvent::StorageRequest(StorageRequest::GetEraValidatorWeights {
+            era_id: actual_era_id,
+            responder,
+        }) => {
+            assert_eq!(actual_era_id, era_id);
+            responder.respond(Some(validator_weights.clone())).await;
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+    let effects = block_synchronizer.handle_event(
+        mock_reactor.effect_builder(),
+        &mut rng,
+        Event::GotStoredEraValidators {
+            block_hash,
+            era_id,
+            maybe_validator_weights: Some(validator_weights),
+            peers: peers
+                .iter()
+                .copied()
+                .take(MAX_SIMULTANEOUS_PEERS as usize)
+                .collect(),
+        },
+    );
+    assert!(validator_matrix.has_era(&era_id));
+
+    // No sync leap is needed, so the synchronizer moves on to the finality signatures.
+    let events = mock_reactor.process_effects(effects).await;
+    assert!(!events.is_empty());
+    for event in events {
+        assert_matches!(event, MockReactorEvent::FinalitySignatureFetcherRequest(_));
+    }
+}
 #[tokio::test]
 async fn historical_sync_gets_peers_form_both_connected_peers_and_accumulator() {
     let mut rng = TestRng::new();
@@ -1183,13 +1290,13 @@ async fn fwd_more_signatures_are_requested_if_weak_finality_is_not_reached() {
     for event in events {
         assert_matches!(
             event,
-            MockReactorEvent::BlockFetcherRequest(FetcherRequest {
+            MockReactorEvent::BlockBodyOrChunkFetcherRequest(FetcherRequest {
                 id,
                 peer,
                 ..
             }) => {
                 assert!(peers.contains(&peer));
-                assert_eq!(id, *block.hash());
+                assert_eq!(id, BlockBodyOrChunkId::new(*block.hash()));
             }
         );
     }
@@ -1382,13 +1489,13 @@ async fn next_action_for_have_weak_finality_is_fetching_block_body() {
     for event in events {
         assert_matches!(
             event,
-            MockReactorEvent::BlockFetcherRequest(FetcherRequest {
+            MockReactorEvent::BlockBodyOrChunkFetcherRequest(FetcherRequest {
                 id,
                 peer,
                 ..
             }) => {
                 assert!(peers.contains(&peer));
-                assert_eq!(id, *block.hash());
+                assert_eq!(id, BlockBodyOrChunkId::new(*block.hash()));
             }
         );
     }
@@ -1448,13 +1555,13 @@ async fn registering_block_body_transitions_builder_to_have_block_state() {
     for event in events {
         assert_matches!(
             event,
-            MockReactorEvent::BlockFetcherRequest(FetcherRequest {
+            MockReactorEvent::BlockBodyOrChunkFetcherRequest(FetcherRequest {
                 id,
                 peer,
                 ..
             }) => {
                 assert!(peers.contains(&peer));
-                assert_eq!(id, *block.hash());
+                assert_eq!(id, BlockBodyOrChunkId::new(*block.hash()));
             }
         );
     }
@@ -1462,8 +1569,8 @@ async fn registering_block_body_transitions_builder_to_have_block_state() {
     block_synchronizer.handle_event(
         mock_reactor.effect_builder(),
         &mut rng,
-        Event::BlockFetched(Ok(FetchedData::FromPeer {
-            item: Box::new(block.clone()),
+        Event::BlockBodyOrChunkFetched(Ok(FetchedData::FromPeer {
+            item: Box::new(BlockBodyOrChunk::new_value(&block)),
             peer: peers[0],
         })),
     );
@@ -3406,13 +3513,13 @@ async fn fwd_sync_latch_should_not_decrement_for_old_responses() {
         for event in events {
             assert_matches!(
                 event,
-                MockReactorEvent::BlockFetcherRequest(FetcherRequest {
+                MockReactorEvent::BlockBodyOrChunkFetcherRequest(FetcherRequest {
                     id,
                     peer,
                     ..
                 }) => {
                     assert!(peers.contains(&peer));
-                    assert_eq!(id, *block.hash());
+                    assert_eq!(id, BlockBodyOrChunkId::new(*block.hash()));
                 }
             );
         }
@@ -3472,8 +3579,8 @@ async fn fwd_sync_latch_should_not_decrement_for_old_responses() {
         let effects = block_synchronizer.handle_event(
             mock_reactor.effect_builder(),
             &mut rng,
-            Event::BlockFetched(Ok(FetchedData::FromPeer {
-                item: Box::new(block.clone()),
+            Event::BlockBodyOrChunkFetched(Ok(FetchedData::FromPeer {
+                item: Box::new(BlockBodyOrChunk::new_value(&block)),
                 peer: peers[0],
             })),
         );
@@ -3505,8 +3612,8 @@ async fn fwd_sync_latch_should_not_decrement_for_old_responses() {
         let effects = block_synchronizer.handle_event(
             mock_reactor.effect_builder(),
             &mut rng,
-            Event::BlockFetched(Ok(FetchedData::FromPeer {
-                item: Box::new(block.clone()),
+            Event::BlockBodyOrChunkFetched(Ok(FetchedData::FromPeer {
+                item: Box::new(BlockBodyOrChunk::new_value(&block)),
                 peer: peers[1],
             })),
         );
@@ -1,13 +1,15 @@
+mod metrics;
 #[cfg(test)]
 mod tests;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::{self, Debug},
 };
 
 use datasize::DataSize;
 use derive_more::From;
+use prometheus::Registry;
 use rand::seq::SliceRandom;
 use serde::Serialize;
 use thiserror::Error;
@@ -26,13 +28,14 @@ use crate::{
     },
     effect::{
         announcements::PeerBehaviorAnnouncement,
-        requests::{FetcherRequest, TrieAccumulatorRequest},
+        requests::{FetcherRequest, StorageRequest, TrieAccumulatorRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{NodeId, TrieOrChunk, TrieOrChunkId},
     utils::DisplayIter,
     NodeRng,
 };
+use metrics::Metrics;
 
 const COMPONENT_NAME: &str = "trie_accumulator";
 
@@ -76,14 +79,50 @@ struct PartialChunks {
     peers: Vec<NodeId>,
     responders: Vec<Responder<Result<Response, Error>>>,
     chunks: HashMap<u64, ChunkWithProof>,
+    /// The chunks currently being fetched, along with the peer each was requested from.
+    in_flight: HashMap<u64, NodeId>,
+    /// The total number of chunks, known once the first chunk has been fetched.
+    count: Option<u64>,
+    /// Whether this trie is queued to resume fetching once a peer has spare capacity.
+    awaiting_peers: bool,
+    /// Whether the chunks stored by an earlier attempt to fetch this trie have been requested.
+    restore_requested: bool,
+    /// Whether fetching is paused until the chunks stored by an earlier attempt have been read.
+    restoring: bool,
     unreliable_peers: Vec<NodeId>,
 }
 
 impl PartialChunks {
+    fn new(peers: Vec<NodeId>, responders: Vec<Responder<Result<Response, Error>>>) -> Self {
+        PartialChunks {
+            peers,
+            responders,
+            chunks: Default::default(),
+            in_flight: Default::default(),
+            count: None,
+            awaiting_peers: false,
+            restore_requested: false,
+            restoring: false,
+            unreliable_peers: Vec::new(),
+        }
+    }
+
     fn missing_chunk(&self, count: u64) -> Option<u64> {
         (0..count).find(|idx| !self.chunks.contains_key(idx))
     }
 
+    /// Returns the indices of the chunks to request next, keeping at most `max_parallel` chunks
+    /// in flight.
+    ///
+    /// Until the first chunk arrives the total number of chunks is unknown, so only the first one
+    /// is requested.
+    fn chunks_to_fetch(&self, max_parallel: usize) -> Vec<u64> {
+        (0..self.count.unwrap_or(1))
+            .filter(|idx| !self.chunks.contains_key(idx) && !self.in_flight.contains_key(idx))
+            .take(max_parallel.saturating_sub(self.in_flight.len()))
+            .collect()
+    }
+
     fn assemble_chunks(&self, count: u64) -> TrieRaw {
         let data: Bytes = (0..count)
             .filter_map(|index| self.chunks.get(&index))
@@ -93,14 +132,10 @@ impl PartialChunks {
         TrieRaw::new(data)
     }
 
-    fn next_peer(&mut self) -> Option<&NodeId> {
-        // remove the last used peer from the queue
-        self.peers.pop();
-        self.peers.last()
-    }
-
     fn merge(&mut self, other: PartialChunks) {
         self.chunks.extend(other.chunks);
+        self.in_flight.extend(other.in_flight);
+        self.count = self.count.or(other.count);
         self.responders.extend(other.responders);
         // set used for filtering out duplicates
         let mut filter_peers: HashSet<NodeId> = self.peers.iter().cloned().collect();
@@ -118,14 +153,52 @@ impl PartialChunks {
             .collect()
     }
 
+    /// Responds with the assembled trie, and deletes any of its chunks stored while fetching it.
+    fn respond_with_trie<REv>(
+        self,
+        effect_builder: EffectBuilder<REv>,
+        hash: Digest,
+        count: u64,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let trie = self.assemble_chunks(count);
+        let unreliable_peers = self.unreliable_peers.clone();
+        let restore_requested = self.restore_requested;
+        let mut effects = self.respond(Ok(Response::new(trie, unreliable_peers)));
+        if restore_requested {
+            effects.extend(
+                effect_builder
+                    .delete_trie_chunks_from_storage(hash)
+                    .ignore(),
+            );
+        }
+        effects
+    }
+
+    /// Marks the peer as unreliable and stops requesting further chunks from it.
     fn mark_peer_unreliable(&mut self, peer: &NodeId) {
-        self.unreliable_peers.push(*peer);
+        self.peers.retain(|other| other != peer);
+        if !self.unreliable_peers.contains(peer) {
+            self.unreliable_peers.push(*peer);
+        }
     }
 }
 
 #[derive(DataSize, Debug)]
 pub(super) struct TrieAccumulator {
     partial_chunks: HashMap<Digest, PartialChunks>,
+    /// Maximum number of chunks of a single trie to fetch in parallel.
+    max_parallel_chunk_fetches: usize,
+    /// Maximum number of fetches in flight to any single peer.
+    max_in_flight_fetches_per_peer: usize,
+    /// Number of fetches currently in flight to each peer.
+    in_flight_per_peer: HashMap<NodeId, usize>,
+    /// Tries with chunks held back because all of their peers were at capacity.
+    awaiting_peers: VecDeque<Digest>,
+    #[data_size(skip)]
+    metrics: Metrics,
 }
 
 #[derive(DataSize, Debug, From, Serialize)]
@@ -134,26 +207,49 @@ pub(crate) enum Event {
     Request(TrieAccumulatorRequest),
     TrieOrChunkFetched {
         id: TrieOrChunkId,
+        peer: NodeId,
         fetch_result: FetchResult<TrieOrChunk>,
     },
+    /// The chunks of a trie stored by an earlier attempt to fetch it have been read.
+    RestoredChunks {
+        hash: Digest,
+        chunks: Vec<ChunkWithProof>,
+    },
 }
 
 impl fmt::Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Event::Request(_) => write!(f, "trie fetcher request"),
-            Event::TrieOrChunkFetched { id, .. } => {
-                write!(f, "got a result for trie or chunk {}", id)
+            Event::TrieOrChunkFetched { id, peer, .. } => {
+                write!(f, "got a result for trie or chunk {} from {}", id, peer)
+            }
+            Event::RestoredChunks { hash, chunks } => {
+                write!(
+                    f,
+                    "restored {} stored chunks of trie {}",
+                    chunks.len(),
+                    hash
+                )
             }
         }
     }
 }
 
 impl TrieAccumulator {
-    pub(crate) fn new() -> Self {
-        TrieAccumulator {
+    pub(crate) fn new(
+        max_parallel_chunk_fetches: usize,
+        max_in_flight_fetches_per_peer: usize,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(TrieAccumulator {
             partial_chunks: Default::default(),
-        }
+            max_parallel_chunk_fetches,
+            max_in_flight_fetches_per_peer,
+            in_flight_per_peer: Default::default(),
+            awaiting_peers: Default::default(),
+            metrics: Metrics::new(registry)?,
+        })
     }
 
     fn consume_trie_or_chunk<REv>(
@@ -162,7 +258,10 @@ impl TrieAccumulator {
         trie_or_chunk: TrieOrChunk,
     ) -> Effects<Event>
     where
-        REv: From<FetcherRequest<TrieOrChunk>> + From<PeerBehaviorAnnouncement> + Send,
+        REv: From<FetcherRequest<TrieOrChunk>>
+            + From<PeerBehaviorAnnouncement>
+            + From<StorageRequest>
+            + Send,
     {
         let TrieOrChunkId(_index, hash) = trie_or_chunk.fetch_id();
         match trie_or_chunk {
@@ -187,79 +286,256 @@ impl TrieAccumulator {
         chunk: ChunkWithProof,
     ) -> Effects<Event>
     where
-        REv: From<FetcherRequest<TrieOrChunk>> + From<PeerBehaviorAnnouncement> + Send,
+        REv: From<FetcherRequest<TrieOrChunk>>
+            + From<PeerBehaviorAnnouncement>
+            + From<StorageRequest>
+            + Send,
     {
         let digest = chunk.proof().root_hash();
         let index = chunk.proof().index();
         let count = chunk.proof().count();
         let mut partial_chunks = match self.partial_chunks.remove(&digest) {
             None => {
-                error!(%digest, %index, "got a chunk that wasn't requested");
+                // This can happen if fetching the trie failed while other chunks were in flight.
+                debug!(%digest, %index, "got a chunk that wasn't requested");
                 return Effects::new();
             }
             Some(partial_chunks) => partial_chunks,
         };
 
         // Add the downloaded chunk to cache.
-        let _ = partial_chunks.chunks.insert(index, chunk);
+        let _ = partial_chunks.chunks.insert(index, chunk.clone());
+        partial_chunks.count = Some(count);
 
         // Check if we can now return a complete trie.
-        match partial_chunks.missing_chunk(count) {
-            Some(missing_index) => {
-                let peer = match partial_chunks.peers.last() {
-                    Some(peer) => *peer,
-                    None => {
-                        debug!(
-                            %digest, %missing_index,
-                            "no peers to download the next chunk from, giving up",
-                        );
-                        let unreliable_peers = partial_chunks.unreliable_peers.clone();
-                        return partial_chunks.respond(Err(Error::Absent(
-                            digest,
-                            index,
-                            unreliable_peers,
-                        )));
-                    }
-                };
-                let next_id = TrieOrChunkId(missing_index, digest);
-                self.try_download_chunk(effect_builder, next_id, peer, partial_chunks)
-            }
+        if partial_chunks.missing_chunk(count).is_none() {
+            return partial_chunks.respond_with_trie(effect_builder, digest, count);
+        }
+
+        // Store the chunk, so that it isn't fetched again if fetching the trie is interrupted.
+        let mut effects = effect_builder.put_trie_chunk_to_storage(chunk).ignore();
+        if !partial_chunks.restore_requested {
+            // An earlier attempt may have stored some of the chunks already, so hold back further
+            // fetches until they are read.
+            partial_chunks.restore_requested = true;
+            partial_chunks.restoring = true;
+            effects.extend(effect_builder.get_trie_chunks_from_storage(digest).event(
+                move |chunks| Event::RestoredChunks {
+                    hash: digest,
+                    chunks,
+                },
+            ));
+        }
+        self.partial_chunks.insert(digest, partial_chunks);
+        effects.extend(self.fetch_missing_chunks(effect_builder, digest));
+        effects
+    }
+
+    /// Adds the chunks stored by an earlier attempt to fetch the trie, then resumes fetching the
+    /// ones still missing.
+    fn restore_chunks<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        hash: Digest,
+        chunks: Vec<ChunkWithProof>,
+    ) -> Effects<Event>
+    where
+        REv: From<FetcherRequest<TrieOrChunk>> + From<StorageRequest> + Send,
+    {
+        let mut partial_chunks = match self.partial_chunks.remove(&hash) {
             None => {
-                let trie = partial_chunks.assemble_chunks(count);
-                let unreliable_peers = partial_chunks.unreliable_peers.clone();
-                partial_chunks.respond(Ok(Response::new(trie, unreliable_peers)))
+                // This can happen if fetching the trie failed while the chunks were being read.
+                debug!(%hash, "restored chunks of a trie we're not fetching");
+                return Effects::new();
             }
+            Some(partial_chunks) => partial_chunks,
+        };
+        partial_chunks.restoring = false;
+
+        for chunk in chunks {
+            let index = chunk.proof().index();
+            if Some(chunk.proof().count()) != partial_chunks.count
+                || partial_chunks.chunks.contains_key(&index)
+            {
+                continue;
+            }
+            if let Err(error) = chunk.verify() {
+                warn!(%hash, %index, %error, "ignoring invalid stored trie chunk");
+                continue;
+            }
+            self.metrics.restored_chunks.inc();
+            self.metrics
+                .refetched_bytes_avoided
+                .inc_by(chunk.chunk().len() as u64);
+            let _ = partial_chunks.chunks.insert(index, chunk);
         }
+
+        if let Some(count) = partial_chunks.count {
+            if partial_chunks.missing_chunk(count).is_none() {
+                debug!(%hash, "trie complete after restoring stored chunks");
+                return partial_chunks.respond_with_trie(effect_builder, hash, count);
+            }
+        }
+        self.partial_chunks.insert(hash, partial_chunks);
+        self.fetch_missing_chunks(effect_builder, hash)
     }
 
-    fn try_download_chunk<REv>(
+    fn try_download_chunks<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        id: TrieOrChunkId,
-        peer: NodeId,
+        hash: Digest,
         partial_chunks: PartialChunks,
     ) -> Effects<Event>
     where
         REv: From<FetcherRequest<TrieOrChunk>> + Send,
     {
-        let hash = id.digest();
-        let maybe_old_partial_chunks = self.partial_chunks.insert(*hash, partial_chunks);
-        if let Some(old_partial_chunks) = maybe_old_partial_chunks {
-            // unwrap is safe as we just inserted a value at this key
-            self.partial_chunks
-                .get_mut(hash)
+        match self.partial_chunks.entry(hash) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(partial_chunks),
+            Entry::Vacant(entry) => {
+                entry.insert(partial_chunks);
+            }
+        }
+        self.fetch_missing_chunks(effect_builder, hash)
+    }
+
+    /// Requests the missing chunks of the given trie, each from the least busy of its peers.
+    ///
+    /// If all of the trie's peers are at capacity, the trie is queued to resume fetching once one
+    /// of them responds.
+    fn fetch_missing_chunks<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        hash: Digest,
+    ) -> Effects<Event>
+    where
+        REv: From<FetcherRequest<TrieOrChunk>> + Send,
+    {
+        let partial_chunks = match self.partial_chunks.get_mut(&hash) {
+            None => return Effects::new(),
+            Some(partial_chunks) => partial_chunks,
+        };
+
+        if partial_chunks.restoring {
+            // Fetching resumes once the stored chunks have been read.
+            return Effects::new();
+        }
+
+        if partial_chunks.peers.is_empty() {
+            if !partial_chunks.in_flight.is_empty() {
+                // Wait for the outstanding fetches before giving up.
+                return Effects::new();
+            }
+            let missing_index = partial_chunks
+                .missing_chunk(partial_chunks.count.unwrap_or(1))
+                .unwrap_or_default();
+            debug!(
+                %hash, %missing_index,
+                "no peers to download the next chunk from, giving up",
+            );
+            let unreliable_peers = partial_chunks.unreliable_peers.clone();
+            // unwrap is safe as we just got a value at this key
+            return self
+                .partial_chunks
+                .remove(&hash)
                 .unwrap()
-                .merge(old_partial_chunks);
+                .respond(Err(Error::Absent(hash, missing_index, unreliable_peers)));
         }
-        effect_builder
-            .fetch::<TrieOrChunk>(id, peer, Box::new(EmptyValidationMetadata))
-            .event(move |fetch_result| Event::TrieOrChunkFetched { id, fetch_result })
+
+        let max_in_flight_fetches_per_peer = self.max_in_flight_fetches_per_peer;
+        let in_flight_per_peer = &mut self.in_flight_per_peer;
+        let mut effects = Effects::new();
+        for index in partial_chunks.chunks_to_fetch(self.max_parallel_chunk_fetches) {
+            // Peers were shuffled on request; on a tie, prefer the ones at the back of the list.
+            let maybe_peer = partial_chunks
+                .peers
+                .iter()
+                .rev()
+                .map(|peer| {
+                    (
+                        *peer,
+                        in_flight_per_peer.get(peer).copied().unwrap_or_default(),
+                    )
+                })
+                .filter(|(_, in_flight)| *in_flight < max_in_flight_fetches_per_peer)
+                .min_by_key(|(_, in_flight)| *in_flight)
+                .map(|(peer, _)| peer);
+            let peer = match maybe_peer {
+                Some(peer) => peer,
+                None => {
+                    trace!(%hash, "all peers busy, holding back chunk fetches");
+                    if !partial_chunks.awaiting_peers {
+                        partial_chunks.awaiting_peers = true;
+                        self.awaiting_peers.push_back(hash);
+                    }
+                    break;
+                }
+            };
+            partial_chunks.in_flight.insert(index, peer);
+            *in_flight_per_peer.entry(peer).or_default() += 1;
+            let id = TrieOrChunkId(index, hash);
+            effects.extend(
+                effect_builder
+                    .fetch::<TrieOrChunk>(id, peer, Box::new(EmptyValidationMetadata))
+                    .event(move |fetch_result| Event::TrieOrChunkFetched {
+                        id,
+                        peer,
+                        fetch_result,
+                    }),
+            );
+        }
+        effects
+    }
+
+    /// Records that a fetch from the given peer has completed.
+    fn release_peer(&mut self, peer: &NodeId) {
+        if let Some(in_flight) = self.in_flight_per_peer.get_mut(peer) {
+            *in_flight = in_flight.saturating_sub(1);
+            if *in_flight == 0 {
+                self.in_flight_per_peer.remove(peer);
+            }
+        }
+    }
+
+    /// Resumes fetching for tries held back waiting for spare capacity, until `released_peer` is
+    /// busy again.
+    fn resume_awaiting_peers<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        released_peer: &NodeId,
+    ) -> Effects<Event>
+    where
+        REv: From<FetcherRequest<TrieOrChunk>> + Send,
+    {
+        let mut effects = Effects::new();
+        // Each queued trie is visited at most once, as tries still stuck are queued again.
+        for _ in 0..self.awaiting_peers.len() {
+            let released_peer_in_flight = self
+                .in_flight_per_peer
+                .get(released_peer)
+                .copied()
+                .unwrap_or_default();
+            if released_peer_in_flight >= self.max_in_flight_fetches_per_peer {
+                break;
+            }
+            let hash = match self.awaiting_peers.pop_front() {
+                Some(hash) => hash,
+                None => break,
+            };
+            if let Some(partial_chunks) = self.partial_chunks.get_mut(&hash) {
+                partial_chunks.awaiting_peers = false;
+                effects.extend(self.fetch_missing_chunks(effect_builder, hash));
+            }
+        }
+        effects
     }
 }
 
 impl<REv> Component<REv> for TrieAccumulator
 where
-    REv: From<FetcherRequest<TrieOrChunk>> + From<PeerBehaviorAnnouncement> + Send,
+    REv: From<FetcherRequest<TrieOrChunk>>
+        + From<PeerBehaviorAnnouncement>
+        + From<StorageRequest>
+        + Send,
 {
     type Event = Event;
 
@@ -277,51 +553,48 @@ where
                 mut peers,
             }) => {
                 peers.shuffle(rng);
-                let trie_id = TrieOrChunkId(0, hash);
-                let peer = match peers.last() {
-                    Some(peer) => *peer,
-                    None => {
-                        error!(%hash, "tried to fetch trie with no peers available");
-                        return responder.respond(Err(Error::NoPeers(hash))).ignore();
-                    }
-                };
-                let partial_chunks = PartialChunks {
-                    responders: vec![responder],
-                    peers,
-                    chunks: Default::default(),
-                    unreliable_peers: Vec::new(),
-                };
-                self.try_download_chunk(effect_builder, trie_id, peer, partial_chunks)
+                if peers.is_empty() {
+                    error!(%hash, "tried to fetch trie with no peers available");
+                    return responder.respond(Err(Error::NoPeers(hash))).ignore();
+                }
+                let partial_chunks = PartialChunks::new(peers, vec![responder]);
+                self.try_download_chunks(effect_builder, hash, partial_chunks)
             }
-            Event::TrieOrChunkFetched { id, fetch_result } => {
-                let hash = id.digest();
-                match fetch_result {
-                    Err(error) => match self.partial_chunks.remove(hash) {
+            Event::TrieOrChunkFetched {
+                id,
+                peer,
+                fetch_result,
+            } => {
+                let TrieOrChunkId(index, hash) = id;
+                self.release_peer(&peer);
+                if let Some(partial_chunks) = self.partial_chunks.get_mut(&hash) {
+                    if partial_chunks.in_flight.get(&index) == Some(&peer) {
+                        partial_chunks.in_flight.remove(&index);
+                    }
+                }
+                let mut effects = match fetch_result {
+                    Err(error) => match self.partial_chunks.get_mut(&hash) {
                         None => {
-                            error!(%id,
+                            debug!(%id,
                                 "got a fetch result for a chunk we weren't trying to fetch",
                             );
                             Effects::new()
                         }
-                        Some(mut partial_chunks) => {
+                        Some(partial_chunks) => {
                             debug!(%error, %id, "error fetching trie chunk");
                             partial_chunks.mark_peer_unreliable(error.peer());
-                            // try with the next peer, if possible
-                            match partial_chunks.next_peer().cloned() {
-                                Some(next_peer) => self.try_download_chunk(
-                                    effect_builder,
-                                    id,
-                                    next_peer,
-                                    partial_chunks,
-                                ),
-                                None => {
-                                    warn!(%id, "couldn't fetch chunk");
-                                    let faulty_peers = partial_chunks.unreliable_peers.clone();
-                                    partial_chunks.respond(Err(Error::PeersExhausted(
-                                        Box::new(error),
-                                        faulty_peers,
-                                    )))
-                                }
+                            if partial_chunks.peers.is_empty()
+                                && partial_chunks.in_flight.is_empty()
+                            {
+                                warn!(%id, "couldn't fetch chunk");
+                                let faulty_peers = partial_chunks.unreliable_peers.clone();
+                                // unwrap is safe as we just got a value at this key
+                                self.partial_chunks.remove(&hash).unwrap().respond(Err(
+                                    Error::PeersExhausted(Box::new(error), faulty_peers),
+                                ))
+                            } else {
+                                // try with the remaining peers, if possible
+                                self.fetch_missing_chunks(effect_builder, hash)
                             }
                         }
                     },
@@ -338,7 +611,12 @@ where
                         debug!(%peer, %trie_or_chunk, "got trie or chunk from peer");
                         self.consume_trie_or_chunk(effect_builder, *trie_or_chunk)
                     }
-                }
+                };
+                effects.extend(self.resume_awaiting_peers(effect_builder, &peer));
+                effects
+            }
+            Event::RestoredChunks { hash, chunks } => {
+                self.restore_chunks(effect_builder, hash, chunks)
             }
         }
     }
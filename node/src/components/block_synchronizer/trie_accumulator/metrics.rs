@@ -0,0 +1,42 @@
+use prometheus::{IntCounter, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the trie accumulator.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of trie chunks restored from storage rather than fetched again.
+    pub(super) restored_chunks: IntCounter,
+    /// Number of bytes of trie chunks restored from storage rather than fetched again.
+    pub(super) refetched_bytes_avoided: IntCounter,
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates a new instance of the trie accumulator metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let restored_chunks = IntCounter::new(
+            "trie_accumulator_restored_chunks",
+            "number of trie chunks restored from storage rather than fetched again",
+        )?;
+        let refetched_bytes_avoided = IntCounter::new(
+            "trie_accumulator_refetched_bytes_avoided",
+            "number of bytes of trie chunks restored from storage rather than fetched again",
+        )?;
+        registry.register(Box::new(restored_chunks.clone()))?;
+        registry.register(Box::new(refetched_bytes_avoided.clone()))?;
+
+        Ok(Metrics {
+            restored_chunks,
+            refetched_bytes_avoided,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.restored_chunks);
+        unregister_metric!(self.registry, self.refetched_bytes_avoided);
+    }
+}
@@ -8,11 +8,15 @@ use crate::{
 use casper_types::testing::TestRng;
 use futures::channel::oneshot;
 
+const MAX_PARALLEL_CHUNK_FETCHES: usize = 10;
+const MAX_IN_FLIGHT_FETCHES_PER_PEER: usize = 50;
+
 /// Event for the mock reactor.
 #[derive(Debug)]
 enum ReactorEvent {
     FetcherRequest(FetcherRequest<TrieOrChunk>),
     PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
+    StorageRequest(StorageRequest),
 }
 
 impl From<PeerBehaviorAnnouncement> for ReactorEvent {
@@ -27,6 +31,12 @@ impl From<FetcherRequest<TrieOrChunk>> for ReactorEvent {
     }
 }
 
+impl From<StorageRequest> for ReactorEvent {
+    fn from(req: StorageRequest) -> ReactorEvent {
+        ReactorEvent::StorageRequest(req)
+    }
+}
+
 struct MockReactor {
     scheduler: &'static Scheduler<ReactorEvent>,
     effect_builder: EffectBuilder<ReactorEvent>,
@@ -48,16 +58,19 @@ impl MockReactor {
     }
 
     async fn expect_fetch_event(&self, chunk_id: &TrieOrChunkId, peer: &NodeId) {
+        let (id, fetched_from) = self.next_fetch_request().await;
+        assert_eq!(id, *chunk_id);
+        assert_eq!(fetched_from, *peer);
+    }
+
+    async fn next_fetch_request(&self) -> (TrieOrChunkId, NodeId) {
         let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
         match reactor_event {
-            ReactorEvent::FetcherRequest(request) => {
-                assert_eq!(request.id, *chunk_id);
-                assert_eq!(request.peer, *peer);
-            }
+            ReactorEvent::FetcherRequest(request) => (request.id, request.peer),
             _ => {
                 unreachable!();
             }
-        };
+        }
     }
 }
 
@@ -68,11 +81,10 @@ async fn download_chunk_and_check(
     peer: &NodeId,
     partial_chunks: PartialChunks,
 ) {
-    // Try to download a chunk from a peer
-    let mut effects = trie_accumulator.try_download_chunk(
+    // Try to download a trie; the first chunk should be requested from the given peer
+    let mut effects = trie_accumulator.try_download_chunks(
         reactor.effect_builder(),
-        *chunk_to_download,
-        *peer,
+        *chunk_to_download.digest(),
         partial_chunks,
     );
     // A fetch effect should be generated
@@ -88,7 +100,12 @@ fn unsolicited_chunk_produces_no_effects() {
     let reactor = MockReactor::new();
 
     // Empty accumulator. Does not expect any chunks.
-    let mut trie_accumulator = TrieAccumulator::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
     let (test_chunks, _, _) = test_chunks_with_proof(1);
 
     let effects = trie_accumulator.consume_chunk(reactor.effect_builder(), test_chunks[0].clone());
@@ -99,17 +116,17 @@ fn unsolicited_chunk_produces_no_effects() {
 async fn try_download_chunk_generates_fetch_effect() {
     let mut rng = TestRng::new();
     let reactor = MockReactor::new();
-    let mut trie_accumulator = TrieAccumulator::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
 
     // Create a test chunk
     let (_, chunk_ids, _) = test_chunks_with_proof(1);
     let peer = NodeId::random(&mut rng);
-    let chunks = PartialChunks {
-        peers: vec![peer],
-        responders: Default::default(),
-        chunks: Default::default(),
-        unreliable_peers: Default::default(),
-    };
+    let chunks = PartialChunks::new(vec![peer], Default::default());
 
     download_chunk_and_check(
         &reactor,
@@ -125,7 +142,12 @@ async fn try_download_chunk_generates_fetch_effect() {
 async fn failed_fetch_retriggers_download_with_different_peer() {
     let mut rng = TestRng::new();
     let reactor = MockReactor::new();
-    let mut trie_accumulator = TrieAccumulator::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
 
     // Create a test chunk
     let (_, chunk_ids, _) = test_chunks_with_proof(1);
@@ -136,12 +158,7 @@ async fn failed_fetch_retriggers_download_with_different_peer() {
         .map(|_| NodeId::random(&mut rng))
         .collect();
 
-    let chunks = PartialChunks {
-        peers: peers.clone(),
-        responders: Default::default(),
-        chunks: Default::default(),
-        unreliable_peers: Default::default(),
-    };
+    let chunks = PartialChunks::new(peers.clone(), Default::default());
 
     download_chunk_and_check(
         &reactor,
@@ -159,6 +176,7 @@ async fn failed_fetch_retriggers_download_with_different_peer() {
     });
     let event = Event::TrieOrChunkFetched {
         id: chunk_ids[0],
+        peer: peers[1],
         fetch_result,
     };
 
@@ -176,50 +194,64 @@ async fn failed_fetch_retriggers_download_with_different_peer() {
 async fn fetched_chunk_triggers_download_of_missing_chunk() {
     let mut rng = TestRng::new();
     let reactor = MockReactor::new();
-    let mut trie_accumulator = TrieAccumulator::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
 
     // Create test chunks
     let (test_chunks, chunk_ids, _) = test_chunks_with_proof(2);
     let peer = NodeId::random(&mut rng);
 
-    let chunks = PartialChunks {
-        peers: vec![peer],
-        responders: Default::default(),
-        chunks: Default::default(),
-        unreliable_peers: Default::default(),
-    };
+    let chunks = PartialChunks::new(vec![peer], Default::default());
 
     download_chunk_and_check(
         &reactor,
         &mut trie_accumulator,
-        &chunk_ids[1],
+        &chunk_ids[0],
         &peer,
         chunks,
     )
     .await;
 
     // Simulate a successful fetch
-    let chunk = Box::new(ValueOrChunk::ChunkWithProof(test_chunks[1].clone()));
+    let chunk = Box::new(ValueOrChunk::ChunkWithProof(test_chunks[0].clone()));
     let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer { peer, item: chunk });
     let event = Event::TrieOrChunkFetched {
-        id: chunk_ids[1],
+        id: chunk_ids[0],
+        peer,
         fetch_result,
     };
 
-    // Process the downloaded chunk
+    // Process the downloaded chunk; it should be stored, and any chunks stored earlier read
+    let effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+
+    // No chunks were stored earlier
+    let event = Event::RestoredChunks {
+        hash: *chunk_ids[0].digest(),
+        chunks: vec![],
+    };
     let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
     assert_eq!(effects.len(), 1);
 
     // Check if a new fetch was issued for the missing chunk
     tokio::spawn(async move { effects.remove(0).await });
-    reactor.expect_fetch_event(&chunk_ids[0], &peer).await;
+    reactor.expect_fetch_event(&chunk_ids[1], &peer).await;
 }
 
 #[tokio::test]
 async fn trie_returned_when_all_chunks_fetched() {
     let mut rng = TestRng::new();
     let reactor = MockReactor::new();
-    let mut trie_accumulator = TrieAccumulator::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
 
     // Create test chunks
     let (test_chunks, chunk_ids, data) = test_chunks_with_proof(3);
@@ -229,12 +261,7 @@ async fn trie_returned_when_all_chunks_fetched() {
     let (sender, receiver) = oneshot::channel();
     let responder = Responder::without_shutdown(sender);
 
-    let chunks = PartialChunks {
-        peers: vec![peer],
-        responders: vec![responder],
-        chunks: Default::default(),
-        unreliable_peers: Default::default(),
-    };
+    let chunks = PartialChunks::new(vec![peer], vec![responder]);
 
     download_chunk_and_check(
         &reactor,
@@ -247,7 +274,7 @@ async fn trie_returned_when_all_chunks_fetched() {
 
     let mut effects = Effects::new();
 
-    for i in 0..3 {
+    for (i, expected_effects) in [2, 1, 2].iter().enumerate() {
         // Simulate a successful fetch
         let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer {
             peer,
@@ -255,13 +282,23 @@ async fn trie_returned_when_all_chunks_fetched() {
         });
         let event = Event::TrieOrChunkFetched {
             id: chunk_ids[i],
+            peer,
             fetch_result,
         };
 
-        // Expect to get one effect for each call. First 2 will be requests to download missing
-        // chunks. Last one will be the returned trie since all chunks are available.
+        // The first chunk reveals the chunk count, so once it is known that no chunks were stored
+        // earlier, the 2 missing chunks are requested in parallel. The second one is stored, and
+        // the last one will return the trie and delete the stored chunks.
         effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
-        assert_eq!(effects.len(), 1);
+        assert_eq!(effects.len(), *expected_effects);
+        if i == 0 {
+            let event = Event::RestoredChunks {
+                hash: *chunk_ids[0].digest(),
+                chunks: vec![],
+            };
+            let effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+            assert_eq!(effects.len(), 2);
+        }
     }
 
     // Validate the returned trie
@@ -269,3 +306,219 @@ async fn trie_returned_when_all_chunks_fetched() {
     let result_trie = receiver.await.unwrap().expect("Expected trie").trie;
     assert_eq!(*result_trie, TrieRaw::new(Bytes::from(data)));
 }
+
+#[tokio::test]
+async fn missing_chunks_fetched_in_parallel_from_multiple_peers() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let (test_chunks, chunk_ids, _) = test_chunks_with_proof(3);
+    let peers: Vec<NodeId> = (0..2)
+        .into_iter()
+        .map(|_| NodeId::random(&mut rng))
+        .collect();
+    let chunks = PartialChunks::new(peers.clone(), Default::default());
+
+    download_chunk_and_check(
+        &reactor,
+        &mut trie_accumulator,
+        &chunk_ids[0],
+        &peers[1],
+        chunks,
+    )
+    .await;
+
+    let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer {
+        peer: peers[1],
+        item: Box::new(ValueOrChunk::ChunkWithProof(test_chunks[0].clone())),
+    });
+    let event = Event::TrieOrChunkFetched {
+        id: chunk_ids[0],
+        peer: peers[1],
+        fetch_result,
+    };
+
+    let effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+
+    // Once it is known that no chunks were stored earlier, both missing chunks should be requested
+    // at once, one from each peer.
+    let event = Event::RestoredChunks {
+        hash: *chunk_ids[0].digest(),
+        chunks: vec![],
+    };
+    let effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    let mut requested = vec![
+        reactor.next_fetch_request().await,
+        reactor.next_fetch_request().await,
+    ];
+    requested.sort_by_key(|(id, _)| id.0);
+    assert_eq!(requested[0].0, chunk_ids[1]);
+    assert_eq!(requested[1].0, chunk_ids[2]);
+    assert_ne!(requested[0].1, requested[1].1);
+}
+
+#[tokio::test]
+async fn fetches_held_back_while_peer_is_busy() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let mut trie_accumulator =
+        TrieAccumulator::new(MAX_PARALLEL_CHUNK_FETCHES, 1, &Registry::new()).unwrap();
+
+    let (first_trie_chunks, first_trie_ids, _) = test_chunks_with_proof(1);
+    let (_, second_trie_ids, _) = test_chunks_with_proof(1);
+    let peer = NodeId::random(&mut rng);
+
+    download_chunk_and_check(
+        &reactor,
+        &mut trie_accumulator,
+        &first_trie_ids[0],
+        &peer,
+        PartialChunks::new(vec![peer], Default::default()),
+    )
+    .await;
+
+    // The only peer is at capacity, so the second trie shouldn't be requested yet.
+    let effects = trie_accumulator.try_download_chunks(
+        reactor.effect_builder(),
+        *second_trie_ids[0].digest(),
+        PartialChunks::new(vec![peer], Default::default()),
+    );
+    assert!(effects.is_empty());
+
+    // Once the peer responds, the second trie should be requested from it.
+    let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer {
+        peer,
+        item: Box::new(ValueOrChunk::ChunkWithProof(first_trie_chunks[0].clone())),
+    });
+    let event = Event::TrieOrChunkFetched {
+        id: first_trie_ids[0],
+        peer,
+        fetch_result,
+    };
+    let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    tokio::spawn(async move { effects.remove(0).await });
+    reactor.expect_fetch_event(&second_trie_ids[0], &peer).await;
+}
+
+#[tokio::test]
+async fn restored_chunks_are_not_fetched_again() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let (test_chunks, chunk_ids, _) = test_chunks_with_proof(3);
+    let peer = NodeId::random(&mut rng);
+
+    download_chunk_and_check(
+        &reactor,
+        &mut trie_accumulator,
+        &chunk_ids[0],
+        &peer,
+        PartialChunks::new(vec![peer], Default::default()),
+    )
+    .await;
+
+    let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer {
+        peer,
+        item: Box::new(ValueOrChunk::ChunkWithProof(test_chunks[0].clone())),
+    });
+    let event = Event::TrieOrChunkFetched {
+        id: chunk_ids[0],
+        peer,
+        fetch_result,
+    };
+
+    // The chunk should be stored and the chunks stored earlier read, with no fetches until then.
+    let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+    tokio::spawn(async move { effects.remove(0).await });
+    match reactor.scheduler.pop().await {
+        ((_, ReactorEvent::StorageRequest(StorageRequest::PutTrieChunk { chunk, .. })), _) => {
+            assert_eq!(*chunk, test_chunks[0]);
+        }
+        _ => unreachable!(),
+    }
+
+    // Only the chunk which wasn't stored earlier should be fetched.
+    let event = Event::RestoredChunks {
+        hash: *chunk_ids[0].digest(),
+        chunks: vec![test_chunks[0].clone(), test_chunks[1].clone()],
+    };
+    let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    tokio::spawn(async move { effects.remove(0).await });
+    reactor.expect_fetch_event(&chunk_ids[2], &peer).await;
+
+    assert_eq!(trie_accumulator.metrics.restored_chunks.get(), 1);
+    assert_eq!(
+        trie_accumulator.metrics.refetched_bytes_avoided.get(),
+        test_chunks[1].chunk().len() as u64
+    );
+}
+
+#[tokio::test]
+async fn trie_returned_when_missing_chunks_restored() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let mut trie_accumulator = TrieAccumulator::new(
+        MAX_PARALLEL_CHUNK_FETCHES,
+        MAX_IN_FLIGHT_FETCHES_PER_PEER,
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let (test_chunks, chunk_ids, data) = test_chunks_with_proof(2);
+    let peer = NodeId::random(&mut rng);
+    let (sender, receiver) = oneshot::channel();
+    let responder = Responder::without_shutdown(sender);
+
+    download_chunk_and_check(
+        &reactor,
+        &mut trie_accumulator,
+        &chunk_ids[0],
+        &peer,
+        PartialChunks::new(vec![peer], vec![responder]),
+    )
+    .await;
+
+    let fetch_result: FetchResult<TrieOrChunk> = Ok(FetchedData::FromPeer {
+        peer,
+        item: Box::new(ValueOrChunk::ChunkWithProof(test_chunks[0].clone())),
+    });
+    let event = Event::TrieOrChunkFetched {
+        id: chunk_ids[0],
+        peer,
+        fetch_result,
+    };
+    let effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+
+    // Restoring the missing chunk should return the trie and delete the stored chunks.
+    let event = Event::RestoredChunks {
+        hash: *chunk_ids[0].digest(),
+        chunks: vec![test_chunks[1].clone()],
+    };
+    let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 2);
+    tokio::spawn(async move { effects.remove(0).await });
+    let result_trie = receiver.await.unwrap().expect("Expected trie").trie;
+    assert_eq!(*result_trie, TrieRaw::new(Bytes::from(data)));
+    assert!(trie_accumulator.partial_chunks.is_empty());
+}
@@ -5,6 +5,7 @@
 mod cl_context;
 mod config;
 mod consensus_protocol;
+mod equivocation;
 mod era_supervisor;
 #[macro_use]
 pub mod highway_core;
@@ -17,6 +18,7 @@ pub(crate) mod tests;
 mod traits;
 pub mod utils;
 mod validator_change;
+mod validator_performance;
 
 use std::{
     borrow::Cow,
@@ -30,7 +32,7 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use casper_types::{EraId, Timestamp};
+use casper_types::{EraId, PublicKey, Timestamp};
 
 use crate::{
     components::Component,
@@ -59,8 +61,12 @@ use traits::Context;
 
 pub use cl_context::ClContext;
 pub(crate) use config::{ChainspecConsensusExt, Config};
-pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
-pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor, SerializedMessage};
+pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock, ScheduledRound};
+pub(crate) use equivocation::{ConflictingMessage, EquivocationRecord};
+pub(crate) use era_supervisor::{
+    debug::{EraDump, EraReplay},
+    EraSupervisor, SerializedMessage,
+};
 #[cfg(test)]
 pub(crate) use highway_core::highway::Vertex as HighwayVertex;
 pub(crate) use leader_sequence::LeaderSequence;
@@ -68,6 +74,7 @@ pub(crate) use protocols::highway::max_rounds_per_era;
 #[cfg(test)]
 pub(crate) use protocols::highway::HighwayMessage;
 pub(crate) use validator_change::ValidatorChange;
+pub(crate) use validator_performance::{ValidatorPerformance, ValidatorPerformanceReport};
 
 const COMPONENT_NAME: &str = "consensus";
 
@@ -170,6 +177,11 @@ pub(crate) enum Event {
         header: Box<BlockHeader>,
         header_hash: BlockHash,
     },
+    /// A new finality signature for a block in the given era has been accepted.
+    FinalitySignatureAccepted {
+        era_id: EraId,
+        public_key: PublicKey,
+    },
     /// The proposed block has been validated.
     ResolveValidity(ResolveValidity),
     /// Deactivate the era with the given ID, unless the number of faulty validators increases.
@@ -280,6 +292,11 @@ impl Display for Event {
                 "A block has been added to the linear chain: {}",
                 header_hash,
             ),
+            Event::FinalitySignatureAccepted { era_id, public_key } => write!(
+                f,
+                "A finality signature by {} for a block in {} has been accepted",
+                public_key, era_id,
+            ),
             Event::ResolveValidity(ResolveValidity {
                 era_id,
                 sender,
@@ -460,6 +477,10 @@ where
                 header,
                 header_hash: _,
             } => self.handle_block_added(effect_builder, rng, *header),
+            Event::FinalitySignatureAccepted { era_id, public_key } => {
+                self.handle_finality_signature(era_id, &public_key);
+                Effects::new()
+            }
             Event::ResolveValidity(resolve_validity) => {
                 self.resolve_validity(effect_builder, rng, resolve_validity)
             }
@@ -473,6 +494,10 @@ where
                 let validator_changes = self.get_validator_changes();
                 responder.respond(validator_changes).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::LeaderSchedule(responder)) => {
+                let leader_schedule = self.leader_schedule(Timestamp::now());
+                responder.respond(leader_schedule).ignore()
+            }
             Event::DumpState(req @ DumpConsensusStateRequest { era_id, .. }) => {
                 let current_era = match self.current_era() {
                     None => {
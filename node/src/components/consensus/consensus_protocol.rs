@@ -12,7 +12,7 @@ use casper_hashing::Digest;
 use casper_types::{bytesrepr::ToBytes, TimeDiff, Timestamp};
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{traits::Context, ActionId, ConflictingMessage, TimerId},
     types::NodeId,
     NodeRng,
 };
@@ -189,8 +189,30 @@ pub(crate) struct FinalizedBlock<C: Context> {
     pub(crate) proposer: C::ValidatorId,
 }
 
+/// An upcoming round, with the validator expected to propose a block in it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ScheduledRound<VID> {
+    /// The round ID.
+    pub(crate) round_id: u64,
+    /// The time the round starts, if the protocol's rounds are tied to fixed times.
+    pub(crate) start: Option<Timestamp>,
+    /// The round's leader.
+    pub(crate) leader: VID,
+}
+
 pub(crate) type ProtocolOutcomes<C> = Vec<ProtocolOutcome<C>>;
 
+/// A measurement of the protocol's progress, to be recorded in the consensus metrics.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum ProtocolMetric {
+    /// A new echo arrived this long after the timestamp of its round's proposal.
+    EchoArrival(TimeDiff),
+    /// A new vote arrived this long after the timestamp of its round's proposal.
+    VoteArrival(TimeDiff),
+    /// This many rounds were skipped between a newly finalized block and its parent.
+    SkippedRounds(u32),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) enum ProtocolOutcome<C: Context> {
     CreatedGossipMessage(SerializedMessage),
@@ -231,6 +253,10 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     /// This is used to inform the deploy buffer, so we don't propose the same deploys again.
     /// Does not need to be raised for proposals this node created itself.
     HandledProposedBlock(ProposedBlock<C>),
+    /// A measurement to be recorded in the metrics.
+    Metric(ProtocolMetric),
+    /// The given validator was the leader of a round that did not produce a finalized block.
+    MissedRound(C::ValidatorId),
 }
 
 /// An API for a single instance of the consensus.
@@ -309,6 +335,10 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Sends evidence for a faulty of validator `vid` to the `sender` of the request.
     fn send_evidence(&self, sender: NodeId, vid: &C::ValidatorId) -> ProtocolOutcomes<C>;
 
+    /// Returns the conflicting messages by which validator `vid` equivocated in this instance, or
+    /// an empty vector if we have no direct evidence against them.
+    fn conflicting_messages(&self, vid: &C::ValidatorId) -> Vec<ConflictingMessage<C>>;
+
     /// Sets the pause status: While paused we don't create consensus messages other than pings.
     fn set_paused(&mut self, paused: bool, now: Timestamp) -> ProtocolOutcomes<C>;
 
@@ -323,4 +353,7 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
 
     // TODO: Make this less Highway-specific.
     fn next_round_length(&self) -> Option<TimeDiff>;
+
+    /// Returns up to `count` upcoming rounds with their leaders, starting with the current one.
+    fn leader_schedule(&self, now: Timestamp, count: usize) -> Vec<ScheduledRound<C::ValidatorId>>;
 }
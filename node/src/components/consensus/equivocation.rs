@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use casper_types::{EraId, PublicKey, Timestamp};
+
+use super::{traits::Context, ClContext};
+
+/// One of two or more conflicting messages signed by the same validator, which together prove that
+/// the validator equivocated.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum ConflictingMessage<C>
+where
+    C: Context,
+{
+    /// A Highway unit.
+    Unit {
+        /// The unit's hash.
+        hash: C::Hash,
+        /// The unit's sequence number in its creator's swimlane.
+        seq_number: u64,
+        /// The unit's timestamp.
+        timestamp: Timestamp,
+        /// The creator's signature of the unit's hash.
+        signature: C::Signature,
+    },
+    /// A Highway endorsement.
+    Endorsement {
+        /// The hash of the endorsed unit.
+        unit: C::Hash,
+        /// The endorser's signature.
+        signature: C::Signature,
+    },
+    /// A Zug echo of a round's proposal.
+    Echo {
+        /// The round.
+        round_id: u32,
+        /// The hash of the echoed proposal.
+        proposal_hash: C::Hash,
+        /// The validator's signature.
+        signature: C::Signature,
+    },
+    /// A Zug vote in a round.
+    Vote {
+        /// The round.
+        round_id: u32,
+        /// Whether the vote was in favor of the round's proposal.
+        vote: bool,
+        /// The validator's signature.
+        signature: C::Signature,
+    },
+}
+
+/// A validator's equivocation, as observed by our node, with the conflicting messages.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EquivocationRecord {
+    /// The era in which the validator equivocated.
+    pub(crate) era_id: EraId,
+    /// The equivocating validator.
+    pub(crate) validator: PublicKey,
+    /// When our node first observed the equivocation.
+    pub(crate) detected_at: Timestamp,
+    /// The conflicting messages, or an empty list if the protocol couldn't provide them.
+    pub(crate) conflicting_messages: Vec<ConflictingMessage<ClContext>>,
+}
@@ -15,7 +15,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     convert::TryInto,
     fmt::{self, Debug, Formatter},
-    fs, io,
+    fs, io, iter,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
@@ -39,12 +39,13 @@ use crate::{
             cl_context::{ClContext, Keypair},
             consensus_protocol::{
                 ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
-                ProtocolOutcome,
+                ProtocolMetric, ProtocolOutcome, ScheduledRound,
             },
             metrics::Metrics,
             validator_change::{ValidatorChange, ValidatorChanges},
             ActionId, ChainspecConsensusExt, Config, ConsensusMessage, ConsensusRequestMessage,
-            Event, HighwayProtocol, NewBlockPayload, ReactorEventT, ResolveValidity, TimerId, Zug,
+            EquivocationRecord, Event, HighwayProtocol, NewBlockPayload, ReactorEventT,
+            ResolveValidity, TimerId, Zug,
         },
         network::blocklist::BlocklistJustification,
     },
@@ -72,6 +73,8 @@ use super::traits::ConsensusNetworkMessage;
 const FTT_EXCEEDED_SHUTDOWN_DELAY_MILLIS: u64 = 60 * 1000;
 /// A warning is printed if a timer is delayed by more than this.
 const TIMER_DELAY_WARNING_MILLIS: u64 = 1000;
+/// The number of upcoming rounds included in the leader schedule.
+const LEADER_SCHEDULE_ROUNDS: usize = 100;
 
 /// The number of eras across which evidence can be cited.
 /// If this is 1, you can cite evidence from the previous era, but not the one before that.
@@ -1066,11 +1069,15 @@ impl EraSupervisor {
                     return Effects::new();
                 }
                 let era = self.open_eras.get_mut(&era_id).unwrap();
+                self.metrics
+                    .update_round_length(era.consensus.next_round_length());
                 era.add_accusations(&equivocators);
                 era.add_accusations(value.accusations());
+                era.record_proposed_block(&proposer);
                 // If this is the era's last block, it contains rewards. Everyone who is accused in
                 // the block or seen as equivocating via the consensus protocol gets faulty.
                 let compute_rewards = self.chainspec.core_config.compute_rewards;
+                let is_switch_block = terminal_block_data.is_some();
                 let era_report = terminal_block_data.map(|mut tbd| {
                     // If block rewards are disabled, zero them.
                     if !compute_rewards {
@@ -1129,6 +1136,9 @@ impl EraSupervisor {
                     execute_finalized_block(effect_builder, finalized_approvals, finalized_block)
                         .ignore(),
                 );
+                if is_switch_block {
+                    effects.extend(self.store_performance_reports(effect_builder, era_id));
+                }
                 let effects_from_updating_pause =
                     self.update_consensus_pause(effect_builder, rng, era_id);
                 effects.extend(effects_from_updating_pause);
@@ -1189,9 +1199,22 @@ impl EraSupervisor {
                 .ignore(),
             ProtocolOutcome::NewEvidence(pub_key) => {
                 info!(%pub_key, era = era_id.value(), "validator equivocated");
+                let now = Timestamp::now();
+                let conflicting_messages = self
+                    .open_eras
+                    .get(&era_id)
+                    .map(|era| era.consensus.conflicting_messages(&pub_key))
+                    .unwrap_or_default();
+                let record = EquivocationRecord {
+                    era_id,
+                    validator: pub_key.clone(),
+                    detected_at: now,
+                    conflicting_messages,
+                };
                 let mut effects = effect_builder
-                    .announce_fault_event(era_id, pub_key.clone(), Timestamp::now())
+                    .announce_fault_event(era_id, pub_key.clone(), now)
                     .ignore();
+                effects.extend(effect_builder.put_equivocation_to_storage(record).ignore());
                 for e_id in self.iter_future(era_id, PAST_EVIDENCE_ERAS) {
                     let proposed_blocks = if let Some(era) = self.open_eras.get_mut(&e_id) {
                         era.resolve_evidence_and_mark_faulty(&pub_key)
@@ -1219,6 +1242,20 @@ impl EraSupervisor {
                     })
                 })
                 .collect(),
+            ProtocolOutcome::Metric(metric) => {
+                match metric {
+                    ProtocolMetric::EchoArrival(delay) => self.metrics.echo_arrival(delay),
+                    ProtocolMetric::VoteArrival(delay) => self.metrics.vote_arrival(delay),
+                    ProtocolMetric::SkippedRounds(count) => self.metrics.skipped_rounds(count),
+                }
+                Effects::new()
+            }
+            ProtocolOutcome::MissedRound(pub_key) => {
+                if let Some(era) = self.open_eras.get_mut(&era_id) {
+                    era.record_missed_round(&pub_key);
+                }
+                Effects::new()
+            }
             ProtocolOutcome::WeAreFaulty => Default::default(),
             ProtocolOutcome::DoppelgangerDetected => Default::default(),
             ProtocolOutcome::FttExceeded => effect_builder
@@ -1228,6 +1265,33 @@ impl EraSupervisor {
         }
     }
 
+    /// Records a finality signature by `public_key` for one of the given era's blocks.
+    pub(super) fn handle_finality_signature(&mut self, era_id: EraId, public_key: &PublicKey) {
+        if let Some(era) = self.open_eras.get_mut(&era_id) {
+            era.record_finality_signature(public_key);
+        }
+    }
+
+    /// Stores the validator performance reports of the given era and of the one before it.
+    ///
+    /// The previous era's report is stored again, so that it includes the finality signatures for
+    /// its last blocks that only arrived after its switch block was finalized.
+    fn store_performance_reports<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        era_id: EraId,
+    ) -> Effects<Event> {
+        iter::once(era_id)
+            .chain(era_id.predecessor())
+            .filter_map(|e_id| Some(self.open_eras.get(&e_id)?.performance_report(e_id)))
+            .flat_map(|report| {
+                effect_builder
+                    .put_validator_performance_to_storage(report)
+                    .ignore()
+            })
+            .collect()
+    }
+
     pub(super) fn status(
         &self,
         responder: Responder<Option<(PublicKey, Option<TimeDiff>)>>,
@@ -1241,6 +1305,17 @@ impl EraSupervisor {
         responder.respond(Some((public_key, round_length))).ignore()
     }
 
+    /// Returns the current era and the leaders of its upcoming rounds, or `None` if there is no
+    /// open era yet.
+    pub(super) fn leader_schedule(
+        &self,
+        now: Timestamp,
+    ) -> Option<(EraId, Vec<ScheduledRound<PublicKey>>)> {
+        let (era_id, era) = self.open_eras.iter().last()?;
+        let schedule = era.consensus.leader_schedule(now, LEADER_SCHEDULE_ROUNDS);
+        Some((*era_id, schedule))
+    }
+
     /// Get a reference to the era supervisor's open eras.
     pub(crate) fn open_eras(&self) -> &BTreeMap<EraId, Era> {
         &self.open_eras
@@ -6,15 +6,21 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
+use casper_hashing::Digest;
 use casper_types::{EraId, PublicKey, Timestamp, U512};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::components::consensus::{highway_core::State, ClContext, HighwayProtocol};
+use crate::components::consensus::{
+    highway_core::{state::Replay, State},
+    ClContext, HighwayProtocol,
+};
 
 use super::Era;
 
 /// Debug dump of era used for serialization.
-#[derive(Debug, Serialize)]
+///
+/// A dump written in bincode can be loaded again and replayed offline, see [`EraReplay`].
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct EraDump<'a> {
     /// The era that is being dumped.
     pub(crate) id: EraId,
@@ -27,16 +33,20 @@ pub(crate) struct EraDump<'a> {
     // omitted: pending blocks
     /// Validators that have been faulty in any of the recent BONDED_ERAS switch blocks. This
     /// includes `new_faulty`.
-    pub(crate) faulty: &'a HashSet<PublicKey>,
+    pub(crate) faulty: Cow<'a, HashSet<PublicKey>>,
     /// Validators that are excluded from proposing new blocks.
-    pub(crate) cannot_propose: &'a HashSet<PublicKey>,
+    pub(crate) cannot_propose: Cow<'a, HashSet<PublicKey>>,
     /// Accusations collected in this era so far.
-    pub(crate) accusations: &'a HashSet<PublicKey>,
+    pub(crate) accusations: Cow<'a, HashSet<PublicKey>>,
     /// The validator weights.
-    pub(crate) validators: &'a BTreeMap<PublicKey, U512>,
+    pub(crate) validators: Cow<'a, BTreeMap<PublicKey, U512>>,
 
     /// The state of the highway instance associated with the era.
-    pub(crate) highway_state: &'a State<ClContext>,
+    pub(crate) highway_state: Cow<'a, State<ClContext>>,
+    /// The ID of the era's consensus protocol instance.
+    ///
+    /// This comes last, so that tools reading only the fields above can still parse the dump.
+    pub(crate) instance_id: Digest,
 }
 
 impl<'a> Display for EraDump<'a> {
@@ -60,11 +70,81 @@ impl<'a> EraDump<'a> {
             id: era_id,
             start_time: era.start_time,
             start_height: era.start_height,
-            faulty: &era.faulty,
-            cannot_propose: &era.cannot_propose,
-            accusations: &era.accusations,
-            validators: &era.validators,
-            highway_state: highway.highway().state(),
+            faulty: Cow::Borrowed(&era.faulty),
+            cannot_propose: Cow::Borrowed(&era.cannot_propose),
+            accusations: Cow::Borrowed(&era.accusations),
+            validators: Cow::Borrowed(&era.validators),
+            highway_state: Cow::Borrowed(highway.highway().state()),
+            instance_id: *highway.highway().instance_id(),
         })
     }
 }
+
+/// The result of re-running the Highway state machine on the units of an era dump.
+#[derive(Debug)]
+pub(crate) struct EraReplay {
+    /// The era that was replayed.
+    pub(crate) id: EraId,
+    /// The number of units in the dump.
+    pub(crate) unit_count: usize,
+    /// The number of units that were added again.
+    pub(crate) replayed_count: usize,
+    /// The units that failed validation, with the reason.
+    pub(crate) invalid_units: Vec<(Digest, String)>,
+    /// The units whose dependencies are missing or invalid.
+    pub(crate) unresolved_units: Vec<Digest>,
+    /// Whether the replayed state's panorama matches the dumped one.
+    pub(crate) panorama_matches: bool,
+}
+
+impl EraReplay {
+    /// Adds all units in the dump to a new protocol state with the same parameters, in
+    /// topological order, validating each of them again.
+    pub(crate) fn replay(dump: &EraDump<'_>) -> Self {
+        let Replay {
+            state,
+            invalid,
+            unresolved,
+        } = dump.highway_state.replay(dump.instance_id);
+        EraReplay {
+            id: dump.id,
+            unit_count: dump.highway_state.unit_count(),
+            replayed_count: state.unit_count(),
+            invalid_units: invalid
+                .into_iter()
+                .map(|(hash, err)| (hash, err.to_string()))
+                .collect(),
+            unresolved_units: unresolved,
+            panorama_matches: state.panorama() == dump.highway_state.panorama(),
+        }
+    }
+
+    /// Returns whether the replay reproduced the dumped state without any issues.
+    pub(crate) fn is_consistent(&self) -> bool {
+        self.invalid_units.is_empty() && self.unresolved_units.is_empty() && self.panorama_matches
+    }
+}
+
+impl Display for EraReplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "era {}: replayed {} of {} units, panorama {}",
+            self.id,
+            self.replayed_count,
+            self.unit_count,
+            if self.panorama_matches {
+                "matches"
+            } else {
+                "differs"
+            }
+        )?;
+        for (hash, err) in &self.invalid_units {
+            writeln!(f, "invalid unit {}: {}", hash, err)?;
+        }
+        for hash in &self.unresolved_units {
+            writeln!(f, "unresolved unit {}", hash)?;
+        }
+        Ok(())
+    }
+}
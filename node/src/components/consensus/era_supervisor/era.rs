@@ -8,12 +8,13 @@ use itertools::Itertools;
 use once_cell::sync::Lazy;
 use tracing::{debug, warn};
 
-use casper_types::{PublicKey, Timestamp, U512};
+use casper_types::{EraId, PublicKey, Timestamp, U512};
 
 use crate::components::consensus::{
     cl_context::ClContext,
     consensus_protocol::{ConsensusProtocol, ProposedBlock},
     protocols::{highway::HighwayProtocol, zug::Zug},
+    ValidatorPerformance, ValidatorPerformanceReport,
 };
 
 const CASPER_ENABLE_DETAILED_CONSENSUS_METRICS_ENV_VAR: &str =
@@ -61,6 +62,8 @@ pub struct Era {
     pub(crate) accusations: HashSet<PublicKey>,
     /// The validator weights.
     pub(crate) validators: BTreeMap<PublicKey, U512>,
+    /// The validators' performance in this era so far.
+    pub(crate) performance: BTreeMap<PublicKey, ValidatorPerformance>,
 }
 
 impl Era {
@@ -72,6 +75,10 @@ impl Era {
         cannot_propose: HashSet<PublicKey>,
         validators: BTreeMap<PublicKey, U512>,
     ) -> Self {
+        let performance = validators
+            .keys()
+            .map(|pub_key| (pub_key.clone(), ValidatorPerformance::default()))
+            .collect();
         Era {
             consensus,
             start_time,
@@ -81,6 +88,7 @@ impl Era {
             cannot_propose,
             accusations: HashSet::new(),
             validators,
+            performance,
         }
     }
 
@@ -149,6 +157,35 @@ impl Era {
     pub(crate) fn validators(&self) -> &BTreeMap<PublicKey, U512> {
         &self.validators
     }
+
+    /// Records a finalized block proposed by `pub_key`.
+    pub(crate) fn record_proposed_block(&mut self, pub_key: &PublicKey) {
+        if let Some(performance) = self.performance.get_mut(pub_key) {
+            performance.proposed_blocks = performance.proposed_blocks.saturating_add(1);
+        }
+    }
+
+    /// Records a round led by `pub_key` that did not produce a finalized block.
+    pub(crate) fn record_missed_round(&mut self, pub_key: &PublicKey) {
+        if let Some(performance) = self.performance.get_mut(pub_key) {
+            performance.rounds_missed = performance.rounds_missed.saturating_add(1);
+        }
+    }
+
+    /// Records a finality signature by `pub_key` for one of this era's blocks.
+    pub(crate) fn record_finality_signature(&mut self, pub_key: &PublicKey) {
+        if let Some(performance) = self.performance.get_mut(pub_key) {
+            performance.finality_signatures = performance.finality_signatures.saturating_add(1);
+        }
+    }
+
+    /// Returns the report of the validators' performance in this era so far.
+    pub(crate) fn performance_report(&self, era_id: EraId) -> ValidatorPerformanceReport {
+        ValidatorPerformanceReport {
+            era_id,
+            validators: self.performance.clone(),
+        }
+    }
 }
 
 impl DataSize for Era {
@@ -168,6 +205,7 @@ impl DataSize for Era {
             cannot_propose,
             accusations,
             validators,
+            performance,
         } = self;
 
         // `DataSize` cannot be made object safe due its use of associated constants. We implement
@@ -212,5 +250,6 @@ impl DataSize for Era {
             .saturating_add(cannot_propose.estimate_heap_size())
             .saturating_add(accusations.estimate_heap_size())
             .saturating_add(validators.estimate_heap_size())
+            .saturating_add(performance.estimate_heap_size())
     }
 }
@@ -33,13 +33,13 @@
 mod test_macros;
 
 pub(crate) mod active_validator;
+pub(crate) mod evidence;
 pub mod finality_detector;
 pub mod highway;
 pub(crate) mod state;
 pub(super) mod synchronizer;
 
 mod endorsement;
-mod evidence;
 #[cfg(test)]
 pub(crate) mod highway_testing;
 
@@ -134,6 +134,21 @@ impl<C: Context> Fault<C> {
     }
 }
 
+/// The outcome of re-adding all units of a protocol state to a new one.
+#[derive(Debug)]
+pub(crate) struct Replay<C>
+where
+    C: Context,
+{
+    /// The state containing all units that were successfully added again.
+    pub(crate) state: State<C>,
+    /// The units that failed validation, with the reason.
+    pub(crate) invalid: Vec<(C::Hash, UnitError)>,
+    /// The units that could not be added because some of their dependencies are missing or
+    /// invalid.
+    pub(crate) unresolved: Vec<C::Hash>,
+}
+
 /// A passive instance of the Highway protocol, containing its local state.
 ///
 /// Both observers and active validators must instantiate this, pass in all incoming vertices from
@@ -901,6 +916,70 @@ impl<C: Context> State<C> {
         result
     }
 
+    /// Adds all units of this state again to a new state with the same parameters and faults,
+    /// validating each of them on the way, so that the protocol state machine can be re-run e.g.
+    /// on a state loaded from a dump.
+    ///
+    /// Units are added in topological order. Faults are copied upfront, since the evidence may
+    /// have been received separately instead of being derived from the units.
+    pub(crate) fn replay(&self, instance_id: C::InstanceId) -> Replay<C> {
+        let mut state = State {
+            params: self.params.clone(),
+            weights: self.weights.clone(),
+            leader_sequence: self.leader_sequence.clone(),
+            units: HashMap::new(),
+            blocks: HashMap::new(),
+            faults: self.faults.clone(),
+            panorama: Panorama::new(self.weights.len()),
+            endorsements: HashMap::new(),
+            incomplete_endorsements: HashMap::new(),
+            pings: iter::repeat(self.params.start_timestamp())
+                .take(self.weights.len())
+                .collect(),
+            clock: Clock::new(),
+        };
+        for idx in self.faults.keys() {
+            state.panorama[*idx] = Observation::Faulty;
+        }
+        let mut pending: Vec<SignedWireUnit<C>> = self
+            .units
+            .iter()
+            .sorted_by_key(|(hash, unit)| (unit.timestamp, unit.seq_number, **hash))
+            .filter_map(|(hash, _)| self.wire_unit(hash, instance_id))
+            .collect();
+        let mut invalid = Vec::new();
+        loop {
+            let count = pending.len();
+            pending.retain(|swunit| {
+                if swunit
+                    .wire_unit()
+                    .panorama
+                    .missing_dependency(&state)
+                    .is_some()
+                {
+                    return true; // Retry once the dependencies have been added.
+                }
+                match state
+                    .pre_validate_unit(swunit)
+                    .and_then(|()| state.validate_unit(swunit))
+                {
+                    Ok(()) => state.add_valid_unit(swunit.clone()),
+                    Err(err) => invalid.push((swunit.hash(), err)),
+                }
+                false
+            });
+            if pending.len() == count {
+                break;
+            }
+        }
+        let unresolved = pending.iter().map(SignedWireUnit::hash).collect();
+        Replay {
+            state,
+            invalid,
+            unresolved,
+        }
+    }
+
     /// Drops all state other than evidence.
     pub(crate) fn retain_evidence_only(&mut self) {
         self.units.clear();
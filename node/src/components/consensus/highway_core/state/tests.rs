@@ -865,6 +865,25 @@ fn retain_evidence_only() -> Result<(), AddUnitError<TestContext>> {
     Ok(())
 }
 
+#[test]
+fn replay() -> Result<(), AddUnitError<TestContext>> {
+    let mut state = State::new_test(WEIGHTS, 0);
+
+    let a0 = add_unit!(state, ALICE, 0xA; N, N, N)?;
+    let b0 = add_unit!(state, BOB, 0xB; a0, N, N)?;
+    let c0 = add_unit!(state, CAROL, 0xC; a0, b0, N)?;
+    let _a0_prime = add_unit!(state, ALICE, 0xA2; N, N, N)?;
+    assert_eq!(&panorama!(F, b0, c0), state.panorama());
+
+    let replay = state.replay(TEST_INSTANCE_ID);
+    assert!(replay.invalid.is_empty());
+    assert!(replay.unresolved.is_empty());
+    assert_eq!(4, replay.state.unit_count());
+    assert_eq!(state.panorama(), replay.state.panorama());
+    assert!(replay.state.has_evidence(ALICE));
+    Ok(())
+}
+
 #[test]
 fn test_log2() {
     assert_eq!(0, log2(0));
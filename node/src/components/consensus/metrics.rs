@@ -1,14 +1,42 @@
-use prometheus::{Gauge, IntGauge, Registry};
+use prometheus::{Gauge, Histogram, IntCounter, IntGauge, Registry};
 
-use casper_types::Timestamp;
+use casper_types::{TimeDiff, Timestamp};
 
-use crate::{types::FinalizedBlock, unregister_metric};
+use crate::{types::FinalizedBlock, unregister_metric, utils};
+
+const FINALIZATION_LATENCY_NAME: &str = "consensus_finalization_latency_seconds";
+const FINALIZATION_LATENCY_HELP: &str =
+    "duration (in sec) between a block's timestamp and its finalization";
+const ECHO_ARRIVAL_NAME: &str = "consensus_echo_arrival_seconds";
+const ECHO_ARRIVAL_HELP: &str =
+    "duration (in sec) between a round's proposal timestamp and the arrival of an echo";
+const VOTE_ARRIVAL_NAME: &str = "consensus_vote_arrival_seconds";
+const VOTE_ARRIVAL_HELP: &str =
+    "duration (in sec) between a round's proposal timestamp and the arrival of a vote";
+
+// We use exponential buckets to observe the consensus latencies.
+// Buckets start at 10ms and double 12 times, covering durations up to about 20s.
+const EXPONENTIAL_BUCKET_START: f64 = 0.01;
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+const EXPONENTIAL_BUCKET_COUNT: usize = 12;
 
 /// Network metrics to track Consensus
 #[derive(Debug)]
 pub(super) struct Metrics {
     /// Gauge to track time between proposal and finalization.
     finalization_time: Gauge,
+    /// Histogram of the time between proposal and finalization.
+    finalization_latency: Histogram,
+    /// Histogram of the time between a round's proposal and the arrival of its echoes.
+    echo_arrival: Histogram,
+    /// Histogram of the time between a round's proposal and the arrival of its votes.
+    vote_arrival: Histogram,
+    /// Number of rounds that were skipped, i.e. that did not finalize a block.
+    skipped_rounds: IntCounter,
+    /// The current round length, in milliseconds.
+    round_length: IntGauge,
+    /// Number of times the round length changed.
+    round_length_changes: IntCounter,
     /// Amount of finalized blocks.
     finalized_block_count: IntGauge,
     /// Timestamp of the most recently accepted block payload.
@@ -23,10 +51,28 @@ pub(super) struct Metrics {
 
 impl Metrics {
     pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let buckets = prometheus::exponential_buckets(
+            EXPONENTIAL_BUCKET_START,
+            EXPONENTIAL_BUCKET_FACTOR,
+            EXPONENTIAL_BUCKET_COUNT,
+        )?;
+
         let finalization_time = Gauge::new(
             "finalization_time",
             "the amount of time, in milliseconds, between proposal and finalization of the latest finalized block",
         )?;
+        let skipped_rounds = IntCounter::new(
+            "consensus_skipped_rounds_total",
+            "the number of consensus rounds that did not finalize a block",
+        )?;
+        let round_length = IntGauge::new(
+            "consensus_round_length",
+            "the current consensus round length, in milliseconds",
+        )?;
+        let round_length_changes = IntCounter::new(
+            "consensus_round_length_changes_total",
+            "the number of times the consensus round length changed",
+        )?;
         let finalized_block_count =
             IntGauge::new("amount_of_blocks", "the number of blocks finalized so far")?;
         let time_of_last_proposed_block = IntGauge::new(
@@ -40,12 +86,36 @@ impl Metrics {
         let consensus_current_era =
             IntGauge::new("consensus_current_era", "the current era in consensus")?;
         registry.register(Box::new(finalization_time.clone()))?;
+        registry.register(Box::new(skipped_rounds.clone()))?;
+        registry.register(Box::new(round_length.clone()))?;
+        registry.register(Box::new(round_length_changes.clone()))?;
         registry.register(Box::new(finalized_block_count.clone()))?;
         registry.register(Box::new(consensus_current_era.clone()))?;
         registry.register(Box::new(time_of_last_proposed_block.clone()))?;
         registry.register(Box::new(time_of_last_finalized_block.clone()))?;
         Ok(Metrics {
             finalization_time,
+            finalization_latency: utils::register_histogram_metric(
+                registry,
+                FINALIZATION_LATENCY_NAME,
+                FINALIZATION_LATENCY_HELP,
+                buckets.clone(),
+            )?,
+            echo_arrival: utils::register_histogram_metric(
+                registry,
+                ECHO_ARRIVAL_NAME,
+                ECHO_ARRIVAL_HELP,
+                buckets.clone(),
+            )?,
+            vote_arrival: utils::register_histogram_metric(
+                registry,
+                VOTE_ARRIVAL_NAME,
+                VOTE_ARRIVAL_HELP,
+                buckets,
+            )?,
+            skipped_rounds,
+            round_length,
+            round_length_changes,
             finalized_block_count,
             time_of_last_proposed_block,
             time_of_last_finalized_block,
@@ -56,8 +126,11 @@ impl Metrics {
 
     /// Updates the metrics based on a newly finalized block.
     pub(super) fn finalized_block(&mut self, finalized_block: &FinalizedBlock) {
-        let time_since_block_payload = finalized_block.timestamp().elapsed().millis() as f64;
-        self.finalization_time.set(time_since_block_payload);
+        let time_since_block_payload = finalized_block.timestamp().elapsed();
+        self.finalization_time
+            .set(time_since_block_payload.millis() as f64);
+        self.finalization_latency
+            .observe(time_since_block_payload.millis() as f64 / 1000.0);
         self.time_of_last_finalized_block
             .set(finalized_block.timestamp().millis() as i64);
         self.finalized_block_count
@@ -69,11 +142,47 @@ impl Metrics {
         self.time_of_last_proposed_block
             .set(Timestamp::now().millis() as i64);
     }
+
+    /// Records the arrival of an echo, the given duration after its round's proposal.
+    pub(super) fn echo_arrival(&mut self, delay: TimeDiff) {
+        self.echo_arrival.observe(delay.millis() as f64 / 1000.0);
+    }
+
+    /// Records the arrival of a vote, the given duration after its round's proposal.
+    pub(super) fn vote_arrival(&mut self, delay: TimeDiff) {
+        self.vote_arrival.observe(delay.millis() as f64 / 1000.0);
+    }
+
+    /// Records the given number of rounds that were skipped.
+    pub(super) fn skipped_rounds(&mut self, count: u32) {
+        self.skipped_rounds.inc_by(u64::from(count));
+    }
+
+    /// Updates the current round length, counting it as a change if it differs from the last one.
+    pub(super) fn update_round_length(&mut self, maybe_round_length: Option<TimeDiff>) {
+        let round_length = match maybe_round_length {
+            Some(round_length) => round_length.millis() as i64,
+            None => return,
+        };
+        let previous = self.round_length.get();
+        if previous != round_length {
+            if previous != 0 {
+                self.round_length_changes.inc();
+            }
+            self.round_length.set(round_length);
+        }
+    }
 }
 
 impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.finalization_time);
+        unregister_metric!(self.registry, self.finalization_latency);
+        unregister_metric!(self.registry, self.echo_arrival);
+        unregister_metric!(self.registry, self.vote_arrival);
+        unregister_metric!(self.registry, self.skipped_rounds);
+        unregister_metric!(self.registry, self.round_length);
+        unregister_metric!(self.registry, self.round_length_changes);
         unregister_metric!(self.registry, self.finalized_block_count);
         unregister_metric!(self.registry, self.consensus_current_era);
         unregister_metric!(self.registry, self.time_of_last_finalized_block);
@@ -23,23 +23,25 @@ use crate::{
     components::consensus::{
         config::Config,
         consensus_protocol::{
-            BlockContext, ConsensusProtocol, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome,
+            ProtocolOutcomes, ScheduledRound,
         },
         era_supervisor::SerializedMessage,
         highway_core::{
             active_validator::Effect as AvEffect,
+            evidence::Evidence,
             finality_detector::{FinalityDetector, FttExceeded},
             highway::{
-                Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, ValidVertex,
-                Vertex, VertexError,
+                Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, SignedWireUnit,
+                ValidVertex, Vertex, VertexError,
             },
-            state::{IndexObservation, IndexPanorama, Observation},
+            state::{self, IndexObservation, IndexPanorama, Observation},
             synchronizer::Synchronizer,
         },
         protocols,
         traits::{ConsensusValueT, Context},
         utils::ValidatorIndex,
-        ActionId, TimerId,
+        ActionId, ConflictingMessage, TimerId,
     },
     types::{Chainspec, NodeId},
     NodeRng,
@@ -233,7 +235,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             AvEffect::NewVertex(vv) => {
                 self.log_unit_size(vv.inner(), "sending new unit");
                 self.calculate_round_length(&vv, now);
-                self.process_new_vertex(vv)
+                self.process_new_vertex(vv, now)
             }
             AvEffect::ScheduleTimer(timestamp) => {
                 vec![ProtocolOutcome::ScheduleTimer(
@@ -251,7 +253,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         }
     }
 
-    fn process_new_vertex(&mut self, vv: ValidVertex<C>) -> ProtocolOutcomes<C> {
+    fn process_new_vertex(&mut self, vv: ValidVertex<C>, now: Timestamp) -> ProtocolOutcomes<C> {
         let mut outcomes = Vec::new();
         if let Vertex::Evidence(ev) = vv.inner() {
             let v_id = self
@@ -266,13 +268,28 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         outcomes.push(ProtocolOutcome::CreatedGossipMessage(
             SerializedMessage::from_message(&msg),
         ));
-        outcomes.extend(self.detect_finality());
+        outcomes.extend(self.detect_finality(now));
         outcomes
     }
 
-    fn detect_finality(&mut self) -> ProtocolOutcomes<C> {
+    fn detect_finality(&mut self, now: Timestamp) -> ProtocolOutcomes<C> {
+        let prev_timestamp = self
+            .finality_detector
+            .last_finalized()
+            .map(|hash| self.highway.state().unit(hash).timestamp);
         let faulty_weight = match self.finality_detector.run(&self.highway) {
-            Ok(iter) => return iter.map(ProtocolOutcome::FinalizedBlock).collect(),
+            Ok(iter) => {
+                let finalized_blocks: Vec<_> = iter.collect();
+                let timestamps = finalized_blocks.iter().map(|fb| fb.timestamp).collect_vec();
+                self.round_success_meter.blocks_finalized(&timestamps, now);
+                let missed_round_leaders =
+                    self.missed_round_leaders(prev_timestamp, &finalized_blocks);
+                return finalized_blocks
+                    .into_iter()
+                    .map(ProtocolOutcome::FinalizedBlock)
+                    .chain(missed_round_leaders.map(ProtocolOutcome::MissedRound))
+                    .collect();
+            }
             Err(FttExceeded(weight)) => weight.0,
         };
         error!(
@@ -284,6 +301,45 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         vec![ProtocolOutcome::FttExceeded]
     }
 
+    /// Returns the leaders of the rounds between the block finalized at `prev_timestamp` and the
+    /// last one of the `finalized_blocks` that did not produce a finalized block.
+    ///
+    /// The round length of the last finalized block is assumed for all these rounds.
+    fn missed_round_leaders(
+        &self,
+        prev_timestamp: Option<Timestamp>,
+        finalized_blocks: &[FinalizedBlock<C>],
+    ) -> impl Iterator<Item = C::ValidatorId> + '_ {
+        let state = self.highway.state();
+        let maybe_last_unit = finalized_blocks
+            .last()
+            .and(self.finality_detector.last_finalized())
+            .map(|hash| state.unit(hash));
+        let (round_len, end) = match maybe_last_unit {
+            Some(unit) if unit.round_len().millis() > 0 => (unit.round_len(), unit.timestamp),
+            _ => (TimeDiff::from_millis(1), Timestamp::zero()),
+        };
+        let start = match prev_timestamp {
+            Some(timestamp) => state::round_id(timestamp, round_len) + round_len,
+            None => {
+                let era_start = state.params().start_timestamp();
+                let first_round_id = state::round_id(era_start, round_len);
+                if first_round_id < era_start {
+                    first_round_id + round_len
+                } else {
+                    first_round_id
+                }
+            }
+        };
+        let finalized_timestamps: HashSet<Timestamp> =
+            finalized_blocks.iter().map(|fb| fb.timestamp).collect();
+        iter::successors(Some(start), move |round_id| Some(*round_id + round_len))
+            .take_while(move |round_id| *round_id < end)
+            .filter(move |round_id| !finalized_timestamps.contains(round_id))
+            .filter_map(move |round_id| self.highway.validators().id(state.leader(round_id)))
+            .cloned()
+    }
+
     /// Adds the given vertices to the protocol state, if possible, or requests missing
     /// dependencies or validation. Recursively schedules events to add everything that is
     /// unblocked now.
@@ -360,7 +416,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         // waiting for are now satisfied, and try adding the pending vertices as well.
         outcomes.extend(self.synchronizer.remove_satisfied_deps(&self.highway));
         // Check whether any new blocks were finalized.
-        outcomes.extend(self.detect_finality());
+        outcomes.extend(self.detect_finality(now));
         outcomes
     }
 
@@ -1022,7 +1078,7 @@ where
                 .flat_map(|(vv, _)| self.add_valid_vertex(vv, now))
                 .collect_vec();
             outcomes.extend(self.synchronizer.remove_satisfied_deps(&self.highway));
-            outcomes.extend(self.detect_finality());
+            outcomes.extend(self.detect_finality(now));
             outcomes
         } else {
             // TODO: Report proposer as faulty?
@@ -1102,6 +1158,38 @@ where
     }
 
     /// Sets the pause status: While paused we don't create any new units, just pings.
+    fn conflicting_messages(&self, vid: &C::ValidatorId) -> Vec<ConflictingMessage<C>> {
+        let evidence = match self
+            .highway
+            .validators()
+            .get_index(vid)
+            .and_then(|vidx| self.highway.state().maybe_evidence(vidx))
+        {
+            Some(evidence) => evidence,
+            None => return vec![],
+        };
+        let unit_msg = |swunit: &SignedWireUnit<C>| ConflictingMessage::Unit {
+            hash: swunit.hash(),
+            seq_number: swunit.wire_unit().seq_number,
+            timestamp: swunit.wire_unit().timestamp,
+            signature: swunit.signature,
+        };
+        match evidence {
+            Evidence::Equivocation(unit1, unit2) => vec![unit_msg(unit1), unit_msg(unit2)],
+            Evidence::Endorsements {
+                endorsement1,
+                endorsement2,
+                ..
+            } => [endorsement1, endorsement2]
+                .iter()
+                .map(|endorsement| ConflictingMessage::Endorsement {
+                    unit: *endorsement.unit(),
+                    signature: *endorsement.signature(),
+                })
+                .collect(),
+        }
+    }
+
     fn set_paused(&mut self, paused: bool, _now: Timestamp) -> ProtocolOutcomes<C> {
         self.highway.set_paused(paused);
         vec![]
@@ -1126,6 +1214,29 @@ where
     fn next_round_length(&self) -> Option<TimeDiff> {
         self.highway.next_round_length()
     }
+
+    fn leader_schedule(&self, now: Timestamp, count: usize) -> Vec<ScheduledRound<C::ValidatorId>> {
+        let state = self.highway.state();
+        // Rounds are identified by their start time, so the schedule depends on the round length.
+        // We assume our own, or the initial one if we are not an active validator.
+        let round_len = self
+            .highway
+            .next_round_length()
+            .filter(|round_len| round_len.millis() > 0)
+            .unwrap_or_else(|| state.params().init_round_len());
+        let start = state::round_id(now.max(state.params().start_timestamp()), round_len);
+        iter::successors(Some(start), |round_id| Some(*round_id + round_len))
+            .take(count)
+            .filter_map(|round_id| {
+                let leader = self.highway.validators().id(state.leader(round_id))?;
+                Some(ScheduledRound {
+                    round_id: round_id.millis(),
+                    start: Some(round_id),
+                    leader: leader.clone(),
+                })
+            })
+            .collect()
+    }
 }
 
 /// Maximum possible rounds in one era.
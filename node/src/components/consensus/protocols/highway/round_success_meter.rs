@@ -1,9 +1,14 @@
 #[cfg(test)]
 mod tests;
 
-use std::{cmp::max, collections::VecDeque, mem};
+use std::{
+    cmp::max,
+    collections::{BTreeSet, VecDeque},
+    mem,
+};
 
 use datasize::DataSize;
+use num_rational::Ratio;
 use tracing::{error, trace};
 
 use casper_types::{TimeDiff, Timestamp};
@@ -25,6 +30,14 @@ where
     // store whether a particular round was successful
     // index 0 is the last handled round, 1 is the second-to-last etc.
     rounds: VecDeque<bool>,
+    // the time between the timestamps of recent proposals and their finalization
+    // index 0 is the most recently finalized proposal
+    finalization_latencies: VecDeque<TimeDiff>,
+    // store whether a recent proposal was orphaned, i.e. a later block was finalized instead
+    // index 0 is the most recently decided proposal
+    orphans: VecDeque<bool>,
+    // the timestamps of proposals that have been neither finalized nor orphaned yet
+    undecided_proposals: BTreeSet<Timestamp>,
     current_round_id: Timestamp,
     proposals: Vec<C::Hash>,
     min_round_len: TimeDiff,
@@ -44,6 +57,9 @@ impl<C: Context> RoundSuccessMeter<C> {
         let current_round_id = state::round_id(timestamp, round_len);
         Self {
             rounds: VecDeque::with_capacity(config.num_rounds_to_consider as usize),
+            finalization_latencies: VecDeque::new(),
+            orphans: VecDeque::new(),
+            undecided_proposals: BTreeSet::new(),
             current_round_id,
             proposals: Vec::new(),
             min_round_len,
@@ -55,6 +71,9 @@ impl<C: Context> RoundSuccessMeter<C> {
 
     fn change_length(&mut self, new_len: TimeDiff, timestamp: Timestamp) {
         self.rounds = VecDeque::with_capacity(self.config.num_rounds_to_consider as usize);
+        // Latencies and orphans observed with the old length say little about the new one.
+        self.finalization_latencies.clear();
+        self.orphans.clear();
         self.current_round_len = new_len;
         self.current_round_id = state::round_id(timestamp, new_len);
         self.proposals = Vec::new();
@@ -87,6 +106,7 @@ impl<C: Context> RoundSuccessMeter<C> {
                 "adding a proposal"
             );
             self.proposals.push(proposal_h);
+            self.undecided_proposals.insert(timestamp);
         } else {
             trace!(
                 %self.current_round_id,
@@ -159,10 +179,36 @@ impl<C: Context> RoundSuccessMeter<C> {
         new_len
     }
 
+    /// Registers newly finalized blocks with the given timestamps. All undecided proposals up to
+    /// the latest of them are now decided: they are either among the finalized blocks, or orphaned.
+    pub fn blocks_finalized(&mut self, timestamps: &[Timestamp], now: Timestamp) {
+        let latest = match timestamps.iter().max() {
+            Some(latest) => *latest,
+            None => return,
+        };
+        let still_undecided = self
+            .undecided_proposals
+            .split_off(&(latest + TimeDiff::from_millis(1)));
+        for timestamp in mem::replace(&mut self.undecided_proposals, still_undecided) {
+            if timestamps.contains(&timestamp) {
+                self.finalization_latencies
+                    .push_front(now.saturating_diff(timestamp));
+                self.orphans.push_front(false);
+            } else {
+                trace!(timestamp = timestamp.millis(), "proposal orphaned");
+                self.orphans.push_front(true);
+            }
+        }
+        self.clean_old_rounds();
+    }
+
     /// Returns an instance of `Self` for the new era: resetting the counters where appropriate.
     pub fn next_era(&self, timestamp: Timestamp) -> Self {
         Self {
             rounds: self.rounds.clone(),
+            finalization_latencies: self.finalization_latencies.clone(),
+            orphans: self.orphans.clone(),
+            undecided_proposals: Default::default(),
             current_round_id: state::round_id(timestamp, self.current_round_len),
             proposals: Default::default(),
             min_round_len: self.min_round_len,
@@ -176,19 +222,56 @@ impl<C: Context> RoundSuccessMeter<C> {
         while self.rounds.len() as u64 > self.config.num_rounds_to_consider {
             self.rounds.pop_back();
         }
+        let max_len = self.config.num_rounds_to_consider as usize;
+        self.finalization_latencies.truncate(max_len);
+        self.orphans.truncate(max_len);
     }
 
     fn count_failures(&self) -> usize {
         self.rounds.iter().filter(|&success| !success).count()
     }
 
+    /// Returns whether we have enough recent data about latencies and orphans, and it indicates
+    /// that the current round length is too short for the network to keep up.
+    fn is_network_lagging(&self) -> bool {
+        let num_samples = self.config.num_rounds_to_consider;
+        if num_samples == 0 {
+            return false;
+        }
+        if self.finalization_latencies.len() as u64 == num_samples {
+            let total_latency_ms = self
+                .finalization_latencies
+                .iter()
+                .fold(0u64, |total, latency| {
+                    total.saturating_add(latency.millis())
+                });
+            let max_latency = self
+                .current_round_len
+                .saturating_mul(self.config.max_finalization_latency_rounds);
+            if total_latency_ms > max_latency.saturating_mul(num_samples).millis() {
+                trace!(%total_latency_ms, "finalization latency too high");
+                return true;
+            }
+        }
+        if self.orphans.len() as u64 == num_samples {
+            let num_orphans = self.orphans.iter().filter(|&orphan| *orphan).count() as u64;
+            if Ratio::new(num_orphans, num_samples) > self.config.max_orphan_rate {
+                trace!(%num_orphans, "orphan rate too high");
+                return true;
+            }
+        }
+        false
+    }
+
     /// Returns the round length to be used in the next round, based on the previously used round
-    /// length and the current counts of successes and failures.
+    /// length, the current counts of successes and failures, and the recent finalization latencies
+    /// and orphan rate.
     pub(super) fn new_length(&self) -> TimeDiff {
         let current_round_index = round_index(self.current_round_id, self.current_round_len);
         let num_failures = self.count_failures() as u64;
+        let is_network_lagging = self.is_network_lagging();
         #[allow(clippy::integer_arithmetic)] // The acceleration_parameter is not zero.
-        if num_failures > self.config.max_failed_rounds()
+        if (num_failures > self.config.max_failed_rounds() || is_network_lagging)
             && self.current_round_len * 2 <= self.max_round_len
         {
             self.current_round_len * 2
@@ -197,6 +280,7 @@ impl<C: Context> RoundSuccessMeter<C> {
             // we will only accelerate if we collected data about enough rounds
             && self.rounds.len() as u64 == self.config.num_rounds_to_consider
             && num_failures < self.config.max_failures_for_acceleration()
+            && !is_network_lagging
         {
             self.current_round_len / 2
         } else {
@@ -20,6 +20,11 @@ pub(crate) const ACCELERATION_PARAMETER: u64 = 40;
 /// The required quorum in a summit we will look for to check if a round was successful is
 /// determined by this FTT.
 pub(crate) const THRESHOLD: u64 = 1;
+/// If the average time between a proposal's timestamp and its finalization exceeds this many
+/// rounds, we increase our round length.
+pub(crate) const MAX_FINALIZATION_LATENCY_ROUNDS: u64 = 4;
+/// If more than this fraction of the recent proposals was orphaned, we increase our round length.
+pub(crate) const MAX_ORPHAN_RATE: (u64, u64) = (1, 5);
 
 #[cfg(test)]
 pub(crate) const MAX_FAILED_ROUNDS: usize = NUM_ROUNDS_TO_CONSIDER - NUM_ROUNDS_SLOWDOWN - 1;
@@ -32,6 +37,9 @@ pub struct Config {
     pub acceleration_parameter: u64,
     #[data_size(skip)]
     pub acceleration_ftt: Ratio<u64>,
+    pub max_finalization_latency_rounds: u64,
+    #[data_size(skip)]
+    pub max_orphan_rate: Ratio<u64>,
 }
 
 impl Default for Config {
@@ -42,6 +50,8 @@ impl Default for Config {
             num_rounds_speedup: NUM_ROUNDS_SPEEDUP as u64,
             acceleration_parameter: ACCELERATION_PARAMETER,
             acceleration_ftt: Ratio::new(THRESHOLD, 100),
+            max_finalization_latency_rounds: MAX_FINALIZATION_LATENCY_ROUNDS,
+            max_orphan_rate: Ratio::new(MAX_ORPHAN_RATE.0, MAX_ORPHAN_RATE.1),
         }
     }
 }
@@ -1,4 +1,7 @@
-use config::{Config, ACCELERATION_PARAMETER, MAX_FAILED_ROUNDS, NUM_ROUNDS_TO_CONSIDER};
+use config::{
+    Config, ACCELERATION_PARAMETER, MAX_FAILED_ROUNDS, MAX_FINALIZATION_LATENCY_ROUNDS,
+    NUM_ROUNDS_TO_CONSIDER,
+};
 
 use casper_types::{TimeDiff, Timestamp};
 
@@ -107,3 +110,72 @@ fn new_length_can_not_speed_up_because_min_round_len() {
     }
     assert_eq!(round_success_meter.new_length(), TEST_MIN_ROUND_LEN);
 }
+
+#[test]
+fn new_length_slow_down_because_orphans() {
+    let mut round_success_meter: super::RoundSuccessMeter<ClContext> =
+        super::RoundSuccessMeter::new(
+            TEST_ROUND_LEN,
+            TEST_MIN_ROUND_LEN,
+            TEST_MAX_ROUND_LEN,
+            Timestamp::now(),
+            Config::default(),
+        );
+    // If more than a fifth of the recent proposals didn't get finalized in the end, slow down.
+    let mut orphans = vec![false; NUM_ROUNDS_TO_CONSIDER];
+    orphans[..NUM_ROUNDS_TO_CONSIDER / 5].fill(true);
+    round_success_meter.orphans = orphans.clone().into();
+    assert_eq!(round_success_meter.new_length(), TEST_ROUND_LEN);
+    orphans[NUM_ROUNDS_TO_CONSIDER / 5] = true;
+    round_success_meter.orphans = orphans.into();
+    assert_eq!(round_success_meter.new_length(), TEST_ROUND_LEN * 2);
+}
+
+#[test]
+fn new_length_slow_down_because_latency() {
+    let mut round_success_meter: super::RoundSuccessMeter<ClContext> =
+        super::RoundSuccessMeter::new(
+            TEST_ROUND_LEN,
+            TEST_MIN_ROUND_LEN,
+            TEST_MAX_ROUND_LEN,
+            Timestamp::now(),
+            Config::default(),
+        );
+    let max_latency = TEST_ROUND_LEN * MAX_FINALIZATION_LATENCY_ROUNDS;
+    round_success_meter.finalization_latencies = vec![max_latency; NUM_ROUNDS_TO_CONSIDER].into();
+    assert_eq!(round_success_meter.new_length(), TEST_ROUND_LEN);
+    round_success_meter.finalization_latencies[0] += TimeDiff::from_millis(1);
+    assert_eq!(round_success_meter.new_length(), TEST_ROUND_LEN * 2);
+}
+
+#[test]
+fn blocks_finalized_decides_earlier_proposals() {
+    let start = Timestamp::zero() + TEST_ROUND_LEN * 100;
+    let mut round_success_meter: super::RoundSuccessMeter<ClContext> =
+        super::RoundSuccessMeter::new(
+            TEST_ROUND_LEN,
+            TEST_MIN_ROUND_LEN,
+            TEST_MAX_ROUND_LEN,
+            start,
+            Config::default(),
+        );
+    let round_id = |i: u64| start + TEST_ROUND_LEN * i;
+    round_success_meter.undecided_proposals = (0..4).map(round_id).collect();
+
+    // The block from round 2 is finalized, so the one from round 1 is orphaned. Round 3 is still
+    // undecided.
+    let now = round_id(3);
+    round_success_meter.blocks_finalized(&[round_id(0), round_id(2)], now);
+    assert_eq!(round_success_meter.orphans, vec![false, true, false]);
+    assert_eq!(
+        round_success_meter.finalization_latencies,
+        vec![TEST_ROUND_LEN, TEST_ROUND_LEN * 3]
+    );
+    assert_eq!(
+        round_success_meter
+            .undecided_proposals
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![round_id(3)]
+    );
+}
@@ -2,6 +2,7 @@
 #![allow(clippy::integer_arithmetic)]
 
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     fs::File,
     io::Write,
@@ -465,16 +466,18 @@ impl ConsensusEnvironment {
         let dump = EraDump {
             id: 0.into(),
             start_time: 0.into(),
-            accusations: &Default::default(),
-            cannot_propose: &Default::default(),
-            faulty: &Default::default(),
+            accusations: Default::default(),
+            cannot_propose: Default::default(),
+            faulty: Default::default(),
             start_height: 0,
-            validators: &self
-                .validators
-                .iter()
-                .map(|(pub_key, (_, weight))| (pub_key.clone(), (*weight).into()))
-                .collect(),
-            highway_state: self.highway().highway().state(),
+            validators: Cow::Owned(
+                self.validators
+                    .iter()
+                    .map(|(pub_key, (_, weight))| (pub_key.clone(), (*weight).into()))
+                    .collect(),
+            ),
+            highway_state: Cow::Borrowed(self.highway().highway().state()),
+            instance_id: *self.highway().highway().instance_id(),
         };
 
         let mut file = File::create("/tmp/consensus.dump").unwrap();
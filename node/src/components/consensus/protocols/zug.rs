@@ -52,6 +52,11 @@
 //! A `SyncRequest` message containing information about a random part of the local protocol state
 //! is periodically sent to a random peer. The peer compares that to its local state, and responds
 //! with all signed messages that it has and the other is missing.
+//!
+//! In addition, rounds that already have an accepted proposal but are still missing the votes to
+//! be committed are repaired actively: a `SyncRequest` about the oldest such round is sent to a
+//! random peer periodically, so that the missing echoes and votes are pulled in without waiting
+//! for the random sync to pick that round.
 
 pub(crate) mod config;
 #[cfg(test)]
@@ -87,14 +92,14 @@ use crate::{
     components::consensus::{
         config::Config,
         consensus_protocol::{
-            BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome,
-            ProtocolOutcomes, TerminalBlockData,
+            BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolMetric,
+            ProtocolOutcome, ProtocolOutcomes, ScheduledRound, TerminalBlockData,
         },
         era_supervisor::SerializedMessage,
         protocols,
         traits::{ConsensusValueT, Context},
         utils::{ValidatorIndex, ValidatorMap, Validators, Weight},
-        ActionId, LeaderSequence, TimerId,
+        ActionId, ConflictingMessage, LeaderSequence, TimerId,
     },
     types::{Chainspec, NodeId},
     utils, NodeRng,
@@ -115,6 +120,8 @@ const TIMER_ID_SYNC_PEER: TimerId = TimerId(0);
 const TIMER_ID_UPDATE: TimerId = TimerId(1);
 /// The timer for logging inactive validators.
 const TIMER_ID_LOG_PARTICIPATION: TimerId = TimerId(2);
+/// The timer for requesting missing echoes and votes of accepted but uncommitted rounds.
+const TIMER_ID_REPAIR: TimerId = TimerId(3);
 
 /// The maximum number of future rounds we instantiate if we get messages from rounds that we
 /// haven't started yet.
@@ -447,6 +454,50 @@ impl<C: Context + 'static> Zug<C> {
         outcomes
     }
 
+    /// Returns the oldest round that has an accepted proposal but is not committed yet, i.e. that
+    /// is still missing votes before it can be finalized.
+    fn oldest_uncommitted_accepted_round(&self) -> Option<RoundId> {
+        (self.first_non_finalized_round_id..self.current_round).find(|round_id| {
+            self.has_accepted_proposal(*round_id) && !self.is_committed_round(*round_id)
+        })
+    }
+
+    /// Requests the missing echoes and votes of the oldest accepted but uncommitted round from a
+    /// random peer.
+    fn handle_repair_timer(&self, now: Timestamp) -> ProtocolOutcomes<C> {
+        if self.evidence_only || self.finalized_switch_block() {
+            return vec![]; // Era has ended. No further progress is expected.
+        }
+        let mut outcomes = vec![];
+        if let Some(round_id) = self.oldest_uncommitted_accepted_round() {
+            // Start the bit field at the first validator we are missing a vote from, so that it
+            // is covered even if there are more than 128 validators.
+            let first_validator_idx = self
+                .validators
+                .enumerate_ids()
+                .map(|(vidx, _)| vidx)
+                .find(|vidx| !self.faults.contains_key(vidx) && !self.has_voted(round_id, *vidx))
+                .unwrap_or(ValidatorIndex(0));
+            debug!(
+                our_idx = self.our_idx(),
+                instance_id = ?self.instance_id(),
+                round_id,
+                "requesting missing votes for accepted round",
+            );
+            let payload = self.create_sync_request(first_validator_idx, round_id);
+            outcomes.push(ProtocolOutcome::CreatedRequestToRandomPeer(
+                SerializedMessage::from_message(&payload),
+            ));
+        }
+        if let Some(interval) = self.config.repair_interval {
+            outcomes.push(ProtocolOutcome::ScheduleTimer(
+                now + interval,
+                TIMER_ID_REPAIR,
+            ));
+        }
+        outcomes
+    }
+
     /// Prints a log message if the message is a proposal.
     fn log_proposal(&self, proposal: &HashedProposal<C>, round_id: RoundId, msg: &str) {
         let creator_index = self.leader(round_id);
@@ -1071,8 +1122,11 @@ impl<C: Context + 'static> Zug<C> {
             );
         } else {
             self.record_entry(&Entry::SignedMessage(signed_msg.clone()));
+            let maybe_arrival = self.arrival_metric(&signed_msg, now);
             if self.add_content(signed_msg) {
-                return self.update(now);
+                let mut outcomes = self.update(now);
+                outcomes.extend(maybe_arrival.map(ProtocolOutcome::Metric));
+                return outcomes;
             }
         }
 
@@ -1428,7 +1482,9 @@ impl<C: Context + 'static> Zug<C> {
                             | ProtocolOutcome::QueueAction(_)
                             | ProtocolOutcome::CreateNewBlock(_)
                             | ProtocolOutcome::DoppelgangerDetected
-                            | ProtocolOutcome::Disconnect(_) => false,
+                            | ProtocolOutcome::Disconnect(_)
+                            | ProtocolOutcome::Metric(_)
+                            | ProtocolOutcome::MissedRound(_) => false,
                         }));
                     }
                 },
@@ -1460,6 +1516,24 @@ impl<C: Context + 'static> Zug<C> {
         outcomes
     }
 
+    /// Returns how long after its round's proposal timestamp the echo or vote arrived, if we have
+    /// the proposal.
+    fn arrival_metric(
+        &self,
+        signed_msg: &SignedMessage<C>,
+        now: Timestamp,
+    ) -> Option<ProtocolMetric> {
+        let proposal = self.round(signed_msg.round_id)?.proposal()?;
+        let delay = now.saturating_diff(proposal.timestamp());
+        match signed_msg.content {
+            Content::Echo(hash) if hash == *proposal.hash() => {
+                Some(ProtocolMetric::EchoArrival(delay))
+            }
+            Content::Echo(_) => None,
+            Content::Vote(_) => Some(ProtocolMetric::VoteArrival(delay)),
+        }
+    }
+
     /// Adds a signed message content to the state.
     /// Does not call `update` and does not detect faults.
     fn add_content(&mut self, signed_msg: SignedMessage<C>) -> bool {
@@ -1810,6 +1884,15 @@ impl<C: Context + 'static> Zug<C> {
                 "skipped round"
             );
             self.round_mut(prune_round_id).prune_skipped();
+            if let Some(leader_id) = self.validators.id(self.leader(prune_round_id)) {
+                outcomes.push(ProtocolOutcome::MissedRound(leader_id.clone()));
+            }
+        }
+        let skipped_rounds = round_id.saturating_sub(self.first_non_finalized_round_id);
+        if skipped_rounds > 0 {
+            outcomes.push(ProtocolOutcome::Metric(ProtocolMetric::SkippedRounds(
+                skipped_rounds,
+            )));
         }
         self.first_non_finalized_round_id = round_id.saturating_add(1);
         let value = if let Some(block) = proposal.maybe_block() {
@@ -2179,6 +2262,7 @@ where
                 self.mark_dirty(self.current_round);
                 self.update(now)
             }
+            TIMER_ID_REPAIR => self.handle_repair_timer(now),
             TIMER_ID_LOG_PARTICIPATION => {
                 self.log_participation();
                 match self.config.log_participation_interval {
@@ -2210,6 +2294,12 @@ where
                 TIMER_ID_SYNC_PEER,
             ));
         }
+        if let Some(interval) = self.config.repair_interval {
+            outcomes.push(ProtocolOutcome::ScheduleTimer(
+                now.max(self.params.start_timestamp()) + interval,
+                TIMER_ID_REPAIR,
+            ));
+        }
         if let Some(interval) = self.config.log_participation_interval {
             outcomes.push(ProtocolOutcome::ScheduleTimer(
                 now.max(self.params.start_timestamp()) + interval,
@@ -2374,6 +2464,38 @@ where
             .unwrap_or_default()
     }
 
+    fn conflicting_messages(&self, vid: &C::ValidatorId) -> Vec<ConflictingMessage<C>> {
+        let (signed_msg, content2, signature2) = match self
+            .validators
+            .get_index(vid)
+            .and_then(|idx| self.faults.get(&idx))
+        {
+            Some(Fault::Direct(signed_msg, content2, signature2)) => {
+                (signed_msg, content2, signature2)
+            }
+            Some(Fault::Banned) | Some(Fault::Indirect) | None => return vec![],
+        };
+        let round_id = signed_msg.round_id;
+        [
+            (&signed_msg.content, &signed_msg.signature),
+            (content2, signature2),
+        ]
+        .iter()
+        .map(|(content, signature)| match content {
+            Content::Echo(proposal_hash) => ConflictingMessage::Echo {
+                round_id,
+                proposal_hash: *proposal_hash,
+                signature: **signature,
+            },
+            Content::Vote(vote) => ConflictingMessage::Vote {
+                round_id,
+                vote: *vote,
+                signature: **signature,
+            },
+        })
+        .collect()
+    }
+
     fn set_paused(&mut self, paused: bool, now: Timestamp) -> ProtocolOutcomes<C> {
         if self.paused && !paused {
             info!(
@@ -2422,6 +2544,25 @@ where
     fn next_round_length(&self) -> Option<TimeDiff> {
         Some(self.params.min_block_time())
     }
+
+    fn leader_schedule(
+        &self,
+        _now: Timestamp,
+        count: usize,
+    ) -> Vec<ScheduledRound<C::ValidatorId>> {
+        // Rounds are not tied to fixed times: a round ends as soon as it is committed or skippable.
+        (self.current_round..=RoundId::MAX)
+            .take(count)
+            .filter_map(|round_id| {
+                let leader = self.validators.id(self.leader(round_id))?;
+                Some(ScheduledRound {
+                    round_id: u64::from(round_id),
+                    start: None,
+                    leader: leader.clone(),
+                })
+            })
+            .collect()
+    }
 }
 
 mod specimen_support {
@@ -13,6 +13,10 @@ pub struct Config {
     /// means disabled.
     #[serde(with = "serde_option_time_diff")]
     pub sync_state_interval: Option<TimeDiff>,
+    /// Request the missing votes of accepted but not yet committed rounds from a random peer
+    /// periodically, with this interval. 0 means disabled.
+    #[serde(with = "serde_option_time_diff")]
+    pub repair_interval: Option<TimeDiff>,
     /// Log inactive or faulty validators periodically, with this interval. 0 means disabled.
     #[serde(with = "serde_option_time_diff")]
     pub log_participation_interval: Option<TimeDiff>,
@@ -35,6 +39,7 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             sync_state_interval: Some("1sec".parse().unwrap()),
+            repair_interval: Some("1sec".parse().unwrap()),
             log_participation_interval: Some("10sec".parse().unwrap()),
             proposal_timeout: "1sec".parse().unwrap(),
             clock_tolerance: "1sec".parse().unwrap(),
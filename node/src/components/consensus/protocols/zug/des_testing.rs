@@ -23,7 +23,8 @@ use super::{
 use crate::{
     components::consensus::{
         consensus_protocol::{
-            ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolMetric, ProtocolOutcome,
+            ProtocolOutcomes,
         },
         tests::{
             consensus_des_testing::{
@@ -77,6 +78,8 @@ enum ZugMessage {
     FttExceeded,
     Disconnect(NodeId),
     HandledProposedBlock(ProposedBlock<TestContext>),
+    Metric(ProtocolMetric),
+    MissedRound(ValidatorId),
 }
 
 impl ZugMessage {
@@ -151,6 +154,8 @@ impl From<ProtocolOutcome<TestContext>> for ZugMessage {
             ProtocolOutcome::HandledProposedBlock(proposed_block) => {
                 ZugMessage::HandledProposedBlock(proposed_block)
             }
+            ProtocolOutcome::Metric(metric) => ZugMessage::Metric(metric),
+            ProtocolOutcome::MissedRound(vid) => ZugMessage::MissedRound(vid),
         }
     }
 }
@@ -241,7 +246,9 @@ impl ZugValidator {
                     | ZugMessage::ValidateConsensusValue(_, _)
                     | ZugMessage::NewEvidence(_)
                     | ZugMessage::Disconnect(_)
-                    | ZugMessage::HandledProposedBlock(_) => vec![msg],
+                    | ZugMessage::HandledProposedBlock(_)
+                    | ZugMessage::Metric(_)
+                    | ZugMessage::MissedRound(_) => vec![msg],
                     ZugMessage::WeAreFaulty => {
                         panic!("validator equivocated unexpectedly");
                     }
@@ -271,7 +278,9 @@ impl ZugValidator {
                     | ZugMessage::ValidateConsensusValue(_, _)
                     | ZugMessage::NewEvidence(_)
                     | ZugMessage::Disconnect(_)
-                    | ZugMessage::HandledProposedBlock(_) => vec![msg],
+                    | ZugMessage::HandledProposedBlock(_)
+                    | ZugMessage::Metric(_)
+                    | ZugMessage::MissedRound(_) => vec![msg],
                     ZugMessage::WeAreFaulty => {
                         panic!("validator equivocated unexpectedly");
                     }
@@ -459,6 +468,8 @@ where
             | ZugMessage::NewEvidence(_)
             | ZugMessage::Disconnect(_)
             | ZugMessage::HandledProposedBlock(_)
+            | ZugMessage::Metric(_)
+            | ZugMessage::MissedRound(_)
             | ZugMessage::SendEvidence(_, _)
             | ZugMessage::WeAreFaulty
             | ZugMessage::DoppelgangerDetected
@@ -612,6 +623,8 @@ where
                     vec![] // TODO: register the disconnect attempt somehow?
                 }
                 ZugMessage::HandledProposedBlock(_) => vec![], // irrelevant to consensus
+                ZugMessage::Metric(_) => vec![],               // irrelevant to consensus
+                ZugMessage::MissedRound(_) => vec![],          // irrelevant to consensus
                 ZugMessage::WeAreFaulty => {
                     warn!("{} detected that it is faulty", validator_id);
                     vec![] // TODO: stop the node or something?
@@ -733,6 +746,8 @@ impl DeliveryStrategy for InstantDeliveryNoDropping {
             | ZugMessage::NewEvidence(_)
             | ZugMessage::Disconnect(_)
             | ZugMessage::HandledProposedBlock(_)
+            | ZugMessage::Metric(_)
+            | ZugMessage::MissedRound(_)
             | ZugMessage::WeAreFaulty
             | ZugMessage::DoppelgangerDetected
             | ZugMessage::FttExceeded
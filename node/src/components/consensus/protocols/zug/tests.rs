@@ -10,7 +10,7 @@ use crate::{
     components::consensus::{
         cl_context::{ClContext, Keypair},
         config::Config,
-        consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
+        consensus_protocol::{ConsensusProtocol, ProtocolMetric, ProtocolOutcome},
         leader_sequence,
         protocols::common,
         tests::utils::{
@@ -470,6 +470,8 @@ fn zug_no_fault() {
     assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
     let timeout = timestamp + sc_c.proposal_timeout();
     expect_timer(&outcomes, timeout, TIMER_ID_UPDATE);
+    let arrival = ProtocolMetric::EchoArrival(timestamp.saturating_diff(proposal0.timestamp));
+    assert!(outcomes.contains(&ProtocolOutcome::Metric(arrival)));
 
     // Bob votes false in round 0. That's not a quorum yet.
     let msg = create_message(&validators, 0, vote(false), &bob_kp);
@@ -497,6 +499,7 @@ fn zug_no_fault() {
     assert!(remove_signed(&mut gossip, 2, carol_idx, vote(true)));
     assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
     expect_finalized(&outcomes, &[(&proposal1, 0), (&proposal2, 1)]);
+    assert!(outcomes.contains(&ProtocolOutcome::MissedRound(BOB_PUBLIC_KEY.clone())));
     expect_timer(&outcomes, timestamp + block_time, TIMER_ID_UPDATE);
 
     timestamp += block_time;
@@ -604,6 +607,21 @@ fn zug_faults() {
     let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
     expect_finalized(&outcomes, &[(&proposal1, 0), (&proposal2, 1)]);
 
+    // Bob's conflicting votes are available as evidence.
+    let mut bob_votes: Vec<bool> = zug
+        .conflicting_messages(&BOB_PUBLIC_KEY)
+        .into_iter()
+        .map(|msg| match msg {
+            ConflictingMessage::Vote {
+                round_id: 3, vote, ..
+            } => vote,
+            msg => panic!("unexpected conflicting message: {:?}", msg),
+        })
+        .collect();
+    bob_votes.sort_unstable();
+    assert_eq!(vec![false, true], bob_votes);
+    assert!(zug.conflicting_messages(&ALICE_PUBLIC_KEY).is_empty());
+
     // Now Carol starts two nodes by mistake, and equivocates. That crosses the FTT.
     let msg = create_message(&validators, 3, vote(true), &carol_kp);
     expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
@@ -719,6 +737,84 @@ fn zug_sends_sync_request() {
     }
 }
 
+/// Tests that the missing votes of an accepted but uncommitted round are requested from a peer.
+#[test]
+fn zug_requests_missing_votes() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Bob.
+    let mut zug = new_test_zug(weights, vec![], &[bob_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let interval = zug.config.repair_interval.expect("repair timer");
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    let outcomes = zug.handle_is_current(timestamp);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_REPAIR);
+
+    timestamp += interval;
+
+    // No round is accepted yet, so there is nothing to repair.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_REPAIR, &mut rng);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_REPAIR);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+
+    // Bob proposes and Alice echoes, so the proposal is accepted, but nobody has voted yet.
+    let msg = create_proposal_message(0, &proposal0, &validators, &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(zug.has_accepted_proposal(0));
+
+    timestamp += interval;
+
+    // The repair request is about round 0 and starts with the first validator without a vote.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_REPAIR, &mut rng);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_REPAIR);
+    let mut msg_iter = remove_requests_to_random(&mut outcomes).into_iter();
+    match (msg_iter.next(), msg_iter.next()) {
+        (
+            Some(SyncRequest {
+                round_id: 0,
+                proposal_hash: Some(hash),
+                has_proposal: true,
+                first_validator_idx: ValidatorIndex(0),
+                true_votes: 0,
+                false_votes: 0,
+                ..
+            }),
+            None,
+        ) => assert_eq!(hash0, hash),
+        (msg0, msg1) => panic!("unexpected messages: {:?}, {:?}", msg0, msg1),
+    }
+
+    // Once Alice and Bob vote for it, the round is finalized and there is nothing left to repair.
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, vote(true), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(zug.first_non_finalized_round_id, 1);
+
+    timestamp += interval;
+
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_REPAIR, &mut rng);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_REPAIR);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+}
+
 /// Tests that we respond to a `SyncRequest` message with the missing signatures.
 #[test]
 fn zug_handles_sync_request() {
@@ -971,6 +1067,28 @@ fn test_quorum() {
     }
 }
 
+#[test]
+fn zug_leader_schedule() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // The first round leaders are Bob, Alice, Carol.
+    let leader_seq = &[bob_idx, alice_idx, carol_idx];
+    let zug = new_test_zug(weights, vec![], leader_seq);
+
+    let schedule = zug.leader_schedule(Timestamp::from(100000), 3);
+    let leaders: Vec<_> = schedule.iter().map(|round| &round.leader).collect();
+    assert_eq!(
+        vec![&*BOB_PUBLIC_KEY, &*ALICE_PUBLIC_KEY, &*CAROL_PUBLIC_KEY],
+        leaders
+    );
+    let round_ids: Vec<_> = schedule.iter().map(|round| round.round_id).collect();
+    assert_eq!(vec![0, 1, 2], round_ids);
+    assert!(schedule.iter().all(|round| round.start.is_none()));
+}
+
 #[test]
 fn update_proposal_timeout() {
     macro_rules! assert_approx {
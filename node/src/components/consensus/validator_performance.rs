@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{EraId, PublicKey};
+
+/// A validator's contributions to consensus in a single era, as observed by our node.
+#[derive(Clone, Copy, Debug, Default, DataSize, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ValidatorPerformance {
+    /// The number of the era's finalized blocks proposed by the validator.
+    pub(crate) proposed_blocks: u64,
+    /// The number of finality signatures by the validator for the era's blocks.
+    pub(crate) finality_signatures: u64,
+    /// The number of rounds led by the validator that did not produce a finalized block.
+    pub(crate) rounds_missed: u64,
+}
+
+/// The performance of all of an era's validators.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ValidatorPerformanceReport {
+    /// The era the report is about.
+    pub(crate) era_id: EraId,
+    /// The performance of each validator in the era.
+    pub(crate) validators: BTreeMap<PublicKey, ValidatorPerformance>,
+}
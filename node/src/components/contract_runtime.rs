@@ -2,6 +2,7 @@
 
 mod config;
 mod error;
+mod gas_profiles;
 mod metrics;
 mod operations;
 #[cfg(test)]
@@ -13,9 +14,10 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     convert::TryInto,
     fmt::{self, Debug, Display, Formatter},
+    future::Future,
     path::Path,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use datasize::DataSize;
@@ -25,7 +27,7 @@ use once_cell::sync::Lazy;
 use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, info_span, trace, warn};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -33,16 +35,19 @@ use casper_execution_engine::{
         engine_config::{FeeHandling, RefundHandling},
         genesis::GenesisError,
         ChainspecRegistry, DeployItem, EngineConfigBuilder, EngineState, GenesisSuccess,
-        SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
+        QueryRequest, QueryResult, SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
     },
     shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
     storage::{
-        global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
-        trie_store::lmdb::LmdbTrieStore,
+        global_state::lmdb::LmdbGlobalState,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::{gc::TrieGcOutcome, lmdb::LmdbTrieStore},
     },
 };
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, EraId, ProtocolVersion, PublicKey, Timestamp};
+use casper_types::{
+    bytesrepr::Bytes, EraId, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+};
 
 use crate::{
     components::{fetcher::FetchResponse, Component, ComponentState},
@@ -53,18 +58,20 @@ use crate::{
         },
         incoming::{TrieDemand, TrieRequest, TrieRequestIncoming},
         requests::{ContractRuntimeRequest, NetworkRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects,
+        EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
     protocol::Message,
     types::{
+        chainspec::{Feature, FeaturesConfig},
         ActivationPoint, BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, ChunkingError,
         Deploy, FinalizedBlock, MetaBlock, MetaBlockState, TrieOrChunk, TrieOrChunkId,
     },
     NodeRng,
 };
 pub(crate) use config::Config;
-pub(crate) use error::{BlockExecutionError, ConfigError};
+pub(crate) use error::{BlockExecutionError, ConfigError, TrieGcError};
+use gas_profiles::GasProfiles;
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
 use operations::execute_only;
@@ -93,6 +100,10 @@ pub(crate) enum ContractRuntimeError {
 /// TODO: Fine tune this constant to the machine executing the node.
 const MAX_PARALLEL_INTENSIVE_TASKS: usize = 4;
 
+/// Maximum number of tries indexed per intensive task when indexing the references held by the
+/// tries written before they were tracked.
+const TRIES_INDEXED_PER_TASK: usize = 10_000;
+
 pub(crate) const APPROVALS_CHECKSUM_NAME: &str = "approvals_checksum";
 pub(crate) const EXECUTION_RESULTS_CHECKSUM_NAME: &str = "execution_results_checksum";
 
@@ -117,6 +128,39 @@ where
         .expect("task panicked")
 }
 
+/// The parts of the contract runtime required to execute deploys speculatively, shared with the
+/// speculative executor.
+#[derive(Clone)]
+pub(crate) struct SpeculativeExecutionHandle {
+    engine_state: Arc<EngineState<LmdbGlobalState>>,
+    metrics: Arc<Metrics>,
+    gas_profiles: Option<Arc<Mutex<GasProfiles>>>,
+    features_config: FeaturesConfig,
+}
+
+impl SpeculativeExecutionHandle {
+    /// Executes `deploy` on top of `execution_prestate` without committing its effects.
+    ///
+    /// This is CPU intensive and blocks the calling thread.
+    pub(crate) fn execute(
+        &self,
+        execution_prestate: SpeculativeExecutionState,
+        deploy: &Deploy,
+    ) -> Result<Option<ExecutionResult>, engine_state::Error> {
+        let allow_zk_host_functions = self
+            .features_config
+            .is_active(Feature::ZkHostFunctions, execution_prestate.era_id);
+        execute_only(
+            self.engine_state.as_ref(),
+            &self.metrics,
+            self.gas_profiles.as_deref(),
+            execution_prestate,
+            DeployItem::from(deploy.clone()),
+            allow_zk_host_functions,
+        )
+    }
+}
+
 #[derive(DataSize, Debug, Clone, Serialize)]
 /// Wrapper for speculative execution prestate.
 pub struct SpeculativeExecutionState {
@@ -126,6 +170,8 @@ pub struct SpeculativeExecutionState {
     pub block_time: Timestamp,
     /// Protocol version used when creating the original block.
     pub protocol_version: ProtocolVersion,
+    /// Era of the original block.
+    pub era_id: EraId,
 }
 
 /// State to use to construct the next block in the blockchain. Includes the state root hash for the
@@ -183,6 +229,9 @@ pub(crate) enum Event {
 
     #[from]
     TrieDemand(TrieDemand),
+
+    /// Run the periodic garbage collection of global state tries.
+    CollectTrieGarbage,
 }
 
 impl Display for Event {
@@ -193,6 +242,7 @@ impl Display for Event {
             }
             Event::TrieRequestIncoming(req) => write!(f, "trie request incoming: {}", req),
             Event::TrieDemand(demand) => write!(f, "trie demand: {}", demand),
+            Event::CollectTrieGarbage => write!(f, "collect trie garbage"),
         }
     }
 }
@@ -212,6 +262,19 @@ pub(crate) struct ContractRuntime {
     system_contract_registry: Option<SystemContractRegistry>,
     activation_point: ActivationPoint,
     prune_batch_size: u64,
+    /// Breakdowns of the gas charged while executing deploys, if recorded.
+    #[data_size(skip)]
+    gas_profiles: Option<Arc<Mutex<GasProfiles>>>,
+    /// Whether the global state tries no longer reachable are periodically garbage collected.
+    trie_gc_enabled: bool,
+    /// Interval between two garbage collections of global state tries.
+    trie_gc_interval: TimeDiff,
+    /// Number of blocks below the highest complete block whose global state is retained.
+    trie_gc_retained_blocks: u64,
+    /// Time since a trie was last written before it can be garbage collected.
+    trie_gc_grace_period: TimeDiff,
+    /// The eras in which protocol features are activated.
+    features_config: FeaturesConfig,
 }
 
 impl Debug for ContractRuntime {
@@ -247,6 +310,25 @@ where
                 self.handle_trie_request(effect_builder, request)
             }
             Event::TrieDemand(demand) => self.handle_trie_demand(demand),
+            Event::CollectTrieGarbage => {
+                let trie_gc_interval = self.trie_gc_interval;
+                let collect_trie_garbage = self.collect_trie_garbage(effect_builder, false);
+                async move {
+                    match collect_trie_garbage.await {
+                        Ok(outcome) => info!(
+                            candidates_checked = outcome.candidates_checked,
+                            tries_collected = outcome.tries_collected,
+                            bytes_collected = outcome.bytes_collected,
+                            "ContractRuntime: collected garbage tries"
+                        ),
+                        Err(error) => {
+                            warn!(%error, "ContractRuntime: failed to collect garbage tries")
+                        }
+                    }
+                    effect_builder.set_timeout(trie_gc_interval.into()).await;
+                }
+                .event(|_| Event::CollectTrieGarbage)
+            }
         }
     }
 
@@ -322,6 +404,26 @@ impl ContractRuntime {
         }
     }
 
+    /// Runs a query against global state.
+    fn handle_query(
+        &self,
+        query_request: QueryRequest,
+        responder: Responder<Result<QueryResult, engine_state::Error>>,
+    ) -> Effects<Event> {
+        trace!(?query_request, "query");
+        let engine_state = Arc::clone(&self.engine_state);
+        let metrics = Arc::clone(&self.metrics);
+        async move {
+            let correlation_id = CorrelationId::new();
+            let start = Instant::now();
+            let result = engine_state.run_query(correlation_id, query_request);
+            metrics.run_query.observe(start.elapsed().as_secs_f64());
+            trace!(?result, "query result");
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+
     /// Handles a contract runtime request.
     fn handle_contract_runtime_request<REv>(
         &mut self,
@@ -342,19 +444,18 @@ impl ContractRuntime {
             ContractRuntimeRequest::Query {
                 query_request,
                 responder,
+            } => self.handle_query(query_request, responder),
+            ContractRuntimeRequest::QueryWithProofAtRoot {
+                state_root,
+                key,
+                path,
+                responder,
             } => {
-                trace!(?query_request, "query");
-                let engine_state = Arc::clone(&self.engine_state);
-                let metrics = Arc::clone(&self.metrics);
-                async move {
-                    let correlation_id = CorrelationId::new();
-                    let start = Instant::now();
-                    let result = engine_state.run_query(correlation_id, query_request);
-                    metrics.run_query.observe(start.elapsed().as_secs_f64());
-                    trace!(?result, "query result");
-                    responder.respond(result).await
-                }
-                .ignore()
+                // Queries are not restricted to the current state root: any root still present in
+                // the trie store can be queried, and the result carries the Merkle proof of the
+                // value against that root.
+                let query_request = QueryRequest::new(state_root, key, path);
+                self.handle_query(query_request, responder)
             }
             ContractRuntimeRequest::GetBalance {
                 balance_request,
@@ -494,6 +595,10 @@ impl ContractRuntime {
                         let shared_pre_state = Arc::clone(&self.execution_pre_state);
                         let activation_point = self.activation_point;
                         let prune_batch_size = self.prune_batch_size;
+                        let gas_profiles = self.gas_profiles.clone();
+                        let allow_zk_host_functions = self
+                            .features_config
+                            .is_active(Feature::ZkHostFunctions, finalized_block.era_id());
                         effects.extend(
                             Self::execute_finalized_block_or_requeue(
                                 engine_state,
@@ -508,6 +613,8 @@ impl ContractRuntime {
                                 activation_point,
                                 key_block_height_for_activation_point,
                                 prune_batch_size,
+                                gas_profiles,
+                                allow_zk_host_functions,
                                 meta_block_state,
                             )
                             .ignore(),
@@ -566,25 +673,122 @@ impl ContractRuntime {
                     });
                 responder.respond(result).ignore()
             }
-            ContractRuntimeRequest::SpeculativeDeployExecution {
-                execution_prestate,
-                deploy,
+            ContractRuntimeRequest::GetDeployGasProfile {
+                deploy_hash,
                 responder,
             } => {
+                let gas_profile = self.gas_profiles.as_ref().and_then(|gas_profiles| {
+                    gas_profiles
+                        .lock()
+                        .expect("gas profiles mutex poisoned")
+                        .get(&deploy_hash.into())
+                });
+                responder.respond(gas_profile).ignore()
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                from_root,
+                to_root,
+                responder,
+            } => {
+                trace!(%from_root, %to_root, "get state diff");
                 let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
                 async move {
-                    let result = run_intensive_task(move || {
-                        execute_only(
-                            engine_state.as_ref(),
-                            execution_prestate,
-                            DeployItem::from((*deploy).clone()),
-                        )
-                    })
-                    .await;
+                    let correlation_id = CorrelationId::new();
+                    let start = Instant::now();
+                    let result = engine_state.get_state_diff(correlation_id, from_root, to_root);
+                    metrics
+                        .get_state_diff
+                        .observe(start.elapsed().as_secs_f64());
                     responder.respond(result).await
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::CollectTrieGarbage { dry_run, responder } => {
+                let collect_trie_garbage = self.collect_trie_garbage(effect_builder, dry_run);
+                async move { responder.respond(collect_trie_garbage.await).await }.ignore()
+            }
+        }
+    }
+
+    /// Schedules the first periodic garbage collection of global state tries, if enabled.
+    pub(crate) fn start_trie_gc<REv: Send>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event> {
+        if !self.trie_gc_enabled {
+            return Effects::new();
+        }
+        effect_builder
+            .set_timeout(self.trie_gc_interval.into())
+            .event(|_| Event::CollectTrieGarbage)
+    }
+
+    /// Collects the global state tries no longer reachable from the state of the highest complete
+    /// block or of the retained blocks below it.
+    ///
+    /// Unless this is a dry run, the older blocks are first removed from the available block range,
+    /// so that we stop advertising state we may no longer hold, and the references held by the
+    /// tries written before they were tracked are indexed, which is only slow the first time.
+    fn collect_trie_garbage<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        dry_run: bool,
+    ) -> impl Future<Output = Result<TrieGcOutcome, TrieGcError>>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let engine_state = Arc::clone(&self.engine_state);
+        let metrics = Arc::clone(&self.metrics);
+        let retained_blocks = self.trie_gc_retained_blocks;
+        let grace_period = Duration::from(self.trie_gc_grace_period);
+        async move {
+            let highest_block_header = effect_builder
+                .get_highest_complete_block_header_from_storage()
+                .await
+                .ok_or(TrieGcError::NoCompleteBlock)?;
+            let lowest_retained_height = highest_block_header
+                .height()
+                .saturating_sub(retained_blocks);
+            let retained_roots = effect_builder
+                .get_state_root_hashes_from_height_from_storage(lowest_retained_height)
+                .await;
+
+            if !dry_run {
+                effect_builder
+                    .mark_global_state_pruned_below(lowest_retained_height)
+                    .await;
+                loop {
+                    let engine_state = Arc::clone(&engine_state);
+                    let is_indexed = run_intensive_task(move || {
+                        engine_state
+                            .get_state()
+                            .index_trie_references(TRIES_INDEXED_PER_TASK)
+                    })
+                    .await?;
+                    if is_indexed {
+                        break;
+                    }
+                }
+            }
+
+            let outcome = run_intensive_task(move || {
+                engine_state.get_state().collect_trie_garbage(
+                    &retained_roots,
+                    grace_period,
+                    dry_run,
+                )
+            })
+            .await?;
+            if !dry_run {
+                metrics
+                    .trie_gc_tries_collected
+                    .inc_by(outcome.tries_collected);
+                metrics
+                    .trie_gc_bytes_collected
+                    .inc_by(outcome.bytes_collected);
+            }
+            Ok(outcome)
         }
     }
 }
@@ -611,6 +815,7 @@ impl ContractRuntime {
         allow_unrestricted_transfers: bool,
         refund_handling: RefundHandling,
         fee_handling: FeeHandling,
+        features_config: FeaturesConfig,
     ) -> Result<Self, ConfigError> {
         // TODO: This is bogus, get rid of this
         let execution_pre_state = Arc::new(Mutex::new(ExecutionPreState {
@@ -627,11 +832,13 @@ impl ContractRuntime {
             contract_runtime_config.manual_sync_enabled_or_default(),
         )?);
 
-        let trie_store = Arc::new(LmdbTrieStore::new(
-            &environment,
-            None,
-            DatabaseFlags::empty(),
-        )?);
+        let trie_gc_enabled = contract_runtime_config.trie_gc_enabled_or_default();
+        let trie_store = if trie_gc_enabled {
+            LmdbTrieStore::with_reference_tracking(&environment, None, DatabaseFlags::empty())?
+        } else {
+            LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())?
+        };
+        let trie_store = Arc::new(trie_store);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store)?;
         let engine_config = EngineConfigBuilder::new()
@@ -649,12 +856,18 @@ impl ContractRuntime {
             .with_allow_unrestricted_transfers(allow_unrestricted_transfers)
             .with_refund_handling(refund_handling)
             .with_fee_handling(fee_handling)
+            .with_module_cache_size(contract_runtime_config.wasm_module_cache_size_or_default())
             .build();
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
 
         let metrics = Arc::new(Metrics::new(registry)?);
 
+        let gas_profiles = match contract_runtime_config.max_gas_profiles_or_default() {
+            0 => None,
+            max_gas_profiles => Some(Arc::new(Mutex::new(GasProfiles::new(max_gas_profiles)))),
+        };
+
         Ok(ContractRuntime {
             state: ComponentState::Initialized,
             execution_pre_state,
@@ -665,6 +878,12 @@ impl ContractRuntime {
             system_contract_registry: None,
             activation_point,
             prune_batch_size,
+            gas_profiles,
+            trie_gc_enabled,
+            trie_gc_interval: contract_runtime_config.trie_gc_interval_or_default(),
+            trie_gc_retained_blocks: contract_runtime_config.trie_gc_retained_blocks_or_default(),
+            trie_gc_grace_period: contract_runtime_config.trie_gc_grace_period_or_default(),
+            features_config,
         })
     }
 
@@ -723,6 +942,33 @@ impl ContractRuntime {
         Ok(result)
     }
 
+    /// Executes the given finalized block again on top of `execution_pre_state`, for comparing
+    /// the outcome with that of its original execution.
+    pub(crate) fn replay_finalized_block(
+        &self,
+        execution_pre_state: ExecutionPreState,
+        finalized_block: FinalizedBlock,
+        deploys: Vec<Deploy>,
+        key_block_height_for_activation_point: u64,
+    ) -> Result<BlockAndExecutionResults, BlockExecutionError> {
+        let allow_zk_host_functions = self
+            .features_config
+            .is_active(Feature::ZkHostFunctions, finalized_block.era_id());
+        execute_finalized_block(
+            self.engine_state.as_ref(),
+            Some(Arc::clone(&self.metrics)),
+            self.protocol_version,
+            execution_pre_state,
+            finalized_block,
+            deploys,
+            self.activation_point.era_id(),
+            key_block_height_for_activation_point,
+            self.prune_batch_size,
+            None,
+            allow_zk_host_functions,
+        )
+    }
+
     pub(crate) fn set_initial_state(&mut self, sequential_block_state: ExecutionPreState) {
         let next_block_height = sequential_block_state.next_block_height;
         let mut execution_pre_state = self.execution_pre_state.lock().unwrap();
@@ -756,6 +1002,8 @@ impl ContractRuntime {
         activation_point: ActivationPoint,
         key_block_height_for_activation_point: u64,
         prune_batch_size: u64,
+        gas_profiles: Option<Arc<Mutex<GasProfiles>>>,
+        allow_zk_host_functions: bool,
         mut meta_block_state: MetaBlockState,
     ) where
         REv: From<ContractRuntimeRequest>
@@ -767,12 +1015,19 @@ impl ContractRuntime {
     {
         debug!("ContractRuntime: execute_finalized_block_or_requeue");
         let contract_runtime_metrics = metrics.clone();
+        let span = info_span!(
+            "execute_block",
+            height = finalized_block.height(),
+            era_id = %finalized_block.era_id(),
+            deploy_count = deploys.len(),
+        );
         let BlockAndExecutionResults {
             block,
             approvals_hashes,
             execution_results,
             maybe_step_effect_and_upcoming_era_validators,
         } = match run_intensive_task(move || {
+            let _entered = span.enter();
             debug!("ContractRuntime: execute_finalized_block");
             execute_finalized_block(
                 engine_state.as_ref(),
@@ -784,6 +1039,8 @@ impl ContractRuntime {
                 activation_point.era_id(),
                 key_block_height_for_activation_point,
                 prune_batch_size,
+                gas_profiles.as_deref(),
+                allow_zk_host_functions,
             )
         })
         .await
@@ -951,6 +1208,16 @@ impl ContractRuntime {
         result.map(|option| option.map(|trie_raw| trie_raw.into_inner()))
     }
 
+    /// Returns a handle for executing deploys speculatively.
+    pub(crate) fn speculative_execution_handle(&self) -> SpeculativeExecutionHandle {
+        SpeculativeExecutionHandle {
+            engine_state: Arc::clone(&self.engine_state),
+            metrics: Arc::clone(&self.metrics),
+            gas_profiles: self.gas_profiles.clone(),
+            features_config: self.features_config.clone(),
+        }
+    }
+
     /// Returns the engine state, for testing only.
     #[cfg(test)]
     pub(crate) fn engine_state(&self) -> &Arc<EngineState<LmdbGlobalState>> {
@@ -2,11 +2,18 @@ use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::shared::utils;
+use casper_types::TimeDiff;
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_MAX_GAS_PROFILES: usize = 0;
+const DEFAULT_WASM_MODULE_CACHE_SIZE: usize = 64;
+const DEFAULT_TRIE_GC_ENABLED: bool = false;
+const DEFAULT_TRIE_GC_INTERVAL_SECONDS: u32 = 3_600;
+const DEFAULT_TRIE_GC_RETAINED_BLOCKS: u64 = 10_000;
+const DEFAULT_TRIE_GC_GRACE_PERIOD_SECONDS: u32 = 86_400;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -31,6 +38,33 @@ pub struct Config {
     ///
     /// Defaults to `true`.
     pub enable_manual_sync: Option<bool>,
+    /// The number of deploys for which a breakdown of the gas charged while executing them is
+    /// kept.
+    ///
+    /// `0` disables recording gas profiles.  Defaults to `0`.
+    pub max_gas_profiles: Option<usize>,
+    /// The number of parsed wasm modules of stored contracts cached for reuse by later calls.
+    ///
+    /// Defaults to 64.
+    pub wasm_module_cache_size: Option<usize>,
+    /// Enable tracking the references between global state tries, and periodically deleting the
+    /// tries no longer reachable from the state of any retained block.
+    ///
+    /// Defaults to `false`.
+    pub enable_trie_gc: Option<bool>,
+    /// The interval between two garbage collections of global state tries.
+    ///
+    /// Defaults to 1 hour.
+    pub trie_gc_interval: Option<TimeDiff>,
+    /// The number of blocks below the highest complete block whose global state is retained.
+    ///
+    /// Defaults to 10,000.
+    pub trie_gc_retained_blocks: Option<u64>,
+    /// The time since a trie was last written before it can be deleted, protecting the state of
+    /// blocks still being executed or synchronized.
+    ///
+    /// Defaults to 1 day.
+    pub trie_gc_grace_period: Option<TimeDiff>,
 }
 
 impl Config {
@@ -58,6 +92,40 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    /// Number of kept gas profiles.
+    pub fn max_gas_profiles_or_default(&self) -> usize {
+        self.max_gas_profiles.unwrap_or(DEFAULT_MAX_GAS_PROFILES)
+    }
+
+    /// Number of cached wasm modules.
+    pub fn wasm_module_cache_size_or_default(&self) -> usize {
+        self.wasm_module_cache_size
+            .unwrap_or(DEFAULT_WASM_MODULE_CACHE_SIZE)
+    }
+
+    /// Is garbage collection of global state tries enabled.
+    pub fn trie_gc_enabled_or_default(&self) -> bool {
+        self.enable_trie_gc.unwrap_or(DEFAULT_TRIE_GC_ENABLED)
+    }
+
+    /// Interval between garbage collections of global state tries.
+    pub fn trie_gc_interval_or_default(&self) -> TimeDiff {
+        self.trie_gc_interval
+            .unwrap_or_else(|| TimeDiff::from_seconds(DEFAULT_TRIE_GC_INTERVAL_SECONDS))
+    }
+
+    /// Number of blocks below the highest complete block whose global state is retained.
+    pub fn trie_gc_retained_blocks_or_default(&self) -> u64 {
+        self.trie_gc_retained_blocks
+            .unwrap_or(DEFAULT_TRIE_GC_RETAINED_BLOCKS)
+    }
+
+    /// Time since a trie was last written before it can be deleted.
+    pub fn trie_gc_grace_period_or_default(&self) -> TimeDiff {
+        self.trie_gc_grace_period
+            .unwrap_or_else(|| TimeDiff::from_seconds(DEFAULT_TRIE_GC_GRACE_PERIOD_SECONDS))
+    }
 }
 
 impl Default for Config {
@@ -67,6 +135,14 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            max_gas_profiles: Some(DEFAULT_MAX_GAS_PROFILES),
+            wasm_module_cache_size: Some(DEFAULT_WASM_MODULE_CACHE_SIZE),
+            enable_trie_gc: Some(DEFAULT_TRIE_GC_ENABLED),
+            trie_gc_interval: Some(TimeDiff::from_seconds(DEFAULT_TRIE_GC_INTERVAL_SECONDS)),
+            trie_gc_retained_blocks: Some(DEFAULT_TRIE_GC_RETAINED_BLOCKS),
+            trie_gc_grace_period: Some(TimeDiff::from_seconds(
+                DEFAULT_TRIE_GC_GRACE_PERIOD_SECONDS,
+            )),
         }
     }
 }
@@ -25,6 +25,17 @@ pub(crate) enum ConfigError {
     Prometheus(#[from] prometheus::Error),
 }
 
+/// An error collecting the global state tries no longer reachable from any retained block.
+#[derive(Debug, Error)]
+pub(crate) enum TrieGcError {
+    /// No complete block is stored yet, so the state to retain is unknown.
+    #[error("no complete block stored yet")]
+    NoCompleteBlock,
+    /// Error in the global state store, e.g. if tries are not tracked or not indexed yet.
+    #[error(transparent)]
+    Lmdb(#[from] StorageLmdbError),
+}
+
 /// An error during block execution.
 #[derive(Debug, Error, Serialize)]
 pub enum BlockExecutionError {
@@ -0,0 +1,75 @@
+//! Breakdowns of the gas charged while executing deploys.
+//!
+//! Recording where the gas of a deploy went, i.e. how much was charged for Wasm instructions,
+//! for each host function, for storage and for system contract calls, helps contract authors
+//! finding out why a deploy is expensive.  Only the breakdown of the most recent execution of a
+//! deploy is kept, and only for a bounded number of deploys.
+
+use linked_hash_map::LinkedHashMap;
+
+use casper_execution_engine::core::engine_state::GasProfile;
+use casper_types::DeployHash;
+
+/// A least-recently-used store of gas profiles, keyed by deploy hash.
+#[derive(Debug)]
+pub struct GasProfiles {
+    /// The kept gas profiles, least recently used first.
+    entries: LinkedHashMap<DeployHash, GasProfile>,
+    /// The maximum number of kept gas profiles.
+    capacity: usize,
+}
+
+impl GasProfiles {
+    /// Creates a new, empty store keeping the gas profiles of up to `capacity` deploys.
+    pub(super) fn new(capacity: usize) -> Self {
+        GasProfiles {
+            entries: LinkedHashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the gas profile of the most recent execution of the given deploy, if kept.
+    pub(super) fn get(&mut self, deploy_hash: &DeployHash) -> Option<GasProfile> {
+        self.entries.get_refresh(deploy_hash).cloned()
+    }
+
+    /// Keeps the gas profile of an execution of the given deploy, replacing any earlier one and
+    /// evicting the least recently used ones as required to stay within the capacity.
+    pub(super) fn put(&mut self, deploy_hash: DeployHash, gas_profile: GasProfile) {
+        if self.capacity == 0 {
+            return;
+        }
+        let _ = self.entries.insert(deploy_hash, gas_profile);
+        while self.entries.len() > self.capacity {
+            let _ = self.entries.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::GasProfile;
+    use casper_types::DeployHash;
+
+    use super::GasProfiles;
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let deploy_hashes = [
+            DeployHash::new([1; 32]),
+            DeployHash::new([2; 32]),
+            DeployHash::new([3; 32]),
+        ];
+
+        let mut gas_profiles = GasProfiles::new(2);
+        for deploy_hash in &deploy_hashes[..2] {
+            gas_profiles.put(*deploy_hash, GasProfile::default());
+        }
+        // The profile of the second deploy is now the least recently used one.
+        assert!(gas_profiles.get(&deploy_hashes[0]).is_some());
+        gas_profiles.put(deploy_hashes[2], GasProfile::default());
+        assert!(gas_profiles.get(&deploy_hashes[1]).is_none());
+        assert!(gas_profiles.get(&deploy_hashes[0]).is_some());
+        assert!(gas_profiles.get(&deploy_hashes[2]).is_some());
+    }
+}
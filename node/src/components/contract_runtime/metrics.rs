@@ -1,4 +1,8 @@
-use prometheus::{self, Gauge, Histogram, IntGauge, Registry};
+use std::convert::TryInto;
+
+use prometheus::{self, Gauge, Histogram, IntCounter, IntGauge, Registry};
+
+use casper_execution_engine::shared::module_cache::ModuleCache;
 
 use crate::{unregister_metric, utils};
 
@@ -39,6 +43,9 @@ const GET_ERA_VALIDATORS_HELP: &str =
 const GET_BIDS_NAME: &str = "contract_runtime_get_bids";
 const GET_BIDS_HELP: &str = "time in seconds to get bids from global state";
 
+const GET_STATE_DIFF_NAME: &str = "contract_runtime_get_state_diff";
+const GET_STATE_DIFF_HELP: &str = "time in seconds to diff global state under two state roots";
+
 const PUT_TRIE_NAME: &str = "contract_runtime_put_trie";
 const PUT_TRIE_HELP: &str = "time in seconds to put a trie";
 
@@ -48,6 +55,22 @@ const GET_TRIE_HELP: &str = "time in seconds to get a trie";
 const EXEC_BLOCK_NAME: &str = "contract_runtime_execute_block";
 const EXEC_BLOCK_HELP: &str = "time in seconds to execute all deploys in a block";
 
+const WASM_MODULE_CACHE_HITS_NAME: &str = "contract_runtime_wasm_module_cache_hits";
+const WASM_MODULE_CACHE_HITS_HELP: &str =
+    "number of calls to stored contracts which found the parsed wasm module cached";
+
+const WASM_MODULE_CACHE_MISSES_NAME: &str = "contract_runtime_wasm_module_cache_misses";
+const WASM_MODULE_CACHE_MISSES_HELP: &str =
+    "number of calls to stored contracts which had to parse the wasm module";
+
+const TRIE_GC_TRIES_COLLECTED_NAME: &str = "contract_runtime_trie_gc_tries_collected";
+const TRIE_GC_TRIES_COLLECTED_HELP: &str =
+    "number of global state tries deleted by the trie garbage collection";
+
+const TRIE_GC_BYTES_COLLECTED_NAME: &str = "contract_runtime_trie_gc_bytes_collected";
+const TRIE_GC_BYTES_COLLECTED_HELP: &str =
+    "total size in bytes of the global state tries deleted by the trie garbage collection";
+
 const LATEST_COMMIT_STEP_NAME: &str = "contract_runtime_latest_commit_step";
 const LATEST_COMMIT_STEP_HELP: &str = "duration in seconds of latest commit step at era end";
 
@@ -66,9 +89,14 @@ pub struct Metrics {
     pub(super) get_balance: Histogram,
     pub(super) get_era_validators: Histogram,
     pub(super) get_bids: Histogram,
+    pub(super) get_state_diff: Histogram,
     pub(super) put_trie: Histogram,
     pub(super) get_trie: Histogram,
     pub(super) exec_block: Histogram,
+    wasm_module_cache_hits: IntGauge,
+    wasm_module_cache_misses: IntGauge,
+    pub(super) trie_gc_tries_collected: IntCounter,
+    pub(super) trie_gc_bytes_collected: IntCounter,
     pub(super) latest_commit_step: Gauge,
     pub(super) exec_queue_size: IntGauge,
     registry: Registry,
@@ -92,6 +120,22 @@ impl Metrics {
         let latest_commit_step = Gauge::new(LATEST_COMMIT_STEP_NAME, LATEST_COMMIT_STEP_HELP)?;
         registry.register(Box::new(latest_commit_step.clone()))?;
 
+        let wasm_module_cache_hits =
+            IntGauge::new(WASM_MODULE_CACHE_HITS_NAME, WASM_MODULE_CACHE_HITS_HELP)?;
+        registry.register(Box::new(wasm_module_cache_hits.clone()))?;
+
+        let wasm_module_cache_misses =
+            IntGauge::new(WASM_MODULE_CACHE_MISSES_NAME, WASM_MODULE_CACHE_MISSES_HELP)?;
+        registry.register(Box::new(wasm_module_cache_misses.clone()))?;
+
+        let trie_gc_tries_collected =
+            IntCounter::new(TRIE_GC_TRIES_COLLECTED_NAME, TRIE_GC_TRIES_COLLECTED_HELP)?;
+        registry.register(Box::new(trie_gc_tries_collected.clone()))?;
+
+        let trie_gc_bytes_collected =
+            IntCounter::new(TRIE_GC_BYTES_COLLECTED_NAME, TRIE_GC_BYTES_COLLECTED_HELP)?;
+        registry.register(Box::new(trie_gc_bytes_collected.clone()))?;
+
         let exec_queue_size = IntGauge::new(EXEC_QUEUE_SIZE_NAME, EXEC_QUEUE_SIZE_HELP)?;
         registry.register(Box::new(exec_queue_size.clone()))?;
 
@@ -144,6 +188,12 @@ impl Metrics {
                 GET_BIDS_HELP,
                 common_buckets.clone(),
             )?,
+            get_state_diff: utils::register_histogram_metric(
+                registry,
+                GET_STATE_DIFF_NAME,
+                GET_STATE_DIFF_HELP,
+                common_buckets.clone(),
+            )?,
             get_trie: utils::register_histogram_metric(
                 registry,
                 GET_TRIE_NAME,
@@ -162,11 +212,23 @@ impl Metrics {
                 EXEC_BLOCK_HELP,
                 common_buckets,
             )?,
+            wasm_module_cache_hits,
+            wasm_module_cache_misses,
+            trie_gc_tries_collected,
+            trie_gc_bytes_collected,
             latest_commit_step,
             exec_queue_size,
             registry: registry.clone(),
         })
     }
+
+    /// Updates the wasm module cache metrics from the cache's counters.
+    pub(super) fn update_wasm_module_cache(&self, module_cache: &ModuleCache) {
+        self.wasm_module_cache_hits
+            .set(module_cache.hits().try_into().unwrap_or(i64::MAX));
+        self.wasm_module_cache_misses
+            .set(module_cache.misses().try_into().unwrap_or(i64::MAX));
+    }
 }
 
 impl Drop for Metrics {
@@ -179,9 +241,14 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.get_balance);
         unregister_metric!(self.registry, self.get_era_validators);
         unregister_metric!(self.registry, self.get_bids);
+        unregister_metric!(self.registry, self.get_state_diff);
         unregister_metric!(self.registry, self.put_trie);
         unregister_metric!(self.registry, self.get_trie);
         unregister_metric!(self.registry, self.exec_block);
+        unregister_metric!(self.registry, self.wasm_module_cache_hits);
+        unregister_metric!(self.registry, self.wasm_module_cache_misses);
+        unregister_metric!(self.registry, self.trie_gc_tries_collected);
+        unregister_metric!(self.registry, self.trie_gc_bytes_collected);
         unregister_metric!(self.registry, self.latest_commit_step);
         unregister_metric!(self.registry, self.exec_queue_size);
     }
@@ -1,4 +1,10 @@
-use std::{cmp, collections::BTreeMap, ops::Range, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    collections::BTreeMap,
+    ops::Range,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use itertools::Itertools;
 use tracing::{debug, error, info, trace, warn};
@@ -14,7 +20,9 @@ use casper_execution_engine::{
         execution,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
-    storage::global_state::{lmdb::LmdbGlobalState, CommitProvider, StateProvider},
+    storage::global_state::{
+        lmdb::LmdbGlobalState, scratch::ScratchGlobalState, CommitProvider, StateProvider,
+    },
 };
 use casper_hashing::Digest;
 use casper_types::{
@@ -25,9 +33,10 @@ use crate::{
     components::{
         consensus::EraReport,
         contract_runtime::{
-            error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeExecutionState,
-            APPROVALS_CHECKSUM_NAME, EXECUTION_RESULTS_CHECKSUM_NAME,
+            error::BlockExecutionError, gas_profiles::GasProfiles,
+            types::StepEffectAndUpcomingEraValidators, BlockAndExecutionResults, ExecutionPreState,
+            Metrics, SpeculativeExecutionState, APPROVALS_CHECKSUM_NAME,
+            EXECUTION_RESULTS_CHECKSUM_NAME,
         },
         fetcher::FetchItem,
     },
@@ -99,6 +108,8 @@ pub fn execute_finalized_block(
     activation_point_era_id: EraId,
     key_block_height_for_activation_point: u64,
     prune_batch_size: u64,
+    gas_profiles: Option<&Mutex<GasProfiles>>,
+    allow_zk_host_functions: bool,
 ) -> Result<BlockAndExecutionResults, BlockExecutionError> {
     if finalized_block.height() != execution_pre_state.next_block_height {
         return Err(BlockExecutionError::WrongBlockHeight {
@@ -123,7 +134,7 @@ pub fn execute_finalized_block(
         .map_err(BlockCreationError::BytesRepr)?;
 
     // Create a new EngineState that reads from LMDB but only caches changes in memory.
-    let scratch_state = engine_state.get_scratch_engine_state();
+    let scratch_state = scratch_engine_state(engine_state, allow_zk_host_functions);
 
     // WARNING: Do not change the order of `deploys` as it will result in a different root hash.
     for deploy in deploys {
@@ -142,7 +153,12 @@ pub fn execute_finalized_block(
         // mapping between deploy_hash and execution result, and this outer logic is
         // enriching it with the deploy hash. If we were passing multiple deploys per exec
         // the relation between the deploy and the execution results would be lost.
-        let result = execute(&scratch_state, metrics.clone(), execute_request)?;
+        let result = execute(
+            &scratch_state,
+            metrics.clone(),
+            gas_profiles,
+            execute_request,
+        )?;
 
         trace!(?deploy_hash, ?result, "deploy execution result");
         // As for now a given state is expected to exist.
@@ -408,19 +424,19 @@ where
 /// Intended to be used for discovery operations on read-only nodes.
 ///
 /// Returns effects of the execution.
-pub fn execute_only<S>(
-    engine_state: &EngineState<S>,
+pub fn execute_only(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: &Metrics,
+    gas_profiles: Option<&Mutex<GasProfiles>>,
     execution_state: SpeculativeExecutionState,
     deploy: DeployItem,
-) -> Result<Option<ExecutionResult>, engine_state::Error>
-where
-    S: StateProvider + CommitProvider,
-    S::Error: Into<execution::Error>,
-{
+    allow_zk_host_functions: bool,
+) -> Result<Option<ExecutionResult>, engine_state::Error> {
     let SpeculativeExecutionState {
         state_root_hash,
         block_time,
         protocol_version,
+        era_id: _,
     } = execution_state;
     let deploy_hash = deploy.deploy_hash;
     let execute_request = ExecuteRequest::new(
@@ -430,28 +446,47 @@ where
         protocol_version,
         PublicKey::System,
     );
-    let results = execute(engine_state, None, execute_request);
-    results.map(|mut execution_results| {
-        let len = execution_results.len();
-        if len != 1 {
-            warn!(
-                ?deploy_hash,
-                "got more ({}) execution results from a single transaction", len
-            );
-            None
-        } else {
-            // We know it must be 1, we could unwrap and then wrap
-            // with `Some(_)` but `pop_front` already returns an `Option`.
-            // We need to transform the `engine_state::ExecutionResult` into
-            // `casper_types::ExecutionResult` as well.
-            execution_results.pop_front().map(Into::into)
-        }
-    })
+    // The effects are discarded, so executing on a scratch state costs nothing but lets the host
+    // functions available be set.
+    let scratch_state = scratch_engine_state(engine_state, allow_zk_host_functions);
+    let mut execution_results = execute(&scratch_state, None, gas_profiles, execute_request)?;
+    metrics.update_wasm_module_cache(engine_state.config().module_cache());
+    let len = execution_results.len();
+    if len != 1 {
+        warn!(
+            ?deploy_hash,
+            "got more ({}) execution results from a single transaction", len
+        );
+        Ok(None)
+    } else {
+        // We know it must be 1, we could unwrap and then wrap
+        // with `Some(_)` but `pop_front` already returns an `Option`.
+        // We need to transform the `engine_state::ExecutionResult` into
+        // `casper_types::ExecutionResult` as well.
+        Ok(execution_results.pop_front().map(Into::into))
+    }
 }
 
+/// Returns a new `EngineState` that reads from LMDB but only caches changes in memory, and which
+/// lets contracts import the host functions verifying zero-knowledge proofs if
+/// `allow_zk_host_functions` is set.
+fn scratch_engine_state(
+    engine_state: &EngineState<LmdbGlobalState>,
+    allow_zk_host_functions: bool,
+) -> EngineState<ScratchGlobalState> {
+    let mut scratch_state = engine_state.get_scratch_engine_state();
+    let mut engine_config = scratch_state.config().clone();
+    engine_config.set_allow_zk_host_functions(allow_zk_host_functions);
+    scratch_state.update_config(engine_config);
+    scratch_state
+}
+
+/// Executes the deploys of `execute_request`, keeping a gas profile of each if `gas_profiles` is
+/// provided.
 fn execute<S>(
     engine_state: &EngineState<S>,
     metrics: Option<Arc<Metrics>>,
+    gas_profiles: Option<&Mutex<GasProfiles>>,
     execute_request: ExecuteRequest,
 ) -> Result<ExecutionResults, engine_state::Error>
 where
@@ -461,9 +496,29 @@ where
     trace!(?execute_request, "execute");
     let correlation_id = CorrelationId::new();
     let start = Instant::now();
-    let result = engine_state.run_execute(correlation_id, execute_request);
+    let result = match gas_profiles {
+        Some(gas_profiles) => {
+            let deploy_hashes = execute_request
+                .deploys
+                .iter()
+                .map(|deploy_item| deploy_item.deploy_hash)
+                .collect_vec();
+            engine_state
+                .run_execute_with_gas_profiles(correlation_id, execute_request)
+                .map(|(execution_results, profiles)| {
+                    let mut gas_profiles =
+                        gas_profiles.lock().expect("gas profiles mutex poisoned");
+                    for (deploy_hash, gas_profile) in deploy_hashes.into_iter().zip(profiles) {
+                        gas_profiles.put(deploy_hash, gas_profile);
+                    }
+                    execution_results
+                })
+        }
+        None => engine_state.run_execute(correlation_id, execute_request),
+    };
     if let Some(metrics) = metrics {
         metrics.run_execute.observe(start.elapsed().as_secs_f64());
+        metrics.update_wasm_module_cache(engine_state.config().module_cache());
     }
     trace!(?result, "execute result");
     result
@@ -125,6 +125,7 @@ impl reactor::Reactor for Reactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.features_config.clone(),
         )?;
 
         let reactor = Reactor {
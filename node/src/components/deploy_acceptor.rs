@@ -3,6 +3,7 @@
 mod config;
 mod event;
 mod metrics;
+mod spam_filter;
 mod tests;
 
 use std::{collections::BTreeSet, fmt::Debug, sync::Arc};
@@ -11,7 +12,7 @@ use datasize::DataSize;
 use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{debug, error, trace};
+use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::core::engine_state::{
     executable_deploy_item::{
@@ -24,20 +25,21 @@ use casper_types::{
     account::{Account, AccountHash},
     system::auction::ARG_AMOUNT,
     Contract, ContractHash, ContractPackage, ContractPackageHash, ContractVersion,
-    ContractVersionKey, Key, ProtocolVersion, Timestamp, U512,
+    ContractVersionKey, ExecutionResult, Key, ProtocolVersion, TimeDiff, Timestamp, U512,
 };
 
 use crate::{
-    components::Component,
+    components::{contract_runtime::SpeculativeExecutionState, Component},
     effect::{
         announcements::{DeployAcceptorAnnouncement, FatalAnnouncement},
-        requests::{ContractRuntimeRequest, StorageRequest},
+        requests::{ContractRuntimeRequest, SpeculativeExecutionRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
     types::{
         chainspec::{CoreConfig, DeployConfig},
         BlockHash, BlockHeader, Chainspec, Deploy, DeployConfigurationFailure, FinalizedApprovals,
+        NodeId,
     },
     utils::Source,
     NodeRng,
@@ -45,11 +47,15 @@ use crate::{
 
 pub(crate) use config::Config;
 pub(crate) use event::{Event, EventMetadata};
+use spam_filter::{Rejection, SpamFilter};
 
 const COMPONENT_NAME: &str = "deploy_acceptor";
 
 const ARG_TARGET: &str = "target";
 
+/// How often sources which behave like new ones are dropped from the spam filters.
+const SPAM_FILTER_PURGE_INTERVAL: TimeDiff = TimeDiff::from_seconds(60);
+
 #[derive(Debug, Error, Serialize)]
 pub(crate) enum Error {
     /// The block chain has no blocks.
@@ -84,6 +90,25 @@ pub(crate) enum Error {
         /// The timestamp when the node validated the expiry timestamp.
         current_node_timestamp: Timestamp,
     },
+
+    /// The deploy's account submitted more deploys than its rate limit allows.
+    #[error("too many deploys received from account with hash {account_hash}")]
+    AccountRateLimitExceeded { account_hash: AccountHash },
+
+    /// The peer gossiping the deploy sent more deploys than its rate limit allows.
+    #[error("too many deploys received from peer")]
+    PeerRateLimitExceeded,
+
+    /// The peer gossiping the deploy is greylisted for having sent too many invalid deploys.
+    #[error("peer is greylisted until {until}")]
+    PeerGreylisted { until: Timestamp },
+
+    /// The deploy failed when executed speculatively on top of the highest block.
+    #[error("deploy failed when executed on top of block {block_hash}: {error_message}")]
+    PreflightExecutionFailed {
+        block_hash: BlockHash,
+        error_message: String,
+    },
 }
 
 impl Error {
@@ -152,6 +177,7 @@ pub(crate) trait ReactorEventT:
     + From<DeployAcceptorAnnouncement>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
+    + From<SpeculativeExecutionRequest>
     + From<FatalAnnouncement>
     + Send
 {
@@ -162,6 +188,7 @@ impl<REv> ReactorEventT for REv where
         + From<DeployAcceptorAnnouncement>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
+        + From<SpeculativeExecutionRequest>
         + From<FatalAnnouncement>
         + Send
 {
@@ -211,6 +238,9 @@ pub struct DeployAcceptor {
     deploy_config: DeployConfig,
     core_config: CoreConfig,
     max_associated_keys: u32,
+    peer_spam_filter: SpamFilter<NodeId>,
+    account_spam_filter: SpamFilter<AccountHash>,
+    last_spam_filter_purge: Timestamp,
     #[data_size(skip)]
     metrics: metrics::Metrics,
 }
@@ -221,6 +251,20 @@ impl DeployAcceptor {
         chainspec: &Chainspec,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
+        let peer_spam_filter = SpamFilter::new(
+            acceptor_config.peer_deploys_per_second,
+            acceptor_config.peer_deploy_burst,
+            acceptor_config.greylist_threshold,
+            acceptor_config.greylist_duration,
+        );
+        // Accounts are not greylisted: a deploy's account is only authenticated by its signatures,
+        // so anyone could get an account greylisted by sending invalid deploys on its behalf.
+        let account_spam_filter = SpamFilter::new(
+            acceptor_config.account_deploys_per_second,
+            acceptor_config.account_deploy_burst,
+            0,
+            acceptor_config.greylist_duration,
+        );
         Ok(DeployAcceptor {
             acceptor_config,
             chain_name: chainspec.network_config.name.clone(),
@@ -228,6 +272,9 @@ impl DeployAcceptor {
             deploy_config: chainspec.deploy_config,
             core_config: chainspec.core_config.clone(),
             max_associated_keys: chainspec.core_config.max_associated_keys,
+            peer_spam_filter,
+            account_spam_filter,
+            last_spam_filter_purge: Timestamp::now(),
             metrics: metrics::Metrics::new(registry)?,
         })
     }
@@ -245,6 +292,15 @@ impl DeployAcceptor {
     ) -> Effects<Event> {
         debug!(%source, %deploy, "checking acceptance");
         let verification_start_timestamp = Timestamp::now();
+        if let Err(error) = self.check_spam_filters(&deploy, &source, verification_start_timestamp)
+        {
+            debug!(%source, %deploy, %error, "deploy rejected by spam filter");
+            return match maybe_responder {
+                Some(responder) => responder.respond(Err(error)).ignore(),
+                None => Effects::new(),
+            };
+        }
+
         let acceptable_result = deploy.is_config_compliant(
             &self.chain_name,
             &self.deploy_config,
@@ -311,6 +367,53 @@ impl DeployAcceptor {
             })
     }
 
+    /// Checks the deploy against the rate limits of its source and account, and the greylist.
+    ///
+    /// Deploys fetched from peers or created by ourselves are always needed, and speculatively
+    /// executed ones are never stored, so only those sent by clients or gossiped are checked.
+    fn check_spam_filters(
+        &mut self,
+        deploy: &Deploy,
+        source: &Source,
+        now: Timestamp,
+    ) -> Result<(), Error> {
+        let maybe_peer = match source {
+            Source::PeerGossiped(node_id) => Some(*node_id),
+            Source::Client => None,
+            Source::Peer(_) | Source::SpeculativeExec(_) | Source::Ourself => return Ok(()),
+        };
+
+        if now.saturating_diff(self.last_spam_filter_purge) >= SPAM_FILTER_PURGE_INTERVAL {
+            self.peer_spam_filter.purge(now);
+            self.account_spam_filter.purge(now);
+            self.last_spam_filter_purge = now;
+            self.metrics
+                .greylisted_peers
+                .set(self.peer_spam_filter.greylisted_count(now) as i64);
+        }
+
+        if let Some(node_id) = maybe_peer {
+            match self.peer_spam_filter.check(node_id, now) {
+                Ok(()) => (),
+                Err(Rejection::RateLimited) => {
+                    self.metrics.rate_limited_deploys.inc();
+                    return Err(Error::PeerRateLimitExceeded);
+                }
+                Err(Rejection::Greylisted { until }) => {
+                    self.metrics.greylisted_deploys.inc();
+                    return Err(Error::PeerGreylisted { until });
+                }
+            }
+        }
+
+        let account_hash = deploy.header().account().to_account_hash();
+        if self.account_spam_filter.check(account_hash, now).is_err() {
+            self.metrics.rate_limited_deploys.inc();
+            return Err(Error::AccountRateLimitExceeded { account_hash });
+        }
+        Ok(())
+    }
+
     fn handle_get_block_header_result<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -447,7 +550,7 @@ impl DeployAcceptor {
     }
 
     fn handle_get_balance_result<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
@@ -506,7 +609,7 @@ impl DeployAcceptor {
     }
 
     fn verify_payment_logic<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
@@ -626,7 +729,7 @@ impl DeployAcceptor {
     }
 
     fn verify_session_logic<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
@@ -686,6 +789,7 @@ impl DeployAcceptor {
                 self.validate_deploy_cryptography(
                     effect_builder,
                     event_metadata,
+                    block_header,
                     verification_start_timestamp,
                 )
             }
@@ -735,7 +839,7 @@ impl DeployAcceptor {
 
     #[allow(clippy::too_many_arguments)]
     fn handle_get_contract_result<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
@@ -775,6 +879,7 @@ impl DeployAcceptor {
             return self.validate_deploy_cryptography(
                 effect_builder,
                 event_metadata,
+                block_header,
                 verification_start_timestamp,
             );
         }
@@ -794,7 +899,7 @@ impl DeployAcceptor {
 
     #[allow(clippy::too_many_arguments)]
     fn handle_get_contract_package_result<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
@@ -881,6 +986,7 @@ impl DeployAcceptor {
                         self.validate_deploy_cryptography(
                             effect_builder,
                             event_metadata,
+                            block_header,
                             verification_start_timestamp,
                         )
                     }
@@ -890,9 +996,10 @@ impl DeployAcceptor {
     }
 
     fn validate_deploy_cryptography<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
+        block_header: Box<BlockHeader>,
         verification_start_timestamp: Timestamp,
     ) -> Effects<Event> {
         if let Err(deploy_configuration_failure) = event_metadata.deploy.is_valid() {
@@ -919,6 +1026,66 @@ impl DeployAcceptor {
             return effects;
         }
 
+        // If enabled, deploys from clients are executed speculatively before being stored, so
+        // that the client learns about deploys which would fail right away.
+        if self.acceptor_config.preflight_execution && event_metadata.source.is_client() {
+            let execution_prestate = SpeculativeExecutionState {
+                state_root_hash: *block_header.state_root_hash(),
+                block_time: block_header.timestamp(),
+                protocol_version: block_header.protocol_version(),
+                era_id: block_header.era_id(),
+            };
+            return effect_builder
+                .speculative_execute_deploy(execution_prestate, event_metadata.deploy.clone())
+                .event(move |result| {
+                    let maybe_execution_result = result.unwrap_or_else(|error| {
+                        warn!(%error, "failed to execute deploy speculatively");
+                        None
+                    });
+                    Event::PreflightExecutionResult {
+                        event_metadata,
+                        block_header,
+                        maybe_execution_result,
+                        verification_start_timestamp,
+                    }
+                });
+        }
+
+        self.put_to_storage(effect_builder, event_metadata, verification_start_timestamp)
+    }
+
+    fn handle_preflight_execution_result<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        block_header: Box<BlockHeader>,
+        maybe_execution_result: Option<ExecutionResult>,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        // Only a definite failure rejects the deploy: if it could not be executed at all, it is
+        // still accepted as it would have been without preflight execution.
+        if let Some(ExecutionResult::Failure { error_message, .. }) = maybe_execution_result {
+            debug!(%error_message, "deploy failed preflight execution");
+            let error = Error::PreflightExecutionFailed {
+                block_hash: block_header.block_hash(),
+                error_message,
+            };
+            return self.handle_invalid_deploy_result(
+                effect_builder,
+                event_metadata,
+                error,
+                verification_start_timestamp,
+            );
+        }
+        self.put_to_storage(effect_builder, event_metadata, verification_start_timestamp)
+    }
+
+    fn put_to_storage<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
         effect_builder
             .put_deploy_to_storage(event_metadata.deploy.clone())
             .event(move |is_new| Event::PutToStorageResult {
@@ -929,7 +1096,7 @@ impl DeployAcceptor {
     }
 
     fn handle_invalid_deploy_result<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         error: Error,
@@ -943,6 +1110,20 @@ impl DeployAcceptor {
         if !matches!(source, Source::SpeculativeExec(_)) {
             self.metrics.observe_rejected(verification_start_timestamp);
         }
+        // Only deploys violating the chainspec or carrying invalid signatures count towards
+        // greylisting a peer: other failures depend on our view of global state, which can lag
+        // behind the peer's.
+        if let (Source::PeerGossiped(node_id), Error::InvalidDeployConfiguration(_)) =
+            (&source, &error)
+        {
+            let now = Timestamp::now();
+            if self.peer_spam_filter.record_invalid(*node_id, now) {
+                info!(%node_id, "greylisting peer for gossiping invalid deploys");
+                self.metrics
+                    .greylisted_peers
+                    .set(self.peer_spam_filter.greylisted_count(now) as i64);
+            }
+        }
         let mut effects = Effects::new();
         if let Some(responder) = maybe_responder {
             // The client has submitted an invalid deploy
@@ -1127,6 +1308,18 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 maybe_contract_package,
                 verification_start_timestamp,
             ),
+            Event::PreflightExecutionResult {
+                event_metadata,
+                block_header,
+                maybe_execution_result,
+                verification_start_timestamp,
+            } => self.handle_preflight_execution_result(
+                effect_builder,
+                event_metadata,
+                block_header,
+                maybe_execution_result,
+                verification_start_timestamp,
+            ),
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
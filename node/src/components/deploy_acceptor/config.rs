@@ -6,6 +6,12 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_TIMESTAMP_LEEWAY: &str = "2sec";
+const DEFAULT_PEER_DEPLOYS_PER_SECOND: u32 = 200;
+const DEFAULT_PEER_DEPLOY_BURST: u32 = 1000;
+const DEFAULT_ACCOUNT_DEPLOYS_PER_SECOND: u32 = 20;
+const DEFAULT_ACCOUNT_DEPLOY_BURST: u32 = 100;
+const DEFAULT_GREYLIST_THRESHOLD: u32 = 10;
+const DEFAULT_GREYLIST_DURATION: &str = "10min";
 
 /// Configuration options for accepting deploys.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, DataSize)]
@@ -18,12 +24,42 @@ pub struct Config {
     /// The maximum value to which `timestamp_leeway` can be set is defined by the chainspec
     /// setting `deploys.max_timestamp_leeway`.
     pub timestamp_leeway: TimeDiff,
+    /// The sustained number of gossiped deploys per second accepted from a single peer.
+    ///
+    /// Setting this to 0 disables the per-peer rate limit.
+    pub peer_deploys_per_second: u32,
+    /// The maximum number of gossiped deploys a single peer can send in a burst.
+    pub peer_deploy_burst: u32,
+    /// The sustained number of deploys per second accepted from a single account, whether received
+    /// from a client or gossiped by a peer.
+    ///
+    /// Setting this to 0 disables the per-account rate limit.
+    pub account_deploys_per_second: u32,
+    /// The maximum number of deploys a single account can submit in a burst.
+    pub account_deploy_burst: u32,
+    /// The number of invalid deploys a peer can gossip within `greylist_duration` before all of
+    /// its deploys are rejected without validation.
+    ///
+    /// Setting this to 0 disables greylisting.
+    pub greylist_threshold: u32,
+    /// How long a peer stays greylisted, and how long its invalid deploys are remembered.
+    pub greylist_duration: TimeDiff,
+    /// Whether to execute deploys received from clients speculatively on top of the highest block
+    /// before accepting them, and reject those which fail.
+    pub preflight_execution: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             timestamp_leeway: TimeDiff::from_str(DEFAULT_TIMESTAMP_LEEWAY).unwrap(),
+            peer_deploys_per_second: DEFAULT_PEER_DEPLOYS_PER_SECOND,
+            peer_deploy_burst: DEFAULT_PEER_DEPLOY_BURST,
+            account_deploys_per_second: DEFAULT_ACCOUNT_DEPLOYS_PER_SECOND,
+            account_deploy_burst: DEFAULT_ACCOUNT_DEPLOY_BURST,
+            greylist_threshold: DEFAULT_GREYLIST_THRESHOLD,
+            greylist_duration: TimeDiff::from_str(DEFAULT_GREYLIST_DURATION).unwrap(),
+            preflight_execution: false,
         }
     }
 }
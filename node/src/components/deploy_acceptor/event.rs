@@ -7,7 +7,8 @@ use serde::Serialize;
 
 use casper_types::{
     account::{Account, AccountHash},
-    Contract, ContractHash, ContractPackage, ContractPackageHash, ContractVersion, Timestamp, U512,
+    Contract, ContractHash, ContractPackage, ContractPackageHash, ContractVersion, ExecutionResult,
+    Timestamp, U512,
 };
 
 use super::Source;
@@ -48,6 +49,13 @@ pub(crate) enum Event {
         source: Source,
         maybe_responder: Option<Responder<Result<(), Error>>>,
     },
+    /// The result of speculatively executing a `Deploy` received from a client.
+    PreflightExecutionResult {
+        event_metadata: Box<EventMetadata>,
+        block_header: Box<BlockHeader>,
+        maybe_execution_result: Option<ExecutionResult>,
+        verification_start_timestamp: Timestamp,
+    },
     /// The result of the `DeployAcceptor` putting a `Deploy` to the storage component.
     PutToStorageResult {
         event_metadata: Box<EventMetadata>,
@@ -109,6 +117,13 @@ impl Display for Event {
             Event::Accept { deploy, source, .. } => {
                 write!(formatter, "accept {} from {}", deploy.hash(), source)
             }
+            Event::PreflightExecutionResult { event_metadata, .. } => {
+                write!(
+                    formatter,
+                    "executed deploy with hash {} speculatively",
+                    event_metadata.deploy.hash()
+                )
+            }
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
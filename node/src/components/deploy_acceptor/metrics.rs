@@ -1,4 +1,4 @@
-use prometheus::{Histogram, Registry};
+use prometheus::{Histogram, IntCounter, IntGauge, Registry};
 
 use casper_types::Timestamp;
 
@@ -8,6 +8,13 @@ const DEPLOY_ACCEPTED_NAME: &str = "deploy_acceptor_accepted_deploy";
 const DEPLOY_ACCEPTED_HELP: &str = "time in seconds to accept a deploy in the deploy acceptor";
 const DEPLOY_REJECTED_NAME: &str = "deploy_acceptor_rejected_deploy";
 const DEPLOY_REJECTED_HELP: &str = "time in seconds to reject a deploy in the deploy acceptor";
+const RATE_LIMITED_DEPLOYS_NAME: &str = "deploy_acceptor_rate_limited_deploys";
+const RATE_LIMITED_DEPLOYS_HELP: &str =
+    "number of deploys rejected because their peer or account exceeded its rate limit";
+const GREYLISTED_DEPLOYS_NAME: &str = "deploy_acceptor_greylisted_deploys";
+const GREYLISTED_DEPLOYS_HELP: &str = "number of deploys rejected because their peer is greylisted";
+const GREYLISTED_PEERS_NAME: &str = "deploy_acceptor_greylisted_peers";
+const GREYLISTED_PEERS_HELP: &str = "number of peers greylisted for gossiping invalid deploys";
 
 /// Value of upper bound of the first bucked. In ms.
 const EXPONENTIAL_BUCKET_START: f64 = 10.0;
@@ -22,6 +29,9 @@ const EXPONENTIAL_BUCKET_COUNT: usize = 10;
 pub(super) struct Metrics {
     deploy_accepted: Histogram,
     deploy_rejected: Histogram,
+    pub(super) rate_limited_deploys: IntCounter,
+    pub(super) greylisted_deploys: IntCounter,
+    pub(super) greylisted_peers: IntGauge,
     registry: Registry,
 }
 
@@ -32,6 +42,13 @@ impl Metrics {
             EXPONENTIAL_BUCKET_FACTOR,
             EXPONENTIAL_BUCKET_COUNT,
         )?;
+        let rate_limited_deploys =
+            IntCounter::new(RATE_LIMITED_DEPLOYS_NAME, RATE_LIMITED_DEPLOYS_HELP)?;
+        let greylisted_deploys = IntCounter::new(GREYLISTED_DEPLOYS_NAME, GREYLISTED_DEPLOYS_HELP)?;
+        let greylisted_peers = IntGauge::new(GREYLISTED_PEERS_NAME, GREYLISTED_PEERS_HELP)?;
+        registry.register(Box::new(rate_limited_deploys.clone()))?;
+        registry.register(Box::new(greylisted_deploys.clone()))?;
+        registry.register(Box::new(greylisted_peers.clone()))?;
 
         Ok(Self {
             deploy_accepted: utils::register_histogram_metric(
@@ -46,6 +63,9 @@ impl Metrics {
                 DEPLOY_REJECTED_HELP,
                 common_buckets,
             )?,
+            rate_limited_deploys,
+            greylisted_deploys,
+            greylisted_peers,
             registry: registry.clone(),
         })
     }
@@ -65,5 +85,8 @@ impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.deploy_accepted);
         unregister_metric!(self.registry, self.deploy_rejected);
+        unregister_metric!(self.registry, self.rate_limited_deploys);
+        unregister_metric!(self.registry, self.greylisted_deploys);
+        unregister_metric!(self.registry, self.greylisted_peers);
     }
 }
@@ -0,0 +1,247 @@
+//! Rate limiting and greylisting of deploy sources.
+
+use std::{collections::HashMap, hash::Hash};
+
+use datasize::DataSize;
+
+use casper_types::{TimeDiff, Timestamp};
+
+/// The cost of a single deploy, in thousandths of a token.
+const MILLITOKENS_PER_DEPLOY: u64 = 1000;
+
+/// The reason a deploy was rejected by a `SpamFilter`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(super) enum Rejection {
+    /// The source exceeded its rate limit.
+    RateLimited,
+    /// The source is greylisted until the given time.
+    Greylisted { until: Timestamp },
+}
+
+/// The rate limit and invalid deploy record of a single source.
+#[derive(Clone, DataSize, Debug)]
+struct Record {
+    /// The available tokens, in thousandths of a token.
+    millitokens: u64,
+    /// The last time the tokens were refilled.
+    last_refill: Timestamp,
+    /// The number of invalid deploys received since `first_invalid`.
+    invalid_count: u32,
+    /// The time of the first invalid deploy that is still remembered.
+    first_invalid: Option<Timestamp>,
+    /// The time until which all deploys of this source are rejected.
+    greylisted_until: Option<Timestamp>,
+}
+
+/// A per-source token bucket rate limiter and greylist.
+///
+/// Each source has a bucket holding up to `burst` tokens which is refilled at `per_second` tokens
+/// per second; every deploy takes one token. A source which submitted `greylist_threshold`
+/// invalid deploys within `greylist_duration` is greylisted for `greylist_duration`.
+#[derive(DataSize, Debug)]
+pub(super) struct SpamFilter<K: Eq + Hash> {
+    records: HashMap<K, Record>,
+    per_second: u32,
+    burst: u32,
+    greylist_threshold: u32,
+    greylist_duration: TimeDiff,
+}
+
+impl<K: Eq + Hash> SpamFilter<K> {
+    pub(super) fn new(
+        per_second: u32,
+        burst: u32,
+        greylist_threshold: u32,
+        greylist_duration: TimeDiff,
+    ) -> Self {
+        SpamFilter {
+            records: HashMap::new(),
+            per_second,
+            burst: burst.max(1),
+            greylist_threshold,
+            greylist_duration,
+        }
+    }
+
+    fn capacity(&self) -> u64 {
+        u64::from(self.burst).saturating_mul(MILLITOKENS_PER_DEPLOY)
+    }
+
+    fn record(&mut self, key: K, now: Timestamp) -> &mut Record {
+        let capacity = self.capacity();
+        self.records.entry(key).or_insert_with(|| Record {
+            millitokens: capacity,
+            last_refill: now,
+            invalid_count: 0,
+            first_invalid: None,
+            greylisted_until: None,
+        })
+    }
+
+    /// Checks whether a deploy from the given source should be validated, and if so takes a token
+    /// from its bucket.
+    pub(super) fn check(&mut self, key: K, now: Timestamp) -> Result<(), Rejection> {
+        let capacity = self.capacity();
+        let per_second = u64::from(self.per_second);
+        let record = self.record(key, now);
+        if let Some(until) = record.greylisted_until {
+            if now < until {
+                return Err(Rejection::Greylisted { until });
+            }
+            record.greylisted_until = None;
+        }
+        if per_second == 0 {
+            return Ok(());
+        }
+        // A rate of `per_second` tokens per second is `per_second` millitokens per millisecond.
+        let refill = now
+            .saturating_diff(record.last_refill)
+            .millis()
+            .saturating_mul(per_second);
+        record.millitokens = record.millitokens.saturating_add(refill).min(capacity);
+        record.last_refill = now;
+        if record.millitokens < MILLITOKENS_PER_DEPLOY {
+            return Err(Rejection::RateLimited);
+        }
+        record.millitokens -= MILLITOKENS_PER_DEPLOY;
+        Ok(())
+    }
+
+    /// Records an invalid deploy from the given source, and returns `true` if this caused the
+    /// source to be greylisted.
+    pub(super) fn record_invalid(&mut self, key: K, now: Timestamp) -> bool {
+        let threshold = self.greylist_threshold;
+        let duration = self.greylist_duration;
+        if threshold == 0 {
+            return false;
+        }
+        let record = self.record(key, now);
+        match record.first_invalid {
+            Some(first) if now.saturating_diff(first) <= duration => {
+                record.invalid_count = record.invalid_count.saturating_add(1);
+            }
+            _ => {
+                record.first_invalid = Some(now);
+                record.invalid_count = 1;
+            }
+        }
+        if record.invalid_count < threshold {
+            return false;
+        }
+        record.greylisted_until = Some(now.saturating_add(duration));
+        record.first_invalid = None;
+        record.invalid_count = 0;
+        true
+    }
+
+    /// Returns the number of currently greylisted sources.
+    pub(super) fn greylisted_count(&self, now: Timestamp) -> usize {
+        self.records
+            .values()
+            .filter(|record| record.greylisted_until.map_or(false, |until| now < until))
+            .count()
+    }
+
+    /// Forgets all sources which have a full bucket and no invalid deploys or greylisting in
+    /// effect, as they would behave exactly like new ones.
+    pub(super) fn purge(&mut self, now: Timestamp) {
+        let capacity = self.capacity();
+        let per_second = u64::from(self.per_second);
+        let duration = self.greylist_duration;
+        self.records.retain(|_, record| {
+            let refill = now
+                .saturating_diff(record.last_refill)
+                .millis()
+                .saturating_mul(per_second);
+            let is_full = per_second == 0 || record.millitokens.saturating_add(refill) >= capacity;
+            let remembers_invalid = record
+                .first_invalid
+                .map_or(false, |first| now.saturating_diff(first) <= duration);
+            let is_greylisted = record.greylisted_until.map_or(false, |until| now < until);
+            !is_full || remembers_invalid || is_greylisted
+        });
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.records.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_rate_limit_after_burst_and_refill() {
+        let mut filter = SpamFilter::new(2, 3, 0, TimeDiff::from_seconds(60));
+        let start = Timestamp::from(1_000_000);
+        for _ in 0..3 {
+            assert_eq!(filter.check(1, start), Ok(()));
+        }
+        assert_eq!(filter.check(1, start), Err(Rejection::RateLimited));
+        // Other sources have their own buckets.
+        assert_eq!(filter.check(2, start), Ok(()));
+
+        // Two tokens per second: one is available again after half a second.
+        let later = start + TimeDiff::from_millis(500);
+        assert_eq!(filter.check(1, later), Ok(()));
+        assert_eq!(filter.check(1, later), Err(Rejection::RateLimited));
+
+        // The bucket never holds more than `burst` tokens.
+        let much_later = later + TimeDiff::from_seconds(3600);
+        for _ in 0..3 {
+            assert_eq!(filter.check(1, much_later), Ok(()));
+        }
+        assert_eq!(filter.check(1, much_later), Err(Rejection::RateLimited));
+    }
+
+    #[test]
+    fn should_greylist_after_repeated_invalid_deploys() {
+        let duration = TimeDiff::from_seconds(60);
+        let mut filter = SpamFilter::new(0, 1, 3, duration);
+        let start = Timestamp::from(1_000_000);
+        assert!(!filter.record_invalid(1, start));
+        assert!(!filter.record_invalid(1, start));
+        assert_eq!(filter.check(1, start), Ok(()));
+        assert!(filter.record_invalid(1, start));
+        assert_eq!(filter.greylisted_count(start), 1);
+
+        let until = start + duration;
+        assert_eq!(
+            filter.check(1, start + TimeDiff::from_seconds(1)),
+            Err(Rejection::Greylisted { until })
+        );
+        assert_eq!(filter.check(2, start), Ok(()));
+        assert_eq!(filter.check(1, until), Ok(()));
+        assert_eq!(filter.greylisted_count(until), 0);
+    }
+
+    #[test]
+    fn should_forget_invalid_deploys_outside_greylist_duration() {
+        let duration = TimeDiff::from_seconds(60);
+        let mut filter = SpamFilter::new(0, 1, 2, duration);
+        let start = Timestamp::from(1_000_000);
+        assert!(!filter.record_invalid(1, start));
+        let later = start + duration + TimeDiff::from_millis(1);
+        assert!(!filter.record_invalid(1, later));
+        assert!(filter.record_invalid(1, later));
+    }
+
+    #[test]
+    fn should_purge_idle_sources() {
+        let duration = TimeDiff::from_seconds(60);
+        let mut filter = SpamFilter::new(1, 2, 5, duration);
+        let start = Timestamp::from(1_000_000);
+        assert_eq!(filter.check(1, start), Ok(()));
+        filter.record_invalid(2, start);
+        assert_eq!(filter.len(), 2);
+
+        // Source 1 is still refilling and source 2's invalid deploy is remembered.
+        filter.purge(start);
+        assert_eq!(filter.len(), 2);
+
+        filter.purge(start + duration + TimeDiff::from_millis(1));
+        assert_eq!(filter.len(), 0);
+    }
+}
@@ -27,7 +27,7 @@ use casper_types::{
     account::{Account, ActionThresholds, AssociatedKeys, Weight},
     contracts::NamedKeys,
     testing::TestRng,
-    CLValue, EraId, PublicKey, SecretKey, StoredValue, TimeDiff, URef, U512,
+    CLValue, EraId, ExecutionEffect, PublicKey, SecretKey, StoredValue, TimeDiff, URef, U512,
 };
 
 use super::*;
@@ -40,7 +40,7 @@ use crate::{
         announcements::{ControlAnnouncement, DeployAcceptorAnnouncement},
         requests::{
             ContractRuntimeRequest, MakeBlockExecutableRequest, MarkBlockCompletedRequest,
-            NetworkRequest,
+            NetworkRequest, SpeculativeExecutionRequest,
         },
         Responder,
     },
@@ -76,6 +76,8 @@ enum Event {
     #[from]
     ContractRuntime(#[serde(skip_serializing)] ContractRuntimeRequest),
     #[from]
+    SpeculativeExecution(#[serde(skip_serializing)] SpeculativeExecutionRequest),
+    #[from]
     StorageRequest(StorageRequest),
     #[from]
     NetworkRequest(NetworkRequest<Message>),
@@ -123,6 +125,9 @@ impl Display for Event {
             Event::ContractRuntime(event) => {
                 write!(formatter, "contract-runtime event: {:?}", event)
             }
+            Event::SpeculativeExecution(request) => {
+                write!(formatter, "speculative execution request: {}", request)
+            }
             Event::StorageRequest(request) => write!(formatter, "storage request: {:?}", request),
             Event::NetworkRequest(request) => write!(formatter, "network request: {:?}", request),
         }
@@ -178,6 +183,8 @@ enum TestScenario {
     FromClientCustomPaymentContractPackage(ContractPackageScenario),
     FromClientSessionContract(ContractScenario),
     FromClientSessionContractPackage(ContractPackageScenario),
+    FromClientPassingPreflightExecution,
+    FromClientFailingPreflightExecution,
     DeployWithNativeTransferInPayment,
     DeployWithEmptySessionModuleBytes,
     DeployWithoutPaymentAmount,
@@ -234,6 +241,8 @@ impl TestScenario {
             | TestScenario::FromClientCustomPaymentContractPackage(_)
             | TestScenario::FromClientSessionContract(_)
             | TestScenario::FromClientSessionContractPackage(_)
+            | TestScenario::FromClientPassingPreflightExecution
+            | TestScenario::FromClientFailingPreflightExecution
             | TestScenario::DeployWithEmptySessionModuleBytes
             | TestScenario::DeployWithNativeTransferInPayment
             | TestScenario::ShouldNotAcceptExpiredDeploySentByClient
@@ -261,7 +270,9 @@ impl TestScenario {
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::AccountWithUnknownBalance
-            | TestScenario::BalanceCheckForDeploySentByPeer => {
+            | TestScenario::BalanceCheckForDeploySentByPeer
+            | TestScenario::FromClientPassingPreflightExecution
+            | TestScenario::FromClientFailingPreflightExecution => {
                 Deploy::random_valid_native_transfer(rng)
             }
             TestScenario::DeployWithoutPaymentAmount => Deploy::random_without_payment_amount(rng),
@@ -383,6 +394,7 @@ impl TestScenario {
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientValidDeploy
             | TestScenario::FromClientSlightlyFutureDatedDeploy
+            | TestScenario::FromClientPassingPreflightExecution
             | TestScenario::ShouldAcceptExpiredDeploySentByPeer=> true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
@@ -400,6 +412,7 @@ impl TestScenario {
             | TestScenario::DeployWithoutTransferAmount
             | TestScenario::DeployWithoutTransferTarget
             | TestScenario::BalanceCheckForDeploySentByPeer
+            | TestScenario::FromClientFailingPreflightExecution
             | TestScenario::ShouldNotAcceptExpiredDeploySentByClient => false,
             TestScenario::FromPeerCustomPaymentContract(contract_scenario)
             | TestScenario::FromPeerSessionContract(contract_scenario)
@@ -427,6 +440,14 @@ impl TestScenario {
         }
     }
 
+    fn is_preflight_execution_case(&self) -> bool {
+        matches!(
+            self,
+            TestScenario::FromClientPassingPreflightExecution
+                | TestScenario::FromClientFailingPreflightExecution
+        )
+    }
+
     fn is_repeated_deploy_case(&self) -> bool {
         matches!(
             self,
@@ -446,6 +467,8 @@ impl TestScenario {
                     deploy_config: chainspec.deploy_config,
                     wasm_config: chainspec.wasm_config,
                     system_costs_config: chainspec.system_costs_config,
+                    features_config: chainspec.features_config.clone(),
+                    proof_requests_config: chainspec.proof_requests_config,
                 };
 
                 chainspec.core_config.administrators.clear();
@@ -510,8 +533,12 @@ impl reactor::Reactor for Reactor {
         let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
         let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
 
+        let acceptor_config = Config {
+            preflight_execution: config.is_preflight_execution_case(),
+            ..Config::default()
+        };
         let deploy_acceptor = DeployAcceptor::new(
-            Config::default(),
+            acceptor_config,
             &config.create_chainspec_for_test(Arc::clone(&chainspec)),
             registry,
         )
@@ -709,6 +736,24 @@ impl reactor::Reactor for Reactor {
                 }
                 _ => panic!("should not receive {:?}", event),
             },
+            Event::SpeculativeExecution(SpeculativeExecutionRequest { responder, .. }) => {
+                let execution_result =
+                    if self.test_scenario == TestScenario::FromClientFailingPreflightExecution {
+                        ExecutionResult::Failure {
+                            effect: ExecutionEffect::default(),
+                            transfers: vec![],
+                            cost: U512::from(MAX_PAYMENT_AMOUNT),
+                            error_message: "Insufficient funds".to_string(),
+                        }
+                    } else {
+                        ExecutionResult::Success {
+                            effect: ExecutionEffect::default(),
+                            transfers: vec![],
+                            cost: U512::from(MAX_PAYMENT_AMOUNT),
+                        }
+                    };
+                responder.respond(Ok(Some(execution_result))).ignore()
+            }
             Event::NetworkRequest(_) => panic!("test does not handle network requests"),
         }
     }
@@ -898,6 +943,7 @@ async fn run_deploy_acceptor_without_timeout(
             | TestScenario::DeployWithMangledTransferAmount
             | TestScenario::DeployWithoutTransferTarget
             | TestScenario::DeployWithoutTransferAmount
+            | TestScenario::FromClientFailingPreflightExecution
             | TestScenario::ShouldNotAcceptExpiredDeploySentByClient => {
                 matches!(
                     event,
@@ -992,7 +1038,8 @@ async fn run_deploy_acceptor_without_timeout(
             // Check that a, new and valid, deploy sent by a client raises an `AcceptedNewDeploy`
             // announcement with the appropriate source.
             TestScenario::FromClientValidDeploy
-            | TestScenario::FromClientSlightlyFutureDatedDeploy => {
+            | TestScenario::FromClientSlightlyFutureDatedDeploy
+            | TestScenario::FromClientPassingPreflightExecution => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(
@@ -1127,6 +1174,21 @@ async fn should_reject_invalid_deploy_from_client() {
     ))
 }
 
+#[tokio::test]
+async fn should_accept_deploy_passing_preflight_execution_from_client() {
+    let result = run_deploy_acceptor(TestScenario::FromClientPassingPreflightExecution).await;
+    assert!(result.is_ok())
+}
+
+#[tokio::test]
+async fn should_reject_deploy_failing_preflight_execution_from_client() {
+    let result = run_deploy_acceptor(TestScenario::FromClientFailingPreflightExecution).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::PreflightExecutionFailed { .. })
+    ))
+}
+
 #[tokio::test]
 async fn should_accept_slightly_future_dated_deploy_from_client() {
     let result = run_deploy_acceptor(TestScenario::FromClientSlightlyFutureDatedDeploy).await;
@@ -15,10 +15,12 @@ use datasize::DataSize;
 use futures::FutureExt;
 use itertools::Itertools;
 use prometheus::Registry;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 use tracing::{debug, error, info, warn};
 
-use casper_types::Timestamp;
+use casper_types::{Motes, PublicKey, Timestamp};
 
 use crate::{
     components::{
@@ -49,8 +51,25 @@ use metrics::Metrics;
 
 const COMPONENT_NAME: &str = "deploy_buffer";
 
+/// The maximum number of stuck deploys reported by `DeployBuffer::stuck_deploys`.
+const MAX_REPORTED_STUCK_DEPLOYS: usize = 100;
+
 type FootprintAndApprovals = (DeployFootprint, BTreeSet<Approval>);
 
+/// A buffered deploy which can't be proposed because some of its dependencies have not been
+/// executed yet.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StuckDeploy {
+    /// The hash of the stuck deploy.
+    pub deploy_hash: DeployHash,
+    /// Dependencies which are buffered, but have not been executed yet.
+    pub pending_dependencies: Vec<DeployHash>,
+    /// Dependencies unknown to the deploy buffer: they have either never been received, or have
+    /// been executed too long ago to be tracked.
+    pub unknown_dependencies: Vec<DeployHash>,
+}
+
 #[derive(DataSize, Debug)]
 pub(crate) struct DeployBuffer {
     state: ComponentState,
@@ -72,6 +91,9 @@ pub(crate) struct DeployBuffer {
     hold: BTreeMap<Timestamp, HashSet<DeployHash>>,
     // deploy_hashes that should not be proposed, ever
     dead: HashSet<DeployHash>,
+    // deploy_hashes that have been executed in a block; deploys which depend on others are only
+    // proposed once all their dependencies are in here
+    executed: HashSet<DeployHash>,
     // deploy buffer metrics
     #[data_size(skip)]
     metrics: Metrics,
@@ -91,6 +113,7 @@ impl DeployBuffer {
             buffer: HashMap::new(),
             hold: BTreeMap::new(),
             dead: HashSet::new(),
+            executed: HashSet::new(),
             metrics: Metrics::new(registry)?,
         })
     }
@@ -172,6 +195,9 @@ impl DeployBuffer {
         self.dead
             .retain(|deploy_hash| freed.remove(deploy_hash).is_none());
         self.buffer = buffer;
+        let buffer = &self.buffer;
+        self.executed
+            .retain(|deploy_hash| buffer.contains_key(deploy_hash));
 
         if !freed.is_empty() {
             info!(
@@ -211,27 +237,57 @@ impl DeployBuffer {
     }
 
     /// Update buffer considering new stored deploy.
-    fn register_deploy(&mut self, deploy: Deploy) {
+    ///
+    /// Returns the hashes of the pending deploys that were replaced by the new one.
+    fn register_deploy(&mut self, deploy: Deploy) -> Vec<DeployHash> {
         let deploy_hash = deploy.hash();
         if deploy.is_valid().is_err() {
             error!(%deploy_hash, "DeployBuffer: invalid deploy must not be buffered");
-            return;
+            return vec![];
         }
         if self.dead.contains(deploy_hash) {
             info!(%deploy_hash, "DeployBuffer: attempt to register already dead deploy");
-            return;
+            return vec![];
         }
         if self.hold.values().any(|dhs| dhs.contains(deploy_hash)) {
             info!(%deploy_hash, "DeployBuffer: attempt to register already held deploy");
-            return;
+            return vec![];
         }
         let footprint = match deploy.footprint() {
             Ok(footprint) => footprint,
             Err(err) => {
                 error!(%deploy_hash, %err, "DeployBuffer: deploy footprint exceeds tolerances");
-                return;
+                return vec![];
+            }
+        };
+        let replaced = match self.replaced_deploys(deploy_hash, &footprint) {
+            Some(replaced) => replaced,
+            None => {
+                info!(
+                    %deploy_hash,
+                    "DeployBuffer: conflicting pending deploy pays at least as much, not buffering"
+                );
+                return vec![];
             }
         };
+        let account = footprint.header.account();
+        if replaced.is_empty()
+            && !self.buffer.contains_key(deploy_hash)
+            && self.pending_deploys_of(account)
+                >= self.cfg.max_pending_deploys_per_account() as usize
+        {
+            info!(
+                %deploy_hash,
+                %account,
+                "DeployBuffer: account has too many pending deploys, not buffering"
+            );
+            self.metrics.rejected_over_limit_deploys.inc();
+            return vec![];
+        }
+        for replaced_hash in &replaced {
+            info!(%deploy_hash, %replaced_hash, "DeployBuffer: pending deploy replaced");
+            self.buffer.remove(replaced_hash);
+        }
         let expiry_time = deploy.header().expires();
         let approvals = deploy.approvals().clone();
         match self
@@ -243,9 +299,70 @@ impl DeployBuffer {
             }
             None => {
                 debug!(%deploy_hash, "DeployBuffer: new deploy buffered");
-                self.metrics.total_deploys.inc();
             }
         }
+        self.update_all_metrics();
+        replaced
+    }
+
+    /// Returns the pending deploys that the new deploy replaces, i.e. those by the same account
+    /// with timestamps within the replacement window, or `None` if any of them has a payment at
+    /// least as high as the new one's, in which case the new deploy must not be buffered.
+    ///
+    /// Deploys that are held or dead have already been proposed and can't be replaced.
+    fn replaced_deploys(
+        &self,
+        deploy_hash: &DeployHash,
+        footprint: &DeployFootprint,
+    ) -> Option<Vec<DeployHash>> {
+        let payment = Self::payment(footprint);
+        let account = footprint.header.account();
+        let timestamp = footprint.header.timestamp();
+        let mut replaced = vec![];
+        for (dh, (_, maybe_data)) in &self.buffer {
+            let other_footprint = match maybe_data {
+                Some((other_footprint, _)) => other_footprint,
+                None => continue,
+            };
+            if dh == deploy_hash
+                || other_footprint.header.account() != account
+                || self.dead.contains(dh)
+                || self.hold.values().any(|hs| hs.contains(dh))
+            {
+                continue;
+            }
+            let other_timestamp = other_footprint.header.timestamp();
+            let time_diff = timestamp
+                .max(other_timestamp)
+                .saturating_diff(timestamp.min(other_timestamp));
+            if time_diff > self.cfg.replacement_window() {
+                continue;
+            }
+            if Self::payment(other_footprint) >= payment {
+                return None;
+            }
+            replaced.push(*dh);
+        }
+        Some(replaced)
+    }
+
+    /// Returns the number of buffered deploys by the given account that haven't been included in a
+    /// block yet.
+    fn pending_deploys_of(&self, account: &PublicKey) -> usize {
+        self.buffer
+            .iter()
+            .filter(|(dh, _)| !self.dead.contains(dh))
+            .filter(|(_, (_, maybe_data))| {
+                maybe_data.as_ref().map_or(false, |(footprint, _)| {
+                    footprint.header.account() == account
+                })
+            })
+            .count()
+    }
+
+    /// Returns the payment amount of the deploy with the given footprint.
+    fn payment(footprint: &DeployFootprint) -> Motes {
+        Motes::from_gas(footprint.gas_estimate, footprint.header.gas_price()).unwrap_or_default()
     }
 
     /// Update holds considering new proposed block.
@@ -298,6 +415,8 @@ impl DeployBuffer {
         let timestamp = block.timestamp();
         debug!(%timestamp, "DeployBuffer: register_block({}) timestamp finalized", block_height);
         self.register_deploys(timestamp, block.deploy_and_transfer_hashes());
+        self.executed
+            .extend(block.deploy_and_transfer_hashes().copied());
     }
 
     /// Update buffer and holds considering new finalized block.
@@ -308,7 +427,8 @@ impl DeployBuffer {
         self.register_deploys(timestamp, finalized_block.deploy_and_transfer_hashes());
     }
 
-    /// Returns eligible deploys that are buffered and not held or dead.
+    /// Returns eligible deploys that are buffered and not held or dead, and whose dependencies have
+    /// all been executed.
     fn proposable(&self) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
         debug!("DeployBuffer: getting proposable deploys");
         self.buffer
@@ -323,16 +443,77 @@ impl DeployBuffer {
                     )
                 })
             })
+            .filter(|(_, footprint)| {
+                footprint
+                    .header
+                    .dependencies()
+                    .iter()
+                    .all(|dependency| self.executed.contains(dependency))
+            })
+            .collect()
+    }
+
+    /// Returns the pending deploys which can't be proposed because some of their dependencies have
+    /// not been executed, together with those dependencies.
+    fn stuck_deploys(&self) -> Vec<StuckDeploy> {
+        self.buffer
+            .iter()
+            .filter(|(dh, _)| !self.dead.contains(dh))
+            .filter_map(|(dh, (_, maybe_data))| {
+                let (footprint, _) = maybe_data.as_ref()?;
+                let (pending_dependencies, unknown_dependencies): (Vec<_>, Vec<_>) = footprint
+                    .header
+                    .dependencies()
+                    .iter()
+                    .copied()
+                    .filter(|dependency| !self.executed.contains(dependency))
+                    .partition(|dependency| self.buffer.contains_key(dependency));
+                if pending_dependencies.is_empty() && unknown_dependencies.is_empty() {
+                    return None;
+                }
+                Some(StuckDeploy {
+                    deploy_hash: *dh,
+                    pending_dependencies,
+                    unknown_dependencies,
+                })
+            })
+            .sorted_by_key(|stuck_deploy| stuck_deploy.deploy_hash)
+            .take(MAX_REPORTED_STUCK_DEPLOYS)
             .collect()
     }
 
+    /// Orders the given deploys so that accounts take turns: first each account's oldest deploy,
+    /// then each account's second oldest one, and so on. That way, if the block is full, every
+    /// account gets a fair share of it and a single account cannot monopolize the block space.
+    fn fair_share_order(
+        deploys: Vec<(DeployHashWithApprovals, DeployFootprint)>,
+    ) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
+        let mut by_account: BTreeMap<PublicKey, Vec<_>> = BTreeMap::new();
+        for (with_approvals, footprint) in deploys {
+            by_account
+                .entry(footprint.header.account().clone())
+                .or_default()
+                .push((with_approvals, footprint));
+        }
+        let mut ranked = by_account
+            .into_values()
+            .flat_map(|mut account_deploys| {
+                account_deploys.sort_by_key(|(_, footprint)| footprint.header.timestamp());
+                account_deploys.into_iter().enumerate()
+            })
+            .collect_vec();
+        // The sort is stable, so within a rank the accounts remain ordered.
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked.into_iter().map(|(_, deploy)| deploy).collect()
+    }
+
     /// Returns a right-sized payload of deploys that can be proposed.
     fn appendable_block(&mut self, timestamp: Timestamp) -> AppendableBlock {
         let mut ret = AppendableBlock::new(self.deploy_config, timestamp);
         let mut holds = HashSet::new();
         let mut have_hit_transfer_limit = false;
         let mut have_hit_deploy_limit = false;
-        for (with_approvals, footprint) in self.proposable() {
+        for (with_approvals, footprint) in Self::fair_share_order(self.proposable()) {
             if footprint.is_transfer && have_hit_transfer_limit {
                 continue;
             }
@@ -552,6 +733,9 @@ where
                     timestamp,
                     responder,
                 }) => responder.respond(self.appendable_block(timestamp)).ignore(),
+                Event::Request(DeployBufferRequest::GetStuckDeploys { responder }) => {
+                    responder.respond(self.stuck_deploys()).ignore()
+                }
                 Event::BlockFinalized(finalized_block) => {
                     self.register_block_finalized(&finalized_block);
                     Effects::new()
@@ -567,17 +751,23 @@ where
                 Event::ReceiveDeployGossiped(deploy_id) => {
                     self.register_deploy_gossiped(deploy_id, effect_builder)
                 }
-                Event::StoredDeploy(deploy_id, maybe_deploy) => {
-                    match maybe_deploy {
-                        Some(deploy) => {
-                            self.register_deploy(*deploy);
-                        }
-                        None => {
-                            warn!("cannot register un-stored deploy({})", deploy_id);
+                Event::StoredDeploy(deploy_id, maybe_deploy) => match maybe_deploy {
+                    Some(deploy) => {
+                        let deploy_hash = *deploy.hash();
+                        let replaced = self.register_deploy(*deploy);
+                        if replaced.is_empty() {
+                            Effects::new()
+                        } else {
+                            effect_builder
+                                .announce_replaced_deploys(replaced, deploy_hash)
+                                .ignore()
                         }
                     }
-                    Effects::new()
-                }
+                    None => {
+                        warn!("cannot register un-stored deploy({})", deploy_id);
+                        Effects::new()
+                    }
+                },
                 Event::Expire => self.expire(effect_builder),
             },
         }
@@ -4,12 +4,19 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_EXPIRY_CHECK_INTERVAL: &str = "1min";
+const DEFAULT_REPLACEMENT_WINDOW: &str = "0s";
+const DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT: u32 = 1000;
 
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The interval of checking for expired deploys.
     pub expiry_check_interval: TimeDiff,
+    /// Pending deploys by the same account with timestamps at most this far apart conflict: only
+    /// the one with the highest payment is kept.
+    pub replacement_window: TimeDiff,
+    /// The maximum number of pending deploys per account. Further deploys are not buffered.
+    pub max_pending_deploys_per_account: u32,
 }
 
 impl Config {
@@ -17,12 +24,24 @@ impl Config {
     pub fn expiry_check_interval(&self) -> TimeDiff {
         self.expiry_check_interval
     }
+
+    /// Returns the maximum timestamp difference of conflicting pending deploys.
+    pub fn replacement_window(&self) -> TimeDiff {
+        self.replacement_window
+    }
+
+    /// Returns the maximum number of pending deploys per account.
+    pub fn max_pending_deploys_per_account(&self) -> u32 {
+        self.max_pending_deploys_per_account
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             expiry_check_interval: DEFAULT_EXPIRY_CHECK_INTERVAL.parse().unwrap(),
+            replacement_window: DEFAULT_REPLACEMENT_WINDOW.parse().unwrap(),
+            max_pending_deploys_per_account: DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT,
         }
     }
 }
@@ -1,4 +1,4 @@
-use prometheus::{IntGauge, Registry};
+use prometheus::{IntCounter, IntGauge, Registry};
 
 use crate::unregister_metric;
 
@@ -11,6 +11,8 @@ pub(super) struct Metrics {
     pub(super) held_deploys: IntGauge,
     /// Number of deploys that should not be included in future proposals ever again.
     pub(super) dead_deploys: IntGauge,
+    /// Number of deploys that were not buffered because their account had too many pending ones.
+    pub(super) rejected_over_limit_deploys: IntCounter,
     registry: Registry,
 }
 
@@ -29,15 +31,22 @@ impl Metrics {
             "deploy_buffer_dead_deploys".to_string(),
             "number of deploys that should not be included in future proposals.".to_string(),
         )?;
+        let rejected_over_limit_deploys = IntCounter::new(
+            "deploy_buffer_rejected_over_limit_deploys".to_string(),
+            "number of deploys rejected because their account had too many pending deploys."
+                .to_string(),
+        )?;
 
         registry.register(Box::new(total_deploys.clone()))?;
         registry.register(Box::new(held_deploys.clone()))?;
         registry.register(Box::new(dead_deploys.clone()))?;
+        registry.register(Box::new(rejected_over_limit_deploys.clone()))?;
 
         Ok(Metrics {
             total_deploys,
             held_deploys,
             dead_deploys,
+            rejected_over_limit_deploys,
             registry: registry.clone(),
         })
     }
@@ -48,5 +57,6 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.total_deploys);
         unregister_metric!(self.registry, self.held_deploys);
         unregister_metric!(self.registry, self.dead_deploys);
+        unregister_metric!(self.registry, self.rejected_over_limit_deploys);
     }
 }
@@ -5,7 +5,10 @@ use crate::{
     types::{Block, FinalizedBlock},
     utils,
 };
-use casper_types::{testing::TestRng, EraId, TimeDiff};
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::Bytes, runtime_args, testing::TestRng, EraId, RuntimeArgs, SecretKey, TimeDiff, U512,
+};
 use prometheus::Registry;
 use rand::Rng;
 
@@ -63,6 +66,51 @@ fn create_valid_deploys(
     deploys
 }
 
+// Generates a valid deploy by the given account, with the given payment amount.
+fn create_deploy_with_payment(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    payment_amount: u64,
+) -> Deploy {
+    create_deploy(secret_key, timestamp, payment_amount, vec![])
+}
+
+// Generates a valid deploy by the given account, depending on the given deploys.
+fn create_deploy_with_dependencies(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    dependencies: Vec<DeployHash>,
+) -> Deploy {
+    create_deploy(secret_key, timestamp, 1_000, dependencies)
+}
+
+fn create_deploy(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    payment_amount: u64,
+    dependencies: Vec<DeployHash>,
+) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { "amount" => U512::from(payment_amount) },
+    };
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: RuntimeArgs::new(),
+    };
+    Deploy::new(
+        timestamp,
+        TimeDiff::from_seconds(60),
+        1,
+        dependencies,
+        "casper-example".to_string(),
+        payment,
+        session,
+        secret_key,
+        None,
+    )
+}
+
 fn create_invalid_deploys(rng: &mut TestRng, size: usize) -> Vec<Deploy> {
     let mut deploys = create_valid_deploys(rng, size, DeployType::Random, None, None);
 
@@ -115,17 +163,17 @@ fn register_deploy_and_check_size() {
     let num_valid_deploys: usize = rng.gen_range(50..500);
     let valid_deploys =
         create_valid_deploys(&mut rng, num_valid_deploys, DeployType::Random, None, None);
-    valid_deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    valid_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, valid_deploys.len(), 0, 0);
 
     // Try to register invalid deploys
     let num_invalid_deploys: usize = rng.gen_range(10..100);
     let invalid_deploys = create_invalid_deploys(&mut rng, num_invalid_deploys);
-    invalid_deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    invalid_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, valid_deploys.len(), 0, 0);
 
     // Try to register a duplicate deploy
@@ -142,6 +190,185 @@ fn register_deploy_and_check_size() {
     assert_container_sizes(&deploy_buffer, valid_deploys.len(), 0, 0);
 }
 
+#[test]
+fn replace_pending_deploy_with_higher_payment() {
+    let mut rng = TestRng::new();
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let secret_key = SecretKey::random(&mut rng);
+    let timestamp = Timestamp::now();
+    let cheap_deploy = create_deploy_with_payment(&secret_key, timestamp, 1_000);
+    let expensive_deploy = create_deploy_with_payment(&secret_key, timestamp, 2_000);
+
+    // The deploy with the higher payment replaces the pending one.
+    assert!(deploy_buffer
+        .register_deploy(cheap_deploy.clone())
+        .is_empty());
+    assert_eq!(
+        vec![*cheap_deploy.hash()],
+        deploy_buffer.register_deploy(expensive_deploy.clone())
+    );
+    assert_container_sizes(&deploy_buffer, 1, 0, 0);
+    assert!(deploy_buffer.buffer.contains_key(expensive_deploy.hash()));
+
+    // The replaced deploy can't come back.
+    assert!(deploy_buffer.register_deploy(cheap_deploy).is_empty());
+    assert_container_sizes(&deploy_buffer, 1, 0, 0);
+    assert!(deploy_buffer.buffer.contains_key(expensive_deploy.hash()));
+
+    // Deploys outside of the replacement window don't conflict.
+    let later_deploy =
+        create_deploy_with_payment(&secret_key, timestamp + TimeDiff::from_millis(1), 1_000);
+    assert!(deploy_buffer.register_deploy(later_deploy).is_empty());
+    assert_container_sizes(&deploy_buffer, 2, 0, 0);
+
+    // Deploys that have already been included in a block can't be replaced.
+    deploy_buffer.dead.insert(*expensive_deploy.hash());
+    let replacement_deploy = create_deploy_with_payment(&secret_key, timestamp, 3_000);
+    assert!(deploy_buffer.register_deploy(replacement_deploy).is_empty());
+    assert!(deploy_buffer.buffer.contains_key(expensive_deploy.hash()));
+}
+
+#[test]
+fn limit_pending_deploys_per_account() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        max_pending_deploys_per_account: 2,
+        ..Config::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), config, &Registry::new()).unwrap();
+    let secret_key = SecretKey::random(&mut rng);
+    let timestamp = Timestamp::now();
+    let deploys: Vec<_> = (0..3)
+        .map(|i| {
+            create_deploy_with_payment(&secret_key, timestamp + TimeDiff::from_millis(i), 1_000)
+        })
+        .collect();
+    deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
+    assert_container_sizes(&deploy_buffer, 2, 0, 0);
+    assert_eq!(1, deploy_buffer.metrics.rejected_over_limit_deploys.get());
+
+    // Other accounts are not affected.
+    let other_deploy = create_deploy_with_payment(&SecretKey::random(&mut rng), timestamp, 1_000);
+    deploy_buffer.register_deploy(other_deploy);
+    assert_container_sizes(&deploy_buffer, 3, 0, 0);
+
+    // Once a deploy has been included in a block, the account can buffer another one.
+    deploy_buffer.dead.insert(*deploys[0].hash());
+    deploy_buffer.register_deploy(deploys[2].clone());
+    assert_container_sizes(&deploy_buffer, 4, 1, 0);
+}
+
+#[test]
+fn appendable_block_is_shared_fairly_between_accounts() {
+    let mut rng = TestRng::new();
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: 4,
+        ..DeployConfig::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let spammer_key = SecretKey::random(&mut rng);
+    let other_key = SecretKey::random(&mut rng);
+    let timestamp = Timestamp::now().saturating_sub(TimeDiff::from_seconds(10));
+    for i in 0..20 {
+        let deploy =
+            create_deploy_with_payment(&spammer_key, timestamp + TimeDiff::from_millis(i), 1_000);
+        deploy_buffer.register_deploy(deploy);
+    }
+    let other_deploys: Vec<_> = (0..2)
+        .map(|i| {
+            create_deploy_with_payment(&other_key, timestamp + TimeDiff::from_millis(i), 1_000)
+        })
+        .collect();
+    other_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
+    assert_container_sizes(&deploy_buffer, 22, 0, 0);
+
+    // Despite the spammer's many deploys, both of the other account's deploys are included.
+    let appendable_block = deploy_buffer.appendable_block(Timestamp::now());
+    let included = appendable_block.deploy_and_transfer_set();
+    assert_eq!(4, included.len());
+    for deploy in &other_deploys {
+        assert!(included.contains(deploy.hash()));
+    }
+}
+
+#[test]
+fn propose_deploys_only_after_their_dependencies_are_executed() {
+    let mut rng = TestRng::new();
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let timestamp = Timestamp::now().saturating_sub(TimeDiff::from_seconds(10));
+    let prerequisite =
+        create_deploy_with_dependencies(&SecretKey::random(&mut rng), timestamp, vec![]);
+    let dependent = create_deploy_with_dependencies(
+        &SecretKey::random(&mut rng),
+        timestamp,
+        vec![*prerequisite.hash()],
+    );
+    let unknown_hash = DeployHash::random(&mut rng);
+    let orphan = create_deploy_with_dependencies(
+        &SecretKey::random(&mut rng),
+        timestamp,
+        vec![unknown_hash],
+    );
+    for deploy in [&prerequisite, &dependent, &orphan] {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+    assert_container_sizes(&deploy_buffer, 3, 0, 0);
+
+    let proposable_hashes = |deploy_buffer: &DeployBuffer| -> HashSet<DeployHash> {
+        deploy_buffer
+            .proposable()
+            .into_iter()
+            .map(|(with_approvals, _)| *with_approvals.deploy_hash())
+            .collect()
+    };
+    assert_eq!(
+        HashSet::from_iter([*prerequisite.hash()]),
+        proposable_hashes(&deploy_buffer)
+    );
+    let mut expected_stuck = vec![
+        StuckDeploy {
+            deploy_hash: *dependent.hash(),
+            pending_dependencies: vec![*prerequisite.hash()],
+            unknown_dependencies: vec![],
+        },
+        StuckDeploy {
+            deploy_hash: *orphan.hash(),
+            pending_dependencies: vec![],
+            unknown_dependencies: vec![unknown_hash],
+        },
+    ];
+    expected_stuck.sort_by_key(|stuck_deploy| stuck_deploy.deploy_hash);
+    assert_eq!(expected_stuck, deploy_buffer.stuck_deploys());
+
+    // Finalizing the prerequisite is not enough, it must have been executed.
+    let finalized_block = FinalizedBlock::random_with_deploys(&mut rng, [&prerequisite]);
+    deploy_buffer.register_block_finalized(&finalized_block);
+    assert!(proposable_hashes(&deploy_buffer).is_empty());
+
+    let block = Block::random_with_deploys(&mut rng, [&prerequisite]);
+    deploy_buffer.register_block(&block);
+    assert_eq!(
+        HashSet::from_iter([*dependent.hash()]),
+        proposable_hashes(&deploy_buffer)
+    );
+    assert_eq!(
+        vec![StuckDeploy {
+            deploy_hash: *orphan.hash(),
+            pending_dependencies: vec![],
+            unknown_dependencies: vec![unknown_hash],
+        }],
+        deploy_buffer.stuck_deploys()
+    );
+}
+
 #[test]
 fn register_block_with_valid_deploys() {
     let mut rng = TestRng::new();
@@ -176,9 +403,9 @@ fn get_proposable_deploys() {
 
     // populate deploy buffer with some deploys
     let deploys = create_valid_deploys(&mut rng, 50, DeployType::Random, None, None);
-    deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, deploys.len(), 0, 0);
 
     // Create a block with some deploys and register it with the deploy_buffer
@@ -290,9 +517,9 @@ fn get_appendable_block(
 ) {
     // populate deploy buffer with more transfers than a block can fit
     let deploys = create_valid_deploys(rng, deploy_limit + 50, deploy_type, None, None);
-    deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(deploy_buffer, deploys.len(), 0, 0);
 
     // now check how many transfers were added in the block; should not exceed the config limits.
@@ -317,9 +544,9 @@ fn register_deploys_and_blocks() {
     let num_valid_deploys: usize = rng.gen_range(50..500);
     let valid_deploys =
         create_valid_deploys(&mut rng, num_valid_deploys, DeployType::Random, None, None);
-    valid_deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    valid_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, valid_deploys.len(), 0, 0);
 
     // register a block with deploys
@@ -334,9 +561,9 @@ fn register_deploys_and_blocks() {
     );
 
     // try to register the deploys of the block again. Should not work since those deploys are dead.
-    block_deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    block_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(
         &deploy_buffer,
         block_deploys.len() + valid_deploys.len(),
@@ -367,7 +594,9 @@ fn register_deploys_and_blocks() {
         })
         .peekable();
     assert!(held_deploys.peek().is_some());
-    held_deploys.for_each(|deploy| deploy_buffer.register_deploy(deploy));
+    held_deploys.for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy);
+    });
     assert_container_sizes(
         &deploy_buffer,
         block_deploys.len() + valid_deploys.len(),
@@ -465,9 +694,9 @@ async fn expire_deploys_and_check_announcement() {
         Some(past_timestamp),
         Some(ttl),
     );
-    expired_deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    expired_deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, expired_deploys.len(), 0, 0);
 
     // include the last expired deploy in a block and register it
@@ -477,9 +706,9 @@ async fn expire_deploys_and_check_announcement() {
 
     // generate and register some valid deploys
     let deploys = create_valid_deploys(&mut rng, num_deploys, DeployType::Transfer, None, None);
-    deploys
-        .iter()
-        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    deploys.iter().for_each(|deploy| {
+        deploy_buffer.register_deploy(deploy.clone());
+    });
     assert_container_sizes(&deploy_buffer, deploys.len() + expired_deploys.len(), 1, 0);
 
     // expire deploys and check that they were announced as expired
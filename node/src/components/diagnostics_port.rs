@@ -25,7 +25,10 @@ use crate::{
     effect::{
         announcements::ControlAnnouncement,
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{
+            BlockAccumulatorRequest, ContractRuntimeRequest, NetworkInfoRequest,
+            SetNodeStopRequest, StorageRequest, ZkProverRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     reactor::main_reactor::MainEvent,
@@ -108,6 +111,10 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<BlockAccumulatorRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<ZkProverRequest>
         + Send,
 {
     type Event = Event;
@@ -162,6 +169,10 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<BlockAccumulatorRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<ZkProverRequest>
         + Send,
 {
     fn state(&self) -> &ComponentState {
@@ -186,6 +197,10 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<BlockAccumulatorRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<ZkProverRequest>
         + Send,
 {
     type Error = Error;
@@ -1,5 +1,6 @@
 use std::{
     fmt::{self, Display, Formatter},
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -7,7 +8,10 @@ use serde::Serialize;
 use structopt::StructOpt;
 use thiserror::Error;
 
+use casper_types::TimeDiff;
+
 use super::StopAtSpec;
+use crate::{components::zk_prover::JobId, types::NodeId};
 
 /// Command processing error.
 ///
@@ -84,6 +88,13 @@ pub(super) enum Action {
     GetLogFilter,
     /// Change the current log filter configuration.
     SetLogFilter { directive: String },
+    /// Read the config file again and apply the changed settings which can be reloaded, like on
+    /// `SIGHUP`.
+    ///
+    /// These are `logging.filter`, the `gossip` section, the `qps_limit` of the RPC and
+    /// speculative execution servers, and `network.known_addresses`. Changes to other settings are
+    /// logged and only take effect after a restart.
+    ReloadConfig,
     /// Dump the state of the consensus component.
     ///
     /// It is recommended to set the output format to `bincode` if the data is to be visualized
@@ -91,11 +102,110 @@ pub(super) enum Action {
     DumpConsensus {
         /// Era to dump. If omitted, dumps the latest era.
         era: Option<u64>,
+        /// Write the dump in bincode to this file on the node instead of sending it, so that it
+        /// can be replayed offline with the `replay-consensus` subcommand. The file must not exist
+        /// yet.
+        #[structopt(long, parse(from_os_str))]
+        file: Option<PathBuf>,
     },
     /// Dump the event queues.
     DumpQueues,
+    /// Show the current depth of every event queue and the rate at which events were dispatched
+    /// from each recently, along with the most frequently dispatched events.
+    ///
+    /// Works even if the reactor is stuck, as it does not wait for the reactor to handle it.
+    InspectQueues {
+        /// How far back to look at dispatched events, e.g. `30s` or `5min`. At most 5 minutes.
+        #[structopt(short, long, default_value = "1min")]
+        window: TimeDiff,
+        /// Number of most frequently dispatched events to list.
+        #[structopt(short, long, default_value = "10")]
+        top: usize,
+    },
+    /// List the time spent dispatching each kind of event since the node started, per component
+    /// and event, largest total first.
+    DispatchTimes,
     /// Get detailed networking insights.
     NetInfo,
+    /// List the bytes sent to and received from each peer, per message kind, busiest peers first.
+    NetStats,
+    /// List the reputations of all peers which committed offenses or were banned, worst first.
+    PeerReputations,
+    /// Ban a peer by node ID, disconnecting it and refusing further connections until the ban
+    /// expires.
+    ///
+    /// Bans persist across restarts of the node. An existing ban is never shortened.
+    BanPeer {
+        /// Full hex-encoded node ID of the peer.
+        peer_id: NodeId,
+        /// How long to ban the peer for, e.g. `30min` or `7days`.
+        #[structopt(short, long, default_value = "1day")]
+        duration: TimeDiff,
+    },
+    /// Lift the ban of a peer.
+    UnbanPeer {
+        /// Full hex-encoded node ID of the peer.
+        peer_id: NodeId,
+    },
+    /// Dump the state of the block accumulator as JSON, including its block acceptors, their
+    /// signature weight progress, and the known child blocks.
+    DumpBlockAccumulator,
+    /// List the blocks held by the block accumulator within a height range, along with their
+    /// finality status.
+    AccumulatedBlocks {
+        /// Lowest block height to include.
+        start_height: u64,
+        /// Highest block height to include.
+        end_height: u64,
+    },
+    /// Prune the bodies, deploys and execution results of all blocks below the given height,
+    /// keeping their headers and finality signatures.
+    ///
+    /// The highest complete block, blocks from the switch block preceding the last emergency
+    /// restart onwards, and blocks whose deploys are still within the max TTL are never pruned.
+    Prune {
+        /// Height below which to prune.
+        below_height: u64,
+    },
+    /// Create a consistent copy of the storage databases in a new directory, without stopping the
+    /// node.
+    ///
+    /// The storage component does not handle other requests while copying. Global state is not
+    /// included.
+    CreateSnapshot {
+        /// Directory to create the snapshot in, which must not exist yet.
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Verify the integrity of all stored blocks, reporting any inconsistencies found.
+    ///
+    /// Re-hashes block headers and bodies, and verifies stored finality signatures against the
+    /// validator weights of their era.
+    VerifyStorage {
+        /// Discard inconsistent data and mark the affected blocks as incomplete, so that they are
+        /// synchronized again.
+        #[structopt(long)]
+        repair: bool,
+    },
+    /// Delete the global state tries no longer reachable from the state of any retained block.
+    ///
+    /// Requires trie garbage collection to be enabled in the contract runtime config.  Tries
+    /// written within the configured grace period are never deleted.
+    TrieGc {
+        /// Only report what would be collected, without deleting anything.
+        #[structopt(long)]
+        dry_run: bool,
+    },
+    /// List the queued and running zero-knowledge proving jobs, running ones first.
+    ProvingJobs,
+    /// Cancel a queued or running zero-knowledge proving job, and remove it from storage.
+    ///
+    /// Proving cannot be interrupted, so a running job keeps its worker thread busy until it
+    /// finishes, but its result is discarded.
+    CancelProvingJob {
+        /// Full hex-encoded ID of the job.
+        job_id: JobId,
+    },
     /// Stop the node at a certain condition.
     Stop {
         /// When to stop the node.
@@ -158,14 +268,125 @@ impl Command {
 
 #[cfg(test)]
 mod tests {
-    use crate::components::diagnostics_port::command::{Action, Command};
+    use std::path::PathBuf;
+
+    use casper_types::TimeDiff;
+
+    use crate::{
+        components::{
+            diagnostics_port::command::{Action, Command},
+            zk_prover::JobId,
+        },
+        types::NodeId,
+    };
 
     #[test]
     fn can_parse_simple_commands() {
         let cmd = Command::from_line("dump-consensus 123").expect("command parsing failed");
-        assert!(matches!(cmd.action, Action::DumpConsensus { era } if era == Some(123)));
+        assert!(matches!(
+            cmd.action,
+            Action::DumpConsensus { era, file: None } if era == Some(123)
+        ));
+
+        let cmd = Command::from_line("dump-consensus --file /tmp/consensus.dump")
+            .expect("command parsing failed");
+        let dump_path = PathBuf::from("/tmp/consensus.dump");
+        assert!(matches!(
+            cmd.action,
+            Action::DumpConsensus { era: None, file: Some(file) } if file == dump_path
+        ));
 
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("inspect-queues").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::InspectQueues { window, top: 10 } if window == TimeDiff::from_seconds(60)
+        ));
+
+        let cmd = Command::from_line("inspect-queues --window 30s --top 3")
+            .expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::InspectQueues { window, top: 3 } if window == TimeDiff::from_seconds(30)
+        ));
+
+        let cmd = Command::from_line("dispatch-times").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DispatchTimes));
+
+        let cmd = Command::from_line("dump-block-accumulator").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DumpBlockAccumulator));
+
+        let cmd = Command::from_line("accumulated-blocks 10 20").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::AccumulatedBlocks {
+                start_height: 10,
+                end_height: 20
+            }
+        ));
+
+        let cmd = Command::from_line("prune 100").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::Prune { below_height: 100 }));
+
+        let cmd =
+            Command::from_line("create-snapshot /tmp/snapshot").expect("command parsing failed");
+        let snapshot_path = PathBuf::from("/tmp/snapshot");
+        assert!(matches!(cmd.action, Action::CreateSnapshot { path } if path == snapshot_path));
+
+        let cmd = Command::from_line("verify-storage").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::VerifyStorage { repair: false }
+        ));
+
+        let cmd = Command::from_line("verify-storage --repair").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::VerifyStorage { repair: true }));
+
+        let cmd = Command::from_line("trie-gc").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::TrieGc { dry_run: false }));
+
+        let cmd = Command::from_line("trie-gc --dry-run").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::TrieGc { dry_run: true }));
+
+        let cmd = Command::from_line("net-stats").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::NetStats));
+
+        let cmd = Command::from_line("peer-reputations").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::PeerReputations));
+
+        let hex_id = "ab".repeat(64);
+        let peer: NodeId = hex_id.parse().unwrap();
+        let cmd =
+            Command::from_line(&format!("ban-peer {}", hex_id)).expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::BanPeer { peer_id, duration }
+                if peer_id == peer && duration == TimeDiff::from_seconds(86_400)
+        ));
+
+        let cmd = Command::from_line(&format!("ban-peer tls:{} --duration 30min", hex_id))
+            .expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::BanPeer { peer_id, duration }
+                if peer_id == peer && duration == TimeDiff::from_seconds(1_800)
+        ));
+
+        let cmd =
+            Command::from_line(&format!("unban-peer {}", hex_id)).expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::UnbanPeer { peer_id } if peer_id == peer));
+        assert!(Command::from_line("unban-peer abc").is_err());
+
+        let cmd = Command::from_line("proving-jobs").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::ProvingJobs));
+
+        let hex_digest = "cd".repeat(32);
+        let job: JobId = hex_digest.parse().unwrap();
+        let cmd = Command::from_line(&format!("cancel-proving-job {}", hex_digest))
+            .expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::CancelProvingJob { job_id } if job_id == job));
+        assert!(Command::from_line("cancel-proving-job cdcd").is_err());
     }
 }
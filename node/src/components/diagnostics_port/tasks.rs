@@ -3,7 +3,7 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     fs::{self, File},
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
@@ -31,10 +31,14 @@ use super::{
 };
 use crate::{
     components::consensus::EraDump,
+    config_reload,
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{
+            BlockAccumulatorRequest, ContractRuntimeRequest, NetworkInfoRequest,
+            SetNodeStopRequest, StorageRequest, ZkProverRequest,
+        },
         EffectBuilder,
     },
     failpoints::FailpointActivation,
@@ -89,6 +93,23 @@ impl Display for Outcome {
     }
 }
 
+/// A list of items sent to the client, one item per line in interactive mode.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Listing<'a, T>(&'a [T]);
+
+impl<'a, T: Display> Display for Listing<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, item) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
 /// Configuration for a connection diagnostics port session.
 #[derive(Copy, Clone, Debug, Default, Serialize)]
 struct Session {
@@ -162,14 +183,16 @@ impl Session {
                 buf.push(b'\n');
                 Ok(buf)
             },
-            OutputFormat::Bincode => |data: &EraDump| {
-                bincode::serialize(&data).map_err(|err| {
-                    Cow::Owned(format!("failed to serialize era dump as bincode: {}", err))
-                })
-            },
+            OutputFormat::Bincode => Self::serialize_era_dump_bincode,
         }
     }
 
+    /// Serializes an `EraDump` as bincode.
+    fn serialize_era_dump_bincode(data: &EraDump<'_>) -> Result<Vec<u8>, Cow<'static, str>> {
+        bincode::serialize(&data)
+            .map_err(|err| Cow::Owned(format!("failed to serialize era dump as bincode: {}", err)))
+    }
+
     /// Creates a generic serializer that is writing to a temporary file.
     ///
     /// The resulting serializer will write to the given file.
@@ -204,6 +227,10 @@ impl Session {
             + From<ControlAnnouncement>
             + From<NetworkInfoRequest>
             + From<SetNodeStopRequest>
+            + From<BlockAccumulatorRequest>
+            + From<StorageRequest>
+            + From<ContractRuntimeRequest>
+            + From<ZkProverRequest>
             + Send,
     {
         debug!(%line, "line received");
@@ -270,16 +297,43 @@ impl Session {
                             .await?;
                         }
                     },
-                    Action::DumpConsensus { era } => {
+                    Action::ReloadConfig => {
+                        config_reload::request_reload();
+                        self.send_outcome(
+                            writer,
+                            &Outcome::success(
+                                "config reload requested, see the node log for the outcome",
+                            ),
+                        )
+                        .await?;
+                    }
+                    Action::DumpConsensus { era, ref file } => {
+                        let serializer = if file.is_some() {
+                            Self::serialize_era_dump_bincode
+                        } else {
+                            self.create_era_dump_serializer()
+                        };
                         let output = effect_builder
-                            .diagnostics_port_dump_consensus_state(
-                                era.map(EraId::new),
-                                self.create_era_dump_serializer(),
-                            )
+                            .diagnostics_port_dump_consensus_state(era.map(EraId::new), serializer)
                             .await;
 
-                        match output {
-                            Ok(ref data) => {
+                        match (output, file) {
+                            (Ok(ref data), Some(path)) => match write_new_file(path, data).await {
+                                Ok(()) => {
+                                    let msg =
+                                        format!("dumped consensus state to {}", path.display());
+                                    self.send_outcome(writer, &Outcome::success(msg)).await?;
+                                }
+                                Err(err) => {
+                                    let reason = format!(
+                                        "could not write consensus state to {}: {}",
+                                        path.display(),
+                                        err
+                                    );
+                                    self.send_outcome(writer, &Outcome::failed(reason)).await?;
+                                }
+                            },
+                            (Ok(ref data), None) => {
                                 self.send_outcome(
                                     writer,
                                     &Outcome::success("dumping consensus state"),
@@ -287,7 +341,7 @@ impl Session {
                                 .await?;
                                 writer.write_all(data).await?;
                             }
-                            Err(err) => {
+                            (Err(err), _) => {
                                 self.send_outcome(writer, &Outcome::failed(err)).await?;
                             }
                         }
@@ -318,12 +372,138 @@ impl Session {
                             }
                         };
                     }
+                    Action::InspectQueues { window, top } => {
+                        self.send_outcome(writer, &Outcome::success("inspecting queues"))
+                            .await?;
+                        let inspection = effect_builder
+                            .into_inner()
+                            .inspect_queues(window.into(), top);
+                        self.send_to_client(writer, &inspection).await?;
+                    }
+                    Action::DispatchTimes => {
+                        self.send_outcome(writer, &Outcome::success("listing dispatch times"))
+                            .await?;
+                        let dispatch_times = effect_builder.get_dispatch_times().await;
+                        self.send_to_client(writer, &dispatch_times).await?;
+                    }
                     Action::NetInfo => {
                         self.send_outcome(writer, &Outcome::success("collecting insights"))
                             .await?;
                         let insights = effect_builder.get_network_insights().await;
                         self.send_to_client(writer, &insights).await?;
                     }
+                    Action::NetStats => {
+                        self.send_outcome(writer, &Outcome::success("listing peer traffic"))
+                            .await?;
+                        let traffic = effect_builder.get_peer_traffic().await;
+                        self.send_to_client(writer, &Listing(&traffic)).await?;
+                    }
+                    Action::PeerReputations => {
+                        self.send_outcome(writer, &Outcome::success("listing peer reputations"))
+                            .await?;
+                        let reputations = effect_builder.get_peer_reputations().await;
+                        self.send_to_client(writer, &Listing(&reputations)).await?;
+                    }
+                    Action::BanPeer { peer_id, duration } => {
+                        let until = effect_builder.ban_peer(peer_id, duration).await;
+                        self.send_outcome(
+                            writer,
+                            &Outcome::success(format!("banned {} until {}", peer_id, until)),
+                        )
+                        .await?;
+                    }
+                    Action::UnbanPeer { peer_id } => {
+                        let outcome = if effect_builder.unban_peer(peer_id).await {
+                            Outcome::success(format!("unbanned {}", peer_id))
+                        } else {
+                            Outcome::failed(format!("{} is not banned", peer_id))
+                        };
+                        self.send_outcome(writer, &outcome).await?;
+                    }
+                    Action::DumpBlockAccumulator => {
+                        self.send_outcome(
+                            writer,
+                            &Outcome::success("dumping block accumulator state"),
+                        )
+                        .await?;
+                        let dump = effect_builder.get_block_accumulator_dump().await;
+                        self.send_to_client(writer, &dump).await?;
+                    }
+                    Action::AccumulatedBlocks {
+                        start_height,
+                        end_height,
+                    } => {
+                        self.send_outcome(writer, &Outcome::success("listing accumulated blocks"))
+                            .await?;
+                        let blocks = effect_builder
+                            .get_block_accumulator_blocks_in_range(start_height, end_height)
+                            .await;
+                        self.send_to_client(writer, &blocks).await?;
+                    }
+                    Action::Prune { below_height } => {
+                        self.send_outcome(writer, &Outcome::success("pruning blocks"))
+                            .await?;
+                        let outcome = effect_builder.prune_blocks_below(below_height).await;
+                        self.send_to_client(writer, &outcome).await?;
+                    }
+                    Action::CreateSnapshot { ref path } => {
+                        match effect_builder.create_storage_snapshot(path.clone()).await {
+                            Ok(snapshot) => {
+                                self.send_outcome(writer, &Outcome::success("created snapshot"))
+                                    .await?;
+                                self.send_to_client(writer, &snapshot).await?;
+                            }
+                            Err(err) => {
+                                self.send_outcome(
+                                    writer,
+                                    &Outcome::failed(format!("failed to create snapshot: {}", err)),
+                                )
+                                .await?;
+                            }
+                        }
+                    }
+                    Action::VerifyStorage { repair } => {
+                        self.send_outcome(writer, &Outcome::success("verifying storage"))
+                            .await?;
+                        let verification = effect_builder.verify_storage_integrity(repair).await;
+                        self.send_to_client(writer, &verification).await?;
+                    }
+                    Action::TrieGc { dry_run } => {
+                        match effect_builder.collect_trie_garbage(dry_run).await {
+                            Ok(outcome) => {
+                                self.send_outcome(
+                                    writer,
+                                    &Outcome::success("collected garbage tries"),
+                                )
+                                .await?;
+                                self.send_to_client(writer, &outcome).await?;
+                            }
+                            Err(err) => {
+                                self.send_outcome(
+                                    writer,
+                                    &Outcome::failed(format!(
+                                        "failed to collect garbage tries: {}",
+                                        err
+                                    )),
+                                )
+                                .await?;
+                            }
+                        }
+                    }
+                    Action::ProvingJobs => {
+                        self.send_outcome(writer, &Outcome::success("listing proving jobs"))
+                            .await?;
+                        let jobs = effect_builder.list_proving_jobs().await;
+                        self.send_to_client(writer, &jobs).await?;
+                    }
+                    Action::CancelProvingJob { job_id } => {
+                        let outcome = if effect_builder.cancel_proving_job(job_id).await {
+                            Outcome::success(format!("cancelled {}", job_id))
+                        } else {
+                            Outcome::failed(format!("{} is neither queued nor running", job_id))
+                        };
+                        self.send_outcome(writer, &outcome).await?;
+                    }
                     Action::Stop { at, clear } => {
                         let (msg, stop_at) = if clear {
                             ("clearing stopping point", None)
@@ -484,6 +664,17 @@ fn set_log_filter(filter_str: &str) -> Result<(), SetLogFilterError> {
     logging::reload_global_env_filter(new_filter).map_err(SetLogFilterError::SetFailed)
 }
 
+/// Writes `data` to a new file at `path`, failing if the file already exists.
+async fn write_new_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await?;
+    file.write_all(data).await?;
+    file.sync_all().await
+}
+
 /// Handler for client connection.
 ///
 /// The core loop for the diagnostics port; reads commands via unix socket and processes them.
@@ -503,6 +694,10 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<BlockAccumulatorRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<ZkProverRequest>
         + Send,
 {
     debug!("accepted new connection on diagnostics port");
@@ -547,6 +742,10 @@ pub(super) async fn server<REv>(
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<BlockAccumulatorRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<ZkProverRequest>
         + Send,
 {
     let handling_shutdown_receiver = shutdown_receiver.clone();
@@ -626,7 +825,10 @@ mod tests {
         effect::{
             announcements::ControlAnnouncement,
             diagnostics_port::DumpConsensusStateRequest,
-            requests::{NetworkInfoRequest, SetNodeStopRequest},
+            requests::{
+                BlockAccumulatorRequest, ContractRuntimeRequest, NetworkInfoRequest,
+                SetNodeStopRequest, StorageRequest, ZkProverRequest,
+            },
             EffectBuilder, EffectExt, Effects,
         },
         reactor::{
@@ -686,6 +888,14 @@ mod tests {
         NetworkInfoRequest(NetworkInfoRequest),
         #[from]
         SetNodeStopRequest(SetNodeStopRequest),
+        #[from]
+        BlockAccumulatorRequest(BlockAccumulatorRequest),
+        #[from]
+        StorageRequest(StorageRequest),
+        #[from]
+        ContractRuntimeRequest(ContractRuntimeRequest),
+        #[from]
+        ZkProverRequest(#[serde(skip_serializing)] ZkProverRequest),
     }
 
     impl Display for Event {
@@ -732,7 +942,11 @@ mod tests {
                 Event::DumpConsensusStateRequest(_)
                 | Event::SetNodeStopRequest(_)
                 | Event::ControlAnnouncement(_)
-                | Event::NetworkInfoRequest(_) => {
+                | Event::NetworkInfoRequest(_)
+                | Event::BlockAccumulatorRequest(_)
+                | Event::StorageRequest(_)
+                | Event::ContractRuntimeRequest(_)
+                | Event::ZkProverRequest(_) => {
                     panic!("unexpected: {}", event)
                 }
             }
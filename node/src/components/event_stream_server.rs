@@ -11,17 +11,20 @@
 //!
 //! This component is passive and receives announcements made by other components while never making
 //! a request of other components itself. The handled announcements are serialized to JSON and
-//! pushed to subscribers.
+//! pushed to subscribers, optionally restricted to given accounts, contracts or event types.
 //!
 //! This component uses a ring buffer for outbound events providing some robustness against
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
-//! has advanced past their last received event.
+//! has advanced past their last received event.  Optionally, events are also persisted to disk,
+//! allowing subscribers to resume from events which are no longer buffered.
 
 mod config;
 mod event;
 mod event_indexer;
+mod event_store;
 mod http_server;
 mod sse_server;
+mod subscription_filter;
 #[cfg(test)]
 mod tests;
 
@@ -49,6 +52,7 @@ use crate::{
 pub use config::Config;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
+use event_store::EventStore;
 use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
 
@@ -128,6 +132,11 @@ impl EventStreamServer {
 
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
 
+        let event_store = EventStore::open(
+            &self.storage_path,
+            self.config.event_stream_retention_length,
+        );
+
         let listening_address = match self.config.cors_origin.as_str() {
             "" => {
                 let (listening_address, server_with_shutdown) = warp::serve(sse_filter)
@@ -147,6 +156,7 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    event_store,
                 ));
                 listening_address
             }
@@ -169,6 +179,7 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    event_store,
                 ));
                 listening_address
             }
@@ -192,6 +203,7 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    event_store,
                 ));
                 listening_address
             }
@@ -265,6 +277,7 @@ where
                 | Event::DeployAccepted(_)
                 | Event::DeployProcessed { .. }
                 | Event::DeploysExpired(_)
+                | Event::DeploysReplaced { .. }
                 | Event::Fault { .. }
                 | Event::FinalitySignature(_)
                 | Event::Step { .. } => {
@@ -308,6 +321,18 @@ where
                     .into_iter()
                     .flat_map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
                     .collect(),
+                Event::DeploysReplaced {
+                    replaced,
+                    replacement,
+                } => replaced
+                    .into_iter()
+                    .flat_map(|deploy_hash| {
+                        self.broadcast(SseData::DeployReplaced {
+                            deploy_hash,
+                            replacement_deploy_hash: replacement,
+                        })
+                    })
+                    .collect(),
                 Event::Fault {
                     era_id,
                     public_key,
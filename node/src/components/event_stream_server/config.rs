@@ -9,6 +9,9 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default number of SSEs to buffer.
 const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 5000;
 
+/// Default number of SSEs to persist for replaying, persisting none.
+const DEFAULT_EVENT_STREAM_RETENTION_LENGTH: u32 = 0;
+
 /// Default maximum number of subscribers.
 const DEFAULT_MAX_CONCURRENT_SUBSCRIBERS: u32 = 100;
 
@@ -29,6 +32,10 @@ pub struct Config {
     /// Number of SSEs to buffer.
     pub event_stream_buffer_length: u32,
 
+    /// Number of SSEs to persist to disk, so that they can be replayed to clients resuming from
+    /// events no longer buffered.  Zero disables persisting events.
+    pub event_stream_retention_length: u32,
+
     /// Default maximum number of subscribers across all event streams permitted at any one time.
     pub max_concurrent_subscribers: u32,
 
@@ -43,6 +50,7 @@ impl Config {
             enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
+            event_stream_retention_length: DEFAULT_EVENT_STREAM_RETENTION_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
@@ -20,6 +20,10 @@ pub enum Event {
         execution_result: Box<ExecutionResult>,
     },
     DeploysExpired(Vec<DeployHash>),
+    DeploysReplaced {
+        replaced: Vec<DeployHash>,
+        replacement: DeployHash,
+    },
     Fault {
         era_id: EraId,
         public_key: Box<PublicKey>,
@@ -47,6 +51,17 @@ impl Display for Event {
                     deploy_hashes.iter().join(", ")
                 )
             }
+            Event::DeploysReplaced {
+                replaced,
+                replacement,
+            } => {
+                write!(
+                    formatter,
+                    "deploys replaced by {}: {}",
+                    replacement,
+                    replaced.iter().join(", ")
+                )
+            }
             Event::DeployProcessed { deploy_hash, .. } => {
                 write!(formatter, "deploy processed {}", deploy_hash)
             }
@@ -0,0 +1,298 @@
+//! Persistent store of the events sent on the event stream.
+//!
+//! The in-memory buffer only holds the most recent events, and is lost on restart.  The event store
+//! additionally appends every event to segment files on disk, so that a client resuming from an
+//! event which is no longer buffered can still be sent all events it missed, as long as they are
+//! within the configured retention length.
+//!
+//! Each segment is named after the ID of its first event, and holds a sequence of records, each
+//! being the little-endian event ID and length of the JSON-encoded data followed by the data
+//! itself.  Replaying across a wraparound of event IDs is not supported.
+
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use tracing::{debug, warn};
+
+use super::{
+    sse_server::{Id, ServerSentEvent},
+    SseData,
+};
+
+/// The name of the folder holding the segment files, relative to the storage path.
+const EVENT_STORE_DIR: &str = "sse_events";
+
+/// The number of segments the retained events are split into, so that dropping the oldest segment
+/// only discards a fraction of them.
+const SEGMENTS_PER_RETENTION_LENGTH: u32 = 10;
+
+/// A single segment file.
+#[derive(Debug)]
+struct Segment {
+    first_id: Id,
+    path: PathBuf,
+    event_count: u32,
+}
+
+/// Appends events to segment files, dropping the oldest segments once they are beyond the
+/// retention length.
+#[derive(Debug)]
+pub(super) struct EventStore {
+    dir: PathBuf,
+    retention_length: u32,
+    segment_length: u32,
+    segments: VecDeque<Segment>,
+    /// The file of the last segment, opened for appending.
+    writer: Option<File>,
+}
+
+impl EventStore {
+    /// Opens the event store in `storage_path`, returning `None` if `retention_length` is zero or
+    /// the store cannot be opened.
+    pub(super) fn open(storage_path: &Path, retention_length: u32) -> Option<Self> {
+        if retention_length == 0 {
+            return None;
+        }
+        let dir = storage_path.join(EVENT_STORE_DIR);
+        match Self::try_open(dir.clone(), retention_length) {
+            Ok(event_store) => {
+                debug!(
+                    dir = %dir.display(),
+                    segment_count = event_store.segments.len(),
+                    "opened sse event store"
+                );
+                Some(event_store)
+            }
+            Err(error) => {
+                warn!(dir = %dir.display(), %error, "failed to open sse event store");
+                None
+            }
+        }
+    }
+
+    fn try_open(dir: PathBuf, retention_length: u32) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut segments = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let first_id = match path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<Id>().ok())
+            {
+                Some(first_id) => first_id,
+                None => continue,
+            };
+            let event_count = read_segment(&path)?.len() as u32;
+            segments.push(Segment {
+                first_id,
+                path,
+                event_count,
+            });
+        }
+        segments.sort_by_key(|segment| segment.first_id);
+
+        let mut event_store = EventStore {
+            dir,
+            retention_length,
+            segment_length: (retention_length / SEGMENTS_PER_RETENTION_LENGTH).max(1),
+            segments: segments.into(),
+            writer: None,
+        };
+        event_store.prune();
+        Ok(event_store)
+    }
+
+    /// Appends `event` to the store.  Shutdown events are not stored, as replaying them would end
+    /// the client's stream.
+    pub(super) fn append(&mut self, event: &ServerSentEvent) {
+        let id = match (event.id, &event.data) {
+            (_, SseData::Shutdown) | (None, _) => return,
+            (Some(id), _) => id,
+        };
+        if let Err(error) = self.try_append(id, &event.data) {
+            warn!(%id, %error, "failed to store sse event");
+            // Start a new segment with the next event, rather than appending to a possibly
+            // truncated record.
+            self.writer = None;
+        }
+    }
+
+    fn try_append(&mut self, id: Id, data: &SseData) -> io::Result<()> {
+        let json = serde_json::to_vec(data)?;
+        let is_last_segment_full = self
+            .segments
+            .back()
+            .map_or(true, |segment| segment.event_count >= self.segment_length);
+        if self.writer.is_none() || is_last_segment_full {
+            let path = self.dir.join(format!("{:010}", id));
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            self.segments.push_back(Segment {
+                first_id: id,
+                path,
+                event_count: 0,
+            });
+            self.writer = Some(file);
+            self.prune();
+        }
+
+        let mut record = Vec::with_capacity(8 + json.len());
+        record.extend_from_slice(&id.to_le_bytes());
+        record.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        record.extend_from_slice(&json);
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_all(&record)?;
+        }
+        if let Some(segment) = self.segments.back_mut() {
+            segment.event_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Removes the oldest segments not required to hold the last `retention_length` events.
+    fn prune(&mut self) {
+        let mut event_count: u32 = self
+            .segments
+            .iter()
+            .map(|segment| segment.event_count)
+            .sum();
+        while let Some(oldest) = self.segments.front() {
+            if self.segments.len() == 1 || event_count - oldest.event_count < self.retention_length
+            {
+                break;
+            }
+            event_count -= oldest.event_count;
+            if let Err(error) = fs::remove_file(&oldest.path) {
+                warn!(file = %oldest.path.display(), %error, "failed to remove sse event segment");
+            }
+            let _ = self.segments.pop_front();
+        }
+    }
+
+    /// Returns a reader of the events currently in the store.
+    pub(super) fn reader(&self) -> EventStoreReader {
+        EventStoreReader {
+            segments: self
+                .segments
+                .iter()
+                .map(|segment| (segment.first_id, segment.path.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A snapshot of the segments of an `EventStore`, which can be read on a blocking thread.
+#[derive(Debug)]
+pub(super) struct EventStoreReader {
+    segments: Vec<(Id, PathBuf)>,
+}
+
+impl EventStoreReader {
+    /// Returns the stored events with IDs not less than `start_from`, up to but excluding the event
+    /// with ID `end_before` if given.
+    pub(super) fn read(&self, start_from: Id, end_before: Option<Id>) -> Vec<ServerSentEvent> {
+        // Skip all segments entirely preceding the one holding `start_from`.
+        let first_segment = self
+            .segments
+            .iter()
+            .rposition(|(first_id, _)| *first_id <= start_from)
+            .unwrap_or_default();
+
+        let mut events = vec![];
+        for (_, path) in &self.segments[first_segment..] {
+            let records = match read_segment(path) {
+                Ok(records) => records,
+                Err(error) => {
+                    warn!(file = %path.display(), %error, "failed to read sse event segment");
+                    continue;
+                }
+            };
+            for (id, data) in records {
+                if Some(id) == end_before {
+                    return events;
+                }
+                if id >= start_from {
+                    events.push(ServerSentEvent { id: Some(id), data });
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Reads all complete records of the segment at `path`.  A truncated record at the end, e.g. due
+/// to a crash while writing it, is ignored.
+fn read_segment(path: &Path) -> io::Result<Vec<(Id, SseData)>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut records = vec![];
+    let mut id_bytes = [0; 4];
+    let mut length_bytes = [0; 4];
+    loop {
+        match reader
+            .read_exact(&mut id_bytes)
+            .and_then(|()| reader.read_exact(&mut length_bytes))
+        {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error),
+        }
+        let id = Id::from_le_bytes(id_bytes);
+        let length = u32::from_le_bytes(length_bytes);
+        let mut json = vec![0; length as usize];
+        match reader.read_exact(&mut json) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error),
+        }
+        records.push((id, serde_json::from_slice(&json)?));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging;
+
+    #[test]
+    fn should_replay_retained_events_across_restarts() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events: Vec<_> = (0..50)
+            .map(|id| ServerSentEvent {
+                id: Some(id),
+                data: SseData::random_block_added(&mut rng),
+            })
+            .collect();
+
+        assert!(EventStore::open(tempdir.path(), 0).is_none());
+        let mut event_store = EventStore::open(tempdir.path(), 20).unwrap();
+        for event in &events[..30] {
+            event_store.append(event);
+        }
+        event_store.append(&ServerSentEvent {
+            id: Some(30),
+            data: SseData::Shutdown,
+        });
+        drop(event_store);
+
+        // Reopening should keep the stored events, and continue appending to them.
+        let mut event_store = EventStore::open(tempdir.path(), 20).unwrap();
+        for event in &events[30..] {
+            event_store.append(event);
+        }
+        let reader = event_store.reader();
+        assert_eq!(reader.read(40, Some(45)), events[40..45].to_vec());
+        assert_eq!(reader.read(45, None), events[45..].to_vec());
+
+        // At least the last 20 events should be retained, but no more than one extra segment.
+        let retained = reader.read(0, None);
+        assert!(retained.len() >= 20 && retained.len() <= 22);
+        assert_eq!(retained[..], events[events.len() - retained.len()..]);
+    }
+}
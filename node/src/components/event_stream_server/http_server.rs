@@ -4,12 +4,13 @@ use tokio::{
     sync::{broadcast, mpsc, oneshot},
     task,
 };
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 use wheelbuf::WheelBuf;
 
 use casper_types::ProtocolVersion;
 
 use super::{
+    event_store::EventStore,
     sse_server::{BroadcastChannelMessage, Id, NewSubscriberInfo, ServerSentEvent},
     Config, EventIndex, SseData,
 };
@@ -25,6 +26,9 @@ use super::{
 /// * `new_subscriber_info_receiver` is used to notify the server of the details of a new client
 ///   having subscribed to the event stream.  It allows the server to populate that client's stream
 ///   with the requested number of historical events.
+/// * `event_store` persists the events, if enabled, so that clients requesting events which are no
+///   longer buffered can still be provided with them.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
@@ -33,6 +37,7 @@ pub(super) async fn run(
     mut data_receiver: mpsc::UnboundedReceiver<(EventIndex, SseData)>,
     broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     mut new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
+    mut event_store: Option<EventStore>,
 ) {
     let server_joiner = task::spawn(server_with_shutdown);
 
@@ -57,6 +62,24 @@ pub(super) async fn run(
                         // If the client supplied a "start_from" index, provide the buffered events.
                         // If they requested more than is buffered, just provide the whole buffer.
                         if let Some(start_index) = subscriber.start_from {
+                            // If the client requested events preceding the buffered ones, provide
+                            // them from the event store first.
+                            let first_buffered_id = buffer.iter().next().and_then(|event| event.id);
+                            if let Some(reader) = event_store.as_ref().map(EventStore::reader) {
+                                if first_buffered_id.map_or(true, |id| start_index < id) {
+                                    let stored_events = task::spawn_blocking(move || {
+                                        reader.read(start_index, first_buffered_id)
+                                    })
+                                    .await
+                                    .unwrap_or_else(|error| {
+                                        warn!(%error, "failed to read stored events");
+                                        vec![]
+                                    });
+                                    for event in stored_events {
+                                        let _ = subscriber.initial_events_sender.send(event);
+                                    }
+                                }
+                            }
                             // If the buffer's first event ID is in the range [0, buffer size) or
                             // (Id::MAX - buffer size, Id::MAX], then the events in the buffer are
                             // considered to have their IDs wrapping round, or that was recently the
@@ -94,6 +117,9 @@ pub(super) async fn run(
                             // Buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
                             let event = ServerSentEvent { id: Some(event_index), data };
+                            if let Some(event_store) = event_store.as_mut() {
+                                event_store.append(&event);
+                            }
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
                             // This can validly fail if there are no connected clients, so don't log
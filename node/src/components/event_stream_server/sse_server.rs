@@ -38,6 +38,7 @@ use casper_types::{
     EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
 };
 
+use super::subscription_filter::SubscriptionFilter;
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
 #[cfg(test)]
 use crate::{testing, types::Block};
@@ -53,12 +54,19 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name to restrict events to those relating to the given accounts.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name to restrict events to those relating to the given contracts.
+pub const CONTRACT_QUERY_FIELD: &str = "contract";
+/// The URL query string field name to restrict events to the given event types.
+pub const EVENT_TYPES_QUERY_FIELD: &str = "event_types";
 
 /// The filter associated with `/events/main` path.
-const MAIN_FILTER: [EventFilter; 5] = [
+const MAIN_FILTER: [EventFilter; 6] = [
     EventFilter::BlockAdded,
     EventFilter::DeployProcessed,
     EventFilter::DeployExpired,
+    EventFilter::DeployReplaced,
     EventFilter::Fault,
     EventFilter::Step,
 ];
@@ -100,6 +108,11 @@ pub enum SseData {
     },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
+    /// The given pending deploy has been replaced by a conflicting deploy with a higher payment.
+    DeployReplaced {
+        deploy_hash: DeployHash,
+        replacement_deploy_hash: DeployHash,
+    },
     /// Generic representation of validator's fault in an era.
     Fault {
         era_id: EraId,
@@ -126,6 +139,7 @@ impl SseData {
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
+            SseData::DeployReplaced { .. } => filter.contains(&EventFilter::DeployReplaced),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
             SseData::Step { .. } => filter.contains(&EventFilter::Step),
@@ -146,7 +160,7 @@ impl SseData {
     }
 
     /// Returns a random `SseData::BlockAdded`.
-    pub(super) fn random_block_added(rng: &mut TestRng) -> Self {
+    pub(crate) fn random_block_added(rng: &mut TestRng) -> Self {
         let block = Block::random(rng);
         SseData::BlockAdded {
             block_hash: *block.hash(),
@@ -164,7 +178,7 @@ impl SseData {
     }
 
     /// Returns a random `SseData::DeployProcessed`.
-    pub(super) fn random_deploy_processed(rng: &mut TestRng) -> Self {
+    pub(crate) fn random_deploy_processed(rng: &mut TestRng) -> Self {
         let deploy = Deploy::random(rng);
         SseData::DeployProcessed {
             deploy_hash: Box::new(*deploy.hash()),
@@ -185,6 +199,14 @@ impl SseData {
         }
     }
 
+    /// Returns a random `SseData::DeployReplaced`.
+    pub(super) fn random_deploy_replaced(rng: &mut TestRng) -> Self {
+        SseData::DeployReplaced {
+            deploy_hash: DeployHash::random(rng),
+            replacement_deploy_hash: DeployHash::random(rng),
+        }
+    }
+
     /// Returns a random `SseData::Fault`.
     pub(super) fn random_fault(rng: &mut TestRng) -> Self {
         SseData::Fault {
@@ -270,6 +292,7 @@ pub(super) enum EventFilter {
     DeployAccepted,
     DeployProcessed,
     DeployExpired,
+    DeployReplaced,
     Fault,
     FinalitySignature,
     Step,
@@ -312,6 +335,7 @@ async fn filter_map_server_sent_event(
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
         | &SseData::DeployExpired { .. }
+        | &SseData::DeployReplaced { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
         | &SseData::FinalitySignature(_)
@@ -345,26 +369,33 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID, or `None` if not given, and the filter of the events to send
+/// from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
-
-    if query.len() > 1 {
-        return Err(create_422());
-    }
-
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
+/// Returns a 422 response if `query` has any entries other than "start_from" mapped to a value
+/// representing an event ID, or "account", "contract" and "event_types" mapped to valid lists.
+fn parse_query(
+    query: HashMap<String, String>,
+) -> Result<(Option<Id>, SubscriptionFilter), Response> {
+    let mut start_from = None;
+    let mut subscription_filter = SubscriptionFilter::default();
+    for (field, value) in &query {
+        let result = match field.as_str() {
+            QUERY_FIELD => match value.parse::<Id>() {
+                Ok(id) => {
+                    start_from = Some(id);
+                    Ok(())
+                }
+                Err(_) => return Err(create_422()),
+            },
+            ACCOUNT_QUERY_FIELD => subscription_filter.set_accounts(value),
+            CONTRACT_QUERY_FIELD => subscription_filter.set_contracts(value),
+            EVENT_TYPES_QUERY_FIELD => subscription_filter.set_event_types(value),
+            _ => return Err(create_422()),
+        };
+        result.map_err(|error| create_422_for_field(field, &error))?;
     }
+    Ok((start_from, subscription_filter))
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -384,8 +415,20 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>' and '{}=<EVENT TYPES>'\n",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, CONTRACT_QUERY_FIELD, EVENT_TYPES_QUERY_FIELD
+    )));
+    *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+    response
+}
+
+/// Creates a 422 response with a useful error message in the body for use in case of an invalid
+/// value of a field of the query string.
+fn create_422_for_field(field: &str, error: &str) -> Response {
+    let mut response = Response::new(Body::from(format!(
+        "invalid query field '{}': {}\n",
+        field, error
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -441,8 +484,8 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            let (start_from, subscription_filter) = match parse_query(query) {
+                Ok(parsed_query) => parsed_query,
                 Err(error_response) => return error_response,
             };
 
@@ -469,6 +512,7 @@ impl ChannelsAndFilter {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                subscription_filter,
                 remote_address,
             )))
             .into_response()
@@ -505,11 +549,12 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// skipped, and a `SubscriptionFilter` which further restricts them as requested in the query.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    subscription_filter: SubscriptionFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
@@ -548,7 +593,7 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // `subscription_filter` and `event_filter`.
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -557,6 +602,12 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
+        .filter(move |result| {
+            future::ready(match result {
+                Ok(event) => subscription_filter.matches(&event.data),
+                Err(_) => true,
+            })
+        })
         .filter_map(move |result| async move {
             match result {
                 Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
@@ -855,6 +906,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                SubscriptionFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
@@ -0,0 +1,197 @@
+//! Server-side filtering of the events sent to a client, as requested in its query string.
+//!
+//! Clients only interested in a few accounts or contracts can restrict their stream to the events
+//! relating to them, and to a subset of the event types of the path they subscribed to, rather than
+//! receiving and discarding all other events.
+
+use std::{collections::HashSet, convert::TryFrom};
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{AsymmetricType, ExecutionResult, HashAddr, Key, PublicKey};
+
+use super::{sse_server::EventFilter, SseData};
+
+/// The event types which can be given in the `event_types` query field, named like the variants of
+/// `SseData`.
+const EVENT_TYPES: [(&str, EventFilter); 8] = [
+    ("BlockAdded", EventFilter::BlockAdded),
+    ("DeployAccepted", EventFilter::DeployAccepted),
+    ("DeployProcessed", EventFilter::DeployProcessed),
+    ("DeployExpired", EventFilter::DeployExpired),
+    ("DeployReplaced", EventFilter::DeployReplaced),
+    ("Fault", EventFilter::Fault),
+    ("FinalitySignature", EventFilter::FinalitySignature),
+    ("Step", EventFilter::Step),
+];
+
+/// The restrictions a client placed on the events it receives.
+///
+/// If accounts or contracts are given, only events relating to at least one of them are sent.  The
+/// `ApiVersion` and `Shutdown` events are always sent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct SubscriptionFilter {
+    /// The public keys of the accounts of interest.
+    accounts: Option<HashSet<PublicKey>>,
+    /// The hashes of the contracts or contract packages of interest.
+    contracts: Option<HashSet<HashAddr>>,
+    /// The event types of interest.
+    event_types: Option<Vec<EventFilter>>,
+}
+
+impl SubscriptionFilter {
+    /// Restricts the events to those relating to one of the comma-separated, hex-encoded public
+    /// keys in `value`.
+    pub(super) fn set_accounts(&mut self, value: &str) -> Result<(), String> {
+        let accounts = split(value)
+            .map(|account| {
+                PublicKey::from_hex(account)
+                    .map_err(|error| format!("invalid public key '{}': {}", account, error))
+            })
+            .collect::<Result<_, _>>()?;
+        self.accounts = Some(accounts);
+        Ok(())
+    }
+
+    /// Restricts the events to those relating to one of the comma-separated, hex-encoded contract
+    /// or contract package hashes in `value`.
+    pub(super) fn set_contracts(&mut self, value: &str) -> Result<(), String> {
+        let contracts = split(value)
+            .map(|contract| {
+                base16::decode(contract)
+                    .ok()
+                    .and_then(|bytes| HashAddr::try_from(bytes.as_slice()).ok())
+                    .ok_or_else(|| format!("invalid contract hash '{}'", contract))
+            })
+            .collect::<Result<_, _>>()?;
+        self.contracts = Some(contracts);
+        Ok(())
+    }
+
+    /// Restricts the events to those of the comma-separated event types in `value`.
+    pub(super) fn set_event_types(&mut self, value: &str) -> Result<(), String> {
+        let event_types = split(value)
+            .map(|event_type| {
+                EVENT_TYPES
+                    .iter()
+                    .find(|(name, _)| *name == event_type)
+                    .map(|(_, filter)| *filter)
+                    .ok_or_else(|| format!("invalid event type '{}'", event_type))
+            })
+            .collect::<Result<_, _>>()?;
+        self.event_types = Some(event_types);
+        Ok(())
+    }
+
+    /// Returns whether `data` should be sent to the client.
+    pub(super) fn matches(&self, data: &SseData) -> bool {
+        if matches!(data, SseData::ApiVersion(_) | SseData::Shutdown) {
+            return true;
+        }
+        if let Some(event_types) = &self.event_types {
+            if !data.should_include(event_types) {
+                return false;
+            }
+        }
+        if self.accounts.is_none() && self.contracts.is_none() {
+            return true;
+        }
+        self.matches_account(data) || self.matches_contract(data)
+    }
+
+    fn matches_account(&self, data: &SseData) -> bool {
+        let accounts = match &self.accounts {
+            Some(accounts) => accounts,
+            None => return false,
+        };
+        let account = match data {
+            SseData::BlockAdded { block, .. } => block.proposer(),
+            SseData::DeployAccepted { deploy } => deploy.header().account(),
+            SseData::DeployProcessed { account, .. } => &**account,
+            SseData::Fault { public_key, .. } => &**public_key,
+            SseData::FinalitySignature(signature) => &signature.public_key,
+            SseData::ApiVersion(_)
+            | SseData::DeployExpired { .. }
+            | SseData::DeployReplaced { .. }
+            | SseData::Step { .. }
+            | SseData::Shutdown => return false,
+        };
+        accounts.contains(account)
+    }
+
+    fn matches_contract(&self, data: &SseData) -> bool {
+        let contracts = match &self.contracts {
+            Some(contracts) => contracts,
+            None => return false,
+        };
+        match data {
+            SseData::DeployAccepted { deploy } => {
+                [deploy.payment(), deploy.session()]
+                    .iter()
+                    .any(|item| match item {
+                        ExecutableDeployItem::StoredContractByHash { hash, .. } => {
+                            contracts.contains(&hash.value())
+                        }
+                        ExecutableDeployItem::StoredVersionedContractByHash { hash, .. } => {
+                            contracts.contains(&hash.value())
+                        }
+                        _ => false,
+                    })
+            }
+            SseData::DeployProcessed {
+                execution_result, ..
+            } => {
+                let effect = match &**execution_result {
+                    ExecutionResult::Failure { effect, .. }
+                    | ExecutionResult::Success { effect, .. } => effect,
+                };
+                effect.transforms.iter().any(|entry| {
+                    matches!(
+                        Key::from_formatted_str(&entry.key),
+                        Ok(Key::Hash(hash_addr)) if contracts.contains(&hash_addr)
+                    )
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Splits a comma-separated query value, ignoring empty elements.
+fn split(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_filter_by_account_and_event_type() {
+        let mut rng = crate::new_rng();
+        let (deploy_accepted, deploy) = SseData::random_deploy_accepted(&mut rng);
+        let block_added = SseData::random_block_added(&mut rng);
+        let fault = SseData::random_fault(&mut rng);
+
+        let mut filter = SubscriptionFilter::default();
+        assert!(filter.matches(&deploy_accepted) && filter.matches(&fault));
+
+        filter
+            .set_accounts(&format!("{},", deploy.header().account().to_hex()))
+            .unwrap();
+        assert!(filter.matches(&deploy_accepted));
+        assert!(!filter.matches(&block_added));
+        assert!(!filter.matches(&fault));
+        assert!(filter.matches(&SseData::Shutdown));
+
+        filter.set_event_types("BlockAdded,Fault").unwrap();
+        assert!(!filter.matches(&deploy_accepted));
+
+        assert!(filter.set_accounts("not-a-key").is_err());
+        assert!(filter.set_contracts("0102").is_err());
+        assert!(filter.set_event_types("BlockAdded,Unknown").is_err());
+        assert!(filter.set_contracts(&"ab".repeat(32)).is_ok());
+    }
+}
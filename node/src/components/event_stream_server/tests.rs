@@ -27,9 +27,9 @@ use casper_types::testing::TestRng;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, CONTRACT_QUERY_FIELD, EVENT_TYPES_QUERY_FIELD,
+    QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -199,7 +199,7 @@ struct TestFixture {
 impl TestFixture {
     /// Constructs a new `TestFixture` including `EVENT_COUNT` random events ready to be served.
     fn new(rng: &mut TestRng) -> Self {
-        const DISTINCT_EVENTS_COUNT: u32 = 7;
+        const DISTINCT_EVENTS_COUNT: u32 = 8;
 
         let _ = logging::init();
         let storage_dir = tempfile::tempdir().unwrap();
@@ -220,6 +220,7 @@ impl TestFixture {
                 4 => SseData::random_fault(rng),
                 5 => SseData::random_step(rng),
                 6 => SseData::random_finality_signature(rng),
+                7 => SseData::random_deploy_replaced(rng),
                 _ => unreachable!(),
             })
             .collect();
@@ -958,8 +959,9 @@ async fn should_handle_bad_url_query() {
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>' and '{}=<EVENT TYPES>'",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, CONTRACT_QUERY_FIELD, EVENT_TYPES_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -977,6 +979,31 @@ async fn should_handle_bad_url_query() {
         );
     }
 
+    // Invalid values of the filtering fields should be reported along with the field.
+    let urls = [
+        format!("{}?{}=not-a-key", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=0102", deploys_url, CONTRACT_QUERY_FIELD),
+        format!("{}?{}=NotAnEvent", sigs_url, EVENT_TYPES_QUERY_FIELD),
+    ];
+    for url in &urls {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "URL: {}",
+            url
+        );
+        assert!(
+            response
+                .text()
+                .await
+                .unwrap()
+                .starts_with("invalid query field"),
+            "URL: {}",
+            url
+        );
+    }
+
     fixture.stop_server().await;
 }
 
@@ -15,7 +15,7 @@ use std::{collections::HashMap, fmt::Debug, time::Duration};
 
 use datasize::DataSize;
 use prometheus::Registry;
-use tracing::trace;
+use tracing::{debug, error, trace};
 
 use crate::{
     components::Component,
@@ -50,6 +50,10 @@ pub(crate) use tag::Tag;
 pub(crate) type FetchResult<T> = Result<FetchedData<T>, Error<T>>;
 pub(crate) type FetchResponder<T> = Responder<FetchResult<T>>;
 
+/// A peer's smoothed response latency moves by `1 / LATENCY_SMOOTHING_DIVISOR` of the way towards
+/// each new sample.
+const LATENCY_SMOOTHING_DIVISOR: u32 = 4;
+
 /// The component which fetches an item from local component(s) or asks a peer if it's not
 /// available locally.
 #[derive(DataSize, Debug)]
@@ -59,6 +63,11 @@ where
 {
     get_from_peer_timeout: Duration,
     item_handles: HashMap<T::Id, HashMap<NodeId, ItemHandle<T>>>,
+    /// Whether each request should also be sent to the fastest other peer known to hold items.
+    is_racing: bool,
+    /// Smoothed response latencies of peers which have provided items, used to choose the peer to
+    /// race a request against.  Only populated if `is_racing` is set.
+    peer_latencies: HashMap<NodeId, Duration>,
     #[data_size(skip)]
     name: &'static str,
     #[data_size(skip)]
@@ -74,10 +83,97 @@ impl<T: FetchItem> Fetcher<T> {
         Ok(Fetcher {
             get_from_peer_timeout: config.get_from_peer_timeout().into(),
             item_handles: HashMap::new(),
+            is_racing: config.is_racing(name),
+            peer_latencies: HashMap::new(),
             name,
             metrics: Metrics::new(name, registry)?,
         })
     }
+
+    /// Sends the request for the item to the fastest known peer other than `peer`, to be raced
+    /// against the request already sent to `peer`.
+    fn race<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peer: NodeId,
+        validation_metadata: Box<T::ValidationMetadata>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message>> + Send,
+    {
+        let racer = match self
+            .peer_latencies
+            .iter()
+            .filter(|(other_peer, _)| **other_peer != peer)
+            .min_by_key(|(_, latency)| **latency)
+        {
+            Some((racer, _)) => *racer,
+            None => return Effects::new(),
+        };
+        let item_handles = match self.item_handles.get_mut(&id) {
+            Some(item_handles) => item_handles,
+            None => return Effects::new(),
+        };
+        match item_handles.get_mut(&peer) {
+            Some(item_handle) => item_handle.set_race_partner(Some(racer)),
+            None => return Effects::new(),
+        }
+        let message = match Message::new_get_request::<T>(&id) {
+            Ok(message) => message,
+            Err(error) => {
+                error!(%racer, %error, "failed to construct get request");
+                return Effects::new();
+            }
+        };
+        let _ = item_handles.insert(racer, ItemHandle::new_racer(validation_metadata, peer));
+        debug!(TAG=%T::TAG, %id, %peer, %racer, "racing fetch request");
+        self.metrics.fetch_total.inc();
+        self.metrics.races.inc();
+        let peer_timeout = self.get_from_peer_timeout;
+        async move {
+            effect_builder.send_message(racer, message).await;
+            effect_builder.set_timeout(peer_timeout).await
+        }
+        .event(move |_| Event::TimeoutPeer { id, peer: racer })
+    }
+
+    /// Records the latency of a response from `peer` to a request for the item with the given ID.
+    fn record_response_latency(&mut self, id: &T::Id, peer: NodeId) {
+        if !self.is_racing {
+            return;
+        }
+        let latency = match self
+            .item_handles
+            .get(id)
+            .and_then(|item_handles| item_handles.get(&peer))
+        {
+            Some(item_handle) => item_handle.sent_at().elapsed(),
+            None => return,
+        };
+        self.peer_latencies
+            .entry(peer)
+            .and_modify(|smoothed| {
+                *smoothed = (*smoothed * (LATENCY_SMOOTHING_DIVISOR - 1) + latency)
+                    / LATENCY_SMOOTHING_DIVISOR
+            })
+            .or_insert(latency);
+    }
+
+    /// Stops considering `peer` for racing requests if it failed to provide the item with the given
+    /// ID.
+    fn record_failure(&mut self, id: &T::Id, peer: NodeId) {
+        if !self.is_racing {
+            return;
+        }
+        let is_awaited = self
+            .item_handles
+            .get(id)
+            .map_or(false, |item_handles| item_handles.contains_key(&peer));
+        if is_awaited {
+            let _ = self.peer_latencies.remove(&peer);
+        }
+    }
 }
 
 impl<T, REv> Component<REv> for Fetcher<T>
@@ -122,16 +218,27 @@ where
                         .respond(Ok(FetchedData::from_storage(item)))
                         .ignore()
                 }
-                None => self.failed_to_get_locally(
-                    effect_builder,
-                    id,
-                    peer,
-                    validation_metadata,
-                    responder,
-                ),
+                None => {
+                    // Only race the first request for a given item.
+                    let race_validation_metadata = (self.is_racing
+                        && !self.item_handles.contains_key(&id))
+                    .then(|| validation_metadata.clone());
+                    let mut effects = self.failed_to_get_locally(
+                        effect_builder,
+                        id.clone(),
+                        peer,
+                        validation_metadata,
+                        responder,
+                    );
+                    if let Some(validation_metadata) = race_validation_metadata {
+                        effects.extend(self.race(effect_builder, id, peer, validation_metadata));
+                    }
+                    effects
+                }
             },
             Event::GotRemotely { item, source } => match source {
                 Source::PeerGossiped(peer) | Source::Peer(peer) => {
+                    self.record_response_latency(&item.fetch_id(), peer);
                     self.got_from_peer(effect_builder, peer, item)
                 }
                 Source::Client | Source::SpeculativeExec(_) | Source::Ourself => Effects::new(),
@@ -139,6 +246,7 @@ where
             Event::GotInvalidRemotely { .. } => Effects::new(),
             Event::AbsentRemotely { id, peer } => {
                 trace!(TAG=%T::TAG, %id, %peer, "item absent on the remote node");
+                self.record_failure(&id, peer);
                 self.signal(
                     id.clone(),
                     Err(Error::Absent {
@@ -150,6 +258,7 @@ where
             }
             Event::RejectedRemotely { id, peer } => {
                 trace!(TAG=%T::TAG, %id, %peer, "peer rejected fetch request");
+                self.record_failure(&id, peer);
                 self.signal(
                     id.clone(),
                     Err(Error::Rejected {
@@ -159,14 +268,17 @@ where
                     peer,
                 )
             }
-            Event::TimeoutPeer { id, peer } => self.signal(
-                id.clone(),
-                Err(Error::TimedOut {
-                    id: Box::new(id),
+            Event::TimeoutPeer { id, peer } => {
+                self.record_failure(&id, peer);
+                self.signal(
+                    id.clone(),
+                    Err(Error::TimedOut {
+                        id: Box::new(id),
+                        peer,
+                    }),
                     peer,
-                }),
-                peer,
-            ),
+                )
+            }
             Event::PutToStorage { item, peer } => {
                 let mut effects =
                     Self::announce_fetched_new_item(effect_builder, (*item).clone(), peer).ignore();
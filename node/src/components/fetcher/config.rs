@@ -8,9 +8,13 @@ use casper_types::TimeDiff;
 const DEFAULT_GET_FROM_PEER_TIMEOUT: &str = "3sec";
 
 /// Configuration options for fetching.
-#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     get_from_peer_timeout: TimeDiff,
+    /// Names of the fetchers which send each request both to the requested peer and to the
+    /// fastest other peer known to hold items of that type, responding with whichever valid item
+    /// arrives first.
+    racing_fetchers: Vec<String>,
 }
 
 impl Config {
@@ -18,12 +22,26 @@ impl Config {
     pub fn get_from_peer_timeout(&self) -> TimeDiff {
         self.get_from_peer_timeout
     }
+
+    /// Returns whether the fetcher with the given name should race requests to two peers.
+    pub fn is_racing(&self, fetcher_name: &str) -> bool {
+        self.racing_fetchers
+            .iter()
+            .any(|racing_fetcher| racing_fetcher == fetcher_name)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_racing_fetcher(mut self, fetcher_name: &str) -> Self {
+        self.racing_fetchers.push(fetcher_name.to_string());
+        self
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             get_from_peer_timeout: TimeDiff::from_str(DEFAULT_GET_FROM_PEER_TIMEOUT).unwrap(),
+            racing_fetchers: vec![],
         }
     }
 }
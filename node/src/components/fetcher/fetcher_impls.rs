@@ -1,4 +1,5 @@
 mod approvals_hashes_fetcher;
+mod block_body_or_chunk_fetcher;
 mod block_execution_results_or_chunk_fetcher;
 mod block_fetcher;
 mod block_header_fetcher;
@@ -0,0 +1,52 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    components::fetcher::{metrics::Metrics, Fetcher, ItemFetcher, ItemHandle, StoringState},
+    effect::{requests::StorageRequest, EffectBuilder},
+    types::{BlockBodyOrChunk, BlockBodyOrChunkId, NodeId},
+};
+
+#[async_trait]
+impl ItemFetcher<BlockBodyOrChunk> for Fetcher<BlockBodyOrChunk> {
+    const SAFE_TO_RESPOND_TO_ALL: bool = true;
+
+    fn item_handles(
+        &mut self,
+    ) -> &mut HashMap<BlockBodyOrChunkId, HashMap<NodeId, ItemHandle<BlockBodyOrChunk>>> {
+        &mut self.item_handles
+    }
+
+    fn metrics(&mut self) -> &Metrics {
+        &self.metrics
+    }
+
+    fn peer_timeout(&self) -> Duration {
+        self.get_from_peer_timeout
+    }
+
+    async fn get_locally<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        id: BlockBodyOrChunkId,
+    ) -> Option<BlockBodyOrChunk> {
+        effect_builder
+            .get_block_body_or_chunk_from_storage(id)
+            .await
+    }
+
+    fn put_to_storage<'a, REv>(
+        _effect_builder: EffectBuilder<REv>,
+        item: BlockBodyOrChunk,
+    ) -> StoringState<'a, BlockBodyOrChunk> {
+        // Stored by the BlockSynchronizer as part of the block once all chunks are fetched.
+        StoringState::WontStore(item)
+    }
+
+    async fn announce_fetched_new_item<REv: Send>(
+        _effect_builder: EffectBuilder<REv>,
+        _item: BlockBodyOrChunk,
+        _peer: NodeId,
+    ) {
+    }
+}
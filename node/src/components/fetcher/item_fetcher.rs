@@ -149,20 +149,24 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
     {
         self.metrics().found_on_peer.inc();
 
-        let validation_metadata = match self
+        let (validation_result, sent_at) = match self
             .item_handles()
             .get(&item.fetch_id())
             .and_then(|item_handles| item_handles.get(&peer))
         {
-            Some(item_handle) => item_handle.validation_metadata(),
+            Some(item_handle) => (
+                item.validate(item_handle.validation_metadata()),
+                item_handle.sent_at(),
+            ),
             None => {
                 debug!(item_id = %item.fetch_id(), tag = ?T::TAG, %peer, "got unexpected item from peer");
                 return Effects::new();
             }
         };
 
-        if let Err(err) = item.validate(validation_metadata) {
+        if let Err(err) = validation_result {
             debug!(%peer, %err, ?item, "peer sent invalid item");
+            self.metrics().validation_failures.inc();
             effect_builder
                 .announce_block_peer_with_justification(
                     peer,
@@ -173,6 +177,16 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
                 )
                 .ignore()
         } else {
+            let metrics = self.metrics();
+            metrics
+                .fetch_latency
+                .observe(sent_at.elapsed().as_secs_f64());
+            match bincode::serialized_size(&*item) {
+                Ok(size) => metrics.bytes_fetched.inc_by(size),
+                Err(error) => {
+                    debug!(%error, tag = ?T::TAG, "failed to get serialized size of item")
+                }
+            }
             match Self::put_to_storage(effect_builder, *item.clone()) {
                 StoringState::WontStore(item) => self.signal(item.fetch_id(), Ok(item), peer),
                 StoringState::Enqueued(store_future) => {
@@ -205,12 +219,20 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
         let mut item_handles = self.item_handles().remove(&id).unwrap_or_default();
         match result {
             Ok(item) => {
-                // Since this is a success, we can safely respond to all awaiting processes.
-                for responder in item_handles
-                    .remove(&peer)
-                    .map(ItemHandle::take_responders)
-                    .unwrap_or_default()
-                {
+                // Since this is a success, we can safely respond to all awaiting processes.  If
+                // the request was raced, the partner's request is cancelled and its processes are
+                // responded to as well, since they were awaiting the same item.
+                let mut responders = vec![];
+                if let Some(item_handle) = item_handles.remove(&peer) {
+                    if let Some(partner_handle) = item_handle
+                        .race_partner()
+                        .and_then(|partner| item_handles.remove(&partner))
+                    {
+                        responders.extend(partner_handle.take_responders());
+                    }
+                    responders.extend(item_handle.take_responders());
+                }
+                for responder in responders {
                     effects.extend(
                         responder
                             .respond(Ok(FetchedData::from_peer(item.clone(), peer)))
@@ -237,7 +259,9 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
                     None => false,
                 };
                 if should_remove_item_handle {
-                    item_handles.remove(&peer);
+                    if let Some(item_handle) = item_handles.remove(&peer) {
+                        Self::end_race(&mut item_handles, &item_handle);
+                    }
                 }
             }
             Err(
@@ -248,12 +272,11 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
             ) => {
                 // For all other error variants we can safely respond with failure as there's no
                 // chance for the request to succeed.
-                for responder in item_handles
-                    .remove(&peer)
-                    .map(ItemHandle::take_responders)
-                    .unwrap_or_default()
-                {
-                    effects.extend(responder.respond(Err(error.clone())).ignore());
+                if let Some(item_handle) = item_handles.remove(&peer) {
+                    Self::end_race(&mut item_handles, &item_handle);
+                    for responder in item_handle.take_responders() {
+                        effects.extend(responder.respond(Err(error.clone())).ignore());
+                    }
                 }
             }
         }
@@ -263,6 +286,16 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
         effects
     }
 
+    /// Unlinks the partner of a failed raced request, leaving it to complete on its own.
+    fn end_race(item_handles: &mut HashMap<NodeId, ItemHandle<T>>, item_handle: &ItemHandle<T>) {
+        if let Some(partner_handle) = item_handle
+            .race_partner()
+            .and_then(|partner| item_handles.get_mut(&partner))
+        {
+            partner_handle.set_race_partner(None);
+        }
+    }
+
     fn put_to_storage<'a, REv>(
         _effect_builder: EffectBuilder<REv>,
         _item: T,
@@ -1,6 +1,9 @@
+use std::time::Instant;
+
 use datasize::DataSize;
 
 use super::{FetchItem, FetchResponder};
+use crate::types::NodeId;
 
 #[derive(Debug, DataSize)]
 pub(crate) struct ItemHandle<T>
@@ -9,6 +12,11 @@ where
 {
     validation_metadata: Box<T::ValidationMetadata>,
     responders: Vec<FetchResponder<T>>,
+    /// The other peer to which the same request was sent, if the request is being raced.
+    race_partner: Option<NodeId>,
+    /// When the request was first sent to the peer.
+    #[data_size(skip)]
+    sent_at: Instant,
 }
 
 impl<T: FetchItem> ItemHandle<T> {
@@ -19,6 +27,24 @@ impl<T: FetchItem> ItemHandle<T> {
         Self {
             validation_metadata,
             responders: vec![responder],
+            race_partner: None,
+            sent_at: Instant::now(),
+        }
+    }
+
+    /// Returns a handle for a request raced against the same request sent to `race_partner`.
+    ///
+    /// The handle has no responders of its own; if it wins the race, the responders of the
+    /// partner's handle are responded to instead.
+    pub(super) fn new_racer(
+        validation_metadata: Box<T::ValidationMetadata>,
+        race_partner: NodeId,
+    ) -> Self {
+        Self {
+            validation_metadata,
+            responders: vec![],
+            race_partner: Some(race_partner),
+            sent_at: Instant::now(),
         }
     }
 
@@ -44,4 +70,16 @@ impl<T: FetchItem> ItemHandle<T> {
     pub(super) fn has_no_responders(&self) -> bool {
         self.responders.is_empty()
     }
+
+    pub(super) fn race_partner(&self) -> Option<NodeId> {
+        self.race_partner
+    }
+
+    pub(super) fn set_race_partner(&mut self, race_partner: Option<NodeId>) {
+        self.race_partner = race_partner
+    }
+
+    pub(super) fn sent_at(&self) -> Instant {
+        self.sent_at
+    }
 }
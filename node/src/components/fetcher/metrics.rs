@@ -1,6 +1,12 @@
-use prometheus::{IntCounter, Registry};
+use prometheus::{Histogram, IntCounter, Registry};
 
-use crate::unregister_metric;
+use crate::{unregister_metric, utils};
+
+// We use exponential buckets to observe the time it takes to fetch an item from a peer.  The
+// smallest bucket covers 10ms and each subsequent one doubles, up to roughly 20s.
+const EXPONENTIAL_BUCKET_START: f64 = 0.01;
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+const EXPONENTIAL_BUCKET_COUNT: usize = 12;
 
 #[derive(Debug)]
 pub(crate) struct Metrics {
@@ -10,8 +16,16 @@ pub(crate) struct Metrics {
     pub found_on_peer: IntCounter,
     /// Number of fetch requests that timed out.
     pub timeouts: IntCounter,
-    /// Number of total fetch requests made.
+    /// Number of total fetch requests made, i.e. attempts to fetch an item from a peer.
     pub fetch_total: IntCounter,
+    /// Number of items received from peers which failed validation.
+    pub validation_failures: IntCounter,
+    /// Total size in bytes of the valid items received from peers.
+    pub bytes_fetched: IntCounter,
+    /// Time between requesting an item from a peer and receiving it from that peer.
+    pub fetch_latency: Histogram,
+    /// Number of fetch requests additionally sent to a second peer to race the first.
+    pub races: IntCounter,
     /// Reference to the registry for unregistering.
     registry: Registry,
 }
@@ -37,16 +51,57 @@ impl Metrics {
             format!("{}_fetch_total", name),
             format!("number of {} all fetch requests made", name),
         )?;
+        let races = IntCounter::new(
+            format!("{}_races", name),
+            format!(
+                "number of {} fetch requests raced against a second peer",
+                name
+            ),
+        )?;
+        let validation_failures = IntCounter::new(
+            format!("{}_validation_failures", name),
+            format!(
+                "number of {} items received from peers which were invalid",
+                name
+            ),
+        )?;
+        let bytes_fetched = IntCounter::new(
+            format!("{}_bytes_fetched", name),
+            format!(
+                "total size in bytes of the valid {} items received from peers",
+                name
+            ),
+        )?;
+        let fetch_latency = utils::register_histogram_metric(
+            registry,
+            &format!("{}_fetch_latency_seconds", name),
+            &format!(
+                "time in seconds to receive {} items from peers after requesting them",
+                name
+            ),
+            prometheus::exponential_buckets(
+                EXPONENTIAL_BUCKET_START,
+                EXPONENTIAL_BUCKET_FACTOR,
+                EXPONENTIAL_BUCKET_COUNT,
+            )?,
+        )?;
         registry.register(Box::new(found_in_storage.clone()))?;
         registry.register(Box::new(found_on_peer.clone()))?;
         registry.register(Box::new(timeouts.clone()))?;
         registry.register(Box::new(fetch_total.clone()))?;
+        registry.register(Box::new(races.clone()))?;
+        registry.register(Box::new(validation_failures.clone()))?;
+        registry.register(Box::new(bytes_fetched.clone()))?;
 
         Ok(Metrics {
             found_in_storage,
             found_on_peer,
             timeouts,
             fetch_total,
+            races,
+            validation_failures,
+            bytes_fetched,
+            fetch_latency,
             registry: registry.clone(),
         })
     }
@@ -58,5 +113,9 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.found_on_peer);
         unregister_metric!(self.registry, self.timeouts);
         unregister_metric!(self.registry, self.fetch_total);
+        unregister_metric!(self.registry, self.races);
+        unregister_metric!(self.registry, self.validation_failures);
+        unregister_metric!(self.registry, self.bytes_fetched);
+        unregister_metric!(self.registry, self.fetch_latency);
     }
 }
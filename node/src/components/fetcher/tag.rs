@@ -53,4 +53,11 @@ pub enum Tag {
     /// The execution results for a single block.
     #[display(fmt = "block execution results")]
     BlockExecutionResults,
+    /// Signed switch block headers allowing a light client to follow the chain from a trusted
+    /// switch block to a later block.
+    #[display(fmt = "light client update")]
+    LightClientUpdate,
+    /// The body or a chunk of the body of a single block.
+    #[display(fmt = "block body or chunk")]
+    BlockBodyOrChunk,
 }
@@ -577,6 +577,7 @@ async fn should_fetch_from_peer() {
         )
         .await;
 
+    let deploy_size = bincode::serialized_size(&deploy).unwrap();
     let expected_result = ExpectedFetchedDeployResult::FromPeer {
         expected_deploy: Box::new(deploy),
         expected_peer: node_with_deploy,
@@ -592,6 +593,19 @@ async fn should_fetch_from_peer() {
     )
     .await;
 
+    let metrics = &network
+        .nodes()
+        .get(&node_without_deploy)
+        .unwrap()
+        .reactor()
+        .inner()
+        .deploy_fetcher
+        .metrics;
+    assert_eq!(metrics.fetch_total.get(), 1);
+    assert_eq!(metrics.validation_failures.get(), 0);
+    assert_eq!(metrics.bytes_fetched.get(), deploy_size);
+    assert_eq!(metrics.fetch_latency.get_sample_count(), 1);
+
     NetworkController::<Message>::remove_active();
 }
 
@@ -684,3 +698,91 @@ async fn should_timeout_fetch_from_peer() {
 
     NetworkController::<Message>::remove_active();
 }
+
+#[tokio::test]
+async fn should_race_fetch_against_fastest_known_peer() {
+    NetworkController::<Message>::create_active();
+    let mut network = TestingNetwork::<Reactor>::new();
+    let mut rng = TestRng::new();
+    let holding_nodes = network.add_nodes(&mut rng, 2).await;
+    let racing_config = FetcherTestConfig {
+        fetcher_config: Config::default().with_racing_fetcher("deploy"),
+        ..Default::default()
+    };
+    let (requesting_node, _) = network
+        .add_node_with_config(racing_config, &mut rng)
+        .await
+        .unwrap();
+
+    // Store the first deploy on one holding node, and the second deploy on both.
+    let first_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    store_deploy(&first_deploy, &holding_nodes[0], &mut network, &mut rng).await;
+    let second_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    for holding_node in &holding_nodes {
+        store_deploy(&second_deploy, holding_node, &mut network, &mut rng).await;
+    }
+
+    // Fetching the first deploy makes the first holding node known to the requesting node, but
+    // there's no other known peer to race against yet.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(
+                first_deploy.fetch_id(),
+                holding_nodes[0],
+                Arc::clone(&fetched),
+            ),
+        )
+        .await;
+    let expected_result = ExpectedFetchedDeployResult::FromPeer {
+        expected_deploy: Box::new(first_deploy.clone()),
+        expected_peer: holding_nodes[0],
+    };
+    assert_settled(
+        &requesting_node,
+        first_deploy.fetch_id(),
+        expected_result,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    // Fetching the second deploy from the second holding node should race the request against the
+    // first holding node, and either may win.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(
+                second_deploy.fetch_id(),
+                holding_nodes[1],
+                Arc::clone(&fetched),
+            ),
+        )
+        .await;
+    let has_responded = |_nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        fetched.lock().unwrap().0
+    };
+    network.settle_on(&mut rng, has_responded, TIMEOUT).await;
+
+    match fetched.lock().unwrap().1.clone() {
+        Some(Ok(FetchedData::FromPeer { item, peer })) => {
+            assert_eq!(*item, second_deploy);
+            assert!(holding_nodes.contains(&peer));
+        }
+        result => panic!("unexpected fetch result {:?}", result),
+    }
+    let deploy_fetcher = &network
+        .nodes()
+        .get(&requesting_node)
+        .unwrap()
+        .reactor()
+        .inner()
+        .deploy_fetcher;
+    assert_eq!(deploy_fetcher.metrics.races.get(), 1);
+
+    NetworkController::<Message>::remove_active();
+}
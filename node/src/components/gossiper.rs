@@ -2,6 +2,8 @@ mod config;
 #[cfg(test)]
 mod error;
 mod event;
+mod fanout;
+mod finished_entries_cache;
 mod gossip_item;
 mod gossip_table;
 mod item_provider;
@@ -10,13 +12,18 @@ mod metrics;
 mod provider_impls;
 mod tests;
 
+#[cfg(not(test))]
+use std::time::Instant;
 use std::{
     collections::HashSet,
     fmt::{self, Debug, Formatter},
+    path::Path,
     time::Duration,
 };
 
 use datasize::DataSize;
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
 use prometheus::Registry;
 use tracing::{debug, error, trace, warn};
 
@@ -34,6 +41,8 @@ use crate::{
 };
 pub(crate) use config::Config;
 pub(crate) use event::Event;
+use fanout::Fanout;
+use finished_entries_cache::FinishedEntriesCache;
 pub(crate) use gossip_item::{GossipItem, LargeGossipItem, SmallGossipItem};
 use gossip_table::{GossipAction, GossipTable};
 use item_provider::ItemProvider;
@@ -47,6 +56,8 @@ where
     T: GossipItem + 'static,
 {
     table: GossipTable<T::Id>,
+    fanout: Fanout,
+    finished_entries_cache: Option<FinishedEntriesCache>,
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
     validate_and_store_timeout: Duration,
@@ -59,13 +70,26 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
     ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
+    ///
+    /// If `storage_path` is provided, the IDs of items which have finished being gossiped are
+    /// written to a file there when the gossiper is dropped, and restored from it on construction,
+    /// so that they are not gossiped again after a restart.
     pub(crate) fn new(
         name: &'static str,
         config: Config,
+        storage_path: Option<&Path>,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
+        let mut table = GossipTable::new(config);
+        let finished_entries_cache =
+            storage_path.map(|storage_path| FinishedEntriesCache::new(storage_path, name));
+        if let Some(cache) = finished_entries_cache.as_ref() {
+            table.restore_finished_entries(cache.load());
+        }
         Ok(Gossiper {
-            table: GossipTable::new(config),
+            table,
+            fanout: Fanout::new(config),
+            finished_entries_cache,
             gossip_timeout: config.gossip_request_timeout().into(),
             get_from_peer_timeout: config.get_remainder_timeout().into(),
             validate_and_store_timeout: config.validate_and_store_timeout().into(),
@@ -74,6 +98,22 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         })
     }
 
+    /// Applies the settings of a reloaded config.
+    ///
+    /// Items already being gossiped are finished against the new infection target and saturation
+    /// limit, while requests already in flight keep their original timeouts.
+    pub(crate) fn update_config(&mut self, config: Config) {
+        let infection_target = self.fanout.reconfigure(config);
+        self.table.reconfigure(config, infection_target);
+        self.gossip_timeout = config.gossip_request_timeout().into();
+        self.get_from_peer_timeout = config.get_remainder_timeout().into();
+        self.validate_and_store_timeout = config.validate_and_store_timeout().into();
+        self.metrics
+            .infection_target
+            .set(self.fanout.infection_target() as i64);
+        debug!(name = self.name, "reloaded gossiper config");
+    }
+
     /// This could be the first time we've encountered this item in the gossiper (e.g. the
     /// `Network` component requesting that we gossip an address, or the `DeployAcceptor` having
     /// accepted a deploy which we received from a client), or it could be the result of this
@@ -149,6 +189,10 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         REv: From<GossiperAnnouncement<T>> + Send,
     {
         self.metrics.times_gossiped.inc_by(peers.len() as u64);
+        let now = Instant::now();
+        for peer in &peers {
+            self.fanout.register_peer(*peer, now);
+        }
         // We don't have any peers to gossip to, so pause the process, which will eventually result
         // in the entry being removed.
         if peers.is_empty() {
@@ -275,6 +319,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
     where
         REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
     {
+        self.fanout.register_peer(sender, Instant::now());
         let mut effects = match action {
             GossipAction::ShouldGossip(should_gossip) => {
                 debug!(item=%item_id, %sender, %should_gossip, "received gossip request");
@@ -374,6 +419,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             + Send,
         Self: ItemProvider<T>,
     {
+        self.fanout.register_peer(sender, Instant::now());
         let mut effects: Effects<_> = Effects::new();
         if !self.table.has_entry(&item_id) {
             debug!(
@@ -384,6 +430,11 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             return effects;
         }
 
+        self.fanout.register_response(is_already_held);
+        if is_already_held {
+            self.metrics.duplicate_gossip_responses.inc();
+        }
+
         let action = if is_already_held {
             self.table.already_infected(&item_id, sender)
         } else {
@@ -540,6 +591,30 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         Effects::new()
     }
 
+    /// Recalculates the infection target from the known peers and the duplication rate, applying
+    /// it to the gossip table if it changed.
+    fn update_fanout(&mut self) {
+        if let Some(infection_target) = self.fanout.update(Instant::now()) {
+            debug!(
+                name = self.name,
+                infection_target,
+                known_peers = self.fanout.known_peers(),
+                duplication_rate = self.fanout.duplication_rate(),
+                "updated gossip infection target"
+            );
+            self.table.set_infection_target(infection_target);
+        }
+        self.metrics
+            .infection_target
+            .set(self.fanout.infection_target() as i64);
+        self.metrics
+            .known_peers
+            .set(self.fanout.known_peers() as i64);
+        self.metrics
+            .duplication_rate_percent
+            .set((self.fanout.duplication_rate() * 100.0).round() as i64);
+    }
+
     /// Updates the gossiper metrics from the state of the gossip table.
     fn update_gossip_table_metrics(&self) {
         self.metrics
@@ -644,6 +719,7 @@ where
                 None => self.failed_to_get_from_storage(effect_builder, item_id),
             },
         };
+        self.update_fanout();
         self.update_gossip_table_metrics();
         effects
     }
@@ -740,6 +816,7 @@ where
                 Effects::new()
             }
         };
+        self.update_fanout();
         self.update_gossip_table_metrics();
         effects
     }
@@ -756,6 +833,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Debug
         formatter
             .debug_struct(self.name)
             .field("table", &self.table)
+            .field("fanout", &self.fanout)
             .field("gossip_timeout", &self.gossip_timeout)
             .field("get_from_peer_timeout", &self.get_from_peer_timeout)
             .field(
@@ -777,6 +855,8 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
     fn estimate_heap_size(&self) -> usize {
         let Gossiper {
             table,
+            fanout,
+            finished_entries_cache,
             gossip_timeout,
             get_from_peer_timeout,
             validate_and_store_timeout,
@@ -785,9 +865,21 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
         } = self;
 
         table.estimate_heap_size()
+            + fanout.estimate_heap_size()
+            + finished_entries_cache.estimate_heap_size()
             + gossip_timeout.estimate_heap_size()
             + get_from_peer_timeout.estimate_heap_size()
             + validate_and_store_timeout.estimate_heap_size()
             + name.estimate_heap_size()
     }
 }
+
+impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Drop
+    for Gossiper<ID_IS_COMPLETE_ITEM, T>
+{
+    fn drop(&mut self) {
+        if let Some(cache) = self.finished_entries_cache.as_ref() {
+            cache.store(self.table.finished_entries());
+        }
+    }
+}
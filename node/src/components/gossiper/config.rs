@@ -13,6 +13,8 @@ use casper_types::TimeDiff;
 use super::error::Error;
 
 const DEFAULT_INFECTION_TARGET: u8 = 3;
+const DEFAULT_ADAPTIVE_INFECTION_TARGET: bool = true;
+const DEFAULT_MAX_INFECTION_TARGET: u8 = 8;
 const DEFAULT_SATURATION_LIMIT_PERCENT: u8 = 80;
 pub(super) const MAX_SATURATION_LIMIT_PERCENT: u8 = 99;
 pub(super) const DEFAULT_FINISHED_ENTRY_DURATION: &str = "60sec";
@@ -32,7 +34,14 @@ const SMALL_TIMEOUTS_VALIDATE_AND_STORE_TIMEOUT: &str = "1sec";
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     /// Target number of peers to infect with a given piece of data.
+    ///
+    /// If `adaptive_infection_target` is enabled, this is the lower bound of the adaptive target.
     pub infection_target: u8,
+    /// Whether to scale the infection target with the number of peers known to the gossiper and
+    /// the observed rate of gossip sent to peers which already held the data.
+    pub adaptive_infection_target: bool,
+    /// The upper bound of the infection target when `adaptive_infection_target` is enabled.
+    pub max_infection_target: u8,
     /// The saturation limit as a percentage, with a maximum value of 99.  Used as a termination
     /// condition.
     ///
@@ -73,6 +82,8 @@ impl Config {
         }
         Ok(Config {
             infection_target,
+            adaptive_infection_target: DEFAULT_ADAPTIVE_INFECTION_TARGET,
+            max_infection_target: DEFAULT_MAX_INFECTION_TARGET,
             saturation_limit_percent,
             finished_entry_duration,
             gossip_request_timeout,
@@ -102,6 +113,14 @@ impl Config {
         self.infection_target
     }
 
+    pub(crate) fn adaptive_infection_target(&self) -> bool {
+        self.adaptive_infection_target
+    }
+
+    pub(crate) fn max_infection_target(&self) -> u8 {
+        self.max_infection_target
+    }
+
     pub(crate) fn saturation_limit_percent(&self) -> u8 {
         self.saturation_limit_percent
     }
@@ -127,6 +146,8 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             infection_target: DEFAULT_INFECTION_TARGET,
+            adaptive_infection_target: DEFAULT_ADAPTIVE_INFECTION_TARGET,
+            max_infection_target: DEFAULT_MAX_INFECTION_TARGET,
             saturation_limit_percent: DEFAULT_SATURATION_LIMIT_PERCENT,
             finished_entry_duration: TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION).unwrap(),
             gossip_request_timeout: TimeDiff::from_str(DEFAULT_GOSSIP_REQUEST_TIMEOUT).unwrap(),
@@ -166,6 +187,8 @@ mod tests {
         // saturation_limit_percent > MAX_SATURATION_LIMIT_PERCENT
         let invalid_config = Config {
             infection_target: 3,
+            adaptive_infection_target: DEFAULT_ADAPTIVE_INFECTION_TARGET,
+            max_infection_target: DEFAULT_MAX_INFECTION_TARGET,
             saturation_limit_percent: MAX_SATURATION_LIMIT_PERCENT + 1,
             finished_entry_duration: TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION).unwrap(),
             gossip_request_timeout: TimeDiff::from_str(DEFAULT_GOSSIP_REQUEST_TIMEOUT).unwrap(),
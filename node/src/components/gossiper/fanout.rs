@@ -0,0 +1,266 @@
+#[cfg(not(test))]
+use std::time::Instant;
+use std::{collections::HashMap, time::Duration};
+
+use datasize::DataSize;
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
+
+use super::Config;
+use crate::types::NodeId;
+
+/// Peers not seen sending or receiving gossip for this long are no longer counted as known.
+const KNOWN_PEER_EXPIRY: Duration = Duration::from_secs(300);
+/// The minimum interval between recalculations of the infection target.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+/// The weight given to the duplication rate observed in the latest interval when updating the
+/// smoothed duplication rate.
+const DUPLICATION_RATE_WEIGHT: f64 = 0.25;
+
+/// Derives the number of peers to infect with each item from the number of peers known to the
+/// gossiper and the proportion of gossip requests sent to peers which already held the item.
+///
+/// Infecting roughly `ln(n)` peers suffices for gossip to reach all `n` nodes with high
+/// probability, so the target grows logarithmically with the known-peer count.  A high duplication
+/// rate means the network is already saturated with gossip, so the target then shrinks back towards
+/// the configured minimum.
+#[derive(DataSize, Debug)]
+pub(super) struct Fanout {
+    /// See `Config::adaptive_infection_target`.
+    is_adaptive: bool,
+    /// See `Config::infection_target`.
+    min_infection_target: usize,
+    /// See `Config::max_infection_target`.
+    max_infection_target: usize,
+    /// Peers seen sending or receiving gossip, with the time they were last seen.
+    known_peers: HashMap<NodeId, Instant>,
+    /// Number of gossip responses received since the last update.
+    responses: u64,
+    /// Number of gossip responses received since the last update from peers already holding the
+    /// item.
+    duplicate_responses: u64,
+    /// Smoothed proportion of gossip responses from peers already holding the item, in `[0, 1]`.
+    duplication_rate: f64,
+    /// The current infection target.
+    infection_target: usize,
+    /// The time of the last recalculation of the infection target.
+    last_update: Instant,
+}
+
+impl Fanout {
+    pub(super) fn new(config: Config) -> Self {
+        let min_infection_target = usize::from(config.infection_target());
+        let max_infection_target =
+            usize::from(config.max_infection_target()).max(min_infection_target);
+        Fanout {
+            is_adaptive: config.adaptive_infection_target(),
+            min_infection_target,
+            max_infection_target,
+            known_peers: HashMap::new(),
+            responses: 0,
+            duplicate_responses: 0,
+            duplication_rate: 0.0,
+            infection_target: min_infection_target,
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Applies the infection target settings of a reloaded config, returning the new infection
+    /// target.
+    ///
+    /// The known peers and the duplication rate are kept.
+    pub(super) fn reconfigure(&mut self, config: Config) -> usize {
+        self.is_adaptive = config.adaptive_infection_target();
+        self.min_infection_target = usize::from(config.infection_target());
+        self.max_infection_target =
+            usize::from(config.max_infection_target()).max(self.min_infection_target);
+        self.infection_target = self.calculate_infection_target();
+        self.infection_target
+    }
+
+    /// The current infection target.
+    pub(super) fn infection_target(&self) -> usize {
+        self.infection_target
+    }
+
+    /// Number of peers seen sending or receiving gossip recently.
+    pub(super) fn known_peers(&self) -> usize {
+        self.known_peers.len()
+    }
+
+    /// Smoothed proportion of gossip responses from peers already holding the item, in `[0, 1]`.
+    pub(super) fn duplication_rate(&self) -> f64 {
+        self.duplication_rate
+    }
+
+    /// Records that the given peer was seen sending or receiving gossip.
+    pub(super) fn register_peer(&mut self, peer: NodeId, now: Instant) {
+        let _ = self.known_peers.insert(peer, now);
+    }
+
+    /// Records a gossip response, and whether the responding peer already held the item.
+    pub(super) fn register_response(&mut self, is_already_held: bool) {
+        self.responses += 1;
+        if is_already_held {
+            self.duplicate_responses += 1;
+        }
+    }
+
+    /// Recalculates the infection target if `UPDATE_INTERVAL` has elapsed since the last update.
+    ///
+    /// Returns the new infection target if it changed.
+    pub(super) fn update(&mut self, now: Instant) -> Option<usize> {
+        if now < self.last_update + UPDATE_INTERVAL {
+            return None;
+        }
+        self.last_update = now;
+
+        self.known_peers
+            .retain(|_, last_seen| *last_seen + KNOWN_PEER_EXPIRY > now);
+
+        if self.responses > 0 {
+            let latest_rate = self.duplicate_responses as f64 / self.responses as f64;
+            self.duplication_rate = DUPLICATION_RATE_WEIGHT * latest_rate
+                + (1.0 - DUPLICATION_RATE_WEIGHT) * self.duplication_rate;
+            self.responses = 0;
+            self.duplicate_responses = 0;
+        }
+
+        let infection_target = self.calculate_infection_target();
+        if infection_target == self.infection_target {
+            return None;
+        }
+        self.infection_target = infection_target;
+        Some(infection_target)
+    }
+
+    fn calculate_infection_target(&self) -> usize {
+        if !self.is_adaptive {
+            return self.min_infection_target;
+        }
+        let scaled = (self.known_peers.len().max(1) as f64).ln().ceil() as usize;
+        let scaled = scaled.clamp(self.min_infection_target, self.max_infection_target);
+        let extra = (scaled - self.min_infection_target) as f64 * (1.0 - self.duplication_rate);
+        self.min_infection_target + extra.round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    fn register_peers(fanout: &mut Fanout, count: usize, rng: &mut TestRng, now: Instant) {
+        for peer in iter::repeat_with(|| NodeId::random(rng)).take(count) {
+            fanout.register_peer(peer, now);
+        }
+    }
+
+    #[test]
+    fn should_scale_infection_target_with_known_peers() {
+        let mut rng = crate::new_rng();
+        let mut fanout = Fanout::new(Config::default());
+        assert_eq!(fanout.infection_target(), 3);
+
+        // ln(20) rounds up to 3, so the minimum still applies.
+        let mut now = Instant::now() + UPDATE_INTERVAL;
+        register_peers(&mut fanout, 20, &mut rng, now);
+        assert_eq!(fanout.update(now), None);
+        assert_eq!(fanout.infection_target(), 3);
+
+        // An update before the interval elapses should be a no-op.
+        register_peers(&mut fanout, 180, &mut rng, now);
+        assert_eq!(fanout.update(now), None);
+
+        // ln(200) rounds up to 6.
+        now = now + UPDATE_INTERVAL;
+        assert_eq!(fanout.update(now), Some(6));
+        assert_eq!(fanout.known_peers(), 200);
+
+        // ln(10,200) rounds up to 10, which is capped to the maximum of 8.
+        register_peers(&mut fanout, 10_000, &mut rng, now);
+        now = now + UPDATE_INTERVAL;
+        assert_eq!(fanout.update(now), Some(8));
+
+        // Once the peers expire, the target should fall back to the minimum.
+        now = now + KNOWN_PEER_EXPIRY;
+        assert_eq!(fanout.update(now), Some(3));
+        assert_eq!(fanout.known_peers(), 0);
+    }
+
+    #[test]
+    fn should_reduce_infection_target_when_duplication_rate_is_high() {
+        let mut rng = crate::new_rng();
+        let mut fanout = Fanout::new(Config::default());
+
+        let mut now = Instant::now() + UPDATE_INTERVAL;
+        register_peers(&mut fanout, 200, &mut rng, now);
+        assert_eq!(fanout.update(now), Some(6));
+
+        // Repeated intervals where every response is a duplicate should drive the target back down
+        // to the minimum.
+        while fanout.infection_target() > 3 {
+            for _ in 0..10 {
+                fanout.register_response(true);
+            }
+            now = now + UPDATE_INTERVAL;
+            let _ = fanout.update(now);
+            assert!(fanout.duplication_rate() <= 1.0);
+        }
+        assert!(fanout.duplication_rate() > 0.8);
+
+        // Responses from peers not yet holding the item should let the target recover.
+        while fanout.infection_target() < 6 {
+            for _ in 0..10 {
+                fanout.register_response(false);
+            }
+            now = now + UPDATE_INTERVAL;
+            let _ = fanout.update(now);
+        }
+        assert!(fanout.duplication_rate() < 0.2);
+    }
+
+    #[test]
+    fn should_use_fixed_infection_target_if_not_adaptive() {
+        let mut rng = crate::new_rng();
+        let config = Config {
+            adaptive_infection_target: false,
+            ..Default::default()
+        };
+        let mut fanout = Fanout::new(config);
+
+        let now = Instant::now() + UPDATE_INTERVAL;
+        register_peers(&mut fanout, 10_000, &mut rng, now);
+        assert_eq!(fanout.update(now), None);
+        assert_eq!(fanout.infection_target(), 3);
+    }
+
+    #[test]
+    fn should_keep_known_peers_when_reconfigured() {
+        let mut rng = crate::new_rng();
+        let mut fanout = Fanout::new(Config::default());
+
+        let now = Instant::now() + UPDATE_INTERVAL;
+        register_peers(&mut fanout, 200, &mut rng, now);
+        assert_eq!(fanout.update(now), Some(6));
+
+        // Lowering the maximum should cap the target straight away.
+        let config = Config {
+            max_infection_target: 4,
+            ..Default::default()
+        };
+        assert_eq!(fanout.reconfigure(config), 4);
+        assert_eq!(fanout.known_peers(), 200);
+
+        // Disabling adaptation should fall back to the fixed target.
+        let config = Config {
+            infection_target: 5,
+            adaptive_infection_target: false,
+            ..Default::default()
+        };
+        assert_eq!(fanout.reconfigure(config), 5);
+    }
+}
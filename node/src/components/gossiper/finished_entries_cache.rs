@@ -0,0 +1,168 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use casper_types::{TimeDiff, Timestamp};
+
+/// A finished gossip table entry as persisted to disk.
+#[derive(Serialize, Deserialize)]
+struct CachedEntry<T> {
+    item_id: T,
+    /// The time at which the entry should be purged from the finished entries.
+    expiry: Timestamp,
+}
+
+/// A file in which a gossiper keeps the IDs of items it has finished gossiping across restarts.
+///
+/// Without it, a restarting node would gossip again every recently-finished item it receives,
+/// causing a burst of redundant traffic which peers may throttle.
+#[derive(Debug, DataSize)]
+pub(super) struct FinishedEntriesCache {
+    path: PathBuf,
+}
+
+impl FinishedEntriesCache {
+    /// Returns a cache for the gossiper with the given name, stored under `storage_path`.
+    pub(super) fn new(storage_path: &Path, name: &str) -> Self {
+        FinishedEntriesCache {
+            path: storage_path.join(format!("{}_finished_entries", name)),
+        }
+    }
+
+    /// Reads the cached entries, returning the ID of each along with the remaining duration for
+    /// which it should be retained, in order of expiry.
+    ///
+    /// Entries which expired while the node was down are skipped.  The cache file is removed once
+    /// read so that stale entries are never restored twice.
+    pub(super) fn load<T: DeserializeOwned>(&self) -> Vec<(T, Duration)> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                if self.path.exists() {
+                    warn!(
+                        file = %self.path.display(),
+                        %error,
+                        "failed to read gossiper finished entries cache file"
+                    );
+                }
+                return vec![];
+            }
+        };
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!(
+                file = %self.path.display(),
+                %error,
+                "failed to remove gossiper finished entries cache file"
+            );
+        }
+
+        let cached_entries: Vec<CachedEntry<T>> = match bincode::deserialize(&bytes) {
+            Ok(cached_entries) => cached_entries,
+            Err(error) => {
+                warn!(
+                    file = %self.path.display(),
+                    %error,
+                    "failed to parse gossiper finished entries cache file"
+                );
+                return vec![];
+            }
+        };
+
+        let now = Timestamp::now();
+        let entries: Vec<_> = cached_entries
+            .into_iter()
+            .filter_map(|CachedEntry { item_id, expiry }| {
+                let remaining = expiry.saturating_diff(now);
+                (remaining > TimeDiff::default()).then(|| (item_id, remaining.into()))
+            })
+            .collect();
+        debug!(
+            file = %self.path.display(),
+            count = entries.len(),
+            "restored gossiper finished entries from cache file"
+        );
+        entries
+    }
+
+    /// Writes the given entries, each with the remaining duration for which it should be retained,
+    /// to the cache file.
+    pub(super) fn store<T: Serialize>(&self, entries: Vec<(T, Duration)>) {
+        let now = Timestamp::now();
+        let count = entries.len();
+        let cached_entries: Vec<_> = entries
+            .into_iter()
+            .map(|(item_id, remaining)| CachedEntry {
+                item_id,
+                expiry: now + TimeDiff::from(remaining),
+            })
+            .collect();
+        let bytes = match bincode::serialize(&cached_entries) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                warn!(%error, "failed to serialize gossiper finished entries");
+                return;
+            }
+        };
+        match fs::write(&self.path, bytes) {
+            Err(error) => warn!(
+                file = %self.path.display(),
+                %error,
+                "failed to write gossiper finished entries cache file"
+            ),
+            Ok(_) => debug!(
+                file = %self.path.display(),
+                %count,
+                "cached gossiper finished entries to file"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging;
+
+    #[test]
+    fn should_persist_unexpired_entries() {
+        let _ = logging::init();
+        let tempdir = tempfile::tempdir().unwrap();
+        let cache = FinishedEntriesCache::new(tempdir.path(), "test_gossiper");
+
+        // Should be empty when no cache file exists.
+        assert!(cache.load::<u64>().is_empty());
+
+        let entries = vec![
+            (1_u64, Duration::from_millis(0)),
+            (2, Duration::from_secs(60)),
+            (3, Duration::from_secs(120)),
+        ];
+        cache.store(entries);
+
+        // The already-expired entry should be skipped.
+        let restored = cache.load::<u64>();
+        let restored_ids: Vec<_> = restored.iter().map(|(item_id, _)| *item_id).collect();
+        assert_eq!(restored_ids, vec![2, 3]);
+        assert!(restored[0].1 <= Duration::from_secs(60));
+        assert!(restored[1].1 > restored[0].1);
+
+        // The cache file should have been consumed.
+        assert!(cache.load::<u64>().is_empty());
+    }
+
+    #[test]
+    fn should_ignore_corrupt_cache_file() {
+        let _ = logging::init();
+        let tempdir = tempfile::tempdir().unwrap();
+        let cache = FinishedEntriesCache::new(tempdir.path(), "test_gossiper");
+
+        fs::write(&cache.path, [1, 2, 3]).unwrap();
+        assert!(cache.load::<u64>().is_empty());
+    }
+}
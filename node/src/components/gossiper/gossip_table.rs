@@ -191,8 +191,10 @@ pub(super) struct GossipTable<T> {
     finished: HashSet<T>,
     /// Timeouts for removal of items from the `finished` cache.
     timeouts: Timeouts<T>,
-    /// See `Config::infection_target`.
+    /// See `Config::infection_target`.  May be adjusted via `set_infection_target`.
     infection_target: usize,
+    /// See `Config::saturation_limit_percent`.
+    saturation_limit_percent: usize,
     /// Derived from `Config::saturation_limit_percent` - we gossip data while the number of
     /// attempts to infect doesn't exceed `attempted_to_infect_limit`.
     attempted_to_infect_limit: usize,
@@ -215,18 +217,49 @@ impl<T> GossipTable<T> {
 impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     /// Returns a new `GossipTable` using the provided configuration.
     pub(super) fn new(config: Config) -> Self {
-        let attempted_to_infect_limit = (100 * usize::from(config.infection_target()))
-            / (100 - usize::from(config.saturation_limit_percent()));
+        let infection_target = usize::from(config.infection_target());
+        let saturation_limit_percent = usize::from(config.saturation_limit_percent());
         GossipTable {
             current: HashMap::new(),
             finished: HashSet::new(),
             timeouts: Timeouts::new(),
-            infection_target: usize::from(config.infection_target()),
-            attempted_to_infect_limit,
+            infection_target,
+            saturation_limit_percent,
+            attempted_to_infect_limit: Self::attempted_to_infect_limit(
+                infection_target,
+                saturation_limit_percent,
+            ),
             finished_entry_duration: config.finished_entry_duration().into(),
         }
     }
 
+    /// Sets the number of peers to infect with data, adjusting the saturation limit accordingly.
+    ///
+    /// Entries already being gossiped are finished against the new target the next time they are
+    /// updated.
+    pub(super) fn set_infection_target(&mut self, infection_target: usize) {
+        self.infection_target = infection_target;
+        self.attempted_to_infect_limit =
+            Self::attempted_to_infect_limit(infection_target, self.saturation_limit_percent);
+    }
+
+    /// Applies the saturation limit and finished entry duration of a reloaded config, along with
+    /// the given infection target.
+    ///
+    /// Entries already in the `finished` cache keep their original expiry.
+    pub(super) fn reconfigure(&mut self, config: Config, infection_target: usize) {
+        self.saturation_limit_percent = usize::from(config.saturation_limit_percent());
+        self.finished_entry_duration = config.finished_entry_duration().into();
+        self.set_infection_target(infection_target);
+    }
+
+    fn attempted_to_infect_limit(
+        infection_target: usize,
+        saturation_limit_percent: usize,
+    ) -> usize {
+        (100 * infection_target) / (100 - saturation_limit_percent)
+    }
+
     /// We received knowledge about potentially new data with given ID from the given peer.  This
     /// should only be called where we don't already hold everything locally we need to be able to
     /// gossip it onwards.  If we are able to gossip the data already, call `new_complete_data`
@@ -515,6 +548,39 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         Some(action)
     }
 
+    /// Returns the IDs of finished entries along with the remaining duration for which each will
+    /// be retained, in order of expiry.
+    pub(super) fn finished_entries(&mut self) -> Vec<(T, Duration)> {
+        self.purge_finished();
+        let now = Instant::now();
+        // An entry is purged on its earliest timeout, so ignore any later duplicates.
+        let mut seen = HashSet::new();
+        self.timeouts
+            .values
+            .iter()
+            .filter(|(timeout, data_id)| {
+                *timeout > now && self.finished.contains(data_id) && seen.insert(data_id)
+            })
+            .map(|(timeout, data_id)| (data_id.clone(), timeout.duration_since(now)))
+            .collect()
+    }
+
+    /// Marks the given data IDs as finished, each to be retained for the given duration.
+    ///
+    /// Intended to restore the finished entries returned by `finished_entries` in a previous
+    /// session, so must be called before any other entries are added.
+    pub(super) fn restore_finished_entries<I: IntoIterator<Item = (T, Duration)>>(
+        &mut self,
+        entries: I,
+    ) {
+        let now = Instant::now();
+        for (data_id, remaining) in entries {
+            if self.finished.insert(data_id.clone()) {
+                self.timeouts.push(now + remaining, data_id);
+            }
+        }
+    }
+
     fn insert_to_finished(&mut self, data_id: &T) {
         let timeout = Instant::now() + self.finished_entry_duration;
         let _ = self.finished.insert(data_id.clone());
@@ -1112,6 +1178,47 @@ mod tests {
         assert_eq!(purged, vec![0]);
     }
 
+    #[test]
+    fn should_restore_finished_entries() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_ids: Vec<u64> = iter::repeat_with(|| rng.gen()).take(3).collect();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        for data_id in &data_ids[..2] {
+            let _ = gossip_table.new_complete_data(data_id, None, GossipTarget::All);
+            assert!(gossip_table.force_finish(data_id));
+        }
+        // An entry still being gossiped should not be included.
+        let _ = gossip_table.new_data_id(&data_ids[2], node_ids[0]);
+
+        let finished_entries = gossip_table.finished_entries();
+        let finished_ids: Vec<_> = finished_entries
+            .iter()
+            .map(|(data_id, _)| *data_id)
+            .collect();
+        assert_eq!(finished_ids, data_ids[..2]);
+
+        // Restored entries should be treated as finished, and be purged after the remaining
+        // duration.
+        let mut restored_table = GossipTable::new(Config::default());
+        restored_table.restore_finished_entries(finished_entries);
+        for data_id in &data_ids[..2] {
+            assert_eq!(
+                GossipAction::Noop,
+                restored_table.new_complete_data(data_id, None, GossipTarget::All)
+            );
+        }
+
+        let millis = TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION)
+            .unwrap()
+            .millis();
+        Instant::advance_time(millis + 1);
+        assert!(restored_table.finished_entries().is_empty());
+        assert!(restored_table.is_empty());
+    }
+
     #[test]
     fn timeouts_depends_on_binary_search_by_implementation() {
         // This test is meant to document the dependency of
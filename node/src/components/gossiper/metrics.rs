@@ -15,6 +15,14 @@ pub(super) struct Metrics {
     pub(super) table_items_current: IntGauge,
     /// Number of items in the gossip table that are finished.
     pub(super) table_items_finished: IntGauge,
+    /// Total number of gossip responses from peers which already held the item.
+    pub(super) duplicate_gossip_responses: IntCounter,
+    /// Current number of peers to infect with each item.
+    pub(super) infection_target: IntGauge,
+    /// Number of peers recently seen sending or receiving gossip.
+    pub(super) known_peers: IntGauge,
+    /// Smoothed percentage of gossip responses from peers which already held the item.
+    pub(super) duplication_rate_percent: IntGauge,
     /// Reference to the registry for unregistering.
     registry: Registry,
 }
@@ -51,12 +59,46 @@ impl Metrics {
                 name
             ),
         )?;
+        let duplicate_gossip_responses = IntCounter::new(
+            format!("{}_duplicate_gossip_responses", name),
+            format!(
+                "number of gossip responses received by the {} from peers which already held the \
+                item",
+                name
+            ),
+        )?;
+        let infection_target = IntGauge::new(
+            format!("{}_infection_target", name),
+            format!(
+                "current number of peers the {} infects with each item",
+                name
+            ),
+        )?;
+        let known_peers = IntGauge::new(
+            format!("{}_known_peers", name),
+            format!(
+                "number of peers recently seen sending or receiving gossip by the {}",
+                name
+            ),
+        )?;
+        let duplication_rate_percent = IntGauge::new(
+            format!("{}_duplication_rate_percent", name),
+            format!(
+                "smoothed percentage of gossip responses received by the {} from peers which \
+                already held the item",
+                name
+            ),
+        )?;
 
         registry.register(Box::new(items_received.clone()))?;
         registry.register(Box::new(times_gossiped.clone()))?;
         registry.register(Box::new(times_ran_out_of_peers.clone()))?;
         registry.register(Box::new(table_items_current.clone()))?;
         registry.register(Box::new(table_items_finished.clone()))?;
+        registry.register(Box::new(duplicate_gossip_responses.clone()))?;
+        registry.register(Box::new(infection_target.clone()))?;
+        registry.register(Box::new(known_peers.clone()))?;
+        registry.register(Box::new(duplication_rate_percent.clone()))?;
 
         Ok(Metrics {
             items_received,
@@ -64,6 +106,10 @@ impl Metrics {
             times_ran_out_of_peers,
             table_items_current,
             table_items_finished,
+            duplicate_gossip_responses,
+            infection_target,
+            known_peers,
+            duplication_rate_percent,
             registry: registry.clone(),
         })
     }
@@ -76,5 +122,9 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.times_ran_out_of_peers);
         unregister_metric!(self.registry, self.table_items_current);
         unregister_metric!(self.registry, self.table_items_finished);
+        unregister_metric!(self.registry, self.duplicate_gossip_responses);
+        unregister_metric!(self.registry, self.infection_target);
+        unregister_metric!(self.registry, self.known_peers);
+        unregister_metric!(self.registry, self.duplication_rate_percent);
     }
 }
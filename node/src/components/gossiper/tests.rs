@@ -175,6 +175,7 @@ impl reactor::Reactor for Reactor {
         let deploy_gossiper = Gossiper::<{ Deploy::ID_IS_COMPLETE_ITEM }, _>::new(
             "deploy_gossiper",
             config,
+            None,
             registry,
         )?;
 
@@ -25,7 +25,9 @@
 
 mod bincode_format;
 pub(crate) mod blocklist;
+mod capabilities;
 mod chain_info;
+mod compression_format;
 mod config;
 mod counting_format;
 mod error;
@@ -39,8 +41,12 @@ mod message;
 mod message_pack_format;
 mod metrics;
 mod outgoing;
+mod port_mapping;
+mod quic;
+mod reputation;
 mod symmetry;
 pub(crate) mod tasks;
+mod traffic;
 #[cfg(test)]
 mod tests;
 
@@ -49,13 +55,17 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     io,
     net::{SocketAddr, TcpListener},
+    path::Path,
+    pin::Pin,
     sync::{Arc, Weak},
+    task::{Context, Poll},
     time::{Duration, Instant},
 };
 
 use datasize::DataSize;
 use futures::{future::BoxFuture, FutureExt};
 use itertools::Itertools;
+use pin_project::pin_project;
 use prometheus::Registry;
 use rand::{
     seq::{IteratorRandom, SliceRandom},
@@ -63,6 +73,7 @@ use rand::{
 };
 use serde::{Deserialize, Serialize};
 use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
     net::TcpStream,
     sync::{
         mpsc::{self, UnboundedSender},
@@ -74,7 +85,7 @@ use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
-use casper_types::{EraId, PublicKey, SecretKey};
+use casper_types::{EraId, PublicKey, SecretKey, Timestamp};
 
 pub(crate) use self::{
     bincode_format::BincodeFormat,
@@ -85,13 +96,17 @@ pub(crate) use self::{
     identity::Identity,
     insights::NetworkInsights,
     message::{
-        generate_largest_serialized_message, EstimatorWeights, FromIncoming, Message, MessageKind,
-        Payload,
+        generate_largest_serialized_message, Channel, EstimatorWeights, FromIncoming, Message,
+        MessageKind, Payload,
     },
+    reputation::PeerReputation,
+    traffic::PeerTraffic,
 };
 use self::{
     blocklist::BlocklistJustification,
+    capabilities::Capabilities,
     chain_info::ChainInfo,
+    compression_format::CompressionFormat,
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
@@ -100,6 +115,9 @@ use self::{
     message::NodeKeyPair,
     metrics::Metrics,
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    port_mapping::{PortMapping, PortMappingError},
+    quic::QuicStream,
+    reputation::ReputationStore,
     symmetry::ConnectionSymmetry,
     tasks::{MessageQueueItem, NetworkContext},
 };
@@ -150,9 +168,21 @@ const PING_RETRIES: u16 = 5;
 
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
+    /// The sender for consensus-critical messages to a trusted validator of the private overlay,
+    /// which are sent before any other messages and bypass the bandwidth limiter.
+    ///
+    /// Only present if the peer proved to be a trusted validator during the handshake.
+    #[data_size(skip)]
+    overlay_sender: Option<UnboundedSender<MessageQueueItem<P>>>,
+    /// The sender for consensus-critical messages, which are sent before any bulk messages.
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
-    sender: UnboundedSender<MessageQueueItem<P>>,
+    priority_sender: UnboundedSender<MessageQueueItem<P>>,
+    /// The sender for all other messages.
+    #[data_size(skip)]
+    bulk_sender: UnboundedSender<MessageQueueItem<P>>,
     peer_addr: SocketAddr,
+    /// Capabilities supported by both us and the peer.
+    capabilities: Capabilities,
 }
 
 impl<P> Display for OutgoingHandle<P> {
@@ -180,6 +210,15 @@ where
     /// Tracks nodes that have announced themselves as nodes that are syncing.
     syncing_nodes: HashSet<NodeId>,
 
+    /// Reputations of peers, including their bans by node ID.
+    reputation: ReputationStore,
+
+    /// Consensus keys of the trusted validators of the private overlay, by their node's address.
+    trusted_validators: HashMap<SocketAddr, PublicKey>,
+
+    /// Our public port to map on the local router, if port mapping is enabled.
+    port_mapping: Option<PortMapping>,
+
     channel_management: Option<ChannelManagement>,
 
     /// Networking metrics.
@@ -213,6 +252,9 @@ struct ChannelManagement {
     /// Join handle for the server thread.
     #[data_size(skip)]
     server_join_handle: Option<JoinHandle<()>>,
+    /// Join handle for the QUIC server thread, if QUIC is enabled.
+    #[data_size(skip)]
+    quic_server_join_handle: Option<JoinHandle<()>>,
 
     /// Channel signaling a shutdown of the incoming connections.
     // Note: This channel is closed when we finished syncing, so the `Network` can close all
@@ -246,6 +288,7 @@ where
         registry: &Registry,
         chain_info_source: C,
         validator_matrix: ValidatorMatrix,
+        storage_path: Option<&Path>,
     ) -> Result<Network<REv, P>> {
         let net_metrics = Arc::new(Metrics::new(registry)?);
 
@@ -273,6 +316,9 @@ where
                     ping_retries: PING_RETRIES,
                     pong_limit: (1 + PING_RETRIES as u32) * 2,
                 },
+                max_backoff: cfg.max_dial_backoff.into(),
+                backoff_jitter: cfg.dial_backoff_jitter,
+                max_dial_rate: cfg.max_dials_per_second,
             },
             net_metrics.create_outgoing_metrics(),
         );
@@ -291,6 +337,9 @@ where
             outgoing_manager,
             connection_symmetries: HashMap::new(),
             syncing_nodes: HashSet::new(),
+            reputation: ReputationStore::new(storage_path),
+            trusted_validators: HashMap::new(),
+            port_mapping: None,
             channel_management: None,
             net_metrics,
             outgoing_limiter,
@@ -318,6 +367,21 @@ where
             }
         }
 
+        // Connections to trusted validators are maintained just like those to known addresses.
+        for trusted in &self.cfg.trusted_validators {
+            match utils::resolve_address(&trusted.address) {
+                Ok(addr) => {
+                    let _ = known_addresses.insert(addr);
+                    let _ = self
+                        .trusted_validators
+                        .insert(addr, trusted.public_key.clone());
+                }
+                Err(ref err) => {
+                    warn!(address=%trusted.address, err=display_error(err), "failed to resolve trusted validator address");
+                }
+            }
+        }
+
         // Assert we have at least one known address in the config.
         if known_addresses.is_empty() {
             warn!("no known addresses provided via config or all failed DNS resolution");
@@ -344,9 +408,27 @@ where
             public_addr.set_port(local_addr.port());
         }
 
+        if self.cfg.port_mapping {
+            self.port_mapping = Some(PortMapping::new(local_addr.port(), public_addr.port()));
+        }
+
+        // QUIC connections are accepted on the UDP port matching the TCP port, so peers can reach
+        // us via either at our public address.
+        let quic_endpoint = if self.cfg.quic_transport {
+            let endpoint = quic::create_endpoint(local_addr)
+                .map_err(|error| Error::QuicEndpointCreation(error, local_addr))?;
+            Some(endpoint)
+        } else {
+            None
+        };
+
         Arc::get_mut(&mut self.context)
             .expect("should be no other pointers")
-            .initialize(public_addr, effect_builder.into_inner());
+            .initialize(
+                public_addr,
+                quic_endpoint.clone(),
+                effect_builder.into_inner(),
+            );
 
         let protocol_version = self.context.chain_info().protocol_version;
         // Run the server task.
@@ -362,14 +444,23 @@ where
             tasks::server(
                 context,
                 tokio::net::TcpListener::from_std(listener).map_err(Error::ListenerConversion)?,
-                server_shutdown_receiver,
+                server_shutdown_receiver.clone(),
             )
             .in_current_span(),
         );
 
+        let quic_server_join_handle = quic_endpoint.map(|endpoint| {
+            info!(%local_addr, "starting QUIC server background task");
+            tokio::spawn(
+                tasks::quic_server(self.context.clone(), endpoint, server_shutdown_receiver)
+                    .in_current_span(),
+            )
+        });
+
         let channel_management = ChannelManagement {
             shutdown_sender: Some(server_shutdown_sender),
             server_join_handle: Some(server_join_handle),
+            quic_server_join_handle,
             close_incoming_sender: Some(close_incoming_sender),
             close_incoming_receiver,
         };
@@ -399,6 +490,8 @@ where
                 .event(|_| Event::SweepOutgoing),
         );
 
+        effects.extend(self.map_port());
+
         <Self as InitializedComponent<REv>>::set_state(self, ComponentState::Initialized);
         Ok(effects)
     }
@@ -419,7 +512,9 @@ where
 
         for peer_id in self.outgoing_manager.connected_peers() {
             total_outgoing_manager_connected_peers += 1;
-            if self.outgoing_limiter.is_validator_in_era(era_id, &peer_id) {
+            if self.outgoing_limiter.is_validator_in_era(era_id, &peer_id)
+                || self.is_overlay_peer(peer_id)
+            {
                 total_connected_validators_in_era += 1;
                 self.send_message(peer_id, msg.clone(), None)
             }
@@ -499,11 +594,25 @@ where
                 error!(kind=%msg.classify(), node_id=%dest, "sending unsafe message to syncing node");
             }
 
-            if let Err(msg) = connection.sender.send((msg, opt_responder)) {
+            let channel = msg.channel();
+            let (sender, queued) = match connection.overlay_sender {
+                Some(ref overlay_sender) if channel == Channel::Priority => {
+                    (overlay_sender, &self.net_metrics.queued_overlay_messages)
+                }
+                _ => {
+                    let sender = match channel {
+                        Channel::Priority => &connection.priority_sender,
+                        Channel::Bulk => &connection.bulk_sender,
+                    };
+                    (sender, self.net_metrics.queued_messages_on(channel))
+                }
+            };
+            if let Err(msg) = sender.send((msg, opt_responder)) {
                 // We lost the connection, but that fact has not reached us yet.
                 warn!(our_id=%self.context.our_id(), %dest, ?msg, "dropped outgoing message, lost connection");
             } else {
                 self.net_metrics.queued_messages.inc();
+                queued.inc();
             }
         } else {
             // We are not connected, so the reconnection is likely already in progress.
@@ -552,7 +661,15 @@ where
                 peer_consensus_public_key,
                 stream,
             } => {
-                if self.cfg.max_incoming_peer_connections != 0 {
+                if self.reputation.is_banned(peer_id, Timestamp::now()) {
+                    info!(%public_addr, "rejecting new incoming connection from banned peer");
+                    return Effects::new();
+                }
+
+                // Trusted validators are exempt from the limit, so they can always reconnect.
+                if self.cfg.max_incoming_peer_connections != 0
+                    && !self.is_trusted_validator(peer_consensus_public_key.as_ref())
+                {
                     if let Some(symmetries) = self.connection_symmetries.get(&peer_id) {
                         let incoming_count = symmetries
                             .incoming_addrs()
@@ -667,6 +784,7 @@ where
             | ConnectionError::TcpConnection(_)
             | ConnectionError::TcpNoDelay(_)
             | ConnectionError::TlsHandshake(_)
+            | ConnectionError::QuicSetup(_)
             | ConnectionError::HandshakeSend(_)
             | ConnectionError::HandshakeRecv(_)
             | ConnectionError::IncompatibleVersion(_) => None,
@@ -756,11 +874,41 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                capabilities,
             } => {
-                info!("new outgoing connection established");
+                if self.reputation.is_banned(peer_id, Timestamp::now()) {
+                    // Dropping the sink closes the connection, and the address stays blocked.
+                    info!("outgoing connection to banned peer established, blocking address");
+                    self.reputation.set_banned_addr(peer_id, peer_addr);
+                    let request = self.outgoing_manager.block_addr(
+                        peer_addr,
+                        now,
+                        BlocklistJustification::Banned,
+                    );
+                    return self.process_dial_requests(request);
+                }
 
-                let (sender, receiver) = mpsc::unbounded_channel();
-                let handle = OutgoingHandle { sender, peer_addr };
+                if let Some(expected) = self.trusted_validators.get(&peer_addr) {
+                    if peer_consensus_public_key.as_ref() != Some(expected) {
+                        warn!(%expected, "peer at trusted validator address did not prove the expected consensus key");
+                    }
+                }
+                let is_trusted = self.is_trusted_validator(peer_consensus_public_key.as_ref());
+
+                info!(is_trusted, "new outgoing connection established");
+
+                // The overlay sender is dropped right away for untrusted peers, which leaves the
+                // overlay queue closed and empty.
+                let (overlay_sender, overlay_receiver) = mpsc::unbounded_channel();
+                let (priority_sender, priority_receiver) = mpsc::unbounded_channel();
+                let (bulk_sender, bulk_receiver) = mpsc::unbounded_channel();
+                let handle = OutgoingHandle {
+                    overlay_sender: is_trusted.then_some(overlay_sender),
+                    priority_sender,
+                    bulk_sender,
+                    peer_addr,
+                    capabilities,
+                };
 
                 let request = self
                     .outgoing_manager
@@ -786,11 +934,16 @@ where
 
                 effects.extend(
                     tasks::message_sender(
-                        receiver,
+                        overlay_receiver,
+                        priority_receiver,
+                        bulk_receiver,
                         sink,
                         self.outgoing_limiter
                             .create_handle(peer_id, peer_consensus_public_key),
                         self.net_metrics.queued_messages.clone(),
+                        self.net_metrics.queued_overlay_messages.clone(),
+                        self.net_metrics.queued_priority_messages.clone(),
+                        self.net_metrics.queued_bulk_messages.clone(),
                     )
                     .instrument(span)
                     .event(move |_| Event::OutgoingDropped {
@@ -861,6 +1014,41 @@ where
         }
     }
 
+    /// Requests a mapping of our public port from the local router, if enabled.
+    fn map_port(&self) -> Effects<Event<P>> {
+        match self.port_mapping {
+            Some(port_mapping) => port_mapping.map().event(|result| Event::PortMapped {
+                result: Box::new(result),
+            }),
+            None => Effects::new(),
+        }
+    }
+
+    /// Advertises the external address obtained by mapping our public port, and schedules the
+    /// renewal of the mapping.
+    fn handle_port_mapped(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        result: std::result::Result<SocketAddr, PortMappingError>,
+    ) -> Effects<Event<P>> {
+        match result {
+            Ok(external_addr) => {
+                if self.context.public_addr() != Some(external_addr) {
+                    info!(%external_addr, "mapped public port on local router, advertising external address");
+                }
+                self.context.set_mapped_addr(external_addr);
+            }
+            Err(error) => {
+                warn!(%error, "failed to map public port on local router");
+            }
+        }
+
+        // Renew the mapping well before its lease expires, or retry if it failed.
+        effect_builder
+            .set_timeout(port_mapping::LEASE_DURATION / 2)
+            .event(|_| Event::MapPort)
+    }
+
     fn handle_outgoing_dropped(
         &mut self,
         peer_id: NodeId,
@@ -958,12 +1146,32 @@ where
                 }
             }
             Message::Payload(payload) => {
+                if payload.is_light_client_request() && !self.cfg.light_client {
+                    debug!(%peer_id, "dropping light client request, as serving them is disabled");
+                    return Effects::new();
+                }
                 effect_builder.announce_incoming(peer_id, payload).ignore()
             }
         })
     }
 
     /// Emits an announcement that a connection has been completed.
+    /// Returns whether the given consensus key belongs to a trusted validator of the overlay.
+    fn is_trusted_validator(&self, consensus_key: Option<&PublicKey>) -> bool {
+        consensus_key.map_or(false, |key| {
+            self.trusted_validators
+                .values()
+                .any(|trusted_key| trusted_key == key)
+        })
+    }
+
+    /// Returns whether we have an outgoing overlay connection to the given peer.
+    fn is_overlay_peer(&self, peer_id: NodeId) -> bool {
+        self.outgoing_manager
+            .get_route(peer_id)
+            .map_or(false, |handle| handle.overlay_sender.is_some())
+    }
+
     fn connection_completed(&self, peer_id: NodeId) {
         trace!(num_peers = self.peers().len(), new_peer=%peer_id, "connection complete");
         self.net_metrics.peers.set(self.peers().len() as i64);
@@ -1026,6 +1234,59 @@ where
             >= self.cfg.min_peers_for_initialization as usize
     }
 
+    /// Applies the known addresses of a reloaded config, connecting to any new ones.
+    ///
+    /// Addresses removed from the config are still remembered until the node is restarted.
+    pub(crate) fn update_known_addresses(
+        &mut self,
+        known_addresses: Vec<String>,
+    ) -> Effects<Event<P>> {
+        let added: Vec<_> = known_addresses
+            .iter()
+            .filter(|address| !self.cfg.known_addresses.contains(address))
+            .cloned()
+            .collect();
+        if self
+            .cfg
+            .known_addresses
+            .iter()
+            .any(|address| !known_addresses.contains(address))
+        {
+            warn!("removed known addresses are only forgotten after a restart");
+        }
+        self.cfg.known_addresses = known_addresses;
+
+        // Until initialized, the new addresses are picked up from the config on initialization.
+        if self.state != ComponentState::Initialized {
+            return Effects::new();
+        }
+        let now = Instant::now();
+        let mut dial_requests = Vec::new();
+        for address in added {
+            match utils::resolve_address(&address) {
+                Ok(addr) => {
+                    info!(%address, resolved=%addr, "learned new known address from config");
+                    dial_requests.extend(self.outgoing_manager.learn_addr(addr, true, now));
+                }
+                Err(ref err) => {
+                    warn!(%address, err=display_error(err), "failed to resolve known address");
+                }
+            }
+        }
+        self.process_dial_requests(dial_requests)
+    }
+
+    /// Stops accepting new connections and closes the incoming ones ahead of a shutdown.
+    ///
+    /// Outgoing connections are kept open, so that messages already queued can still be sent.
+    pub(crate) fn close_incoming(&mut self) {
+        if let Some(channel_management) = self.channel_management.as_mut() {
+            drop(channel_management.shutdown_sender.take());
+            drop(channel_management.close_incoming_sender.take());
+            info!("stopped accepting incoming connections");
+        }
+    }
+
     #[cfg(test)]
     /// Returns the node id of this network node.
     pub(crate) fn node_id(&self) -> NodeId {
@@ -1058,6 +1319,21 @@ where
                         }
                     }
                 }
+
+                if let Some(join_handle) = channel_management.quic_server_join_handle.take() {
+                    match join_handle.await {
+                        Ok(_) => {
+                            debug!(our_id=%self.context.our_id(), "QUIC server exited cleanly")
+                        }
+                        Err(ref err) => {
+                            error!(
+                                our_id=%self.context.our_id(),
+                                err=display_error(err),
+                                "could not join QUIC server task cleanly"
+                            )
+                        }
+                    }
+                }
             }
 
             // Ensure there are no ongoing metrics updates.
@@ -1168,6 +1444,8 @@ where
                 | Event::GossipOurAddress
                 | Event::PeerAddressReceived(_)
                 | Event::SweepOutgoing
+                | Event::MapPort
+                | Event::PortMapped { .. }
                 | Event::BlocklistAnnouncement(_) => {
                     warn!(
                         ?event,
@@ -1218,6 +1496,47 @@ where
                         NetworkInfoRequest::Insight { responder } => responder
                             .respond(NetworkInsights::collect_from_component(self))
                             .ignore(),
+                        NetworkInfoRequest::PeerTraffic { responder } => responder
+                            .respond(self.net_metrics.peer_traffic.peer_traffic())
+                            .ignore(),
+                        NetworkInfoRequest::PeerReputations { responder } => {
+                            responder.respond(self.reputation.reputations()).ignore()
+                        }
+                        NetworkInfoRequest::BanPeer {
+                            peer_id,
+                            duration,
+                            responder,
+                        } => {
+                            let addr = self.outgoing_manager.get_addr(peer_id);
+                            let until = self.reputation.ban(
+                                peer_id,
+                                Timestamp::now() + duration,
+                                "banned by operator".to_string(),
+                                addr,
+                            );
+                            info!(%peer_id, %until, "banning peer on request");
+                            let request = addr.and_then(|addr| {
+                                self.outgoing_manager.block_addr(
+                                    addr,
+                                    Instant::now(),
+                                    BlocklistJustification::Banned,
+                                )
+                            });
+                            let mut effects = self.process_dial_requests(request);
+                            effects.extend(responder.respond(until).ignore());
+                            effects
+                        }
+                        NetworkInfoRequest::UnbanPeer { peer_id, responder } => {
+                            let ban = self.reputation.unban(peer_id);
+                            let was_banned = ban.is_some();
+                            info!(%peer_id, %was_banned, "unbanning peer on request");
+                            let request = ban.and_then(|ban| ban.addr).and_then(|addr| {
+                                self.outgoing_manager.redeem_addr(addr, Instant::now())
+                            });
+                            let mut effects = self.process_dial_requests(request);
+                            effects.extend(responder.respond(was_banned).ignore());
+                            effects
+                        }
                     }
                 }
                 Event::GossipOurAddress => {
@@ -1248,6 +1567,7 @@ where
                 Event::SweepOutgoing => {
                     let now = Instant::now();
                     let requests = self.outgoing_manager.perform_housekeeping(rng, now);
+                    self.reputation.purge_expired_bans(Timestamp::now());
 
                     let mut effects = self.process_dial_requests(requests);
 
@@ -1259,16 +1579,27 @@ where
 
                     effects
                 }
+                Event::MapPort => self.map_port(),
+                Event::PortMapped { result } => self.handle_port_mapped(effect_builder, *result),
                 Event::BlocklistAnnouncement(announcement) => match announcement {
                     PeerBehaviorAnnouncement::OffenseCommitted {
                         offender,
                         justification,
                     } => {
-                        // TODO: We do not have a proper by-node-ID blocklist, but rather only block
-                        // the current outgoing address of a peer.
+                        // The peer is banned by node ID, which prevents new connections to and from
+                        // it. Existing connections are only terminated for its current outgoing
+                        // address.
                         info!(%offender, %justification, "adding peer to blocklist after transgression");
 
-                        if let Some(addr) = self.outgoing_manager.get_addr(*offender) {
+                        let addr = self.outgoing_manager.get_addr(*offender);
+                        self.reputation.record_offense(
+                            *offender,
+                            &justification,
+                            addr,
+                            Timestamp::now() + self.cfg.blocklist_retain_duration,
+                        );
+
+                        if let Some(addr) = addr {
                             let requests = self.outgoing_manager.block_addr(
                                 addr,
                                 Instant::now(),
@@ -1276,7 +1607,7 @@ where
                             );
                             self.process_dial_requests(requests)
                         } else {
-                            // Peer got away with it, no longer an outgoing connection.
+                            // No longer an outgoing connection, the ban will block the next one.
                             Effects::new()
                         }
                     }
@@ -1316,15 +1647,73 @@ where
     }
 }
 
-/// Transport type alias for base encrypted connections.
-type Transport = SslStream<TcpStream>;
+/// Base encrypted connection to a peer.
+#[pin_project(project = TransportProjection)]
+pub(crate) enum Transport {
+    /// TLS over TCP.
+    Tls(#[pin] SslStream<TcpStream>),
+    /// A QUIC stream, only used if enabled in the config and supported by the peer.
+    Quic(#[pin] QuicStream),
+}
+
+impl Transport {
+    /// Returns the ID of the connection, which is the same on both ends.
+    fn connection_id(&self, our_id: NodeId, their_id: NodeId) -> ConnectionId {
+        match self {
+            Transport::Tls(stream) => ConnectionId::from_connection(stream.ssl(), our_id, their_id),
+            Transport::Quic(stream) => {
+                ConnectionId::from_quic_connection(stream.connection(), our_id, their_id)
+            }
+        }
+    }
+}
+
+impl AsyncRead for Transport {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.project() {
+            TransportProjection::Tls(stream) => stream.poll_read(cx, buf),
+            TransportProjection::Quic(stream) => stream.poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Transport {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            TransportProjection::Tls(stream) => stream.poll_write(cx, buf),
+            TransportProjection::Quic(stream) => stream.poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            TransportProjection::Tls(stream) => stream.poll_flush(cx),
+            TransportProjection::Quic(stream) => stream.poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            TransportProjection::Tls(stream) => stream.poll_shutdown(cx),
+            TransportProjection::Quic(stream) => stream.poll_shutdown(cx),
+        }
+    }
+}
 
 /// A framed transport for `Message`s.
 pub(crate) type FullTransport<P> = tokio_serde::Framed<
     FramedTransport,
     Message<P>,
     Arc<Message<P>>,
-    CountingFormat<BincodeFormat>,
+    CountingFormat<CompressionFormat>,
 >;
 
 pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDelimitedCodec>;
@@ -1332,11 +1721,15 @@ pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDel
 /// Constructs a new full transport on a stream.
 ///
 /// A full transport contains the framing as well as the encoding scheme used to send messages.
+/// Messages are compressed using the given threshold if compression was negotiated with the peer.
 fn full_transport<P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
     framed: FramedTransport,
     role: Role,
+    peer_id: NodeId,
+    compression_threshold: Option<u32>,
+    maximum_net_message_size: u32,
 ) -> FullTransport<P>
 where
     for<'de> P: Serialize + Deserialize<'de>,
@@ -1344,7 +1737,13 @@ where
 {
     tokio_serde::Framed::new(
         framed,
-        CountingFormat::new(metrics, connection_id, role, BincodeFormat::default()),
+        CountingFormat::new(
+            metrics.clone(),
+            connection_id,
+            role,
+            peer_id,
+            CompressionFormat::new(metrics, compression_threshold, maximum_net_message_size),
+        ),
     )
 }
 
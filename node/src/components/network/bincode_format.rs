@@ -12,7 +12,7 @@ use bincode::{
     Options,
 };
 use bytes::{Bytes, BytesMut};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio_serde::{Deserializer, Serializer};
 
 use super::Message;
@@ -45,6 +45,17 @@ impl BincodeFormat {
             .serialize(item)
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
     }
+
+    /// Deserializes an arbitrary value with the networking bincode deserializer.
+    #[inline]
+    pub(crate) fn deserialize_arbitrary<T>(&self, bytes: &[u8]) -> io::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.0
+            .deserialize(bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
 }
 
 impl Debug for BincodeFormat {
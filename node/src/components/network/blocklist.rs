@@ -57,6 +57,32 @@ pub(crate) enum BlocklistJustification {
     DishonestPeer,
     /// Peer sent too many finality signatures.
     SentTooManyFinalitySignatures { max_allowed: u32 },
+    /// Peer is banned by node ID, either by the node operator or for an earlier offense.
+    Banned,
+}
+
+impl BlocklistJustification {
+    /// The penalty added to the reputation of a peer committing the offense.
+    ///
+    /// Offenses which can only be committed deliberately weigh heavier than those which may be
+    /// caused by misconfiguration or overload.
+    pub(crate) fn penalty(&self) -> u64 {
+        match self {
+            BlocklistJustification::SentBadItem { .. }
+            | BlocklistJustification::SentInvalidItem { .. }
+            | BlocklistJustification::SentBadFinalitySignature { .. }
+            | BlocklistJustification::SentBadBlock { .. }
+            | BlocklistJustification::SentInvalidConsensusValue { .. }
+            | BlocklistJustification::BadConsensusBehavior
+            | BlocklistJustification::DishonestPeer => 100,
+            BlocklistJustification::WrongNetwork { .. }
+            | BlocklistJustification::WrongChainspecHash { .. }
+            | BlocklistJustification::MissingChainspecHash => 50,
+            BlocklistJustification::PongLimitExceeded
+            | BlocklistJustification::SentTooManyFinalitySignatures { .. } => 25,
+            BlocklistJustification::Banned => 0,
+        }
+    }
 }
 
 impl Display for BlocklistJustification {
@@ -105,6 +131,7 @@ impl Display for BlocklistJustification {
                 f,
                 "sent too many finality signatures: maximum {max_allowed} signatures are allowed"
             ),
+            BlocklistJustification::Banned => f.write_str("is banned"),
         }
     }
 }
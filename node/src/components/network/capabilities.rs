@@ -0,0 +1,142 @@
+//! Capabilities negotiated during the handshake.
+//!
+//! Every node advertises the optional features it supports as a set of flags in its handshake. A
+//! feature is only used on a connection if both sides advertised it, which allows new features to
+//! be rolled out gradually instead of requiring all nodes to upgrade at once. Flags unknown to a
+//! node are ignored, as is the absence of the field in handshakes of older nodes.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::BitOr,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// A set of optional features supported by a node.
+#[derive(Clone, Copy, DataSize, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub(crate) struct Capabilities(u64);
+
+impl Capabilities {
+    /// The node can verify zero-knowledge proofs received from its peers.
+    ///
+    /// Reserved, not advertised by this version of the node.
+    pub(crate) const ZK_PROOFS: Capabilities = Capabilities(1 << 0);
+    /// The node accepts messages compressed with zstd.
+    pub(crate) const COMPRESSION: Capabilities = Capabilities(1 << 1);
+    /// The node answers requests for light client updates.
+    pub(crate) const LIGHT_CLIENT_SERVING: Capabilities = Capabilities(1 << 2);
+    /// The node accepts QUIC connections on the UDP port matching its advertised TCP port.
+    pub(crate) const QUIC_TRANSPORT: Capabilities = Capabilities(1 << 3);
+
+    /// All known capabilities, along with their names.
+    const NAMED: [(Capabilities, &'static str); 4] = [
+        (Capabilities::ZK_PROOFS, "zk-proofs"),
+        (Capabilities::COMPRESSION, "compression"),
+        (Capabilities::LIGHT_CLIENT_SERVING, "light-client-serving"),
+        (Capabilities::QUIC_TRANSPORT, "quic-transport"),
+    ];
+
+    /// Returns an empty set of capabilities.
+    pub(crate) const fn empty() -> Self {
+        Capabilities(0)
+    }
+
+    /// Returns whether no capabilities are set.
+    pub(crate) fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all capabilities in `other` are set.
+    pub(crate) fn contains(self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the capabilities set in both `self` and `other`, i.e. those usable on a connection.
+    pub(crate) fn intersection(self, other: Capabilities) -> Self {
+        Capabilities(self.0 & other.0)
+    }
+
+    /// Returns `self` with the capabilities in `other` added if `enabled` is true.
+    pub(crate) fn with_if(self, other: Capabilities, enabled: bool) -> Self {
+        if enabled {
+            self | other
+        } else {
+            self
+        }
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Capabilities;
+
+    fn bitor(self, rhs: Capabilities) -> Self::Output {
+        Capabilities(self.0 | rhs.0)
+    }
+}
+
+impl Display for Capabilities {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+
+        let mut unknown = self.0;
+        let mut separator = "";
+        for (capability, name) in Capabilities::NAMED {
+            if self.contains(capability) {
+                write!(f, "{}{}", separator, name)?;
+                separator = ",";
+                unknown &= !capability.0;
+            }
+        }
+        if unknown != 0 {
+            write!(f, "{}unknown({:#x})", separator, unknown)?;
+        }
+        Ok(())
+    }
+}
+
+mod specimen_support {
+    use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
+
+    use super::Capabilities;
+
+    impl LargestSpecimen for Capabilities {
+        fn largest_specimen<E: SizeEstimator>(_estimator: &E, _cache: &mut Cache) -> Self {
+            Capabilities(u64::MAX)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Capabilities;
+
+    #[test]
+    fn should_negotiate_common_capabilities() {
+        let ours = Capabilities::empty()
+            .with_if(Capabilities::COMPRESSION, true)
+            .with_if(Capabilities::LIGHT_CLIENT_SERVING, false);
+        let theirs = Capabilities::COMPRESSION | Capabilities::LIGHT_CLIENT_SERVING;
+
+        let negotiated = ours.intersection(theirs);
+        assert!(negotiated.contains(Capabilities::COMPRESSION));
+        assert!(!negotiated.contains(Capabilities::LIGHT_CLIENT_SERVING));
+        assert!(ours.intersection(Capabilities::empty()).is_empty());
+        assert_eq!(negotiated.to_string(), "compression");
+    }
+
+    #[test]
+    fn should_ignore_unknown_capabilities() {
+        let theirs: Capabilities = serde_json::from_str("65540").unwrap();
+        assert!(theirs.contains(Capabilities::LIGHT_CLIENT_SERVING));
+        assert!(!theirs.contains(Capabilities::COMPRESSION));
+        assert_eq!(theirs.to_string(), "light-client-serving,unknown(0x10000)");
+        assert_eq!(
+            Capabilities::COMPRESSION.intersection(theirs),
+            Capabilities::empty()
+        );
+    }
+}
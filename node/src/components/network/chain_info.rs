@@ -10,6 +10,7 @@ use casper_types::ProtocolVersion;
 use datasize::DataSize;
 
 use super::{
+    capabilities::Capabilities,
     counting_format::ConnectionId,
     message::{ConsensusCertificate, NodeKeyPair},
     Message,
@@ -52,6 +53,7 @@ impl ChainInfo {
         consensus_keys: Option<&NodeKeyPair>,
         connection_id: ConnectionId,
         is_syncing: bool,
+        capabilities: Capabilities,
     ) -> Message<P> {
         Message::Handshake {
             network_name: self.network_name.clone(),
@@ -61,6 +63,7 @@ impl ChainInfo {
                 .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
             is_syncing,
             chainspec_hash: Some(self.chainspec_hash),
+            capabilities,
         }
     }
 }
@@ -0,0 +1,237 @@
+//! Compressing wire format.
+//!
+//! Wraps the bincode encoding of messages, compressing serialized messages at or above a size
+//! threshold with zstd. Compression is only used on connections where both peers offered it in
+//! their handshake; on such connections every frame is prefixed with a single byte indicating
+//! whether the remainder of the frame is compressed. Connections without compression use the
+//! plain bincode encoding, unchanged.
+
+use std::{
+    fmt::Debug,
+    io::{self, Read},
+    pin::Pin,
+    sync::{Arc, Weak},
+};
+
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_serde::{Deserializer, Serializer};
+
+use super::{BincodeFormat, Message, Metrics};
+
+/// Frame prefix marking a message which was sent uncompressed.
+const UNCOMPRESSED: u8 = 0;
+/// Frame prefix marking a message which was sent compressed.
+const COMPRESSED: u8 = 1;
+/// The zstd compression level, chosen to favor speed over compression ratio.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// bincode encoder/decoder for messages, compressing them if negotiated with the peer.
+pub struct CompressionFormat {
+    /// The underlying message encoding.
+    bincode: BincodeFormat,
+    /// Minimum size of a serialized message for it to be compressed, or `None` if compression was
+    /// not negotiated for this connection.
+    threshold: Option<usize>,
+    /// Maximum size of a message after decompression.
+    maximum_message_size: usize,
+    /// Metrics to update.
+    metrics: Weak<Metrics>,
+}
+
+impl CompressionFormat {
+    /// Creates a new compression format.
+    ///
+    /// `threshold` should be `None` unless both peers offered compression during the handshake.
+    pub(super) fn new(
+        metrics: Weak<Metrics>,
+        threshold: Option<u32>,
+        maximum_message_size: u32,
+    ) -> Self {
+        CompressionFormat {
+            bincode: BincodeFormat::default(),
+            threshold: threshold.map(|threshold| threshold as usize),
+            maximum_message_size: maximum_message_size as usize,
+            metrics,
+        }
+    }
+}
+
+impl Debug for CompressionFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressionFormat")
+            .field("threshold", &self.threshold)
+            .field("maximum_message_size", &self.maximum_message_size)
+            .finish()
+    }
+}
+
+impl<P> Serializer<Arc<Message<P>>> for CompressionFormat
+where
+    Message<P>: Serialize,
+{
+    type Error = io::Error;
+
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        let msg = &**item;
+        let threshold = match self.threshold {
+            Some(threshold) => threshold,
+            None => return self.bincode.serialize_arbitrary(msg).map(Into::into),
+        };
+
+        // Serialize directly after the prefix, to avoid copying uncompressed messages.
+        let mut uncompressed = vec![UNCOMPRESSED];
+        self.bincode
+            .0
+            .serialize_into(&mut uncompressed, msg)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if uncompressed.len() - 1 < threshold {
+            return Ok(uncompressed.into());
+        }
+
+        let mut compressed = vec![COMPRESSED];
+        zstd::stream::copy_encode(&uncompressed[1..], &mut compressed, COMPRESSION_LEVEL)?;
+        if compressed.len() >= uncompressed.len() {
+            // The message is incompressible, there is no point in making the peer decompress it.
+            return Ok(uncompressed.into());
+        }
+
+        Metrics::record_compression_out(
+            &self.metrics,
+            (uncompressed.len() - 1) as u64,
+            (compressed.len() - 1) as u64,
+        );
+        Ok(compressed.into())
+    }
+}
+
+impl<P> Deserializer<Message<P>> for CompressionFormat
+where
+    for<'de> Message<P>: Deserialize<'de>,
+{
+    type Error = io::Error;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        if self.threshold.is_none() {
+            return self.bincode.deserialize_arbitrary(src);
+        }
+
+        match src.split_first() {
+            Some((&UNCOMPRESSED, uncompressed)) => self.bincode.deserialize_arbitrary(uncompressed),
+            Some((&COMPRESSED, compressed)) => {
+                // Bound the decompressed size, otherwise a small frame could exhaust our memory.
+                let mut uncompressed = Vec::new();
+                zstd::stream::Decoder::new(compressed)?
+                    .take(self.maximum_message_size as u64 + 1)
+                    .read_to_end(&mut uncompressed)?;
+                if uncompressed.len() > self.maximum_message_size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "decompressed message exceeds maximum message size",
+                    ));
+                }
+
+                Metrics::record_compression_in(
+                    &self.metrics,
+                    uncompressed.len() as u64,
+                    compressed.len() as u64,
+                );
+                self.bincode.deserialize_arbitrary(&uncompressed)
+            }
+            Some((prefix, _)) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid compression prefix {}", prefix),
+            )),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "received empty frame",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use casper_types::ProtocolVersion;
+
+    use super::*;
+    use crate::{components::network::capabilities::Capabilities, protocol};
+
+    const THRESHOLD: u32 = 1024;
+    const MAXIMUM_MESSAGE_SIZE: u32 = 1024 * 1024;
+
+    fn handshake(network_name_len: usize) -> Arc<Message<protocol::Message>> {
+        Arc::new(Message::Handshake {
+            network_name: iter::repeat('a').take(network_name_len).collect(),
+            public_addr: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::V1_0_0,
+            consensus_certificate: None,
+            is_syncing: false,
+            chainspec_hash: None,
+            capabilities: Capabilities::COMPRESSION,
+        })
+    }
+
+    fn roundtrip(
+        format: &mut CompressionFormat,
+        msg: &Arc<Message<protocol::Message>>,
+    ) -> (Bytes, Message<protocol::Message>) {
+        let serialized = Pin::new(&mut *format).serialize(msg).unwrap();
+        let deserialized = Pin::new(&mut *format)
+            .deserialize(&BytesMut::from(&serialized[..]))
+            .unwrap();
+        (serialized, deserialized)
+    }
+
+    fn network_name_len(msg: &Message<protocol::Message>) -> usize {
+        match msg {
+            Message::Handshake { network_name, .. } => network_name.len(),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_compress_large_messages_only() {
+        let mut format = CompressionFormat::new(Weak::new(), Some(THRESHOLD), MAXIMUM_MESSAGE_SIZE);
+
+        let small = handshake(10);
+        let (serialized, deserialized) = roundtrip(&mut format, &small);
+        assert_eq!(serialized[0], UNCOMPRESSED);
+        assert_eq!(network_name_len(&deserialized), 10);
+
+        let large = handshake(100_000);
+        let (serialized, deserialized) = roundtrip(&mut format, &large);
+        assert_eq!(serialized[0], COMPRESSED);
+        assert!(serialized.len() < 1_000);
+        assert_eq!(network_name_len(&deserialized), 100_000);
+    }
+
+    #[test]
+    fn should_use_plain_bincode_if_not_negotiated() {
+        let mut format = CompressionFormat::new(Weak::new(), None, MAXIMUM_MESSAGE_SIZE);
+
+        let large = handshake(100_000);
+        let (serialized, deserialized) = roundtrip(&mut format, &large);
+        let expected = BincodeFormat::default()
+            .serialize_arbitrary(&*large)
+            .unwrap();
+        assert_eq!(&serialized[..], &expected[..]);
+        assert_eq!(network_name_len(&deserialized), 100_000);
+    }
+
+    #[test]
+    fn should_reject_oversized_decompressed_messages() {
+        let mut sender = CompressionFormat::new(Weak::new(), Some(THRESHOLD), MAXIMUM_MESSAGE_SIZE);
+        let mut receiver = CompressionFormat::new(Weak::new(), Some(THRESHOLD), 10_000);
+
+        let serialized = Pin::new(&mut sender)
+            .serialize(&handshake(100_000))
+            .unwrap();
+        assert_eq!(serialized[0], COMPRESSED);
+        let result: io::Result<Message<protocol::Message>> =
+            Pin::new(&mut receiver).deserialize(&BytesMut::from(&serialized[..]));
+        assert!(result.is_err());
+    }
+}
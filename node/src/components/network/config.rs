@@ -2,7 +2,7 @@
 use std::net::{Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 
-use casper_types::{ProtocolVersion, TimeDiff};
+use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
@@ -32,12 +32,26 @@ const DEFAULT_MAX_ADDR_PENDING_TIME: TimeDiff = TimeDiff::from_seconds(60);
 /// Default timeout during which the handshake needs to be completed.
 const DEFAULT_HANDSHAKE_TIMEOUT: TimeDiff = TimeDiff::from_seconds(20);
 
+/// Default upper bound of the exponential backoff between reconnection attempts.
+const DEFAULT_MAX_DIAL_BACKOFF: TimeDiff = TimeDiff::from_seconds(120);
+
+/// Default fraction by which reconnection backoffs are randomly varied.
+const DEFAULT_DIAL_BACKOFF_JITTER: f32 = 0.2;
+
+/// Default maximum number of outgoing connection attempts per second.
+const DEFAULT_MAX_DIALS_PER_SECOND: u32 = 20;
+
+/// Default minimum size of a serialized message for it to be compressed.
+const DEFAULT_COMPRESSION_THRESHOLD: u32 = 16 * 1024;
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             bind_address: DEFAULT_BIND_ADDRESS.to_string(),
             public_address: DEFAULT_PUBLIC_ADDRESS.to_string(),
+            port_mapping: false,
             known_addresses: Vec::new(),
+            trusted_validators: Vec::new(),
             min_peers_for_initialization: DEFAULT_MIN_PEERS_FOR_INITIALIZATION,
             gossip_interval: DEFAULT_GOSSIP_INTERVAL,
             initial_gossip_delay: DEFAULT_INITIAL_GOSSIP_DELAY,
@@ -52,6 +66,12 @@ impl Default for Config {
             tarpit_chance: 0.2,
             max_in_flight_demands: 50,
             blocklist_retain_duration: TimeDiff::from_seconds(600),
+            max_dial_backoff: DEFAULT_MAX_DIAL_BACKOFF,
+            dial_backoff_jitter: DEFAULT_DIAL_BACKOFF_JITTER,
+            max_dials_per_second: DEFAULT_MAX_DIALS_PER_SECOND,
+            compression_threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            light_client: false,
+            quic_transport: false,
             identity: None,
         }
     }
@@ -70,6 +90,17 @@ pub struct IdentityConfig {
     pub ca_certificate: PathBuf,
 }
 
+/// A trusted validator peer of the private validator overlay.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct TrustedValidator {
+    /// The consensus public key the validator's node must prove during the handshake.
+    pub public_key: PublicKey,
+    /// Address of the validator's node.
+    pub address: String,
+}
+
 /// Network configuration.
 #[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -81,8 +112,18 @@ pub struct Config {
     ///
     /// If the port is specified as `0`, it will be replaced with the actually bound port.
     pub public_address: String,
+    /// Whether to request a mapping of the public port from the local router via UPnP or NAT-PMP.
+    ///
+    /// If successful, the external address reported by the router is advertised instead of
+    /// `public_address`.
+    pub port_mapping: bool,
     /// Known address of a node on the network used for joining.
     pub known_addresses: Vec<String>,
+    /// Validators whose nodes are part of the private validator overlay.
+    ///
+    /// Connections to these nodes are always maintained, and consensus messages sent to them use a
+    /// dedicated queue which is neither held up by other traffic nor subject to bandwidth limits.
+    pub trusted_validators: Vec<TrustedValidator>,
     /// Minimum number of fully-connected peers to consider component initialized.
     pub min_peers_for_initialization: u16,
     /// Interval in milliseconds used for gossiping.
@@ -111,6 +152,26 @@ pub struct Config {
     pub max_in_flight_demands: u32,
     /// Duration peers are kept on the block list, before being redeemed.
     pub blocklist_retain_duration: TimeDiff,
+    /// Upper bound of the exponential backoff between attempts to reconnect to an address.
+    pub max_dial_backoff: TimeDiff,
+    /// Fraction, between 0.0 and 1.0, by which each reconnection backoff is randomly lengthened or
+    /// shortened, to avoid many nodes redialing an address in lockstep.
+    pub dial_backoff_jitter: f32,
+    /// Maximum number of outgoing connection attempts started per second. Unlimited if 0.
+    pub max_dials_per_second: u32,
+    /// Minimum size in bytes of a serialized message for it to be compressed with zstd before
+    /// being sent to a peer which negotiated compression during the handshake.
+    ///
+    /// Compression is disabled entirely, and not offered to peers, if `0`.
+    pub compression_threshold: u32,
+    /// Whether to answer requests for light client updates.
+    pub light_client: bool,
+    /// Whether to accept QUIC connections, and to dial peers via QUIC before falling back to TCP.
+    ///
+    /// QUIC connections are accepted on the UDP port with the same number as the bound TCP port.
+    /// Peers which could not be reached via QUIC, or which did not advertise support for it, are
+    /// dialed via TCP only from then on.
+    pub quic_transport: bool,
     /// Network identity configuration option.
     ///
     /// An identity will be automatically generated when starting up a node if this option is
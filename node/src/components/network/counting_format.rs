@@ -15,6 +15,7 @@ use std::{
 use bytes::{Bytes, BytesMut};
 use openssl::ssl::SslRef;
 use pin_project::pin_project;
+use quinn::Connection;
 #[cfg(test)]
 use rand::RngCore;
 use static_assertions::const_assert;
@@ -59,6 +60,8 @@ pub struct CountingFormat<F> {
     in_count: u64,
     /// Our role in the connection.
     role: Role,
+    /// The peer on the other end of the connection.
+    peer_id: NodeId,
     /// Metrics to update.
     metrics: Weak<Metrics>,
 }
@@ -70,6 +73,7 @@ impl<F> CountingFormat<F> {
         metrics: Weak<Metrics>,
         connection_id: ConnectionId,
         role: Role,
+        peer_id: NodeId,
         inner: F,
     ) -> Self {
         Self {
@@ -78,6 +82,7 @@ impl<F> CountingFormat<F> {
             out_count: 0,
             in_count: 0,
             role,
+            peer_id,
             inner,
         }
     }
@@ -98,7 +103,7 @@ where
         let serialized = F::serialize(projection, item)?;
         let msg_size = serialized.len() as u64;
         let msg_kind = item.classify();
-        Metrics::record_payload_out(this.metrics, msg_kind, msg_size);
+        Metrics::record_payload_out(this.metrics, *this.peer_id, msg_kind, msg_size);
 
         let trace_id = this
             .connection_id
@@ -130,7 +135,7 @@ where
 
         let deserialized = F::deserialize(projection, src)?;
         let msg_kind = deserialized.classify();
-        Metrics::record_payload_in(this.metrics, msg_kind, msg_size);
+        Metrics::record_payload_in(this.metrics, *this.peer_id, msg_kind, msg_size);
 
         let trace_id = this
             .connection_id
@@ -170,6 +175,9 @@ pub(super) struct TlsRandomData {
 /// Used to check random data.
 const ZERO_RANDOMNESS: [u8; 12] = [0; 12];
 
+/// Label of the keying material exported from QUIC connections as their random data.
+const QUIC_RANDOM_LABEL: &[u8] = b"EXPORTER-casper-node-connection-id";
+
 impl TlsRandomData {
     /// Collects random data from an existing SSL collection.
     ///
@@ -207,6 +215,23 @@ impl TlsRandomData {
         }
     }
 
+    /// Collects random data from an established QUIC connection.
+    ///
+    /// QUIC connections do not expose the TLS nonces, so keying material exported from the TLS
+    /// session is used instead, which is likewise the same on both ends of the connection.
+    fn export(connection: &Connection) -> Self {
+        let mut combined_random = [0; 12];
+
+        if connection
+            .export_keying_material(&mut combined_random, QUIC_RANDOM_LABEL, &[])
+            .is_err()
+        {
+            warn!("could not export keying material from QUIC connection");
+        }
+
+        Self { combined_random }
+    }
+
     /// Creates random `TlsRandomData`.
     #[cfg(test)]
     fn random(rng: &mut TestRng) -> Self {
@@ -271,6 +296,16 @@ impl ConnectionId {
         Self::create(TlsRandomData::collect(ssl), our_id, their_id)
     }
 
+    /// Creates a new connection ID from an established QUIC connection.
+    #[inline]
+    pub(crate) fn from_quic_connection(
+        connection: &Connection,
+        our_id: NodeId,
+        their_id: NodeId,
+    ) -> Self {
+        Self::create(TlsRandomData::export(connection), our_id, their_id)
+    }
+
     /// Creates a random `ConnectionId`.
     #[cfg(test)]
     pub(super) fn random(rng: &mut TestRng) -> Self {
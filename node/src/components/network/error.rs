@@ -8,6 +8,7 @@ use thiserror::Error;
 use casper_hashing::Digest;
 use casper_types::{crypto, ProtocolVersion};
 
+use super::quic::QuicError;
 use crate::{
     tls::{LoadCertError, ValidationError},
     utils::ResolveAddressError,
@@ -43,6 +44,14 @@ pub enum Error {
         #[source]
         io::Error,
     ),
+    /// Failed to create the QUIC endpoint.
+    #[error("failed to create QUIC endpoint on {1}")]
+    QuicEndpointCreation(
+        #[serde(skip_serializing)]
+        #[source]
+        QuicError,
+        SocketAddr,
+    ),
     /// Failed to convert std TCP listener to tokio TCP listener.
     #[error("failed to convert listener to tokio")]
     ListenerConversion(
@@ -126,6 +135,13 @@ pub enum ConnectionError {
         #[source]
         ssl::Error,
     ),
+    /// Setting up a QUIC connection, including the exchange of node certificates, failed.
+    #[error("QUIC connection setup failed")]
+    QuicSetup(
+        #[serde(skip_serializing)]
+        #[source]
+        IoError<QuicError>,
+    ),
     /// Remote failed to present a client/server certificate.
     #[error("no client certificate presented")]
     NoPeerCertificate,
@@ -13,7 +13,10 @@ use tracing::Span;
 
 use casper_types::PublicKey;
 
-use super::{error::ConnectionError, FullTransport, GossipedAddress, Message, NodeId};
+use super::{
+    capabilities::Capabilities, error::ConnectionError, port_mapping::PortMappingError,
+    FullTransport, GossipedAddress, Message, NodeId,
+};
 use crate::{
     effect::{
         announcements::PeerBehaviorAnnouncement,
@@ -91,6 +94,15 @@ pub(crate) enum Event<P> {
     /// Housekeeping for the outgoing manager.
     SweepOutgoing,
 
+    /// Our public port should be mapped on the local router, or its mapping renewed.
+    MapPort,
+
+    /// An attempt to map our public port on the local router finished.
+    PortMapped {
+        #[serde(skip_serializing)]
+        result: Box<Result<SocketAddr, PortMappingError>>,
+    },
+
     /// Blocklist announcement.
     #[from]
     BlocklistAnnouncement(PeerBehaviorAnnouncement),
@@ -141,6 +153,11 @@ impl<P: Display> Display for Event<P> {
             Event::SweepOutgoing => {
                 write!(f, "sweep outgoing connections")
             }
+            Event::MapPort => write!(f, "map port"),
+            Event::PortMapped { result } => match result.as_ref() {
+                Ok(external_addr) => write!(f, "port mapped to {}", external_addr),
+                Err(error) => write!(f, "port mapping failed: {}", error),
+            },
         }
     }
 }
@@ -251,6 +268,8 @@ pub(crate) enum OutgoingConnection<P> {
         sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
         /// Holds the information whether the remote node is syncing.
         is_syncing: bool,
+        /// Capabilities supported by both us and the peer.
+        capabilities: Capabilities,
     },
 }
 
@@ -272,11 +291,12 @@ impl<P> Display for OutgoingConnection<P> {
                 peer_consensus_public_key,
                 sink: _,
                 is_syncing,
+                capabilities,
             } => {
                 write!(
                     f,
-                    "connection established to {}/{}, is_syncing: {}",
-                    peer_addr, peer_id, is_syncing
+                    "connection established to {}/{}, is_syncing: {}, capabilities: {}",
+                    peer_addr, peer_id, is_syncing, capabilities
                 )?;
 
                 if let Some(public_key) = peer_consensus_public_key {
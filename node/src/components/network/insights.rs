@@ -56,6 +56,10 @@ pub(crate) struct NetworkInsights {
 struct OutgoingInsight {
     /// Whether or not the address is marked unforgettable.
     unforgettable: bool,
+    /// Number of successful dials to the address.
+    dial_successes: u64,
+    /// Number of failed dials to the address.
+    dial_failures: u64,
     /// The current connection state.
     state: OutgoingStateInsight,
 }
@@ -71,10 +75,13 @@ enum OutgoingStateInsight {
         failures_so_far: u8,
         error: Option<String>,
         last_failure: SystemTime,
+        backoff: Duration,
     },
     Connected {
         peer_id: NodeId,
         peer_addr: SocketAddr,
+        is_overlay: bool,
+        capabilities: String,
         last_ping_sent: Option<SystemTime>,
         last_pong_received: Option<SystemTime>,
         invalid_pong_count: u32,
@@ -114,10 +121,12 @@ impl OutgoingStateInsight {
                 failures_so_far,
                 error,
                 last_failure,
+                backoff,
             } => OutgoingStateInsight::Waiting {
                 failures_so_far: *failures_so_far,
                 error: error.as_ref().map(ToString::to_string),
                 last_failure: anchor.convert(*last_failure),
+                backoff: *backoff,
             },
             OutgoingState::Connected {
                 peer_id,
@@ -126,6 +135,8 @@ impl OutgoingStateInsight {
             } => OutgoingStateInsight::Connected {
                 peer_id: *peer_id,
                 peer_addr: handle.peer_addr,
+                is_overlay: handle.overlay_sender.is_some(),
+                capabilities: handle.capabilities.to_string(),
                 last_ping_sent: health
                     .last_ping_sent
                     .map(|tt| anchor.convert(tt.timestamp())),
@@ -162,16 +173,20 @@ impl OutgoingStateInsight {
                 failures_so_far,
                 error,
                 last_failure,
+                backoff,
             } => write!(
                 f,
-                "waiting (fails: {}, last error: {}), since {}",
+                "waiting (fails: {}, last error: {}, backoff: {}), since {}",
                 failures_so_far,
                 OptDisplay::new(error.as_ref(), "none"),
+                humantime::format_duration(*backoff),
                 time_delta(now, *last_failure)
             ),
             OutgoingStateInsight::Connected {
                 peer_id,
                 peer_addr,
+                is_overlay,
+                capabilities,
                 last_ping_sent,
                 last_pong_received,
                 invalid_pong_count,
@@ -181,9 +196,11 @@ impl OutgoingStateInsight {
 
                 write!(
                     f,
-                    "connected -> {} @ {} (rtt {}, invalid {}, last ping/pong {}/{})",
+                    "connected{} -> {} @ {} (capabilities {}, rtt {}, invalid {}, last ping/pong {}/{})",
+                    if *is_overlay { " (overlay)" } else { "" },
                     peer_id,
                     peer_addr,
+                    capabilities,
                     OptDisplay::new(rtt_ms, "?"),
                     invalid_pong_count,
                     OptDisplay::new(last_ping_sent.map(|t| time_delta(now, t)), "-"),
@@ -289,6 +306,8 @@ impl NetworkInsights {
                     *addr,
                     OutgoingInsight {
                         unforgettable: outgoing.is_unforgettable,
+                        dial_successes: outgoing.dial_successes,
+                        dial_failures: outgoing.dial_failures,
                         state,
                     },
                 )
@@ -365,9 +384,13 @@ impl Display for NetworkInsights {
         )?;
 
         f.write_str("outgoing connections:\n")?;
-        writeln!(f, "address                  uf     state")?;
+        writeln!(f, "address                  uf     dials ok/fail  state")?;
         for (addr, outgoing) in &self.outgoing_connections {
-            write!(f, "{:23}  {:5}  ", addr, outgoing.unforgettable,)?;
+            write!(
+                f,
+                "{:23}  {:5}  {:>6}/{:<6}  ",
+                addr, outgoing.unforgettable, outgoing.dial_successes, outgoing.dial_failures,
+            )?;
             outgoing.state.fmt_time_relative(now, f)?;
             f.write_str("\n")?;
         }
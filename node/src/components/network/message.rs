@@ -17,7 +17,9 @@ use casper_hashing::Digest;
 use casper_types::testing::TestRng;
 use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
 
-use super::{counting_format::ConnectionId, health::Nonce, BincodeFormat};
+use super::{
+    capabilities::Capabilities, counting_format::ConnectionId, health::Nonce, BincodeFormat,
+};
 use crate::{
     effect::EffectBuilder,
     protocol,
@@ -55,6 +57,9 @@ pub(crate) enum Message<P> {
         /// Hash of the chainspec the node is running.
         #[serde(default)]
         chainspec_hash: Option<Digest>,
+        /// Optional features supported by the node.
+        #[serde(default)]
+        capabilities: Capabilities,
     },
     /// A ping request.
     Ping {
@@ -81,6 +86,12 @@ impl<P: Payload> Message<P> {
         }
     }
 
+    /// Returns the outgoing channel the message is queued on.
+    #[inline]
+    pub(super) fn channel(&self) -> Channel {
+        self.classify().channel()
+    }
+
     /// Determines whether or not a message is low priority.
     #[inline]
     pub(super) fn is_low_priority(&self) -> bool {
@@ -293,16 +304,18 @@ impl<P: Display> Display for Message<P> {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                capabilities,
             } => {
                 write!(
                     f,
-                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}",
+                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}, capabilities: {}",
                     network_name,
                     public_addr,
                     protocol_version,
                     OptDisplay::new(consensus_certificate.as_ref(), "none"),
                     is_syncing,
-                    OptDisplay::new(chainspec_hash.as_ref(), "none")
+                    OptDisplay::new(chainspec_hash.as_ref(), "none"),
+                    capabilities
                 )
             }
             Message::Ping { nonce } => write!(f, "ping({})", nonce),
@@ -313,7 +326,7 @@ impl<P: Display> Display for Message<P> {
 }
 
 /// A classification system for networking messages.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub(crate) enum MessageKind {
     /// Non-payload messages, like handshakes.
     Protocol,
@@ -354,6 +367,46 @@ impl Display for MessageKind {
     }
 }
 
+impl MessageKind {
+    /// Returns the outgoing channel messages of this kind are queued on.
+    pub(super) fn channel(self) -> Channel {
+        match self {
+            MessageKind::Protocol
+            | MessageKind::Consensus
+            | MessageKind::FinalitySignatureGossip => Channel::Priority,
+            MessageKind::DeployGossip
+            | MessageKind::BlockGossip
+            | MessageKind::AddressGossip
+            | MessageKind::DeployTransfer
+            | MessageKind::BlockTransfer
+            | MessageKind::TrieTransfer
+            | MessageKind::Other => Channel::Bulk,
+        }
+    }
+}
+
+/// An outgoing message queue of a connection.
+///
+/// Messages on the priority channel are always sent before any queued bulk messages, so that bulk
+/// traffic cannot delay consensus under load.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum Channel {
+    /// Consensus-critical messages: proposals, echoes, votes and finality signatures, as well as
+    /// protocol messages like pings.
+    Priority,
+    /// All other messages, like gossip and data transfers.
+    Bulk,
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Priority => f.write_str("priority"),
+            Channel::Bulk => f.write_str("bulk"),
+        }
+    }
+}
+
 /// Network message payload.
 ///
 /// Payloads are what is transferred across the network outside of control messages from the
@@ -376,6 +429,12 @@ pub(crate) trait Payload:
     ///
     /// This functionality should be removed once multiplexed networking lands.
     fn is_unsafe_for_syncing_peers(&self) -> bool;
+
+    /// Indicates a message is a request for data only needed by light clients, which is only
+    /// answered if serving light clients is enabled.
+    fn is_light_client_request(&self) -> bool {
+        false
+    }
 }
 
 /// Network message conversion support.
@@ -432,6 +491,8 @@ pub struct EstimatorWeights {
     pub approvals_hashes_responses: u32,
     pub execution_results_requests: u32,
     pub execution_results_responses: u32,
+    pub light_client_update_requests: u32,
+    pub light_client_update_responses: u32,
 }
 
 mod specimen_support {
@@ -464,6 +525,7 @@ mod specimen_support {
                         consensus_certificate: LargestSpecimen::largest_specimen(estimator, cache),
                         is_syncing: LargestSpecimen::largest_specimen(estimator, cache),
                         chainspec_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                        capabilities: LargestSpecimen::largest_specimen(estimator, cache),
                     },
                     MessageDiscriminants::Ping => Message::Ping {
                         nonce: LargestSpecimen::largest_specimen(estimator, cache),
@@ -768,6 +830,7 @@ mod tests {
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
             is_syncing: false,
             chainspec_hash: Some(Digest::hash("example-chainspec")),
+            capabilities: Capabilities::COMPRESSION,
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -802,6 +865,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            capabilities,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -809,7 +873,8 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(capabilities.is_empty());
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -826,6 +891,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            capabilities,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -834,7 +900,8 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(capabilities.is_empty());
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -851,6 +918,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            capabilities,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -878,7 +946,8 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(capabilities.is_empty());
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -895,6 +964,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            capabilities,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -922,7 +992,8 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(capabilities.is_empty());
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -971,4 +1042,17 @@ mod tests {
             "the size of the largest possible network message based on the production chainspec has changed"
         );
     }
+
+    #[test]
+    fn consensus_critical_messages_use_the_priority_channel() {
+        assert_eq!(MessageKind::Protocol.channel(), Channel::Priority);
+        assert_eq!(MessageKind::Consensus.channel(), Channel::Priority);
+        assert_eq!(
+            MessageKind::FinalitySignatureGossip.channel(),
+            Channel::Priority
+        );
+        assert_eq!(MessageKind::DeployGossip.channel(), Channel::Bulk);
+        assert_eq!(MessageKind::TrieTransfer.channel(), Channel::Bulk);
+        assert_eq!(MessageKind::Other.channel(), Channel::Bulk);
+    }
 }
@@ -3,8 +3,8 @@ use std::sync::Weak;
 use prometheus::{Counter, IntCounter, IntGauge, Registry};
 use tracing::debug;
 
-use super::{outgoing::OutgoingMetrics, MessageKind};
-use crate::unregister_metric;
+use super::{message::Channel, outgoing::OutgoingMetrics, traffic::TrafficStats, MessageKind};
+use crate::{types::NodeId, unregister_metric};
 
 /// Network-type agnostic networking metrics.
 #[derive(Debug)]
@@ -15,6 +15,12 @@ pub(super) struct Metrics {
     pub(super) direct_message_requests: IntCounter,
     /// Number of messages still waiting to be sent out (broadcast and direct).
     pub(super) queued_messages: IntGauge,
+    /// Number of messages still waiting to be sent out on the priority channel.
+    pub(super) queued_priority_messages: IntGauge,
+    /// Number of messages still waiting to be sent out to trusted validators of the overlay.
+    pub(super) queued_overlay_messages: IntGauge,
+    /// Number of messages still waiting to be sent out on the bulk channel.
+    pub(super) queued_bulk_messages: IntGauge,
     /// Number of connected peers.
     pub(super) peers: IntGauge,
 
@@ -66,6 +72,12 @@ pub(super) struct Metrics {
     pub(super) out_state_blocked: IntGauge,
     /// Number of outgoing connections in loopback state.
     pub(super) out_state_loopback: IntGauge,
+    /// Number of successful outgoing dials.
+    pub(super) out_dial_successes: IntCounter,
+    /// Number of failed outgoing dials.
+    pub(super) out_dial_failures: IntCounter,
+    /// Number of outgoing dials deferred due to the dial rate limit.
+    pub(super) out_dials_deferred: IntCounter,
 
     /// Volume in bytes of incoming messages that are protocol overhead.
     pub(super) in_bytes_protocol: IntCounter,
@@ -86,6 +98,15 @@ pub(super) struct Metrics {
     /// Volume in bytes of incoming messages with other payload.
     pub(super) in_bytes_other: IntCounter,
 
+    /// Volume in bytes of outgoing messages sent compressed, before compression.
+    pub(super) out_bytes_uncompressed: IntCounter,
+    /// Volume in bytes of outgoing messages sent compressed, after compression.
+    pub(super) out_bytes_compressed: IntCounter,
+    /// Volume in bytes of incoming messages received compressed, after decompression.
+    pub(super) in_bytes_uncompressed: IntCounter,
+    /// Volume in bytes of incoming messages received compressed, before decompression.
+    pub(super) in_bytes_compressed: IntCounter,
+
     /// Count of incoming messages that are protocol overhead.
     pub(super) in_count_protocol: IntCounter,
     /// Count of incoming messages with consensus payload.
@@ -115,6 +136,9 @@ pub(super) struct Metrics {
     /// Total time spent delaying incoming traffic from non-validators due to limiter, in seconds.
     pub(super) accumulated_incoming_limiter_delay: Counter,
 
+    /// Traffic exchanged with each peer.
+    pub(super) peer_traffic: TrafficStats,
+
     /// Registry instance.
     registry: Registry,
 }
@@ -132,6 +156,18 @@ impl Metrics {
             "net_queued_direct_messages",
             "number of messages waiting to be sent out",
         )?;
+        let queued_priority_messages = IntGauge::new(
+            "net_queued_priority_messages",
+            "number of consensus-critical messages waiting to be sent out on the priority channel",
+        )?;
+        let queued_overlay_messages = IntGauge::new(
+            "net_queued_overlay_messages",
+            "number of consensus-critical messages waiting to be sent out to trusted validators",
+        )?;
+        let queued_bulk_messages = IntGauge::new(
+            "net_queued_bulk_messages",
+            "number of messages waiting to be sent out on the bulk channel",
+        )?;
         let peers = IntGauge::new("peers", "number of connected peers")?;
 
         let out_count_protocol = IntCounter::new(
@@ -236,6 +272,18 @@ impl Metrics {
             "out_state_loopback",
             "number of connections in the loopback state",
         )?;
+        let out_dial_successes = IntCounter::new(
+            "out_dial_successes",
+            "number of outgoing connection attempts that succeeded",
+        )?;
+        let out_dial_failures = IntCounter::new(
+            "out_dial_failures",
+            "number of outgoing connection attempts that failed",
+        )?;
+        let out_dials_deferred = IntCounter::new(
+            "out_dials_deferred",
+            "number of outgoing connection attempts deferred due to the dial rate limit",
+        )?;
 
         let in_count_protocol = IntCounter::new(
             "net_in_count_protocol",
@@ -319,6 +367,23 @@ impl Metrics {
             "volume in bytes of incoming messages with other payload",
         )?;
 
+        let out_bytes_uncompressed = IntCounter::new(
+            "net_out_bytes_uncompressed",
+            "volume in bytes of outgoing messages sent compressed, before compression",
+        )?;
+        let out_bytes_compressed = IntCounter::new(
+            "net_out_bytes_compressed",
+            "volume in bytes of outgoing messages sent compressed, after compression",
+        )?;
+        let in_bytes_uncompressed = IntCounter::new(
+            "net_in_bytes_uncompressed",
+            "volume in bytes of incoming messages received compressed, after decompression",
+        )?;
+        let in_bytes_compressed = IntCounter::new(
+            "net_in_bytes_compressed",
+            "volume in bytes of incoming messages received compressed, before decompression",
+        )?;
+
         let requests_for_trie_accepted = IntCounter::new(
             "requests_for_trie_accepted",
             "number of trie requests accepted for processing",
@@ -340,6 +405,9 @@ impl Metrics {
         registry.register(Box::new(broadcast_requests.clone()))?;
         registry.register(Box::new(direct_message_requests.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
+        registry.register(Box::new(queued_priority_messages.clone()))?;
+        registry.register(Box::new(queued_overlay_messages.clone()))?;
+        registry.register(Box::new(queued_bulk_messages.clone()))?;
         registry.register(Box::new(peers.clone()))?;
 
         registry.register(Box::new(out_count_protocol.clone()))?;
@@ -369,6 +437,9 @@ impl Metrics {
         registry.register(Box::new(out_state_connected.clone()))?;
         registry.register(Box::new(out_state_blocked.clone()))?;
         registry.register(Box::new(out_state_loopback.clone()))?;
+        registry.register(Box::new(out_dial_successes.clone()))?;
+        registry.register(Box::new(out_dial_failures.clone()))?;
+        registry.register(Box::new(out_dials_deferred.clone()))?;
 
         registry.register(Box::new(in_count_protocol.clone()))?;
         registry.register(Box::new(in_count_consensus.clone()))?;
@@ -392,16 +463,28 @@ impl Metrics {
         registry.register(Box::new(in_bytes_trie_transfer.clone()))?;
         registry.register(Box::new(in_bytes_other.clone()))?;
 
+        registry.register(Box::new(out_bytes_uncompressed.clone()))?;
+        registry.register(Box::new(out_bytes_compressed.clone()))?;
+        registry.register(Box::new(in_bytes_uncompressed.clone()))?;
+        registry.register(Box::new(in_bytes_compressed.clone()))?;
+
         registry.register(Box::new(requests_for_trie_accepted.clone()))?;
         registry.register(Box::new(requests_for_trie_finished.clone()))?;
 
         registry.register(Box::new(accumulated_outgoing_limiter_delay.clone()))?;
         registry.register(Box::new(accumulated_incoming_limiter_delay.clone()))?;
 
+        let peer_traffic = TrafficStats::new()?;
+        registry.register(Box::new(peer_traffic.out_bytes_per_peer.clone()))?;
+        registry.register(Box::new(peer_traffic.in_bytes_per_peer.clone()))?;
+
         Ok(Metrics {
             broadcast_requests,
             direct_message_requests,
             queued_messages,
+            queued_priority_messages,
+            queued_overlay_messages,
+            queued_bulk_messages,
             peers,
             out_count_protocol,
             out_count_consensus,
@@ -428,6 +511,9 @@ impl Metrics {
             out_state_connected,
             out_state_blocked,
             out_state_loopback,
+            out_dial_successes,
+            out_dial_failures,
+            out_dials_deferred,
             in_count_protocol,
             in_count_consensus,
             in_count_deploy_gossip,
@@ -448,17 +534,36 @@ impl Metrics {
             in_bytes_block_transfer,
             in_bytes_trie_transfer,
             in_bytes_other,
+            out_bytes_uncompressed,
+            out_bytes_compressed,
+            in_bytes_uncompressed,
+            in_bytes_compressed,
             requests_for_trie_accepted,
             requests_for_trie_finished,
             accumulated_outgoing_limiter_delay,
             accumulated_incoming_limiter_delay,
+            peer_traffic,
             registry: registry.clone(),
         })
     }
 
+    /// Returns the gauge of messages waiting to be sent out on the given channel.
+    pub(super) fn queued_messages_on(&self, channel: Channel) -> &IntGauge {
+        match channel {
+            Channel::Priority => &self.queued_priority_messages,
+            Channel::Bulk => &self.queued_bulk_messages,
+        }
+    }
+
     /// Records an outgoing payload.
-    pub(crate) fn record_payload_out(this: &Weak<Self>, kind: MessageKind, size: u64) {
+    pub(crate) fn record_payload_out(
+        this: &Weak<Self>,
+        peer_id: NodeId,
+        kind: MessageKind,
+        size: u64,
+    ) {
         if let Some(metrics) = this.upgrade() {
+            metrics.peer_traffic.record_out(peer_id, kind, size);
             match kind {
                 MessageKind::Protocol => {
                     metrics.out_bytes_protocol.inc_by(size);
@@ -507,8 +612,14 @@ impl Metrics {
     }
 
     /// Records an incoming payload.
-    pub(crate) fn record_payload_in(this: &Weak<Self>, kind: MessageKind, size: u64) {
+    pub(crate) fn record_payload_in(
+        this: &Weak<Self>,
+        peer_id: NodeId,
+        kind: MessageKind,
+        size: u64,
+    ) {
         if let Some(metrics) = this.upgrade() {
+            metrics.peer_traffic.record_in(peer_id, kind, size);
             match kind {
                 MessageKind::Protocol => {
                     metrics.in_bytes_protocol.inc_by(size);
@@ -556,6 +667,26 @@ impl Metrics {
         }
     }
 
+    /// Records an outgoing message which was sent compressed.
+    pub(crate) fn record_compression_out(this: &Weak<Self>, uncompressed: u64, compressed: u64) {
+        if let Some(metrics) = this.upgrade() {
+            metrics.out_bytes_uncompressed.inc_by(uncompressed);
+            metrics.out_bytes_compressed.inc_by(compressed);
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records an incoming message which was received compressed.
+    pub(crate) fn record_compression_in(this: &Weak<Self>, uncompressed: u64, compressed: u64) {
+        if let Some(metrics) = this.upgrade() {
+            metrics.in_bytes_uncompressed.inc_by(uncompressed);
+            metrics.in_bytes_compressed.inc_by(compressed);
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
     /// Creates a set of outgoing metrics that is connected to this set of metrics.
     pub(super) fn create_outgoing_metrics(&self) -> OutgoingMetrics {
         OutgoingMetrics {
@@ -564,6 +695,9 @@ impl Metrics {
             out_state_connected: self.out_state_connected.clone(),
             out_state_blocked: self.out_state_blocked.clone(),
             out_state_loopback: self.out_state_loopback.clone(),
+            out_dial_successes: self.out_dial_successes.clone(),
+            out_dial_failures: self.out_dial_failures.clone(),
+            out_dials_deferred: self.out_dials_deferred.clone(),
         }
     }
 
@@ -591,6 +725,9 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.broadcast_requests);
         unregister_metric!(self.registry, self.direct_message_requests);
         unregister_metric!(self.registry, self.queued_messages);
+        unregister_metric!(self.registry, self.queued_priority_messages);
+        unregister_metric!(self.registry, self.queued_overlay_messages);
+        unregister_metric!(self.registry, self.queued_bulk_messages);
         unregister_metric!(self.registry, self.peers);
 
         unregister_metric!(self.registry, self.out_count_protocol);
@@ -620,6 +757,9 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.out_state_connected);
         unregister_metric!(self.registry, self.out_state_blocked);
         unregister_metric!(self.registry, self.out_state_loopback);
+        unregister_metric!(self.registry, self.out_dial_successes);
+        unregister_metric!(self.registry, self.out_dial_failures);
+        unregister_metric!(self.registry, self.out_dials_deferred);
 
         unregister_metric!(self.registry, self.in_count_protocol);
         unregister_metric!(self.registry, self.in_count_consensus);
@@ -643,10 +783,18 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.in_bytes_trie_transfer);
         unregister_metric!(self.registry, self.in_bytes_other);
 
+        unregister_metric!(self.registry, self.out_bytes_uncompressed);
+        unregister_metric!(self.registry, self.out_bytes_compressed);
+        unregister_metric!(self.registry, self.in_bytes_uncompressed);
+        unregister_metric!(self.registry, self.in_bytes_compressed);
+
         unregister_metric!(self.registry, self.requests_for_trie_accepted);
         unregister_metric!(self.registry, self.requests_for_trie_finished);
 
         unregister_metric!(self.registry, self.accumulated_outgoing_limiter_delay);
         unregister_metric!(self.registry, self.accumulated_incoming_limiter_delay);
+
+        unregister_metric!(self.registry, self.peer_traffic.out_bytes_per_peer);
+        unregister_metric!(self.registry, self.peer_traffic.in_bytes_per_peer);
     }
 }
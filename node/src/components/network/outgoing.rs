@@ -85,6 +85,16 @@
 //! Should a dial attempt exceed a certain timeout, it is considered failed and put into the waiting
 //! state again.
 //!
+//! # Backoff and dial rate
+//!
+//! The time spent waiting between reconnection attempts doubles with every failure, up to
+//! [`OutgoingConfig::max_backoff`], and is randomly varied by [`OutgoingConfig::backoff_jitter`] to
+//! avoid many nodes redialing an address in lockstep. Additionally, dials to newly learned
+//! addresses and reconnection attempts are subject to a global rate limit of
+//! [`OutgoingConfig::max_dial_rate`] per second. Dials exceeding it are deferred, newly learned
+//! addresses are put into the waiting state to be dialed during a later `perform_housekeeping`
+//! call. Reconnections of dropped connections and redeemed addresses are not limited.
+//!
 //! If a conflict (multiple successful dial results) occurs, the more recent connection takes
 //! precedence over the previous one. This prevents problems when a notification of a terminated
 //! connection is overtaken by the new connection announcement.
@@ -103,8 +113,8 @@ use std::{
 
 use datasize::DataSize;
 
-use prometheus::IntGauge;
-use rand::Rng;
+use prometheus::{IntCounter, IntGauge};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use tracing::{debug, error, error_span, field::Empty, info, trace, warn, Span};
 
 use super::{
@@ -125,6 +135,10 @@ where
     pub(super) is_unforgettable: bool,
     /// The current state the connection/address is in.
     pub(super) state: OutgoingState<H, E>,
+    /// Number of successful dials to the address.
+    pub(super) dial_successes: u64,
+    /// Number of failed dials to the address.
+    pub(super) dial_failures: u64,
 }
 
 /// Active state for a connection/address.
@@ -151,6 +165,8 @@ where
         error: Option<E>,
         /// The precise moment when the last connection attempt failed.
         last_failure: Instant,
+        /// Time to wait after the last failure before attempting to reconnect.
+        backoff: Duration,
     },
     /// An established outgoing connection.
     Connected {
@@ -297,15 +313,79 @@ pub struct OutgoingConfig {
     pub(crate) sweep_timeout: Duration,
     /// Health check configuration.
     pub(crate) health: HealthConfig,
+    /// Upper bound for the exponential backoff, before jitter is applied.
+    pub(crate) max_backoff: Duration,
+    /// Fraction, between 0.0 and 1.0, by which the backoff is randomly lengthened or shortened.
+    pub(crate) backoff_jitter: f32,
+    /// Maximum number of rate limited dials per second, unlimited if 0.
+    pub(crate) max_dial_rate: u32,
 }
 
 impl OutgoingConfig {
     /// Calculates the backoff time.
     ///
-    /// `failed_attempts` (n) is the number of previous attempts *before* the current failure (thus
-    /// starting at 0). The backoff time will be double for each attempt.
-    fn calc_backoff(&self, failed_attempts: u8) -> Duration {
-        2u32.pow(failed_attempts as u32) * self.base_timeout
+    /// `failed_attempts` (n) is the number of failed attempts, including the current failure. The
+    /// backoff time will be double for each attempt, capped at `max_backoff` and then randomly
+    /// varied by up to `backoff_jitter` in either direction.
+    fn calc_backoff<R: Rng>(&self, failed_attempts: u8, rng: &mut R) -> Duration {
+        let backoff = 2u32
+            .checked_pow(failed_attempts as u32)
+            .and_then(|factor| self.base_timeout.checked_mul(factor))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+
+        let jitter = self.backoff_jitter.min(1.0);
+        if jitter > 0.0 {
+            backoff.mul_f32(1.0 + rng.gen_range(-jitter..=jitter))
+        } else {
+            backoff
+        }
+    }
+}
+
+/// A token bucket limiting the number of dials started per second.
+#[derive(DataSize, Debug)]
+struct DialRateLimiter {
+    /// Maximum number of dials per second, also the bucket size. Unlimited if 0.
+    max_rate: u32,
+    /// Number of dials that can currently be started.
+    tokens: f64,
+    /// The last time tokens were replenished.
+    last_refill: Option<Instant>,
+}
+
+impl DialRateLimiter {
+    /// Creates a new rate limiter, with a full bucket.
+    fn new(max_rate: u32) -> Self {
+        DialRateLimiter {
+            max_rate,
+            tokens: max_rate as f64,
+            last_refill: None,
+        }
+    }
+
+    /// Takes a token for a dial, returning `false` if the dial should be deferred.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.max_rate == 0 {
+            return true;
+        }
+
+        match self.last_refill {
+            Some(last_refill) if now <= last_refill => {}
+            Some(last_refill) => {
+                let elapsed = (now - last_refill).as_secs_f64();
+                self.tokens =
+                    (self.tokens + elapsed * self.max_rate as f64).min(self.max_rate as f64);
+                self.last_refill = Some(now);
+            }
+            None => self.last_refill = Some(now),
+        }
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
     }
 }
 
@@ -327,6 +407,13 @@ where
     /// Contains a mapping from node IDs to connected socket addresses. A missing entry means that
     /// the destination is not connected.
     routes: HashMap<NodeId, SocketAddr>,
+    /// Rate limiter for dials.
+    dial_limiter: DialRateLimiter,
+    /// Source of randomness for backoff jitter.
+    ///
+    /// Kept separately, since dial outcomes are handled without access to the reactor's RNG.
+    #[data_size(skip)]
+    rng: StdRng,
     /// A set of outgoing metrics.
     #[data_size(skip)]
     metrics: OutgoingMetrics,
@@ -345,6 +432,12 @@ pub(super) struct OutgoingMetrics {
     pub(super) out_state_blocked: IntGauge,
     /// Number of outgoing connections in loopback state.
     pub(super) out_state_loopback: IntGauge,
+    /// Number of successful outgoing dials.
+    pub(super) out_dial_successes: IntCounter,
+    /// Number of failed outgoing dials.
+    pub(super) out_dial_failures: IntCounter,
+    /// Number of outgoing dials deferred due to the dial rate limit.
+    pub(super) out_dials_deferred: IntCounter,
 }
 
 // Note: We only implement `Default` here for use in testing with `OutgoingManager::new`.
@@ -367,6 +460,18 @@ impl Default for OutgoingMetrics {
             out_state_blocked: IntGauge::new("out_state_blocked", "internal out_state_blocked")
                 .unwrap(),
             out_state_loopback: IntGauge::new("out_state_loopback", "internal loopback").unwrap(),
+            out_dial_successes: IntCounter::new(
+                "out_dial_successes",
+                "internal out_dial_successes",
+            )
+            .unwrap(),
+            out_dial_failures: IntCounter::new("out_dial_failures", "internal out_dial_failures")
+                .unwrap(),
+            out_dials_deferred: IntCounter::new(
+                "out_dials_deferred",
+                "internal out_dials_deferred",
+            )
+            .unwrap(),
         }
     }
 }
@@ -386,9 +491,11 @@ where
     /// Creates a new outgoing manager with an already existing set of metrics.
     pub(super) fn with_metrics(config: OutgoingConfig, metrics: OutgoingMetrics) -> Self {
         Self {
+            dial_limiter: DialRateLimiter::new(config.max_dial_rate),
             config,
             outgoing: Default::default(),
             routes: Default::default(),
+            rng: StdRng::from_entropy(),
             metrics,
         }
     }
@@ -448,6 +555,8 @@ where
                 let inserted = vacant.insert(Outgoing {
                     state: new_state,
                     is_unforgettable: false,
+                    dial_successes: 0,
+                    dial_failures: 0,
                 });
 
                 (None, inserted)
@@ -562,19 +671,34 @@ where
                     None
                 }
                 Entry::Vacant(_vacant) => {
-                    info!("connecting to newly learned address");
-                    let (outgoing, _) = self.change_outgoing_state(
-                        addr,
+                    let can_dial = self.dial_limiter.try_acquire(now);
+                    let new_state = if can_dial {
+                        info!("connecting to newly learned address");
                         OutgoingState::Connecting {
                             failures_so_far: 0,
                             since: now,
-                        },
-                    );
+                        }
+                    } else {
+                        // Wait for the next housekeeping round to dial.
+                        info!("dial rate limit reached, deferring dial of newly learned address");
+                        self.metrics.out_dials_deferred.inc();
+                        OutgoingState::Waiting {
+                            failures_so_far: 0,
+                            error: None,
+                            last_failure: now,
+                            backoff: Duration::ZERO,
+                        }
+                    };
+                    let (outgoing, _) = self.change_outgoing_state(addr, new_state);
                     if outgoing.is_unforgettable != unforgettable {
                         outgoing.is_unforgettable = unforgettable;
                         debug!(unforgettable, "marked");
                     }
-                    Some(DialRequest::Dial { addr, span })
+                    if can_dial {
+                        Some(DialRequest::Dial { addr, span })
+                    } else {
+                        None
+                    }
                 }
             })
     }
@@ -653,8 +777,6 @@ where
     /// Removes an address from the block list.
     ///
     /// Does nothing if the address was not blocked.
-    // This function is currently not in use by `network` itself.
-    #[allow(dead_code)]
     pub(crate) fn redeem_addr(&mut self, addr: SocketAddr, now: Instant) -> Option<DialRequest<H>> {
         let span = make_span(addr, self.outgoing.get(&addr));
         span.clone()
@@ -727,6 +849,7 @@ where
                 OutgoingState::Waiting {
                     failures_so_far,
                     last_failure,
+                    backoff,
                     ..
                 } => {
                     if failures_so_far > self.config.retry_attempts {
@@ -744,7 +867,7 @@ where
                         }
                     } else {
                         // The address has not exceeded the limit, so check if it is due.
-                        let due = last_failure + self.config.calc_backoff(failures_so_far);
+                        let due = last_failure + backoff;
                         if now >= due {
                             debug!(attempts = failures_so_far, "address reconnecting");
 
@@ -813,16 +936,20 @@ where
         // Fail connections that are taking way too long to connect.
         to_fail.into_iter().for_each(|(addr, failures_so_far)| {
             let span = make_span(addr, self.outgoing.get(&addr));
+            let backoff = self.config.calc_backoff(failures_so_far, &mut self.rng);
 
             span.in_scope(|| {
-                self.change_outgoing_state(
+                let (outgoing, _) = self.change_outgoing_state(
                     addr,
                     OutgoingState::Waiting {
                         failures_so_far,
                         error: None,
                         last_failure: now,
+                        backoff,
                     },
-                )
+                );
+                outgoing.dial_failures += 1;
+                self.metrics.out_dial_failures.inc();
             });
         });
 
@@ -853,10 +980,16 @@ where
             dial_requests.push(DialRequest::Dial { addr, span });
         }
 
-        // Reconnect others.
-        dial_requests.extend(to_reconnect.into_iter().map(|(addr, failures_so_far)| {
+        // Reconnect others, deferring the dials exceeding the rate limit to the next call.
+        for (addr, failures_so_far) in to_reconnect {
             let span = make_span(addr, self.outgoing.get(&addr));
 
+            if !self.dial_limiter.try_acquire(now) {
+                span.in_scope(|| debug!("dial rate limit reached, deferring reconnection"));
+                self.metrics.out_dials_deferred.inc();
+                continue;
+            }
+
             span.clone().in_scope(|| {
                 self.change_outgoing_state(
                     addr,
@@ -867,8 +1000,8 @@ where
                 )
             });
 
-            DialRequest::Dial { addr, span }
-        }));
+            dial_requests.push(DialRequest::Dial { addr, span });
+        }
 
         // Finally, schedule pings.
         dial_requests.extend(to_ping.into_iter().map(|(peer_id, addr, nonce)| {
@@ -912,7 +1045,7 @@ where
                     })
                 } else {
                     // Otherwise, just record the connected state.
-                    self.change_outgoing_state(
+                    let (outgoing, _) = self.change_outgoing_state(
                         addr,
                         OutgoingState::Connected {
                             peer_id: node_id,
@@ -920,6 +1053,8 @@ where
                             health: ConnectionHealth::new(when),
                         },
                     );
+                    outgoing.dial_successes += 1;
+                    self.metrics.out_dial_successes.inc();
                     None
                 }
             }
@@ -930,14 +1065,19 @@ where
                 if let Some(outgoing) = self.outgoing.get(&addr) {
                     match outgoing.state {
                         OutgoingState::Connecting { failures_so_far,.. } => {
-                            self.change_outgoing_state(
+                            let failures_so_far = failures_so_far + 1;
+                            let backoff = self.config.calc_backoff(failures_so_far, &mut self.rng);
+                            let (outgoing, _) = self.change_outgoing_state(
                                 addr,
                                 OutgoingState::Waiting {
-                                    failures_so_far: failures_so_far + 1,
+                                    failures_so_far,
                                     error: Some(error),
                                     last_failure: when,
+                                    backoff,
                                 },
                             );
+                            outgoing.dial_failures += 1;
+                            self.metrics.out_dial_failures.inc();
                             None
                         }
                         OutgoingState::Blocked { .. } => {
@@ -1053,6 +1193,9 @@ mod tests {
             unblock_after: Duration::from_secs(60),
             sweep_timeout: Duration::from_secs(45),
             health: HealthConfig::test_config(),
+            max_backoff: Duration::from_secs(3600),
+            backoff_jitter: 0.0,
+            max_dial_rate: 0,
         }
     }
 
@@ -1825,4 +1968,91 @@ mod tests {
         assert!(!manager.record_pong(id, TaggedTimestamp::from_parts(clock.now(), rng.gen())));
         assert!(manager.record_pong(id, TaggedTimestamp::from_parts(clock.now(), rng.gen())));
     }
+
+    #[test]
+    fn defers_dials_exceeding_rate_limit() {
+        init_logging();
+
+        let mut rng = crate::new_rng();
+        let mut clock = TestClock::new();
+
+        let addrs: Vec<SocketAddr> = (1..=3)
+            .map(|port| SocketAddr::from(([1, 2, 3, 4], port)))
+            .collect();
+        let id = NodeId::random(&mut rng);
+
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(OutgoingConfig {
+            max_dial_rate: 2,
+            ..test_config()
+        });
+
+        // Only the first two addresses are dialed immediately, the third has to wait.
+        assert!(dials(
+            addrs[0],
+            &manager.learn_addr(addrs[0], false, clock.now())
+        ));
+        assert!(dials(
+            addrs[1],
+            &manager.learn_addr(addrs[1], false, clock.now())
+        ));
+        assert!(manager.learn_addr(addrs[2], false, clock.now()).is_none());
+        assert_eq!(manager.metrics().out_state_connecting.get(), 2);
+        assert_eq!(manager.metrics().out_state_waiting.get(), 1);
+        assert_eq!(manager.metrics().out_dials_deferred.get(), 1);
+
+        // Without time passing, no dial should be permitted during housekeeping either.
+        assert!(manager
+            .perform_housekeeping(&mut rng, clock.now())
+            .is_empty());
+        assert_eq!(manager.metrics().out_dials_deferred.get(), 2);
+
+        // Half a second replenishes a single dial.
+        clock.advance(Duration::from_millis(500));
+        assert!(dials(
+            addrs[2],
+            &manager.perform_housekeeping(&mut rng, clock.now())
+        ));
+        assert_eq!(manager.metrics().out_state_connecting.get(), 3);
+
+        // Dial outcomes are counted per address.
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Failed {
+                addr: addrs[0],
+                error: TestDialerError { id: 1 },
+                when: clock.now(),
+            })
+            .is_none());
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Successful {
+                addr: addrs[1],
+                handle: 1,
+                node_id: id,
+                when: clock.now(),
+            })
+            .is_none());
+        assert_eq!(manager.outgoing[&addrs[0]].dial_failures, 1);
+        assert_eq!(manager.outgoing[&addrs[0]].dial_successes, 0);
+        assert_eq!(manager.outgoing[&addrs[1]].dial_successes, 1);
+        assert_eq!(manager.metrics().out_dial_failures.get(), 1);
+        assert_eq!(manager.metrics().out_dial_successes.get(), 1);
+    }
+
+    #[test]
+    fn caps_and_jitters_backoff() {
+        let mut rng = crate::new_rng();
+        let config = OutgoingConfig {
+            max_backoff: Duration::from_secs(10),
+            backoff_jitter: 0.5,
+            ..test_config()
+        };
+
+        // Allow for rounding errors of the floating point jitter.
+        let tolerance = Duration::from_millis(1);
+        for failed_attempts in 0..=u8::MAX {
+            let capped = Duration::from_secs(2u64.saturating_pow(failed_attempts as u32).min(10));
+            let backoff = config.calc_backoff(failed_attempts, &mut rng);
+            assert!(backoff + tolerance >= capped / 2);
+            assert!(backoff <= capped * 3 / 2 + tolerance);
+        }
+    }
 }
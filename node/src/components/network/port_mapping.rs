@@ -0,0 +1,309 @@
+//! NAT traversal via port mapping.
+//!
+//! Nodes run behind a home router are usually not reachable by their peers, as the router does not
+//! forward incoming connections. If enabled, the node asks the router to forward its public port,
+//! trying UPnP IGD first and NAT-PMP (RFC 6886) second. The external address reported by the router
+//! is then advertised to peers in place of the configured public address.
+//!
+//! Mappings are requested with a limited lease, so they do not outlive the node for long; the
+//! networking component renews them periodically.
+
+use std::{
+    fs, io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use igd::{
+    aio::search_gateway, AddPortError, GetExternalIpError, PortMappingProtocol, SearchError,
+};
+use thiserror::Error;
+use tokio::{net::UdpSocket, time};
+use tracing::debug;
+
+use super::display_error;
+
+/// Duration for which a mapping is requested, it should be renewed well before.
+pub(super) const LEASE_DURATION: Duration = Duration::from_secs(3600);
+/// Timeout for discovering a UPnP gateway.
+const UPNP_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Description of the mapping, shown by routers listing their UPnP mappings.
+const UPNP_DESCRIPTION: &str = "casper-node";
+/// The port NAT-PMP gateways listen on.
+const NAT_PMP_PORT: u16 = 5351;
+/// The only NAT-PMP version.
+const NAT_PMP_VERSION: u8 = 0;
+/// NAT-PMP opcode to request the external address.
+const NAT_PMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+/// NAT-PMP opcode to request a TCP mapping.
+const NAT_PMP_OP_MAP_TCP: u8 = 2;
+/// Offset added by the gateway to the opcode of a request in its response.
+const NAT_PMP_RESPONSE_OFFSET: u8 = 128;
+/// Timeout for the first attempt of a NAT-PMP request, doubled on every retry.
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+/// Number of attempts made for each NAT-PMP request.
+const NAT_PMP_ATTEMPTS: usize = 4;
+
+/// Error mapping a port via UPnP.
+#[derive(Debug, Error)]
+pub(super) enum UpnpError {
+    /// No gateway was found.
+    #[error("failed to find gateway: {0}")]
+    Search(#[from] SearchError),
+    /// Failed to determine the local address facing the gateway.
+    #[error("failed to determine local address: {0}")]
+    LocalAddr(#[source] io::Error),
+    /// The gateway is only reachable via IPv6, which UPnP IGD does not support port mapping for.
+    #[error("gateway {0} is not reachable via IPv4")]
+    NotIpv4(SocketAddr),
+    /// The gateway refused the mapping.
+    #[error("failed to add mapping: {0}")]
+    AddPort(#[from] AddPortError),
+    /// The gateway did not report its external address.
+    #[error("failed to get external address: {0}")]
+    ExternalIp(#[from] GetExternalIpError),
+}
+
+/// Error mapping a port via NAT-PMP.
+#[derive(Debug, Error)]
+pub(super) enum NatPmpError {
+    /// The default gateway could not be determined.
+    #[error("no default gateway found")]
+    NoGateway,
+    /// Communicating with the gateway failed.
+    #[error("failed to communicate with gateway: {0}")]
+    Io(#[from] io::Error),
+    /// The gateway did not respond.
+    #[error("gateway did not respond")]
+    Timeout,
+    /// The gateway sent a malformed response.
+    #[error("invalid response from gateway")]
+    InvalidResponse,
+    /// The gateway refused the request.
+    #[error("gateway refused request with result code {0}")]
+    Refused(u16),
+}
+
+/// Error mapping a port with either method.
+#[derive(Debug, Error)]
+#[error("UPnP: {upnp}, NAT-PMP: {nat_pmp}")]
+pub(crate) struct PortMappingError {
+    upnp: UpnpError,
+    nat_pmp: NatPmpError,
+}
+
+/// A port to map on the local router.
+#[derive(Clone, Copy, DataSize, Debug)]
+pub(super) struct PortMapping {
+    /// The port the node is listening on.
+    local_port: u16,
+    /// The port to request on the router.
+    external_port: u16,
+}
+
+impl PortMapping {
+    /// Creates a new port mapping, forwarding `external_port` on the router to `local_port`.
+    pub(super) fn new(local_port: u16, external_port: u16) -> Self {
+        PortMapping {
+            local_port,
+            external_port,
+        }
+    }
+
+    /// Requests the mapping, returning the resulting external address.
+    pub(super) async fn map(self) -> Result<SocketAddr, PortMappingError> {
+        let upnp = match self.map_upnp().await {
+            Ok(external_addr) => return Ok(external_addr),
+            Err(error) => {
+                debug!(err = display_error(&error), "UPnP port mapping failed");
+                error
+            }
+        };
+
+        let gateway = match default_gateway() {
+            Some(gateway) => SocketAddr::from((gateway, NAT_PMP_PORT)),
+            None => {
+                return Err(PortMappingError {
+                    upnp,
+                    nat_pmp: NatPmpError::NoGateway,
+                })
+            }
+        };
+        self.map_nat_pmp(gateway)
+            .await
+            .map_err(|nat_pmp| PortMappingError { upnp, nat_pmp })
+    }
+
+    /// Requests the mapping from a UPnP gateway found on the local network.
+    async fn map_upnp(self) -> Result<SocketAddr, UpnpError> {
+        let gateway = search_gateway(igd::SearchOptions {
+            timeout: Some(UPNP_SEARCH_TIMEOUT),
+            ..Default::default()
+        })
+        .await?;
+        let gateway_addr = SocketAddr::from(gateway.addr);
+
+        // The gateway needs our address on the local network to forward connections to.
+        let local_ip = match local_addr_towards(gateway_addr)
+            .await
+            .map_err(UpnpError::LocalAddr)?
+        {
+            SocketAddr::V4(local_addr) => *local_addr.ip(),
+            SocketAddr::V6(_) => return Err(UpnpError::NotIpv4(gateway_addr)),
+        };
+
+        gateway
+            .add_port(
+                PortMappingProtocol::TCP,
+                self.external_port,
+                SocketAddrV4::new(local_ip, self.local_port),
+                LEASE_DURATION.as_secs() as u32,
+                UPNP_DESCRIPTION,
+            )
+            .await?;
+        let external_ip = gateway.get_external_ip().await?;
+
+        Ok(SocketAddr::from((external_ip, self.external_port)))
+    }
+
+    /// Requests the mapping from the NAT-PMP gateway at the given address.
+    async fn map_nat_pmp(self, gateway: SocketAddr) -> Result<SocketAddr, NatPmpError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.connect(gateway).await?;
+
+        let response =
+            nat_pmp_request(&socket, &[NAT_PMP_VERSION, NAT_PMP_OP_EXTERNAL_ADDRESS], 12).await?;
+        let external_ip = Ipv4Addr::new(response[8], response[9], response[10], response[11]);
+
+        let mut request = [0; 12];
+        request[0] = NAT_PMP_VERSION;
+        request[1] = NAT_PMP_OP_MAP_TCP;
+        request[4..6].copy_from_slice(&self.local_port.to_be_bytes());
+        request[6..8].copy_from_slice(&self.external_port.to_be_bytes());
+        request[8..12].copy_from_slice(&(LEASE_DURATION.as_secs() as u32).to_be_bytes());
+        let response = nat_pmp_request(&socket, &request, 16).await?;
+        // The gateway may assign a different port than the one we asked for.
+        let external_port = u16::from_be_bytes([response[10], response[11]]);
+
+        Ok(SocketAddr::from((external_ip, external_port)))
+    }
+}
+
+/// Returns the local address used to communicate with the given remote address.
+async fn local_addr_towards(remote_addr: SocketAddr) -> io::Result<SocketAddr> {
+    let bind_addr: SocketAddr = match remote_addr {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    // Connecting a UDP socket sends no packets, but selects the outgoing interface.
+    let socket = UdpSocket::bind(bind_addr).await?;
+    socket.connect(remote_addr).await?;
+    socket.local_addr()
+}
+
+/// Sends a NAT-PMP request on a connected socket, retrying until a response of at least
+/// `response_len` bytes with a successful result code is received.
+async fn nat_pmp_request(
+    socket: &UdpSocket,
+    request: &[u8],
+    response_len: usize,
+) -> Result<[u8; 16], NatPmpError> {
+    let mut response = [0; 16];
+    let mut timeout = NAT_PMP_INITIAL_TIMEOUT;
+    for _ in 0..NAT_PMP_ATTEMPTS {
+        socket.send(request).await?;
+        let received = match time::timeout(timeout, socket.recv(&mut response)).await {
+            Ok(received) => received?,
+            Err(_elapsed) => {
+                timeout *= 2;
+                continue;
+            }
+        };
+
+        if received < 4
+            || response[0] != NAT_PMP_VERSION
+            || response[1] != request[1] + NAT_PMP_RESPONSE_OFFSET
+        {
+            return Err(NatPmpError::InvalidResponse);
+        }
+        let result_code = u16::from_be_bytes([response[2], response[3]]);
+        if result_code != 0 {
+            return Err(NatPmpError::Refused(result_code));
+        }
+        if received < response_len {
+            return Err(NatPmpError::InvalidResponse);
+        }
+        return Ok(response);
+    }
+    Err(NatPmpError::Timeout)
+}
+
+/// Returns the IPv4 default gateway, as found in the kernel's routing table.
+fn default_gateway() -> Option<Ipv4Addr> {
+    parse_default_gateway(&fs::read_to_string("/proc/net/route").ok()?)
+}
+
+/// Parses the default gateway from the contents of `/proc/net/route`.
+///
+/// The default route has a destination of `0.0.0.0`, addresses are given as hexadecimal numbers in
+/// host byte order.
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        if fields.next()? != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_le_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_default_gateway() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+";
+        assert_eq!(
+            parse_default_gateway(routes),
+            Some(Ipv4Addr::new(192, 168, 2, 1))
+        );
+        assert_eq!(parse_default_gateway(routes.lines().next().unwrap()), None);
+    }
+
+    #[tokio::test]
+    async fn should_map_port_via_nat_pmp() {
+        let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+
+        let mapping = tokio::spawn(PortMapping::new(34553, 35000).map_nat_pmp(gateway_addr));
+
+        // Answer the external address request.
+        let mut request = [0; 12];
+        let (len, node_addr) = gateway.recv_from(&mut request).await.unwrap();
+        assert_eq!(&request[..len], &[0, 0]);
+        let response = [0, 128, 0, 0, 0, 0, 0, 1, 12, 34, 56, 78];
+        gateway.send_to(&response, node_addr).await.unwrap();
+
+        // Answer the mapping request, assigning a different external port than requested.
+        let (len, node_addr) = gateway.recv_from(&mut request).await.unwrap();
+        assert_eq!(len, 12);
+        assert_eq!(request[..2], [0, 2]);
+        assert_eq!(u16::from_be_bytes([request[4], request[5]]), 34553);
+        assert_eq!(u16::from_be_bytes([request[6], request[7]]), 35000);
+        let mut response = [0, 130, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        response[8..10].copy_from_slice(&34553u16.to_be_bytes());
+        response[10..12].copy_from_slice(&35001u16.to_be_bytes());
+        response[12..16].copy_from_slice(&request[8..12]);
+        gateway.send_to(&response, node_addr).await.unwrap();
+
+        let external_addr = mapping.await.unwrap().unwrap();
+        assert_eq!(external_addr, SocketAddr::from(([12, 34, 56, 78], 35001)));
+    }
+}
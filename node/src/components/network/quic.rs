@@ -0,0 +1,477 @@
+//! QUIC transport.
+//!
+//! An alternative to TCP with TLS for connections between nodes, used if enabled in the config and
+//! supported by the peer.
+//!
+//! QUIC mandates a TLS 1.3 handshake, which cannot be performed with the secp521r1 keys of node
+//! certificates.  Each endpoint therefore presents an ephemeral certificate during the handshake,
+//! which is not checked.  Instead, once the connection is established, both sides send their node
+//! certificate along with a signature over keying material exported from the connection's TLS
+//! session.  As the keying material is unique to the connection, this binds the node certificate
+//! to it the same way the TLS handshake does for TCP connections.  The dialer and the listener
+//! export the keying material with different contexts, so that a peer cannot pass off the
+//! identity it received as its own by sending it back.
+//!
+//! All traffic of a connection goes through a single bidirectional stream, opened by the dialer.
+
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use openssl::{
+    asn1::Asn1Time,
+    bn::{BigNum, MsbOption},
+    ec::{EcGroup, EcKey},
+    error::ErrorStack,
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    sign::{Signer, Verifier},
+    x509::{X509Builder, X509NameBuilder, X509},
+};
+use pin_project::pin_project;
+use quinn::{Connecting, Connection, Endpoint, RecvStream, SendStream, TransportConfig};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, PrivateKey, ServerName,
+};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Protocol negotiated via ALPN, rejecting connections by other QUIC applications early.
+const ALPN_PROTOCOL: &[u8] = b"casper-node";
+
+/// Server name sent when connecting, which is not checked.
+const SERVER_NAME: &str = "this-will-not-be-checked.example.com";
+
+/// Label of the keying material signed by both sides to prove ownership of their certificates.
+const IDENTITY_LABEL: &[u8] = b"EXPORTER-casper-node-quic-identity";
+
+/// Context of the keying material signed by the side which initiated the connection.
+const DIALER_IDENTITY_CONTEXT: &[u8] = b"dialer";
+
+/// Context of the keying material signed by the side which accepted the connection.
+const LISTENER_IDENTITY_CONTEXT: &[u8] = b"listener";
+
+/// Length of the keying material signed to prove ownership of a certificate.
+const IDENTITY_MATERIAL_LENGTH: usize = 64;
+
+/// Maximum size of a certificate or signature sent by a peer.
+const MAX_IDENTITY_FIELD_SIZE: usize = 16 * 1024;
+
+/// Interval of keep-alive packets, preventing idle connections from timing out.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of days the ephemeral certificate is valid for.
+const EPHEMERAL_CERT_VALIDITY_DAYS: u32 = 3650;
+
+/// An error setting up a QUIC endpoint or connection.
+#[derive(Debug, Error)]
+pub enum QuicError {
+    /// Failed to generate the ephemeral certificate.
+    #[error("failed to generate ephemeral certificate")]
+    EphemeralCertificate(#[source] ErrorStack),
+    /// Failed to configure TLS.
+    #[error("failed to configure TLS")]
+    TlsConfiguration(#[source] rustls::Error),
+    /// Failed to bind the UDP socket.
+    #[error("failed to bind UDP socket")]
+    Bind(#[source] io::Error),
+    /// Failed to initiate a connection.
+    #[error("failed to connect")]
+    Connect(#[from] quinn::ConnectError),
+    /// The connection failed or was closed.
+    #[error("connection failed")]
+    Connection(#[from] quinn::ConnectionError),
+    /// Failed to send our identity.
+    #[error("failed to send identity")]
+    SendIdentity(#[from] quinn::WriteError),
+    /// Failed to receive the peer's identity.
+    #[error("failed to receive identity")]
+    ReceiveIdentity(#[from] quinn::ReadExactError),
+    /// Failed to export keying material from the TLS session.
+    #[error("failed to export keying material")]
+    ExportKeyingMaterial,
+    /// The peer sent a certificate or signature exceeding the size limit.
+    #[error("identity field of {0} bytes exceeds size limit")]
+    IdentityTooLarge(usize),
+    /// Failed to sign our identity, or to decode or verify the peer's.
+    #[error("failed to sign or verify identity")]
+    Identity(#[source] ErrorStack),
+    /// The peer's signature over the keying material is invalid.
+    #[error("invalid identity signature")]
+    InvalidIdentitySignature,
+}
+
+/// Creates an endpoint accepting and initiating QUIC connections on the UDP socket `bind_addr`.
+///
+/// Must be called from within a tokio runtime.
+pub(super) fn create_endpoint(bind_addr: SocketAddr) -> Result<Endpoint, QuicError> {
+    let (cert, private_key) = generate_ephemeral_cert().map_err(QuicError::EphemeralCertificate)?;
+
+    let mut transport_config = TransportConfig::default();
+    transport_config.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    let transport_config = Arc::new(transport_config);
+
+    let mut server_crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], private_key)
+        .map_err(QuicError::TlsConfiguration)?;
+    server_crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(server_crypto));
+    server_config.transport_config(transport_config.clone());
+
+    let mut client_crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(SkipCertificateVerification))
+        .with_no_client_auth();
+    client_crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    let mut client_config = quinn::ClientConfig::new(Arc::new(client_crypto));
+    client_config.transport_config(transport_config);
+
+    let mut endpoint = Endpoint::server(server_config, bind_addr).map_err(QuicError::Bind)?;
+    endpoint.set_default_client_config(client_config);
+    Ok(endpoint)
+}
+
+/// Connects to `peer_addr`, returning the peer's node certificate and the connection's stream.
+///
+/// The returned certificate has proven to be owned by the peer, but must still be validated.
+pub(super) async fn connect(
+    endpoint: &Endpoint,
+    peer_addr: SocketAddr,
+    our_cert: &X509,
+    secret_key: &PKey<Private>,
+) -> Result<(X509, QuicStream), QuicError> {
+    let connection = endpoint.connect(peer_addr, SERVER_NAME)?.await?;
+    let (mut send, mut recv) = connection.open_bi().await?;
+    let peer_cert = exchange_identities(
+        &connection,
+        &mut send,
+        &mut recv,
+        our_cert,
+        secret_key,
+        Role::Dialer,
+    )
+    .await?;
+    Ok((
+        peer_cert,
+        QuicStream {
+            send,
+            recv,
+            connection,
+        },
+    ))
+}
+
+/// Accepts an incoming connection, returning the peer's node certificate and the connection's
+/// stream.
+///
+/// The returned certificate has proven to be owned by the peer, but must still be validated.
+pub(super) async fn accept(
+    connecting: Connecting,
+    our_cert: &X509,
+    secret_key: &PKey<Private>,
+) -> Result<(X509, QuicStream), QuicError> {
+    let connection = connecting.await?;
+    let (mut send, mut recv) = connection.accept_bi().await?;
+    let peer_cert = exchange_identities(
+        &connection,
+        &mut send,
+        &mut recv,
+        our_cert,
+        secret_key,
+        Role::Listener,
+    )
+    .await?;
+    Ok((
+        peer_cert,
+        QuicStream {
+            send,
+            recv,
+            connection,
+        },
+    ))
+}
+
+/// The bidirectional stream of a QUIC connection.
+///
+/// The connection is closed once the stream is dropped.
+#[pin_project]
+pub(crate) struct QuicStream {
+    #[pin]
+    send: SendStream,
+    #[pin]
+    recv: RecvStream,
+    connection: Connection,
+}
+
+impl QuicStream {
+    /// Returns the underlying connection.
+    pub(super) fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.project().recv.poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        AsyncWrite::poll_write(self.project().send, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_flush(self.project().send, cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_shutdown(self.project().send, cx)
+    }
+}
+
+/// Our side of a connection.
+#[derive(Clone, Copy, Debug)]
+enum Role {
+    /// We initiated the connection.
+    Dialer,
+    /// We accepted the connection.
+    Listener,
+}
+
+impl Role {
+    /// Returns the role of the peer.
+    fn peer(self) -> Self {
+        match self {
+            Role::Dialer => Role::Listener,
+            Role::Listener => Role::Dialer,
+        }
+    }
+
+    /// Returns the context of the keying material signed by the side with this role.
+    fn identity_context(self) -> &'static [u8] {
+        match self {
+            Role::Dialer => DIALER_IDENTITY_CONTEXT,
+            Role::Listener => LISTENER_IDENTITY_CONTEXT,
+        }
+    }
+}
+
+/// Sends our node certificate and a signature proving we own it, and receives and checks the
+/// peer's in turn.
+async fn exchange_identities(
+    connection: &Connection,
+    send: &mut SendStream,
+    recv: &mut RecvStream,
+    our_cert: &X509,
+    secret_key: &PKey<Private>,
+    our_role: Role,
+) -> Result<X509, QuicError> {
+    let our_keying_material = identity_keying_material(connection, our_role)?;
+    let peer_keying_material = identity_keying_material(connection, our_role.peer())?;
+
+    let our_cert_der = our_cert.to_der().map_err(QuicError::Identity)?;
+    let mut signer =
+        Signer::new(MessageDigest::sha512(), secret_key).map_err(QuicError::Identity)?;
+    let our_signature = signer
+        .sign_oneshot_to_vec(&our_keying_material)
+        .map_err(QuicError::Identity)?;
+    write_field(send, &our_cert_der).await?;
+    write_field(send, &our_signature).await?;
+
+    let peer_cert_der = read_field(recv).await?;
+    let peer_signature = read_field(recv).await?;
+    let peer_cert = X509::from_der(&peer_cert_der).map_err(QuicError::Identity)?;
+    let peer_public_key = peer_cert.public_key().map_err(QuicError::Identity)?;
+    let mut verifier =
+        Verifier::new(MessageDigest::sha512(), &peer_public_key).map_err(QuicError::Identity)?;
+    if !verifier
+        .verify_oneshot(&peer_signature, &peer_keying_material)
+        .map_err(QuicError::Identity)?
+    {
+        return Err(QuicError::InvalidIdentitySignature);
+    }
+
+    Ok(peer_cert)
+}
+
+/// Exports the keying material to be signed by the side of `connection` with the given role.
+fn identity_keying_material(
+    connection: &Connection,
+    role: Role,
+) -> Result<[u8; IDENTITY_MATERIAL_LENGTH], QuicError> {
+    let mut keying_material = [0; IDENTITY_MATERIAL_LENGTH];
+    connection
+        .export_keying_material(
+            &mut keying_material,
+            IDENTITY_LABEL,
+            role.identity_context(),
+        )
+        .map_err(|_| QuicError::ExportKeyingMaterial)?;
+    Ok(keying_material)
+}
+
+/// Writes a length-prefixed field to `send`.
+async fn write_field(send: &mut SendStream, field: &[u8]) -> Result<(), QuicError> {
+    // Our own certificate and signature are far below the limit.
+    let length = field.len() as u32;
+    send.write_all(&length.to_be_bytes()).await?;
+    send.write_all(field).await?;
+    Ok(())
+}
+
+/// Reads a length-prefixed field from `recv`.
+async fn read_field(recv: &mut RecvStream) -> Result<Vec<u8>, QuicError> {
+    let mut length = [0; 4];
+    recv.read_exact(&mut length).await?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_IDENTITY_FIELD_SIZE {
+        return Err(QuicError::IdentityTooLarge(length));
+    }
+    let mut field = vec![0; length];
+    recv.read_exact(&mut field).await?;
+    Ok(field)
+}
+
+/// Generates a self-signed certificate for the QUIC handshake, with a fresh P-256 key.
+fn generate_ephemeral_cert() -> Result<(Certificate, PrivateKey), ErrorStack> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    let private_key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_text("CN", "casper-node")?;
+    let name = name.build();
+
+    let mut serial_number = BigNum::new()?;
+    serial_number.rand(127, MsbOption::MAYBE_ZERO, false)?;
+
+    let serial_number = serial_number.to_asn1_integer()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(EPHEMERAL_CERT_VALIDITY_DAYS)?;
+
+    let mut builder = X509Builder::new()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(&serial_number)?;
+    builder.set_subject_name(&name)?;
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(&private_key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    builder.sign(&private_key, MessageDigest::sha256())?;
+    let cert = builder.build();
+
+    Ok((
+        Certificate(cert.to_der()?),
+        PrivateKey(private_key.private_key_to_pkcs8()?),
+    ))
+}
+
+/// Accepts any certificate presented during the QUIC handshake.
+///
+/// Peers are authenticated by the identity exchange following the handshake instead.
+struct SkipCertificateVerification;
+
+impl ServerCertVerifier for SkipCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::{SinkExt, StreamExt};
+    use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+    use super::*;
+    use crate::tls;
+
+    #[tokio::test]
+    async fn should_exchange_node_certificates_and_messages() {
+        let (dialer_cert, dialer_key) = tls::generate_node_cert().unwrap();
+        let (listener_cert, listener_key) = tls::generate_node_cert().unwrap();
+        let dialer = create_endpoint(([127, 0, 0, 1], 0).into()).unwrap();
+        let listener = create_endpoint(([127, 0, 0, 1], 0).into()).unwrap();
+        let listener_addr = listener.local_addr().unwrap();
+
+        let expected_listener_cert = listener_cert.to_der().unwrap();
+        let accepting = tokio::spawn(async move {
+            let connecting = listener.accept().await.unwrap();
+            let (peer_cert, stream) = accept(connecting, &listener_cert, &listener_key)
+                .await
+                .unwrap();
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+            let message = framed.next().await.unwrap().unwrap();
+            framed.send(message.freeze()).await.unwrap();
+            // Keep the connection open until the dialer closes it.
+            assert!(framed.next().await.is_none());
+            peer_cert
+        });
+
+        let (peer_cert, stream) = connect(&dialer, listener_addr, &dialer_cert, &dialer_key)
+            .await
+            .unwrap();
+        assert_eq!(peer_cert.to_der().unwrap(), expected_listener_cert);
+
+        let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+        framed.send(Bytes::from_static(b"ping")).await.unwrap();
+        let echoed = framed.next().await.unwrap().unwrap();
+        assert_eq!(&echoed[..], b"ping");
+        framed.close().await.unwrap();
+
+        // The connection is closed once `framed` is dropped, so wait for the listener first.
+        let peer_cert = accepting.await.unwrap();
+        assert_eq!(peer_cert.to_der().unwrap(), dialer_cert.to_der().unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_reject_reflected_identity() {
+        let (dialer_cert, dialer_key) = tls::generate_node_cert().unwrap();
+        let dialer = create_endpoint(([127, 0, 0, 1], 0).into()).unwrap();
+        let listener = create_endpoint(([127, 0, 0, 1], 0).into()).unwrap();
+        let listener_addr = listener.local_addr().unwrap();
+
+        // The listener sends the dialer's own certificate and signature back to it.
+        let reflecting = tokio::spawn(async move {
+            let connection = listener.accept().await.unwrap().await.unwrap();
+            let (mut send, mut recv) = connection.accept_bi().await.unwrap();
+            let dialer_cert = read_field(&mut recv).await.unwrap();
+            let dialer_signature = read_field(&mut recv).await.unwrap();
+            write_field(&mut send, &dialer_cert).await.unwrap();
+            write_field(&mut send, &dialer_signature).await.unwrap();
+            // Keep the connection open until the dialer closes it.
+            connection.closed().await;
+        });
+
+        let result = connect(&dialer, listener_addr, &dialer_cert, &dialer_key).await;
+        assert!(matches!(result, Err(QuicError::InvalidIdentitySignature)));
+        drop(result);
+        dialer.close(0u32.into(), b"");
+        reflecting.await.unwrap();
+    }
+}
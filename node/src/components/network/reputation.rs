@@ -0,0 +1,326 @@
+//! Peer reputation tracking.
+//!
+//! Accumulates penalties for the offenses peers are reported to have committed, and keeps track of
+//! peers banned by node ID. Unlike the address-based block list of the outgoing manager, bans are
+//! persisted to disk so that they survive a restart of the node.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use casper_types::Timestamp;
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::blocklist::BlocklistJustification;
+use crate::types::NodeId;
+
+/// Name of the file bans are persisted to.
+const BANS_FILENAME: &str = "banned_peers";
+
+/// A ban of a peer.
+#[derive(Clone, Debug, DataSize, Serialize, Deserialize)]
+pub(crate) struct Ban {
+    /// The time at which the ban expires.
+    pub(crate) until: Timestamp,
+    /// Why the peer was banned.
+    pub(crate) reason: String,
+    /// The last known outgoing address of the peer, blocked by the outgoing manager.
+    pub(crate) addr: Option<SocketAddr>,
+}
+
+/// The reputation of a single peer.
+#[derive(Clone, Debug, DataSize, Serialize)]
+pub(crate) struct PeerReputation {
+    /// The ID of the peer.
+    pub(crate) peer_id: NodeId,
+    /// Accumulated penalty for the offenses committed by the peer.
+    pub(crate) penalty: u64,
+    /// Number of offenses committed by the peer.
+    pub(crate) offenses: u64,
+    /// The current ban of the peer, if any.
+    pub(crate) ban: Option<Ban>,
+}
+
+impl Display for PeerReputation {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?}: penalty {} for {} offenses",
+            self.peer_id, self.penalty, self.offenses
+        )?;
+        if let Some(ban) = &self.ban {
+            write!(formatter, ", banned until {}: {}", ban.until, ban.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl PeerReputation {
+    fn new(peer_id: NodeId) -> Self {
+        PeerReputation {
+            peer_id,
+            penalty: 0,
+            offenses: 0,
+            ban: None,
+        }
+    }
+}
+
+/// Reputations of peers, persisting their bans to a file if a storage path is given.
+#[derive(Debug, DataSize)]
+pub(super) struct ReputationStore {
+    peers: BTreeMap<NodeId, PeerReputation>,
+    path: Option<PathBuf>,
+}
+
+impl ReputationStore {
+    /// Creates a new store, restoring the unexpired bans persisted under `storage_path`.
+    pub(super) fn new(storage_path: Option<&Path>) -> Self {
+        let mut store = ReputationStore {
+            peers: BTreeMap::new(),
+            path: storage_path.map(|storage_path| storage_path.join(BANS_FILENAME)),
+        };
+        let now = Timestamp::now();
+        for (peer_id, ban) in store.load() {
+            if ban.until > now {
+                info!(%peer_id, until = %ban.until, reason = %ban.reason, "restored peer ban");
+                store.entry(peer_id).ban = Some(ban);
+            }
+        }
+        store
+    }
+
+    /// Records an offense committed by the given peer, banning it until `until`.
+    pub(super) fn record_offense(
+        &mut self,
+        peer_id: NodeId,
+        justification: &BlocklistJustification,
+        addr: Option<SocketAddr>,
+        until: Timestamp,
+    ) {
+        let reputation = self.entry(peer_id);
+        reputation.penalty = reputation.penalty.saturating_add(justification.penalty());
+        reputation.offenses += 1;
+        let _ = self.ban(peer_id, until, justification.to_string(), addr);
+    }
+
+    /// Bans the given peer until `until`, returning the time at which the ban expires.
+    ///
+    /// An existing ban is extended, but never shortened.
+    pub(super) fn ban(
+        &mut self,
+        peer_id: NodeId,
+        until: Timestamp,
+        reason: String,
+        addr: Option<SocketAddr>,
+    ) -> Timestamp {
+        let reputation = self.entry(peer_id);
+        let until = match reputation.ban {
+            Some(ref ban) => ban.until.max(until),
+            None => until,
+        };
+        let addr = addr.or_else(|| reputation.ban.as_ref().and_then(|ban| ban.addr));
+        reputation.ban = Some(Ban {
+            until,
+            reason,
+            addr,
+        });
+        self.persist();
+        until
+    }
+
+    /// Lifts the ban of the given peer, returning it if the peer was banned.
+    pub(super) fn unban(&mut self, peer_id: NodeId) -> Option<Ban> {
+        let ban = self.peers.get_mut(&peer_id)?.ban.take()?;
+        self.persist();
+        Some(ban)
+    }
+
+    /// Returns whether the given peer is currently banned.
+    pub(super) fn is_banned(&self, peer_id: NodeId, now: Timestamp) -> bool {
+        self.peers
+            .get(&peer_id)
+            .and_then(|reputation| reputation.ban.as_ref())
+            .map_or(false, |ban| ban.until > now)
+    }
+
+    /// Records the outgoing address of a banned peer, so that it can be unblocked once the ban is
+    /// lifted.
+    pub(super) fn set_banned_addr(&mut self, peer_id: NodeId, addr: SocketAddr) {
+        if let Some(ban) = self
+            .peers
+            .get_mut(&peer_id)
+            .and_then(|reputation| reputation.ban.as_mut())
+        {
+            ban.addr = Some(addr);
+        }
+    }
+
+    /// Removes all bans which have expired.
+    pub(super) fn purge_expired_bans(&mut self, now: Timestamp) {
+        let mut purged = false;
+        for reputation in self.peers.values_mut() {
+            if reputation
+                .ban
+                .as_ref()
+                .map_or(false, |ban| ban.until <= now)
+            {
+                debug!(peer_id = %reputation.peer_id, "peer ban expired");
+                reputation.ban = None;
+                purged = true;
+            }
+        }
+        if purged {
+            self.persist();
+        }
+    }
+
+    /// Returns the reputations of all peers which committed offenses or were banned, worst first.
+    pub(super) fn reputations(&self) -> Vec<PeerReputation> {
+        let mut reputations: Vec<_> = self.peers.values().cloned().collect();
+        reputations.sort_by(|a, b| b.penalty.cmp(&a.penalty));
+        reputations
+    }
+
+    fn entry(&mut self, peer_id: NodeId) -> &mut PeerReputation {
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerReputation::new(peer_id))
+    }
+
+    /// Reads the persisted bans, if any.
+    fn load(&self) -> Vec<(NodeId, Ban)> {
+        let path = match self.path {
+            Some(ref path) if path.exists() => path,
+            _ => return vec![],
+        };
+        let result = fs::read(path)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| bincode::deserialize(&bytes).map_err(|error| error.to_string()));
+        match result {
+            Ok(bans) => bans,
+            Err(error) => {
+                warn!(file = %path.display(), %error, "failed to read persisted peer bans");
+                vec![]
+            }
+        }
+    }
+
+    /// Writes all current bans to disk.
+    fn persist(&self) {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return,
+        };
+        let bans: Vec<_> = self
+            .peers
+            .values()
+            .filter_map(|reputation| Some((reputation.peer_id, reputation.ban.clone()?)))
+            .collect();
+        let result = bincode::serialize(&bans)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| fs::write(path, bytes).map_err(|error| error.to_string()));
+        if let Err(error) = result {
+            warn!(file = %path.display(), %error, "failed to persist peer bans");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::TimeDiff;
+
+    use super::*;
+
+    #[test]
+    fn should_persist_bans_across_restarts() {
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let now = Timestamp::now();
+        let offender = NodeId::random(&mut rng);
+        let expiring = NodeId::random(&mut rng);
+        let unbanned = NodeId::random(&mut rng);
+
+        let mut store = ReputationStore::new(Some(tempdir.path()));
+        store.record_offense(
+            offender,
+            &BlocklistJustification::DishonestPeer,
+            None,
+            now + TimeDiff::from_seconds(600),
+        );
+        let _ = store.ban(
+            expiring,
+            now + TimeDiff::from_millis(1),
+            "test".to_string(),
+            None,
+        );
+        let _ = store.ban(
+            unbanned,
+            now + TimeDiff::from_seconds(600),
+            "test".to_string(),
+            None,
+        );
+        assert!(store.unban(unbanned).is_some());
+        assert!(store.unban(unbanned).is_none());
+        assert!(store.is_banned(offender, now));
+        assert!(!store.is_banned(unbanned, now));
+
+        // Only the unexpired ban should be restored, the penalty is not persisted.
+        let restored = ReputationStore::new(Some(tempdir.path()));
+        let expiry = now + TimeDiff::from_millis(1);
+        assert!(restored.is_banned(offender, expiry));
+        assert!(!restored.is_banned(expiring, expiry));
+        assert!(!restored.is_banned(unbanned, expiry));
+        let reputations = restored.reputations();
+        assert!(reputations
+            .iter()
+            .all(|reputation| reputation.penalty == 0 && reputation.offenses == 0));
+    }
+
+    #[test]
+    fn should_accumulate_penalties_and_never_shorten_bans() {
+        let mut rng = crate::new_rng();
+        let now = Timestamp::now();
+        let minor = NodeId::random(&mut rng);
+        let major = NodeId::random(&mut rng);
+
+        let mut store = ReputationStore::new(None);
+        let long_ban = now + TimeDiff::from_seconds(600);
+        store.record_offense(
+            minor,
+            &BlocklistJustification::PongLimitExceeded,
+            None,
+            long_ban,
+        );
+        store.record_offense(
+            major,
+            &BlocklistJustification::DishonestPeer,
+            None,
+            long_ban,
+        );
+        store.record_offense(
+            major,
+            &BlocklistJustification::PongLimitExceeded,
+            None,
+            now + TimeDiff::from_seconds(1),
+        );
+
+        let reputations = store.reputations();
+        assert_eq!(reputations[0].peer_id, major);
+        assert_eq!(reputations[0].penalty, 125);
+        assert_eq!(reputations[0].offenses, 2);
+        assert_eq!(reputations[0].ban.as_ref().unwrap().until, long_ban);
+        assert_eq!(reputations[1].peer_id, minor);
+
+        store.purge_expired_bans(long_ban);
+        assert!(!store.is_banned(major, now));
+        assert!(!store.is_banned(minor, now));
+        assert_eq!(store.reputations().len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 //! Tasks run by the component.
 
 use std::{
+    collections::HashSet,
     error::Error as StdError,
     fmt::Display,
     io,
@@ -8,7 +9,7 @@ use std::{
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Weak,
+        Arc, RwLock, Weak,
     },
     time::Duration,
 };
@@ -25,6 +26,7 @@ use openssl::{
     x509::X509,
 };
 use prometheus::IntGauge;
+use quinn::{Connecting, Endpoint};
 use rand::Rng;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::{
@@ -42,6 +44,7 @@ use tracing::{
 use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
 
 use super::{
+    capabilities::Capabilities,
     chain_info::ChainInfo,
     counting_format::{ConnectionId, Role},
     error::{ConnectionError, IoError},
@@ -50,8 +53,8 @@ use super::{
     limiter::LimiterHandle,
     message::NodeKeyPair,
     message_pack_format::MessagePackFormat,
-    EstimatorWeights, Event, FramedTransport, FullTransport, Identity, Message, Metrics, Payload,
-    Transport,
+    quic, EstimatorWeights, Event, FramedTransport, FullTransport, Identity, Message, Metrics,
+    Payload, Transport,
 };
 use crate::{
     components::network::{framed_transport, BincodeFormat, Config, FromIncoming},
@@ -81,6 +84,10 @@ struct HandshakeOutcome {
     peer_consensus_public_key: Option<PublicKey>,
     /// Holds the information whether the remote node is syncing.
     is_peer_syncing: bool,
+    /// Minimum size of messages to compress, or `None` if compression was not negotiated.
+    compression_threshold: Option<u32>,
+    /// Capabilities supported by both us and the peer.
+    capabilities: Capabilities,
 }
 
 /// Low-level TLS connection function.
@@ -125,10 +132,71 @@ where
 
     let peer_id = NodeId::from(validated_peer_cert.public_key_fingerprint());
 
-    Ok((peer_id, transport))
+    Ok((peer_id, Transport::Tls(transport)))
 }
 
-/// Initiates a TLS connection to a remote address.
+/// Low-level QUIC connection function.
+///
+/// Performs the QUIC connection setup, including the exchange of node certificates.
+async fn quic_connect<REv>(
+    context: &NetworkContext<REv>,
+    endpoint: &Endpoint,
+    peer_addr: SocketAddr,
+) -> Result<(NodeId, Transport), ConnectionError>
+where
+    REv: 'static,
+{
+    let (peer_cert, stream) = io_timeout(
+        context.handshake_timeout.into(),
+        quic::connect(
+            endpoint,
+            peer_addr,
+            context.our_cert.as_x509(),
+            &context.secret_key,
+        ),
+    )
+    .await
+    .map_err(ConnectionError::QuicSetup)?;
+
+    let validated_peer_cert = context
+        .validate_peer_cert(peer_cert)
+        .map_err(ConnectionError::PeerCertificateInvalid)?;
+
+    let peer_id = NodeId::from(validated_peer_cert.public_key_fingerprint());
+
+    Ok((peer_id, Transport::Quic(stream)))
+}
+
+/// Connects to a remote address.
+///
+/// Uses QUIC if enabled, unless the peer is known not to accept QUIC connections, falling back to
+/// TCP+TLS if the QUIC connection fails.
+async fn connect<REv>(
+    context: &NetworkContext<REv>,
+    peer_addr: SocketAddr,
+) -> Result<(NodeId, Transport), ConnectionError>
+where
+    REv: 'static,
+{
+    if let Some(endpoint) = context.quic_endpoint.as_ref() {
+        if !context.is_quic_unsupported(peer_addr) {
+            match quic_connect(context, endpoint, peer_addr).await {
+                Ok(value) => return Ok(value),
+                Err(ref error) => {
+                    debug!(
+                        err = display_error(error),
+                        "QUIC connection failed, falling back to TCP"
+                    );
+                    context.set_quic_unsupported(peer_addr);
+                }
+            }
+        }
+    }
+
+    tls_connect(context, peer_addr).await
+}
+
+/// Initiates a connection to a remote address.
 pub(super) async fn connect_outgoing<P, REv>(
     context: Arc<NetworkContext<REv>>,
     peer_addr: SocketAddr,
@@ -137,7 +205,7 @@ where
     REv: 'static,
     P: Payload,
 {
-    let (peer_id, transport) = match tls_connect(&context, peer_addr).await {
+    let (peer_id, transport) = match connect(&context, peer_addr).await {
         Ok(value) => value,
         Err(error) => return OutgoingConnection::FailedEarly { peer_addr, error },
     };
@@ -150,10 +218,10 @@ where
         return OutgoingConnection::Loopback { peer_addr };
     }
 
-    debug!("Outgoing TLS connection established");
+    debug!("Outgoing encrypted connection established");
 
     // Setup connection id and framed transport.
-    let connection_id = ConnectionId::from_connection(transport.ssl(), context.our_id, peer_id);
+    let connection_id = transport.connection_id(context.our_id, peer_id);
     let framed_transport = framed_transport(transport, context.chain_info.maximum_net_message_size);
 
     // Negotiate the handshake, concluding the incoming connection process.
@@ -163,6 +231,8 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            compression_threshold,
+            capabilities,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
@@ -173,12 +243,17 @@ where
                 warn!(%public_addr, %peer_addr, "peer advertises a different public address than what we connected to");
             }
 
+            context.record_quic_support(peer_addr, capabilities);
+
             // Setup full framed transport, then close down receiving end of the transport.
             let full_transport = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
                 Role::Dialer,
+                peer_id,
+                compression_threshold,
+                context.chain_info.maximum_net_message_size,
             );
             let (sink, _stream) = full_transport.split();
 
@@ -188,6 +263,7 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                capabilities,
             }
         }
         Err(error) => OutgoingConnection::Failed {
@@ -222,6 +298,9 @@ where
     node_key_pair: Option<NodeKeyPair>,
     /// Our own public listening address.
     public_addr: Option<SocketAddr>,
+    /// External address obtained by mapping our public port on the local router, advertised
+    /// instead of `public_addr` if set.
+    mapped_addr: RwLock<Option<SocketAddr>>,
     /// Timeout for handshake completion.
     handshake_timeout: TimeDiff,
     /// Weights to estimate payloads with.
@@ -236,6 +315,15 @@ where
     max_in_flight_demands: usize,
     /// Flag indicating whether this node is syncing.
     is_syncing: AtomicBool,
+    /// Minimum size of messages to compress, or `None` if compression is disabled.
+    compression_threshold: Option<u32>,
+    /// Capabilities we advertise in our handshakes.
+    capabilities: Capabilities,
+    /// Endpoint accepting and initiating QUIC connections, if enabled.
+    quic_endpoint: Option<Endpoint>,
+    /// Addresses of peers which could not be reached via QUIC or did not advertise support for it,
+    /// and are hence dialed via TCP only.
+    quic_unsupported: RwLock<HashSet<SocketAddr>>,
 }
 
 impl<REv> NetworkContext<REv> {
@@ -260,9 +348,17 @@ impl<REv> NetworkContext<REv> {
         } = our_identity;
         let our_id = NodeId::from(tls_certificate.public_key_fingerprint());
 
+        let compression_threshold =
+            (cfg.compression_threshold != 0).then(|| cfg.compression_threshold);
+        let capabilities = Capabilities::empty()
+            .with_if(Capabilities::COMPRESSION, compression_threshold.is_some())
+            .with_if(Capabilities::LIGHT_CLIENT_SERVING, cfg.light_client)
+            .with_if(Capabilities::QUIC_TRANSPORT, cfg.quic_transport);
+
         NetworkContext {
             our_id,
             public_addr: None,
+            mapped_addr: RwLock::new(None),
             event_queue: None,
             our_cert: tls_certificate,
             network_ca,
@@ -277,15 +373,21 @@ impl<REv> NetworkContext<REv> {
             tarpit_chance: cfg.tarpit_chance,
             max_in_flight_demands,
             is_syncing: AtomicBool::new(false),
+            compression_threshold,
+            capabilities,
+            quic_endpoint: None,
+            quic_unsupported: RwLock::new(HashSet::new()),
         }
     }
 
     pub(super) fn initialize(
         &mut self,
         our_public_addr: SocketAddr,
+        quic_endpoint: Option<Endpoint>,
         event_queue: EventQueueHandle<REv>,
     ) {
         self.public_addr = Some(our_public_addr);
+        self.quic_endpoint = quic_endpoint;
         self.event_queue = Some(event_queue);
     }
 
@@ -294,9 +396,20 @@ impl<REv> NetworkContext<REv> {
         self.our_id
     }
 
-    /// Our own public listening address.
+    /// Our own public listening address, as advertised to peers.
     pub(super) fn public_addr(&self) -> Option<SocketAddr> {
-        self.public_addr
+        match self.mapped_addr.read() {
+            Ok(mapped_addr) => mapped_addr.or(self.public_addr),
+            Err(_) => self.public_addr,
+        }
+    }
+
+    /// Sets the external address obtained by mapping our public port on the local router.
+    pub(super) fn set_mapped_addr(&self, addr: SocketAddr) {
+        match self.mapped_addr.write() {
+            Ok(mut mapped_addr) => *mapped_addr = Some(addr),
+            Err(_) => error!("could not set mapped address, lock poisoned"),
+        }
     }
 
     /// Chain info extract from chainspec.
@@ -318,14 +431,48 @@ impl<REv> NetworkContext<REv> {
     pub(crate) fn is_syncing(&self) -> &AtomicBool {
         &self.is_syncing
     }
+
+    /// Returns whether the peer at `addr` is known not to accept QUIC connections.
+    fn is_quic_unsupported(&self, addr: SocketAddr) -> bool {
+        match self.quic_unsupported.read() {
+            Ok(quic_unsupported) => quic_unsupported.contains(&addr),
+            Err(_) => true,
+        }
+    }
+
+    /// Marks the peer at `addr` as not accepting QUIC connections.
+    fn set_quic_unsupported(&self, addr: SocketAddr) {
+        match self.quic_unsupported.write() {
+            Ok(mut quic_unsupported) => {
+                quic_unsupported.insert(addr);
+            }
+            Err(_) => error!("could not mark address as not supporting QUIC, lock poisoned"),
+        }
+    }
+
+    /// Marks the peer at `addr` as not accepting QUIC connections if it did not advertise support
+    /// for them in its handshake.
+    fn record_quic_support(&self, addr: SocketAddr, capabilities: Capabilities) {
+        if self.quic_endpoint.is_some() && !capabilities.contains(Capabilities::QUIC_TRANSPORT) {
+            self.set_quic_unsupported(addr);
+        }
+    }
+}
+
+/// A freshly accepted incoming connection, before encryption is set up.
+enum IncomingStream {
+    /// A TCP connection, to be secured with TLS.
+    Tcp(TcpStream),
+    /// A QUIC connection, whose handshake may still be in progress.
+    Quic(Connecting),
 }
 
 /// Handles an incoming connection.
 ///
-/// Sets up a TLS stream and performs the protocol handshake.
+/// Sets up a TLS or QUIC stream and performs the protocol handshake.
 async fn handle_incoming<P, REv>(
     context: Arc<NetworkContext<REv>>,
-    stream: TcpStream,
+    stream: IncomingStream,
     peer_addr: SocketAddr,
 ) -> IncomingConnection<P>
 where
@@ -334,7 +481,11 @@ where
     for<'de> P: Serialize + Deserialize<'de>,
     for<'de> Message<P>: Serialize + Deserialize<'de>,
 {
-    let (peer_id, transport) = match server_setup_tls(&context, stream).await {
+    let setup_result = match stream {
+        IncomingStream::Tcp(stream) => server_setup_tls(&context, stream).await,
+        IncomingStream::Quic(connecting) => server_setup_quic(&context, connecting).await,
+    };
+    let (peer_id, transport) = match setup_result {
         Ok(value) => value,
         Err(error) => {
             return IncomingConnection::FailedEarly { peer_addr, error };
@@ -349,10 +500,10 @@ where
         return IncomingConnection::Loopback;
     }
 
-    debug!("Incoming TLS connection established");
+    debug!("Incoming encrypted connection established");
 
     // Setup connection id and framed transport.
-    let connection_id = ConnectionId::from_connection(transport.ssl(), context.our_id, peer_id);
+    let connection_id = transport.connection_id(context.our_id, peer_id);
     let framed_transport = framed_transport(transport, context.chain_info.maximum_net_message_size);
 
     // Negotiate the handshake, concluding the incoming connection process.
@@ -362,17 +513,24 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: _,
+            compression_threshold,
+            capabilities,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
             }
 
+            context.record_quic_support(public_addr, capabilities);
+
             // Establish full transport and close the receiving end.
             let full_transport = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
                 Role::Listener,
+                peer_id,
+                compression_threshold,
+                context.chain_info.maximum_net_message_size,
             );
 
             let (_sink, stream) = full_transport.split();
@@ -424,7 +582,31 @@ pub(super) async fn server_setup_tls<REv>(
 
     Ok((
         NodeId::from(validated_peer_cert.public_key_fingerprint()),
-        tls_stream,
+        Transport::Tls(tls_stream),
+    ))
+}
+
+/// Server-side QUIC setup.
+///
+/// Completes the QUIC handshake and exchanges node certificates with the peer.
+pub(super) async fn server_setup_quic<REv>(
+    context: &NetworkContext<REv>,
+    connecting: Connecting,
+) -> Result<(NodeId, Transport), ConnectionError> {
+    let (peer_cert, stream) = io_timeout(
+        context.handshake_timeout.into(),
+        quic::accept(connecting, context.our_cert.as_x509(), &context.secret_key),
+    )
+    .await
+    .map_err(ConnectionError::QuicSetup)?;
+
+    let validated_peer_cert = context
+        .validate_peer_cert(peer_cert)
+        .map_err(ConnectionError::PeerCertificateInvalid)?;
+
+    Ok((
+        NodeId::from(validated_peer_cert.public_key_fingerprint()),
+        Transport::Quic(stream),
     ))
 }
 
@@ -470,10 +652,11 @@ where
 
     // Manually encode a handshake.
     let handshake_message = context.chain_info.create_handshake::<P>(
-        context.public_addr.expect("component not initialized"),
+        context.public_addr().expect("component not initialized"),
         context.node_key_pair.as_ref(),
         connection_id,
         context.is_syncing.load(Ordering::SeqCst),
+        context.capabilities,
     );
 
     let serialized_handshake_message = Pin::new(&mut encoder)
@@ -513,9 +696,10 @@ where
         consensus_certificate,
         is_syncing,
         chainspec_hash,
+        capabilities,
     } = remote_message
     {
-        debug!(%protocol_version, "handshake received");
+        debug!(%protocol_version, %capabilities, "handshake received");
 
         // The handshake was valid, we can check the network name.
         if network_name != context.chain_info.network_name {
@@ -566,11 +750,18 @@ where
             .reunite(stream)
             .map_err(|_| ConnectionError::FailedToReuniteHandshakeSinkAndStream)?;
 
+        // Only features supported by both sides are used on the connection.
+        let capabilities = context.capabilities.intersection(capabilities);
+
         Ok(HandshakeOutcome {
             framed_transport,
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            compression_threshold: context
+                .compression_threshold
+                .filter(|_| capabilities.contains(Capabilities::COMPRESSION)),
+            capabilities,
         })
     } else {
         // Received a non-handshake, this is an error.
@@ -608,8 +799,12 @@ pub(super) async fn server<P, REv>(
                     let handler_span = span.clone();
                     tokio::spawn(
                         async move {
-                            let incoming =
-                                handle_incoming(context.clone(), stream, peer_addr).await;
+                            let incoming = handle_incoming(
+                                context.clone(),
+                                IncomingStream::Tcp(stream),
+                                peer_addr,
+                            )
+                            .await;
                             event_queue
                                 .schedule(
                                     Event::IncomingConnection {
@@ -651,6 +846,61 @@ pub(super) async fn server<P, REv>(
     }
 }
 
+/// Runs the QUIC acceptor loop.
+pub(super) async fn quic_server<P, REv>(
+    context: Arc<NetworkContext<REv>>,
+    endpoint: Endpoint,
+    mut shutdown_receiver: watch::Receiver<()>,
+) where
+    REv: From<Event<P>> + Send,
+    P: Payload,
+{
+    // Mirrors `server`, except that the endpoint is shared with outgoing connections, so it must
+    // not be closed on shutdown. Only new incoming connections are rejected.
+    let accept_connections = async {
+        let event_queue = context.event_queue.expect("component not initialized");
+        // `accept` only returns `None` once the endpoint is closed, which we never do.
+        while let Some(connecting) = endpoint.accept().await {
+            let peer_addr = connecting.remote_address();
+            // The span setup here is used throughout the entire lifetime of the connection.
+            let span = error_span!("incoming", %peer_addr, peer_id=Empty, consensus_key=Empty);
+
+            let context = context.clone();
+            let handler_span = span.clone();
+            tokio::spawn(
+                async move {
+                    let incoming = handle_incoming(
+                        context.clone(),
+                        IncomingStream::Quic(connecting),
+                        peer_addr,
+                    )
+                    .await;
+                    event_queue
+                        .schedule(
+                            Event::IncomingConnection {
+                                incoming: Box::new(incoming),
+                                span,
+                            },
+                            QueueKind::NetworkIncoming,
+                        )
+                        .await;
+                }
+                .instrument(handler_span),
+            );
+        }
+    };
+
+    let shutdown_messages = async move { while shutdown_receiver.changed().await.is_ok() {} };
+
+    match future::select(Box::pin(shutdown_messages), Box::pin(accept_connections)).await {
+        Either::Left(_) => {
+            endpoint.reject_new_connections();
+            info!(%context.our_id, "no longer accepting incoming QUIC connections")
+        }
+        Either::Right(_) => warn!(%context.our_id, "QUIC endpoint closed unexpectedly"),
+    }
+}
+
 /// Network message reader.
 ///
 /// Schedules all received messages until the stream is closed or an error occurs.
@@ -793,16 +1043,41 @@ where
 
 /// Network message sender.
 ///
-/// Reads from a channel and sends all messages, until the stream is closed or an error occurs.
+/// Reads from the overlay, priority and bulk channels and sends all messages, until the streams are
+/// closed or an error occurs. Messages on the overlay channel, which is only used for trusted
+/// validators, are always sent first, followed by those on the priority channel.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn message_sender<P>(
-    mut queue: UnboundedReceiver<MessageQueueItem<P>>,
+    mut overlay_queue: UnboundedReceiver<MessageQueueItem<P>>,
+    mut priority_queue: UnboundedReceiver<MessageQueueItem<P>>,
+    mut bulk_queue: UnboundedReceiver<MessageQueueItem<P>>,
     mut sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
     limiter: LimiterHandle,
     counter: IntGauge,
+    overlay_counter: IntGauge,
+    priority_counter: IntGauge,
+    bulk_counter: IntGauge,
 ) where
     P: Payload,
 {
-    while let Some((message, opt_responder)) = queue.recv().await {
+    loop {
+        // Messages on the overlay queue of trusted validators are not subject to the limiter.
+        let ((message, opt_responder), is_limited) = tokio::select! {
+            biased;
+            Some(item) = overlay_queue.recv() => {
+                overlay_counter.dec();
+                (item, false)
+            }
+            Some(item) = priority_queue.recv() => {
+                priority_counter.dec();
+                (item, true)
+            }
+            Some(item) = bulk_queue.recv() => {
+                bulk_counter.dec();
+                (item, true)
+            }
+            else => break,
+        };
         counter.dec();
 
         let estimated_wire_size = match BincodeFormat::default().0.serialized_size(&*message) {
@@ -815,7 +1090,9 @@ pub(super) async fn message_sender<P>(
                 break;
             }
         };
-        limiter.request_allowance(estimated_wire_size).await;
+        if is_limited {
+            limiter.request_allowance(estimated_wire_size).await;
+        }
 
         let mut outcome = sink.send(message).await;
 
@@ -833,10 +1110,21 @@ pub(super) async fn message_sender<P>(
                 "message send failed, closing outgoing connection"
             );
 
-            // To ensure, metrics are up to date, we close the queue and drain it.
-            queue.close();
-            while queue.recv().await.is_some() {
+            // To ensure, metrics are up to date, we close the queues and drain them.
+            overlay_queue.close();
+            while overlay_queue.recv().await.is_some() {
+                counter.dec();
+                overlay_counter.dec();
+            }
+            priority_queue.close();
+            while priority_queue.recv().await.is_some() {
+                counter.dec();
+                priority_counter.dec();
+            }
+            bulk_queue.close();
+            while bulk_queue.recv().await.is_some() {
                 counter.dec();
+                bulk_counter.dec();
             }
 
             break;
@@ -199,11 +199,13 @@ impl Reactor for TestReactor {
             registry,
             ChainInfo::create_for_testing(),
             ValidatorMatrix::new_with_validator(Arc::new(secret_key)),
+            None,
         )?;
         let gossiper_config = gossiper::Config::new_with_small_timeouts();
         let address_gossiper = Gossiper::<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, _>::new(
             "address_gossiper",
             gossiper_config,
+            None,
             registry,
         )?;
 
@@ -0,0 +1,220 @@
+//! Per-peer traffic accounting.
+//!
+//! Counts the messages and bytes exchanged with every peer, broken down by message kind, so that
+//! operators can identify bandwidth-hungry peers and protocols. The totals per peer are also
+//! exported as labeled Prometheus counters.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    sync::Mutex,
+};
+
+use prometheus::{IntCounter, IntCounterVec, Opts};
+use serde::Serialize;
+use tracing::error;
+
+use super::MessageKind;
+use crate::types::NodeId;
+
+/// Number and volume of messages of a single kind.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(crate) struct TrafficCount {
+    /// Number of messages.
+    pub(crate) count: u64,
+    /// Volume of the messages in bytes.
+    pub(crate) bytes: u64,
+}
+
+impl TrafficCount {
+    fn record(&mut self, size: u64) {
+        self.count += 1;
+        self.bytes += size;
+    }
+}
+
+/// Traffic exchanged with a single peer since the node started.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct PeerTraffic {
+    /// The ID of the peer.
+    pub(crate) peer_id: NodeId,
+    /// Total volume in bytes of the messages sent to the peer.
+    pub(crate) bytes_sent: u64,
+    /// Total volume in bytes of the messages received from the peer.
+    pub(crate) bytes_received: u64,
+    /// Messages sent to the peer, by kind.
+    pub(crate) sent: BTreeMap<MessageKind, TrafficCount>,
+    /// Messages received from the peer, by kind.
+    pub(crate) received: BTreeMap<MessageKind, TrafficCount>,
+}
+
+impl Display for PeerTraffic {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?}: sent {} bytes, received {} bytes",
+            self.peer_id, self.bytes_sent, self.bytes_received
+        )?;
+        for (direction, counts) in [("sent", &self.sent), ("received", &self.received)] {
+            for (kind, traffic_count) in counts {
+                write!(
+                    formatter,
+                    "\n  {} {}: {} messages, {} bytes",
+                    direction, kind, traffic_count.count, traffic_count.bytes
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PeerTraffic {
+    fn new(peer_id: NodeId) -> Self {
+        PeerTraffic {
+            peer_id,
+            bytes_sent: 0,
+            bytes_received: 0,
+            sent: BTreeMap::new(),
+            received: BTreeMap::new(),
+        }
+    }
+}
+
+/// Traffic of a single peer, along with its labeled Prometheus counters.
+#[derive(Debug)]
+struct PeerEntry {
+    traffic: PeerTraffic,
+    bytes_sent: IntCounter,
+    bytes_received: IntCounter,
+}
+
+/// Traffic exchanged with all peers, shared by the tasks handling connections.
+#[derive(Debug)]
+pub(super) struct TrafficStats {
+    /// Volume in bytes of outgoing messages, labeled by peer.
+    pub(super) out_bytes_per_peer: IntCounterVec,
+    /// Volume in bytes of incoming messages, labeled by peer.
+    pub(super) in_bytes_per_peer: IntCounterVec,
+    /// Traffic per peer.
+    peers: Mutex<HashMap<NodeId, PeerEntry>>,
+}
+
+impl TrafficStats {
+    /// Creates new traffic statistics, with metrics which still need to be registered.
+    pub(super) fn new() -> Result<Self, prometheus::Error> {
+        let out_bytes_per_peer = IntCounterVec::new(
+            Opts::new(
+                "net_out_bytes_per_peer",
+                "volume in bytes of outgoing messages per peer",
+            ),
+            &["peer"],
+        )?;
+        let in_bytes_per_peer = IntCounterVec::new(
+            Opts::new(
+                "net_in_bytes_per_peer",
+                "volume in bytes of incoming messages per peer",
+            ),
+            &["peer"],
+        )?;
+
+        Ok(TrafficStats {
+            out_bytes_per_peer,
+            in_bytes_per_peer,
+            peers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Records a message sent to a peer.
+    pub(super) fn record_out(&self, peer_id: NodeId, kind: MessageKind, size: u64) {
+        self.with_entry(peer_id, |entry| {
+            entry.traffic.bytes_sent += size;
+            entry.traffic.sent.entry(kind).or_default().record(size);
+            entry.bytes_sent.inc_by(size);
+        })
+    }
+
+    /// Records a message received from a peer.
+    pub(super) fn record_in(&self, peer_id: NodeId, kind: MessageKind, size: u64) {
+        self.with_entry(peer_id, |entry| {
+            entry.traffic.bytes_received += size;
+            entry.traffic.received.entry(kind).or_default().record(size);
+            entry.bytes_received.inc_by(size);
+        })
+    }
+
+    /// Returns the traffic of all peers, busiest first.
+    pub(super) fn peer_traffic(&self) -> Vec<PeerTraffic> {
+        let mut peer_traffic: Vec<_> = match self.peers.lock() {
+            Ok(peers) => peers.values().map(|entry| entry.traffic.clone()).collect(),
+            Err(_) => {
+                error!("could not read peer traffic, lock poisoned");
+                return vec![];
+            }
+        };
+        peer_traffic.sort_by_key(|traffic| {
+            Reverse(traffic.bytes_sent.saturating_add(traffic.bytes_received))
+        });
+        peer_traffic
+    }
+
+    fn with_entry<F: FnOnce(&mut PeerEntry)>(&self, peer_id: NodeId, f: F) {
+        let mut peers = match self.peers.lock() {
+            Ok(peers) => peers,
+            Err(_) => {
+                error!("could not record peer traffic, lock poisoned");
+                return;
+            }
+        };
+        let entry = peers.entry(peer_id).or_insert_with(|| {
+            let label = peer_id.to_string();
+            PeerEntry {
+                traffic: PeerTraffic::new(peer_id),
+                bytes_sent: self.out_bytes_per_peer.with_label_values(&[&label]),
+                bytes_received: self.in_bytes_per_peer.with_label_values(&[&label]),
+            }
+        });
+        f(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_account_traffic_per_peer_and_kind() {
+        let mut rng = crate::new_rng();
+        let quiet = NodeId::random(&mut rng);
+        let busy = NodeId::random(&mut rng);
+
+        let stats = TrafficStats::new().unwrap();
+        stats.record_out(quiet, MessageKind::Protocol, 10);
+        stats.record_out(busy, MessageKind::Consensus, 100);
+        stats.record_out(busy, MessageKind::Consensus, 50);
+        stats.record_in(busy, MessageKind::TrieTransfer, 1000);
+
+        let peer_traffic = stats.peer_traffic();
+        assert_eq!(peer_traffic.len(), 2);
+        assert_eq!(peer_traffic[0].peer_id, busy);
+        assert_eq!(peer_traffic[0].bytes_sent, 150);
+        assert_eq!(peer_traffic[0].bytes_received, 1000);
+        let consensus = peer_traffic[0].sent[&MessageKind::Consensus];
+        assert_eq!((consensus.count, consensus.bytes), (2, 150));
+        assert_eq!(
+            peer_traffic[0].received[&MessageKind::TrieTransfer].count,
+            1
+        );
+        assert_eq!(peer_traffic[1].peer_id, quiet);
+
+        let label = busy.to_string();
+        assert_eq!(
+            stats.out_bytes_per_peer.with_label_values(&[&label]).get(),
+            150
+        );
+        assert_eq!(
+            stats.in_bytes_per_peer.with_label_values(&[&label]).get(),
+            1000
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! Proof relayer component.
+//!
+//! Submits each era transition proof generated by the zero-knowledge prover to a configured
+//! endpoint.  Depending on the configured transport, the proof is either posted as JSON in the
+//! format of the `chain_get_era_transition_proof` RPC's result, e.g. to a service relaying it
+//! further, or submitted to a bridge contract via the endpoint's Ethereum JSON-RPC API, as
+//! described in the [`ethereum`] module.
+//!
+//! Proofs are submitted one at a time, in the order they were generated.  A failed attempt is
+//! retried after an exponentially growing delay, up to the configured number of attempts, after
+//! which the proof is dropped.  Proofs still waiting when the node stops are not submitted after a
+//! restart, but remain available via the RPC.
+
+mod config;
+mod ethereum;
+mod metrics;
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use prometheus::Registry;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use casper_types::{crypto::ErrorExt as CryptoError, ProtocolVersion, TimeDiff};
+
+use crate::{
+    components::{
+        rpc_server::rpcs::chain::GetEraTransitionProofResult,
+        zk_prover::{EraTransitionProof, ProofSystem},
+        Component,
+    },
+    effect::{EffectBuilder, EffectExt, Effects},
+    utils::{LoadError, WithDir},
+    NodeRng,
+};
+pub(crate) use config::Config;
+use config::Transport;
+use ethereum::Bridge;
+use metrics::Metrics;
+
+const COMPONENT_NAME: &str = "proof_relayer";
+
+/// An error in the proof relayer's configuration.
+#[derive(Debug, Error)]
+pub(crate) enum ConfigError {
+    /// The metrics could not be registered.
+    #[error("failed to register metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
+    /// The HTTP client could not be built.
+    #[error("failed to build http client: {0}")]
+    HttpClient(#[from] reqwest::Error),
+    /// The bridge contract's address is not a hex-encoded address.
+    #[error("invalid bridge contract address {0:?}")]
+    InvalidBridgeContract(String),
+    /// The secret key signing transactions could not be loaded.
+    #[error("failed to load ethereum secret key: {0}")]
+    LoadSecretKey(#[from] LoadError<CryptoError>),
+    /// The secret key signing transactions is not a secp256k1 key.
+    #[error("ethereum secret key is not a secp256k1 key")]
+    NotSecp256k1,
+}
+
+/// An error submitting a proof.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// The proof could not be serialized.
+    #[error("failed to serialize proof: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// The request failed, or the endpoint responded with an error status.
+    #[error("failed to post proof to {endpoint}: {error}")]
+    Post {
+        /// The URL posted to.
+        endpoint: String,
+        /// The underlying error.
+        error: reqwest::Error,
+    },
+    /// The proof could not be submitted to the bridge contract.
+    #[error("failed to submit proof to {endpoint}: {error}")]
+    Submit {
+        /// The URL of the JSON-RPC API.
+        endpoint: String,
+        /// The underlying error.
+        error: ethereum::Error,
+    },
+}
+
+/// The proof relayer's event.
+#[derive(Debug, Serialize)]
+pub(crate) enum Event {
+    /// The validators a switch block sets for the next era were proven.
+    EraTransitionProven(Box<EraTransitionProof>),
+    /// An attempt to submit the first pending proof finished.
+    PostFinished(#[serde(skip_serializing)] Result<(), Error>),
+    /// The delay before retrying to submit the first pending proof elapsed.
+    RetryDue,
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::EraTransitionProven(proof) => {
+                write!(formatter, "proved era transition of {}", proof.era_id)
+            }
+            Event::PostFinished(Ok(())) => write!(formatter, "posted proof"),
+            Event::PostFinished(Err(error)) => write!(formatter, "{}", error),
+            Event::RetryDue => write!(formatter, "retry due"),
+        }
+    }
+}
+
+/// Proof relayer component.
+#[derive(DataSize, Debug)]
+pub(crate) struct ProofRelayer {
+    config: Config,
+    protocol_version: ProtocolVersion,
+    #[data_size(skip)]
+    client: reqwest::Client,
+    /// The bridge contract proofs are submitted to, if the transport is Ethereum.
+    #[data_size(skip)]
+    bridge: Option<Arc<Bridge>>,
+    /// The proofs waiting to be submitted, oldest first.
+    pending: VecDeque<EraTransitionProof>,
+    /// Whether the first pending proof is being submitted, or waiting to be retried.
+    posting: bool,
+    /// The number of failed attempts to submit the first pending proof.
+    failed_attempts: u32,
+    #[data_size(skip)]
+    metrics: Metrics,
+}
+
+impl ProofRelayer {
+    pub(crate) fn new(
+        config: WithDir<Config>,
+        protocol_version: ProtocolVersion,
+        registry: &Registry,
+    ) -> Result<Self, ConfigError> {
+        let bridge = match (&config.value().endpoint, config.value().transport) {
+            (Some(_), Transport::Ethereum) => Some(Arc::new(Bridge::new(
+                config.map_ref(|config| config.ethereum.clone()),
+            )?)),
+            _ => None,
+        };
+        let (_, config) = config.into_parts();
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from(config.request_timeout))
+            .build()?;
+        Ok(ProofRelayer {
+            config,
+            protocol_version,
+            client,
+            bridge,
+            pending: VecDeque::new(),
+            posting: false,
+            failed_attempts: 0,
+            metrics: Metrics::new(registry)?,
+        })
+    }
+
+    fn handle_era_transition_proven(&mut self, proof: EraTransitionProof) -> Effects<Event> {
+        if self.config.endpoint.is_none() {
+            return Effects::new();
+        }
+        if self.bridge.is_some() && proof.proof_system != ProofSystem::Groth16Bn254 {
+            warn!(
+                era_id = %proof.era_id,
+                proof_system = %proof.proof_system,
+                "only proofs over BN254 can be submitted to the bridge contract, dropping proof"
+            );
+            self.metrics.dropped_proofs.inc();
+            return Effects::new();
+        }
+        self.pending.push_back(proof);
+        // The first pending proof is kept while it is being posted.
+        let first_droppable = usize::from(self.posting);
+        while self.pending.len() > self.config.max_pending_proofs.max(1) {
+            if let Some(dropped) = self.pending.remove(first_droppable) {
+                warn!(era_id = %dropped.era_id, "too many proofs pending, dropping proof");
+                self.metrics.dropped_proofs.inc();
+            }
+        }
+        self.metrics.pending_proofs.set(self.pending.len() as i64);
+        if self.posting {
+            return Effects::new();
+        }
+        self.post_first_pending()
+    }
+
+    /// Submits the first pending proof, if any.
+    fn post_first_pending(&mut self) -> Effects<Event> {
+        let (endpoint, proof) = match (&self.config.endpoint, self.pending.front()) {
+            (Some(endpoint), Some(proof)) => (endpoint.clone(), proof.clone()),
+            _ => {
+                self.posting = false;
+                return Effects::new();
+            }
+        };
+        self.posting = true;
+        debug!(era_id = %proof.era_id, %endpoint, "posting proof");
+        let client = self.client.clone();
+        match &self.bridge {
+            None => {
+                let body = GetEraTransitionProofResult::new(self.protocol_version, proof);
+                post(client, endpoint, body).event(Event::PostFinished)
+            }
+            Some(bridge) => {
+                let bridge = Arc::clone(bridge);
+                async move {
+                    ethereum::submit(&client, &endpoint, &bridge, &proof)
+                        .await
+                        .map_err(|error| Error::Submit { endpoint, error })
+                }
+                .event(Event::PostFinished)
+            }
+        }
+    }
+
+    fn handle_post_finished<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        result: Result<(), Error>,
+    ) -> Effects<Event>
+    where
+        REv: Send,
+    {
+        match result {
+            Ok(()) => {
+                if let Some(proof) = self.pending.pop_front() {
+                    info!(era_id = %proof.era_id, "posted proof");
+                }
+                self.metrics.posted_proofs.inc();
+                self.failed_attempts = 0;
+            }
+            Err(error) => {
+                self.metrics.failed_attempts.inc();
+                self.failed_attempts += 1;
+                if self.failed_attempts < self.config.max_attempts {
+                    let delay = backoff(
+                        self.config.initial_backoff,
+                        self.config.max_backoff,
+                        self.failed_attempts,
+                    );
+                    warn!(%error, attempts = self.failed_attempts, ?delay, "retrying to post proof");
+                    return effect_builder.set_timeout(delay).event(|_| Event::RetryDue);
+                }
+                if let Some(proof) = self.pending.pop_front() {
+                    warn!(
+                        %error,
+                        era_id = %proof.era_id,
+                        attempts = self.failed_attempts,
+                        "giving up posting proof"
+                    );
+                }
+                self.metrics.dropped_proofs.inc();
+                self.failed_attempts = 0;
+            }
+        }
+        self.metrics.pending_proofs.set(self.pending.len() as i64);
+        self.post_first_pending()
+    }
+}
+
+impl<REv> Component<REv> for ProofRelayer
+where
+    REv: Send,
+{
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::EraTransitionProven(proof) => self.handle_era_transition_proven(*proof),
+            Event::PostFinished(result) => self.handle_post_finished(effect_builder, result),
+            Event::RetryDue => self.post_first_pending(),
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
+
+/// Returns the delay before the retry following the given number of failed attempts.
+fn backoff(initial_backoff: TimeDiff, max_backoff: TimeDiff, failed_attempts: u32) -> Duration {
+    let factor = 1u32
+        .checked_shl(failed_attempts.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    Duration::from(initial_backoff)
+        .saturating_mul(factor)
+        .min(Duration::from(max_backoff))
+}
+
+/// Posts `body` as JSON to `endpoint`.
+async fn post<T: Serialize>(
+    client: reqwest::Client,
+    endpoint: String,
+    body: T,
+) -> Result<(), Error> {
+    let body = serde_json::to_vec(&body)?;
+    let to_error = |error| Error::Post {
+        endpoint: endpoint.clone(),
+        error,
+    };
+    client
+        .post(&endpoint)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(to_error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use tokio::sync::mpsc;
+    use warp::{http::StatusCode, Filter};
+
+    use super::*;
+
+    /// Serves an endpoint responding with `status`, which passes on the bodies posted to it.
+    fn serve(status: StatusCode) -> (SocketAddr, mpsc::UnboundedReceiver<serde_json::Value>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |body: serde_json::Value| {
+                let _ = sender.send(body);
+                warp::reply::with_status(warp::reply(), status)
+            });
+        let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (address, receiver)
+    }
+
+    #[tokio::test]
+    async fn should_post_json() {
+        let (address, mut receiver) = serve(StatusCode::OK);
+
+        post(
+            reqwest::Client::new(),
+            format!("http://{}", address),
+            vec![1, 2, 3],
+        )
+        .await
+        .unwrap();
+        assert_eq!(receiver.recv().await, Some(serde_json::json!([1, 2, 3])));
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_error_status() {
+        let (address, _receiver) = serve(StatusCode::SERVICE_UNAVAILABLE);
+
+        let result = post(
+            reqwest::Client::new(),
+            format!("http://{}", address),
+            vec![1, 2, 3],
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Post { .. })));
+    }
+
+    #[test]
+    fn should_double_backoff_up_to_maximum() {
+        let initial = "1sec".parse().unwrap();
+        let max = "1min".parse().unwrap();
+
+        let delays: Vec<_> = (1..=8)
+            .map(|attempts| backoff(initial, max, attempts).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(backoff(initial, max, u32::MAX), Duration::from_secs(60));
+    }
+}
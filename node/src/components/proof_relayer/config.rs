@@ -0,0 +1,98 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+use crate::utils::External;
+
+const DEFAULT_REQUEST_TIMEOUT: &str = "30sec";
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+const DEFAULT_INITIAL_BACKOFF: &str = "1sec";
+const DEFAULT_MAX_BACKOFF: &str = "10min";
+const DEFAULT_MAX_PENDING_PROOFS: usize = 64;
+const DEFAULT_CHAIN_ID: u64 = 1;
+const DEFAULT_GAS_LIMIT: u64 = 500_000;
+
+/// How proofs are submitted to the endpoint.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// Each proof is posted as JSON in the format of the `chain_get_era_transition_proof` RPC's
+    /// result.
+    Json,
+    /// Each proof is submitted to a bridge contract in a transaction sent via the endpoint's
+    /// Ethereum JSON-RPC API.
+    Ethereum,
+}
+
+/// Configuration options for relaying proofs to an external endpoint.
+#[derive(Clone, Serialize, Deserialize, Debug, DataSize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The URL each proof is submitted to.
+    ///
+    /// If not set, proofs are not relayed.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// How proofs are submitted to the endpoint.
+    pub transport: Transport,
+    /// The maximum time a single attempt to submit a proof may take.
+    pub request_timeout: TimeDiff,
+    /// The number of attempts to submit a proof before giving up on it.
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled for each further retry.
+    pub initial_backoff: TimeDiff,
+    /// The maximum delay between retries.
+    pub max_backoff: TimeDiff,
+    /// The maximum number of proofs waiting to be submitted.
+    ///
+    /// Once full, the oldest waiting proof is dropped for each new one.
+    pub max_pending_proofs: usize,
+    /// Options for the Ethereum transport.
+    #[serde(default)]
+    pub ethereum: EthereumConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            endpoint: None,
+            transport: Transport::Json,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT.parse().unwrap(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF.parse().unwrap(),
+            max_backoff: DEFAULT_MAX_BACKOFF.parse().unwrap(),
+            max_pending_proofs: DEFAULT_MAX_PENDING_PROOFS,
+            ethereum: EthereumConfig::default(),
+        }
+    }
+}
+
+/// Configuration options for submitting proofs to a bridge contract on an Ethereum-compatible
+/// chain.
+#[derive(Clone, Serialize, Deserialize, Debug, DataSize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct EthereumConfig {
+    /// The hex-encoded address of the bridge contract.
+    pub bridge_contract: String,
+    /// The ID of the chain, which transactions are signed for.
+    pub chain_id: u64,
+    /// Path to the secp256k1 secret key signing the transactions, in the node's PEM format.
+    #[serde(default)]
+    pub secret_key_path: External,
+    /// The gas limit of each transaction.
+    pub gas_limit: u64,
+}
+
+impl Default for EthereumConfig {
+    fn default() -> Self {
+        EthereumConfig {
+            bridge_contract: String::new(),
+            chain_id: DEFAULT_CHAIN_ID,
+            secret_key_path: External::Missing,
+            gas_limit: DEFAULT_GAS_LIMIT,
+        }
+    }
+}
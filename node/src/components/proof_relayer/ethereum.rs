@@ -0,0 +1,516 @@
+//! Submission of proofs to a bridge contract on an Ethereum-compatible chain.
+//!
+//! Each proof is submitted in a legacy transaction, signed for the configured chain as specified
+//! by EIP-155, calling
+//!
+//! ```solidity
+//! function submitEraTransitionProof(
+//!     bytes32 switchBlockHash,
+//!     uint64 eraId,
+//!     bytes32 eraEndHash,
+//!     uint256[8] calldata proof
+//! )
+//! ```
+//!
+//! on the bridge contract.  `proof` holds the Groth16 proof's points `A`, `B` and `C` as expected
+//! by the EVM's BN254 precompiles, i.e. as big-endian coordinates with the imaginary part of each
+//! coordinate of `B` first.  Hence only proofs over BN254 can be submitted.
+//!
+//! The nonce and gas price of the transaction are queried from the endpoint before each attempt.
+//! A proof counts as submitted once the endpoint accepts the transaction; its inclusion is not
+//! awaited, so the contract has to ignore proofs of eras it already accepted.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use ark_bn254::{Bn254, Fq};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, SerializationError};
+use k256::ecdsa::SigningKey;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha3::{Digest as _, Keccak256};
+use thiserror::Error;
+use tracing::debug;
+
+use casper_types::SecretKey;
+
+use super::{config::EthereumConfig, ConfigError};
+use crate::{components::zk_prover::EraTransitionProof, utils::WithDir};
+
+/// The signature of the bridge contract's function called with each proof.
+const SUBMIT_FUNCTION: &str = "submitEraTransitionProof(bytes32,uint64,bytes32,uint256[8])";
+
+/// The size of an Ethereum address.
+const ADDRESS_LENGTH: usize = 20;
+
+type Address = [u8; ADDRESS_LENGTH];
+
+/// An error submitting a proof.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// The proof could not be deserialized.
+    #[error("malformed proof: {0}")]
+    MalformedProof(#[from] SerializationError),
+    /// The request failed, or the endpoint responded with an error status.
+    #[error("{method} request failed: {error}")]
+    Request {
+        /// The JSON-RPC method called.
+        method: &'static str,
+        /// The underlying error.
+        error: reqwest::Error,
+    },
+    /// The endpoint's response is not a JSON-RPC response.
+    #[error("{method} returned an invalid response: {error}")]
+    InvalidResponse {
+        /// The JSON-RPC method called.
+        method: &'static str,
+        /// The underlying error.
+        error: serde_json::Error,
+    },
+    /// The endpoint responded with a JSON-RPC error.
+    #[error("{method} returned error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC method called.
+        method: &'static str,
+        /// The error code.
+        code: i64,
+        /// The error message.
+        message: String,
+    },
+    /// The endpoint's result is not of the expected type.
+    #[error("{method} returned an invalid result: {result}")]
+    InvalidResult {
+        /// The JSON-RPC method called.
+        method: &'static str,
+        /// The result.
+        result: Value,
+    },
+    /// The transaction could not be signed.
+    #[error("failed to sign transaction: {0}")]
+    Signing(k256::ecdsa::Error),
+}
+
+/// A bridge contract, and the key signing the transactions submitting proofs to it.
+#[derive(Debug)]
+pub(super) struct Bridge {
+    contract: Address,
+    chain_id: u64,
+    gas_limit: u64,
+    signing_key: SigningKey,
+    sender: Address,
+}
+
+impl Bridge {
+    pub(super) fn new(config: WithDir<EthereumConfig>) -> Result<Self, ConfigError> {
+        let (root_dir, config) = config.into_parts();
+        let contract = parse_address(&config.bridge_contract)
+            .ok_or_else(|| ConfigError::InvalidBridgeContract(config.bridge_contract.clone()))?;
+        let secret_key: Arc<SecretKey> = config.secret_key_path.load(root_dir)?;
+        let signing_key = match secret_key.as_ref() {
+            SecretKey::Secp256k1(signing_key) => signing_key.clone(),
+            _ => return Err(ConfigError::NotSecp256k1),
+        };
+        Ok(Bridge {
+            contract,
+            chain_id: config.chain_id,
+            gas_limit: config.gas_limit,
+            sender: account_address(&signing_key),
+            signing_key,
+        })
+    }
+
+    /// Returns the data of a call submitting `proof`.
+    fn call_data(&self, proof: &EraTransitionProof) -> Result<Vec<u8>, Error> {
+        let mut data = keccak256(SUBMIT_FUNCTION.as_bytes())[..4].to_vec();
+        data.extend(proof.switch_block_hash.value());
+        data.extend(uint_word(proof.era_id.value().into()));
+        data.extend(proof.era_end_hash.value());
+        for word in proof_words(proof.proof.bytes())? {
+            data.extend(word);
+        }
+        Ok(data)
+    }
+}
+
+/// Submits `proof` to the bridge contract via the JSON-RPC API at `endpoint`.
+pub(super) async fn submit(
+    client: &reqwest::Client,
+    endpoint: &str,
+    bridge: &Bridge,
+    proof: &EraTransitionProof,
+) -> Result<(), Error> {
+    let data = bridge.call_data(proof)?;
+    let sender = hex(&bridge.sender);
+    let nonce = call(
+        client,
+        endpoint,
+        "eth_getTransactionCount",
+        json!([sender, "pending"]),
+    )
+    .await?;
+    let gas_price = call(client, endpoint, "eth_gasPrice", json!([])).await?;
+    let transaction = Transaction {
+        nonce: parse_quantity("eth_getTransactionCount", nonce)?,
+        gas_price: parse_quantity("eth_gasPrice", gas_price)?,
+        gas_limit: bridge.gas_limit.into(),
+        to: bridge.contract,
+        value: 0,
+        data,
+        chain_id: bridge.chain_id,
+    };
+    let raw_transaction = transaction.sign(&bridge.signing_key)?;
+    let transaction_hash = call(
+        client,
+        endpoint,
+        "eth_sendRawTransaction",
+        json!([hex(&raw_transaction)]),
+    )
+    .await?;
+    debug!(%transaction_hash, era_id = %proof.era_id, "sent transaction submitting proof");
+    Ok(())
+}
+
+/// A JSON-RPC response.
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Value,
+    error: Option<ResponseError>,
+}
+
+/// A JSON-RPC error.
+#[derive(Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+/// Calls `method` of the JSON-RPC API at `endpoint`, returning its result.
+async fn call(
+    client: &reqwest::Client,
+    endpoint: &str,
+    method: &'static str,
+    params: Value,
+) -> Result<Value, Error> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let to_error = |error| Error::Request { method, error };
+    let body = client
+        .post(endpoint)
+        .header(CONTENT_TYPE, "application/json")
+        .body(request.to_string())
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(to_error)?
+        .bytes()
+        .await
+        .map_err(to_error)?;
+    let response: Response =
+        serde_json::from_slice(&body).map_err(|error| Error::InvalidResponse { method, error })?;
+    match response.error {
+        Some(ResponseError { code, message }) => Err(Error::Rpc {
+            method,
+            code,
+            message,
+        }),
+        None => Ok(response.result),
+    }
+}
+
+/// Parses a hex-encoded quantity as returned by the JSON-RPC API.
+fn parse_quantity(method: &'static str, result: Value) -> Result<u128, Error> {
+    result
+        .as_str()
+        .and_then(|quantity| quantity.strip_prefix("0x"))
+        .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+        .ok_or(Error::InvalidResult { method, result })
+}
+
+/// A legacy transaction.
+struct Transaction {
+    nonce: u128,
+    gas_price: u128,
+    gas_limit: u128,
+    to: Address,
+    value: u128,
+    data: Vec<u8>,
+    chain_id: u64,
+}
+
+impl Transaction {
+    /// Returns the transaction signed by `signing_key`, encoded as specified by EIP-155.
+    fn sign(&self, signing_key: &SigningKey) -> Result<Vec<u8>, Error> {
+        let mut fields = vec![
+            rlp_uint(self.nonce),
+            rlp_uint(self.gas_price),
+            rlp_uint(self.gas_limit),
+            rlp_bytes(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+        ];
+        let mut unsigned_fields = fields.clone();
+        unsigned_fields.extend([rlp_uint(self.chain_id.into()), rlp_uint(0), rlp_uint(0)]);
+        let hash = keccak256(&rlp_list(&unsigned_fields));
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(&hash)
+            .map_err(Error::Signing)?;
+        let (r, s) = signature.split_bytes();
+        let v = u128::from(self.chain_id) * 2 + 35 + u128::from(recovery_id.to_byte());
+        fields.extend([
+            rlp_uint(v),
+            rlp_bytes(trim_leading_zeros(&r)),
+            rlp_bytes(trim_leading_zeros(&s)),
+        ]);
+        Ok(rlp_list(&fields))
+    }
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Returns the address of the account with the given key.
+fn account_address(signing_key: &SigningKey) -> Address {
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let mut address = [0; ADDRESS_LENGTH];
+    address.copy_from_slice(&keccak256(&public_key.as_bytes()[1..])[32 - ADDRESS_LENGTH..]);
+    address
+}
+
+fn parse_address(address: &str) -> Option<Address> {
+    let bytes = base16::decode(address.strip_prefix("0x")?).ok()?;
+    Address::try_from(bytes.as_slice()).ok()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", base16::encode_lower(bytes))
+}
+
+/// Returns the ABI encoding of an unsigned integer.
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Returns the ABI encoding of a serialized Groth16 proof over BN254, as the `uint256[8]` of the
+/// coordinates of its points.
+fn proof_words(proof: &[u8]) -> Result<[[u8; 32]; 8], Error> {
+    let proof = ark_groth16::Proof::<Bn254>::deserialize_compressed(proof)?;
+    let word = |coordinate: &Fq| {
+        let mut word = [0; 32];
+        word.copy_from_slice(&coordinate.into_bigint().to_bytes_be());
+        word
+    };
+    Ok([
+        word(&proof.a.x),
+        word(&proof.a.y),
+        word(&proof.b.x.c1),
+        word(&proof.b.x.c0),
+        word(&proof.b.y.c1),
+        word(&proof.b.y.c0),
+        word(&proof.c.x),
+        word(&proof.c.y),
+    ])
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let first_non_zero = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    &bytes[first_non_zero..]
+}
+
+/// Returns the RLP encoding of an unsigned integer.
+fn rlp_uint(value: u128) -> Vec<u8> {
+    rlp_bytes(trim_leading_zeros(&value.to_be_bytes()))
+}
+
+/// Returns the RLP encoding of a byte string.
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => rlp_with_prefix(0x80, bytes),
+    }
+}
+
+/// Returns the RLP encoding of a list of encoded items.
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    rlp_with_prefix(0xc0, &items.concat())
+}
+
+fn rlp_with_prefix(offset: u8, payload: &[u8]) -> Vec<u8> {
+    let mut encoded = if payload.len() <= 55 {
+        vec![offset + payload.len() as u8]
+    } else {
+        let length = (payload.len() as u64).to_be_bytes();
+        let length = trim_leading_zeros(&length);
+        let mut prefix = vec![offset + 55 + length.len() as u8];
+        prefix.extend(length);
+        prefix
+    };
+    encoded.extend(payload);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use ark_bn254::{G1Affine, G2Affine};
+    use ark_ec::AffineRepr;
+    use ark_serialize::CanonicalSerialize;
+    use tokio::sync::mpsc;
+    use warp::Filter;
+
+    use casper_hashing::Digest;
+    use casper_types::{bytesrepr::Bytes, EraId};
+
+    use super::*;
+    use crate::components::zk_prover::{Proof, ProofSystem};
+
+    /// The example transaction of EIP-155.
+    fn example_transaction() -> (Transaction, SigningKey) {
+        let transaction = Transaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: [0x35; ADDRESS_LENGTH],
+            value: 1_000_000_000_000_000_000,
+            data: vec![],
+            chain_id: 1,
+        };
+        let signing_key = SigningKey::from_bytes(&[0x46; 32].into()).unwrap();
+        (transaction, signing_key)
+    }
+
+    #[test]
+    fn should_sign_transaction_as_specified_by_eip_155() {
+        let (transaction, signing_key) = example_transaction();
+        assert_eq!(
+            hex(&transaction.sign(&signing_key).unwrap()),
+            "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400\
+             008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d899\
+             7f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+        assert_eq!(
+            hex(&account_address(&signing_key)),
+            "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+        );
+    }
+
+    #[test]
+    fn should_encode_function_selector() {
+        assert_eq!(
+            keccak256(b"transfer(address,uint256)")[..4],
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+
+    #[test]
+    fn should_encode_long_rlp_strings() {
+        let bytes = [0xab; 56];
+        let encoded = rlp_bytes(&bytes);
+        assert_eq!(encoded[..2], [0xb8, 56]);
+        assert_eq!(encoded[2..], bytes);
+        assert_eq!(rlp_uint(0), [0x80]);
+        assert_eq!(rlp_uint(0x7f), [0x7f]);
+        assert_eq!(rlp_uint(0x80), [0x81, 0x80]);
+    }
+
+    #[test]
+    fn should_parse_addresses() {
+        assert_eq!(
+            parse_address("0x3535353535353535353535353535353535353535"),
+            Some([0x35; ADDRESS_LENGTH])
+        );
+        assert_eq!(
+            parse_address("3535353535353535353535353535353535353535"),
+            None
+        );
+        assert_eq!(parse_address("0x35353535"), None);
+    }
+
+    /// Serves a JSON-RPC endpoint, which passes on the raw transactions sent to it.
+    fn serve() -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |request: Value| {
+                let result = match request["method"].as_str() {
+                    Some("eth_getTransactionCount") => json!("0x9"),
+                    Some("eth_gasPrice") => json!("0x4a817c800"),
+                    Some("eth_sendRawTransaction") => {
+                        let _ = sender.send(request["params"][0].as_str().unwrap().to_string());
+                        json!(hex(&[0; 32]))
+                    }
+                    _ => {
+                        return warp::reply::json(
+                            &json!({ "error": { "code": -32601, "message": "" } }),
+                        )
+                    }
+                };
+                warp::reply::json(&json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+            });
+        let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (address, receiver)
+    }
+
+    fn era_transition_proof() -> EraTransitionProof {
+        let proof = ark_groth16::Proof::<Bn254> {
+            a: G1Affine::generator(),
+            b: G2Affine::generator(),
+            c: (G1Affine::generator() + G1Affine::generator()).into(),
+        };
+        let mut proof_bytes = vec![];
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        EraTransitionProof {
+            switch_block_hash: Digest::hash(b"switch block"),
+            era_id: EraId::new(7),
+            era_end_hash: Digest::hash(b"era end"),
+            proof_system: ProofSystem::Groth16Bn254,
+            circuit_id: "era-transition".to_string(),
+            proof: Proof::new(Bytes::from(proof_bytes)),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_submit_proof() {
+        let (address, mut receiver) = serve();
+        let (example_transaction, signing_key) = example_transaction();
+        let bridge = Bridge {
+            contract: [0x35; ADDRESS_LENGTH],
+            chain_id: 1,
+            gas_limit: 500_000,
+            sender: account_address(&signing_key),
+            signing_key,
+        };
+        let proof = era_transition_proof();
+
+        submit(
+            &reqwest::Client::new(),
+            &format!("http://{}", address),
+            &bridge,
+            &proof,
+        )
+        .await
+        .unwrap();
+
+        let data = bridge.call_data(&proof).unwrap();
+        assert_eq!(data.len(), 4 + 11 * 32);
+        assert_eq!(data[4..36], proof.switch_block_hash.value());
+        assert_eq!(data[36..68], uint_word(7));
+        assert_eq!(data[68..100], proof.era_end_hash.value());
+        let expected_transaction = Transaction {
+            gas_limit: 500_000,
+            value: 0,
+            data,
+            ..example_transaction
+        };
+        let expected_raw_transaction = expected_transaction.sign(&bridge.signing_key).unwrap();
+        assert_eq!(receiver.recv().await, Some(hex(&expected_raw_transaction)));
+    }
+}
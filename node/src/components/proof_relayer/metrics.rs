@@ -0,0 +1,61 @@
+use prometheus::{IntCounter, IntGauge, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the proof relayer component.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of proofs posted to the endpoint.
+    pub(super) posted_proofs: IntCounter,
+    /// Number of failed attempts to post a proof.
+    pub(super) failed_attempts: IntCounter,
+    /// Number of proofs dropped without being posted.
+    pub(super) dropped_proofs: IntCounter,
+    /// Number of proofs waiting to be posted.
+    pub(super) pending_proofs: IntGauge,
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates a new instance of the proof relayer metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let posted_proofs = IntCounter::new(
+            "proof_relayer_posted_proofs".to_string(),
+            "number of proofs posted to the endpoint.".to_string(),
+        )?;
+        let failed_attempts = IntCounter::new(
+            "proof_relayer_failed_attempts".to_string(),
+            "number of failed attempts to post a proof.".to_string(),
+        )?;
+        let dropped_proofs = IntCounter::new(
+            "proof_relayer_dropped_proofs".to_string(),
+            "number of proofs dropped without being posted.".to_string(),
+        )?;
+        let pending_proofs = IntGauge::new(
+            "proof_relayer_pending_proofs".to_string(),
+            "number of proofs waiting to be posted.".to_string(),
+        )?;
+
+        registry.register(Box::new(posted_proofs.clone()))?;
+        registry.register(Box::new(failed_attempts.clone()))?;
+        registry.register(Box::new(dropped_proofs.clone()))?;
+        registry.register(Box::new(pending_proofs.clone()))?;
+
+        Ok(Metrics {
+            posted_proofs,
+            failed_attempts,
+            dropped_proofs,
+            pending_proofs,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.posted_proofs);
+        unregister_metric!(self.registry, self.failed_attempts);
+        unregister_metric!(self.registry, self.dropped_proofs);
+        unregister_metric!(self.registry, self.pending_proofs);
+    }
+}
@@ -0,0 +1,249 @@
+//! Randomness beacon component.
+//!
+//! Derives a random output for each block by evaluating a verifiable random function (VRF) on the
+//! block's hash.  The VRF is schnorrkel's sr25519 VRF.  Its key pair is derived from the node's
+//! signing key, so it stays the same across restarts, and is signed with the signing key.  The
+//! proof is randomized, so repeated requests return different proofs of the same output.
+//!
+//! The proof only shows that the output was computed with the returned VRF key, and the signature
+//! only shows that the node endorsed that key.  Nothing stops the holder of the signing key from
+//! endorsing other VRF keys as well, and thereby choosing between several outputs for a block.  A
+//! client can rule that out only by pinning a VRF public key it obtained beforehand, and rejecting
+//! outputs under any other key.
+//!
+//! The beacon is not part of consensus: the VRF keys are not registered on chain, and the outputs
+//! are neither included in blocks nor available to contracts.  Even with a pinned key, a validator
+//! proposing a block could try many variants of the block to get an output it likes from its own
+//! beacon.  The outputs are therefore not manipulation-resistant, and clients should at least
+//! combine the outputs of several nodes other than the block's proposer.
+
+mod config;
+
+use std::fmt::{self, Debug, Formatter};
+
+use datasize::DataSize;
+use hex_fmt::HexFmt;
+use schnorrkel::{
+    vrf::{VRF_PREOUT_LENGTH, VRF_PROOF_LENGTH},
+    ExpansionMode, Keypair, MiniSecretKey, PUBLIC_KEY_LENGTH,
+};
+use tracing::debug;
+
+use casper_hashing::Digest;
+use casper_types::{crypto, crypto::ErrorExt as CryptoError, PublicKey, SecretKey, Signature};
+
+use crate::{
+    components::Component,
+    effect::{requests::RandomnessBeaconRequest, EffectBuilder, EffectExt, Effects},
+    types::BlockHash,
+    NodeRng,
+};
+pub(crate) use config::Config;
+
+const COMPONENT_NAME: &str = "randomness_beacon";
+
+/// The domain separator hashed with the signing key to derive the VRF key pair.
+const KEY_DERIVATION_CONTEXT: &[u8] = b"casper-randomness-beacon-key";
+/// The domain separator prefixed to the VRF public key when signing it.
+const KEY_BINDING_CONTEXT: &[u8] = b"casper-randomness-beacon-key-binding";
+/// The signing context of the VRF transcript.
+const VRF_CONTEXT: &[u8] = b"casper-randomness-beacon";
+/// The context used to derive the output from the VRF's input and output points.
+const OUTPUT_CONTEXT: &[u8] = b"casper-randomness-beacon-output";
+
+/// The length in bytes of the random output derived for a block.
+const OUTPUT_LENGTH: usize = 32;
+
+/// The random output derived for a block, and the data needed to verify it.
+#[derive(Clone, Debug)]
+pub(crate) struct BlockRandomness {
+    /// The hash of the block the VRF was evaluated on.
+    pub(crate) block_hash: BlockHash,
+    /// The random output.
+    pub(crate) output: [u8; OUTPUT_LENGTH],
+    /// The VRF output point the random output is derived from.
+    pub(crate) vrf_output: [u8; VRF_PREOUT_LENGTH],
+    /// The proof that the VRF output was computed correctly.
+    pub(crate) vrf_proof: [u8; VRF_PROOF_LENGTH],
+    /// The sr25519 public key to verify the proof with.
+    pub(crate) vrf_public_key: [u8; PUBLIC_KEY_LENGTH],
+    /// The node's public key.
+    pub(crate) public_key: PublicKey,
+    /// The signature of the VRF public key by the node's signing key.
+    pub(crate) vrf_public_key_signature: Signature,
+}
+
+/// The VRF key pair, with its binding to the node's public key.
+struct VrfKey {
+    keypair: Keypair,
+    public_key: PublicKey,
+    signature: Signature,
+}
+
+impl VrfKey {
+    /// Derives the VRF key pair from the node's signing key.
+    fn derive(secret_key: &SecretKey, public_key: PublicKey) -> Result<Self, CryptoError> {
+        let secret_key_der = secret_key.to_der()?;
+        let seed = Digest::hash([KEY_DERIVATION_CONTEXT, &secret_key_der].concat());
+        let keypair = MiniSecretKey::from_bytes(&seed.value())
+            .expect("digest should have the length of a mini secret key")
+            .expand_to_keypair(ExpansionMode::Ed25519);
+        let signature = crypto::sign(
+            binding_message(&keypair.public.to_bytes()),
+            secret_key,
+            &public_key,
+        );
+        Ok(VrfKey {
+            keypair,
+            public_key,
+            signature,
+        })
+    }
+
+    /// Evaluates the VRF on the block hash.
+    fn evaluate(&self, block_hash: BlockHash) -> BlockRandomness {
+        let transcript =
+            schnorrkel::signing_context(VRF_CONTEXT).bytes(block_hash.inner().as_ref());
+        let (in_out, proof, _) = self.keypair.vrf_sign(transcript);
+        BlockRandomness {
+            block_hash,
+            output: in_out.make_bytes(OUTPUT_CONTEXT),
+            vrf_output: in_out.to_preout().to_bytes(),
+            vrf_proof: proof.to_bytes(),
+            vrf_public_key: self.keypair.public.to_bytes(),
+            public_key: self.public_key.clone(),
+            vrf_public_key_signature: self.signature,
+        }
+    }
+}
+
+// Not derived, to keep the secret key out of logs.
+impl Debug for VrfKey {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("VrfKey")
+            .field("vrf_public_key", &HexFmt(self.keypair.public.to_bytes()))
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the message signed by the node's signing key to bind the VRF public key to it.
+pub(crate) fn binding_message(vrf_public_key: &[u8; PUBLIC_KEY_LENGTH]) -> Vec<u8> {
+    [KEY_BINDING_CONTEXT, vrf_public_key].concat()
+}
+
+/// Randomness beacon component.
+#[derive(DataSize, Debug)]
+pub(crate) struct RandomnessBeacon {
+    /// The VRF key, if the beacon is enabled.
+    #[data_size(skip)]
+    vrf_key: Option<VrfKey>,
+}
+
+impl RandomnessBeacon {
+    pub(crate) fn new(
+        config: Config,
+        secret_key: &SecretKey,
+        public_key: PublicKey,
+    ) -> Result<Self, CryptoError> {
+        let vrf_key = if config.enabled {
+            Some(VrfKey::derive(secret_key, public_key)?)
+        } else {
+            None
+        };
+        Ok(RandomnessBeacon { vrf_key })
+    }
+}
+
+impl<REv> Component<REv> for RandomnessBeacon {
+    type Event = RandomnessBeaconRequest;
+
+    fn handle_event(
+        &mut self,
+        _effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        req: Self::Event,
+    ) -> Effects<Self::Event> {
+        match req {
+            RandomnessBeaconRequest::GetBlockRandomness {
+                block_hash,
+                responder,
+            } => {
+                let maybe_randomness = self.vrf_key.as_ref().map(|vrf_key| {
+                    debug!(%block_hash, "evaluating VRF");
+                    vrf_key.evaluate(block_hash)
+                });
+                responder.respond(maybe_randomness).ignore()
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schnorrkel::{
+        vrf::{VRFPreOut, VRFProof},
+        PublicKey as VrfPublicKey,
+    };
+
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    /// Verifies the randomness the way a client would, returning the random output.
+    fn verify(randomness: &BlockRandomness) -> Option<[u8; OUTPUT_LENGTH]> {
+        crypto::verify(
+            binding_message(&randomness.vrf_public_key),
+            &randomness.vrf_public_key_signature,
+            &randomness.public_key,
+        )
+        .ok()?;
+        let vrf_public_key = VrfPublicKey::from_bytes(&randomness.vrf_public_key).ok()?;
+        let vrf_output = VRFPreOut::from_bytes(&randomness.vrf_output).ok()?;
+        let vrf_proof = VRFProof::from_bytes(&randomness.vrf_proof).ok()?;
+        let transcript =
+            schnorrkel::signing_context(VRF_CONTEXT).bytes(randomness.block_hash.inner().as_ref());
+        let (in_out, _) = vrf_public_key
+            .vrf_verify(transcript, &vrf_output, &vrf_proof)
+            .ok()?;
+        Some(in_out.make_bytes(OUTPUT_CONTEXT))
+    }
+
+    #[test]
+    fn should_derive_verifiable_and_deterministic_output() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let vrf_key = VrfKey::derive(&secret_key, public_key.clone()).unwrap();
+        let block_hash = BlockHash::random(&mut rng);
+
+        let randomness = vrf_key.evaluate(block_hash);
+        assert_eq!(verify(&randomness), Some(randomness.output));
+
+        // The same key is derived again, and yields the same output for the same block.
+        let rederived_key = VrfKey::derive(&secret_key, public_key).unwrap();
+        let repeated = rederived_key.evaluate(block_hash);
+        assert_eq!(repeated.vrf_public_key, randomness.vrf_public_key);
+        assert_eq!(repeated.output, randomness.output);
+        assert_eq!(verify(&repeated), Some(randomness.output));
+
+        // Another block yields another output.
+        let other = vrf_key.evaluate(BlockHash::random(&mut rng));
+        assert_ne!(other.output, randomness.output);
+
+        // The proof does not verify for another block.
+        let mut tampered = randomness.clone();
+        tampered.block_hash = other.block_hash;
+        assert_eq!(verify(&tampered), None);
+
+        // The VRF key is not accepted without a binding signature by the node's key.
+        let mut rebound = randomness;
+        rebound.public_key = PublicKey::from(&SecretKey::random(&mut rng));
+        assert_eq!(verify(&rebound), None);
+    }
+}
@@ -0,0 +1,11 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Configuration options for the randomness beacon.
+#[derive(Clone, Default, Serialize, Deserialize, Debug, DataSize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Whether to derive random outputs for blocks via the `chain_get_block_randomness` RPC.
+    pub enabled: bool,
+}
@@ -17,10 +17,16 @@
 //!     example: curl -X GET 'http://IP:8888/status'
 //! /metrics : time series data collected from the internals of the node being queried.
 //!     example: curl -X GET 'http://IP:8888/metrics'
+//! /health/live : answers once the node's reactor answers, for use as a liveness probe.
+//!     example: curl -X GET 'http://IP:8888/health/live'
+//! /health/ready : status 200 if the node is caught up with the network and has enough peers, 503
+//!     otherwise, for use as a readiness probe.
+//!     example: curl -X GET 'http://IP:8888/health/ready'
 
 mod config;
 mod event;
 mod filters;
+mod health;
 mod http_server;
 
 use std::{fmt::Debug, time::Instant};
@@ -41,9 +47,9 @@ use crate::{
     },
     effect::{
         requests::{
-            BlockSynchronizerRequest, ChainspecRawBytesRequest, ConsensusRequest, MetricsRequest,
-            NetworkInfoRequest, ReactorStatusRequest, RestRequest, StorageRequest,
-            UpgradeWatcherRequest,
+            BlockAccumulatorRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest,
+            ConsensusRequest, DeployBufferRequest, MetricsRequest, NetworkInfoRequest,
+            ReactorStatusRequest, RestRequest, StorageRequest, UpgradeWatcherRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
@@ -54,6 +60,7 @@ use crate::{
 };
 pub use config::Config;
 pub(crate) use event::Event;
+use health::ReadinessThresholds;
 
 const COMPONENT_NAME: &str = "rest_server";
 
@@ -69,6 +76,8 @@ pub(crate) trait ReactorEventT:
     + From<MetricsRequest>
     + From<ReactorStatusRequest>
     + From<BlockSynchronizerRequest>
+    + From<DeployBufferRequest>
+    + From<BlockAccumulatorRequest>
     + Send
 {
 }
@@ -84,6 +93,8 @@ impl<REv> ReactorEventT for REv where
         + From<MetricsRequest>
         + From<ReactorStatusRequest>
         + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<BlockAccumulatorRequest>
         + Send
         + 'static
 {
@@ -199,6 +210,7 @@ where
                             (reactor_state, last_progress),
                             available_block_range,
                             block_sync,
+                            stuck_deploys,
                         ) = join!(
                             effect_builder.get_highest_complete_block_from_storage(),
                             effect_builder.network_peers(),
@@ -207,6 +219,7 @@ where
                             effect_builder.get_reactor_status(),
                             effect_builder.get_available_block_range_from_storage(),
                             effect_builder.get_block_synchronizer_status(),
+                            effect_builder.get_stuck_deploys(),
                         );
                         let starting_state_root_hash = effect_builder
                             .get_block_header_at_height_from_storage(
@@ -227,6 +240,7 @@ where
                             available_block_range,
                             block_sync,
                             starting_state_root_hash,
+                            stuck_deploys,
                         );
                         responder.respond(status_feed).await;
                     }
@@ -289,6 +303,10 @@ where
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
         let builder = utils::start_listening(&cfg.address)?;
+        let readiness_thresholds = ReadinessThresholds {
+            max_block_lag: cfg.readiness_max_block_lag,
+            min_peers: cfg.readiness_min_peers as usize,
+        };
 
         let server_join_handle = match cfg.cors_origin.as_str() {
             "" => Some(tokio::spawn(http_server::run(
@@ -297,6 +315,7 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                readiness_thresholds,
             ))),
             "*" => Some(tokio::spawn(http_server::run_with_cors(
                 builder,
@@ -304,6 +323,7 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                readiness_thresholds,
                 CorsOrigin::Any,
             ))),
             _ => Some(tokio::spawn(http_server::run_with_cors(
@@ -312,6 +332,7 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                readiness_thresholds,
                 CorsOrigin::Specified(cfg.cors_origin.clone()),
             ))),
         };
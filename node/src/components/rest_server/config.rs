@@ -9,6 +9,10 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
+/// Default maximum number of blocks the node may lag behind the network while being ready.
+const DEFAULT_READINESS_MAX_BLOCK_LAG: u64 = 10;
+/// Default minimum number of connected peers for the node to be ready.
+const DEFAULT_READINESS_MIN_PEERS: u32 = 1;
 
 /// REST HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -26,6 +30,13 @@ pub struct Config {
 
     /// CORS origin.
     pub cors_origin: String,
+
+    /// Maximum number of blocks the highest complete block may lag behind the highest block known
+    /// to the network for the node to be reported as ready.
+    pub readiness_max_block_lag: u64,
+
+    /// Minimum number of connected peers for the node to be reported as ready.
+    pub readiness_min_peers: u32,
 }
 
 impl Config {
@@ -36,6 +47,8 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            readiness_max_block_lag: DEFAULT_READINESS_MAX_BLOCK_LAG,
+            readiness_min_peers: DEFAULT_READINESS_MIN_PEERS,
         }
     }
 }
@@ -1,4 +1,4 @@
-use futures::FutureExt;
+use futures::{join, FutureExt};
 use http::Response;
 use hyper::Body;
 use tracing::warn;
@@ -12,7 +12,10 @@ use warp::{
 
 use casper_types::ProtocolVersion;
 
-use super::ReactorEventT;
+use super::{
+    health::{Readiness, ReadinessThresholds},
+    ReactorEventT,
+};
 use crate::{
     effect::{requests::RestRequest, EffectBuilder},
     reactor::QueueKind,
@@ -35,6 +38,15 @@ pub const VALIDATOR_CHANGES_API_PATH: &str = "validator-changes";
 /// The chainspec file URL path.
 pub const CHAINSPEC_API_PATH: &str = "chainspec";
 
+/// The URL path prefix of the health checks.
+pub const HEALTH_API_PATH: &str = "health";
+
+/// The liveness check URL path, relative to `HEALTH_API_PATH`.
+pub const LIVENESS_API_PATH: &str = "live";
+
+/// The readiness check URL path, relative to `HEALTH_API_PATH`.
+pub const READINESS_API_PATH: &str = "ready";
+
 pub(super) fn create_status_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
@@ -134,3 +146,63 @@ pub(super) fn create_chainspec_filter<REv: ReactorEventT>(
         })
         .boxed()
 }
+
+/// Returns a filter answering liveness checks.
+///
+/// The reply is sent once the reactor answers a request for its state, so a node whose reactor is
+/// stuck will cause the check to time out.
+pub(super) fn create_liveness_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(HEALTH_API_PATH))
+        .and(warp::path(LIVENESS_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || {
+            effect_builder
+                .get_reactor_status()
+                .map(|(reactor_state, last_progress)| {
+                    let body = serde_json::json!({
+                        "reactor_state": reactor_state,
+                        "last_progress": last_progress,
+                    });
+                    Ok::<_, Rejection>(reply::json(&body).into_response())
+                })
+        })
+        .boxed()
+}
+
+/// Returns a filter answering readiness checks.
+///
+/// The reply has status 200 if the node meets the `thresholds`, and 503 otherwise.
+pub(super) fn create_readiness_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    thresholds: ReadinessThresholds,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(HEALTH_API_PATH))
+        .and(warp::path(READINESS_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || async move {
+            let ((reactor_state, _), peers, local_block_header, network_block_height) = join!(
+                effect_builder.get_reactor_status(),
+                effect_builder.network_peers(),
+                effect_builder.get_highest_complete_block_header_from_storage(),
+                effect_builder.get_highest_usable_block_height(),
+            );
+            let readiness = Readiness::new(
+                thresholds,
+                reactor_state,
+                peers.len(),
+                local_block_header.map(|header| header.height()),
+                network_block_height,
+            );
+            let status = if readiness.ready {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            Ok::<_, Rejection>(reply::with_status(reply::json(&readiness), status).into_response())
+        })
+        .boxed()
+}
@@ -0,0 +1,106 @@
+//! Health checks for orchestrators such as Kubernetes.
+//!
+//! The node is considered live as long as its reactor answers requests, and ready once it is caught
+//! up with the network and connected to enough peers to serve up-to-date data.
+
+use serde::Serialize;
+
+use crate::reactor::main_reactor::ReactorState;
+
+/// The thresholds the node has to meet to be considered ready.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ReadinessThresholds {
+    /// The maximum number of blocks the highest complete local block may lag behind the highest
+    /// block known to the network.
+    pub(super) max_block_lag: u64,
+    /// The minimum number of connected peers.
+    pub(super) min_peers: usize,
+}
+
+/// The readiness of the node, along with the data it was determined from.
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct Readiness {
+    /// Whether the node is ready to serve traffic.
+    pub(super) ready: bool,
+    /// The reasons the node is not ready, empty if it is.
+    pub(super) reasons: Vec<String>,
+    /// The current state of the reactor.
+    pub(super) reactor_state: ReactorState,
+    /// The number of connected peers.
+    pub(super) peer_count: usize,
+    /// The height of the highest complete block stored locally, if any.
+    pub(super) local_block_height: Option<u64>,
+    /// The height of the highest block with sufficient finality known to the node, if any.
+    pub(super) network_block_height: Option<u64>,
+}
+
+impl Readiness {
+    /// Determines the readiness of the node from its current state.
+    pub(super) fn new(
+        thresholds: ReadinessThresholds,
+        reactor_state: ReactorState,
+        peer_count: usize,
+        local_block_height: Option<u64>,
+        network_block_height: Option<u64>,
+    ) -> Self {
+        let mut reasons = vec![];
+        if !matches!(reactor_state, ReactorState::KeepUp | ReactorState::Validate) {
+            reasons.push(format!("reactor is in state {}", reactor_state));
+        }
+        if peer_count < thresholds.min_peers {
+            reasons.push(format!(
+                "connected to {} peers, but at least {} are required",
+                peer_count, thresholds.min_peers
+            ));
+        }
+        match (local_block_height, network_block_height) {
+            (None, _) => reasons.push("no complete block stored".to_string()),
+            (Some(local), Some(network))
+                if network.saturating_sub(local) > thresholds.max_block_lag =>
+            {
+                reasons.push(format!(
+                    "highest complete block {} lags behind network block {} by more than {} blocks",
+                    local, network, thresholds.max_block_lag
+                ));
+            }
+            (Some(_), _) => (),
+        }
+
+        Readiness {
+            ready: reasons.is_empty(),
+            reasons,
+            reactor_state,
+            peer_count,
+            local_block_height,
+            network_block_height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: ReadinessThresholds = ReadinessThresholds {
+        max_block_lag: 5,
+        min_peers: 2,
+    };
+
+    #[test]
+    fn should_require_sync_and_peers_for_readiness() {
+        let readiness = Readiness::new(THRESHOLDS, ReactorState::KeepUp, 3, Some(100), Some(105));
+        assert!(readiness.ready);
+        assert!(readiness.reasons.is_empty());
+
+        let readiness = Readiness::new(THRESHOLDS, ReactorState::Validate, 2, Some(100), None);
+        assert!(readiness.ready);
+
+        let readiness = Readiness::new(THRESHOLDS, ReactorState::KeepUp, 3, Some(100), Some(106));
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons.len(), 1);
+
+        let readiness = Readiness::new(THRESHOLDS, ReactorState::CatchUp, 1, None, Some(106));
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons.len(), 3);
+    }
+}
@@ -10,7 +10,7 @@ use warp::Filter;
 use casper_json_rpc::CorsOrigin;
 use casper_types::ProtocolVersion;
 
-use super::{filters, ReactorEventT};
+use super::{filters, health::ReadinessThresholds, ReactorEventT};
 use crate::effect::EffectBuilder;
 
 /// Run the REST HTTP server.
@@ -22,6 +22,7 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    readiness_thresholds: ReadinessThresholds,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
@@ -30,13 +31,17 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
+    let rest_liveness = filters::create_liveness_filter(effect_builder);
+    let rest_readiness = filters::create_readiness_filter(effect_builder, readiness_thresholds);
 
     let service = warp::service(
         rest_status
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
-            .or(rest_chainspec_filter),
+            .or(rest_chainspec_filter)
+            .or(rest_liveness)
+            .or(rest_readiness),
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
@@ -70,6 +75,7 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    readiness_thresholds: ReadinessThresholds,
     cors_origin: CorsOrigin,
 ) {
     // REST filters.
@@ -79,6 +85,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
+    let rest_liveness = filters::create_liveness_filter(effect_builder);
+    let rest_readiness = filters::create_readiness_filter(effect_builder, readiness_thresholds);
 
     let service = warp::service(
         rest_status
@@ -86,6 +94,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
             .or(rest_open_rpc)
             .or(rest_validator_changes)
             .or(rest_chainspec_filter)
+            .or(rest_liveness)
+            .or(rest_readiness)
             .with(match cors_origin {
                 CorsOrigin::Any => warp::cors().allow_any_origin(),
                 CorsOrigin::Specified(origin) => warp::cors().allow_origin(origin.as_str()),
@@ -10,49 +10,63 @@
 //! a component implementation that interfaces with other components via being plugged into a
 //! reactor, and an external facing http server that exposes various uri routes and converts
 //! JSON-RPC requests into the appropriate component events.
+//!
+//! Clients connected via the optional WebSocket endpoint can additionally subscribe to some of the
+//! announcements routed to this component by the reactor.  The main APIs, including these
+//! subscriptions, are also offered by an optional gRPC server.
 
+mod access_control;
 mod config;
 mod event;
+mod grpc_config;
+mod grpc_server;
 mod http_server;
+mod qps_limit;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
+mod websocket;
 
-use std::{fmt::Debug, time::Instant};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 
 use datasize::DataSize;
 use futures::join;
+use tokio::sync::{broadcast, Semaphore};
 use tracing::{error, info, warn};
 
 use casper_execution_engine::core::engine_state::{
-    self, BalanceRequest, BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryRequest,
-    QueryResult,
+    self, BalanceRequest, BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryResult,
 };
 use casper_hashing::Digest;
+use casper_json_rpc::BatchLimits;
 use casper_types::{system::auction::EraValidators, Key, ProtocolVersion, URef};
 
 use super::Component;
 use crate::{
     components::{
-        contract_runtime::EraValidatorsRequest, ComponentState, InitializedComponent,
-        PortBoundComponent,
+        contract_runtime::EraValidatorsRequest, event_stream_server::SseData, ComponentState,
+        InitializedComponent, PortBoundComponent,
     },
     effect::{
         requests::{
             AcceptDeployRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest,
-            ConsensusRequest, ContractRuntimeRequest, MetricsRequest, NetworkInfoRequest,
-            ReactorStatusRequest, RpcRequest, StorageRequest, UpgradeWatcherRequest,
+            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, MetricsRequest,
+            NetworkInfoRequest, RandomnessBeaconRequest, ReactorStatusRequest, RpcRequest,
+            SpeculativeExecutionRequest, StorageRequest, UpgradeWatcherRequest, ZkProverRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
     reactor::main_reactor::MainEvent,
-    types::{ChainspecInfo, StatusFeed},
+    types::{ChainspecInfo, JsonBlock, StatusFeed},
     utils::{self, ListeningError},
     NodeRng,
 };
-pub use config::Config;
+pub use config::{AccessControlConfig, Config};
 pub(crate) use event::Event;
+pub use grpc_config::Config as GrpcConfig;
+use qps_limit::QpsLimit;
 pub use speculative_exec_config::Config as SpeculativeExecConfig;
+use websocket::WebSocketSettings;
 
 const COMPONENT_NAME: &str = "rpc_server";
 
@@ -64,12 +78,16 @@ pub(crate) trait ReactorEventT:
     + From<ChainspecRawBytesRequest>
     + From<UpgradeWatcherRequest>
     + From<ContractRuntimeRequest>
+    + From<SpeculativeExecutionRequest>
     + From<ConsensusRequest>
     + From<MetricsRequest>
     + From<NetworkInfoRequest>
     + From<StorageRequest>
     + From<ReactorStatusRequest>
     + From<BlockSynchronizerRequest>
+    + From<DeployBufferRequest>
+    + From<ZkProverRequest>
+    + From<RandomnessBeaconRequest>
     + Send
 {
 }
@@ -81,12 +99,16 @@ impl<REv> ReactorEventT for REv where
         + From<ChainspecRawBytesRequest>
         + From<UpgradeWatcherRequest>
         + From<ContractRuntimeRequest>
+        + From<SpeculativeExecutionRequest>
         + From<ConsensusRequest>
         + From<MetricsRequest>
         + From<NetworkInfoRequest>
         + From<StorageRequest>
         + From<ReactorStatusRequest>
         + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<ZkProverRequest>
+        + From<RandomnessBeaconRequest>
         + Send
         + 'static
 {
@@ -100,6 +122,13 @@ pub(crate) struct RpcServer {
     config: Config,
     /// The config for speculative execution.
     speculative_exec_config: SpeculativeExecConfig,
+    /// The rate limit of the JSON-RPC server, which can be changed on a config reload.
+    #[data_size(skip)]
+    qps_limit: QpsLimit,
+    /// The rate limit of the speculative execution server, which can be changed on a config
+    /// reload.
+    #[data_size(skip)]
+    speculative_exec_qps_limit: QpsLimit,
     /// The api version.
     api_version: ProtocolVersion,
     /// The network name.
@@ -112,25 +141,70 @@ pub(crate) struct RpcServer {
     /// no fields and no methods because all that is needed to operate it is the
     /// spawned tokio task, so a unit struct will suffice here.
     speculative_exec: Option<()>,
+    /// The config for the gRPC server.
+    grpc_config: GrpcConfig,
+    /// The sender of the events WebSocket and gRPC clients can subscribe to.
+    #[data_size(skip)]
+    notifications: broadcast::Sender<Arc<SseData>>,
+    /// The permits of the requests waiting for a deploy to be processed.
+    #[data_size(skip)]
+    deploy_waiters: Arc<Semaphore>,
+    /// Whether clients can request zero-knowledge proofs.
+    zk_rpc_enabled: bool,
 }
 
 impl RpcServer {
     pub(crate) fn new(
         config: Config,
         speculative_exec_config: SpeculativeExecConfig,
+        grpc_config: GrpcConfig,
+        zk_rpc_enabled: bool,
         api_version: ProtocolVersion,
         network_name: String,
         node_startup_instant: Instant,
     ) -> Self {
+        let (notifications, _) = broadcast::channel(websocket::NOTIFICATIONS_BUFFER_SIZE);
+        let deploy_waiters = Arc::new(Semaphore::new(config.max_deploy_waiters as usize));
         RpcServer {
             state: ComponentState::Uninitialized,
+            qps_limit: QpsLimit::new(config.qps_limit),
+            speculative_exec_qps_limit: QpsLimit::new(speculative_exec_config.qps_limit),
             config,
             speculative_exec_config,
             api_version,
             network_name,
             node_startup_instant,
             speculative_exec: None,
+            grpc_config,
+            notifications,
+            deploy_waiters,
+            zk_rpc_enabled,
+        }
+    }
+
+    /// Returns whether any WebSocket or gRPC clients, or RPC requests waiting for a deploy to be
+    /// processed, are subscribed, i.e. whether the events they can subscribe to should be routed
+    /// to this component.
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.notifications.receiver_count() > 0
+    }
+
+    /// Applies the rate limits of a reloaded config to the running servers.
+    pub(crate) fn update_qps_limits(&mut self, qps_limit: u64, speculative_exec_qps_limit: u64) {
+        if self.qps_limit.set(qps_limit) {
+            info!(qps_limit, "updated JSON-RPC server rate limit");
         }
+        self.config.qps_limit = qps_limit;
+        if self
+            .speculative_exec_qps_limit
+            .set(speculative_exec_qps_limit)
+        {
+            info!(
+                qps_limit = speculative_exec_qps_limit,
+                "updated speculative execution server rate limit"
+            );
+        }
+        self.speculative_exec_config.qps_limit = speculative_exec_qps_limit;
     }
 }
 
@@ -143,9 +217,8 @@ impl RpcServer {
         path: Vec<String>,
         responder: Responder<Result<QueryResult, engine_state::Error>>,
     ) -> Effects<Event> {
-        let query = QueryRequest::new(state_root_hash, base_key, path);
         effect_builder
-            .query_global_state(query)
+            .query_global_state_with_proof_at_root(state_root_hash, base_key, path)
             .event(move |result| Event::QueryGlobalStateResult {
                 result,
                 main_responder: responder,
@@ -183,6 +256,13 @@ impl RpcServer {
                 main_responder: responder,
             })
     }
+
+    /// Pushes the given event to all connected WebSocket clients.
+    fn notify(&self, data: SseData) -> Effects<Event> {
+        // Sending only fails if all clients disconnected in the meantime.
+        let _ = self.notifications.send(Arc::new(data));
+        Effects::new()
+    }
 }
 
 impl<REv> Component<REv> for RpcServer
@@ -228,7 +308,10 @@ where
                 | Event::GetBidsResult { .. }
                 | Event::GetDeployResult { .. }
                 | Event::GetPeersResult { .. }
-                | Event::GetBalanceResult { .. } => {
+                | Event::GetBalanceResult { .. }
+                | Event::BlockAdded(_)
+                | Event::DeployProcessed { .. }
+                | Event::FinalitySignature(_) => {
                     warn!(
                         ?event,
                         name = <Self as Component<MainEvent>>::name(self),
@@ -329,6 +412,7 @@ where
                             (reactor_state, last_progress),
                             available_block_range,
                             block_sync,
+                            stuck_deploys,
                         ) = join!(
                             effect_builder.get_highest_complete_block_from_storage(),
                             effect_builder.network_peers(),
@@ -337,6 +421,7 @@ where
                             effect_builder.get_reactor_status(),
                             effect_builder.get_available_block_range_from_storage(),
                             effect_builder.get_block_synchronizer_status(),
+                            effect_builder.get_stuck_deploys(),
                         );
                         let starting_state_root_hash = effect_builder
                             .get_block_header_at_height_from_storage(
@@ -357,6 +442,7 @@ where
                             available_block_range,
                             block_sync,
                             starting_state_root_hash,
+                            stuck_deploys,
                         );
                         responder.respond(status_feed).await;
                     }
@@ -372,6 +458,13 @@ where
                         .await
                 }
                 .ignore(),
+                Event::RpcRequest(RpcRequest::SubscribeNotifications { responder }) => {
+                    let maybe_subscription = Arc::clone(&self.deploy_waiters)
+                        .try_acquire_owned()
+                        .ok()
+                        .map(|permit| (self.notifications.subscribe(), permit));
+                    responder.respond(maybe_subscription).ignore()
+                }
                 Event::GetBlockTransfersResult {
                     block_hash: _,
                     result,
@@ -402,6 +495,25 @@ where
                     result,
                     main_responder,
                 } => main_responder.respond(result).ignore(),
+                Event::BlockAdded(block) => self.notify(SseData::BlockAdded {
+                    block_hash: *block.hash(),
+                    block: Box::new(JsonBlock::new(&block, None)),
+                }),
+                Event::DeployProcessed {
+                    deploy_hash,
+                    deploy_header,
+                    block_hash,
+                    execution_result,
+                } => self.notify(SseData::DeployProcessed {
+                    deploy_hash: Box::new(deploy_hash),
+                    account: Box::new(deploy_header.account().clone()),
+                    timestamp: deploy_header.timestamp(),
+                    ttl: deploy_header.ttl(),
+                    dependencies: deploy_header.dependencies().clone(),
+                    block_hash: Box::new(block_hash),
+                    execution_result,
+                }),
+                Event::FinalitySignature(fs) => self.notify(SseData::FinalitySignature(fs)),
             },
         }
     }
@@ -451,8 +563,9 @@ where
                 builder,
                 effect_builder,
                 self.api_version,
-                cfg.qps_limit,
+                self.speculative_exec_qps_limit.clone(),
                 cfg.max_body_bytes,
+                cfg.access_control.clone(),
                 cfg.cors_origin.clone(),
             ));
             Some(())
@@ -460,15 +573,38 @@ where
             None
         };
 
+        if self.grpc_config.enable_server {
+            grpc_server::run(
+                &self.grpc_config,
+                effect_builder,
+                self.api_version,
+                self.notifications.clone(),
+                &self.config.access_control,
+                self.qps_limit.clone(),
+            )?;
+        }
+
         let cfg = &self.config;
         let builder = utils::start_listening(&cfg.address)?;
+        let batch_limits = BatchLimits {
+            max_batch_size: cfg.max_batch_size,
+            max_concurrent_requests: cfg.max_concurrent_batch_requests,
+        };
+        let websocket = cfg.enable_websocket.then(|| WebSocketSettings {
+            notifications: self.notifications.clone(),
+            max_subscriptions: cfg.max_subscriptions_per_connection,
+        });
         tokio::spawn(http_server::run(
             builder,
             effect_builder,
             self.api_version,
-            cfg.qps_limit,
+            self.qps_limit.clone(),
             cfg.max_body_bytes,
+            batch_limits,
+            websocket,
+            cfg.access_control.clone(),
             cfg.cors_origin.clone(),
+            self.zk_rpc_enabled,
         ));
 
         Ok(Effects::new())
@@ -0,0 +1,234 @@
+//! Method-level access control of the JSON-RPC servers.
+//!
+//! Operators can disable methods entirely, or restrict them to clients presenting one of the
+//! configured API keys in the `X-API-Key` header of their HTTP requests.  This allows exposing e.g.
+//! a query-only node publicly, while still being able to send deploys to it.
+
+use std::sync::Arc;
+
+use futures::future;
+use subtle::{Choice, ConstantTimeEq};
+use tracing::warn;
+use warp::{filters::BoxedFilter, reject, Filter, Reply};
+
+use casper_hashing::Digest;
+use casper_json_rpc::{Error, Params, RequestHandlers, RequestHandlersBuilder};
+
+use super::{rpcs::ErrorCode, AccessControlConfig};
+
+/// The HTTP header holding the API key of a client.
+pub(super) const API_KEY_HEADER: &str = "x-api-key";
+
+/// The configured API keys.
+///
+/// Only the hashes of the keys are held, and a presented key is compared against all of them in
+/// constant time, so that the time taken to reject a key reveals nothing about the valid ones.
+#[derive(Clone, Debug)]
+pub(super) struct ApiKeys(Arc<Vec<Digest>>);
+
+impl ApiKeys {
+    pub(super) fn new(api_keys: &[String]) -> Self {
+        ApiKeys(Arc::new(api_keys.iter().map(Digest::hash).collect()))
+    }
+
+    /// Returns whether `api_key` is one of the configured keys.
+    pub(super) fn contains(&self, api_key: &str) -> bool {
+        let presented = Digest::hash(api_key);
+        self.0
+            .iter()
+            .fold(Choice::from(0), |found, valid| {
+                found | valid.value()[..].ct_eq(&presented.value()[..])
+            })
+            .into()
+    }
+}
+
+/// The request handlers of a server, with the configured access control applied.
+#[derive(Clone)]
+pub(super) struct AccessControlledHandlers {
+    /// The handlers for clients without a valid API key.  Methods requiring one fail with
+    /// `ErrorCode::Unauthorized`.
+    public: RequestHandlersBuilder,
+    /// The handlers for clients with a valid API key, along with all valid keys.  `None` if no
+    /// methods require an API key.
+    authorized: Option<(RequestHandlersBuilder, ApiKeys)>,
+}
+
+impl AccessControlledHandlers {
+    /// Removes the disabled methods from `handlers`, and restricts the methods requiring an API key
+    /// to clients presenting one.
+    pub(super) fn new(mut handlers: RequestHandlersBuilder, config: &AccessControlConfig) -> Self {
+        for method in &config.disabled_methods {
+            if handlers.remove_handler(method).is_none() {
+                warn!(%method, "cannot disable unknown json-rpc method");
+            }
+        }
+        if config.api_key_methods.is_empty() {
+            return AccessControlledHandlers {
+                public: handlers,
+                authorized: None,
+            };
+        }
+
+        if config.api_keys.is_empty() {
+            warn!("no api keys configured, json-rpc methods requiring one are unavailable");
+        }
+        let authorized = handlers.clone();
+        for method in &config.api_key_methods {
+            match handlers.remove_handler(method) {
+                Some(method) => {
+                    let unauthorized = move |_: Option<Params>| {
+                        future::ready(Err::<(), _>(Error::new(
+                            ErrorCode::Unauthorized,
+                            format!(
+                                "'{}' requires a valid api key in the '{}' header",
+                                method, API_KEY_HEADER
+                            ),
+                        )))
+                    };
+                    handlers.register_handler(method, Arc::new(unauthorized));
+                }
+                None => warn!(%method, "cannot restrict unknown or disabled json-rpc method"),
+            }
+        }
+
+        AccessControlledHandlers {
+            public: handlers,
+            authorized: Some((authorized, ApiKeys::new(&config.api_keys))),
+        }
+    }
+
+    /// Returns the handlers for a client presenting `maybe_api_key`.
+    pub(super) fn for_api_key(&self, maybe_api_key: Option<&str>) -> RequestHandlersBuilder {
+        match (&self.authorized, maybe_api_key) {
+            (Some((handlers, api_keys)), Some(api_key)) if api_keys.contains(api_key) => {
+                handlers.clone()
+            }
+            _ => self.public.clone(),
+        }
+    }
+
+    /// Returns a filter dispatching requests with a valid API key to the route built by
+    /// `build_route` from the authorized handlers, and all others to the route built from the
+    /// public handlers.
+    pub(super) fn route<F, R>(&self, build_route: F) -> BoxedFilter<(Box<dyn Reply>,)>
+    where
+        F: Fn(RequestHandlers) -> BoxedFilter<(R,)>,
+        R: Reply + 'static,
+    {
+        let public = build_route(self.public.clone().build())
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed();
+        let (handlers, api_keys) = match &self.authorized {
+            Some((handlers, api_keys)) => (handlers.clone(), api_keys.clone()),
+            None => return public,
+        };
+
+        warp::header::<String>(API_KEY_HEADER)
+            .and_then(move |api_key: String| {
+                let is_valid = api_keys.contains(&api_key);
+                async move {
+                    if is_valid {
+                        Ok(())
+                    } else {
+                        Err(reject::not_found())
+                    }
+                }
+            })
+            .untuple_one()
+            .and(build_route(handlers.build()))
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .or(public)
+            .unify()
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde_json::Value;
+
+    use casper_json_rpc::{BatchLimits, ReservedErrorCode};
+
+    use super::*;
+
+    const STATUS: &str = "info_get_status";
+    const PUT_DEPLOY: &str = "account_put_deploy";
+    const SPECULATIVE_EXEC: &str = "speculative_exec";
+
+    async fn send_request(
+        method: &str,
+        maybe_api_key: Option<&str>,
+        filter: &BoxedFilter<(Box<dyn Reply>,)>,
+    ) -> Value {
+        let mut request = warp::test::request()
+            .method("POST")
+            .path("/rpc")
+            .header("content-type", "application/json")
+            .body(format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"{}"}}"#,
+                method
+            ));
+        if let Some(api_key) = maybe_api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        let http_response = request.filter(filter).await.unwrap().into_response();
+        assert_eq!(http_response.status(), StatusCode::OK);
+        let body_bytes = hyper::body::to_bytes(http_response.into_body())
+            .await
+            .unwrap();
+        serde_json::from_slice(&body_bytes).unwrap()
+    }
+
+    fn error_code(response: &Value) -> Option<i64> {
+        response["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn should_check_api_keys() {
+        let api_keys = ApiKeys::new(&["secret".to_string(), "other secret".to_string()]);
+        assert!(api_keys.contains("secret"));
+        assert!(api_keys.contains("other secret"));
+        assert!(!api_keys.contains("secre"));
+        assert!(!api_keys.contains("secrets"));
+        assert!(!api_keys.contains(""));
+        assert!(!ApiKeys::new(&[]).contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn should_restrict_methods() {
+        let mut handlers = RequestHandlersBuilder::new();
+        for method in [STATUS, PUT_DEPLOY, SPECULATIVE_EXEC] {
+            handlers.register_handler(
+                method,
+                Arc::new(|_: Option<Params>| future::ready(Ok::<_, Error>(true))),
+            );
+        }
+        let config = AccessControlConfig {
+            disabled_methods: vec![SPECULATIVE_EXEC.to_string()],
+            api_key_methods: vec![PUT_DEPLOY.to_string()],
+            api_keys: vec!["secret".to_string()],
+        };
+        let handlers = AccessControlledHandlers::new(handlers, &config);
+        let filter = handlers.route(|handlers| {
+            casper_json_rpc::route("rpc", 1024, handlers, true, BatchLimits::disabled())
+        });
+
+        for api_key in [None, Some("secret")] {
+            let response = send_request(STATUS, api_key, &filter).await;
+            assert_eq!(response["result"], true);
+            let response = send_request(SPECULATIVE_EXEC, api_key, &filter).await;
+            assert_eq!(
+                error_code(&response),
+                Some(ReservedErrorCode::MethodNotFound as i64)
+            );
+        }
+        for api_key in [None, Some("wrong")] {
+            let response = send_request(PUT_DEPLOY, api_key, &filter).await;
+            assert_eq!(error_code(&response), Some(ErrorCode::Unauthorized as i64));
+        }
+        let response = send_request(PUT_DEPLOY, Some("secret"), &filter).await;
+        assert_eq!(response["result"], true);
+    }
+}
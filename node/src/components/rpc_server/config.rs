@@ -10,6 +10,14 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single batch.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 50;
+/// Default maximum number of requests of a single batch executed concurrently.
+const DEFAULT_MAX_CONCURRENT_BATCH_REQUESTS: u32 = 10;
+/// Default maximum number of subscriptions of a single WebSocket connection.
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 100;
+/// Default maximum number of requests waiting for a deploy to be processed at the same time.
+const DEFAULT_MAX_DEPLOY_WAITERS: u32 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -26,8 +34,20 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single batch.  Batches are disabled if 0.
+    pub max_batch_size: u32,
+    /// Maximum number of requests of a single batch to execute concurrently.
+    pub max_concurrent_batch_requests: u32,
+    /// Setting to enable the WebSocket endpoint, supporting subscriptions.
+    pub enable_websocket: bool,
+    /// Maximum number of subscriptions of a single WebSocket connection.
+    pub max_subscriptions_per_connection: u32,
+    /// Maximum number of "info_wait_deploy_processed" requests waiting at the same time.
+    pub max_deploy_waiters: u32,
     /// CORS origin.
     pub cors_origin: String,
+    /// Restrictions on the available JSON-RPC methods.
+    pub access_control: AccessControlConfig,
 }
 
 impl Config {
@@ -38,7 +58,13 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_concurrent_batch_requests: DEFAULT_MAX_CONCURRENT_BATCH_REQUESTS,
+            enable_websocket: false,
+            max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
+            max_deploy_waiters: DEFAULT_MAX_DEPLOY_WAITERS,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            access_control: AccessControlConfig::default(),
         }
     }
 }
@@ -48,3 +74,16 @@ impl Default for Config {
         Config::new()
     }
 }
+
+/// Restrictions on the JSON-RPC methods available to clients.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct AccessControlConfig {
+    /// Methods which are not available at all.
+    pub disabled_methods: Vec<String>,
+    /// Methods which are only available to clients presenting one of the `api_keys`.
+    pub api_key_methods: Vec<String>,
+    /// API keys accepted for the `api_key_methods`.
+    pub api_keys: Vec<String>,
+}
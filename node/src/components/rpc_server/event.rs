@@ -1,6 +1,7 @@
 use std::{
     collections::BTreeMap,
     fmt::{self, Display, Formatter},
+    sync::Arc,
 };
 
 use derive_more::From;
@@ -8,11 +9,14 @@ use derive_more::From;
 use casper_execution_engine::core::engine_state::{
     self, BalanceResult, GetBidsResult, GetEraValidatorsError, QueryResult,
 };
-use casper_types::{system::auction::EraValidators, Transfer};
+use casper_types::{system::auction::EraValidators, ExecutionResult, Transfer};
 
 use crate::{
     effect::{requests::RpcRequest, Responder},
-    types::{BlockHash, Deploy, DeployHash, DeployMetadataExt, NodeId},
+    types::{
+        Block, BlockHash, Deploy, DeployHash, DeployHeader, DeployMetadataExt, FinalitySignature,
+        NodeId,
+    },
 };
 
 #[derive(Debug, From)]
@@ -50,6 +54,14 @@ pub(crate) enum Event {
         result: Result<BalanceResult, engine_state::Error>,
         main_responder: Responder<Result<BalanceResult, engine_state::Error>>,
     },
+    BlockAdded(Arc<Block>),
+    DeployProcessed {
+        deploy_hash: DeployHash,
+        deploy_header: Box<DeployHeader>,
+        block_hash: BlockHash,
+        execution_result: Box<ExecutionResult>,
+    },
+    FinalitySignature(Box<FinalitySignature>),
 }
 
 impl Display for Event {
@@ -80,6 +92,11 @@ impl Display for Event {
                 write!(formatter, "get deploy result for {}: {:?}", hash, result)
             }
             Event::GetPeersResult { peers, .. } => write!(formatter, "get peers: {}", peers.len()),
+            Event::BlockAdded(block) => write!(formatter, "block added {}", block.hash()),
+            Event::DeployProcessed { deploy_hash, .. } => {
+                write!(formatter, "deploy processed {}", deploy_hash)
+            }
+            Event::FinalitySignature(fs) => write!(formatter, "finality signature {}", fs),
         }
     }
 }
@@ -0,0 +1,44 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Default binding address for the gRPC server.
+///
+/// Uses a fixed port per node, but binds on any interface.
+const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
+/// Default maximum size of a decoded request message (2.5MB), enough for the largest valid deploy.
+const DEFAULT_MAX_MESSAGE_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests handled concurrently on a single connection.
+const DEFAULT_MAX_CONCURRENT_REQUESTS_PER_CONNECTION: u32 = 100;
+
+/// gRPC server configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable the gRPC server.
+    pub enable_server: bool,
+    /// Address to bind the gRPC server to.
+    pub address: String,
+    /// Maximum size in bytes of a single decoded request message.
+    pub max_message_bytes: u32,
+    /// Maximum number of requests handled concurrently on a single connection.
+    pub max_concurrent_requests_per_connection: u32,
+}
+
+impl Config {
+    /// Creates a default instance for the gRPC server.
+    pub fn new() -> Self {
+        Config {
+            enable_server: false,
+            address: DEFAULT_ADDRESS.to_string(),
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            max_concurrent_requests_per_connection: DEFAULT_MAX_CONCURRENT_REQUESTS_PER_CONNECTION,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
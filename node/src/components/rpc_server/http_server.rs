@@ -1,23 +1,33 @@
 use hyper::server::{conn::AddrIncoming, Builder};
 
-use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
+use casper_json_rpc::{BatchLimits, CorsOrigin, RequestHandlersBuilder};
 use casper_types::ProtocolVersion;
 
 use super::{
+    access_control::AccessControlledHandlers,
+    qps_limit::QpsLimit,
     rpcs::{
         account::PutDeploy,
         chain::{
-            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+            GetBlock, GetBlockExecutionResults, GetBlockRandomness, GetBlockTransfers,
+            GetEraInfoBySwitchBlock, GetEraSummary, GetEraTransitionProof, GetStateDiff,
+            GetStateRootHash, GetTransfersByAccount,
         },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetAccountDeploys, GetChainspec, GetDeploy, GetDeployProfile, GetEquivocations,
+            GetLeaderSchedule, GetPeers, GetStatus, GetValidatorChanges, GetValidatorPerformance,
+            WaitDeployProcessed,
+        },
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetBidsPaged, GetDelegatorsPaged,
+            GetDictionaryItem, GetItem, GetItemZk, GetTrie, QueryBalance, QueryGlobalState,
+            QueryGlobalStateMulti,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
-    ReactorEventT,
+    websocket::{self, WebSocketSettings},
+    AccessControlConfig, ReactorEventT,
 };
 use crate::effect::EffectBuilder;
 
@@ -27,36 +37,67 @@ pub const RPC_API_PATH: &str = "rpc";
 pub const RPC_API_SERVER_NAME: &str = "JSON RPC";
 
 /// Run the JSON-RPC server.
+///
+/// If `websocket` is given, the server additionally accepts WebSocket connections.  Methods
+/// requiring an API key are not available via the WebSocket endpoint.
+///
+/// `state_get_item_zk` is only available if `zk_rpc_enabled` is set, as each request can cost the
+/// node minutes of proving.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
-    qps_limit: u64,
+    qps_limit: QpsLimit,
     max_body_bytes: u32,
+    batch_limits: BatchLimits,
+    websocket: Option<WebSocketSettings>,
+    access_control: AccessControlConfig,
     cors_origin: String,
+    zk_rpc_enabled: bool,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
     PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
     GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
     GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetTransfersByAccount::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetBlockExecutionResults::register_as_handler(effect_builder, api_version, &mut handlers);
     GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
     GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
+    if zk_rpc_enabled {
+        GetItemZk::register_as_handler(effect_builder, api_version, &mut handlers);
+    }
     QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
+    QueryGlobalStateMulti::register_as_handler(effect_builder, api_version, &mut handlers);
     GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
     GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
     GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetAccountDeploys::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetDeployProfile::register_as_handler(effect_builder, api_version, &mut handlers);
+    WaitDeployProcessed::register_as_handler(effect_builder, api_version, &mut handlers);
     GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
     GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
     GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
     GetEraSummary::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetStateDiff::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetEraTransitionProof::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetBlockRandomness::register_as_handler(effect_builder, api_version, &mut handlers);
     GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetBidsPaged::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetDelegatorsPaged::register_as_handler(effect_builder, api_version, &mut handlers);
     GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
     GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetEquivocations::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetValidatorPerformance::register_as_handler(effect_builder, api_version, &mut handlers);
+    GetLeaderSchedule::register_as_handler(effect_builder, api_version, &mut handlers);
     ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
     GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
     GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
     QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    let handlers = handlers.build();
+    let handlers = AccessControlledHandlers::new(handlers, &access_control);
+    let websocket_route = websocket.map(|settings| {
+        websocket::route(handlers.clone(), max_body_bytes, batch_limits, settings)
+    });
 
     match cors_origin.as_str() {
         "" => {
@@ -65,8 +106,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                batch_limits,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                websocket_route,
             )
             .await
         }
@@ -76,8 +119,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                batch_limits,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                websocket_route,
                 CorsOrigin::Any,
             )
             .await
@@ -88,8 +133,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                batch_limits,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                websocket_route,
                 CorsOrigin::Specified(cors_origin),
             )
             .await
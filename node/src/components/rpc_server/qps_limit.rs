@@ -0,0 +1,146 @@
+//! A rate limit on new requests which can be changed while the server is running.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use tower::{
+    limit::{rate::Rate, RateLimit},
+    Service,
+};
+
+/// The maximum number of requests per second a server accepts, shared between the component and
+/// the server task so that it can be updated on a config reload.
+#[derive(Clone, Debug)]
+pub(super) struct QpsLimit(Arc<AtomicU64>);
+
+impl QpsLimit {
+    pub(super) fn new(qps_limit: u64) -> Self {
+        QpsLimit(Arc::new(AtomicU64::new(qps_limit)))
+    }
+
+    /// Returns the current limit, which is at least 1.
+    pub(super) fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed).max(1)
+    }
+
+    /// Sets a new limit, returning whether it differs from the previous one.
+    pub(super) fn set(&self, qps_limit: u64) -> bool {
+        self.0.swap(qps_limit, Ordering::Relaxed) != qps_limit
+    }
+}
+
+/// Rate-limits the inner service to the current value of a `QpsLimit`.
+///
+/// The underlying `RateLimit` is rebuilt when the limit changes, which starts a new one-second
+/// window.
+pub(super) struct DynamicRateLimit<S> {
+    inner: Option<RateLimit<S>>,
+    qps_limit: QpsLimit,
+    current_limit: u64,
+}
+
+impl<S> DynamicRateLimit<S> {
+    pub(super) fn new(service: S, qps_limit: QpsLimit) -> Self {
+        let current_limit = qps_limit.get();
+        DynamicRateLimit {
+            inner: Some(RateLimit::new(service, per_second(current_limit))),
+            qps_limit,
+            current_limit,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut RateLimit<S> {
+        self.inner
+            .as_mut()
+            .expect("rate limit should only be taken while rebuilding it")
+    }
+}
+
+impl<S, Request> Service<Request> for DynamicRateLimit<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let limit = self.qps_limit.get();
+        if limit != self.current_limit {
+            if let Some(inner) = self.inner.take() {
+                self.inner = Some(RateLimit::new(inner.into_inner(), per_second(limit)));
+            }
+            self.current_limit = limit;
+        }
+        self.inner_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.inner_mut().call(request)
+    }
+}
+
+/// Limits requests to the current value of a `QpsLimit`, rejecting those exceeding it rather than
+/// delaying them.
+#[derive(Debug)]
+pub(super) struct RequestRateLimiter {
+    qps_limit: QpsLimit,
+    /// The start of the current one-second window, and the number of requests accepted in it.
+    window: Mutex<(Instant, u64)>,
+}
+
+impl RequestRateLimiter {
+    pub(super) fn new(qps_limit: QpsLimit) -> Self {
+        RequestRateLimiter {
+            qps_limit,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Counts a new request, returning `false` if the limit has already been reached in the
+    /// current one-second window.
+    pub(super) fn try_acquire(&self) -> bool {
+        let limit = self.qps_limit.get();
+        let mut window = self
+            .window
+            .lock()
+            .expect("request rate limiter mutex poisoned");
+        let now = Instant::now();
+        if now.duration_since(window.0) >= Duration::from_secs(1) {
+            *window = (now, 0);
+        }
+        if window.1 >= limit {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+}
+
+fn per_second(limit: u64) -> Rate {
+    Rate::new(limit, Duration::from_secs(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_reject_requests_over_limit() {
+        let qps_limit = QpsLimit::new(2);
+        let rate_limiter = RequestRateLimiter::new(qps_limit.clone());
+        assert!(rate_limiter.try_acquire());
+        assert!(rate_limiter.try_acquire());
+        assert!(!rate_limiter.try_acquire());
+
+        qps_limit.set(3);
+        assert!(rate_limiter.try_acquire());
+        assert!(!rate_limiter.try_acquire());
+    }
+}
@@ -11,7 +11,7 @@ pub mod info;
 pub mod speculative_exec;
 pub mod state;
 
-use std::{str, sync::Arc, time::Duration};
+use std::{str, sync::Arc};
 
 use async_trait::async_trait;
 use http::header::ACCEPT_ENCODING;
@@ -20,16 +20,19 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::oneshot;
-use tower::ServiceBuilder;
-use tracing::info;
-use warp::Filter;
+use tracing::{info, info_span, Instrument};
+use warp::{filters::BoxedFilter, Filter, Reply};
 
 use casper_json_rpc::{
-    CorsOrigin, Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode,
+    BatchLimits, CorsOrigin, Error, Params, RequestHandlersBuilder, ReservedErrorCode,
 };
 use casper_types::ProtocolVersion;
 
-use super::{ReactorEventT, RpcRequest};
+use super::{
+    access_control::AccessControlledHandlers,
+    qps_limit::{DynamicRateLimit, QpsLimit},
+    ReactorEventT, RpcRequest,
+};
 use crate::effect::EffectBuilder;
 pub use common::ErrorData;
 use docs::DocExample;
@@ -39,7 +42,7 @@ pub use error_code::ErrorCode;
 /// standard 'id', 'jsonrpc', 'method', and 'params' fields.
 ///
 /// It will be changed to `false` for casper-node v2.0.0.
-const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
+pub(super) const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
 
 /// A JSON-RPC requiring the "params" field to be present.
 #[async_trait]
@@ -90,9 +93,12 @@ pub(super) trait RpcWithParams {
         api_version: ProtocolVersion,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let handler = move |maybe_params| {
+            async move {
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
+            .instrument(info_span!("rpc", method = Self::METHOD))
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -149,9 +155,12 @@ pub(super) trait RpcWithoutParams {
         api_version: ProtocolVersion,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let handler = move |maybe_params| {
+            async move {
+                Self::check_no_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version).await
+            }
+            .instrument(info_span!("rpc", method = Self::METHOD))
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -229,9 +238,12 @@ pub(super) trait RpcWithOptionalParams {
         api_version: ProtocolVersion,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let handler = move |maybe_params| {
+            async move {
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
+            .instrument(info_span!("rpc", method = Self::METHOD))
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -255,23 +267,29 @@ pub(super) trait RpcWithOptionalParams {
 }
 
 /// Start JSON RPC server with CORS enabled in a background.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run_with_cors(
     builder: Builder<AddrIncoming>,
-    handlers: RequestHandlers,
-    qps_limit: u64,
+    handlers: AccessControlledHandlers,
+    qps_limit: QpsLimit,
     max_body_bytes: u32,
+    batch_limits: BatchLimits,
     api_path: &'static str,
     server_name: &'static str,
+    websocket_route: Option<BoxedFilter<(Box<dyn Reply>,)>>,
     cors_header: CorsOrigin,
 ) {
     let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route_with_cors(
-            api_path,
-            max_body_bytes,
-            handlers.clone(),
-            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
-            &cors_header,
-        );
+        let service_routes = handlers.route(|handlers| {
+            casper_json_rpc::route_with_cors(
+                api_path,
+                max_body_bytes,
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                batch_limits,
+                &cors_header,
+            )
+        });
 
         // Supports content negotiation for gzip responses. This is an interim fix until
         // https://github.com/seanmonstar/warp/pull/513 moves forward.
@@ -279,13 +297,21 @@ pub(super) async fn run_with_cors(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        let service_routes = service_routes_gzip
+            .or(service_routes)
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed();
+        // The WebSocket route is not wrapped in the gzip filter, as it only upgrades connections.
+        let service_routes = match websocket_route.clone() {
+            Some(websocket_route) => websocket_route.or(service_routes).unify().boxed(),
+            None => service_routes,
+        };
+
+        let service = warp::service(service_routes);
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
+    let make_svc = DynamicRateLimit::new(make_svc, qps_limit);
 
     let server = builder.serve(make_svc);
     info!(address = %server.local_addr(), "started {} server", server_name);
@@ -303,19 +329,24 @@ pub(super) async fn run_with_cors(
 /// Start JSON RPC server in a background.
 pub(super) async fn run(
     builder: Builder<AddrIncoming>,
-    handlers: RequestHandlers,
-    qps_limit: u64,
+    handlers: AccessControlledHandlers,
+    qps_limit: QpsLimit,
     max_body_bytes: u32,
+    batch_limits: BatchLimits,
     api_path: &'static str,
     server_name: &'static str,
+    websocket_route: Option<BoxedFilter<(Box<dyn Reply>,)>>,
 ) {
     let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route(
-            api_path,
-            max_body_bytes,
-            handlers.clone(),
-            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
-        );
+        let service_routes = handlers.route(|handlers| {
+            casper_json_rpc::route(
+                api_path,
+                max_body_bytes,
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                batch_limits,
+            )
+        });
 
         // Supports content negotiation for gzip responses. This is an interim fix until
         // https://github.com/seanmonstar/warp/pull/513 moves forward.
@@ -323,13 +354,21 @@ pub(super) async fn run(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        let service_routes = service_routes_gzip
+            .or(service_routes)
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed();
+        // The WebSocket route is not wrapped in the gzip filter, as it only upgrades connections.
+        let service_routes = match websocket_route.clone() {
+            Some(websocket_route) => websocket_route.or(service_routes).unify().boxed(),
+            None => service_routes,
+        };
+
+        let service = warp::service(service_routes);
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
+    let make_svc = DynamicRateLimit::new(make_svc, qps_limit);
 
     let server = builder.serve(make_svc);
     info!(address = %server.local_addr(), "started {} server", server_name);
@@ -392,9 +431,13 @@ mod tests {
             GetDeploy::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                BatchLimits::disabled(),
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -459,9 +502,13 @@ mod tests {
             GetPeers::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                BatchLimits::disabled(),
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -513,9 +560,13 @@ mod tests {
             GetBlock::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                BatchLimits::disabled(),
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -5,26 +5,45 @@
 
 mod era_summary;
 
-use std::{clone::Clone, num::ParseIntError, str};
+use std::{clone::Clone, convert::TryFrom, num::ParseIntError, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
-use casper_execution_engine::core::engine_state::{self, QueryResult};
+use casper_execution_engine::core::engine_state::{
+    self, QueryResult, StateChange, StateDiffResult,
+};
 use casper_hashing::Digest;
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    crypto, AccessRights, CLValue, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey,
+    SecretKey, Signature, Transfer, URef,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams,
 };
 use crate::{
+    components::{
+        randomness_beacon::{self, BlockRandomness},
+        storage::TransferPosition,
+        zk_prover::EraTransitionProof,
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
-    rpcs::{common, state},
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    rpcs::{
+        common,
+        state::{self, AccountIdentifier, DEFAULT_PAGE_SIZE},
+    },
+    types::{
+        json_compatibility::StoredValue, Block, BlockHash, BlockWithMetadata, Deploy, DeployHash,
+        JsonBlock,
+    },
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -46,6 +65,35 @@ static GET_BLOCK_TRANSFERS_RESULT: Lazy<GetBlockTransfersResult> =
         block_hash: Some(*Block::doc_example().hash()),
         transfers: Some(vec![Transfer::default()]),
     });
+static GET_TRANSFERS_BY_ACCOUNT_PARAMS: Lazy<GetTransfersByAccountParams> =
+    Lazy::new(|| GetTransfersByAccountParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::doc_example().clone()),
+        cursor: None,
+        page_size: Some(DEFAULT_PAGE_SIZE),
+    });
+static GET_TRANSFERS_BY_ACCOUNT_RESULT: Lazy<GetTransfersByAccountResult> =
+    Lazy::new(|| GetTransfersByAccountResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        transfers: vec![JsonAccountTransfer {
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+            transfer: Transfer::default(),
+        }],
+        next_cursor: None,
+    });
+static GET_BLOCK_EXECUTION_RESULTS_PARAMS: Lazy<GetBlockExecutionResultsParams> =
+    Lazy::new(|| GetBlockExecutionResultsParams {
+        block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+    });
+static GET_BLOCK_EXECUTION_RESULTS_RESULT: Lazy<GetBlockExecutionResultsResult> =
+    Lazy::new(|| GetBlockExecutionResultsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: Some(*Block::doc_example().hash()),
+        execution_results: Some(vec![DeployExecutionResult {
+            deploy_hash: *Deploy::doc_example().hash(),
+            result: ExecutionResult::example().clone(),
+        }]),
+    });
 static GET_STATE_ROOT_HASH_PARAMS: Lazy<GetStateRootHashParams> =
     Lazy::new(|| GetStateRootHashParams {
         block_identifier: BlockIdentifier::Height(Block::doc_example().header().height()),
@@ -69,6 +117,57 @@ static GET_ERA_SUMMARY_RESULT: Lazy<GetEraSummaryResult> = Lazy::new(|| GetEraSu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: ERA_SUMMARY.clone(),
 });
+static GET_ERA_TRANSITION_PROOF_PARAMS: Lazy<GetEraTransitionProofParams> =
+    Lazy::new(|| GetEraTransitionProofParams {
+        era_id: EraId::new(1),
+    });
+static GET_ERA_TRANSITION_PROOF_RESULT: Lazy<GetEraTransitionProofResult> =
+    Lazy::new(|| GetEraTransitionProofResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        switch_block_hash: *Block::doc_example().hash(),
+        era_id: EraId::new(1),
+        era_end_hash: Digest::from([7; Digest::LENGTH]),
+        proof_system: "groth16-bn254".to_string(),
+        circuit_id: "era-transition-v1-l20916".to_string(),
+        proof: base16::encode_lower(&[9; 16]),
+    });
+static GET_BLOCK_RANDOMNESS_PARAMS: Lazy<GetBlockRandomnessParams> =
+    Lazy::new(|| GetBlockRandomnessParams {
+        block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+    });
+static GET_BLOCK_RANDOMNESS_RESULT: Lazy<GetBlockRandomnessResult> = Lazy::new(|| {
+    let vrf_public_key = [7; 32];
+    let public_key = PublicKey::doc_example().clone();
+    let vrf_public_key_signature = crypto::sign(
+        randomness_beacon::binding_message(&vrf_public_key),
+        SecretKey::doc_example(),
+        &public_key,
+    );
+    GetBlockRandomnessResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        output: base16::encode_lower(&[3; 32]),
+        vrf_output: base16::encode_lower(&[5; 32]),
+        vrf_proof: base16::encode_lower(&[9; 64]),
+        vrf_public_key: base16::encode_lower(&vrf_public_key),
+        public_key,
+        vrf_public_key_signature,
+    }
+});
+static GET_STATE_DIFF_PARAMS: Lazy<GetStateDiffParams> = Lazy::new(|| GetStateDiffParams {
+    from_block_identifier: BlockIdentifier::Height(Block::doc_example().header().height() - 1),
+    to_block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+});
+static GET_STATE_DIFF_RESULT: Lazy<GetStateDiffResult> = Lazy::new(|| GetStateDiffResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    from_state_root_hash: Digest::from([7u8; Digest::LENGTH]),
+    to_state_root_hash: *Block::doc_example().header().state_root_hash(),
+    changes: vec![JsonStateChange::Modified {
+        key: Key::URef(URef::new([1; 32], AccessRights::READ_ADD_WRITE)).to_formatted_string(),
+        old_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
+        new_value: StoredValue::CLValue(CLValue::from_t(2u64).unwrap()),
+    }],
+});
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -276,6 +375,230 @@ impl RpcWithOptionalParams for GetBlockTransfers {
     }
 }
 
+/// Params for "chain_get_transfers_by_account" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountParams {
+    /// The account the transfers were made from or to.
+    pub account_identifier: AccountIdentifier,
+    /// The cursor returned with the previous page, not set to get the first page.
+    pub cursor: Option<String>,
+    /// The maximum number of transfers to return, 100 if not set.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetTransfersByAccountParams {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_PARAMS
+    }
+}
+
+/// A native transfer from or to an account, along with the block it was executed in.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonAccountTransfer {
+    /// The hash of the block the transfer was executed in.
+    pub block_hash: BlockHash,
+    /// The height of the block the transfer was executed in.
+    pub block_height: u64,
+    /// The transfer.
+    pub transfer: Transfer,
+}
+
+/// Result for "chain_get_transfers_by_account" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The transfers from or to the account, most recent first.
+    pub transfers: Vec<JsonAccountTransfer>,
+    /// The cursor to pass to get the next page, not set if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetTransfersByAccountResult {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_RESULT
+    }
+}
+
+/// "chain_get_transfers_by_account" RPC.
+pub struct GetTransfersByAccount {}
+
+#[async_trait]
+impl RpcWithParams for GetTransfersByAccount {
+    const METHOD: &'static str = "chain_get_transfers_by_account";
+    type RequestParams = GetTransfersByAccountParams;
+    type ResponseResult = GetTransfersByAccountResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let page_size = state::page_size(params.page_size)?;
+        let after = params
+            .cursor
+            .as_deref()
+            .map(decode_transfer_cursor)
+            .transpose()?;
+        let account_hash = match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        };
+
+        // One more transfer than requested is read to find out whether there is a next page.
+        let mut account_transfers = effect_builder
+            .get_transfers_by_account_from_storage(account_hash, after, page_size + 1)
+            .await;
+        let next_cursor = if account_transfers.len() > page_size {
+            account_transfers.truncate(page_size);
+            account_transfers
+                .last()
+                .map(|account_transfer| encode_transfer_cursor(&account_transfer.position()))
+        } else {
+            None
+        };
+        let transfers = account_transfers
+            .into_iter()
+            .map(|account_transfer| JsonAccountTransfer {
+                block_hash: account_transfer.block_hash,
+                block_height: account_transfer.block_height,
+                transfer: account_transfer.transfer,
+            })
+            .collect();
+
+        Ok(Self::ResponseResult {
+            api_version,
+            transfers,
+            next_cursor,
+        })
+    }
+}
+
+/// Encodes the position of the last transfer of a page as an opaque hex-encoded token.
+fn encode_transfer_cursor(position: &TransferPosition) -> String {
+    let bytes = (position.block_height, position.deploy_hash, position.index)
+        .to_bytes()
+        .expect("should serialize transfer cursor");
+    base16::encode_lower(&bytes)
+}
+
+/// Decodes a token returned by `encode_transfer_cursor`.
+fn decode_transfer_cursor(cursor: &str) -> Result<TransferPosition, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorCode::InvalidPageCursor,
+            format!("'{}' is not a valid page cursor", cursor),
+        )
+    };
+    let bytes = base16::decode(cursor).map_err(|_| invalid())?;
+    let (block_height, deploy_hash, index) =
+        bytesrepr::deserialize::<(u64, casper_types::DeployHash, u32)>(bytes)
+            .map_err(|_| invalid())?;
+    Ok(TransferPosition {
+        block_height,
+        deploy_hash,
+        index,
+    })
+}
+
+/// Params for "chain_get_block_execution_results" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockExecutionResultsParams {
+    /// The block hash.
+    pub block_identifier: BlockIdentifier,
+}
+
+impl DocExample for GetBlockExecutionResultsParams {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_EXECUTION_RESULTS_PARAMS
+    }
+}
+
+/// The execution result of a single deploy in a block.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeployExecutionResult {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Execution result.
+    pub result: ExecutionResult,
+}
+
+/// Result for "chain_get_block_execution_results" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockExecutionResultsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block hash, if found.
+    pub block_hash: Option<BlockHash>,
+    /// The execution results of the block's deploys and transfers, in the order they are listed
+    /// in the block, if found.
+    pub execution_results: Option<Vec<DeployExecutionResult>>,
+}
+
+impl DocExample for GetBlockExecutionResultsResult {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_EXECUTION_RESULTS_RESULT
+    }
+}
+
+/// "chain_get_block_execution_results" RPC.
+pub struct GetBlockExecutionResults {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetBlockExecutionResults {
+    const METHOD: &'static str = "chain_get_block_execution_results";
+    type OptionalRequestParams = GetBlockExecutionResultsParams;
+    type ResponseResult = GetBlockExecutionResultsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        // Get the block.
+        let maybe_block_id = maybe_params.map(|params| params.block_identifier);
+        let block_hash = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await
+        .map(|block| *block.hash())?;
+
+        let execution_results = effect_builder
+            .get_execution_results_for_block_from_storage(block_hash)
+            .await
+            .map(|execution_results| {
+                execution_results
+                    .into_iter()
+                    .map(|(deploy_hash, result)| DeployExecutionResult {
+                        deploy_hash,
+                        result,
+                    })
+                    .collect()
+            });
+
+        // Return the result.
+        let result = Self::ResponseResult {
+            api_version,
+            block_hash: Some(block_hash),
+            execution_results,
+        };
+        Ok(result)
+    }
+}
+
 /// Params for "chain_get_state_root_hash" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -470,7 +793,370 @@ impl RpcWithOptionalParams for GetEraSummary {
     }
 }
 
-pub(super) async fn get_block_with_metadata<REv: ReactorEventT>(
+/// Params for "chain_get_era_transition_proof" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraTransitionProofParams {
+    /// The era ended by the switch block.
+    pub era_id: EraId,
+}
+
+impl DocExample for GetEraTransitionProofParams {
+    fn doc_example() -> &'static Self {
+        &GET_ERA_TRANSITION_PROOF_PARAMS
+    }
+}
+
+/// Result for "chain_get_era_transition_proof" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraTransitionProofResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The hash of the switch block ending the era.
+    pub switch_block_hash: BlockHash,
+    /// The era ended by the switch block.
+    pub era_id: EraId,
+    /// The hash of the switch block's serialized era end, which lists the validators of the next
+    /// era.
+    pub era_end_hash: Digest,
+    /// The proof system the proof was generated with.
+    pub proof_system: String,
+    /// The ID of the circuit, whose verifying key checks the proof.
+    pub circuit_id: String,
+    /// The hex-encoded zero-knowledge proof.
+    pub proof: String,
+}
+
+impl GetEraTransitionProofResult {
+    pub(crate) fn new(api_version: ProtocolVersion, proof: EraTransitionProof) -> Self {
+        GetEraTransitionProofResult {
+            api_version,
+            switch_block_hash: BlockHash::new(proof.switch_block_hash),
+            era_id: proof.era_id,
+            era_end_hash: proof.era_end_hash,
+            proof_system: proof.proof_system.to_string(),
+            circuit_id: proof.circuit_id,
+            proof: base16::encode_lower(proof.proof.bytes()),
+        }
+    }
+}
+
+impl DocExample for GetEraTransitionProofResult {
+    fn doc_example() -> &'static Self {
+        &GET_ERA_TRANSITION_PROOF_RESULT
+    }
+}
+
+/// "chain_get_era_transition_proof" RPC.
+///
+/// The proof shows which validators the switch block ending the era sets for the next era, but
+/// not that the switch block is finalized.
+pub struct GetEraTransitionProof {}
+
+#[async_trait]
+impl RpcWithParams for GetEraTransitionProof {
+    const METHOD: &'static str = "chain_get_era_transition_proof";
+    type RequestParams = GetEraTransitionProofParams;
+    type ResponseResult = GetEraTransitionProofResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let proof = match effect_builder
+            .get_era_transition_proof_from_storage(params.era_id)
+            .await
+        {
+            Some(proof) => proof,
+            None => {
+                let message = format!("no era transition proof for {} stored yet", params.era_id);
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchEraTransitionProof, message));
+            }
+        };
+        Ok(Self::ResponseResult::new(api_version, proof))
+    }
+}
+
+/// Params for "chain_get_block_randomness" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockRandomnessParams {
+    /// The block identifier.
+    pub block_identifier: BlockIdentifier,
+}
+
+impl DocExample for GetBlockRandomnessParams {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_RANDOMNESS_PARAMS
+    }
+}
+
+/// Result for "chain_get_block_randomness" RPC response.
+///
+/// The VRF is schnorrkel's sr25519 VRF, evaluated with the signing context
+/// `casper-randomness-beacon` on the block hash. The output is derived from the VRF output with
+/// the context `casper-randomness-beacon-output`.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockRandomnessResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The hash of the block the VRF was evaluated on.
+    pub block_hash: BlockHash,
+    /// The hex-encoded random output.
+    pub output: String,
+    /// The hex-encoded VRF output, from which the random output is derived.
+    pub vrf_output: String,
+    /// The hex-encoded VRF proof.
+    pub vrf_proof: String,
+    /// The hex-encoded sr25519 public key to verify the VRF proof with.
+    pub vrf_public_key: String,
+    /// The public key of the node.
+    pub public_key: PublicKey,
+    /// The node's signature of `casper-randomness-beacon-key-binding` followed by the VRF public
+    /// key.
+    pub vrf_public_key_signature: Signature,
+}
+
+impl GetBlockRandomnessResult {
+    fn new(api_version: ProtocolVersion, randomness: BlockRandomness) -> Self {
+        GetBlockRandomnessResult {
+            api_version,
+            block_hash: randomness.block_hash,
+            output: base16::encode_lower(&randomness.output),
+            vrf_output: base16::encode_lower(&randomness.vrf_output),
+            vrf_proof: base16::encode_lower(&randomness.vrf_proof),
+            vrf_public_key: base16::encode_lower(&randomness.vrf_public_key),
+            public_key: randomness.public_key,
+            vrf_public_key_signature: randomness.vrf_public_key_signature,
+        }
+    }
+}
+
+impl DocExample for GetBlockRandomnessResult {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_RANDOMNESS_RESULT
+    }
+}
+
+/// "chain_get_block_randomness" RPC.
+///
+/// The output is not part of consensus. Clients should combine the outputs of several nodes other
+/// than the block's proposer, as the proposer may have influenced its own node's output.
+pub struct GetBlockRandomness {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetBlockRandomness {
+    const METHOD: &'static str = "chain_get_block_randomness";
+    type OptionalRequestParams = GetBlockRandomnessParams;
+    type ResponseResult = GetBlockRandomnessResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        let maybe_block_id = maybe_params.map(|params| params.block_identifier);
+        let block_hash = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await
+        .map(|block| *block.hash())?;
+
+        let randomness = match effect_builder.get_block_randomness(block_hash).await {
+            Some(randomness) => randomness,
+            None => {
+                let message = "the randomness beacon is disabled on this node".to_string();
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::RandomnessBeaconDisabled, message));
+            }
+        };
+        Ok(Self::ResponseResult::new(api_version, randomness))
+    }
+}
+
+/// Params for "chain_get_state_diff" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffParams {
+    /// The block whose post-state to diff from.
+    pub from_block_identifier: BlockIdentifier,
+    /// The block whose post-state to diff to.
+    pub to_block_identifier: BlockIdentifier,
+}
+
+impl DocExample for GetStateDiffParams {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_PARAMS
+    }
+}
+
+/// A change of the value stored under a key of global state.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum JsonStateChange {
+    /// A value was stored under a key which had none.
+    Created {
+        /// The key as a formatted string.
+        key: String,
+        /// The stored value.
+        value: StoredValue,
+    },
+    /// The value stored under a key was replaced.
+    Modified {
+        /// The key as a formatted string.
+        key: String,
+        /// The value stored before.
+        old_value: StoredValue,
+        /// The value stored after.
+        new_value: StoredValue,
+    },
+    /// The value stored under a key was removed.
+    Deleted {
+        /// The key as a formatted string.
+        key: String,
+        /// The value stored before.
+        old_value: StoredValue,
+    },
+}
+
+impl TryFrom<StateChange> for JsonStateChange {
+    type Error = bytesrepr::Error;
+
+    fn try_from(state_change: StateChange) -> Result<Self, Self::Error> {
+        Ok(match state_change {
+            StateChange::Created { key, value } => JsonStateChange::Created {
+                key: key.to_formatted_string(),
+                value: StoredValue::try_from(value)?,
+            },
+            StateChange::Modified {
+                key,
+                old_value,
+                new_value,
+            } => JsonStateChange::Modified {
+                key: key.to_formatted_string(),
+                old_value: StoredValue::try_from(old_value)?,
+                new_value: StoredValue::try_from(new_value)?,
+            },
+            StateChange::Deleted { key, old_value } => JsonStateChange::Deleted {
+                key: key.to_formatted_string(),
+                old_value: StoredValue::try_from(old_value)?,
+            },
+        })
+    }
+}
+
+/// Result for "chain_get_state_diff" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hex-encoded hash of the state root diffed from.
+    pub from_state_root_hash: Digest,
+    /// Hex-encoded hash of the state root diffed to.
+    pub to_state_root_hash: Digest,
+    /// The changes needed to get from the state of the first block to the state of the second
+    /// one, ordered by the serialized keys.
+    pub changes: Vec<JsonStateChange>,
+}
+
+impl DocExample for GetStateDiffResult {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_RESULT
+    }
+}
+
+/// "chain_get_state_diff" RPC.
+pub struct GetStateDiff {}
+
+#[async_trait]
+impl RpcWithParams for GetStateDiff {
+    const METHOD: &'static str = "chain_get_state_diff";
+    type RequestParams = GetStateDiffParams;
+    type ResponseResult = GetStateDiffResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        let from_block = common::get_block(
+            Some(params.from_block_identifier),
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let to_block = common::get_block(
+            Some(params.to_block_identifier),
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let from_root = *from_block.state_root_hash();
+        let to_root = *to_block.state_root_hash();
+
+        let changes = match effect_builder.get_state_diff(from_root, to_root).await {
+            Ok(StateDiffResult::Success { changes }) => changes,
+            Ok(StateDiffResult::RootNotFound) => {
+                info!(%from_root, %to_root, "state diff failed: root not found");
+                let error = common::missing_block_or_state_root_error(
+                    effect_builder,
+                    ErrorCode::NoSuchStateRoot,
+                    format!("failed to diff state roots {} and {}", from_root, to_root),
+                )
+                .await;
+                return Err(error);
+            }
+            Err(error) => {
+                info!(?error, "state diff failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let changes = match changes
+            .into_iter()
+            .map(JsonStateChange::try_from)
+            .collect::<Result<_, _>>()
+        {
+            Ok(changes) => changes,
+            Err(error) => {
+                warn!(?error, "failed to encode stored value");
+                return Err(Error::new(
+                    ReservedErrorCode::InternalError,
+                    format!("failed to encode stored value: {}", error),
+                ));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            from_state_root_hash: from_root,
+            to_state_root_hash: to_root,
+            changes,
+        };
+        Ok(result)
+    }
+}
+
+pub(crate) async fn get_block_with_metadata<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     only_from_available_block_range: bool,
     effect_builder: EffectBuilder<REv>,
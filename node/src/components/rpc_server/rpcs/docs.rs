@@ -18,12 +18,19 @@ use casper_types::ProtocolVersion;
 use super::{
     account::PutDeploy,
     chain::{
-        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+        GetBlock, GetBlockExecutionResults, GetBlockRandomness, GetBlockTransfers,
+        GetEraInfoBySwitchBlock, GetEraSummary, GetEraTransitionProof, GetStateDiff,
+        GetStateRootHash, GetTransfersByAccount,
+    },
+    info::{
+        GetAccountDeploys, GetChainspec, GetDeploy, GetDeployProfile, GetEquivocations,
+        GetLeaderSchedule, GetPeers, GetStatus, GetValidatorChanges, GetValidatorPerformance,
+        WaitDeployProcessed,
     },
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetBidsPaged, GetDelegatorsPaged,
+        GetDictionaryItem, GetItem, GetItemZk, QueryBalance, QueryGlobalState,
+        QueryGlobalStateMulti,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -70,11 +77,23 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns the hashes of the Deploys sent by an Account which are stored on the node",
+    );
+    schema.push_with_params::<GetDeployProfile>(
+        "returns a breakdown of the gas charged during the most recent execution of a Deploy",
+    );
+    schema.push_with_params::<WaitDeployProcessed>(
+        "waits until a Deploy has been executed and returns its execution result",
+    );
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
     schema.push_with_params::<QueryGlobalState>(
         "a query to global state using either a Block hash or state root hash",
     );
+    schema.push_with_params::<QueryGlobalStateMulti>(
+        "multiple queries to global state under a single Block hash or state root hash",
+    );
     schema.push_with_params::<QueryBalance>(
         "query for a balance using a purse identifier and a state identifier",
     );
@@ -82,6 +101,16 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
+    schema.push_with_params::<GetEquivocations>(
+        "returns the equivocations observed by the node, with the conflicting messages",
+    );
+    schema.push_with_params::<GetValidatorPerformance>(
+        "returns the proposed blocks, finality signatures and missed rounds of each validator in \
+        an era, as observed by the node",
+    );
+    schema.push_without_params::<GetLeaderSchedule>(
+        "returns the leaders of the upcoming consensus rounds in the current era",
+    );
     schema.push_without_params::<GetChainspec>(
         "returns the raw bytes of the chainspec.toml, genesis accounts.toml, and \
         global_state.toml files",
@@ -90,6 +119,12 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_optional_params::<GetBlockTransfers>(
         "returns all transfers for a Block from the network",
     );
+    schema.push_with_params::<GetTransfersByAccount>(
+        "returns a page of the native transfers from or to an account, most recent first",
+    );
+    schema.push_with_optional_params::<GetBlockExecutionResults>(
+        "returns the execution results of all Deploys in a Block from the network",
+    );
     schema.push_with_optional_params::<GetStateRootHash>(
         "returns a state root hash at a given Block",
     );
@@ -97,6 +132,10 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         "returns a stored value from the network. This RPC is deprecated, use \
         `query_global_state` instead.",
     );
+    schema.push_with_params::<GetItemZk>(
+        "returns a stored value along with a zero-knowledge proof of it being stored under the \
+        state root hash, once generated; only available if enabled in the node's config",
+    );
     schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
@@ -105,10 +144,28 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         "returns the bids and validators as of either a specific block (by height or hash), or \
         the most recently added block",
     );
+    schema.push_with_params::<GetBidsPaged>(
+        "returns a page of bids without their delegators, as of either a specific block (by height \
+        or hash), or the most recently added block",
+    );
+    schema.push_with_params::<GetDelegatorsPaged>(
+        "returns a page of the delegators of a validator's bid, as of either a specific block (by \
+        height or hash), or the most recently added block",
+    );
     schema.push_with_optional_params::<GetEraSummary>(
         "returns the era summary at either a specific block (by height or hash), or the most \
         recently added block",
     );
+    schema
+        .push_with_params::<GetStateDiff>("returns the changes of global state between two Blocks");
+    schema.push_with_params::<GetEraTransitionProof>(
+        "returns a zero-knowledge proof of the validators set for the next era by the switch block \
+        ending an era",
+    );
+    schema.push_with_optional_params::<GetBlockRandomness>(
+        "returns a random output for either a specific block (by height or hash), or the most \
+        recently added block, with a VRF proof by this node",
+    );
 
     schema
 });
@@ -556,6 +613,12 @@ mod tests {
         assert!(incorrect_optional_params.is_empty())
     }
 
+    #[test]
+    fn check_chain_get_block_execution_results_required_fields() {
+        let incorrect_optional_params = check_optional_params_fields::<GetBlockExecutionResults>();
+        assert!(incorrect_optional_params.is_empty())
+    }
+
     #[test]
     fn check_chain_get_state_root_hash_required_fields() {
         let incorrect_optional_params = check_optional_params_fields::<GetStateRootHash>();
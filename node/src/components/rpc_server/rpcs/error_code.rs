@@ -35,6 +35,36 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The requested gas profile was not found.
+    NoSuchGasProfile = -32013,
+    /// The requested validator performance report was not found.
+    NoSuchValidatorPerformance = -32014,
+    /// The leader schedule is not available because consensus is not running.
+    NoLeaderSchedule = -32015,
+    /// The connection has reached the maximum number of subscriptions.
+    TooManySubscriptions = -32016,
+    /// The requested validator has no bid.
+    NoSuchBid = -32017,
+    /// The given page cursor is invalid.
+    InvalidPageCursor = -32018,
+    /// The requested method requires a valid API key.
+    Unauthorized = -32019,
+    /// The speculative execution did not complete in time.
+    SpeculativeExecTimedOut = -32020,
+    /// The deploy waited for was not processed in time.
+    WaitDeployProcessedTimedOut = -32021,
+    /// The maximum number of requests waiting for a deploy to be processed has been reached.
+    TooManyDeployWaiters = -32022,
+    /// The zero-knowledge prover's queue is full.
+    ProvingQueueFull = -32023,
+    /// The requested item cannot be proven by the zero-knowledge prover.
+    UnprovableItem = -32024,
+    /// The requested era transition proof was not found.
+    NoSuchEraTransitionProof = -32025,
+    /// The priority hint of the proof request exceeded the maximum.
+    InvalidPriorityHint = -32026,
+    /// The randomness beacon is disabled on this node.
+    RandomnessBeaconDisabled = -32027,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +89,31 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::NoSuchGasProfile => (error_code as i64, "No such gas profile"),
+            ErrorCode::NoSuchValidatorPerformance => {
+                (error_code as i64, "No such validator performance report")
+            }
+            ErrorCode::NoLeaderSchedule => (error_code as i64, "No leader schedule"),
+            ErrorCode::TooManySubscriptions => (error_code as i64, "Too many subscriptions"),
+            ErrorCode::NoSuchBid => (error_code as i64, "No such bid"),
+            ErrorCode::InvalidPageCursor => (error_code as i64, "Invalid page cursor"),
+            ErrorCode::Unauthorized => (error_code as i64, "Unauthorized"),
+            ErrorCode::SpeculativeExecTimedOut => {
+                (error_code as i64, "Speculative execution timed out")
+            }
+            ErrorCode::WaitDeployProcessedTimedOut => {
+                (error_code as i64, "Waiting for deploy timed out")
+            }
+            ErrorCode::TooManyDeployWaiters => (error_code as i64, "Too many deploy waiters"),
+            ErrorCode::ProvingQueueFull => (error_code as i64, "Proving queue full"),
+            ErrorCode::UnprovableItem => (error_code as i64, "Unprovable item"),
+            ErrorCode::NoSuchEraTransitionProof => {
+                (error_code as i64, "No such era transition proof")
+            }
+            ErrorCode::InvalidPriorityHint => (error_code as i64, "Invalid priority hint"),
+            ErrorCode::RandomnessBeaconDisabled => {
+                (error_code as i64, "Randomness beacon disabled")
+            }
         }
     }
 }
@@ -3,22 +3,36 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{collections::BTreeMap, str};
+use std::{collections::BTreeMap, str, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use futures::Future;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info};
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_execution_engine::core::engine_state::GasProfile;
+use casper_hashing::Digest;
+use casper_json_rpc::ReservedErrorCode;
+use casper_types::{
+    crypto, EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, Signature, TimeDiff,
+    Timestamp, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::{
+        consensus::{
+            ClContext, ConflictingMessage, EquivocationRecord, ScheduledRound, ValidatorChange,
+            ValidatorPerformanceReport,
+        },
+        event_stream_server::SseData,
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
@@ -40,6 +54,56 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
     }],
     block_hash_and_height: None,
 });
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        public_key: Deploy::doc_example().header().account().clone(),
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hashes: vec![*Deploy::doc_example().hash()],
+    });
+static GET_DEPLOY_PROFILE_PARAMS: Lazy<GetDeployProfileParams> =
+    Lazy::new(|| GetDeployProfileParams {
+        deploy_hash: *Deploy::doc_example().hash(),
+    });
+static GET_DEPLOY_PROFILE_RESULT: Lazy<GetDeployProfileResult> =
+    Lazy::new(|| GetDeployProfileResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().hash(),
+        gas_profile: JsonGasProfile {
+            wasm_instructions: U512::from(1_234_567),
+            host_functions: vec![
+                JsonHostFunctionUsage {
+                    name: "casper_get_named_arg".to_string(),
+                    calls: 2,
+                    cost: U512::from(400),
+                },
+                JsonHostFunctionUsage {
+                    name: "casper_put_key".to_string(),
+                    calls: 1,
+                    cost: U512::from(38_000),
+                },
+            ],
+            storage: U512::from(2_050_000),
+            system_contract_calls: U512::from(10_000),
+            total: U512::from(3_332_967),
+        },
+    });
+static WAIT_DEPLOY_PROCESSED_PARAMS: Lazy<WaitDeployProcessedParams> =
+    Lazy::new(|| WaitDeployProcessedParams {
+        deploy_hash: *Deploy::doc_example().hash(),
+        timeout: Some(TimeDiff::from_seconds(30)),
+    });
+static WAIT_DEPLOY_PROCESSED_RESULT: Lazy<WaitDeployProcessedResult> =
+    Lazy::new(|| WaitDeployProcessedResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().hash(),
+        execution_result: JsonExecutionResult {
+            block_hash: *Block::doc_example().hash(),
+            result: ExecutionResult::example().clone(),
+        },
+    });
 static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     peers: GetStatusResult::doc_example().peers.clone(),
@@ -53,6 +117,60 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_EQUIVOCATIONS_PARAMS: Lazy<GetEquivocationsParams> =
+    Lazy::new(|| GetEquivocationsParams {
+        from_era_id: Some(EraId::new(1)),
+        public_key: Some(PublicKey::doc_example().clone()),
+    });
+static GET_EQUIVOCATIONS_RESULT: Lazy<GetEquivocationsResult> = Lazy::new(|| {
+    let secret_key = SecretKey::doc_example();
+    let public_key = PublicKey::doc_example().clone();
+    let echo = |proposal_hash: Digest| JsonConflictingMessage::Echo {
+        round_id: 3,
+        proposal_hash,
+        signature: crypto::sign(proposal_hash, secret_key, &public_key),
+    };
+    let conflicting_messages = vec![
+        echo(*Deploy::doc_example().hash().inner()),
+        echo(*Block::doc_example().hash().inner()),
+    ];
+    GetEquivocationsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        equivocations: vec![JsonEquivocation {
+            era_id: EraId::new(1),
+            public_key: public_key.clone(),
+            detected_at: Block::doc_example().header().timestamp(),
+            conflicting_messages,
+        }],
+    }
+});
+static GET_VALIDATOR_PERFORMANCE_PARAMS: Lazy<GetValidatorPerformanceParams> =
+    Lazy::new(|| GetValidatorPerformanceParams {
+        era_id: EraId::new(1),
+    });
+static GET_VALIDATOR_PERFORMANCE_RESULT: Lazy<GetValidatorPerformanceResult> =
+    Lazy::new(|| GetValidatorPerformanceResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_id: EraId::new(1),
+        validators: vec![JsonValidatorPerformance {
+            public_key: PublicKey::doc_example().clone(),
+            proposed_blocks: 12,
+            finality_signatures: 47,
+            rounds_missed: 1,
+        }],
+    });
+static GET_LEADER_SCHEDULE_RESULT: Lazy<GetLeaderScheduleResult> = Lazy::new(|| {
+    let start = Block::doc_example().header().timestamp();
+    GetLeaderScheduleResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_id: EraId::new(1),
+        rounds: vec![JsonScheduledRound {
+            round_id: start.millis(),
+            start: Some(start),
+            public_key: PublicKey::doc_example().clone(),
+        }],
+    }
+});
 static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     chainspec_bytes: ChainspecRawBytes::new(vec![42, 42].into(), None, None),
@@ -177,6 +295,365 @@ impl RpcWithParams for GetDeploy {
     }
 }
 
+/// Params for "info_get_account_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The public key of the account.
+    pub public_key: PublicKey,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "info_get_account_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The hashes of the deploys sent by the account which are stored on this node.
+    pub deploy_hashes: Vec<DeployHash>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_account_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+#[async_trait]
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "info_get_account_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hashes = effect_builder
+            .get_deploy_hashes_by_account_from_storage(params.public_key)
+            .await;
+        let result = Self::ResponseResult {
+            api_version,
+            deploy_hashes,
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "info_get_deploy_profile" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployProfileParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetDeployProfileParams {
+    fn doc_example() -> &'static Self {
+        &GET_DEPLOY_PROFILE_PARAMS
+    }
+}
+
+/// The calls to a host function and the gas charged for them.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonHostFunctionUsage {
+    /// The name the host function is imported under.
+    pub name: String,
+    /// The number of calls.
+    pub calls: u64,
+    /// The gas charged for all calls.
+    pub cost: U512,
+}
+
+/// A breakdown of the gas charged while executing a deploy's payment and session code.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonGasProfile {
+    /// The gas charged for executing Wasm instructions.
+    pub wasm_instructions: U512,
+    /// The calls to and gas charged for each host function.
+    pub host_functions: Vec<JsonHostFunctionUsage>,
+    /// The gas charged for storing data in global state.
+    pub storage: U512,
+    /// The gas charged for calling system contract entry points.
+    pub system_contract_calls: U512,
+    /// The total gas charged.
+    pub total: U512,
+}
+
+impl From<&GasProfile> for JsonGasProfile {
+    fn from(gas_profile: &GasProfile) -> Self {
+        JsonGasProfile {
+            wasm_instructions: gas_profile.wasm_instructions().value(),
+            host_functions: gas_profile
+                .host_functions()
+                .iter()
+                .map(|(name, usage)| JsonHostFunctionUsage {
+                    name: name.to_string(),
+                    calls: usage.calls,
+                    cost: usage.cost.value(),
+                })
+                .collect(),
+            storage: gas_profile.storage().value(),
+            system_contract_calls: gas_profile.system_contract_calls().value(),
+            total: gas_profile.total().value(),
+        }
+    }
+}
+
+/// Result for "info_get_deploy_profile" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployProfileResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The breakdown of the gas charged during the most recent execution of the deploy.
+    pub gas_profile: JsonGasProfile,
+}
+
+impl DocExample for GetDeployProfileResult {
+    fn doc_example() -> &'static Self {
+        &GET_DEPLOY_PROFILE_RESULT
+    }
+}
+
+/// "info_get_deploy_profile" RPC.
+pub struct GetDeployProfile {}
+
+#[async_trait]
+impl RpcWithParams for GetDeployProfile {
+    const METHOD: &'static str = "info_get_deploy_profile";
+    type RequestParams = GetDeployProfileParams;
+    type ResponseResult = GetDeployProfileResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let gas_profile = match effect_builder
+            .get_deploy_gas_profile(params.deploy_hash)
+            .await
+        {
+            Some(gas_profile) => gas_profile,
+            None => {
+                let message = format!(
+                    "no gas profile of {} kept, or gas profiling disabled",
+                    params.deploy_hash
+                );
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchGasProfile, message));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            deploy_hash: params.deploy_hash,
+            gas_profile: JsonGasProfile::from(&gas_profile),
+        };
+        Ok(result)
+    }
+}
+
+/// The time an "info_wait_deploy_processed" request waits if no timeout is given.
+const DEFAULT_WAIT_DEPLOY_PROCESSED_TIMEOUT: Duration = Duration::from_secs(60);
+/// The longest time an "info_wait_deploy_processed" request waits.
+const MAX_WAIT_DEPLOY_PROCESSED_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Params for "info_wait_deploy_processed" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WaitDeployProcessedParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// How long to wait for the deploy to be processed, at most 5 minutes. If omitted, waits
+    /// for 1 minute.
+    #[serde(default)]
+    pub timeout: Option<TimeDiff>,
+}
+
+impl DocExample for WaitDeployProcessedParams {
+    fn doc_example() -> &'static Self {
+        &WAIT_DEPLOY_PROCESSED_PARAMS
+    }
+}
+
+/// Result for "info_wait_deploy_processed" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WaitDeployProcessedResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The execution result of the deploy.
+    pub execution_result: JsonExecutionResult,
+}
+
+impl DocExample for WaitDeployProcessedResult {
+    fn doc_example() -> &'static Self {
+        &WAIT_DEPLOY_PROCESSED_RESULT
+    }
+}
+
+/// "info_wait_deploy_processed" RPC.
+///
+/// Responds once the given deploy has been executed, or with an error once the timeout elapsed,
+/// so that clients need not poll "info_get_deploy" repeatedly.
+pub struct WaitDeployProcessed {}
+
+#[async_trait]
+impl RpcWithParams for WaitDeployProcessed {
+    const METHOD: &'static str = "info_wait_deploy_processed";
+    type RequestParams = WaitDeployProcessedParams;
+    type ResponseResult = WaitDeployProcessedResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = params.deploy_hash;
+        let timeout = params
+            .timeout
+            .map_or(DEFAULT_WAIT_DEPLOY_PROCESSED_TIMEOUT, Duration::from)
+            .min(MAX_WAIT_DEPLOY_PROCESSED_TIMEOUT);
+
+        // Subscribe before checking storage, so that the deploy cannot be processed unnoticed in
+        // between.
+        let (notifications, _permit) = effect_builder
+            .make_request(
+                |responder| RpcRequest::SubscribeNotifications { responder },
+                QueueKind::Api,
+            )
+            .await
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorCode::TooManyDeployWaiters,
+                    "the maximum number of requests waiting for a deploy has been reached",
+                )
+            })?;
+        let execution_result =
+            wait_for_execution_result(notifications, deploy_hash, timeout, || {
+                get_stored_execution_result(effect_builder, deploy_hash)
+            })
+            .await?;
+        Ok(Self::ResponseResult {
+            api_version,
+            deploy_hash,
+            execution_result,
+        })
+    }
+}
+
+/// Waits for `notifications` to announce that the given deploy has been processed, for at most
+/// `timeout`.
+///
+/// The execution result is taken from storage via `get_stored` instead if it is already there, or
+/// if some notifications were missed.
+async fn wait_for_execution_result<F, Fut>(
+    mut notifications: broadcast::Receiver<Arc<SseData>>,
+    deploy_hash: DeployHash,
+    timeout: Duration,
+    get_stored: F,
+) -> Result<JsonExecutionResult, Error>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Option<JsonExecutionResult>>,
+{
+    let wait = async {
+        if let Some(execution_result) = get_stored().await {
+            return Some(execution_result);
+        }
+        loop {
+            match notifications.recv().await {
+                Ok(data) => {
+                    if let SseData::DeployProcessed {
+                        deploy_hash: processed,
+                        block_hash,
+                        execution_result,
+                        ..
+                    } = &*data
+                    {
+                        if **processed == deploy_hash {
+                            return Some(JsonExecutionResult {
+                                block_hash: **block_hash,
+                                result: (**execution_result).clone(),
+                            });
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    // The deploy may have been among the skipped events.
+                    debug!(%deploy_hash, skipped, "lagged behind waiting for deploy");
+                    if let Some(execution_result) = get_stored().await {
+                        return Some(execution_result);
+                    }
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, wait).await {
+        Ok(Some(execution_result)) => Ok(execution_result),
+        Ok(None) => Err(Error::new(
+            ReservedErrorCode::InternalError,
+            "stopped waiting for deploy as the node is shutting down",
+        )),
+        Err(_) => {
+            let message = format!(
+                "{} was not processed within {}",
+                deploy_hash,
+                TimeDiff::from(timeout)
+            );
+            info!("{}", message);
+            Err(Error::new(ErrorCode::WaitDeployProcessedTimedOut, message))
+        }
+    }
+}
+
+/// Returns the execution result of the given deploy if it is held in storage.
+async fn get_stored_execution_result<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    deploy_hash: DeployHash,
+) -> Option<JsonExecutionResult> {
+    let deploy_and_metadata = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetDeploy {
+                hash: deploy_hash,
+                finalized_approvals: false,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await?;
+    match deploy_and_metadata.1 {
+        DeployMetadataExt::Metadata(metadata) => metadata
+            .execution_results
+            .into_iter()
+            .next()
+            .map(|(block_hash, result)| JsonExecutionResult { block_hash, result }),
+        DeployMetadataExt::BlockInfo(_) | DeployMetadataExt::Empty => None,
+    }
+}
+
 /// Result for "info_get_peers" RPC response.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -348,6 +825,346 @@ impl RpcWithoutParams for GetValidatorChanges {
     }
 }
 
+/// Params for "info_get_equivocations" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEquivocationsParams {
+    /// If set, only equivocations in this era or later are returned.
+    pub from_era_id: Option<EraId>,
+    /// If set, only equivocations by the validator with this public key are returned.
+    pub public_key: Option<PublicKey>,
+}
+
+impl DocExample for GetEquivocationsParams {
+    fn doc_example() -> &'static Self {
+        &GET_EQUIVOCATIONS_PARAMS
+    }
+}
+
+/// A message signed by an equivocating validator, which conflicts with another one of theirs.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum JsonConflictingMessage {
+    /// A Highway unit.
+    Unit {
+        /// The unit's hash.
+        hash: Digest,
+        /// The unit's sequence number in its creator's swimlane.
+        seq_number: u64,
+        /// The unit's timestamp.
+        timestamp: Timestamp,
+        /// The creator's signature of the unit's hash.
+        signature: Signature,
+    },
+    /// A Highway endorsement.
+    Endorsement {
+        /// The hash of the endorsed unit.
+        unit: Digest,
+        /// The endorser's signature.
+        signature: Signature,
+    },
+    /// A Zug echo of a round's proposal.
+    Echo {
+        /// The round.
+        round_id: u32,
+        /// The hash of the echoed proposal.
+        proposal_hash: Digest,
+        /// The validator's signature.
+        signature: Signature,
+    },
+    /// A Zug vote in a round.
+    Vote {
+        /// The round.
+        round_id: u32,
+        /// Whether the vote was in favor of the round's proposal.
+        vote: bool,
+        /// The validator's signature.
+        signature: Signature,
+    },
+}
+
+impl From<&ConflictingMessage<ClContext>> for JsonConflictingMessage {
+    fn from(message: &ConflictingMessage<ClContext>) -> Self {
+        match *message {
+            ConflictingMessage::Unit {
+                hash,
+                seq_number,
+                timestamp,
+                signature,
+            } => JsonConflictingMessage::Unit {
+                hash,
+                seq_number,
+                timestamp,
+                signature,
+            },
+            ConflictingMessage::Endorsement { unit, signature } => {
+                JsonConflictingMessage::Endorsement { unit, signature }
+            }
+            ConflictingMessage::Echo {
+                round_id,
+                proposal_hash,
+                signature,
+            } => JsonConflictingMessage::Echo {
+                round_id,
+                proposal_hash,
+                signature,
+            },
+            ConflictingMessage::Vote {
+                round_id,
+                vote,
+                signature,
+            } => JsonConflictingMessage::Vote {
+                round_id,
+                vote,
+                signature,
+            },
+        }
+    }
+}
+
+/// An equivocation observed by the node.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonEquivocation {
+    /// The era in which the validator equivocated.
+    era_id: EraId,
+    /// The public key of the equivocating validator.
+    public_key: PublicKey,
+    /// When the node first observed the equivocation.
+    detected_at: Timestamp,
+    /// The conflicting messages signed by the validator. Empty if the evidence was not available.
+    conflicting_messages: Vec<JsonConflictingMessage>,
+}
+
+impl From<&EquivocationRecord> for JsonEquivocation {
+    fn from(record: &EquivocationRecord) -> Self {
+        JsonEquivocation {
+            era_id: record.era_id,
+            public_key: record.validator.clone(),
+            detected_at: record.detected_at,
+            conflicting_messages: record
+                .conflicting_messages
+                .iter()
+                .map(JsonConflictingMessage::from)
+                .collect(),
+        }
+    }
+}
+
+/// Result for "info_get_equivocations" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEquivocationsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The equivocations recorded by this node, ordered by era.
+    pub equivocations: Vec<JsonEquivocation>,
+}
+
+impl DocExample for GetEquivocationsResult {
+    fn doc_example() -> &'static Self {
+        &GET_EQUIVOCATIONS_RESULT
+    }
+}
+
+/// "info_get_equivocations" RPC.
+pub struct GetEquivocations {}
+
+#[async_trait]
+impl RpcWithParams for GetEquivocations {
+    const METHOD: &'static str = "info_get_equivocations";
+    type RequestParams = GetEquivocationsParams;
+    type ResponseResult = GetEquivocationsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let records = effect_builder
+            .get_equivocations_from_storage(
+                params.from_era_id.unwrap_or_default(),
+                params.public_key,
+            )
+            .await;
+        let result = Self::ResponseResult {
+            api_version,
+            equivocations: records.iter().map(JsonEquivocation::from).collect(),
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "info_get_validator_performance" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetValidatorPerformanceParams {
+    /// The era to report on.
+    pub era_id: EraId,
+}
+
+impl DocExample for GetValidatorPerformanceParams {
+    fn doc_example() -> &'static Self {
+        &GET_VALIDATOR_PERFORMANCE_PARAMS
+    }
+}
+
+/// A validator's contributions to consensus in an era, as observed by the node.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonValidatorPerformance {
+    /// The public key of the validator.
+    public_key: PublicKey,
+    /// The number of the era's finalized blocks proposed by the validator.
+    proposed_blocks: u64,
+    /// The number of finality signatures by the validator for the era's blocks.
+    finality_signatures: u64,
+    /// The number of rounds led by the validator that did not produce a finalized block.
+    rounds_missed: u64,
+}
+
+/// Result for "info_get_validator_performance" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetValidatorPerformanceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The era the report is about.
+    pub era_id: EraId,
+    /// The performance of each of the era's validators.
+    pub validators: Vec<JsonValidatorPerformance>,
+}
+
+impl GetValidatorPerformanceResult {
+    pub(crate) fn new(api_version: ProtocolVersion, report: ValidatorPerformanceReport) -> Self {
+        let validators = report
+            .validators
+            .into_iter()
+            .map(|(public_key, performance)| JsonValidatorPerformance {
+                public_key,
+                proposed_blocks: performance.proposed_blocks,
+                finality_signatures: performance.finality_signatures,
+                rounds_missed: performance.rounds_missed,
+            })
+            .collect();
+        GetValidatorPerformanceResult {
+            api_version,
+            era_id: report.era_id,
+            validators,
+        }
+    }
+}
+
+impl DocExample for GetValidatorPerformanceResult {
+    fn doc_example() -> &'static Self {
+        &GET_VALIDATOR_PERFORMANCE_RESULT
+    }
+}
+
+/// "info_get_validator_performance" RPC.
+pub struct GetValidatorPerformance {}
+
+#[async_trait]
+impl RpcWithParams for GetValidatorPerformance {
+    const METHOD: &'static str = "info_get_validator_performance";
+    type RequestParams = GetValidatorPerformanceParams;
+    type ResponseResult = GetValidatorPerformanceResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let report = match effect_builder
+            .get_validator_performance_from_storage(params.era_id)
+            .await
+        {
+            Some(report) => report,
+            None => {
+                let message = format!(
+                    "no validator performance report for {} stored yet",
+                    params.era_id
+                );
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchValidatorPerformance, message));
+            }
+        };
+        Ok(Self::ResponseResult::new(api_version, report))
+    }
+}
+
+/// An upcoming consensus round, with the validator expected to propose a block in it.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonScheduledRound {
+    /// The round ID.
+    round_id: u64,
+    /// The time the round starts, if the consensus protocol's rounds are tied to fixed times.
+    start: Option<Timestamp>,
+    /// The public key of the round's leader.
+    public_key: PublicKey,
+}
+
+impl From<ScheduledRound<PublicKey>> for JsonScheduledRound {
+    fn from(round: ScheduledRound<PublicKey>) -> Self {
+        JsonScheduledRound {
+            round_id: round.round_id,
+            start: round.start,
+            public_key: round.leader,
+        }
+    }
+}
+
+/// Result for "consensus_get_leader_schedule" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetLeaderScheduleResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The current era.
+    pub era_id: EraId,
+    /// The upcoming rounds of the current era, with their leaders, starting with the current one.
+    pub rounds: Vec<JsonScheduledRound>,
+}
+
+impl DocExample for GetLeaderScheduleResult {
+    fn doc_example() -> &'static Self {
+        &GET_LEADER_SCHEDULE_RESULT
+    }
+}
+
+/// "consensus_get_leader_schedule" RPC.
+pub struct GetLeaderSchedule {}
+
+#[async_trait]
+impl RpcWithoutParams for GetLeaderSchedule {
+    const METHOD: &'static str = "consensus_get_leader_schedule";
+    type ResponseResult = GetLeaderScheduleResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (era_id, rounds) = match effect_builder.get_consensus_leader_schedule().await {
+            Some(schedule) => schedule,
+            None => {
+                let message = "consensus is not running".to_string();
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoLeaderSchedule, message));
+            }
+        };
+        let result = Self::ResponseResult {
+            api_version,
+            era_id,
+            rounds: rounds.into_iter().map(JsonScheduledRound::from).collect(),
+        };
+        Ok(result)
+    }
+}
+
 /// Result for the "info_get_chainspec" RPC.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetChainspecResult {
@@ -390,3 +1207,56 @@ impl RpcWithoutParams for GetChainspec {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_wait_for_deploy_processed() {
+        let mut rng = crate::new_rng();
+        let deploy_processed = Arc::new(SseData::random_deploy_processed(&mut rng));
+        let (deploy_hash, block_hash) = match &*deploy_processed {
+            SseData::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                ..
+            } => (**deploy_hash, **block_hash),
+            _ => unreachable!(),
+        };
+        let (notifications, receiver) = broadcast::channel(10);
+        let wait = tokio::spawn(wait_for_execution_result(
+            receiver,
+            deploy_hash,
+            Duration::from_secs(60),
+            || future::ready(None),
+        ));
+
+        notifications
+            .send(Arc::new(SseData::random_block_added(&mut rng)))
+            .unwrap();
+        notifications.send(deploy_processed).unwrap();
+        let execution_result = wait.await.unwrap().expect("should be processed");
+        assert_eq!(execution_result.block_hash, block_hash);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_deploy_processed() {
+        let mut rng = crate::new_rng();
+        let (notifications, receiver) = broadcast::channel(10);
+        let wait = tokio::spawn(wait_for_execution_result(
+            receiver,
+            DeployHash::random(&mut rng),
+            Duration::from_millis(100),
+            || future::ready(None),
+        ));
+
+        notifications
+            .send(Arc::new(SseData::random_deploy_processed(&mut rng)))
+            .unwrap();
+        let error = wait.await.unwrap().expect_err("should time out");
+        assert_eq!(error.code(), ErrorCode::WaitDeployProcessedTimedOut as i64);
+    }
+}
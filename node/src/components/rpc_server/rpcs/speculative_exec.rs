@@ -21,7 +21,10 @@ use super::{
     Error, ErrorCode, ReactorEventT, RpcWithParams,
 };
 use crate::{
-    components::contract_runtime::SpeculativeExecutionState,
+    components::{
+        contract_runtime::SpeculativeExecutionState,
+        speculative_executor::SpeculativeExecutionError,
+    },
     effect::EffectBuilder,
     types::{Block, BlockHash, Deploy},
 };
@@ -103,6 +106,7 @@ impl RpcWithParams for SpeculativeExec {
             state_root_hash: *block.state_root_hash(),
             block_time: block.timestamp(),
             protocol_version: block.protocol_version(),
+            era_id: block.header().era_id(),
         };
 
         let accept_deploy_result = effect_builder
@@ -130,7 +134,19 @@ impl RpcWithParams for SpeculativeExec {
                 ErrorCode::NoSuchBlock,
                 "block hash not found".to_string(),
             )),
-            Err(error) => {
+            Err(
+                error @ SpeculativeExecutionError::InvalidPaymentAmount
+                | error @ SpeculativeExecutionError::GasLimitExceeded { .. },
+            ) => Err(Error::new(ErrorCode::InvalidDeploy, error.to_string())),
+            Err(error @ SpeculativeExecutionError::TimedOut(_)) => Err(Error::new(
+                ErrorCode::SpeculativeExecTimedOut,
+                error.to_string(),
+            )),
+            Err(error @ SpeculativeExecutionError::Aborted) => Err(Error::new(
+                ReservedErrorCode::InternalError,
+                error.to_string(),
+            )),
+            Err(SpeculativeExecutionError::EngineState(error)) => {
                 let rpc_error = match error {
                     EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
                     EngineStateError::WasmPreprocessing(error) => {
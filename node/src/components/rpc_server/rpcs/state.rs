@@ -3,9 +3,10 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{collections::BTreeMap, ops::Bound, str};
 
 use async_trait::async_trait;
+use futures::future;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -19,12 +20,18 @@ use casper_hashing::Digest;
 use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
-    bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    bytesrepr::{self, Bytes, ToBytes},
+    merkle_proof::TrieProofStep,
+    system::auction::{Bids, Delegator},
+    AccessRights, CLValue, Key, ProtocolVersion, PublicKey, SecretKey,
+    StoredValue as DomainStoredValue, URef, U512,
 };
 
 use crate::{
+    components::zk_prover::{
+        Circuit, JobStatus, Priority, ProvingJob, StateInclusion, StateInclusionError,
+        SubmitJobError,
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{
@@ -34,11 +41,21 @@ use crate::{
         Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithParams,
     },
     types::{
-        json_compatibility::{Account as JsonAccount, AuctionState, StoredValue},
+        json_compatibility::{
+            Account as JsonAccount, AuctionState, JsonBidSummary, JsonDelegator, StoredValue,
+        },
         Block, BlockHash, JsonBlockHeader,
     },
 };
 
+/// The number of entries of a page of bids, delegators or transfers if not specified by the
+/// client.
+pub(super) const DEFAULT_PAGE_SIZE: u32 = 100;
+/// The maximum number of entries of a page of bids, delegators or transfers.
+const MAX_PAGE_SIZE: u32 = 1_000;
+/// The maximum number of queries of a single "query_global_state_multi" RPC request.
+const MAX_GLOBAL_STATE_QUERIES: usize = 100;
+
 static GET_ITEM_PARAMS: Lazy<GetItemParams> = Lazy::new(|| GetItemParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
@@ -49,6 +66,19 @@ static GET_ITEM_RESULT: Lazy<GetItemResult> = Lazy::new(|| GetItemResult {
     stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
     merkle_proof: MERKLE_PROOF.clone(),
 });
+static GET_ITEM_ZK_PARAMS: Lazy<GetItemZkParams> = Lazy::new(|| GetItemZkParams {
+    state_root_hash: *Block::doc_example().header().state_root_hash(),
+    key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
+    priority_hint: Some(10),
+});
+static GET_ITEM_ZK_RESULT: Lazy<GetItemZkResult> = Lazy::new(|| GetItemZkResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
+    merkle_proof: MERKLE_PROOF.clone(),
+    leaf_hash: Digest::from([7; Digest::LENGTH]),
+    circuit_id: "state-inclusion-v1-d8-l8705".to_string(),
+    zk_proof: ZkProofStatus::Queued,
+});
 static GET_BALANCE_PARAMS: Lazy<GetBalanceParams> = Lazy::new(|| GetBalanceParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     purse_uref: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
@@ -66,6 +96,53 @@ static GET_AUCTION_INFO_RESULT: Lazy<GetAuctionInfoResult> = Lazy::new(|| GetAuc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     auction_state: AuctionState::doc_example().clone(),
 });
+static GET_BIDS_PAGED_PARAMS: Lazy<GetBidsPagedParams> = Lazy::new(|| GetBidsPagedParams {
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    cursor: None,
+    page_size: Some(DEFAULT_PAGE_SIZE),
+});
+static GET_BIDS_PAGED_RESULT: Lazy<GetBidsPagedResult> = Lazy::new(|| {
+    let auction_state = AuctionState::doc_example();
+    GetBidsPagedResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        state_root_hash: auction_state.state_root_hash,
+        block_height: auction_state.block_height,
+        bids: Bids::doc_example()
+            .iter()
+            .map(|(public_key, bid)| JsonBidSummary::new(public_key.clone(), bid))
+            .collect(),
+        next_cursor: None,
+    }
+});
+static GET_DELEGATORS_PAGED_PARAMS: Lazy<GetDelegatorsPagedParams> =
+    Lazy::new(|| GetDelegatorsPagedParams {
+        validator_public_key: Bids::doc_example().keys().next().unwrap().clone(),
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        cursor: None,
+        page_size: Some(1),
+    });
+static GET_DELEGATORS_PAGED_RESULT: Lazy<GetDelegatorsPagedResult> = Lazy::new(|| {
+    let auction_state = AuctionState::doc_example();
+    let delegator_public_key = PublicKey::doc_example().clone();
+    let delegator = Delegator::unlocked(
+        delegator_public_key.clone(),
+        U512::from(10),
+        URef::new([250; 32], AccessRights::READ_ADD_WRITE),
+        GET_DELEGATORS_PAGED_PARAMS.validator_public_key.clone(),
+    );
+    let next_cursor = PageCursor {
+        state_root_hash: auction_state.state_root_hash,
+        block_height: auction_state.block_height,
+        last_public_key: delegator_public_key.clone(),
+    };
+    GetDelegatorsPagedResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        state_root_hash: auction_state.state_root_hash,
+        block_height: auction_state.block_height,
+        delegators: vec![JsonDelegator::new(delegator_public_key, &delegator)],
+        next_cursor: Some(next_cursor.encode()),
+    }
+});
 static GET_ACCOUNT_INFO_PARAMS: Lazy<GetAccountInfoParams> = Lazy::new(|| {
     let secret_key = SecretKey::ed25519_from_bytes([0; 32]).unwrap();
     let public_key = PublicKey::from(&secret_key);
@@ -112,6 +189,40 @@ static QUERY_GLOBAL_STATE_RESULT: Lazy<QueryGlobalStateResult> =
         stored_value: StoredValue::Account(JsonAccount::doc_example().clone()),
         merkle_proof: MERKLE_PROOF.clone(),
     });
+static QUERY_GLOBAL_STATE_MULTI_PARAMS: Lazy<QueryGlobalStateMultiParams> =
+    Lazy::new(|| QueryGlobalStateMultiParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        queries: vec![
+            GlobalStateQuery {
+                key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1"
+                    .to_string(),
+                path: vec!["inner".to_string()],
+            },
+            GlobalStateQuery {
+                key:
+                    "account-hash-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c"
+                        .to_string(),
+                path: vec![],
+            },
+        ],
+    });
+static QUERY_GLOBAL_STATE_MULTI_RESULT: Lazy<QueryGlobalStateMultiResult> =
+    Lazy::new(|| QueryGlobalStateMultiResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_header: Some(JsonBlockHeader::doc_example().clone()),
+        results: vec![
+            GlobalStateQueryResult {
+                stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
+                merkle_proof: MERKLE_PROOF.clone(),
+            },
+            GlobalStateQueryResult {
+                stored_value: StoredValue::Account(JsonAccount::doc_example().clone()),
+                merkle_proof: MERKLE_PROOF.clone(),
+            },
+        ],
+    });
 static GET_TRIE_PARAMS: Lazy<GetTrieParams> = Lazy::new(|| GetTrieParams {
     trie_key: *Block::doc_example().header().state_root_hash(),
 });
@@ -211,6 +322,169 @@ impl RpcWithParams for GetItem {
     }
 }
 
+/// Params for "state_get_item_zk" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetItemZkParams {
+    /// Hash of the state root.
+    pub state_root_hash: Digest,
+    /// `casper_types::Key` as formatted string.
+    pub key: String,
+    /// Orders the request among other third-party proof requests, higher first, 0 if not set.
+    /// Proofs the node needs itself always take precedence.
+    pub priority_hint: Option<u64>,
+}
+
+impl DocExample for GetItemZkParams {
+    fn doc_example() -> &'static Self {
+        &GET_ITEM_ZK_PARAMS
+    }
+}
+
+/// The status of a zero-knowledge proof.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum ZkProofStatus {
+    /// The proof is waiting to be generated.
+    Queued,
+    /// The proof is being generated.
+    Running,
+    /// The hex-encoded proof.
+    Proven(String),
+    /// Generating the proof failed, with the error.
+    Failed(String),
+}
+
+/// Result for "state_get_item_zk" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetItemZkResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The stored value.
+    pub stored_value: StoredValue,
+    /// The Merkle proof.
+    pub merkle_proof: String,
+    /// The hash of the trie leaf holding the stored value under the key.
+    pub leaf_hash: Digest,
+    /// The ID of the circuit, whose verifying key checks the proof.
+    pub circuit_id: String,
+    /// The zero-knowledge proof that the leaf is in the trie under the state root hash.
+    pub zk_proof: ZkProofStatus,
+}
+
+impl DocExample for GetItemZkResult {
+    fn doc_example() -> &'static Self {
+        &GET_ITEM_ZK_RESULT
+    }
+}
+
+/// "state_get_item_zk" RPC.
+///
+/// Proving takes minutes, so the proof is generated in the background: the request returns the
+/// status of the proof, and repeating it returns the proof once generated.
+pub struct GetItemZk {}
+
+#[async_trait]
+impl RpcWithParams for GetItemZk {
+    const METHOD: &'static str = "state_get_item_zk";
+    type RequestParams = GetItemZkParams;
+    type ResponseResult = GetItemZkResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let key = match Key::from_formatted_str(&params.key)
+            .map_err(|error| format!("failed to parse key: {}", error))
+        {
+            Ok(key) => key,
+            Err(error_msg) => {
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::FailedToParseQueryKey, error_msg));
+            }
+        };
+
+        let (value, proofs) =
+            run_query(effect_builder, params.state_root_hash, key, vec![]).await?;
+        let merkle_proof = match proofs.last() {
+            Some(merkle_proof) => merkle_proof,
+            None => {
+                warn!(%key, "query returned no Merkle proof");
+                return Err(Error::new(
+                    ReservedErrorCode::InternalError,
+                    "query returned no Merkle proof",
+                ));
+            }
+        };
+        let proof_steps: Vec<TrieProofStep> = merkle_proof
+            .proof_steps()
+            .iter()
+            .map(TrieProofStep::from)
+            .collect();
+        let circuit = match StateInclusion::new(
+            params.state_root_hash,
+            merkle_proof.key(),
+            merkle_proof.value(),
+            &proof_steps,
+        ) {
+            Ok(circuit) => circuit,
+            Err(error @ StateInclusionError::TooDeep { .. }) => {
+                info!(%error, "cannot prove item");
+                return Err(Error::new(ErrorCode::UnprovableItem, error.to_string()));
+            }
+            Err(error) => {
+                warn!(%error, "failed to create state inclusion circuit");
+                return Err(Error::new(
+                    ReservedErrorCode::InternalError,
+                    error.to_string(),
+                ));
+            }
+        };
+        let leaf_hash = circuit.statement().leaf_hash;
+        let circuit_id = circuit.id();
+
+        let priority = Priority::ThirdParty {
+            hint: params.priority_hint.unwrap_or(0),
+        };
+        let zk_proof = match effect_builder
+            .submit_proving_job(ProvingJob::from(circuit), priority)
+            .await
+        {
+            Ok(JobStatus::Queued) => ZkProofStatus::Queued,
+            Ok(JobStatus::Running) => ZkProofStatus::Running,
+            Ok(JobStatus::Finished(Ok(proof))) => {
+                ZkProofStatus::Proven(base16::encode_lower(proof.bytes()))
+            }
+            Ok(JobStatus::Finished(Err(error))) => ZkProofStatus::Failed(error.to_string()),
+            Err(error @ SubmitJobError::QueueFull) => {
+                info!(%error, "cannot prove item");
+                return Err(Error::new(ErrorCode::ProvingQueueFull, error.to_string()));
+            }
+            Err(error @ SubmitJobError::PriorityHintTooHigh { .. }) => {
+                info!(%error, "cannot prove item");
+                return Err(Error::new(
+                    ErrorCode::InvalidPriorityHint,
+                    error.to_string(),
+                ));
+            }
+        };
+
+        let (stored_value, merkle_proof) = common::encode_query_success(value, proofs)?;
+        let result = Self::ResponseResult {
+            api_version,
+            stored_value,
+            merkle_proof,
+            leaf_hash,
+            circuit_id,
+            zk_proof,
+        };
+        Ok(result)
+    }
+}
+
 /// Params for "state_get_balance" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -468,6 +742,329 @@ impl RpcWithOptionalParams for GetAuctionInfo {
     }
 }
 
+/// The position after which the next page of bids or delegators starts.
+///
+/// Handed to clients as an opaque hex-encoded token.  It pins the state root hash of the first
+/// page, so that all pages are consistent even if new blocks are added meanwhile.
+#[derive(Debug, PartialEq)]
+struct PageCursor {
+    state_root_hash: Digest,
+    block_height: u64,
+    last_public_key: PublicKey,
+}
+
+impl PageCursor {
+    fn encode(&self) -> String {
+        let bytes = (
+            self.state_root_hash,
+            self.block_height,
+            self.last_public_key.clone(),
+        )
+            .to_bytes()
+            .expect("should serialize page cursor");
+        base16::encode_lower(&bytes)
+    }
+
+    fn decode(cursor: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::new(
+                ErrorCode::InvalidPageCursor,
+                format!("'{}' is not a valid page cursor", cursor),
+            )
+        };
+        let bytes = base16::decode(cursor).map_err(|_| invalid())?;
+        let (state_root_hash, block_height, last_public_key) =
+            bytesrepr::deserialize::<(Digest, u64, PublicKey)>(bytes).map_err(|_| invalid())?;
+        Ok(PageCursor {
+            state_root_hash,
+            block_height,
+            last_public_key,
+        })
+    }
+}
+
+/// Returns the requested number of entries of a page, or the default if not specified.
+pub(super) fn page_size(maybe_page_size: Option<u32>) -> Result<usize, Error> {
+    match maybe_page_size.unwrap_or(DEFAULT_PAGE_SIZE) {
+        page_size @ 1..=MAX_PAGE_SIZE => Ok(page_size as usize),
+        page_size => Err(Error::new(
+            ReservedErrorCode::InvalidParams,
+            format!(
+                "page_size must be between 1 and {}, but was {}",
+                MAX_PAGE_SIZE, page_size
+            ),
+        )),
+    }
+}
+
+/// Returns the entries of `map` after `after`, at most `page_size` of them, along with the key of
+/// the last returned entry if there are more entries.
+fn paginate<'a, V>(
+    map: &'a BTreeMap<PublicKey, V>,
+    after: Option<&PublicKey>,
+    page_size: usize,
+) -> (Vec<(&'a PublicKey, &'a V)>, Option<&'a PublicKey>) {
+    let mut entries = match after {
+        Some(after) => map.range((Bound::Excluded(after), Bound::Unbounded)),
+        None => map.range::<PublicKey, _>(..),
+    };
+    let page: Vec<_> = entries.by_ref().take(page_size).collect();
+    let last_public_key = match entries.next() {
+        Some(_) => page.last().map(|(public_key, _)| *public_key),
+        None => None,
+    };
+    (page, last_public_key)
+}
+
+/// Returns the bids at the position given by `cursor`, or else at the given block, along with the
+/// state root hash and height of the block they were read at.
+async fn get_bids_for_page<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    block_identifier: Option<BlockIdentifier>,
+    cursor: Option<&PageCursor>,
+) -> Result<(Bids, Digest, u64), Error> {
+    let (state_root_hash, block_height) = match cursor {
+        Some(cursor) => (cursor.state_root_hash, cursor.block_height),
+        None => {
+            // This RPC request is restricted by the block availability index.
+            let only_from_available_block_range = true;
+            let block = common::get_block(
+                block_identifier,
+                only_from_available_block_range,
+                effect_builder,
+            )
+            .await?;
+            (*block.header().state_root_hash(), block.header().height())
+        }
+    };
+
+    let get_bids_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetBids {
+                state_root_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    match get_bids_result {
+        Ok(GetBidsResult::Success { bids }) => Ok((bids, state_root_hash, block_height)),
+        Ok(GetBidsResult::RootNotFound) => {
+            info!(?state_root_hash, "root not found while trying to get bids");
+            Err(common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::NoSuchStateRoot,
+                format!("state root {} not known", state_root_hash),
+            )
+            .await)
+        }
+        Err(error) => {
+            error!(?state_root_hash, ?error, "failed to get bids");
+            Err(Error::new(
+                ReservedErrorCode::InternalError,
+                format!(
+                    "error getting bids at state root {}: {}",
+                    state_root_hash, error
+                ),
+            ))
+        }
+    }
+}
+
+/// Params for "state_get_bids_paged" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBidsPagedParams {
+    /// The block identifier, the most recently added block if not set.  Ignored if a cursor is
+    /// given.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The cursor returned with the previous page, not set to get the first page.
+    pub cursor: Option<String>,
+    /// The maximum number of bids to return, 100 if not set.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetBidsPagedParams {
+    fn doc_example() -> &'static Self {
+        &GET_BIDS_PAGED_PARAMS
+    }
+}
+
+/// Result for "state_get_bids_paged" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBidsPagedResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The state root hash the bids were read at.
+    pub state_root_hash: Digest,
+    /// The height of the block the bids were read at.
+    pub block_height: u64,
+    /// The bids of this page, ordered by the validators' public keys.
+    pub bids: Vec<JsonBidSummary>,
+    /// The cursor to get the next page with, not set if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetBidsPagedResult {
+    fn doc_example() -> &'static Self {
+        &GET_BIDS_PAGED_RESULT
+    }
+}
+
+/// "state_get_bids_paged" RPC.
+pub struct GetBidsPaged {}
+
+#[async_trait]
+impl RpcWithParams for GetBidsPaged {
+    const METHOD: &'static str = "state_get_bids_paged";
+    type RequestParams = GetBidsPagedParams;
+    type ResponseResult = GetBidsPagedResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let page_size = page_size(params.page_size)?;
+        let cursor = params
+            .cursor
+            .as_deref()
+            .map(PageCursor::decode)
+            .transpose()?;
+
+        let (bids, state_root_hash, block_height) =
+            get_bids_for_page(effect_builder, params.block_identifier, cursor.as_ref()).await?;
+        let after = cursor.as_ref().map(|cursor| &cursor.last_public_key);
+        let (page, last_public_key) = paginate(&bids, after, page_size);
+
+        let next_cursor = last_public_key.map(|last_public_key| {
+            PageCursor {
+                state_root_hash,
+                block_height,
+                last_public_key: last_public_key.clone(),
+            }
+            .encode()
+        });
+        let bids = page
+            .into_iter()
+            .map(|(public_key, bid)| JsonBidSummary::new(public_key.clone(), bid))
+            .collect();
+
+        Ok(Self::ResponseResult {
+            api_version,
+            state_root_hash,
+            block_height,
+            bids,
+            next_cursor,
+        })
+    }
+}
+
+/// Params for "state_get_delegators_paged" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDelegatorsPagedParams {
+    /// The public key of the validator whose delegators are returned.
+    pub validator_public_key: PublicKey,
+    /// The block identifier, the most recently added block if not set.  Ignored if a cursor is
+    /// given.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The cursor returned with the previous page, not set to get the first page.
+    pub cursor: Option<String>,
+    /// The maximum number of delegators to return, 100 if not set.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetDelegatorsPagedParams {
+    fn doc_example() -> &'static Self {
+        &GET_DELEGATORS_PAGED_PARAMS
+    }
+}
+
+/// Result for "state_get_delegators_paged" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDelegatorsPagedResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The state root hash the delegators were read at.
+    pub state_root_hash: Digest,
+    /// The height of the block the delegators were read at.
+    pub block_height: u64,
+    /// The delegators of this page, ordered by their public keys.
+    pub delegators: Vec<JsonDelegator>,
+    /// The cursor to get the next page with, not set if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetDelegatorsPagedResult {
+    fn doc_example() -> &'static Self {
+        &GET_DELEGATORS_PAGED_RESULT
+    }
+}
+
+/// "state_get_delegators_paged" RPC.
+pub struct GetDelegatorsPaged {}
+
+#[async_trait]
+impl RpcWithParams for GetDelegatorsPaged {
+    const METHOD: &'static str = "state_get_delegators_paged";
+    type RequestParams = GetDelegatorsPagedParams;
+    type ResponseResult = GetDelegatorsPagedResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let page_size = page_size(params.page_size)?;
+        let cursor = params
+            .cursor
+            .as_deref()
+            .map(PageCursor::decode)
+            .transpose()?;
+
+        let (bids, state_root_hash, block_height) =
+            get_bids_for_page(effect_builder, params.block_identifier, cursor.as_ref()).await?;
+        let bid = bids.get(&params.validator_public_key).ok_or_else(|| {
+            Error::new(
+                ErrorCode::NoSuchBid,
+                format!(
+                    "no bid of validator {} at state root {}",
+                    params.validator_public_key, state_root_hash
+                ),
+            )
+        })?;
+        let after = cursor.as_ref().map(|cursor| &cursor.last_public_key);
+        let (page, last_public_key) = paginate(bid.delegators(), after, page_size);
+
+        let next_cursor = last_public_key.map(|last_public_key| {
+            PageCursor {
+                state_root_hash,
+                block_height,
+                last_public_key: last_public_key.clone(),
+            }
+            .encode()
+        });
+        let delegators = page
+            .into_iter()
+            .map(|(public_key, delegator)| JsonDelegator::new(public_key.clone(), delegator))
+            .collect();
+
+        Ok(Self::ResponseResult {
+            api_version,
+            state_root_hash,
+            block_height,
+            delegators,
+            next_cursor,
+        })
+    }
+}
+
 /// Identifier of an account.
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(deny_unknown_fields, untagged)]
@@ -812,7 +1409,7 @@ pub struct QueryGlobalStateResult {
     /// The RPC API version.
     #[schemars(with = "String")]
     pub api_version: ProtocolVersion,
-    /// The block header if a Block hash was provided.
+    /// The header of the block whose post-state was queried, if known.
     pub block_header: Option<JsonBlockHeader>,
     /// The stored value.
     pub stored_value: StoredValue,
@@ -840,39 +1437,9 @@ impl RpcWithParams for QueryGlobalState {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
-        let (state_root_hash, maybe_block_header) = match params.state_identifier {
-            None => match effect_builder
-                .get_highest_complete_block_header_from_storage()
-                .await
-            {
-                None => {
-                    return Err(Error::new(
-                        ErrorCode::NoSuchBlock,
-                        "query-global-state failed to retrieve highest block header",
-                    ))
-                }
-                Some(block_header) => (
-                    *block_header.state_root_hash(),
-                    Some(JsonBlockHeader::from(block_header.clone())),
-                ),
-            },
-            Some(state_identifier) => {
-                let (state_root_hash, maybe_block_header) =
-                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
-                        .await?;
-                (state_root_hash, maybe_block_header)
-            }
-        };
-
-        let base_key = match Key::from_formatted_str(&params.key)
-            .map_err(|error| format!("failed to parse key: {}", error))
-        {
-            Ok(key) => key,
-            Err(error_msg) => {
-                info!("{}", error_msg);
-                return Err(Error::new(ErrorCode::FailedToParseQueryKey, error_msg));
-            }
-        };
+        let (state_root_hash, maybe_block_header) =
+            get_state_root_hash_or_highest(effect_builder, params.state_identifier).await?;
+        let base_key = parse_query_key(&params.key)?;
 
         let (stored_value, merkle_proof) =
             common::run_query_and_encode(effect_builder, state_root_hash, base_key, params.path)
@@ -888,6 +1455,119 @@ impl RpcWithParams for QueryGlobalState {
     }
 }
 
+/// A single query of "query_global_state_multi" RPC request.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalStateQuery {
+    /// `casper_types::Key` as formatted string.
+    pub key: String,
+    /// The path components starting from the key as base.
+    #[serde(default)]
+    pub path: Vec<String>,
+}
+
+/// Params for "query_global_state_multi" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryGlobalStateMultiParams {
+    /// The identifier used for all the queries. If none is passed the tip of the chain will be
+    /// used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The queries to run, at most 100.
+    pub queries: Vec<GlobalStateQuery>,
+}
+
+impl DocExample for QueryGlobalStateMultiParams {
+    fn doc_example() -> &'static Self {
+        &QUERY_GLOBAL_STATE_MULTI_PARAMS
+    }
+}
+
+/// The result of a single query of "query_global_state_multi" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalStateQueryResult {
+    /// The stored value.
+    pub stored_value: StoredValue,
+    /// The Merkle proof.
+    pub merkle_proof: String,
+}
+
+/// Result for "query_global_state_multi" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryGlobalStateMultiResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The header of the block whose post-state was queried, if known.
+    pub block_header: Option<JsonBlockHeader>,
+    /// The results of the queries, in the order they were requested.
+    pub results: Vec<GlobalStateQueryResult>,
+}
+
+impl DocExample for QueryGlobalStateMultiResult {
+    fn doc_example() -> &'static Self {
+        &QUERY_GLOBAL_STATE_MULTI_RESULT
+    }
+}
+
+/// "query_global_state_multi" RPC
+pub struct QueryGlobalStateMulti {}
+
+#[async_trait]
+impl RpcWithParams for QueryGlobalStateMulti {
+    const METHOD: &'static str = "query_global_state_multi";
+    type RequestParams = QueryGlobalStateMultiParams;
+    type ResponseResult = QueryGlobalStateMultiResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        if params.queries.is_empty() || params.queries.len() > MAX_GLOBAL_STATE_QUERIES {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "between 1 and {} queries are allowed, but {} were given",
+                    MAX_GLOBAL_STATE_QUERIES,
+                    params.queries.len()
+                ),
+            ));
+        }
+
+        // Parse all keys up front, so that no queries are run for an invalid request.
+        let queries = params
+            .queries
+            .into_iter()
+            .map(|query| Ok((parse_query_key(&query.key)?, query.path)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let (state_root_hash, maybe_block_header) =
+            get_state_root_hash_or_highest(effect_builder, params.state_identifier).await?;
+
+        let results =
+            future::try_join_all(queries.into_iter().map(|(base_key, path)| async move {
+                let (stored_value, merkle_proof) =
+                    common::run_query_and_encode(effect_builder, state_root_hash, base_key, path)
+                        .await?;
+                Ok::<_, Error>(GlobalStateQueryResult {
+                    stored_value,
+                    merkle_proof,
+                })
+            }))
+            .await?;
+
+        let result = Self::ResponseResult {
+            api_version,
+            block_header: maybe_block_header,
+            results,
+        };
+        Ok(result)
+    }
+}
+
 /// Identifier of a purse.
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
@@ -1107,7 +1787,7 @@ type QuerySuccess = (
 /// value.
 ///
 /// On error, a `warp_json_rpc::Error` is returned suitable for sending as a JSON-RPC response.
-pub(super) async fn run_query<REv: ReactorEventT>(
+pub(crate) async fn run_query<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     state_root_hash: Digest,
     base_key: Key,
@@ -1186,6 +1866,41 @@ async fn get_account<REv: ReactorEventT>(
     }
 }
 
+/// Parses a key of a global state query given as formatted string.
+fn parse_query_key(key: &str) -> Result<Key, Error> {
+    Key::from_formatted_str(key).map_err(|error| {
+        let error_msg = format!("failed to parse key: {}", error);
+        info!("{}", error_msg);
+        Error::new(ErrorCode::FailedToParseQueryKey, error_msg)
+    })
+}
+
+/// Returns the state root hash and block header for the given state identifier, or for the highest
+/// complete block if none is given.
+pub(crate) async fn get_state_root_hash_or_highest<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    maybe_state_identifier: Option<GlobalStateIdentifier>,
+) -> Result<(Digest, Option<JsonBlockHeader>), Error> {
+    match maybe_state_identifier {
+        None => match effect_builder
+            .get_highest_complete_block_header_from_storage()
+            .await
+        {
+            None => Err(Error::new(
+                ErrorCode::NoSuchBlock,
+                "query-global-state failed to retrieve highest block header",
+            )),
+            Some(block_header) => Ok((
+                *block_header.state_root_hash(),
+                Some(JsonBlockHeader::from(block_header)),
+            )),
+        },
+        Some(state_identifier) => {
+            get_state_root_hash_and_optional_header(effect_builder, state_identifier).await
+        }
+    }
+}
+
 pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     state_identifier: GlobalStateIdentifier,
@@ -1228,6 +1943,64 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                 }
             }
         }
-        GlobalStateIdentifier::StateRootHash(state_root_hash) => Ok((state_root_hash, None)),
+        GlobalStateIdentifier::StateRootHash(state_root_hash) => {
+            // Historical roots are queried as is, but if a stored block has this state as its
+            // post-state, include its header.
+            let maybe_block_header = match effect_builder
+                .get_block_height_for_state_root_hash_from_storage(state_root_hash)
+                .await
+            {
+                Some(block_height) => effect_builder
+                    .get_block_header_at_height_from_storage(
+                        block_height,
+                        only_from_available_block_range,
+                    )
+                    .await
+                    .map(JsonBlockHeader::from),
+                None => None,
+            };
+            Ok((state_root_hash, maybe_block_header))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_page_through_all_entries() {
+        let mut rng = crate::new_rng();
+        let map: BTreeMap<PublicKey, u8> = (0..5)
+            .map(|index| (PublicKey::random(&mut rng), index))
+            .collect();
+
+        let mut seen = vec![];
+        let mut after = None;
+        loop {
+            let (page, last_public_key) = paginate(&map, after.as_ref(), 2);
+            assert!(page.len() <= 2);
+            seen.extend(page.into_iter().map(|(_, index)| *index));
+            match last_public_key {
+                Some(last_public_key) => {
+                    let cursor = PageCursor {
+                        state_root_hash: Digest::hash([1; 32]),
+                        block_height: 7,
+                        last_public_key: last_public_key.clone(),
+                    };
+                    let decoded = PageCursor::decode(&cursor.encode()).unwrap();
+                    assert_eq!(decoded, cursor);
+                    after = Some(decoded.last_public_key);
+                }
+                None => break,
+            }
+        }
+        let expected: Vec<_> = map.values().copied().collect();
+        assert_eq!(seen, expected);
+
+        assert!(PageCursor::decode("not a cursor").is_err());
+        assert!(page_size(Some(0)).is_err());
+        assert!(page_size(Some(MAX_PAGE_SIZE + 1)).is_err());
+        assert_eq!(page_size(None).unwrap(), DEFAULT_PAGE_SIZE as usize);
     }
 }
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use super::AccessControlConfig;
+
 /// Default binding address for the speculative execution RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -27,6 +29,8 @@ pub struct Config {
     pub max_body_bytes: u32,
     /// CORS origin.
     pub cors_origin: String,
+    /// Restrictions on the available JSON-RPC methods.
+    pub access_control: AccessControlConfig,
 }
 
 impl Config {
@@ -38,6 +42,7 @@ impl Config {
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            access_control: AccessControlConfig::default(),
         }
     }
 }
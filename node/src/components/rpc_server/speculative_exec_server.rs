@@ -1,9 +1,12 @@
 use hyper::server::{conn::AddrIncoming, Builder};
 
-use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
+use casper_json_rpc::{BatchLimits, CorsOrigin, RequestHandlersBuilder};
 use casper_types::ProtocolVersion;
 
-use super::ReactorEventT;
+use super::{
+    access_control::AccessControlledHandlers, qps_limit::QpsLimit, AccessControlConfig,
+    ReactorEventT,
+};
 use crate::{
     effect::EffectBuilder,
     rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
@@ -19,13 +22,14 @@ pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
-    qps_limit: u64,
+    qps_limit: QpsLimit,
     max_body_bytes: u32,
+    access_control: AccessControlConfig,
     cors_origin: String,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
     SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
-    let handlers = handlers.build();
+    let handlers = AccessControlledHandlers::new(handlers, &access_control);
 
     match cors_origin.as_str() {
         "" => {
@@ -34,8 +38,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                BatchLimits::disabled(),
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
             )
             .await;
         }
@@ -45,8 +51,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                BatchLimits::disabled(),
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
                 CorsOrigin::Any,
             )
             .await
@@ -57,8 +65,10 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                BatchLimits::disabled(),
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
                 CorsOrigin::Specified(cors_origin),
             )
             .await
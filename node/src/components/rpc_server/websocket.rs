@@ -0,0 +1,407 @@
+//! WebSocket transport of the JSON-RPC server.
+//!
+//! Clients connected via a WebSocket can call all the JSON-RPC methods of the HTTP endpoint, and
+//! can additionally subscribe to new blocks, finality signatures and the processing of a given
+//! deploy.  The subscribed events are pushed to the client as JSON-RPC notifications holding the
+//! same data as the corresponding events of the event stream server.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bytes::Bytes;
+use futures::{future, SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, error};
+use warp::{
+    filters::BoxedFilter,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use casper_json_rpc::{
+    filters, BatchLimits, Error, Params, RequestHandlersBuilder, ReservedErrorCode,
+};
+
+use super::{
+    access_control::{AccessControlledHandlers, API_KEY_HEADER},
+    rpcs::{ErrorCode, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST},
+};
+use crate::{components::event_stream_server::SseData, types::DeployHash};
+
+/// The URL path for WebSocket connections.
+pub const RPC_WS_API_PATH: &str = "ws";
+/// The number of events buffered for every connection before it is considered lagging behind.
+pub(super) const NOTIFICATIONS_BUFFER_SIZE: usize = 1024;
+
+/// The JSON-RPC "method" to subscribe to a topic.
+const SUBSCRIBE: &str = "subscribe";
+/// The JSON-RPC "method" to cancel a subscription.
+const UNSUBSCRIBE: &str = "unsubscribe";
+/// The JSON-RPC "method" of the notifications pushed to subscribers.
+const SUBSCRIPTION_NOTIFICATION: &str = "subscription";
+
+/// The settings of the WebSocket endpoint.
+#[derive(Clone, Debug)]
+pub(super) struct WebSocketSettings {
+    /// The sender of all events which can be subscribed to.
+    pub(super) notifications: broadcast::Sender<Arc<SseData>>,
+    /// Maximum number of subscriptions of a single connection.
+    pub(super) max_subscriptions: u32,
+}
+
+/// The events a client can subscribe to, given as the "params" of a "subscribe" request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "topic", rename_all = "snake_case", deny_unknown_fields)]
+enum Topic {
+    /// Blocks added to the linear chain.
+    NewBlocks,
+    /// Finality signatures received by the node.
+    FinalitySignatures,
+    /// The processing of the given deploy.
+    DeployProcessed { deploy_hash: DeployHash },
+}
+
+impl Topic {
+    /// Returns whether `data` is an event of this topic.
+    fn matches(&self, data: &SseData) -> bool {
+        match (self, data) {
+            (Topic::NewBlocks, SseData::BlockAdded { .. })
+            | (Topic::FinalitySignatures, SseData::FinalitySignature(_)) => true,
+            (
+                Topic::DeployProcessed { deploy_hash },
+                SseData::DeployProcessed {
+                    deploy_hash: processed,
+                    ..
+                },
+            ) => **processed == *deploy_hash,
+            _ => false,
+        }
+    }
+}
+
+/// The "params" of an "unsubscribe" request.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnsubscribeParams {
+    subscription: u64,
+}
+
+/// The subscriptions of a single connection.
+#[derive(Debug)]
+struct Subscriptions {
+    topics: BTreeMap<u64, Topic>,
+    next_id: u64,
+    max_subscriptions: u32,
+}
+
+impl Subscriptions {
+    fn new(max_subscriptions: u32) -> Self {
+        Subscriptions {
+            topics: BTreeMap::new(),
+            next_id: 0,
+            max_subscriptions,
+        }
+    }
+
+    /// Subscribes to `topic`, returning the ID of the new subscription.
+    fn subscribe(&mut self, topic: Topic) -> Result<u64, Error> {
+        if self.topics.len() >= self.max_subscriptions as usize {
+            return Err(Error::new(
+                ErrorCode::TooManySubscriptions,
+                format!(
+                    "at most {} subscriptions per connection are allowed",
+                    self.max_subscriptions
+                ),
+            ));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let _ = self.topics.insert(id, topic);
+        Ok(id)
+    }
+
+    /// Cancels the given subscription, returning whether it existed.
+    fn unsubscribe(&mut self, id: u64) -> bool {
+        self.topics.remove(&id).is_some()
+    }
+
+    /// Returns the IDs of all subscriptions `data` should be sent to.
+    fn matching(&self, data: &SseData) -> Vec<u64> {
+        self.topics
+            .iter()
+            .filter(|(_, topic)| topic.matches(data))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// Returns the warp filter upgrading connections on the WebSocket path.
+///
+/// `handlers` are the handlers of the HTTP endpoint, to which the subscription handlers are added
+/// for every connection.  The access control applies as for HTTP requests, with the API key taken
+/// from the header of the request opening the connection.
+pub(super) fn route(
+    handlers: AccessControlledHandlers,
+    max_message_bytes: u32,
+    batch_limits: BatchLimits,
+    settings: WebSocketSettings,
+) -> BoxedFilter<(Box<dyn Reply>,)> {
+    warp::path(RPC_WS_API_PATH)
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(warp::header::optional::<String>(API_KEY_HEADER))
+        .map(move |ws: Ws, maybe_api_key: Option<String>| {
+            let handlers = handlers.for_api_key(maybe_api_key.as_deref());
+            let notifications = settings.notifications.subscribe();
+            let max_subscriptions = settings.max_subscriptions;
+            let reply = ws
+                .max_message_size(max_message_bytes as usize)
+                .on_upgrade(move |socket| {
+                    handle_connection(
+                        socket,
+                        handlers,
+                        batch_limits,
+                        notifications,
+                        max_subscriptions,
+                    )
+                });
+            Box::new(reply) as Box<dyn Reply>
+        })
+        .boxed()
+}
+
+/// Serves a single WebSocket connection until it is closed by the client, or the client is unable
+/// to keep up with the subscribed events.
+async fn handle_connection(
+    socket: WebSocket,
+    mut handlers: RequestHandlersBuilder,
+    batch_limits: BatchLimits,
+    mut notifications: broadcast::Receiver<Arc<SseData>>,
+    max_subscriptions: u32,
+) {
+    let subscriptions = Arc::new(Mutex::new(Subscriptions::new(max_subscriptions)));
+    register_subscription_handlers(&mut handlers, &subscriptions);
+    let handlers = handlers.build();
+
+    let (mut sink, mut stream) = socket.split();
+    loop {
+        let replies: Vec<Value> = tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(message)) if message.is_text() => {
+                    filters::handle_message(
+                        Bytes::from(message.into_bytes()),
+                        handlers.clone(),
+                        ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                        batch_limits,
+                    )
+                    .await
+                    .into_iter()
+                    .collect()
+                }
+                Some(Ok(message)) if message.is_close() => break,
+                // Pings are answered by the WebSocket implementation itself.
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    debug!(%error, "error on rpc websocket");
+                    break;
+                }
+                None => break,
+            },
+            notification = notifications.recv() => match notification {
+                Ok(data) => notify(&subscriptions, &data),
+                Err(RecvError::Lagged(missed)) => {
+                    debug!(missed, "rpc websocket client lagging behind, closing connection");
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        for reply in replies {
+            if let Err(error) = sink.send(Message::text(reply.to_string())).await {
+                debug!(%error, "failed to send on rpc websocket");
+                return;
+            }
+        }
+    }
+    let _ = sink.close().await;
+}
+
+/// Adds the handlers of the "subscribe" and "unsubscribe" methods for a single connection.
+fn register_subscription_handlers(
+    handlers: &mut RequestHandlersBuilder,
+    subscriptions: &Arc<Mutex<Subscriptions>>,
+) {
+    let subscribe_to = Arc::clone(subscriptions);
+    let subscribe = move |maybe_params| {
+        let result = parse_params::<Topic>(maybe_params).and_then(|topic| {
+            let mut subscriptions = lock(&subscribe_to)?;
+            subscriptions.subscribe(topic)
+        });
+        future::ready(result)
+    };
+    handlers.register_handler(SUBSCRIBE, Arc::new(subscribe));
+
+    let unsubscribe_from = Arc::clone(subscriptions);
+    let unsubscribe = move |maybe_params| {
+        let result = parse_params::<UnsubscribeParams>(maybe_params).and_then(|params| {
+            let mut subscriptions = lock(&unsubscribe_from)?;
+            Ok(subscriptions.unsubscribe(params.subscription))
+        });
+        future::ready(result)
+    };
+    handlers.register_handler(UNSUBSCRIBE, Arc::new(unsubscribe));
+}
+
+/// Returns the notifications of `data` for all matching subscriptions.
+fn notify(subscriptions: &Mutex<Subscriptions>, data: &SseData) -> Vec<Value> {
+    let ids = match subscriptions.lock() {
+        Ok(subscriptions) => subscriptions.matching(data),
+        Err(_) => {
+            error!("could not read rpc websocket subscriptions, lock poisoned");
+            return vec![];
+        }
+    };
+    ids.into_iter()
+        .map(|id| {
+            json!({
+                "jsonrpc": "2.0",
+                "method": SUBSCRIPTION_NOTIFICATION,
+                "params": { "subscription": id, "result": data },
+            })
+        })
+        .collect()
+}
+
+fn lock(subscriptions: &Mutex<Subscriptions>) -> Result<MutexGuard<'_, Subscriptions>, Error> {
+    subscriptions.lock().map_err(|_| {
+        error!("could not update rpc websocket subscriptions, lock poisoned");
+        Error::new(
+            ReservedErrorCode::InternalError,
+            "subscriptions are unavailable",
+        )
+    })
+}
+
+/// Tries to parse the incoming JSON-RPC request's "params" field as `T`.
+fn parse_params<T: for<'de> Deserialize<'de>>(maybe_params: Option<Params>) -> Result<T, Error> {
+    let params = match maybe_params {
+        Some(params) => Value::from(params),
+        None => {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                "Missing 'params' field",
+            ))
+        }
+    };
+    serde_json::from_value::<T>(params).map_err(|error| {
+        Error::new(
+            ReservedErrorCode::InvalidParams,
+            format!("Failed to parse 'params' field: {}", error),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::rpc_server::AccessControlConfig;
+
+    #[test]
+    fn should_track_subscriptions() {
+        let mut rng = crate::new_rng();
+        let block_added = SseData::random_block_added(&mut rng);
+        let deploy_processed = SseData::random_deploy_processed(&mut rng);
+        let deploy_hash = match deploy_processed {
+            SseData::DeployProcessed {
+                ref deploy_hash, ..
+            } => **deploy_hash,
+            _ => unreachable!(),
+        };
+
+        let topic: Topic = serde_json::from_value(json!({
+            "topic": "deploy_processed",
+            "deploy_hash": deploy_hash,
+        }))
+        .unwrap();
+        assert_eq!(topic, Topic::DeployProcessed { deploy_hash });
+
+        let mut subscriptions = Subscriptions::new(3);
+        let blocks = subscriptions.subscribe(Topic::NewBlocks).unwrap();
+        let deploy = subscriptions.subscribe(topic).unwrap();
+        let other_deploy = subscriptions
+            .subscribe(Topic::DeployProcessed {
+                deploy_hash: DeployHash::random(&mut rng),
+            })
+            .unwrap();
+        assert!(subscriptions.subscribe(Topic::FinalitySignatures).is_err());
+
+        assert_eq!(subscriptions.matching(&block_added), vec![blocks]);
+        assert_eq!(subscriptions.matching(&deploy_processed), vec![deploy]);
+
+        assert!(subscriptions.unsubscribe(other_deploy));
+        assert!(!subscriptions.unsubscribe(other_deploy));
+        assert!(subscriptions.subscribe(Topic::FinalitySignatures).is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_apply_access_control() {
+        const PUT_DEPLOY: &str = "account_put_deploy";
+
+        let mut handlers = RequestHandlersBuilder::new();
+        handlers.register_handler(
+            PUT_DEPLOY,
+            Arc::new(|_: Option<Params>| future::ready(Ok::<_, Error>(true))),
+        );
+        let config = AccessControlConfig {
+            disabled_methods: vec![],
+            api_key_methods: vec![PUT_DEPLOY.to_string()],
+            api_keys: vec!["secret".to_string()],
+        };
+        let (notifications, _) = broadcast::channel(NOTIFICATIONS_BUFFER_SIZE);
+        let filter = route(
+            AccessControlledHandlers::new(handlers, &config),
+            1024,
+            BatchLimits::disabled(),
+            WebSocketSettings {
+                notifications,
+                max_subscriptions: 1,
+            },
+        );
+
+        for (maybe_api_key, is_authorized) in vec![
+            (None, false),
+            (Some("wrong"), false),
+            (Some("secret"), true),
+        ] {
+            let mut request = warp::test::ws().path("/ws");
+            if let Some(api_key) = maybe_api_key {
+                request = request.header(API_KEY_HEADER, api_key);
+            }
+            let mut client = request
+                .handshake(filter.clone())
+                .await
+                .expect("should open connection");
+            client
+                .send_text(format!(
+                    r#"{{"jsonrpc":"2.0","id":1,"method":"{}"}}"#,
+                    PUT_DEPLOY
+                ))
+                .await;
+            let message = client.recv().await.expect("should receive reply");
+            let reply: Value = serde_json::from_str(message.to_str().unwrap()).unwrap();
+            if is_authorized {
+                assert_eq!(reply["result"], true);
+            } else {
+                assert_eq!(
+                    reply["error"]["code"].as_i64(),
+                    Some(ErrorCode::Unauthorized as i64)
+                );
+            }
+        }
+    }
+}
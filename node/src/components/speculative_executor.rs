@@ -0,0 +1,229 @@
+//! Speculative executor component.
+//!
+//! Executes deploys on top of a given state without committing their effects, on behalf of the
+//! speculative execution server and the deploy acceptor's preflight checks.
+//!
+//! Deploys are executed on a dedicated pool of threads rather than the one executing blocks, and
+//! the number of concurrent executions, the gas a deploy may pay for and the time a request may
+//! take are all limited, so that abusive speculative execution requests cannot starve the contract
+//! runtime.
+
+mod config;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use derive_more::From;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::{sync::Semaphore, time};
+use tracing::debug;
+
+use casper_execution_engine::core::engine_state;
+use casper_types::{Gas, TimeDiff, U512};
+
+use crate::{
+    components::{contract_runtime::SpeculativeExecutionHandle, Component},
+    effect::{requests::SpeculativeExecutionRequest, EffectBuilder, EffectExt, Effects},
+    types::Deploy,
+    utils::thread_pool::ThreadPool,
+    NodeRng,
+};
+pub(crate) use config::Config;
+
+const COMPONENT_NAME: &str = "speculative_executor";
+
+/// An error executing a deploy speculatively.
+#[derive(Debug, Error)]
+pub(crate) enum SpeculativeExecutionError {
+    /// The execution engine failed to execute the deploy.
+    #[error(transparent)]
+    EngineState(#[from] engine_state::Error),
+    /// The deploy's payment amount is missing or invalid.
+    #[error("missing or invalid payment amount")]
+    InvalidPaymentAmount,
+    /// The deploy pays for more gas than allowed for speculative execution.
+    #[error("deploy pays for {gas_limit} gas, but at most {max_gas} is allowed")]
+    GasLimitExceeded { gas_limit: Gas, max_gas: Gas },
+    /// The request did not complete in time.
+    #[error("speculative execution timed out after {0}")]
+    TimedOut(TimeDiff),
+    /// The execution panicked.
+    #[error("speculative execution aborted")]
+    Aborted,
+}
+
+/// The speculative executor's event.
+#[derive(Debug, From, Serialize)]
+pub(crate) enum Event {
+    #[from]
+    Request(SpeculativeExecutionRequest),
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Request(request) => write!(formatter, "speculative execution: {}", request),
+        }
+    }
+}
+
+/// Speculative executor component.
+#[derive(DataSize)]
+pub(crate) struct SpeculativeExecutor {
+    #[data_size(skip)]
+    execution_handle: SpeculativeExecutionHandle,
+    #[data_size(skip)]
+    thread_pool: ThreadPool,
+    /// Permits for the executions which are running or waiting for a thread.
+    #[data_size(skip)]
+    permits: Arc<Semaphore>,
+    max_gas: Gas,
+    request_timeout: TimeDiff,
+}
+
+impl SpeculativeExecutor {
+    pub(crate) fn new(config: Config, execution_handle: SpeculativeExecutionHandle) -> Self {
+        SpeculativeExecutor {
+            execution_handle,
+            thread_pool: ThreadPool::new(COMPONENT_NAME, config.execution_threads),
+            permits: Arc::new(Semaphore::new(config.max_concurrent_executions.max(1))),
+            max_gas: Gas::new(U512::from(config.max_gas)),
+            request_timeout: config.request_timeout,
+        }
+    }
+
+    fn handle_request(
+        &mut self,
+        SpeculativeExecutionRequest {
+            execution_prestate,
+            deploy,
+            responder,
+        }: SpeculativeExecutionRequest,
+    ) -> Effects<Event> {
+        if let Err(error) = check_gas_limit(&deploy, self.max_gas) {
+            debug!(deploy_hash = %deploy.hash(), %error, "rejecting speculative execution");
+            return responder.respond(Err(error)).ignore();
+        }
+
+        let execution_handle = self.execution_handle.clone();
+        let thread_pool = self.thread_pool.clone();
+        let permits = Arc::clone(&self.permits);
+        let request_timeout = self.request_timeout;
+        async move {
+            let execute = async move {
+                // This will never panic since the semaphore is never closed.
+                let permit = permits.acquire_owned().await.unwrap();
+                thread_pool
+                    .run(move || {
+                        // Hold the permit until the execution finishes, even if the request timed
+                        // out in the meantime.
+                        let _permit = permit;
+                        execution_handle.execute(execution_prestate, &deploy)
+                    })
+                    .await
+            };
+            let result = match time::timeout(Duration::from(request_timeout), execute).await {
+                Ok(Ok(result)) => result.map_err(SpeculativeExecutionError::from),
+                Ok(Err(_)) => Err(SpeculativeExecutionError::Aborted),
+                Err(_) => Err(SpeculativeExecutionError::TimedOut(request_timeout)),
+            };
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+}
+
+/// Checks that `deploy` pays for at most `max_gas`.  Native transfers have a fixed cost, and are
+/// not checked.
+fn check_gas_limit(deploy: &Deploy, max_gas: Gas) -> Result<(), SpeculativeExecutionError> {
+    if deploy.session().is_transfer() {
+        return Ok(());
+    }
+    let gas_limit = deploy
+        .payment()
+        .payment_amount(deploy.header().gas_price())
+        .ok_or(SpeculativeExecutionError::InvalidPaymentAmount)?;
+    if gas_limit > max_gas {
+        return Err(SpeculativeExecutionError::GasLimitExceeded { gas_limit, max_gas });
+    }
+    Ok(())
+}
+
+impl<REv> Component<REv> for SpeculativeExecutor {
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        _effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Request(request) => self.handle_request(request),
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+    use casper_types::{
+        bytesrepr::Bytes, runtime_args, testing::TestRng, RuntimeArgs, SecretKey, Timestamp,
+    };
+
+    use super::*;
+
+    fn deploy_with_payment_args(rng: &mut TestRng, payment_args: RuntimeArgs) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: payment_args,
+        };
+        let session = ExecutableDeployItem::StoredContractByName {
+            name: "Test".to_string(),
+            entry_point: "call".to_string(),
+            args: Default::default(),
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from_seconds(60),
+            2,
+            vec![],
+            "casper-example".to_string(),
+            payment,
+            session,
+            &SecretKey::random(rng),
+            None,
+        )
+    }
+
+    #[test]
+    fn should_check_gas_limit() {
+        let mut rng = TestRng::new();
+        let transfer = Deploy::random_valid_native_transfer(&mut rng);
+        assert!(check_gas_limit(&transfer, Gas::default()).is_ok());
+
+        // Paying 1000 motes at a gas price of 2 buys 500 gas.
+        let deploy =
+            deploy_with_payment_args(&mut rng, runtime_args! { "amount" => U512::from(1000) });
+        assert!(check_gas_limit(&deploy, Gas::new(U512::from(500))).is_ok());
+        assert!(matches!(
+            check_gas_limit(&deploy, Gas::new(U512::from(499))),
+            Err(SpeculativeExecutionError::GasLimitExceeded { .. })
+        ));
+
+        let deploy = deploy_with_payment_args(&mut rng, RuntimeArgs::new());
+        assert!(matches!(
+            check_gas_limit(&deploy, Gas::new(U512::from(500))),
+            Err(SpeculativeExecutionError::InvalidPaymentAmount)
+        ));
+    }
+}
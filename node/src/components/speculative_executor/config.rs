@@ -0,0 +1,47 @@
+use std::str::FromStr;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+const DEFAULT_EXECUTION_THREADS: usize = 2;
+const DEFAULT_MAX_CONCURRENT_EXECUTIONS: usize = 8;
+const DEFAULT_MAX_GAS: u64 = 300_000_000_000;
+const DEFAULT_REQUEST_TIMEOUT: &str = "10sec";
+
+/// Configuration options for executing deploys speculatively.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, DataSize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The number of threads dedicated to speculative execution.
+    ///
+    /// These are separate from the threads executing blocks, so speculative execution can at most
+    /// compete with block execution for CPU time, but never for threads.
+    pub execution_threads: usize,
+    /// The maximum number of deploys being executed or waiting for a thread at any time.
+    ///
+    /// Further requests wait until an execution finishes, or fail once `request_timeout` elapses.
+    pub max_concurrent_executions: usize,
+    /// The maximum gas a deploy may pay for to be executed speculatively.
+    ///
+    /// Deploys paying for more gas are rejected without being executed.  Native transfers have a
+    /// fixed cost and are not limited.
+    pub max_gas: u64,
+    /// The maximum time a request may take, including waiting for a thread.
+    ///
+    /// An execution running beyond it is not interrupted, but its result is discarded.
+    pub request_timeout: TimeDiff,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            execution_threads: DEFAULT_EXECUTION_THREADS,
+            max_concurrent_executions: DEFAULT_MAX_CONCURRENT_EXECUTIONS,
+            max_gas: DEFAULT_MAX_GAS,
+            request_timeout: TimeDiff::from_str(DEFAULT_REQUEST_TIMEOUT).unwrap(),
+        }
+    }
+}
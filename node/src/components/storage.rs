@@ -30,26 +30,34 @@
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
 
+mod account_transfer;
+mod block_export;
+mod cold_storage;
 pub(crate) mod disjoint_sequences;
 mod error;
+mod inspection;
 mod lmdb_ext;
 mod metrics;
 mod object_pool;
+mod prune_outcome;
+mod read_cache;
+mod snapshot;
 #[cfg(test)]
 mod tests;
+mod verification;
 
-#[cfg(test)]
-use std::collections::BTreeSet;
 use std::{
     borrow::Cow,
-    collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet},
+    cell::RefCell,
+    collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
     convert::{TryFrom, TryInto},
     fmt::{self, Display, Formatter},
     fs::{self, OpenOptions},
-    io::ErrorKind,
+    io::{ErrorKind, Read, Write},
     mem,
     path::{Path, PathBuf},
     rc::Rc,
+    str::FromStr,
     sync::Arc,
 };
 
@@ -68,15 +76,19 @@ use static_assertions::const_assert;
 use tempfile::TempDir;
 use tracing::{debug, error, info, trace, warn};
 
-use casper_hashing::Digest;
+use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{
+    account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
-    EraId, ExecutionResult, ProtocolVersion, PublicKey, Timestamp, Transfer, Transform,
+    EraId, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp, Transfer, Transform,
+    U512,
 };
 
 use crate::{
     components::{
+        consensus::{EquivocationRecord, ValidatorPerformanceReport},
         fetcher::{FetchItem, FetchResponse},
+        zk_prover::{EraTransitionProof, JobId, PersistedJob},
         Component,
     },
     effect::{
@@ -91,22 +103,33 @@ use crate::{
     protocol::Message,
     types::{
         ApprovalsHash, ApprovalsHashes, AvailableBlockRange, Block, BlockAndDeploys, BlockBody,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
-        BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
-        BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployHeader, DeployId,
-        DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalizedApprovals, FinalizedBlock, LegacyDeploy, MaxTtl, NodeId, SyncLeap,
-        SyncLeapIdentifier, ValueOrChunk,
+        BlockBodyOrChunk, BlockBodyOrChunkId, BlockExecutionResultsOrChunk,
+        BlockExecutionResultsOrChunkId, BlockHash, BlockHashAndHeight, BlockHashHeightAndEra,
+        BlockHeader, BlockHeaderWithMetadata, BlockSignatures, BlockWithMetadata, Deploy,
+        DeployHash, DeployHeader, DeployId, DeployMetadata, DeployMetadataExt,
+        DeployWithFinalizedApprovals, FinalitySignature, FinalizedApprovals, FinalizedBlock,
+        LegacyDeploy, LightClientUpdate, MaxTtl, NodeId, SyncLeap, SyncLeapIdentifier,
+        ValueOrChunk,
     },
     utils::{display_error, WithDir},
     NodeRng,
 };
+pub(crate) use account_transfer::{AccountTransfer, TransferPosition};
+pub(crate) use block_export::BlockExportError;
+use block_export::BlockExportRecord;
+use cold_storage::{ColdStorage, ColdStorageBatch, ColdStorageKeys};
 use disjoint_sequences::{DisjointSequences, Sequence};
 pub use error::FatalStorageError;
 use error::GetRequestError;
+pub(crate) use inspection::StorageInspector;
 use lmdb_ext::{BytesreprError, LmdbExtError, TransactionExt, WriteTransactionExt};
 use metrics::Metrics;
 use object_pool::ObjectPool;
+use prune_outcome::PruneLimit;
+pub(crate) use prune_outcome::PruneOutcome;
+use read_cache::ReadCache;
+pub(crate) use snapshot::{CreateSnapshotError, StorageSnapshot};
+pub(crate) use verification::{StorageIssue, StorageVerification};
 
 const COMPONENT_NAME: &str = "storage";
 
@@ -117,8 +140,10 @@ const STORAGE_DB_FILENAME: &str = "storage.lmdb";
 /// one time.
 const MAX_TRANSACTIONS: u32 = 1;
 
+/// One Mebibyte.
+const MIB: usize = 1024 * 1024;
 /// One Gibibyte.
-const GIB: usize = 1024 * 1024 * 1024;
+const GIB: usize = 1024 * MIB;
 
 /// Default max block store size.
 const DEFAULT_MAX_BLOCK_STORE_SIZE: usize = 450 * GIB;
@@ -128,10 +153,42 @@ const DEFAULT_MAX_DEPLOY_STORE_SIZE: usize = 300 * GIB;
 const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
+/// Default max cold store size.
+const DEFAULT_MAX_COLD_STORE_SIZE: usize = 750 * GIB;
+/// Default amount by which the memory map is grown once it is full.
+const DEFAULT_MAP_SIZE_INCREMENT: usize = 10 * GIB;
+/// Maximum number of times the memory map is grown while retrying a single write.
+const MAX_MAP_GROWTH_ATTEMPTS: u32 = 8;
+/// Default size of the cache of recently read block headers, blocks and finality signatures.
+const DEFAULT_READ_CACHE_SIZE: usize = 128 * MIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 9;
+const MAX_DB_COUNT: u32 = 16;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the height below which all blocks have been pruned is to be stored.
+const PRUNED_BELOW_HEIGHT_STORAGE_KEY: &[u8] = b"pruned_below_height";
+/// Key under which the height below which the global state of all blocks may have been garbage
+/// collected is to be stored.
+const GLOBAL_STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY: &[u8] = b"global_state_pruned_below_height";
+/// Key under which the height below which all blocks have been migrated to the cold storage tier
+/// is to be stored.
+const MIGRATED_BELOW_HEIGHT_STORAGE_KEY: &[u8] = b"migrated_to_cold_storage_below_height";
+/// Key under which the flag marking the deploys by account index as complete is to be stored.
+const DEPLOYS_BY_ACCOUNT_INDEXED_STORAGE_KEY: &[u8] = b"deploys_by_account_indexed";
+/// Key under which the flag marking the transfers by account index as complete is to be stored.
+const TRANSFERS_BY_ACCOUNT_INDEXED_STORAGE_KEY: &[u8] = b"transfers_by_account_indexed";
+/// Maximum number of blocks pruned in a single step of the background pruning task.
+const MAX_BLOCKS_PRUNED_PER_STEP: u64 = 100;
+/// Default interval between steps of the background pruning task.
+const DEFAULT_PRUNE_INTERVAL: &str = "60sec";
+/// Maximum number of blocks migrated to the cold storage tier in a single step.
+const MAX_BLOCKS_MIGRATED_PER_STEP: u64 = 100;
+/// Default number of eras whose block bodies and execution results are kept in the hot tier.
+const DEFAULT_COLD_STORAGE_ERA_DEPTH: u64 = 100;
+/// Default interval between steps of the background migration to the cold storage tier.
+const DEFAULT_COLD_STORAGE_MIGRATION_INTERVAL: &str = "60sec";
+/// Number of entries written per transaction when creating a snapshot.
+const SNAPSHOT_ENTRIES_PER_TXN: u64 = 10_000;
 /// Name of the file created when initializing a force resync.
 const FORCE_RESYNC_FILE_NAME: &str = "force_resync";
 
@@ -165,6 +222,10 @@ pub struct Storage {
     /// Environment holding LMDB databases.
     #[data_size(skip)]
     env: Rc<Environment>,
+    /// The current size of the memory map of the environment.
+    map_size: usize,
+    /// The amount by which the memory map is grown once it is full, or `0` if it is never grown.
+    map_size_increment: usize,
     /// The block header database.
     #[data_size(skip)]
     block_header_db: Database,
@@ -192,10 +253,42 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The database indexing deploy hashes by the public key of the account which sent them.
+    #[data_size(skip)]
+    deploys_by_account_db: Database,
+    /// The database indexing the successful native transfers by the hashes of their source and
+    /// target accounts.
+    #[data_size(skip)]
+    transfers_by_account_db: Database,
+    /// The database of observed equivocations, keyed by era ID and validator.
+    #[data_size(skip)]
+    equivocations_db: Database,
+    /// The database of validator performance reports, keyed by era ID.
+    #[data_size(skip)]
+    validator_performance_db: Database,
+    /// The database of chunks of partially fetched tries, keyed by trie hash and chunk index.
+    #[data_size(skip)]
+    trie_chunks_db: Database,
+    /// The database of proofs of the validators set by switch blocks, keyed by era ID.
+    #[data_size(skip)]
+    era_transition_proofs_db: Database,
+    /// The database of queued and running proving jobs, keyed by job ID.
+    #[data_size(skip)]
+    proving_jobs_db: Database,
+    /// Whether the deploys by account index covers all stored deploys.
+    ///
+    /// Databases created before the index existed are indexed on first use.
+    deploys_by_account_indexed: bool,
+    /// Whether the transfers by account index covers all stored transfers.
+    ///
+    /// Databases created before the index existed are indexed on first use.
+    transfers_by_account_indexed: bool,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
     switch_block_era_id_index: BTreeMap<EraId, BlockHash>,
+    /// A map of state root hash to the lowest height of the blocks with that post-state.
+    state_root_hash_index: BTreeMap<Digest, u64>,
     /// A map of deploy hashes to hashes, heights and era IDs of blocks containing them.
     deploy_hash_index: BTreeMap<DeployHash, BlockHashHeightAndEra>,
     /// Runs of completed blocks known in storage.
@@ -210,9 +303,39 @@ pub struct Storage {
     ///
     /// Keyed by serialized item ID, contains the serialized item.
     serialized_item_pool: ObjectPool<Box<[u8]>>,
+    /// A cache of recently read block headers, blocks and finality signatures.
+    ///
+    /// Behind a `RefCell` since most reads only borrow the component immutably.
+    #[data_size(with = read_cache::estimate_heap_size)]
+    read_cache: RefCell<ReadCache>,
     /// The number of eras relative to the highest block's era which are considered as recent for
     /// the purpose of deciding how to respond to a `NetRequest::SyncLeap`.
     recent_era_count: u64,
+    /// The number of blocks below the highest complete block whose bodies are retained, if
+    /// pruning is enabled.
+    block_body_retention_depth: Option<u64>,
+    /// The interval between steps of the background pruning task.
+    prune_interval: TimeDiff,
+    /// The era at the start of which the last emergency restart happened, if the current protocol
+    /// version was activated by one.
+    last_emergency_restart: Option<EraId>,
+    /// The height below which the bodies, deploys and execution results of all blocks have been
+    /// pruned.
+    pruned_below_height: u64,
+    /// The height below which the global state of all blocks may have been garbage collected.
+    global_state_pruned_below_height: u64,
+    /// The cold storage tier holding the bodies and execution results of old blocks, if
+    /// configured.
+    #[data_size(skip)]
+    cold_storage: Option<ColdStorage>,
+    /// The number of eras below the era of the highest complete block whose block bodies and
+    /// execution results are kept in the hot tier.
+    cold_storage_era_depth: u64,
+    /// The interval between steps of the background migration to the cold storage tier.
+    cold_storage_migration_interval: TimeDiff,
+    /// The height below which the bodies and execution results of all blocks have been migrated
+    /// to the cold storage tier.
+    migrated_below_height: u64,
     #[data_size(skip)]
     metrics: Option<Metrics>,
     /// The maximum TTL of a deploy.
@@ -234,6 +357,10 @@ pub(crate) enum Event {
     /// Make block executable request.
     #[from]
     MakeBlockExecutableRequest(Box<MakeBlockExecutableRequest>),
+    /// Run a step of the background pruning task.
+    Prune,
+    /// Run a step of the background migration to the cold storage tier.
+    MigrateToColdStorage,
 }
 
 impl Display for Event {
@@ -243,6 +370,8 @@ impl Display for Event {
             Event::NetRequestIncoming(incoming) => incoming.fmt(f),
             Event::MarkBlockCompletedRequest(req) => req.fmt(f),
             Event::MakeBlockExecutableRequest(req) => req.fmt(f),
+            Event::Prune => write!(f, "prune"),
+            Event::MigrateToColdStorage => write!(f, "migrate to cold storage"),
         }
     }
 }
@@ -336,6 +465,8 @@ where
                     Err(err) => Err(err),
                 }
             }
+            Event::Prune => self.handle_prune(effect_builder),
+            Event::MigrateToColdStorage => self.handle_cold_storage_migration(effect_builder),
         };
 
         // Any error is turned into a fatal effect, the component itself does not panic. Note that
@@ -417,11 +548,32 @@ impl Storage {
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
         let approvals_hashes_db =
             env.create_db(Some("approvals_hashes"), DatabaseFlags::empty())?;
+        let deploys_by_account_db =
+            env.create_db(Some("deploys_by_account"), DatabaseFlags::empty())?;
+        let transfers_by_account_db =
+            env.create_db(Some("transfers_by_account"), DatabaseFlags::empty())?;
+        let equivocations_db = env.create_db(Some("equivocations"), DatabaseFlags::empty())?;
+        let validator_performance_db =
+            env.create_db(Some("validator_performance"), DatabaseFlags::empty())?;
+        let trie_chunks_db = env.create_db(Some("trie_chunks"), DatabaseFlags::empty())?;
+        let era_transition_proofs_db =
+            env.create_db(Some("era_transition_proofs"), DatabaseFlags::empty())?;
+        let proving_jobs_db = env.create_db(Some("zk_proving_jobs"), DatabaseFlags::empty())?;
+
+        let cold_storage = match config.cold_storage_path.as_ref() {
+            Some(cold_storage_path) => Some(ColdStorage::open(
+                &cfg.with_dir(cold_storage_path.clone()).join(network_name),
+                config.max_cold_store_size,
+                config.map_size_increment,
+            )?),
+            None => None,
+        };
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
         let mut block_height_index = BTreeMap::new();
         let mut switch_block_era_id_index = BTreeMap::new();
+        let mut state_root_hash_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
         let mut block_txn = env.begin_rw_txn()?;
         let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
@@ -436,8 +588,13 @@ impl Storage {
             let (_, raw_val) = row?;
             let mut body_txn = env.begin_ro_txn()?;
             let block_header: BlockHeader = lmdb_ext::deserialize(raw_val)?;
-            let maybe_block_body =
+            let mut maybe_block_body =
                 get_body_for_block_header(&mut body_txn, block_header.body_hash(), block_body_db);
+            if matches!(maybe_block_body, Ok(None)) {
+                if let Some(cold_storage) = cold_storage.as_ref() {
+                    maybe_block_body = Ok(cold_storage.get_block_body(block_header.body_hash())?);
+                }
+            }
             if let Some(invalid_era) = hard_reset_to_start_of_era {
                 // Remove blocks that are in to-be-upgraded eras, but have obsolete protocol
                 // versions - they were most likely created before the upgrade and should be
@@ -462,6 +619,7 @@ impl Storage {
             insert_to_block_header_indices(
                 &mut block_height_index,
                 &mut switch_block_era_id_index,
+                &mut state_root_hash_index,
                 &block_header,
             )?;
 
@@ -495,10 +653,17 @@ impl Storage {
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_deploy_hashes)?;
 
         let metrics = registry.map(Metrics::new).transpose()?;
+        if let Some(metrics) = metrics.as_ref() {
+            metrics
+                .map_size
+                .set(total_size.try_into().unwrap_or(i64::MAX));
+        }
 
         let mut component = Self {
             root,
             env: Rc::new(env),
+            map_size: total_size,
+            map_size_increment: config.map_size_increment,
             block_header_db,
             block_body_db,
             block_metadata_db,
@@ -508,19 +673,75 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            deploys_by_account_db,
+            transfers_by_account_db,
+            equivocations_db,
+            validator_performance_db,
+            trie_chunks_db,
+            era_transition_proofs_db,
+            proving_jobs_db,
+            deploys_by_account_indexed: false,
+            transfers_by_account_indexed: false,
             block_height_index,
             switch_block_era_id_index,
+            state_root_hash_index,
             deploy_hash_index,
             completed_blocks: Default::default(),
             activation_era,
             key_block_height_for_activation_point: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
             serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
+            read_cache: RefCell::new(ReadCache::new(config.read_cache_size)),
             recent_era_count,
+            block_body_retention_depth: config.block_body_retention_depth,
+            prune_interval: config.prune_interval,
+            last_emergency_restart: hard_reset_to_start_of_era,
+            pruned_below_height: 0,
+            global_state_pruned_below_height: 0,
+            cold_storage,
+            cold_storage_era_depth: config.cold_storage_era_depth,
+            cold_storage_migration_interval: config.cold_storage_migration_interval,
+            migrated_below_height: 0,
             max_ttl,
             metrics,
         };
 
+        component.deploys_by_account_indexed = component
+            .read_state_store(&Cow::Borrowed(DEPLOYS_BY_ACCOUNT_INDEXED_STORAGE_KEY))?
+            .is_some();
+        component.transfers_by_account_indexed = component
+            .read_state_store(&Cow::Borrowed(TRANSFERS_BY_ACCOUNT_INDEXED_STORAGE_KEY))?
+            .is_some();
+
+        if let Some(raw) =
+            component.read_state_store(&Cow::Borrowed(PRUNED_BELOW_HEIGHT_STORAGE_KEY))?
+        {
+            let (pruned_below_height, _) = u64::from_bytes(&raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.pruned_below_height = pruned_below_height;
+        }
+
+        if let Some(raw) = component
+            .read_state_store(&Cow::Borrowed(GLOBAL_STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY))?
+        {
+            let (global_state_pruned_below_height, _) = u64::from_bytes(&raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.global_state_pruned_below_height = global_state_pruned_below_height;
+        }
+
+        if let Some(raw) =
+            component.read_state_store(&Cow::Borrowed(MIGRATED_BELOW_HEIGHT_STORAGE_KEY))?
+        {
+            let (migrated_below_height, _) = u64::from_bytes(&raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            if migrated_below_height > 0 && component.cold_storage.is_none() {
+                return Err(FatalStorageError::MissingColdStorage {
+                    migrated_below_height,
+                });
+            }
+            component.migrated_below_height = migrated_below_height;
+        }
+
         if force_resync {
             let force_resync_file_path = component.root_path().join(FORCE_RESYNC_FILE_NAME);
             // Check if resync is already in progress. Force resync will kick
@@ -773,6 +994,30 @@ impl Storage {
                 let opt_item = self.read_block_execution_results_or_chunk(&item_id)?;
                 let fetch_response = FetchResponse::from_opt(item_id, opt_item);
 
+                Ok(self.update_pool_and_send(
+                    effect_builder,
+                    incoming.sender,
+                    serialized_id,
+                    fetch_response,
+                )?)
+            }
+            NetRequest::BlockBodyOrChunk(ref serialized_id) => {
+                let item_id = decode_item_id::<BlockBodyOrChunk>(serialized_id)?;
+                let opt_item = self.read_block_body_or_chunk(&item_id)?;
+                let fetch_response = FetchResponse::from_opt(item_id, opt_item);
+
+                Ok(self.update_pool_and_send(
+                    effect_builder,
+                    incoming.sender,
+                    serialized_id,
+                    fetch_response,
+                )?)
+            }
+            NetRequest::LightClientUpdate(ref serialized_id) => {
+                let item_id = decode_item_id::<LightClientUpdate>(serialized_id)?;
+                let opt_item = self.get_light_client_update(item_id)?;
+                let fetch_response = FetchResponse::from_opt(item_id, opt_item);
+
                 Ok(self.update_pool_and_send(
                     effect_builder,
                     incoming.sender,
@@ -792,17 +1037,20 @@ impl Storage {
         // The rationale is that long IO operations are very rare and cache misses frequent, so on
         // average the actual execution time will be very low.
         Ok(match req {
-            StorageRequest::PutBlock { block, responder } => {
-                responder.respond(self.write_block(&block)?).ignore()
-            }
+            StorageRequest::PutBlock { block, responder } => responder
+                .respond(self.write_with_map_growth(|storage| storage.write_block(&block))?)
+                .ignore(),
             StorageRequest::PutApprovalsHashes {
                 approvals_hashes,
                 responder,
             } => {
-                let env = Rc::clone(&self.env);
-                let mut txn = env.begin_rw_txn()?;
-                let result = self.write_approvals_hashes(&mut txn, &approvals_hashes)?;
-                txn.commit()?;
+                let result = self.write_with_map_growth(|storage| {
+                    let env = Rc::clone(&storage.env);
+                    let mut txn = env.begin_rw_txn()?;
+                    let result = storage.write_approvals_hashes(&mut txn, &approvals_hashes)?;
+                    txn.commit()?;
+                    Ok(result)
+                })?;
                 responder.respond(result).ignore()
             }
             StorageRequest::GetBlock {
@@ -855,8 +1103,68 @@ impl Storage {
                 let maybe_transfers = self.get_transfers(&block_hash)?;
                 responder.respond(maybe_transfers).ignore()
             }
-            StorageRequest::PutDeploy { deploy, responder } => {
-                responder.respond(self.put_deploy(&deploy)?).ignore()
+            StorageRequest::PutDeploy { deploy, responder } => responder
+                .respond(self.write_with_map_growth(|storage| storage.put_deploy(&deploy))?)
+                .ignore(),
+            StorageRequest::GetDeployHashesByAccount { account, responder } => responder
+                .respond(self.get_deploy_hashes_by_account(&account)?)
+                .ignore(),
+            StorageRequest::GetTransfersByAccount {
+                account_hash,
+                after,
+                max_count,
+                responder,
+            } => responder
+                .respond(self.get_transfers_by_account(&account_hash, after.as_ref(), max_count)?)
+                .ignore(),
+            StorageRequest::PutEquivocation { record, responder } => responder
+                .respond(self.write_with_map_growth(|storage| storage.put_equivocation(&record))?)
+                .ignore(),
+            StorageRequest::GetEquivocations {
+                from_era_id,
+                validator,
+                responder,
+            } => responder
+                .respond(self.get_equivocations(from_era_id, validator.as_ref())?)
+                .ignore(),
+            StorageRequest::PutValidatorPerformance { report, responder } => {
+                responder
+                    .respond(self.write_with_map_growth(|storage| {
+                        storage.put_validator_performance(&report)
+                    })?)
+                    .ignore()
+            }
+            StorageRequest::GetValidatorPerformance { era_id, responder } => responder
+                .respond(self.get_validator_performance(era_id)?)
+                .ignore(),
+            StorageRequest::PutTrieChunk { chunk, responder } => responder
+                .respond(self.write_with_map_growth(|storage| storage.put_trie_chunk(&chunk))?)
+                .ignore(),
+            StorageRequest::GetTrieChunks {
+                trie_hash,
+                responder,
+            } => responder
+                .respond(self.get_trie_chunks(&trie_hash)?)
+                .ignore(),
+            StorageRequest::DeleteTrieChunks {
+                trie_hash,
+                responder,
+            } => responder
+                .respond(self.delete_trie_chunks(&trie_hash)?)
+                .ignore(),
+            StorageRequest::PutEraTransitionProof { proof, responder } => responder
+                .respond(
+                    self.write_with_map_growth(|storage| storage.put_era_transition_proof(&proof))?,
+                )
+                .ignore(),
+            StorageRequest::GetEraTransitionProof { era_id, responder } => responder
+                .respond(self.get_era_transition_proof(era_id)?)
+                .ignore(),
+            StorageRequest::PutProvingJob { job, responder } => responder
+                .respond(self.write_with_map_growth(|storage| storage.put_proving_job(&job))?)
+                .ignore(),
+            StorageRequest::DeleteProvingJob { job_id, responder } => {
+                responder.respond(self.delete_proving_job(job_id)?).ignore()
             }
             StorageRequest::GetDeploys {
                 deploy_hashes,
@@ -914,18 +1222,33 @@ impl Storage {
             } => responder
                 .respond(self.read_execution_results(&block_hash)?)
                 .ignore(),
+            StorageRequest::GetExecutionResultsForBlock {
+                block_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                responder
+                    .respond(self.get_execution_results(&mut txn, &block_hash)?)
+                    .ignore()
+            }
             StorageRequest::GetBlockExecutionResultsOrChunk { id, responder } => responder
                 .respond(self.read_block_execution_results_or_chunk(&id)?)
                 .ignore(),
+            StorageRequest::GetBlockBodyOrChunk { id, responder } => responder
+                .respond(self.read_block_body_or_chunk(&id)?)
+                .ignore(),
             StorageRequest::PutExecutionResults {
                 block_hash,
                 execution_results,
                 responder,
             } => {
-                let env = Rc::clone(&self.env);
-                let mut txn = env.begin_rw_txn()?;
-                self.write_execution_results(&mut txn, &block_hash, execution_results)?;
-                txn.commit()?;
+                self.write_with_map_growth(|storage| {
+                    let env = Rc::clone(&storage.env);
+                    let mut txn = env.begin_rw_txn()?;
+                    storage.write_execution_results(&mut txn, &block_hash, &execution_results)?;
+                    txn.commit()?;
+                    Ok(())
+                })?;
                 responder.respond(()).ignore()
             }
             StorageRequest::GetDeployAndMetadata {
@@ -1091,33 +1414,20 @@ impl Storage {
                     );
                     return Ok(responder.respond(false).ignore());
                 }
-                let mut txn = self.env.begin_rw_txn()?;
-                let old_data: Option<BlockSignatures> =
-                    txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
-                let new_data = match old_data {
-                    None => signatures,
-                    Some(mut data) => {
-                        for (public_key, sig) in signatures.proofs {
-                            data.insert_proof(public_key, sig);
-                        }
-                        data
-                    }
-                };
-                let outcome = txn.put_value(
-                    self.block_metadata_db,
-                    &new_data.block_hash,
-                    &new_data,
-                    true,
-                )?;
-                txn.commit()?;
+                let outcome = self
+                    .write_with_map_growth(|storage| storage.put_block_signatures(&signatures))?;
                 responder.respond(outcome).ignore()
             }
             StorageRequest::PutFinalitySignature {
                 signature,
                 responder,
-            } => responder
-                .respond(self.put_finality_signature(signature)?)
-                .ignore(),
+            } => {
+                responder
+                    .respond(self.write_with_map_growth(|storage| {
+                        storage.put_finality_signature(&signature)
+                    })?)
+                    .ignore()
+            }
             StorageRequest::GetBlockSignature {
                 block_hash,
                 public_key,
@@ -1137,12 +1447,18 @@ impl Storage {
                     .read_block_header_by_height(block_height, only_from_available_block_range)?;
                 responder.respond(maybe_header).ignore()
             }
+            StorageRequest::GetEraValidatorWeights { era_id, responder } => {
+                let maybe_validator_weights = self.read_era_validator_weights(era_id)?;
+                responder.respond(maybe_validator_weights).ignore()
+            }
             StorageRequest::PutBlockHeader {
                 block_header,
                 responder,
             } => {
                 let block_header_hash = block_header.block_hash();
-                match self.put_block_headers(vec![*block_header]) {
+                match self.write_with_map_growth(|storage| {
+                    storage.put_block_headers(vec![(*block_header).clone()])
+                }) {
                     Ok(result) => responder.respond(result).ignore(),
                     Err(err) => {
                         error!(?err, ?block_header_hash, "error when storing block header");
@@ -1153,12 +1469,37 @@ impl Storage {
             StorageRequest::GetAvailableBlockRange { responder } => {
                 responder.respond(self.get_available_block_range()).ignore()
             }
+            StorageRequest::GetBlockHeightForStateRootHash {
+                state_root_hash,
+                responder,
+            } => responder
+                .respond(self.state_root_hash_index.get(&state_root_hash).copied())
+                .ignore(),
+            StorageRequest::GetStateRootHashesFromHeight { height, responder } => responder
+                .respond(self.get_state_root_hashes_from_height(height)?)
+                .ignore(),
             StorageRequest::StoreFinalizedApprovals {
                 ref deploy_hash,
                 ref finalized_approvals,
                 responder,
             } => responder
-                .respond(self.store_finalized_approvals(deploy_hash, finalized_approvals)?)
+                .respond(self.write_with_map_growth(|storage| {
+                    storage.store_finalized_approvals(deploy_hash, finalized_approvals)
+                })?)
+                .ignore(),
+            StorageRequest::PutBlockAndApprovalsAndSignatures {
+                block,
+                approvals_hashes,
+                signatures,
+                responder,
+            } => responder
+                .respond(self.write_with_map_growth(|storage| {
+                    storage.put_block_and_approvals_and_signatures(
+                        &block,
+                        approvals_hashes.as_deref(),
+                        &signatures,
+                    )
+                })?)
                 .ignore(),
             StorageRequest::PutExecutedBlock {
                 block,
@@ -1166,107 +1507,1324 @@ impl Storage {
                 execution_results,
                 responder,
             } => responder
-                .respond(self.put_executed_block(&block, &approvals_hashes, execution_results)?)
+                .respond(self.write_with_map_growth(|storage| {
+                    storage.put_executed_block(&block, &approvals_hashes, &execution_results)
+                })?)
                 .ignore(),
-            StorageRequest::GetKeyBlockHeightForActivationPoint { responder } => {
-                // If we haven't already cached the height, try to retrieve the key block header.
-                if self.key_block_height_for_activation_point.is_none() {
-                    let mut txn = self.env.begin_ro_txn()?;
-                    let key_block_era = self.activation_era.predecessor().unwrap_or_default();
-                    let key_block_header =
-                        match self.get_switch_block_header_by_era_id(&mut txn, key_block_era)? {
-                            Some(block_header) => block_header,
-                            None => return Ok(responder.respond(None).ignore()),
-                        };
-                    self.key_block_height_for_activation_point = Some(key_block_header.height());
+            StorageRequest::PruneBlocks {
+                below_height,
+                responder,
+            } => {
+                let outcome = self.write_with_map_growth(|storage| {
+                    storage.prune_blocks_below(below_height, u64::MAX)
+                })?;
+                info!(%outcome, "Storage: pruned blocks on request");
+                responder.respond(outcome).ignore()
+            }
+            StorageRequest::MarkGlobalStatePrunedBelow { height, responder } => {
+                let global_state_pruned_below_height = self.write_with_map_growth(|storage| {
+                    storage.mark_global_state_pruned_below(height)
+                })?;
+                responder.respond(global_state_pruned_below_height).ignore()
+            }
+            StorageRequest::CreateSnapshot { path, responder } => {
+                let result = self.create_snapshot(path);
+                match result {
+                    Ok(ref snapshot) => info!(%snapshot, "Storage: created snapshot"),
+                    Err(ref error) => warn!(%error, "Storage: failed to create snapshot"),
                 }
-                responder
-                    .respond(self.key_block_height_for_activation_point)
-                    .ignore()
+                responder.respond(result).ignore()
+            }
+            StorageRequest::VerifyIntegrity { repair, responder } => {
+                let verification = self.verify_integrity(repair)?;
+                info!(%verification, "Storage: verified integrity");
+                responder.respond(verification).ignore()
             }
+            StorageRequest::GetKeyBlockHeightForActivationPoint { responder } => responder
+                .respond(self.read_key_block_height_for_activation_point()?)
+                .ignore(),
         })
     }
 
-    fn put_finality_signature(
-        &mut self,
-        signature: Box<FinalitySignature>,
-    ) -> Result<bool, FatalStorageError> {
+    /// Runs `write`, growing the memory map and retrying whenever it fails because the map is full.
+    ///
+    /// `write` must leave the databases unchanged when failing, so that it can be retried.  The map
+    /// can only be grown while no transactions are open, which holds since storage handles a single
+    /// request at a time.
+    fn write_with_map_growth<T, F>(&mut self, mut write: F) -> Result<T, FatalStorageError>
+    where
+        F: FnMut(&mut Self) -> Result<T, FatalStorageError>,
+    {
+        let mut attempts = 0;
+        loop {
+            match write(self) {
+                Err(error)
+                    if error.is_map_full()
+                        && self.map_size_increment > 0
+                        && attempts < MAX_MAP_GROWTH_ATTEMPTS =>
+                {
+                    attempts += 1;
+                    self.grow_map()?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Grows the memory map of the environment by the configured increment.
+    fn grow_map(&mut self) -> Result<(), FatalStorageError> {
+        let new_map_size = self.map_size.saturating_add(self.map_size_increment);
+        self.env.set_map_size(new_map_size)?;
+        warn!(
+            old_map_size = self.map_size,
+            new_map_size, "Storage: database full, grew memory map"
+        );
+        self.map_size = new_map_size;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics
+                .map_size
+                .set(new_map_size.try_into().unwrap_or(i64::MAX));
+            metrics.map_resizes.inc();
+        }
+        Ok(())
+    }
+
+    /// Merges the given finality signatures into those stored for the block.
+    fn put_block_signatures(
+        &mut self,
+        signatures: &BlockSignatures,
+    ) -> Result<bool, FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let outcome = self.write_block_signatures(&mut txn, signatures)?;
+        txn.commit()?;
+        self.read_cache
+            .get_mut()
+            .remove_block_signatures(&signatures.block_hash);
+        Ok(outcome)
+    }
+
+    /// Merges the given finality signatures into the ones already stored for their block, if any.
+    fn write_block_signatures(
+        &self,
+        txn: &mut RwTransaction,
+        signatures: &BlockSignatures,
+    ) -> Result<bool, FatalStorageError> {
+        let old_data: Option<BlockSignatures> =
+            txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
+        let new_data = match old_data {
+            None => signatures.clone(),
+            Some(mut data) => {
+                for (public_key, sig) in &signatures.proofs {
+                    data.insert_proof(public_key.clone(), *sig);
+                }
+                data
+            }
+        };
+        Ok(txn.put_value(
+            self.block_metadata_db,
+            &new_data.block_hash,
+            &new_data,
+            true,
+        )?)
+    }
+
+    fn put_finality_signature(
+        &mut self,
+        signature: &FinalitySignature,
+    ) -> Result<bool, FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut block_signatures = txn
+            .get_value(self.block_metadata_db, &signature.block_hash)?
+            .unwrap_or_else(|| BlockSignatures::new(signature.block_hash, signature.era_id));
+        block_signatures.insert_proof(signature.public_key.clone(), signature.signature);
+        let outcome = txn.put_value(
+            self.block_metadata_db,
+            &block_signatures.block_hash,
+            &block_signatures,
+            true,
+        )?;
+        txn.commit()?;
+        self.read_cache
+            .get_mut()
+            .remove_block_signatures(&signature.block_hash);
+        Ok(outcome)
+    }
+
+    /// Handles a [`BlockCompletedAnnouncement`].
+    fn handle_mark_block_completed_request(
+        &mut self,
+        MarkBlockCompletedRequest {
+            block_height,
+            responder,
+        }: MarkBlockCompletedRequest,
+    ) -> Result<Effects<Event>, FatalStorageError> {
+        let is_new =
+            self.write_with_map_growth(|storage| storage.mark_block_complete(block_height))?;
+        Ok(responder.respond(is_new).ignore())
+    }
+
+    /// Marks the block at height `block_height` as complete by inserting it
+    /// into the `completed_blocks` index and storing it to disk.
+    fn mark_block_complete(&mut self, block_height: u64) -> Result<bool, FatalStorageError> {
+        let is_new = self.completed_blocks.insert(block_height);
+        if is_new {
+            if let Err(error) = self.persist_completed_blocks() {
+                // Undo the insertion, so that the block is marked complete again on a retry.
+                self.completed_blocks.remove(block_height);
+                return Err(error);
+            }
+            info!(
+                "Storage: marked block {} complete: {}",
+                block_height,
+                self.get_available_block_range()
+            );
+            self.update_chain_height_metrics();
+        } else {
+            debug!(
+                "Storage: tried to mark already-complete block {} complete",
+                block_height
+            );
+        }
+        Ok(is_new)
+    }
+
+    /// Starts the background pruning task, if a block body retention depth is configured.
+    pub(crate) fn start_pruning<REv: Send>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event> {
+        if self.block_body_retention_depth.is_none() {
+            return Effects::new();
+        }
+        effect_builder
+            .set_timeout(self.prune_interval.into())
+            .event(|_| Event::Prune)
+    }
+
+    /// Prunes a batch of blocks older than the retention depth and schedules the next step.
+    fn handle_prune<REv: Send>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Result<Effects<Event>, FatalStorageError> {
+        if let (Some(retention_depth), Some(highest_complete_block_height)) = (
+            self.block_body_retention_depth,
+            self.highest_complete_block_height(),
+        ) {
+            let below_height = highest_complete_block_height.saturating_sub(retention_depth);
+            let outcome = self.write_with_map_growth(|storage| {
+                storage.prune_blocks_below(below_height, MAX_BLOCKS_PRUNED_PER_STEP)
+            })?;
+            if outcome.pruned_block_count > 0 {
+                info!(%outcome, "Storage: pruned old blocks");
+            }
+        }
+        Ok(self.start_pruning(effect_builder))
+    }
+
+    /// Prunes the bodies, deploys and execution results of up to `max_blocks` blocks below
+    /// `requested_height`, retaining their headers and finality signatures.
+    ///
+    /// Never prunes the highest complete block, blocks from the switch block preceding the last
+    /// emergency restart onwards, or blocks whose deploys are still within the max TTL of the
+    /// highest complete block.
+    fn prune_blocks_below(
+        &mut self,
+        requested_height: u64,
+        max_blocks: u64,
+    ) -> Result<PruneOutcome, FatalStorageError> {
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        let mut outcome = PruneOutcome {
+            requested_height,
+            pruned_below_height: self.pruned_below_height,
+            pruned_block_count: 0,
+            limited_by: None,
+        };
+        let highest_complete_block_header =
+            match self.get_highest_complete_block_header(&mut txn)? {
+                Some(block_header) => block_header,
+                None => {
+                    outcome.limited_by = Some(PruneLimit::HighestCompleteBlock);
+                    return Ok(outcome);
+                }
+            };
+
+        let mut limit = requested_height;
+        if limit > highest_complete_block_header.height() {
+            limit = highest_complete_block_header.height();
+            outcome.limited_by = Some(PruneLimit::HighestCompleteBlock);
+        }
+        if let Some(era_id) = self.last_emergency_restart {
+            // If the switch block preceding the restart isn't stored, we can't tell where the
+            // restart happened, so we don't prune anything.
+            let key_block_height = self
+                .get_switch_block_header_by_era_id(
+                    &mut txn,
+                    era_id.predecessor().unwrap_or_default(),
+                )?
+                .map_or(0, |block_header| block_header.height());
+            if limit > key_block_height {
+                limit = key_block_height;
+                outcome.limited_by = Some(PruneLimit::EmergencyRestart(era_id));
+            }
+        }
+
+        let mut cold_storage_keys = ColdStorageKeys::default();
+        let mut height = self.pruned_below_height;
+        while height < limit {
+            if outcome.pruned_block_count >= max_blocks {
+                outcome.limited_by = Some(PruneLimit::BatchSize);
+                break;
+            }
+            if let Some(block_hash) = self.block_height_index.get(&height).copied() {
+                if let Some(block_header) = self.get_single_block_header(&mut txn, &block_hash)? {
+                    if !self.max_ttl.ttl_elapsed(
+                        highest_complete_block_header.timestamp(),
+                        block_header.timestamp(),
+                    ) {
+                        outcome.limited_by = Some(PruneLimit::DeployTtl);
+                        break;
+                    }
+                    self.prune_block(&mut txn, &block_header, &mut cold_storage_keys)?;
+                    outcome.pruned_block_count += 1;
+                }
+            }
+            height += 1;
+        }
+        txn.commit()?;
+        if let Some(cold_storage) = self.cold_storage.as_mut() {
+            if !cold_storage_keys.is_empty() {
+                cold_storage.delete(&cold_storage_keys)?;
+            }
+        }
+
+        if height > self.pruned_below_height {
+            self.pruned_below_height = height;
+            let serialized = height
+                .to_bytes()
+                .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+            self.write_state_store(Cow::Borrowed(PRUNED_BELOW_HEIGHT_STORAGE_KEY), &serialized)?;
+            // Pruned blocks are no longer available in full.
+            self.completed_blocks.truncate_below(height);
+            self.persist_completed_blocks()?;
+            self.update_chain_height_metrics();
+        }
+        outcome.pruned_below_height = self.pruned_below_height;
+        Ok(outcome)
+    }
+
+    /// Deletes the body, deploys, execution results, transfers and approvals hashes of the given
+    /// block.
+    ///
+    /// The keys of the items to be deleted from the cold storage tier are added to
+    /// `cold_storage_keys`, as they must only be deleted once `txn` is committed.
+    fn prune_block(
+        &mut self,
+        txn: &mut RwTransaction,
+        block_header: &BlockHeader,
+        cold_storage_keys: &mut ColdStorageKeys,
+    ) -> Result<(), FatalStorageError> {
+        let block_hash = block_header.block_hash();
+        if let Some(block_body) = self.get_block_body(txn, block_header.body_hash())? {
+            // Bodies without deploys can be shared by several blocks of the same proposer, so we
+            // keep them; they're tiny anyway.
+            if block_body.deploy_and_transfer_hashes().next().is_some() {
+                for deploy_hash in block_body.deploy_and_transfer_hashes() {
+                    if let Some(deploy) = txn.get_value::<_, Deploy>(self.deploy_db, deploy_hash)? {
+                        let key = deploy_by_account_key(deploy.header().account(), deploy_hash)?;
+                        delete_if_exists(txn, self.deploys_by_account_db, &key)?;
+                    }
+                    delete_if_exists(txn, self.deploy_db, deploy_hash)?;
+                    delete_if_exists(txn, self.deploy_metadata_db, deploy_hash)?;
+                    delete_if_exists(txn, self.finalized_approvals_db, deploy_hash)?;
+                    self.deploy_hash_index.remove(deploy_hash);
+                    cold_storage_keys.deploy_hashes.push(*deploy_hash);
+                }
+                delete_if_exists(txn, self.block_body_db, block_header.body_hash())?;
+                cold_storage_keys
+                    .block_body_hashes
+                    .push(*block_header.body_hash());
+            }
+        }
+        if let Some(transfers) = txn.get_value::<_, Vec<Transfer>>(self.transfer_db, &block_hash)? {
+            for account_transfer in account_transfers(block_hash, block_header.height(), &transfers)
+            {
+                for key in transfer_by_account_keys(&account_transfer) {
+                    delete_if_exists(txn, self.transfers_by_account_db, &key)?;
+                }
+            }
+        }
+        delete_if_exists(txn, self.transfer_db, &block_hash)?;
+        delete_if_exists(txn, self.approvals_hashes_db, &block_hash)?;
+        self.read_cache.get_mut().remove_block(&block_hash);
+        Ok(())
+    }
+
+    /// Returns the height below which the bodies, deploys and execution results of all blocks
+    /// have been pruned.
+    pub(crate) fn pruned_below_height(&self) -> u64 {
+        self.pruned_below_height
+    }
+
+    /// Returns the height below which the global state of all blocks may have been garbage
+    /// collected.
+    pub(crate) fn global_state_pruned_below_height(&self) -> u64 {
+        self.global_state_pruned_below_height
+    }
+
+    /// Records that the global state of the blocks below `height` may be garbage collected,
+    /// removing them from the available block range so that we stop advertising them to peers.
+    ///
+    /// The height is capped at the highest complete block and never decreases.  Returns the
+    /// resulting height.
+    fn mark_global_state_pruned_below(&mut self, height: u64) -> Result<u64, FatalStorageError> {
+        let height = height.min(self.highest_complete_block_height().unwrap_or_default());
+        if height > self.global_state_pruned_below_height {
+            let serialized = height
+                .to_bytes()
+                .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+            self.write_state_store(
+                Cow::Borrowed(GLOBAL_STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY),
+                &serialized,
+            )?;
+            self.global_state_pruned_below_height = height;
+            info!(
+                height,
+                "Storage: global state of blocks below height may be pruned"
+            );
+        }
+        // Blocks synced again since the last call are removed from the available range again.
+        self.completed_blocks
+            .truncate_below(self.global_state_pruned_below_height);
+        self.persist_completed_blocks()?;
+        self.update_chain_height_metrics();
+        Ok(self.global_state_pruned_below_height)
+    }
+
+    /// Starts the background migration to the cold storage tier, if one is configured.
+    pub(crate) fn start_cold_storage_migration<REv: Send>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event> {
+        if self.cold_storage.is_none() {
+            return Effects::new();
+        }
+        effect_builder
+            .set_timeout(self.cold_storage_migration_interval.into())
+            .event(|_| Event::MigrateToColdStorage)
+    }
+
+    /// Migrates a batch of old blocks to the cold storage tier and schedules the next step.
+    fn handle_cold_storage_migration<REv: Send>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Result<Effects<Event>, FatalStorageError> {
+        let migrated_block_count = self.write_with_map_growth(|storage| {
+            storage.migrate_to_cold_storage(MAX_BLOCKS_MIGRATED_PER_STEP)
+        })?;
+        if migrated_block_count > 0 {
+            info!(
+                migrated_block_count,
+                migrated_below_height = self.migrated_below_height,
+                "Storage: migrated old blocks to cold storage"
+            );
+        }
+        Ok(self.start_cold_storage_migration(effect_builder))
+    }
+
+    /// Moves the bodies and execution results of up to `max_blocks` blocks from eras more than the
+    /// cold storage era depth below the era of the highest complete block to the cold storage
+    /// tier.
+    ///
+    /// Blocks are migrated in ascending order of height, so migration doesn't progress past a
+    /// block which is neither complete nor pruned.  Returns the number of blocks migrated.
+    fn migrate_to_cold_storage(&mut self, max_blocks: u64) -> Result<u64, FatalStorageError> {
+        if self.cold_storage.is_none() {
+            return Ok(0);
+        }
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_ro_txn()?;
+        let highest_complete_block_header =
+            match self.get_highest_complete_block_header(&mut txn)? {
+                Some(block_header) => block_header,
+                None => return Ok(0),
+            };
+        let era_limit = highest_complete_block_header
+            .era_id()
+            .saturating_sub(self.cold_storage_era_depth);
+
+        let mut batch = ColdStorageBatch::default();
+        let mut migrated_block_count = 0;
+        let mut height = self.migrated_below_height;
+        while height < highest_complete_block_header.height() && migrated_block_count < max_blocks {
+            // Blocks whose global state may have been pruned are no longer tracked as complete,
+            // although their bodies are still stored in full.
+            if height
+                >= self
+                    .pruned_below_height
+                    .max(self.global_state_pruned_below_height)
+                && !self.completed_blocks.contains(height)
+            {
+                break;
+            }
+            let maybe_block_header = match self.block_height_index.get(&height) {
+                Some(block_hash) => self.get_single_block_header(&mut txn, block_hash)?,
+                None => None,
+            };
+            if let Some(block_header) = maybe_block_header {
+                if block_header.era_id() >= era_limit {
+                    break;
+                }
+                if let Some(block_body) =
+                    txn.get_value::<_, BlockBody>(self.block_body_db, block_header.body_hash())?
+                {
+                    for deploy_hash in block_body.deploy_and_transfer_hashes() {
+                        if let Some(deploy_metadata) =
+                            txn.get_value(self.deploy_metadata_db, deploy_hash)?
+                        {
+                            batch.deploy_metadata.push((*deploy_hash, deploy_metadata));
+                        }
+                    }
+                    batch
+                        .block_bodies
+                        .push((*block_header.body_hash(), block_body));
+                }
+                migrated_block_count += 1;
+            }
+            height += 1;
+        }
+        drop(txn);
+        if height == self.migrated_below_height {
+            return Ok(0);
+        }
+
+        // The items are written to the cold tier first, so they're never missing from both.
+        if !batch.is_empty() {
+            if let Some(cold_storage) = self.cold_storage.as_mut() {
+                cold_storage.put(&batch)?;
+            }
+        }
+        let mut txn = env.begin_rw_txn()?;
+        for (block_body_hash, _) in &batch.block_bodies {
+            delete_if_exists(&mut txn, self.block_body_db, block_body_hash)?;
+        }
+        for (deploy_hash, _) in &batch.deploy_metadata {
+            delete_if_exists(&mut txn, self.deploy_metadata_db, deploy_hash)?;
+        }
+        let serialized = height
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        txn.put(
+            self.state_store_db,
+            &MIGRATED_BELOW_HEIGHT_STORAGE_KEY,
+            &serialized,
+            WriteFlags::default(),
+        )?;
+        txn.commit()?;
+        self.migrated_below_height = height;
+        Ok(migrated_block_count)
+    }
+
+    /// Copies all databases into a new environment in the directory `path`, which must not exist.
+    ///
+    /// Everything is read within a single read transaction, so the copy is consistent even though
+    /// it is written in several transactions. The storage component is blocked while copying.
+    ///
+    /// The cold storage tier, if any, is not copied.
+    fn create_snapshot(&self, path: PathBuf) -> Result<StorageSnapshot, CreateSnapshotError> {
+        if path.exists() {
+            return Err(CreateSnapshotError::AlreadyExists(path));
+        }
+        fs::create_dir_all(&path)
+            .map_err(|err| CreateSnapshotError::CreateDirectory(path.clone(), err))?;
+
+        // The snapshot uses the same settings as the live environment, so that a node can be
+        // started from it.
+        let snapshot_env = Environment::new()
+            .set_flags(
+                OS_FLAGS
+                    | EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_TLS
+                    | EnvironmentFlags::NO_READAHEAD,
+            )
+            .set_max_dbs(MAX_DB_COUNT)
+            .set_map_size(self.map_size)
+            .open(&path.join(STORAGE_DB_FILENAME))?;
+
+        // All databases must be created before opening a write transaction on the environment.
+        let mut databases = Vec::new();
+        for (name, db) in self.named_databases() {
+            let snapshot_db = snapshot_env.create_db(Some(name), DatabaseFlags::empty())?;
+            databases.push((db, snapshot_db));
+        }
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut snapshot_txn = snapshot_env.begin_rw_txn()?;
+        let mut entry_count = 0;
+        for (db, snapshot_db) in databases {
+            let mut cursor = txn.open_ro_cursor(db)?;
+            for row in cursor.iter() {
+                let (key, value) = row?;
+                // Keys are iterated in order, so they can be appended.
+                snapshot_txn.put(snapshot_db, key, value, WriteFlags::APPEND)?;
+                entry_count += 1;
+                if entry_count % SNAPSHOT_ENTRIES_PER_TXN == 0 {
+                    snapshot_txn.commit()?;
+                    snapshot_txn = snapshot_env.begin_rw_txn()?;
+                }
+            }
+        }
+        snapshot_txn.commit()?;
+        snapshot_env.sync(true)?;
+
+        Ok(StorageSnapshot { path, entry_count })
+    }
+
+    /// Returns all databases along with their names.
+    fn named_databases(&self) -> [(&'static str, Database); 16] {
+        [
+            ("block_header", self.block_header_db),
+            ("block_metadata", self.block_metadata_db),
+            ("deploys", self.deploy_db),
+            ("deploy_metadata", self.deploy_metadata_db),
+            ("transfer", self.transfer_db),
+            ("state_store", self.state_store_db),
+            ("finalized_approvals", self.finalized_approvals_db),
+            ("block_body", self.block_body_db),
+            ("approvals_hashes", self.approvals_hashes_db),
+            ("deploys_by_account", self.deploys_by_account_db),
+            ("transfers_by_account", self.transfers_by_account_db),
+            ("equivocations", self.equivocations_db),
+            ("validator_performance", self.validator_performance_db),
+            ("trie_chunks", self.trie_chunks_db),
+            ("era_transition_proofs", self.era_transition_proofs_db),
+            ("zk_proving_jobs", self.proving_jobs_db),
+        ]
+    }
+
+    /// Verifies the integrity of all stored blocks, optionally repairing the issues found.
+    ///
+    /// Every block header and body is re-hashed, and stored finality signatures are verified and
+    /// checked against the validator weights of the block's era, if the switch block preceding
+    /// that era is stored.
+    pub(crate) fn verify_integrity(
+        &mut self,
+        repair: bool,
+    ) -> Result<StorageVerification, FatalStorageError> {
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_ro_txn()?;
+
+        // Looking up switch blocks needs the transaction mutably, so collect the keys first.
+        let mut keys = vec![];
+        {
+            let mut cursor = txn.open_ro_cursor(self.block_header_db)?;
+            for row in cursor.iter() {
+                let (raw_key, _) = row?;
+                keys.push(raw_key.to_vec());
+            }
+        }
+
+        let mut issues = vec![];
+        let mut damaged_blocks = vec![];
+        let mut era_validator_weights = BTreeMap::new();
+        for raw_key in &keys {
+            let block_header: BlockHeader = match get_raw_value(
+                &txn,
+                self.block_header_db,
+                raw_key,
+            )?
+            .map(lmdb_ext::deserialize)
+            {
+                Some(Ok(block_header)) => block_header,
+                Some(Err(_)) => {
+                    issues.push(StorageIssue::CorruptHeader {
+                        key: base16::encode_lower(raw_key),
+                    });
+                    continue;
+                }
+                None => continue,
+            };
+            let block_hash = block_header.block_hash();
+            if block_hash.as_ref() != raw_key.as_slice() {
+                let stored_under = match Digest::try_from(raw_key.as_slice()) {
+                    Ok(digest) => BlockHash::new(digest),
+                    Err(_) => {
+                        issues.push(StorageIssue::CorruptHeader {
+                            key: base16::encode_lower(raw_key),
+                        });
+                        continue;
+                    }
+                };
+                issues.push(StorageIssue::HeaderHashMismatch {
+                    stored_under,
+                    actual: block_hash,
+                });
+                continue;
+            }
+
+            let era_id = block_header.era_id();
+            if let btree_map::Entry::Vacant(entry) = era_validator_weights.entry(era_id) {
+                let maybe_weights = match era_id.predecessor() {
+                    Some(key_block_era) => self
+                        .get_switch_block_header_by_era_id(&mut txn, key_block_era)?
+                        .and_then(|key_block_header| {
+                            key_block_header.next_era_validator_weights().cloned()
+                        }),
+                    None => None,
+                };
+                entry.insert(maybe_weights);
+            }
+            let maybe_weights = &era_validator_weights[&era_id];
+
+            let mut block_issues = vec![];
+            let height = block_header.height();
+            let maybe_raw_block_body =
+                match get_raw_value(&txn, self.block_body_db, block_header.body_hash())? {
+                    Some(raw) => Some(Cow::Borrowed(raw)),
+                    None => match self.cold_storage.as_ref() {
+                        Some(cold_storage) => cold_storage
+                            .get_raw_block_body(block_header.body_hash())?
+                            .map(Cow::Owned),
+                        None => None,
+                    },
+                };
+            match maybe_raw_block_body
+                .as_deref()
+                .map(lmdb_ext::deserialize::<BlockBody>)
+            {
+                Some(Ok(block_body)) => {
+                    if block_body.hash() != *block_header.body_hash() {
+                        block_issues.push(StorageIssue::BodyHashMismatch { block_hash, height });
+                    }
+                }
+                Some(Err(_)) => block_issues.push(StorageIssue::CorruptBody { block_hash, height }),
+                None => {
+                    if self.completed_blocks.contains(height) && height >= self.pruned_below_height
+                    {
+                        block_issues.push(StorageIssue::MissingBody { block_hash, height });
+                    }
+                }
+            }
+
+            match get_raw_value(&txn, self.block_metadata_db, &block_hash)?
+                .map(lmdb_ext::deserialize::<BlockSignatures>)
+            {
+                Some(Ok(block_signatures)) => {
+                    if block_signatures.block_hash != block_hash
+                        || block_signatures.era_id != era_id
+                    {
+                        block_issues.push(StorageIssue::SignaturesMismatch { block_hash });
+                    } else {
+                        for signature in block_signatures.finality_signatures() {
+                            let public_key = signature.public_key.clone();
+                            if signature.is_verified().is_err() {
+                                block_issues.push(StorageIssue::InvalidSignature {
+                                    block_hash,
+                                    public_key,
+                                });
+                            } else if maybe_weights
+                                .as_ref()
+                                .map_or(false, |weights| !weights.contains_key(&public_key))
+                            {
+                                block_issues.push(StorageIssue::UnknownSigner {
+                                    block_hash,
+                                    public_key,
+                                });
+                            }
+                        }
+                    }
+                }
+                Some(Err(_)) => block_issues.push(StorageIssue::CorruptSignatures { block_hash }),
+                None => (),
+            }
+
+            if !block_issues.is_empty() {
+                issues.extend(block_issues.iter().cloned());
+                damaged_blocks.push((block_header, block_issues));
+            }
+        }
+        drop(txn);
+
+        let checked_block_count = keys.len() as u64;
+        if !issues.is_empty() {
+            warn!(
+                checked_block_count,
+                issue_count = issues.len(),
+                "Storage: found inconsistencies"
+            );
+        }
+        if repair && !damaged_blocks.is_empty() {
+            self.repair_blocks(&damaged_blocks)?;
+        }
+
+        Ok(StorageVerification {
+            checked_block_count,
+            issues,
+            repaired: repair,
+        })
+    }
+
+    /// Discards the damaged data of the given blocks and marks them as incomplete, so that they
+    /// are synchronized again.
+    fn repair_blocks(
+        &mut self,
+        damaged_blocks: &[(BlockHeader, Vec<StorageIssue>)],
+    ) -> Result<(), FatalStorageError> {
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        let mut cold_storage_keys = ColdStorageKeys::default();
+        for (block_header, block_issues) in damaged_blocks {
+            let block_hash = block_header.block_hash();
+            let mut invalid_signers = vec![];
+            for issue in block_issues {
+                match issue {
+                    StorageIssue::CorruptBody { .. } | StorageIssue::BodyHashMismatch { .. } => {
+                        delete_if_exists(&mut txn, self.block_body_db, block_header.body_hash())?;
+                        cold_storage_keys
+                            .block_body_hashes
+                            .push(*block_header.body_hash());
+                    }
+                    StorageIssue::CorruptSignatures { .. }
+                    | StorageIssue::SignaturesMismatch { .. } => {
+                        delete_if_exists(&mut txn, self.block_metadata_db, &block_hash)?;
+                    }
+                    StorageIssue::InvalidSignature { public_key, .. }
+                    | StorageIssue::UnknownSigner { public_key, .. } => {
+                        invalid_signers.push(public_key)
+                    }
+                    StorageIssue::CorruptHeader { .. }
+                    | StorageIssue::HeaderHashMismatch { .. }
+                    | StorageIssue::MissingBody { .. } => (),
+                }
+            }
+
+            if !invalid_signers.is_empty() {
+                let maybe_block_signatures: Option<BlockSignatures> =
+                    txn.get_value(self.block_metadata_db, &block_hash)?;
+                if let Some(mut block_signatures) = maybe_block_signatures {
+                    for public_key in invalid_signers {
+                        block_signatures.proofs.remove(public_key);
+                    }
+                    if block_signatures.proofs.is_empty() {
+                        delete_if_exists(&mut txn, self.block_metadata_db, &block_hash)?;
+                    } else {
+                        let _ = txn.put_value(
+                            self.block_metadata_db,
+                            &block_hash,
+                            &block_signatures,
+                            true,
+                        )?;
+                    }
+                }
+            }
+
+            if block_issues.iter().any(StorageIssue::requires_resync) {
+                self.completed_blocks.remove(block_header.height());
+            }
+            info!(%block_hash, "Storage: repaired block");
+        }
+        txn.commit()?;
+        if let Some(cold_storage) = self.cold_storage.as_mut() {
+            if !cold_storage_keys.is_empty() {
+                cold_storage.delete(&cold_storage_keys)?;
+            }
+        }
+        self.read_cache.get_mut().clear();
+        self.persist_completed_blocks()
+    }
+
+    /// Writes the blocks at heights `from` to `to` inclusive, along with their deploys, finality
+    /// signatures and approvals hashes, to `writer` in the block export format.
+    ///
+    /// Returns the number of blocks exported.
+    pub(crate) fn export_blocks<W: Write>(
+        &self,
+        from: u64,
+        to: u64,
+        writer: &mut W,
+    ) -> Result<u64, BlockExportError> {
+        block_export::write_file_header(writer)?;
+        let mut exported_count = 0;
+        for height in from..=to {
+            let mut txn = self.env.begin_ro_txn().map_err(FatalStorageError::from)?;
+            let block = self
+                .get_block_by_height(&mut txn, height)?
+                .ok_or(BlockExportError::MissingBlock(height))?;
+            let mut deploys = vec![];
+            for deploy_hash in block.deploy_and_transfer_hashes() {
+                let deploy = self
+                    .get_deploy_with_finalized_approvals(&mut txn, deploy_hash)
+                    .map_err(FatalStorageError::from)?
+                    .ok_or(BlockExportError::MissingDeploy {
+                        block_hash: *block.hash(),
+                        deploy_hash: *deploy_hash,
+                    })?;
+                deploys.push(deploy);
+            }
+            let signatures = self.get_block_signatures(&mut txn, block.hash())?;
+            let approvals_hashes = txn
+                .get_value(self.approvals_hashes_db, block.hash())
+                .map_err(FatalStorageError::from)?;
+            drop(txn);
+
+            let record = BlockExportRecord {
+                block,
+                deploys,
+                signatures,
+                approvals_hashes,
+            };
+            block_export::write_record(writer, &record)?;
+            exported_count += 1;
+        }
+        writer.flush()?;
+        Ok(exported_count)
+    }
+
+    /// Reads blocks in the block export format from `reader` and stores them, along with their
+    /// deploys, finality signatures and approvals hashes.
+    ///
+    /// Every record is validated before anything in it is stored. Imported blocks are not marked
+    /// as complete, as their execution results and global state are not part of the export; the
+    /// block synchronizer still acquires those, but finds everything else in storage.
+    ///
+    /// Returns the number of blocks imported.
+    pub(crate) fn import_blocks<R: Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<u64, BlockExportError> {
+        block_export::read_file_header(reader)?;
+        let mut imported_count = 0;
+        while let Some(record) = block_export::read_record(reader)? {
+            let BlockExportRecord {
+                block,
+                deploys,
+                signatures,
+                approvals_hashes,
+            } = record;
+            let height = block.height();
+            let invalid = |reason: String| BlockExportError::InvalidRecord { height, reason };
+
+            block.verify().map_err(|err| invalid(err.to_string()))?;
+            let mut deploys_to_store = vec![];
+            for deploy in deploys {
+                let (deploy, maybe_finalized_approvals) = deploy.into_parts();
+                deploy
+                    .has_valid_hash()
+                    .map_err(|err| invalid(format!("deploy {}: {}", deploy.hash(), err)))?;
+                deploys_to_store.push((deploy, maybe_finalized_approvals));
+            }
+            let imported_deploy_hashes: Vec<_> = deploys_to_store
+                .iter()
+                .map(|(deploy, _)| deploy.hash())
+                .collect();
+            if !block
+                .deploy_and_transfer_hashes()
+                .eq(imported_deploy_hashes.iter().copied())
+            {
+                return Err(invalid("deploys do not match the block body".to_string()));
+            }
+            if let Some(signatures) = &signatures {
+                if signatures.block_hash != *block.hash()
+                    || signatures.era_id != block.header().era_id()
+                {
+                    return Err(invalid(
+                        "finality signatures are for another block".to_string(),
+                    ));
+                }
+                signatures
+                    .verify()
+                    .map_err(|err| invalid(err.to_string()))?;
+            }
+            if let Some(approvals_hashes) = &approvals_hashes {
+                approvals_hashes
+                    .validate(&block)
+                    .map_err(|err| invalid(err.to_string()))?;
+            }
+
+            for (deploy, maybe_finalized_approvals) in deploys_to_store {
+                self.put_deploy(&deploy)?;
+                if let Some(finalized_approvals) = maybe_finalized_approvals {
+                    self.store_finalized_approvals(deploy.hash(), &finalized_approvals)?;
+                }
+            }
+            self.write_block(&block)?;
+            let env = Rc::clone(&self.env);
+            let mut txn = env.begin_rw_txn().map_err(FatalStorageError::from)?;
+            if let Some(signatures) = signatures {
+                let mut merged_signatures = self
+                    .get_block_signatures(&mut txn, block.hash())?
+                    .unwrap_or_else(|| {
+                        BlockSignatures::new(*block.hash(), block.header().era_id())
+                    });
+                merged_signatures.proofs.extend(signatures.proofs);
+                let _ = txn
+                    .put_value(
+                        self.block_metadata_db,
+                        block.hash(),
+                        &merged_signatures,
+                        true,
+                    )
+                    .map_err(FatalStorageError::from)?;
+            }
+            if let Some(approvals_hashes) = approvals_hashes {
+                self.write_approvals_hashes(&mut txn, &approvals_hashes)?;
+            }
+            txn.commit().map_err(FatalStorageError::from)?;
+            self.read_cache
+                .get_mut()
+                .remove_block_signatures(block.hash());
+            imported_count += 1;
+        }
+        info!(imported_count, "Storage: imported blocks");
+        Ok(imported_count)
+    }
+
+    /// Persists the completed blocks disjoint sequences state to the database.
+    fn persist_completed_blocks(&mut self) -> Result<(), FatalStorageError> {
+        let serialized = self
+            .completed_blocks
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        self.write_state_store(Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY), &serialized)
+    }
+
+    /// Put a single deploy into storage.
+    pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let deploy_hash = deploy.hash();
+        let outcome = txn.put_value(self.deploy_db, deploy_hash, deploy, false)?;
+        if outcome {
+            let key = deploy_by_account_key(deploy.header().account(), deploy_hash)?;
+            txn.put(self.deploys_by_account_db, &key, b"", WriteFlags::default())?;
+            debug!(%deploy_hash, "Storage: new deploy stored");
+        } else {
+            debug!(%deploy_hash, "Storage: attempt to store existing deploy");
+        }
+        txn.commit()?;
+        Ok(outcome)
+    }
+
+    /// Retrieves the hashes of all stored deploys sent by the given account, indexing the stored
+    /// deploys first if that hasn't been done yet.
+    fn get_deploy_hashes_by_account(
+        &mut self,
+        account: &PublicKey,
+    ) -> Result<Vec<DeployHash>, FatalStorageError> {
+        if !self.deploys_by_account_indexed {
+            self.index_deploys_by_account()?;
+        }
+        let prefix = account
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.deploys_by_account_db)?;
+        let mut deploy_hashes = Vec::new();
+        for row in cursor.iter_from(&prefix) {
+            let (raw_key, _) = row?;
+            if !raw_key.starts_with(&prefix) {
+                break;
+            }
+            let digest = Digest::try_from(&raw_key[prefix.len()..])
+                .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?;
+            deploy_hashes.push(DeployHash::new(digest));
+        }
+        Ok(deploy_hashes)
+    }
+
+    /// Adds the given transfer to the transfers by account index, under both its source and its
+    /// target account.
+    fn put_transfer_by_account(
+        &self,
+        txn: &mut RwTransaction,
+        account_transfer: &AccountTransfer,
+    ) -> Result<(), FatalStorageError> {
+        for key in transfer_by_account_keys(account_transfer) {
+            let _ = txn.put_value(self.transfers_by_account_db, &key, account_transfer, true)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves at most `max_count` stored transfers from or to the given account, most recent
+    /// first, starting after the transfer at `after` if given.
+    ///
+    /// Indexes the stored transfers first if that hasn't been done yet.
+    fn get_transfers_by_account(
+        &mut self,
+        account_hash: &AccountHash,
+        after: Option<&TransferPosition>,
+        max_count: usize,
+    ) -> Result<Vec<AccountTransfer>, FatalStorageError> {
+        if !self.transfers_by_account_indexed {
+            self.index_transfers_by_account()?;
+        }
+        let prefix = account_hash.value();
+        let start = match after {
+            Some(position) => transfer_by_account_key(account_hash, position),
+            None => prefix.to_vec(),
+        };
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.transfers_by_account_db)?;
+        let mut account_transfers = Vec::new();
+        for row in cursor.iter_from(&start) {
+            let (raw_key, raw_val) = row?;
+            if !raw_key.starts_with(&prefix) || account_transfers.len() >= max_count {
+                break;
+            }
+            if after.is_some() && raw_key == start.as_slice() {
+                continue;
+            }
+            account_transfers.push(lmdb_ext::deserialize(raw_val)?);
+        }
+        Ok(account_transfers)
+    }
+
+    /// Puts the record of an observed equivocation into storage, unless the validator's
+    /// equivocation in that era is already recorded.
+    fn put_equivocation(&self, record: &EquivocationRecord) -> Result<bool, FatalStorageError> {
+        let key = equivocation_key(record.era_id, &record.validator)?;
+        let mut txn = self.env.begin_rw_txn()?;
+        let outcome = txn.put_value(self.equivocations_db, &key, record, false)?;
+        txn.commit()?;
+        if outcome {
+            debug!(
+                validator = %record.validator,
+                era_id = %record.era_id,
+                "Storage: new equivocation stored"
+            );
+        }
+        Ok(outcome)
+    }
+
+    /// Retrieves the records of all stored equivocations in `from_era_id` or later, optionally
+    /// only those by `validator`, ordered by era.
+    fn get_equivocations(
+        &self,
+        from_era_id: EraId,
+        validator: Option<&PublicKey>,
+    ) -> Result<Vec<EquivocationRecord>, FatalStorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.equivocations_db)?;
+        let mut records = Vec::new();
+        for row in cursor.iter_from(from_era_id.value().to_be_bytes()) {
+            let (_, raw_val) = row?;
+            let record: EquivocationRecord = lmdb_ext::deserialize(raw_val)?;
+            if validator.map_or(true, |validator| *validator == record.validator) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Puts the report of the validators' performance in an era into storage, replacing any
+    /// earlier report for that era.
+    fn put_validator_performance(
+        &self,
+        report: &ValidatorPerformanceReport,
+    ) -> Result<(), FatalStorageError> {
+        let key = report.era_id.value().to_be_bytes();
+        let mut txn = self.env.begin_rw_txn()?;
+        txn.put_value(self.validator_performance_db, &key, report, true)?;
+        txn.commit()?;
+        debug!(era_id = %report.era_id, "Storage: validator performance stored");
+        Ok(())
+    }
+
+    /// Retrieves the report of the validators' performance in the given era.
+    fn get_validator_performance(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<ValidatorPerformanceReport>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let key = era_id.value().to_be_bytes();
+        Ok(txn.get_value(self.validator_performance_db, &key)?)
+    }
+
+    /// Puts a chunk of a partially fetched trie into storage, so that it needn't be fetched again
+    /// if fetching the trie is interrupted.
+    fn put_trie_chunk(&self, chunk: &ChunkWithProof) -> Result<(), FatalStorageError> {
+        let key = trie_chunk_key(&chunk.proof().root_hash(), chunk.proof().index());
+        let mut txn = self.env.begin_rw_txn()?;
+        txn.put_value(self.trie_chunks_db, &key, chunk, true)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Retrieves the stored chunks of the trie with the given hash, ordered by index.
+    fn get_trie_chunks(
+        &self,
+        trie_hash: &Digest,
+    ) -> Result<Vec<ChunkWithProof>, FatalStorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.trie_chunks_db)?;
+        let mut chunks = Vec::new();
+        for row in cursor.iter_from(trie_hash) {
+            let (raw_key, raw_val) = row?;
+            if !raw_key.starts_with(trie_hash.as_ref()) {
+                break;
+            }
+            chunks.push(lmdb_ext::deserialize(raw_val)?);
+        }
+        Ok(chunks)
+    }
+
+    /// Deletes the stored chunks of the trie with the given hash.
+    fn delete_trie_chunks(&self, trie_hash: &Digest) -> Result<(), FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut keys = Vec::new();
+        {
+            let mut cursor = txn.open_ro_cursor(self.trie_chunks_db)?;
+            for row in cursor.iter_from(trie_hash) {
+                let (raw_key, _) = row?;
+                if !raw_key.starts_with(trie_hash.as_ref()) {
+                    break;
+                }
+                keys.push(raw_key.to_vec());
+            }
+        }
+        for key in &keys {
+            delete_if_exists(&mut txn, self.trie_chunks_db, key)?;
+        }
+        txn.commit()?;
+        if !keys.is_empty() {
+            debug!(%trie_hash, chunk_count = keys.len(), "Storage: trie chunks deleted");
+        }
+        Ok(())
+    }
+
+    /// Puts the proof of the validators set by the switch block of an era into storage, replacing
+    /// any earlier proof for that era.
+    fn put_era_transition_proof(
+        &self,
+        proof: &EraTransitionProof,
+    ) -> Result<(), FatalStorageError> {
+        let era_id = proof.era_id;
+        let key = era_id.value().to_be_bytes();
+        let mut txn = self.env.begin_rw_txn()?;
+        txn.put_value(self.era_transition_proofs_db, &key, proof, true)?;
+        txn.commit()?;
+        debug!(%era_id, "Storage: era transition proof stored");
+        Ok(())
+    }
+
+    /// Retrieves the proof of the validators set by the switch block of the given era.
+    fn get_era_transition_proof(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<EraTransitionProof>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let key = era_id.value().to_be_bytes();
+        Ok(txn.get_value(self.era_transition_proofs_db, &key)?)
+    }
+
+    /// Puts a queued or running proving job into storage.
+    fn put_proving_job(&self, job: &PersistedJob) -> Result<(), FatalStorageError> {
+        let job_id = job.job.job_id();
         let mut txn = self.env.begin_rw_txn()?;
-        let mut block_signatures = txn
-            .get_value(self.block_metadata_db, &signature.block_hash)?
-            .unwrap_or_else(|| BlockSignatures::new(signature.block_hash, signature.era_id));
-        block_signatures.insert_proof(signature.public_key, signature.signature);
-        let outcome = txn.put_value(
-            self.block_metadata_db,
-            &block_signatures.block_hash,
-            &block_signatures,
-            true,
-        )?;
+        txn.put_value(self.proving_jobs_db, job_id.digest(), job, true)?;
         txn.commit()?;
-        Ok(outcome)
+        debug!(%job_id, "Storage: proving job stored");
+        Ok(())
     }
 
-    /// Handles a [`BlockCompletedAnnouncement`].
-    fn handle_mark_block_completed_request(
-        &mut self,
-        MarkBlockCompletedRequest {
-            block_height,
-            responder,
-        }: MarkBlockCompletedRequest,
-    ) -> Result<Effects<Event>, FatalStorageError> {
-        let is_new = self.mark_block_complete(block_height)?;
-        Ok(responder.respond(is_new).ignore())
+    /// Deletes a proving job from storage.
+    fn delete_proving_job(&self, job_id: JobId) -> Result<(), FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        delete_if_exists(&mut txn, self.proving_jobs_db, job_id.digest())?;
+        txn.commit()?;
+        debug!(%job_id, "Storage: proving job deleted");
+        Ok(())
     }
 
-    /// Marks the block at height `block_height` as complete by inserting it
-    /// into the `completed_blocks` index and storing it to disk.
-    fn mark_block_complete(&mut self, block_height: u64) -> Result<bool, FatalStorageError> {
-        let is_new = self.completed_blocks.insert(block_height);
-        if is_new {
-            self.persist_completed_blocks()?;
-            info!(
-                "Storage: marked block {} complete: {}",
-                block_height,
-                self.get_available_block_range()
-            );
-            self.update_chain_height_metrics();
-        } else {
-            debug!(
-                "Storage: tried to mark already-complete block {} complete",
-                block_height
-            );
+    /// Reads the queued and running proving jobs persisted when the node last stopped.
+    pub(crate) fn read_proving_jobs(&self) -> Result<Vec<PersistedJob>, FatalStorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.proving_jobs_db)?;
+        let mut jobs = Vec::new();
+        for row in cursor.iter() {
+            let (_, raw_val) = row?;
+            jobs.push(lmdb_ext::deserialize(raw_val)?);
         }
-        Ok(is_new)
+        Ok(jobs)
     }
 
-    /// Persists the completed blocks disjoint sequences state to the database.
-    fn persist_completed_blocks(&mut self) -> Result<(), FatalStorageError> {
-        let serialized = self
-            .completed_blocks
-            .to_bytes()
-            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
-        self.write_state_store(Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY), &serialized)
+    /// Adds all stored deploys to the deploys by account index.
+    fn index_deploys_by_account(&mut self) -> Result<(), FatalStorageError> {
+        info!("Storage: indexing deploys by account");
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut keys = Vec::new();
+        {
+            let mut cursor = txn.open_ro_cursor(self.deploy_db)?;
+            for row in cursor.iter() {
+                let (_, raw_val) = row?;
+                let deploy: Deploy = lmdb_ext::deserialize(raw_val)?;
+                keys.push(deploy_by_account_key(
+                    deploy.header().account(),
+                    deploy.hash(),
+                )?);
+            }
+        }
+        for key in &keys {
+            txn.put(self.deploys_by_account_db, key, b"", WriteFlags::default())?;
+        }
+        txn.put(
+            self.state_store_db,
+            &DEPLOYS_BY_ACCOUNT_INDEXED_STORAGE_KEY,
+            &[1u8],
+            WriteFlags::default(),
+        )?;
+        txn.commit()?;
+        self.deploys_by_account_indexed = true;
+        info!(
+            deploy_count = keys.len(),
+            "Storage: deploys indexed by account"
+        );
+        Ok(())
     }
 
-    /// Put a single deploy into storage.
-    pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
+    /// Adds all stored transfers of blocks whose header is stored to the transfers by account
+    /// index.
+    fn index_transfers_by_account(&mut self) -> Result<(), FatalStorageError> {
+        info!("Storage: indexing transfers by account");
         let mut txn = self.env.begin_rw_txn()?;
-        let deploy_hash = deploy.hash();
-        let outcome = txn.put_value(self.deploy_db, deploy_hash, deploy, false)?;
-        if outcome {
-            debug!(%deploy_hash, "Storage: new deploy stored");
-        } else {
-            debug!(%deploy_hash, "Storage: attempt to store existing deploy");
+        let mut stored_transfers = Vec::new();
+        {
+            let mut cursor = txn.open_ro_cursor(self.transfer_db)?;
+            for row in cursor.iter() {
+                let (raw_key, raw_val) = row?;
+                let digest = Digest::try_from(raw_key)
+                    .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?;
+                let transfers: Vec<Transfer> = lmdb_ext::deserialize(raw_val)?;
+                stored_transfers.push((BlockHash::new(digest), transfers));
+            }
         }
+        let mut transfer_count = 0;
+        for (block_hash, transfers) in stored_transfers {
+            let block_height = match self.get_single_block_header(&mut txn, &block_hash)? {
+                Some(block_header) => block_header.height(),
+                None => continue,
+            };
+            for account_transfer in account_transfers(block_hash, block_height, &transfers) {
+                self.put_transfer_by_account(&mut txn, &account_transfer)?;
+                transfer_count += 1;
+            }
+        }
+        txn.put(
+            self.state_store_db,
+            &TRANSFERS_BY_ACCOUNT_INDEXED_STORAGE_KEY,
+            &[1u8],
+            WriteFlags::default(),
+        )?;
         txn.commit()?;
-        Ok(outcome)
+        self.transfers_by_account_indexed = true;
+        info!(transfer_count, "Storage: transfers indexed by account");
+        Ok(())
     }
 
     fn put_executed_block(
         &mut self,
         block: &Block,
         approvals_hashes: &ApprovalsHashes,
-        execution_results: HashMap<DeployHash, ExecutionResult>,
+        execution_results: &HashMap<DeployHash, ExecutionResult>,
     ) -> Result<bool, FatalStorageError> {
         let env = Rc::clone(&self.env);
         let mut txn = env.begin_rw_txn()?;
@@ -1296,7 +2854,13 @@ impl Storage {
                 return Ok(false);
             }
         };
-        Ok(txn.value_exists(self.block_body_db, block_header.body_hash())?)
+        if txn.value_exists(self.block_body_db, block_header.body_hash())? {
+            return Ok(true);
+        }
+        match self.cold_storage.as_ref() {
+            Some(cold_storage) => cold_storage.block_body_exists(block_header.body_hash()),
+            None => Ok(false),
+        }
     }
 
     /// Retrieves a approvals hashes by block hash.
@@ -1428,6 +2992,25 @@ impl Storage {
         Ok(Some((finalized_block, deploys)))
     }
 
+    /// Returns the height of the key block for the current activation point, i.e. the height of
+    /// the switch block of the era preceding the activation era, if stored.
+    pub(crate) fn read_key_block_height_for_activation_point(
+        &mut self,
+    ) -> Result<Option<u64>, FatalStorageError> {
+        // If we haven't already cached the height, try to retrieve the key block header.
+        if self.key_block_height_for_activation_point.is_none() {
+            let mut txn = self.env.begin_ro_txn()?;
+            let key_block_era = self.activation_era.predecessor().unwrap_or_default();
+            let key_block_header =
+                match self.get_switch_block_header_by_era_id(&mut txn, key_block_era)? {
+                    Some(block_header) => block_header,
+                    None => return Ok(None),
+                };
+            self.key_block_height_for_activation_point = Some(key_block_header.height());
+        }
+        Ok(self.key_block_height_for_activation_point)
+    }
+
     /// Writes a block to storage, updating indices as necessary.
     ///
     /// Returns `Ok(true)` if the block has been successfully written, `Ok(false)` if a part of it
@@ -1444,6 +3027,59 @@ impl Storage {
         Ok(wrote)
     }
 
+    /// Writes a block along with its approvals hashes, if provided, and its finality signatures in
+    /// a single transaction, so that either all of them or none are stored.
+    ///
+    /// Returns `Ok(false)` without storing anything if the block already existed with different
+    /// contents, or if the approvals hashes or signatures are not those of the block.
+    fn put_block_and_approvals_and_signatures(
+        &mut self,
+        block: &Block,
+        approvals_hashes: Option<&ApprovalsHashes>,
+        signatures: &BlockSignatures,
+    ) -> Result<bool, FatalStorageError> {
+        let block_hash = block.hash();
+        if let Some(approvals_hashes) = approvals_hashes {
+            if approvals_hashes.block_hash() != block_hash {
+                error!(
+                    %block_hash,
+                    approvals_hashes_block_hash = %approvals_hashes.block_hash(),
+                    "approvals hashes to store are not those of the block"
+                );
+                return Ok(false);
+            }
+        }
+        if signatures.block_hash != *block_hash {
+            error!(
+                %block_hash,
+                signatures_block_hash = %signatures.block_hash,
+                "finality signatures to store are not those of the block"
+            );
+            return Ok(false);
+        }
+
+        // Validate the block prior to inserting it into the database
+        block.verify()?;
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        if !self.write_validated_block(&mut txn, block)? {
+            return Ok(false);
+        }
+        if let Some(approvals_hashes) = approvals_hashes {
+            if !self.write_approvals_hashes(&mut txn, approvals_hashes)? {
+                return Ok(false);
+            }
+        }
+        if !signatures.proofs.is_empty() && !self.write_block_signatures(&mut txn, signatures)? {
+            return Ok(false);
+        }
+        txn.commit()?;
+        self.read_cache
+            .get_mut()
+            .remove_block_signatures(block_hash);
+        Ok(true)
+    }
+
     /// Writes a block to storage and marks it as complete, updating indices as necessary.
     ///
     /// Returns `Ok(true)` if the block has been successfully written, `Ok(false)` if a part of it
@@ -1468,37 +3104,48 @@ impl Storage {
         &mut self,
         txn: &mut RwTransaction,
         block_hash: &BlockHash,
-        execution_results: HashMap<DeployHash, ExecutionResult>,
+        execution_results: &HashMap<DeployHash, ExecutionResult>,
     ) -> Result<bool, FatalStorageError> {
+        // The transfers are only indexed by account if the block header is stored, which is the
+        // case for all executed and synchronized blocks.
+        let block_height = self
+            .get_single_block_header(txn, block_hash)?
+            .map(|block_header| block_header.height());
         let mut transfers: Vec<Transfer> = vec![];
         for (deploy_hash, execution_result) in execution_results {
-            transfers.extend(successful_transfers(&execution_result));
+            transfers.extend(successful_transfers(execution_result));
 
             let mut metadata = self
-                .get_deploy_metadata(txn, &deploy_hash)?
+                .get_deploy_metadata(txn, deploy_hash)?
                 .unwrap_or_default();
 
             // If we have a previous execution result, we can continue if it is the same.
             match metadata.execution_results.entry(*block_hash) {
                 hash_map::Entry::Occupied(entry) => {
-                    if *entry.get() == execution_result {
+                    if entry.get() == execution_result {
                         continue;
                     }
-                    *entry.into_mut() = execution_result;
+                    *entry.into_mut() = execution_result.clone();
                 }
                 hash_map::Entry::Vacant(vacant) => {
-                    vacant.insert(execution_result);
+                    vacant.insert(execution_result.clone());
                 }
             }
 
             let was_written =
-                txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
+                txn.put_value(self.deploy_metadata_db, deploy_hash, &metadata, true)?;
             if !was_written {
                 error!(?block_hash, ?deploy_hash, "failed to write deploy metadata");
                 debug_assert!(was_written);
             }
         }
 
+        if let Some(block_height) = block_height {
+            for account_transfer in account_transfers(*block_hash, block_height, &transfers) {
+                self.put_transfer_by_account(txn, &account_transfer)?;
+            }
+        }
+
         let was_written = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
         if !was_written {
             error!(?block_hash, "failed to write transfers");
@@ -1540,6 +3187,9 @@ impl Storage {
             panic!("write_finality_signatures() failed");
         }
         txn.commit()?;
+        self.read_cache
+            .get_mut()
+            .remove_block_signatures(&block_hash);
         Ok(())
     }
 
@@ -1577,6 +3227,7 @@ impl Storage {
             insert_to_block_header_indices(
                 &mut self.block_height_index,
                 &mut self.switch_block_era_id_index,
+                &mut self.state_root_hash_index,
                 block.header(),
             )?;
             insert_to_deploy_index(
@@ -1622,6 +3273,44 @@ impl Storage {
         Ok(switch_block)
     }
 
+    /// Reconstructs the validator weights of the given era from the stored switch block of the era
+    /// before it.
+    ///
+    /// Returns `None` if that switch block or the block following it is not stored. Also returns
+    /// `None` if the two blocks differ in protocol version, as the upgrade between them may have
+    /// changed the validators.
+    pub(crate) fn read_era_validator_weights(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<BTreeMap<PublicKey, U512>>, FatalStorageError> {
+        let switch_block_era_id = match era_id.predecessor() {
+            Some(switch_block_era_id) => switch_block_era_id,
+            None => return Ok(None),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let switch_block_header =
+            match self.get_switch_block_header_by_era_id(&mut txn, switch_block_era_id)? {
+                Some(switch_block_header) => switch_block_header,
+                None => return Ok(None),
+            };
+        let next_block_header = match self
+            .block_height_index
+            .get(&(switch_block_header.height() + 1))
+        {
+            Some(block_hash) => self.get_single_block_header(&mut txn, block_hash)?,
+            None => None,
+        };
+        match next_block_header {
+            Some(next_block_header)
+                if next_block_header.protocol_version()
+                    == switch_block_header.protocol_version() =>
+            {
+                Ok(switch_block_header.next_era_validator_weights().cloned())
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Returns `count` highest switch block headers, sorted from lowest (oldest) to highest.
     pub(crate) fn read_highest_switch_block_headers(
         &self,
@@ -1941,7 +3630,7 @@ impl Storage {
         block_hash: &BlockHash,
         only_from_available_block_range: bool,
     ) -> Result<Option<BlockHeader>, FatalStorageError> {
-        let block_header: BlockHeader = match txn.get_value(self.block_header_db, &block_hash)? {
+        let block_header = match self.get_single_block_header(txn, block_hash)? {
             Some(block_header) => block_header,
             None => return Ok(None),
         };
@@ -1950,7 +3639,6 @@ impl Storage {
             return Ok(None);
         }
 
-        block_header.set_block_hash(*block_hash);
         Ok(Some(block_header))
     }
 
@@ -2033,11 +3721,17 @@ impl Storage {
         txn: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeader>, FatalStorageError> {
+        if let Some(block_header) = self.read_cache.borrow_mut().get_block_header(block_hash) {
+            return Ok(Some(block_header));
+        }
         let block_header: BlockHeader = match txn.get_value(self.block_header_db, &block_hash)? {
             Some(block_header) => block_header,
             None => return Ok(None),
         };
         block_header.set_block_hash(*block_hash);
+        self.read_cache
+            .borrow_mut()
+            .put_block_header(block_header.clone());
         Ok(Some(block_header))
     }
 
@@ -2047,7 +3741,7 @@ impl Storage {
         txn: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeaderWithMetadata>, FatalStorageError> {
-        let block_header: BlockHeader = match txn.get_value(self.block_header_db, &block_hash)? {
+        let block_header = match self.get_single_block_header(txn, block_hash)? {
             Some(block_header) => block_header,
             None => return Ok(None),
         };
@@ -2098,6 +3792,7 @@ impl Storage {
             insert_to_block_header_indices(
                 &mut self.block_height_index,
                 &mut self.switch_block_era_id_index,
+                &mut self.state_root_hash_index,
                 block_header,
             )?;
         }
@@ -2132,6 +3827,9 @@ impl Storage {
         txn: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<Block>, FatalStorageError> {
+        if let Some(block) = self.read_cache.borrow_mut().get_block(block_hash) {
+            return Ok(Some(block));
+        }
         let block_header: BlockHeader = match self.get_single_block_header(txn, block_hash)? {
             Some(block_header) => block_header,
             None => {
@@ -2142,9 +3840,7 @@ impl Storage {
                 return Ok(None);
             }
         };
-        let maybe_block_body =
-            get_body_for_block_header(txn, block_header.body_hash(), self.block_body_db);
-        let block_body = match maybe_block_body? {
+        let block_body = match self.get_block_body(txn, block_header.body_hash())? {
             Some(block_body) => block_body,
             None => {
                 debug!(
@@ -2156,6 +3852,7 @@ impl Storage {
             }
         };
         let block = Block::new_from_header_and_body(block_header, block_body)?;
+        self.read_cache.borrow_mut().put_block(block.clone());
         Ok(Some(block))
     }
 
@@ -2190,7 +3887,7 @@ impl Storage {
         }
     }
 
-    /// Retrieves deploy metadata associated with deploy.
+    /// Retrieves deploy metadata associated with deploy, falling back to the cold storage tier.
     ///
     /// If no deploy metadata is stored for the specific deploy, an empty metadata instance will be
     /// created, but not stored.
@@ -2199,7 +3896,30 @@ impl Storage {
         txn: &mut Tx,
         deploy_hash: &DeployHash,
     ) -> Result<Option<DeployMetadata>, FatalStorageError> {
-        Ok(txn.get_value(self.deploy_metadata_db, deploy_hash)?)
+        if let Some(deploy_metadata) = txn.get_value(self.deploy_metadata_db, deploy_hash)? {
+            return Ok(Some(deploy_metadata));
+        }
+        match self.cold_storage.as_ref() {
+            Some(cold_storage) => cold_storage.get_deploy_metadata(deploy_hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves a block body by its hash, falling back to the cold storage tier.
+    fn get_block_body<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        block_body_hash: &Digest,
+    ) -> Result<Option<BlockBody>, FatalStorageError> {
+        if let Some(block_body) =
+            get_body_for_block_header(txn, block_body_hash, self.block_body_db)?
+        {
+            return Ok(Some(block_body));
+        }
+        match self.cold_storage.as_ref() {
+            Some(cold_storage) => cold_storage.get_block_body(block_body_hash),
+            None => Ok(None),
+        }
     }
 
     /// Retrieves successful transfers associated with block.
@@ -2255,7 +3975,21 @@ impl Storage {
         txn: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockSignatures>, FatalStorageError> {
-        Ok(txn.get_value(self.block_metadata_db, block_hash)?)
+        if let Some(block_signatures) = self
+            .read_cache
+            .borrow_mut()
+            .get_block_signatures(block_hash)
+        {
+            return Ok(Some(block_signatures));
+        }
+        let maybe_block_signatures: Option<BlockSignatures> =
+            txn.get_value(self.block_metadata_db, block_hash)?;
+        if let Some(block_signatures) = &maybe_block_signatures {
+            self.read_cache
+                .borrow_mut()
+                .put_block_signatures(block_signatures.clone());
+        }
+        Ok(maybe_block_signatures)
     }
 
     /// Retrieves a finality signature for a block with a given block hash.
@@ -2265,8 +3999,7 @@ impl Storage {
         block_hash: &BlockHash,
         public_key: &PublicKey,
     ) -> Result<Option<FinalitySignature>, FatalStorageError> {
-        let maybe_signatures: Option<BlockSignatures> =
-            txn.get_value(self.block_metadata_db, block_hash)?;
+        let maybe_signatures = self.get_block_signatures(txn, block_hash)?;
         Ok(maybe_signatures.and_then(|signatures| signatures.get_finality_signature(public_key)))
     }
 
@@ -2435,6 +4168,58 @@ impl Storage {
         Ok(FetchResponse::NotFound(sync_leap_identifier))
     }
 
+    /// Returns the signed headers of the switch blocks following the given trusted switch block,
+    /// followed by the signed highest complete block, spanning at most `recent_era_count` eras.
+    ///
+    /// Returns `None` if the trusted block is not a switch block within the available block range,
+    /// or if any of the switch blocks or their signatures are missing.
+    pub(crate) fn get_light_client_update(
+        &self,
+        trusted_switch_block_hash: BlockHash,
+    ) -> Result<Option<LightClientUpdate>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+
+        let only_from_available_block_range = true;
+        let trusted_header = match self.get_single_block_header_restricted(
+            &mut txn,
+            &trusted_switch_block_hash,
+            only_from_available_block_range,
+        )? {
+            Some(trusted_header) if trusted_header.is_switch_block() => trusted_header,
+            Some(_) | None => return Ok(None),
+        };
+        let highest_complete_block_header =
+            match self.get_header_with_metadata_of_highest_complete_block(&mut txn)? {
+                Some(highest_complete_block_header) => highest_complete_block_header,
+                None => return Ok(None),
+            };
+
+        let mut signed_block_headers = vec![];
+        if highest_complete_block_header.block_header.height() > trusted_header.height() {
+            let start_era_id = trusted_header.next_block_era_id().value();
+            let highest_era_id = highest_complete_block_header.block_header.era_id().value();
+            let end_era_id = highest_era_id.min(start_era_id.saturating_add(self.recent_era_count));
+            for era_id in start_era_id..end_era_id {
+                let hash = match self.switch_block_era_id_index.get(&EraId::from(era_id)) {
+                    Some(hash) => hash,
+                    None => return Ok(None),
+                };
+                match self.get_single_block_header_with_metadata(&mut txn, hash)? {
+                    Some(signed_block_header) => signed_block_headers.push(signed_block_header),
+                    None => return Ok(None),
+                }
+            }
+            if end_era_id == highest_era_id {
+                signed_block_headers.push(highest_complete_block_header);
+            }
+        }
+
+        Ok(Some(LightClientUpdate {
+            trusted_switch_block_hash,
+            signed_block_headers,
+        }))
+    }
+
     /// Creates a serialized representation of a `FetchResponse` and the resulting message.
     ///
     /// If the given item is `Some`, returns a serialization of `FetchResponse::Fetched`. If
@@ -2482,6 +4267,21 @@ impl Storage {
         }
     }
 
+    /// Returns the state root hashes of all stored blocks at or above the given height.
+    fn get_state_root_hashes_from_height(
+        &self,
+        height: u64,
+    ) -> Result<BTreeSet<Digest>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut state_root_hashes = BTreeSet::new();
+        for (_, block_hash) in self.block_height_index.range(height..) {
+            if let Some(block_header) = self.get_single_block_header(&mut txn, block_hash)? {
+                state_root_hashes.insert(*block_header.state_root_hash());
+            }
+        }
+        Ok(state_root_hashes)
+    }
+
     pub(crate) fn get_available_block_range(&self) -> AvailableBlockRange {
         match self.completed_blocks.highest_sequence() {
             Some(&seq) => seq.into(),
@@ -2529,9 +4329,7 @@ impl Storage {
             Some(block_header) => block_header,
             None => return Ok(None),
         };
-        let maybe_block_body =
-            get_body_for_block_header(txn, block_header.body_hash(), self.block_body_db);
-        let block_body = match maybe_block_body? {
+        let block_body = match self.get_block_body(txn, block_header.body_hash())? {
             Some(block_body) => block_body,
             None => {
                 debug!(
@@ -2577,8 +4375,12 @@ impl Storage {
         Ok(Some(execution_results))
     }
 
+    /// Retrieves the results of executing the deploys of the given block, in the order of the
+    /// block's deploys and transfers.
+    ///
+    /// Returns `None` unless the results of all of the block's deploys are stored.
     #[allow(clippy::type_complexity)]
-    fn read_execution_results(
+    pub(crate) fn read_execution_results(
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<Vec<(DeployHash, DeployHeader, ExecutionResult)>>, FatalStorageError> {
@@ -2634,6 +4436,32 @@ impl Storage {
         Ok(Some(request.response(value_or_chunk)))
     }
 
+    /// Retrieves the body of a block, or the requested chunk of it if the body is larger than a
+    /// single chunk.
+    fn read_block_body_or_chunk(
+        &self,
+        request: &BlockBodyOrChunkId,
+    ) -> Result<Option<BlockBodyOrChunk>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let block_header = match self.get_single_block_header(&mut txn, request.block_hash())? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        let block_body = match self.get_block_body(&mut txn, block_header.body_hash())? {
+            Some(block_body) => block_body,
+            None => return Ok(None),
+        };
+        let value_or_chunk = match ValueOrChunk::new(block_body, request.chunk_index()) {
+            Ok(value_or_chunk) => value_or_chunk,
+            Err(error) => {
+                // As with execution results, we'd rather not answer this query than crash.
+                error!(?request, ?error, "failed to construct `BlockBodyOrChunk`");
+                return Ok(None);
+            }
+        };
+        Ok(Some(request.response(value_or_chunk)))
+    }
+
     fn update_chain_height_metrics(&self) {
         if let Some(metrics) = self.metrics.as_ref() {
             if let Some(sequence) = self.completed_blocks.highest_sequence() {
@@ -2647,6 +4475,122 @@ impl Storage {
     }
 }
 
+/// Returns the key under which `deploy_hash` is stored in the deploys by account index.
+///
+/// Keys are prefixed with the serialized public key, so the hashes of all deploys sent by an
+/// account are stored contiguously.
+fn deploy_by_account_key(
+    account: &PublicKey,
+    deploy_hash: &DeployHash,
+) -> Result<Vec<u8>, FatalStorageError> {
+    let mut key = account
+        .to_bytes()
+        .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+    key.extend_from_slice(deploy_hash.as_ref());
+    Ok(key)
+}
+
+/// Returns the given transfers of a block along with their positions among those made by their
+/// deploy.
+///
+/// The transfers of each deploy must be in the order they were made, as in the block's execution
+/// results.
+fn account_transfers(
+    block_hash: BlockHash,
+    block_height: u64,
+    transfers: &[Transfer],
+) -> Vec<AccountTransfer> {
+    let mut next_indices = HashMap::new();
+    transfers
+        .iter()
+        .map(|transfer| {
+            let next_index = next_indices.entry(transfer.deploy_hash).or_insert(0);
+            let index = *next_index;
+            *next_index += 1;
+            AccountTransfer {
+                block_hash,
+                block_height,
+                index,
+                transfer: *transfer,
+            }
+        })
+        .collect()
+}
+
+/// Returns the key under which a transfer from or to `account_hash` at `position` is stored in the
+/// transfers by account index.
+///
+/// Keys are prefixed with the account hash followed by the bitwise inverted big-endian block
+/// height, so the transfers of an account are stored contiguously, most recent first.
+fn transfer_by_account_key(account_hash: &AccountHash, position: &TransferPosition) -> Vec<u8> {
+    let mut key = account_hash.value().to_vec();
+    key.extend_from_slice(&(!position.block_height).to_be_bytes());
+    key.extend_from_slice(&position.deploy_hash.value());
+    key.extend_from_slice(&position.index.to_be_bytes());
+    key
+}
+
+/// Returns the keys under which the given transfer is stored in the transfers by account index,
+/// one for its source and, if different, one for its target account.
+fn transfer_by_account_keys(account_transfer: &AccountTransfer) -> Vec<Vec<u8>> {
+    let transfer = &account_transfer.transfer;
+    let position = account_transfer.position();
+    let mut keys = vec![transfer_by_account_key(&transfer.from, &position)];
+    if let Some(to) = transfer.to.filter(|to| *to != transfer.from) {
+        keys.push(transfer_by_account_key(&to, &position));
+    }
+    keys
+}
+
+/// Returns the key under which the equivocation by `validator` in `era_id` is stored.
+///
+/// Keys are prefixed with the big-endian era ID, so the equivocations are ordered by era.
+fn equivocation_key(era_id: EraId, validator: &PublicKey) -> Result<Vec<u8>, FatalStorageError> {
+    let mut key = era_id.value().to_be_bytes().to_vec();
+    key.extend(
+        validator
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?,
+    );
+    Ok(key)
+}
+
+/// Returns the key under which the chunk of the trie with hash `trie_hash` at `index` is stored.
+///
+/// Keys are prefixed with the trie hash followed by the big-endian index, so the chunks of a trie
+/// are stored contiguously and in order.
+fn trie_chunk_key(trie_hash: &Digest, index: u64) -> Vec<u8> {
+    let mut key = trie_hash.value().to_vec();
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
+
+/// Deletes the value under `key`, if any. Returns `true` if a value was deleted.
+fn delete_if_exists<K: AsRef<[u8]>>(
+    txn: &mut RwTransaction,
+    db: Database,
+    key: &K,
+) -> Result<bool, FatalStorageError> {
+    match txn.del(db, key, None) {
+        Ok(()) => Ok(true),
+        Err(lmdb::Error::NotFound) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Returns the raw value stored under `key`, if any.
+fn get_raw_value<'txn, Tx: Transaction, K: AsRef<[u8]>>(
+    txn: &'txn Tx,
+    db: Database,
+    key: &K,
+) -> Result<Option<&'txn [u8]>, FatalStorageError> {
+    match txn.get(db, key) {
+        Ok(raw) => Ok(Some(raw)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// Decodes an item's ID, typically from an incoming request.
 fn decode_item_id<T>(raw: &[u8]) -> Result<T::Id, GetRequestError>
 where
@@ -2661,6 +4605,7 @@ where
 fn insert_to_block_header_indices(
     block_height_index: &mut BTreeMap<u64, BlockHash>,
     switch_block_era_id_index: &mut BTreeMap<EraId, BlockHash>,
+    state_root_hash_index: &mut BTreeMap<Digest, u64>,
     block_header: &BlockHeader,
 ) -> Result<(), FatalStorageError> {
     let block_hash = block_header.block_hash();
@@ -2691,6 +4636,13 @@ fn insert_to_block_header_indices(
         }
     }
 
+    // Blocks which don't change global state share their state root hash with their parent, so
+    // index the height at which the state first came into existence.
+    let height = state_root_hash_index
+        .entry(*block_header.state_root_hash())
+        .or_insert_with(|| block_header.height());
+    *height = (*height).min(block_header.height());
+
     let _ = block_height_index.insert(block_header.height(), block_hash);
     Ok(())
 }
@@ -2794,15 +4746,15 @@ pub struct Config {
     ///
     /// If the folder doesn't exist, it and any required parents will be created.
     pub path: PathBuf,
-    /// The maximum size of the database to use for the block store.
+    /// The initial maximum size of the database to use for the block store.
     ///
     /// The size should be a multiple of the OS page size.
     pub max_block_store_size: usize,
-    /// The maximum size of the database to use for the deploy store.
+    /// The initial maximum size of the database to use for the deploy store.
     ///
     /// The size should be a multiple of the OS page size.
     pub max_deploy_store_size: usize,
-    /// The maximum size of the database to use for the deploy metadata store.
+    /// The initial maximum size of the database to use for the deploy metadata store.
     ///
     /// The size should be a multiple of the OS page size.
     pub max_deploy_metadata_store_size: usize,
@@ -2810,10 +4762,44 @@ pub struct Config {
     ///
     /// The size should be a multiple of the OS page size.
     pub max_state_store_size: usize,
+    /// The amount by which the memory map of the database is grown whenever it is full.
+    ///
+    /// The size should be a multiple of the OS page size.  If `0`, the memory map is never grown
+    /// and a full database is a fatal error.
+    pub map_size_increment: usize,
     /// Whether or not memory deduplication is enabled.
     pub enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     pub mem_pool_prune_interval: u16,
+    /// The maximum estimated size in bytes of the cache of recently read block headers, blocks
+    /// and finality signatures.
+    ///
+    /// If `0`, nothing is cached.
+    pub read_cache_size: usize,
+    /// The number of blocks below the highest complete block whose bodies, deploys and execution
+    /// results are retained.  Those of older blocks are pruned in the background, keeping only
+    /// their headers and finality signatures.
+    ///
+    /// If not set, nothing is pruned automatically.
+    pub block_body_retention_depth: Option<u64>,
+    /// The interval between steps of the background pruning task.
+    pub prune_interval: TimeDiff,
+    /// The path to the folder holding the cold storage tier, to which the bodies and execution
+    /// results of old blocks are migrated in the background.
+    ///
+    /// If not set, there is no cold storage tier.  Once blocks have been migrated, it must not be
+    /// unset.
+    pub cold_storage_path: Option<PathBuf>,
+    /// The initial maximum size of the database of the cold storage tier.
+    ///
+    /// The size should be a multiple of the OS page size.
+    pub max_cold_store_size: usize,
+    /// The number of eras below the era of the highest complete block whose block bodies and
+    /// execution results are kept in the hot tier.  Those of blocks from older eras are migrated
+    /// to the cold storage tier, if configured.
+    pub cold_storage_era_depth: u64,
+    /// The interval between steps of the background migration to the cold storage tier.
+    pub cold_storage_migration_interval: TimeDiff,
 }
 
 impl Default for Config {
@@ -2825,8 +4811,19 @@ impl Default for Config {
             max_deploy_store_size: DEFAULT_MAX_DEPLOY_STORE_SIZE,
             max_deploy_metadata_store_size: DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE,
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
+            map_size_increment: DEFAULT_MAP_SIZE_INCREMENT,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            read_cache_size: DEFAULT_READ_CACHE_SIZE,
+            block_body_retention_depth: None,
+            prune_interval: TimeDiff::from_str(DEFAULT_PRUNE_INTERVAL).unwrap(),
+            cold_storage_path: None,
+            max_cold_store_size: DEFAULT_MAX_COLD_STORE_SIZE,
+            cold_storage_era_depth: DEFAULT_COLD_STORAGE_ERA_DEPTH,
+            cold_storage_migration_interval: TimeDiff::from_str(
+                DEFAULT_COLD_STORAGE_MIGRATION_INTERVAL,
+            )
+            .unwrap(),
         }
     }
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use casper_types::{DeployHash, Transfer};
+
+use crate::types::BlockHash;
+
+/// A native transfer from or to an account, as held in the transfers by account index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AccountTransfer {
+    /// The hash of the block the transfer was executed in.
+    pub(crate) block_hash: BlockHash,
+    /// The height of the block the transfer was executed in.
+    pub(crate) block_height: u64,
+    /// The position of the transfer among those made by its deploy.
+    pub(crate) index: u32,
+    /// The transfer.
+    pub(crate) transfer: Transfer,
+}
+
+impl AccountTransfer {
+    /// Returns the position of this transfer in the index.
+    pub(crate) fn position(&self) -> TransferPosition {
+        TransferPosition {
+            block_height: self.block_height,
+            deploy_hash: self.transfer.deploy_hash,
+            index: self.index,
+        }
+    }
+}
+
+/// The position of a transfer in the transfers by account index of a single account.
+///
+/// The transfers of an account are ordered by block height, most recent first, then by deploy hash
+/// and by their position within the deploy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TransferPosition {
+    /// The height of the block the transfer was executed in.
+    pub(crate) block_height: u64,
+    /// The hash of the deploy which made the transfer.
+    pub(crate) deploy_hash: DeployHash,
+    /// The position of the transfer among those made by the deploy.
+    pub(crate) index: u32,
+}
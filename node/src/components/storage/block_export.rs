@@ -0,0 +1,137 @@
+//! Streaming file format for exporting and importing ranges of blocks.
+//!
+//! A file starts with the magic bytes `CSPRBLKS` followed by the little-endian `u32` format
+//! version. It is followed by any number of records, one per block in ascending height order,
+//! each consisting of its little-endian `u64` length followed by the bincode-encoded
+//! `BlockExportRecord`.
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::FatalStorageError;
+use crate::types::{
+    ApprovalsHashes, Block, BlockHash, BlockSignatures, DeployHash, DeployWithFinalizedApprovals,
+};
+
+/// Magic bytes at the start of every block export file.
+const MAGIC: [u8; 8] = *b"CSPRBLKS";
+/// The version of the block export format.
+const FORMAT_VERSION: u32 = 1;
+/// The maximum length of a single serialized record.
+const MAX_RECORD_LENGTH: u64 = 1024 * 1024 * 1024;
+
+/// A single block along with everything needed to store it.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BlockExportRecord {
+    /// The block, including its header and body.
+    pub(super) block: Block,
+    /// The deploys and transfers of the block, in the order listed in its body.
+    pub(super) deploys: Vec<DeployWithFinalizedApprovals>,
+    /// The finality signatures of the block, if any are stored.
+    pub(super) signatures: Option<BlockSignatures>,
+    /// The approvals hashes of the block, if stored.
+    pub(super) approvals_hashes: Option<ApprovalsHashes>,
+}
+
+/// An error exporting or importing blocks.
+#[derive(Debug, Error)]
+pub(crate) enum BlockExportError {
+    /// Failure reading from storage.
+    #[error(transparent)]
+    Storage(#[from] FatalStorageError),
+    /// Failure reading or writing the file.
+    #[error("i/o error: {0}")]
+    Io(#[from] io::Error),
+    /// Failure encoding or decoding a record.
+    #[error("failed to encode or decode record: {0}")]
+    Serialization(#[from] bincode::Error),
+    /// The file does not start with the expected magic bytes.
+    #[error("not a block export file")]
+    InvalidMagic,
+    /// The file was written in an unsupported format version.
+    #[error("unsupported block export format version {0}")]
+    UnsupportedVersion(u32),
+    /// A record is longer than allowed.
+    #[error("record of {0} bytes exceeds the maximum record length")]
+    RecordTooLarge(u64),
+    /// The block at the given height is not stored.
+    #[error("no block stored at height {0}")]
+    MissingBlock(u64),
+    /// A deploy of an exported block is not stored.
+    #[error("deploy {deploy_hash} of block {block_hash} is not stored")]
+    MissingDeploy {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The hash of the missing deploy.
+        deploy_hash: DeployHash,
+    },
+    /// A record failed validation while importing.
+    #[error("invalid record for block at height {height}: {reason}")]
+    InvalidRecord {
+        /// The height of the block in the record.
+        height: u64,
+        /// Why the record is invalid.
+        reason: String,
+    },
+}
+
+/// Writes the magic bytes and format version.
+pub(super) fn write_file_header<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())
+}
+
+/// Reads and checks the magic bytes and format version.
+pub(super) fn read_file_header<R: Read>(reader: &mut R) -> Result<(), BlockExportError> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(BlockExportError::InvalidMagic);
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        return Err(BlockExportError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+/// Writes a single length-prefixed record.
+pub(super) fn write_record<W: Write>(
+    writer: &mut W,
+    record: &BlockExportRecord,
+) -> Result<(), BlockExportError> {
+    let serialized = bincode::serialize(record)?;
+    writer.write_all(&(serialized.len() as u64).to_le_bytes())?;
+    writer.write_all(&serialized)?;
+    Ok(())
+}
+
+/// Reads a single length-prefixed record, returning `None` at the end of the file.
+pub(super) fn read_record<R: Read>(
+    reader: &mut R,
+) -> Result<Option<BlockExportRecord>, BlockExportError> {
+    let mut length = [0; 8];
+    let mut filled = 0;
+    while filled < length.len() {
+        match reader.read(&mut length[filled..]) {
+            // The file may only end between records.
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let length = u64::from_le_bytes(length);
+    if length > MAX_RECORD_LENGTH {
+        return Err(BlockExportError::RecordTooLarge(length));
+    }
+    let mut serialized = vec![0; length as usize];
+    reader.read_exact(&mut serialized)?;
+    Ok(Some(bincode::deserialize(&serialized)?))
+}
@@ -0,0 +1,220 @@
+//! An optional second storage tier holding the bodies and execution results of old blocks.
+//!
+//! Archive nodes keep the full history of the chain, most of which is rarely read.  Moving the
+//! bodies and execution results of old blocks into a separate LMDB environment allows placing
+//! them on a cheaper volume than the recent data, which is read and written all the time.
+//!
+//! Reads fall back to the cold tier whenever an item is missing from the hot one.  Items are
+//! always written to the cold tier before being deleted from the hot one, so a crash in between
+//! leaves them in both tiers, but never in neither.
+
+use std::{fs, path::Path};
+
+use lmdb::{Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction};
+use tracing::warn;
+
+use casper_hashing::Digest;
+
+use super::{
+    delete_if_exists, get_raw_value,
+    lmdb_ext::{TransactionExt, WriteTransactionExt},
+    FatalStorageError, MAX_MAP_GROWTH_ATTEMPTS, MAX_TRANSACTIONS, OS_FLAGS,
+};
+use crate::types::{BlockBody, DeployHash, DeployMetadata};
+
+/// Filename for the LMDB database of the cold storage tier.
+const COLD_STORAGE_DB_FILENAME: &str = "cold_storage.lmdb";
+/// Maximum number of allowed dbs.
+const MAX_DB_COUNT: u32 = 2;
+
+/// Block bodies and deploy metadata moved to the cold storage tier together.
+#[derive(Debug, Default)]
+pub(super) struct ColdStorageBatch {
+    /// Block bodies, along with their hashes.
+    pub(super) block_bodies: Vec<(Digest, BlockBody)>,
+    /// Deploy metadata holding execution results, along with the deploy hashes.
+    pub(super) deploy_metadata: Vec<(DeployHash, DeployMetadata)>,
+}
+
+impl ColdStorageBatch {
+    /// Returns `true` if the batch holds no items.
+    pub(super) fn is_empty(&self) -> bool {
+        self.block_bodies.is_empty() && self.deploy_metadata.is_empty()
+    }
+}
+
+/// The keys of items to be deleted from the cold storage tier.
+#[derive(Debug, Default)]
+pub(super) struct ColdStorageKeys {
+    /// Hashes of block bodies.
+    pub(super) block_body_hashes: Vec<Digest>,
+    /// Hashes of deploys whose metadata is to be deleted.
+    pub(super) deploy_hashes: Vec<DeployHash>,
+}
+
+impl ColdStorageKeys {
+    /// Returns `true` if there are no keys.
+    pub(super) fn is_empty(&self) -> bool {
+        self.block_body_hashes.is_empty() && self.deploy_hashes.is_empty()
+    }
+}
+
+/// The cold storage tier.
+#[derive(Debug)]
+pub(super) struct ColdStorage {
+    /// Environment holding the LMDB databases of the cold tier.
+    env: Environment,
+    /// The block body database.
+    block_body_db: Database,
+    /// The deploy metadata database.
+    deploy_metadata_db: Database,
+    /// The current size of the memory map of the environment.
+    map_size: usize,
+    /// The amount by which the memory map is grown once it is full, or `0` if it is never grown.
+    map_size_increment: usize,
+}
+
+impl ColdStorage {
+    /// Opens the cold storage tier in the directory `root`, creating it if necessary.
+    pub(super) fn open(
+        root: &Path,
+        map_size: usize,
+        map_size_increment: usize,
+    ) -> Result<Self, FatalStorageError> {
+        if !root.exists() {
+            fs::create_dir_all(root).map_err(|err| {
+                FatalStorageError::CreateDatabaseDirectory(root.to_path_buf(), err)
+            })?;
+        }
+
+        let env = Environment::new()
+            .set_flags(
+                OS_FLAGS
+                    | EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_TLS
+                    | EnvironmentFlags::NO_READAHEAD,
+            )
+            .set_max_readers(MAX_TRANSACTIONS)
+            .set_max_dbs(MAX_DB_COUNT)
+            .set_map_size(map_size)
+            .open(&root.join(COLD_STORAGE_DB_FILENAME))?;
+        let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let deploy_metadata_db = env.create_db(Some("deploy_metadata"), DatabaseFlags::empty())?;
+
+        Ok(ColdStorage {
+            env,
+            block_body_db,
+            deploy_metadata_db,
+            map_size,
+            map_size_increment,
+        })
+    }
+
+    /// Retrieves a block body by its hash.
+    pub(super) fn get_block_body(
+        &self,
+        block_body_hash: &Digest,
+    ) -> Result<Option<BlockBody>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        Ok(txn.get_value(self.block_body_db, block_body_hash)?)
+    }
+
+    /// Retrieves a serialized block body by its hash, without deserializing it.
+    pub(super) fn get_raw_block_body(
+        &self,
+        block_body_hash: &Digest,
+    ) -> Result<Option<Vec<u8>>, FatalStorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        Ok(get_raw_value(&txn, self.block_body_db, block_body_hash)?.map(<[u8]>::to_vec))
+    }
+
+    /// Returns `true` if a block body with the given hash is stored.
+    pub(super) fn block_body_exists(
+        &self,
+        block_body_hash: &Digest,
+    ) -> Result<bool, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        Ok(txn.value_exists(self.block_body_db, block_body_hash)?)
+    }
+
+    /// Retrieves the metadata of a deploy.
+    pub(super) fn get_deploy_metadata(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<DeployMetadata>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        Ok(txn.get_value(self.deploy_metadata_db, deploy_hash)?)
+    }
+
+    /// Stores all items of the given batch, overwriting any already stored under the same keys.
+    pub(super) fn put(&mut self, batch: &ColdStorageBatch) -> Result<(), FatalStorageError> {
+        self.write_with_map_growth(|cold_storage, txn| {
+            for (block_body_hash, block_body) in &batch.block_bodies {
+                let _ = txn.put_value(
+                    cold_storage.block_body_db,
+                    block_body_hash,
+                    block_body,
+                    true,
+                )?;
+            }
+            for (deploy_hash, deploy_metadata) in &batch.deploy_metadata {
+                let _ = txn.put_value(
+                    cold_storage.deploy_metadata_db,
+                    deploy_hash,
+                    deploy_metadata,
+                    true,
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Deletes the items under the given keys, if they exist.
+    pub(super) fn delete(&mut self, keys: &ColdStorageKeys) -> Result<(), FatalStorageError> {
+        self.write_with_map_growth(|cold_storage, txn| {
+            for block_body_hash in &keys.block_body_hashes {
+                delete_if_exists(txn, cold_storage.block_body_db, block_body_hash)?;
+            }
+            for deploy_hash in &keys.deploy_hashes {
+                delete_if_exists(txn, cold_storage.deploy_metadata_db, deploy_hash)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs `write` within a single write transaction, growing the memory map and retrying
+    /// whenever it fails because the map is full.
+    fn write_with_map_growth<F>(&mut self, write: F) -> Result<(), FatalStorageError>
+    where
+        F: Fn(&Self, &mut RwTransaction) -> Result<(), FatalStorageError>,
+    {
+        let mut attempts = 0;
+        loop {
+            let result = self
+                .env
+                .begin_rw_txn()
+                .map_err(FatalStorageError::from)
+                .and_then(|mut txn| {
+                    write(self, &mut txn)?;
+                    Ok(txn.commit()?)
+                });
+            match result {
+                Err(error)
+                    if error.is_map_full()
+                        && self.map_size_increment > 0
+                        && attempts < MAX_MAP_GROWTH_ATTEMPTS =>
+                {
+                    attempts += 1;
+                    let new_map_size = self.map_size.saturating_add(self.map_size_increment);
+                    self.env.set_map_size(new_map_size)?;
+                    warn!(
+                        old_map_size = self.map_size,
+                        new_map_size, "Storage: cold storage database full, grew memory map"
+                    );
+                    self.map_size = new_map_size;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -208,6 +208,66 @@ impl DisjointSequences {
             true
         })
     }
+
+    /// Reduces the sequence(s), keeping all entries above and including `min_value`.  If
+    /// `min_value` is not already included in a sequence, it will not be added.
+    ///
+    /// If the current lowest value is higher than `min_value`, or if there are no sequences, this
+    /// has no effect.
+    pub(super) fn truncate_below(&mut self, min_value: u64) {
+        self.sequences.retain_mut(|sequence| {
+            if sequence.low >= min_value {
+                // Keep this sequence unchanged.
+                return true;
+            }
+
+            if sequence.high < min_value {
+                // Delete this entire sequence.
+                return false;
+            }
+
+            // This sequence contains `min_value`, so keep the sequence, but increase its low value.
+            sequence.low = min_value;
+            true
+        })
+    }
+
+    /// Removes `value`, splitting the sequence containing it in two if required.
+    ///
+    /// Returns `true` if `value` was previously contained in the disjoint sequences.
+    pub(super) fn remove(&mut self, value: u64) -> bool {
+        let index = match self
+            .sequences
+            .iter()
+            .position(|sequence| value >= sequence.low && value <= sequence.high)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let sequence = self.sequences[index];
+        match (sequence.low == value, sequence.high == value) {
+            (true, true) => {
+                let _ = self.sequences.remove(index);
+            }
+            (true, false) => self.sequences[index].low = value + 1,
+            (false, true) => self.sequences[index].high = value - 1,
+            (false, false) => {
+                // Sequences are ordered from high to low, so the lower half goes after this one.
+                self.sequences[index].low = value + 1;
+                self.sequences
+                    .insert(index + 1, Sequence::new(sequence.low, value - 1));
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if `value` exists in the disjoint sequences.
+    pub(super) fn contains(&self, value: u64) -> bool {
+        self.sequences
+            .iter()
+            .any(|sequence| value >= sequence.low && value <= sequence.high)
+    }
 }
 #[cfg(test)]
 impl DisjointSequences {
@@ -217,13 +277,6 @@ impl DisjointSequences {
             self.insert(height);
         })
     }
-
-    /// Returns `true` if `value` exists in the disjoint sequences.
-    fn contains(&self, value: u64) -> bool {
-        self.sequences
-            .iter()
-            .any(|sequence| value >= sequence.low && value <= sequence.high)
-    }
 }
 
 impl FromBytes for Sequence {
@@ -538,6 +591,86 @@ mod tests {
         assert!(disjoint_sequences.sequences.is_empty());
     }
 
+    #[test]
+    fn should_truncate_below() {
+        const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
+        const SEQ_MID: Sequence = Sequence { high: 6, low: 6 };
+        const SEQ_LOW: Sequence = Sequence { high: 3, low: 1 };
+        let initial_sequences = DisjointSequences {
+            sequences: vec![SEQ_HIGH, SEQ_MID, SEQ_LOW],
+        };
+
+        // Truncate with `min_value` less or equal to current lowest value should be a no-op.
+        let mut disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(0);
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+        disjoint_sequences.truncate_below(1);
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+
+        // Truncate with `min_value` between two sequences should cause the lower sequences to get
+        // removed and the higher ones retained unchanged.
+        disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(SEQ_MID.low - 1);
+        assert_eq!(disjoint_sequences.sequences, vec![SEQ_HIGH, SEQ_MID]);
+
+        disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(SEQ_MID.low);
+        assert_eq!(disjoint_sequences.sequences, vec![SEQ_HIGH, SEQ_MID]);
+
+        // Truncate with `min_value` within a sequence should cause that sequence to get updated,
+        // any lower sequences to get removed, and any higher ones retained unchanged.
+        disjoint_sequences = initial_sequences.clone();
+        let min_value = SEQ_HIGH.low + 1;
+        disjoint_sequences.truncate_below(min_value);
+        assert_eq!(
+            disjoint_sequences.sequences,
+            vec![new_sequence(SEQ_HIGH.high, min_value)]
+        );
+
+        // Truncate with `min_value` higher than the highest value should cause all sequences to
+        // get removed.
+        disjoint_sequences = initial_sequences;
+        disjoint_sequences.truncate_below(SEQ_HIGH.high + 1);
+        assert!(disjoint_sequences.sequences.is_empty());
+    }
+
+    #[test]
+    fn should_remove() {
+        const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
+        const SEQ_MID: Sequence = Sequence { high: 6, low: 6 };
+        const SEQ_LOW: Sequence = Sequence { high: 3, low: 1 };
+        let initial_sequences = DisjointSequences {
+            sequences: vec![SEQ_HIGH, SEQ_MID, SEQ_LOW],
+        };
+
+        // Removing a value not in any sequence should be a no-op.
+        let mut disjoint_sequences = initial_sequences.clone();
+        assert!(!disjoint_sequences.remove(7));
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+
+        // Removing the only value of a sequence should remove the sequence.
+        assert!(disjoint_sequences.remove(6));
+        assert_eq!(disjoint_sequences.sequences, vec![SEQ_HIGH, SEQ_LOW]);
+
+        // Removing a bound of a sequence should shrink the sequence.
+        disjoint_sequences = initial_sequences.clone();
+        assert!(disjoint_sequences.remove(SEQ_HIGH.high));
+        assert!(disjoint_sequences.remove(SEQ_LOW.low));
+        assert_eq!(
+            disjoint_sequences.sequences,
+            vec![new_sequence(10, 9), SEQ_MID, new_sequence(3, 2)]
+        );
+
+        // Removing a value within a sequence should split the sequence.
+        disjoint_sequences = initial_sequences;
+        assert!(disjoint_sequences.remove(10));
+        assert_eq!(
+            disjoint_sequences.sequences,
+            vec![new_sequence(11, 11), new_sequence(9, 9), SEQ_MID, SEQ_LOW]
+        );
+        assert!(!disjoint_sequences.contains(10));
+    }
+
     #[test]
     fn roundtrip_to_bytes() {
         let mut disjoint_sequences = DisjointSequences::default();
@@ -162,11 +162,32 @@ pub enum FatalStorageError {
         /// The number of approvals hashes.
         actual: usize,
     },
+    /// Blocks were migrated to the cold storage tier, which is not configured.
+    #[error(
+        "blocks below height {migrated_below_height} were migrated to cold storage, \
+         but no cold storage path is configured"
+    )]
+    MissingColdStorage {
+        /// The height below which all blocks were migrated.
+        migrated_below_height: u64,
+    },
     /// Error initializing metrics.
     #[error("failed to initialize metrics for storage: {0}")]
     Prometheus(#[from] prometheus::Error),
 }
 
+impl FatalStorageError {
+    /// Returns `true` if the error was caused by the memory map of the database being full.
+    pub(super) fn is_map_full(&self) -> bool {
+        matches!(
+            self,
+            FatalStorageError::InternalStorage(LmdbExtError::ResourceExhausted(
+                lmdb::Error::MapFull
+            ))
+        )
+    }
+}
+
 // We wholesale wrap lmdb errors and treat them as internal errors here.
 impl From<lmdb::Error> for FatalStorageError {
     fn from(err: lmdb::Error) -> Self {
@@ -0,0 +1,244 @@
+//! Read-only access to the databases of a node which is not running.
+//!
+//! Unlike the storage component, the inspector neither creates, migrates nor indexes anything on
+//! opening, so it is safe to point at the storage of a stopped node while debugging it.  Lookups
+//! by height scan all block headers, as the height index is only held in memory by the component.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::PathBuf,
+};
+
+use lmdb::{Cursor, Database, Environment, EnvironmentFlags, Transaction};
+use serde::Serialize;
+
+use super::{
+    lmdb_ext::{self, TransactionExt},
+    Config, FatalStorageError, MAX_DB_COUNT, STORAGE_DB_FILENAME,
+};
+use crate::{
+    types::{BlockBody, BlockHash, BlockHeader, Deploy, DeployHash},
+    utils::WithDir,
+};
+
+/// The names of all databases of the storage component.
+const DATABASE_NAMES: [&str; 15] = [
+    "block_header",
+    "block_metadata",
+    "deploys",
+    "deploy_metadata",
+    "transfer",
+    "state_store",
+    "finalized_approvals",
+    "block_body",
+    "approvals_hashes",
+    "deploys_by_account",
+    "transfers_by_account",
+    "equivocations",
+    "validator_performance",
+    "era_transition_proofs",
+    "zk_proving_jobs",
+];
+
+/// A stored block header along with its body, if the body is stored.
+///
+/// The body is missing if the block has not been fully synchronized, if it was pruned, or if it
+/// was moved to the cold storage tier.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct StoredBlock {
+    pub(crate) hash: BlockHash,
+    pub(crate) header: BlockHeader,
+    pub(crate) body: Option<BlockBody>,
+}
+
+/// The number and total size of the entries in a database.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct DatabaseStats {
+    pub(crate) name: &'static str,
+    pub(crate) entry_count: u64,
+    /// The total size of the keys and values, in bytes.
+    pub(crate) data_size: u64,
+}
+
+/// Statistics about the storage databases.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct StorageStats {
+    /// The path of the database file.
+    pub(crate) path: PathBuf,
+    /// The size of the database file, in bytes.
+    pub(crate) file_size: u64,
+    pub(crate) databases: Vec<DatabaseStats>,
+}
+
+impl Display for StorageStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {} bytes", self.path.display(), self.file_size)?;
+        for database in &self.databases {
+            writeln!(
+                f,
+                "  {:<24}{:>12} entries{:>16} bytes",
+                database.name, database.entry_count, database.data_size
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Read-only access to the storage databases of a stopped node.
+#[derive(Debug)]
+pub(crate) struct StorageInspector {
+    path: PathBuf,
+    env: Environment,
+    block_header_db: Database,
+    block_body_db: Database,
+    deploy_db: Database,
+}
+
+impl StorageInspector {
+    /// Opens the existing storage databases of the given network read-only.
+    pub(crate) fn open(
+        cfg: &WithDir<Config>,
+        network_name: &str,
+    ) -> Result<Self, FatalStorageError> {
+        let path = cfg
+            .with_dir(cfg.value().path.clone())
+            .join(network_name)
+            .join(STORAGE_DB_FILENAME);
+        if !path.is_file() {
+            return Err(FatalStorageError::MissingStorageFiles {
+                missing_files: vec![path],
+            });
+        }
+        let env = Environment::new()
+            .set_flags(
+                EnvironmentFlags::READ_ONLY
+                    | EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_TLS,
+            )
+            .set_max_dbs(MAX_DB_COUNT)
+            .open(&path)?;
+        let block_header_db = env.open_db(Some("block_header"))?;
+        let block_body_db = env.open_db(Some("block_body"))?;
+        let deploy_db = env.open_db(Some("deploys"))?;
+        Ok(StorageInspector {
+            path,
+            env,
+            block_header_db,
+            block_body_db,
+            deploy_db,
+        })
+    }
+
+    /// Returns the block with the given hash.
+    pub(crate) fn get_block(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<StoredBlock>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let header: BlockHeader = match txn.get_value(self.block_header_db, block_hash)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        let body = txn.get_value(self.block_body_db, header.body_hash())?;
+        Ok(Some(StoredBlock {
+            hash: *block_hash,
+            header,
+            body,
+        }))
+    }
+
+    /// Returns the block at the given height.
+    pub(crate) fn get_block_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<StoredBlock>, FatalStorageError> {
+        match self.find_block_header(|header| header.height() == height)? {
+            Some(block_hash) => self.get_block(&block_hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the block with the greatest height, whether complete or not.
+    pub(crate) fn get_highest_block(&self) -> Result<Option<StoredBlock>, FatalStorageError> {
+        let mut highest: Option<(u64, BlockHash)> = None;
+        self.find_block_header(|header| {
+            if highest.map_or(true, |(height, _)| header.height() > height) {
+                highest = Some((header.height(), header.block_hash()));
+            }
+            false
+        })?;
+        match highest {
+            Some((_, block_hash)) => self.get_block(&block_hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the deploy with the given hash.
+    pub(crate) fn get_deploy(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<Deploy>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        Ok(txn.get_value(self.deploy_db, deploy_hash)?)
+    }
+
+    /// Returns the number and size of the entries in each database.
+    pub(crate) fn stats(&self) -> Result<StorageStats, FatalStorageError> {
+        let file_size = fs::metadata(&self.path)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+        let mut named_databases = Vec::with_capacity(DATABASE_NAMES.len());
+        for name in DATABASE_NAMES.iter().copied() {
+            // Databases created by later versions of the node may be missing.
+            match self.env.open_db(Some(name)) {
+                Ok(db) => named_databases.push((name, db)),
+                Err(lmdb::Error::NotFound) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut databases = Vec::with_capacity(named_databases.len());
+        for (name, db) in named_databases {
+            let mut entry_count = 0;
+            let mut data_size = 0;
+            for row in txn.open_ro_cursor(db)?.iter() {
+                let (key, value) = row?;
+                entry_count += 1;
+                data_size += (key.len() + value.len()) as u64;
+            }
+            databases.push(DatabaseStats {
+                name,
+                entry_count,
+                data_size,
+            });
+        }
+        txn.commit()?;
+        Ok(StorageStats {
+            path: self.path.clone(),
+            file_size,
+            databases,
+        })
+    }
+
+    /// Scans all block headers until `predicate` returns `true`, returning the hash of that
+    /// header.
+    fn find_block_header<F>(&self, mut predicate: F) -> Result<Option<BlockHash>, FatalStorageError>
+    where
+        F: FnMut(&BlockHeader) -> bool,
+    {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.block_header_db)?;
+        // Note: `iter_start` has an undocumented panic if called on an empty database. We rely on
+        //       the iterator being at the start when created.
+        for row in cursor.iter() {
+            let (_, raw_header) = row?;
+            let header: BlockHeader = lmdb_ext::deserialize(raw_header)?;
+            if predicate(&header) {
+                return Ok(Some(header.block_hash()));
+            }
+        }
+        Ok(None)
+    }
+}
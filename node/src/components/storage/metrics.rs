@@ -1,4 +1,4 @@
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{self, IntCounter, IntGauge, Registry};
 
 use crate::unregister_metric;
 
@@ -13,6 +13,13 @@ const LOWEST_AVAILABLE_BLOCK_NAME: &str = "lowest_available_block_height";
 const LOWEST_AVAILABLE_BLOCK_HELP: &str =
     "lowest height of the available block range (the highest contiguous chain of complete blocks)";
 
+const MAP_SIZE_NAME: &str = "storage_map_size_bytes";
+const MAP_SIZE_HELP: &str = "current size of the memory map of the storage database in bytes";
+
+const MAP_RESIZES_NAME: &str = "storage_map_resizes";
+const MAP_RESIZES_HELP: &str =
+    "number of times the memory map of the storage database was grown after filling up";
+
 /// Metrics for the storage component.
 #[derive(Debug)]
 pub struct Metrics {
@@ -20,6 +27,8 @@ pub struct Metrics {
     pub(super) chain_height: IntGauge,
     pub(super) highest_available_block: IntGauge,
     pub(super) lowest_available_block: IntGauge,
+    pub(super) map_size: IntGauge,
+    pub(super) map_resizes: IntCounter,
     registry: Registry,
 }
 
@@ -31,15 +40,21 @@ impl Metrics {
             IntGauge::new(HIGHEST_AVAILABLE_BLOCK_NAME, HIGHEST_AVAILABLE_BLOCK_HELP)?;
         let lowest_available_block =
             IntGauge::new(LOWEST_AVAILABLE_BLOCK_NAME, LOWEST_AVAILABLE_BLOCK_HELP)?;
+        let map_size = IntGauge::new(MAP_SIZE_NAME, MAP_SIZE_HELP)?;
+        let map_resizes = IntCounter::new(MAP_RESIZES_NAME, MAP_RESIZES_HELP)?;
 
         registry.register(Box::new(chain_height.clone()))?;
         registry.register(Box::new(highest_available_block.clone()))?;
         registry.register(Box::new(lowest_available_block.clone()))?;
+        registry.register(Box::new(map_size.clone()))?;
+        registry.register(Box::new(map_resizes.clone()))?;
 
         Ok(Metrics {
             chain_height,
             highest_available_block,
             lowest_available_block,
+            map_size,
+            map_resizes,
             registry: registry.clone(),
         })
     }
@@ -50,5 +65,7 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.chain_height);
         unregister_metric!(self.registry, self.highest_available_block);
         unregister_metric!(self.registry, self.lowest_available_block);
+        unregister_metric!(self.registry, self.map_size);
+        unregister_metric!(self.registry, self.map_resizes);
     }
 }
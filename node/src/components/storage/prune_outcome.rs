@@ -0,0 +1,63 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+
+use casper_types::EraId;
+
+/// The reason pruning stopped short of the requested height.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum PruneLimit {
+    /// The highest complete block is never pruned.
+    HighestCompleteBlock,
+    /// The switch block preceding the last emergency restart and all later blocks are never
+    /// pruned.
+    EmergencyRestart(EraId),
+    /// Blocks whose deploys could still be replayed within the max TTL are never pruned.
+    DeployTtl,
+    /// The maximum number of blocks pruned in a single step was reached.
+    BatchSize,
+}
+
+impl Display for PruneLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PruneLimit::HighestCompleteBlock => write!(f, "the highest complete block"),
+            PruneLimit::EmergencyRestart(era_id) => {
+                write!(f, "the emergency restart at era {}", era_id)
+            }
+            PruneLimit::DeployTtl => write!(f, "the max deploy TTL"),
+            PruneLimit::BatchSize => write!(f, "the batch size"),
+        }
+    }
+}
+
+/// The result of pruning block bodies, deploys and execution results from storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct PruneOutcome {
+    /// The height below which pruning was requested.
+    pub(crate) requested_height: u64,
+    /// The height below which all blocks are now pruned.
+    pub(crate) pruned_below_height: u64,
+    /// The number of blocks pruned by this request.
+    pub(crate) pruned_block_count: u64,
+    /// The reason pruning stopped short of the requested height, if it did.
+    pub(crate) limited_by: Option<PruneLimit>,
+}
+
+impl Display for PruneOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pruned {} block(s), all blocks below height {} are pruned",
+            self.pruned_block_count, self.pruned_below_height
+        )?;
+        if let Some(limit) = self.limited_by {
+            write!(
+                f,
+                " (requested height {}, limited by {})",
+                self.requested_height, limit
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! An in-memory cache of recently read block headers, blocks and finality signatures.
+//!
+//! Items around the tip of the chain are read repeatedly by the RPC server, the gossiper and the
+//! synchronizer, so keeping the most recently read ones around saves both the database lookups
+//! and the cost of deserializing and validating them.
+//!
+//! The cache is bounded by an estimate of the memory consumed by its items, evicting the least
+//! recently used ones once full.
+
+use std::{cell::RefCell, mem};
+
+use datasize::DataSize;
+use linked_hash_map::LinkedHashMap;
+
+use crate::types::{Block, BlockHash, BlockHeader, BlockSignatures};
+
+/// The key under which an item is cached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum CacheKey {
+    BlockHeader(BlockHash),
+    Block(BlockHash),
+    BlockSignatures(BlockHash),
+}
+
+/// A cached item.
+#[derive(Debug, DataSize)]
+enum CachedItem {
+    BlockHeader(BlockHeader),
+    Block(Box<Block>),
+    BlockSignatures(BlockSignatures),
+}
+
+/// A least-recently-used cache of items read from storage, bounded by a byte budget.
+#[derive(Debug)]
+pub(super) struct ReadCache {
+    /// The cached items along with their estimated sizes, least recently used first.
+    items: LinkedHashMap<CacheKey, (CachedItem, usize)>,
+    /// The sum of the estimated sizes of all cached items.
+    size: usize,
+    /// The maximum sum of the estimated sizes of all cached items.  Nothing is cached if `0`.
+    capacity: usize,
+}
+
+impl ReadCache {
+    /// Creates a new, empty cache holding items of an estimated total size of up to `capacity`
+    /// bytes.
+    pub(super) fn new(capacity: usize) -> Self {
+        ReadCache {
+            items: LinkedHashMap::new(),
+            size: 0,
+            capacity,
+        }
+    }
+
+    /// Returns the cached block header with the given hash, if any.
+    pub(super) fn get_block_header(&mut self, block_hash: &BlockHash) -> Option<BlockHeader> {
+        match self.get(CacheKey::BlockHeader(*block_hash)) {
+            Some(CachedItem::BlockHeader(block_header)) => Some(block_header.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the cached block with the given hash, if any.
+    pub(super) fn get_block(&mut self, block_hash: &BlockHash) -> Option<Block> {
+        match self.get(CacheKey::Block(*block_hash)) {
+            Some(CachedItem::Block(block)) => Some((**block).clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the cached finality signatures of the block with the given hash, if any.
+    pub(super) fn get_block_signatures(
+        &mut self,
+        block_hash: &BlockHash,
+    ) -> Option<BlockSignatures> {
+        match self.get(CacheKey::BlockSignatures(*block_hash)) {
+            Some(CachedItem::BlockSignatures(block_signatures)) => Some(block_signatures.clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches a block header.
+    pub(super) fn put_block_header(&mut self, block_header: BlockHeader) {
+        self.put(
+            CacheKey::BlockHeader(block_header.block_hash()),
+            CachedItem::BlockHeader(block_header),
+        );
+    }
+
+    /// Caches a block.
+    pub(super) fn put_block(&mut self, block: Block) {
+        self.put(
+            CacheKey::Block(*block.hash()),
+            CachedItem::Block(Box::new(block)),
+        );
+    }
+
+    /// Caches the finality signatures of a block.
+    pub(super) fn put_block_signatures(&mut self, block_signatures: BlockSignatures) {
+        self.put(
+            CacheKey::BlockSignatures(block_signatures.block_hash),
+            CachedItem::BlockSignatures(block_signatures),
+        );
+    }
+
+    /// Evicts the block with the given hash, but not its header or finality signatures.
+    pub(super) fn remove_block(&mut self, block_hash: &BlockHash) {
+        self.remove(CacheKey::Block(*block_hash));
+    }
+
+    /// Evicts the finality signatures of the block with the given hash.
+    pub(super) fn remove_block_signatures(&mut self, block_hash: &BlockHash) {
+        self.remove(CacheKey::BlockSignatures(*block_hash));
+    }
+
+    /// Evicts all items.
+    pub(super) fn clear(&mut self) {
+        self.items.clear();
+        self.size = 0;
+    }
+
+    /// Returns the cached item under `key`, marking it as the most recently used one.
+    fn get(&mut self, key: CacheKey) -> Option<&CachedItem> {
+        self.items.get_refresh(&key).map(|(item, _)| &*item)
+    }
+
+    /// Caches `item` under `key`, evicting the least recently used items as required to stay
+    /// within the capacity.
+    fn put(&mut self, key: CacheKey, item: CachedItem) {
+        let item_size =
+            mem::size_of::<(CacheKey, (CachedItem, usize))>() + item.estimate_heap_size();
+        if item_size > self.capacity {
+            return;
+        }
+        self.remove(key);
+        self.items.insert(key, (item, item_size));
+        self.size += item_size;
+        while self.size > self.capacity {
+            match self.items.pop_front() {
+                Some((_, (_, evicted_size))) => self.size -= evicted_size,
+                None => break,
+            }
+        }
+    }
+
+    /// Evicts the item under `key`, if any.
+    fn remove(&mut self, key: CacheKey) {
+        if let Some((_, item_size)) = self.items.remove(&key) {
+            self.size -= item_size;
+        }
+    }
+}
+
+impl DataSize for ReadCache {
+    const IS_DYNAMIC: bool = true;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    fn estimate_heap_size(&self) -> usize {
+        // The item sizes already account for the map entries holding them.
+        self.size
+    }
+}
+
+/// Estimates the heap size of a cache shared behind a `RefCell`.
+pub(super) fn estimate_heap_size(cache: &RefCell<ReadCache>) -> usize {
+    cache.borrow().estimate_heap_size()
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{testing::TestRng, EraId};
+
+    use super::ReadCache;
+    use crate::types::{BlockHash, BlockSignatures};
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let mut rng = TestRng::new();
+        let mut signatures = || BlockSignatures::new(BlockHash::random(&mut rng), EraId::new(1));
+        let (signatures_1, signatures_2, signatures_3) = (signatures(), signatures(), signatures());
+
+        // Size the cache to hold exactly two sets of (empty) signatures.
+        let mut cache = ReadCache::new(usize::MAX);
+        cache.put_block_signatures(signatures_1.clone());
+        let mut cache = ReadCache::new(2 * cache.size);
+
+        cache.put_block_signatures(signatures_1.clone());
+        cache.put_block_signatures(signatures_2.clone());
+        assert_eq!(
+            cache.get_block_signatures(&signatures_1.block_hash),
+            Some(signatures_1.clone())
+        );
+
+        // `signatures_2` is now the least recently used one.
+        cache.put_block_signatures(signatures_3.clone());
+        assert_eq!(
+            cache.get_block_signatures(&signatures_1.block_hash),
+            Some(signatures_1.clone())
+        );
+        assert_eq!(cache.get_block_signatures(&signatures_2.block_hash), None);
+        assert_eq!(
+            cache.get_block_signatures(&signatures_3.block_hash),
+            Some(signatures_3)
+        );
+
+        cache.remove_block_signatures(&signatures_1.block_hash);
+        assert_eq!(cache.get_block_signatures(&signatures_1.block_hash), None);
+        cache.clear();
+        assert_eq!(cache.size, 0);
+    }
+
+    #[test]
+    fn should_not_cache_anything_without_capacity() {
+        let mut rng = TestRng::new();
+        let signatures = BlockSignatures::new(BlockHash::random(&mut rng), EraId::new(1));
+
+        let mut cache = ReadCache::new(0);
+        cache.put_block_signatures(signatures.clone());
+        assert_eq!(cache.get_block_signatures(&signatures.block_hash), None);
+        assert_eq!(cache.size, 0);
+    }
+}
@@ -0,0 +1,44 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    path::PathBuf,
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+/// A consistent copy of the storage databases, created while the node is running.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct StorageSnapshot {
+    /// The directory holding the copied database file.
+    pub(crate) path: PathBuf,
+    /// The number of entries copied across all databases.
+    pub(crate) entry_count: u64,
+}
+
+impl Display for StorageSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "copied {} entries to {}",
+            self.entry_count,
+            self.path.display()
+        )
+    }
+}
+
+/// An error creating a storage snapshot.
+///
+/// Unlike a `FatalStorageError`, this never indicates a problem with the live databases.
+#[derive(Debug, Error)]
+pub(crate) enum CreateSnapshotError {
+    /// The snapshot directory already exists.
+    #[error("snapshot directory `{}` already exists", .0.display())]
+    AlreadyExists(PathBuf),
+    /// Failure to create the snapshot directory.
+    #[error("failed to create snapshot directory `{}`: {}", .0.display(), .1)]
+    CreateDirectory(PathBuf, io::Error),
+    /// LMDB error while copying.
+    #[error("failed to copy databases: {0}")]
+    Lmdb(#[from] lmdb::Error),
+}
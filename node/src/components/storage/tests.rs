@@ -13,26 +13,38 @@ use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
+use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{
-    generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
-    Transfer, Transform, TransformEntry, URef, U512,
+    account::AccountHash, bytesrepr::Bytes, generate_ed25519_keypair,
+    system::auction::UnbondingPurse, testing::TestRng, AccessRights, EraId, ExecutionEffect,
+    ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff, Timestamp, Transfer,
+    Transform, TransformEntry, URef, U512,
 };
 
 use super::{
     initialize_block_metadata_db,
     lmdb_ext::{deserialize_internal, serialize_internal, TransactionExt, WriteTransactionExt},
-    move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage, FORCE_RESYNC_FILE_NAME,
+    move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir,
+    BlockExportError, Config, CreateSnapshotError, FatalStorageError, HighestOrphanedBlockResult,
+    PruneLimit, PruneOutcome, Storage, StorageInspector, StorageIssue, TransferPosition,
+    FORCE_RESYNC_FILE_NAME,
 };
 use crate::{
-    components::fetcher::{FetchItem, FetchResponse},
+    components::{
+        consensus::{EquivocationRecord, ValidatorPerformance, ValidatorPerformanceReport},
+        fetcher::{FetchItem, FetchResponse},
+        zk_prover::{
+            EraTransition, EraTransitionProof, PersistedJob, Priority, Proof, ProofSystem,
+            ProvingJob,
+        },
+    },
     effect::{
         requests::{MarkBlockCompletedRequest, StorageRequest},
         Multiple,
     },
     testing::{ComponentHarness, UnitTestEvent},
     types::{
+        light_client_update::LightClientUpdateValidationMetadata,
         sync_leap_validation_metadata::SyncLeapValidationMetaData, AvailableBlockRange, Block,
         BlockHash, BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
         BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployMetadata,
@@ -55,8 +67,17 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_deploy_store_size: 50 * MIB,
         max_deploy_metadata_store_size: 50 * MIB,
         max_state_store_size: 50 * MIB,
+        map_size_increment: 0,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        // Disabled, as several tests modify the databases directly.
+        read_cache_size: 0,
+        block_body_retention_depth: None,
+        prune_interval: TimeDiff::from_seconds(60),
+        cold_storage_path: None,
+        max_cold_store_size: 50 * MIB,
+        cold_storage_era_depth: 1,
+        cold_storage_migration_interval: TimeDiff::from_seconds(60),
     }
 }
 
@@ -190,6 +211,26 @@ fn storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
     .expect("could not create storage component fixture")
 }
 
+/// Storage component test fixture with a custom config.
+///
+/// # Panics
+///
+/// Panics if setting up the storage fixture fails.
+fn storage_fixture_with_config(harness: &ComponentHarness<UnitTestEvent>, cfg: Config) -> Storage {
+    Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    )
+    .expect("could not create storage component fixture")
+}
+
 /// Storage component test fixture.
 ///
 /// Creates a storage component in a temporary directory.
@@ -336,6 +377,37 @@ fn get_block_header_by_height(
     response
 }
 
+/// Loads the height of the lowest block with the given post-state from a storage component.
+fn get_block_height_for_state_root_hash(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    state_root_hash: Digest,
+) -> Option<u64> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockHeightForStateRootHash {
+            state_root_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the validator weights of an era, reconstructed from the stored switch block of the era
+/// before it, from a storage component.
+fn get_era_validator_weights(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    era_id: EraId,
+) -> Option<BTreeMap<PublicKey, U512>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetEraValidatorWeights { era_id, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Loads a block's signatures from a storage component.
 fn get_block_signatures(storage: &mut Storage, block_hash: BlockHash) -> Option<BlockSignatures> {
     let mut txn = storage.env.begin_ro_txn().unwrap();
@@ -448,6 +520,26 @@ fn put_block_signatures(
     response
 }
 
+/// Stores a block along with its signatures in a storage component.
+fn put_block_and_signatures(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block: Arc<Block>,
+    signatures: BlockSignatures,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutBlockAndApprovalsAndSignatures {
+            block,
+            approvals_hashes: None,
+            signatures,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Stores a deploy in a storage component.
 fn put_deploy(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -461,6 +553,42 @@ fn put_deploy(
     response
 }
 
+/// Loads the hashes of the deploys sent by the given account from a storage component.
+fn get_deploy_hashes_by_account(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account: PublicKey,
+) -> Vec<DeployHash> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetDeployHashesByAccount { account, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+fn get_transfers_by_account(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    after: Option<TransferPosition>,
+    max_count: usize,
+) -> Vec<Transfer> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetTransfersByAccount {
+            account_hash,
+            after,
+            max_count,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+        .into_iter()
+        .map(|account_transfer| account_transfer.transfer)
+        .collect()
+}
+
 fn insert_to_deploy_index(
     storage: &mut Storage,
     deploy_hash: &DeployHash,
@@ -472,6 +600,23 @@ fn insert_to_deploy_index(
         .is_none()
 }
 
+/// Loads the execution results of all deploys of a block from a storage component.
+fn get_execution_results_for_block(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<Vec<(DeployHash, ExecutionResult)>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetExecutionResultsForBlock {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Stores execution results in a storage component.
 fn put_execution_results(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -1156,6 +1301,40 @@ fn store_random_execution_results() {
     }
 }
 
+#[test]
+fn should_get_all_execution_results_of_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // The deploys themselves are deliberately not stored.
+    let deploys: Vec<_> = iter::repeat_with(|| Deploy::random(&mut harness.rng))
+        .take(3)
+        .collect();
+    let block = Arc::new(
+        TestBlockBuilder::new()
+            .deploys(deploys.iter())
+            .build(&mut harness.rng),
+    );
+    put_complete_block(&mut harness, &mut storage, block.clone());
+
+    assert!(get_execution_results_for_block(&mut harness, &mut storage, *block.hash()).is_none());
+
+    let expected: Vec<(DeployHash, ExecutionResult)> = block
+        .deploy_and_transfer_hashes()
+        .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+        .collect();
+    put_execution_results(
+        &mut harness,
+        &mut storage,
+        *block.hash(),
+        expected.iter().cloned().collect(),
+    );
+    assert_eq!(
+        get_execution_results_for_block(&mut harness, &mut storage, *block.hash()),
+        Some(expected)
+    );
+}
+
 #[test]
 fn store_execution_results_twice_for_same_block_deploy_pair() {
     let mut harness = ComponentHarness::default();
@@ -1190,17 +1369,50 @@ fn prepare_exec_result_with_transfer(
         rng.gen(),
         Some(rng.gen()),
     );
+    (exec_result_with_transfer(rng, transfer), transfer)
+}
+
+fn exec_result_with_transfer(rng: &mut TestRng, transfer: Transfer) -> ExecutionResult {
     let transform = TransformEntry {
-        key: Key::DeployInfo((*deploy_hash).into()).to_formatted_string(),
+        key: Key::DeployInfo(transfer.deploy_hash).to_formatted_string(),
         transform: Transform::WriteTransfer(transfer),
     };
     let effect = ExecutionEffect::new(vec![transform]);
-    let exec_result = ExecutionResult::Success {
+    ExecutionResult::Success {
         effect,
         transfers: vec![],
         cost: rng.gen(),
-    };
-    (exec_result, transfer)
+    }
+}
+
+/// Stores a block at the given height with a single deploy which transferred from `from` to `to`.
+fn put_block_with_transfer(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    height: u64,
+    from: AccountHash,
+    to: AccountHash,
+) -> Transfer {
+    let deploy = Deploy::random_valid_native_transfer(&mut harness.rng);
+    let block = TestBlockBuilder::new()
+        .height(height)
+        .deploys(iter::once(&deploy))
+        .build(&mut harness.rng);
+    storage.write_block(&block).unwrap();
+    let transfer = Transfer::new(
+        (*deploy.hash()).into(),
+        from,
+        Some(to),
+        harness.rng.gen(),
+        harness.rng.gen(),
+        harness.rng.gen(),
+        harness.rng.gen(),
+        Some(harness.rng.gen()),
+    );
+    let exec_result = exec_result_with_transfer(&mut harness.rng, transfer);
+    let exec_results = iter::once((*deploy.hash(), exec_result)).collect();
+    put_execution_results(harness, storage, *block.hash(), exec_results);
+    transfer
 }
 
 #[test]
@@ -1422,6 +1634,40 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn should_index_block_heights_by_state_root_hash() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random(&mut harness.rng);
+    let state_root_hash = *block.state_root_hash();
+    assert!(
+        get_block_height_for_state_root_hash(&mut harness, &mut storage, state_root_hash).is_none()
+    );
+
+    put_complete_block(&mut harness, &mut storage, Arc::new(block.clone()));
+    assert_eq!(
+        get_block_height_for_state_root_hash(&mut harness, &mut storage, state_root_hash),
+        Some(block.height())
+    );
+    let unknown_root = Digest::hash([1; 32]);
+    assert!(
+        get_block_height_for_state_root_hash(&mut harness, &mut storage, unknown_root).is_none()
+    );
+
+    // The index is rebuilt when the component is reinstantiated.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        get_block_height_for_state_root_hash(&mut harness, &mut storage, state_root_hash),
+        Some(block.height())
+    );
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
@@ -1698,6 +1944,126 @@ fn can_put_and_get_block() {
     assert_eq!(response.as_ref(), Some(block.header()));
 }
 
+#[test]
+fn should_reconstruct_era_validator_weights_from_switch_blocks() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let upgraded_protocol_version = ProtocolVersion::from_parts(1, 1, 0);
+    let mut random_validator_weights = || {
+        iter::repeat_with(|| {
+            (
+                PublicKey::random(&mut harness.rng),
+                U512::from(harness.rng.gen::<u64>()),
+            )
+        })
+        .take(3)
+        .collect::<BTreeMap<PublicKey, U512>>()
+    };
+    let era_1_validator_weights = random_validator_weights();
+    let era_2_validator_weights = random_validator_weights();
+    // Switch blocks of eras 0 and 1, each followed by the first block of the next era. The protocol
+    // is upgraded right after the switch block of era 1.
+    let blocks = vec![
+        (
+            0,
+            0,
+            ProtocolVersion::V1_0_0,
+            era_1_validator_weights.clone(),
+        ),
+        (1, 1, ProtocolVersion::V1_0_0, BTreeMap::new()),
+        (1, 2, ProtocolVersion::V1_0_0, era_2_validator_weights),
+        (2, 3, upgraded_protocol_version, BTreeMap::new()),
+    ]
+    .into_iter()
+    .map(|(era, height, protocol_version, validator_weights)| {
+        Block::random_with_specifics_and_parent_and_validator_weights(
+            &mut harness.rng,
+            EraId::from(era),
+            height,
+            protocol_version,
+            !validator_weights.is_empty(),
+            None,
+            None,
+            validator_weights,
+        )
+    })
+    .collect::<Vec<_>>();
+
+    // Without the block following the switch block, the weights are not reconstructed.
+    put_complete_block(&mut harness, &mut storage, Arc::new(blocks[0].clone()));
+    assert_eq!(
+        get_era_validator_weights(&mut harness, &mut storage, EraId::from(1)),
+        None
+    );
+    put_complete_block(&mut harness, &mut storage, Arc::new(blocks[1].clone()));
+    assert_eq!(
+        get_era_validator_weights(&mut harness, &mut storage, EraId::from(1)),
+        Some(era_1_validator_weights)
+    );
+
+    // Across an upgrade, the weights are not reconstructed either.
+    put_complete_block(&mut harness, &mut storage, Arc::new(blocks[2].clone()));
+    put_complete_block(&mut harness, &mut storage, Arc::new(blocks[3].clone()));
+    assert_eq!(
+        get_era_validator_weights(&mut harness, &mut storage, EraId::from(2)),
+        None
+    );
+
+    // There is no era before the genesis era.
+    assert_eq!(
+        get_era_validator_weights(&mut harness, &mut storage, EraId::from(0)),
+        None
+    );
+}
+
+#[test]
+fn should_put_block_and_signatures_atomically() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Arc::new(Block::random(&mut harness.rng));
+    let other_block = Block::random(&mut harness.rng);
+
+    // Signatures of another block are rejected, and neither they nor the block are stored.
+    let other_signatures = random_signatures(&mut harness.rng, &other_block);
+    assert!(!put_block_and_signatures(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        other_signatures
+    ));
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_none());
+    assert!(get_block_signatures(&mut storage, *other_block.hash()).is_none());
+
+    // Storing the block with its own signatures stores both, merging later signatures in.
+    let signatures_1 = random_signatures(&mut harness.rng, &block);
+    let signatures_2 = random_signatures(&mut harness.rng, &block);
+    assert!(put_block_and_signatures(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        signatures_1.clone()
+    ));
+    assert!(put_block_and_signatures(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        signatures_2.clone()
+    ));
+
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+        Some(&*block)
+    );
+    let mut merged_signatures = signatures_1;
+    merged_signatures.proofs.extend(signatures_2.proofs);
+    assert_eq!(
+        get_block_signatures(&mut storage, *block.hash()),
+        Some(merged_signatures)
+    );
+}
+
 #[test]
 fn should_get_trusted_ancestor_headers() {
     let (storage, _, blocks) = create_sync_leap_test_chain(&[], false, None);
@@ -1899,6 +2265,53 @@ fn should_respect_allowed_era_diff_in_get_sync_leap() {
     );
 }
 
+#[test]
+fn should_get_light_client_update() {
+    let (storage, chainspec, blocks) = create_sync_leap_test_chain(&[], false, None);
+
+    let trusted_header = blocks.get(4).unwrap().header();
+    let light_client_update = storage
+        .get_light_client_update(trusted_header.block_hash())
+        .unwrap()
+        .expect("should have light client update");
+
+    assert_eq!(
+        signed_block_headers_into_heights(&light_client_update.signed_block_headers),
+        vec![7, 10, 12]
+    );
+
+    let validation_metadata = LightClientUpdateValidationMetadata {
+        era_id: trusted_header.next_block_era_id(),
+        validator_weights: trusted_header.next_era_validator_weights().unwrap().clone(),
+        recent_era_count: chainspec.core_config.recent_era_count(),
+        finality_threshold_fraction: chainspec.core_config.finality_threshold_fraction,
+    };
+    light_client_update.validate(&validation_metadata).unwrap();
+}
+
+#[test]
+fn should_truncate_light_client_update_to_recent_era_count() {
+    let maybe_recent_era_count = Some(1);
+    let (storage, _, blocks) = create_sync_leap_test_chain(&[], false, maybe_recent_era_count);
+
+    let get_results = |requested_height: usize| -> Option<Vec<u64>> {
+        let requested_block_hash = blocks.get(requested_height).unwrap().header().block_hash();
+        storage
+            .get_light_client_update(requested_block_hash)
+            .unwrap()
+            .map(|update| signed_block_headers_into_heights(&update.signed_block_headers))
+    };
+
+    assert_eq!(get_results(4), Some(vec![7]));
+    assert_eq!(get_results(7), Some(vec![10, 12]));
+    assert_eq!(get_results(10), Some(vec![12]));
+    assert_eq!(
+        get_results(6),
+        None,
+        "should only provide updates from switch blocks"
+    );
+}
+
 #[test]
 fn should_restrict_returned_blocks() {
     let mut harness = ComponentHarness::default();
@@ -2066,28 +2479,795 @@ fn check_force_resync_with_marker_file() {
 }
 
 #[test]
-fn should_read_legacy_unbonding_purse() {
-    // These bytes represent the `UnbondingPurse` struct with the `new_validator` field removed
-    // and serialized with `bincode`.
-    // In theory, we can generate these bytes by serializing the `WithdrawPurse`, but at some point,
-    // these two structs may diverge and it's a safe bet to rely on the bytes
-    // that are consistent with what we keep in the current storage.
-    const LEGACY_BYTES: &str = "0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e07010000002000000000000000197f6b23e16c8532c6abc838facd5ea789be0c76b2920334039bfa8b3d368d610100000020000000000000004508a07aa941707f3eb2db94c8897a80b2c1197476b6de213ac273df7d86c4ffffffffffffffffff40feffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+fn historical_sync_resumes_from_lowest_completed_block_after_restart() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
 
-    let decoded = base16::decode(LEGACY_BYTES).expect("decode");
-    let deserialized: UnbondingPurse = deserialize_internal(&decoded)
-        .expect("should deserialize w/o error")
-        .expect("should be Some");
+    let mut block_at_height = |height| {
+        Arc::new(Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::new(1),
+            height,
+            ProtocolVersion::from_parts(1, 5, 0),
+            false,
+            None,
+        ))
+    };
+    let block_9 = block_at_height(9);
+    let block_10 = block_at_height(10);
+    let block_11 = block_at_height(11);
+
+    // Blocks 10 and 11 were fully synced, while block 9 was still being synced back.
+    put_complete_block(&mut harness, &mut storage, block_11);
+    put_complete_block(&mut harness, &mut storage, block_10.clone());
+    harness.send_request(&mut storage, move |responder| {
+        StorageRequest::PutBlock {
+            block: block_9,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    drop(storage);
 
-    // Make sure the new field is set to default.
-    assert_eq!(*deserialized.new_validator(), Option::default())
+    // After a restart, historical sync should resume from the lowest fully synced block.
+    let storage = storage_fixture(&harness);
+    match storage.get_highest_orphaned_block_header() {
+        HighestOrphanedBlockResult::Orphan(block_header) => {
+            assert_eq!(block_header, *block_10.header())
+        }
+        other => panic!("unexpected highest orphaned block result: {}", other),
+    }
 }
 
 #[test]
-fn unbonding_purse_serialization_roundtrip() {
-    let original = UnbondingPurse::new(
-        URef::new([14; 32], AccessRights::READ_ADD_WRITE),
-        {
+fn should_prune_blocks_but_keep_headers_and_signatures() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Blocks are two days apart, so their deploys are well beyond the max TTL of each other.
+    let two_days_millis = 2 * MAX_TTL.millis();
+    let mut blocks = vec![];
+    for height in 0..5 {
+        let deploy = Deploy::random(&mut harness.rng);
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .height(height)
+                .timestamp(Timestamp::from(height * two_days_millis))
+                .deploys(iter::once(&deploy))
+                .build(&mut harness.rng),
+        );
+        put_deploy(&mut harness, &mut storage, Arc::new(deploy));
+        put_block_signatures(
+            &mut harness,
+            &mut storage,
+            random_signatures(&mut harness.rng, &block),
+        );
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        blocks.push(block);
+    }
+
+    let outcome = harness.send_request(&mut storage, |responder| {
+        StorageRequest::PruneBlocks {
+            below_height: 3,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(
+        outcome,
+        PruneOutcome {
+            requested_height: 3,
+            pruned_below_height: 3,
+            pruned_block_count: 3,
+            limited_by: None,
+        }
+    );
+
+    for block in &blocks[..3] {
+        let deploy_hash = *block.deploy_and_transfer_hashes().next().unwrap();
+        assert!(get_block(&mut harness, &mut storage, *block.hash()).is_none());
+        assert!(get_block_header_at_height(&mut storage, block.height(), false).is_some());
+        assert!(get_block_signatures(&mut storage, *block.hash()).is_some());
+        assert!(get_naive_deploys(&mut harness, &mut storage, smallvec![deploy_hash])[0].is_none());
+    }
+    for block in &blocks[3..] {
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&**block)
+        );
+    }
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(3, 4)
+    );
+
+    // The highest complete block is never pruned.
+    let outcome = harness.send_request(&mut storage, |responder| {
+        StorageRequest::PruneBlocks {
+            below_height: 10,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(outcome.pruned_below_height, 4);
+    assert_eq!(outcome.pruned_block_count, 1);
+    assert_eq!(outcome.limited_by, Some(PruneLimit::HighestCompleteBlock));
+
+    // The pruned height is persisted across restarts.
+    drop(storage);
+    let storage = storage_fixture(&harness);
+    assert_eq!(storage.pruned_below_height(), 4);
+}
+
+#[test]
+fn should_remove_blocks_with_pruned_global_state_from_available_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let mut blocks = vec![];
+    for height in 0..5 {
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .height(height)
+                .build(&mut harness.rng),
+        );
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        blocks.push(block);
+    }
+
+    let mark_global_state_pruned_below =
+        |harness: &mut ComponentHarness<UnitTestEvent>, storage: &mut Storage, height: u64| {
+            harness.send_request(storage, |responder| {
+                StorageRequest::MarkGlobalStatePrunedBelow { height, responder }.into()
+            })
+        };
+
+    assert_eq!(
+        mark_global_state_pruned_below(&mut harness, &mut storage, 3),
+        3
+    );
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(3, 4)
+    );
+    // The blocks themselves are kept.
+    for block in &blocks {
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&**block)
+        );
+    }
+
+    // The height never decreases, and a block marked complete again below it is removed from the
+    // available range again.
+    put_complete_block(&mut harness, &mut storage, blocks[2].clone());
+    assert_eq!(
+        mark_global_state_pruned_below(&mut harness, &mut storage, 1),
+        3
+    );
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(3, 4)
+    );
+
+    // The highest complete block always remains available.
+    assert_eq!(
+        mark_global_state_pruned_below(&mut harness, &mut storage, 10),
+        4
+    );
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(4, 4)
+    );
+
+    // The height is persisted across restarts.
+    drop(storage);
+    let storage = storage_fixture(&harness);
+    assert_eq!(storage.global_state_pruned_below_height(), 4);
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(4, 4)
+    );
+}
+
+#[test]
+fn should_create_snapshot_usable_as_storage() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Arc::new(Deploy::random(&mut harness.rng));
+    let block = Arc::new(
+        TestBlockBuilder::new()
+            .deploys(iter::once(&*deploy))
+            .build(&mut harness.rng),
+    );
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+    put_complete_block(&mut harness, &mut storage, block.clone());
+
+    // Place the snapshot where a storage component for the "test" network would look for it.
+    let snapshot_dir = harness.tmp.path().join("snapshot");
+    let snapshot_path = snapshot_dir.join("test");
+    let snapshot = harness
+        .send_request(&mut storage, |responder| {
+            StorageRequest::CreateSnapshot {
+                path: snapshot_path.clone(),
+                responder,
+            }
+            .into()
+        })
+        .expect("should create snapshot");
+    assert_eq!(snapshot.path, snapshot_path);
+    assert!(snapshot.entry_count > 0);
+
+    // An existing snapshot is never overwritten.
+    let result = harness.send_request(&mut storage, |responder| {
+        StorageRequest::CreateSnapshot {
+            path: snapshot_path.clone(),
+            responder,
+        }
+        .into()
+    });
+    assert!(matches!(result, Err(CreateSnapshotError::AlreadyExists(_))));
+
+    let snapshot_config = Config {
+        path: snapshot_dir,
+        ..new_config(&harness)
+    };
+    let mut snapshot_storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), snapshot_config),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    )
+    .expect("could not open snapshot");
+    assert_eq!(
+        get_block(&mut harness, &mut snapshot_storage, *block.hash()).as_ref(),
+        Some(&*block)
+    );
+    assert_eq!(
+        get_naive_deploys(
+            &mut harness,
+            &mut snapshot_storage,
+            smallvec![*deploy.hash()]
+        ),
+        vec![Some((*deploy).clone())]
+    );
+    assert_eq!(
+        snapshot_storage.get_available_block_range(),
+        storage.get_available_block_range()
+    );
+}
+
+#[test]
+fn should_verify_and_repair_storage_integrity() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let mut blocks = vec![];
+    for height in 0..3 {
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .height(height)
+                .build(&mut harness.rng),
+        );
+        put_block_signatures(
+            &mut harness,
+            &mut storage,
+            random_signatures(&mut harness.rng, &block),
+        );
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        blocks.push(block);
+    }
+
+    let verification = harness.send_request(&mut storage, |responder| {
+        StorageRequest::VerifyIntegrity {
+            repair: false,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(verification.checked_block_count, 3);
+    assert!(verification.issues.is_empty());
+
+    // Store the body of another block under the body hash of the first block, and add a signature
+    // of another block to the signatures of the second block.
+    let other_block = TestBlockBuilder::new().build(&mut harness.rng);
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let public_key = PublicKey::from(&secret_key);
+    let wrong_signature = FinalitySignature::create(
+        *other_block.hash(),
+        blocks[1].header().era_id(),
+        &secret_key,
+        public_key.clone(),
+    );
+    let mut signatures = get_block_signatures(&mut storage, *blocks[1].hash()).unwrap();
+    signatures.insert_proof(public_key.clone(), wrong_signature.signature);
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    txn.put_value(
+        storage.block_body_db,
+        blocks[0].header().body_hash(),
+        other_block.body(),
+        true,
+    )
+    .unwrap();
+    txn.put_value(
+        storage.block_metadata_db,
+        blocks[1].hash(),
+        &signatures,
+        true,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+
+    let verification = storage.verify_integrity(true).unwrap();
+    assert_eq!(verification.checked_block_count, 3);
+    assert!(verification.repaired);
+    let expected_issues = vec![
+        StorageIssue::BodyHashMismatch {
+            block_hash: *blocks[0].hash(),
+            height: 0,
+        },
+        StorageIssue::InvalidSignature {
+            block_hash: *blocks[1].hash(),
+            public_key: public_key.clone(),
+        },
+    ];
+    assert_eq!(verification.issues.len(), expected_issues.len());
+    for issue in &expected_issues {
+        assert!(verification.issues.contains(issue));
+    }
+
+    // The affected blocks are marked as incomplete, the invalid data discarded and the valid
+    // signatures kept.
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(2, 2)
+    );
+    assert!(get_block(&mut harness, &mut storage, *blocks[0].hash()).is_none());
+    let signatures = get_block_signatures(&mut storage, *blocks[1].hash()).unwrap();
+    assert!(!signatures.has_finality_signature(&public_key));
+    assert_eq!(signatures.proofs.len(), 3);
+
+    let verification = storage.verify_integrity(false).unwrap();
+    assert!(verification.issues.is_empty());
+}
+
+#[test]
+fn should_export_and_import_blocks() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let mut blocks = vec![];
+    let mut deploys = vec![];
+    for height in 0..3 {
+        let deploy = Deploy::random(&mut harness.rng);
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .height(height)
+                .deploys(iter::once(&deploy))
+                .build(&mut harness.rng),
+        );
+        put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+        put_block_signatures(
+            &mut harness,
+            &mut storage,
+            random_signatures(&mut harness.rng, &block),
+        );
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        blocks.push(block);
+        deploys.push(deploy);
+    }
+
+    // Only export the upper two blocks.
+    let mut exported = vec![];
+    assert_eq!(storage.export_blocks(1, 2, &mut exported).unwrap(), 2);
+    assert!(matches!(
+        storage.export_blocks(2, 3, &mut vec![]),
+        Err(BlockExportError::MissingBlock(3))
+    ));
+
+    let mut import_harness = ComponentHarness::default();
+    let mut import_storage = storage_fixture(&import_harness);
+    assert_eq!(
+        import_storage
+            .import_blocks(&mut exported.as_slice())
+            .unwrap(),
+        2
+    );
+
+    assert!(get_block(&mut import_harness, &mut import_storage, *blocks[0].hash()).is_none());
+    for (block, deploy) in blocks.iter().zip(&deploys).skip(1) {
+        assert_eq!(
+            get_block(&mut import_harness, &mut import_storage, *block.hash()).as_ref(),
+            Some(&**block)
+        );
+        assert_eq!(
+            get_naive_deploys(
+                &mut import_harness,
+                &mut import_storage,
+                smallvec![*deploy.hash()]
+            ),
+            vec![Some(deploy.clone())]
+        );
+        assert_eq!(
+            get_block_signatures(&mut import_storage, *block.hash()),
+            get_block_signatures(&mut storage, *block.hash())
+        );
+    }
+    // Imported blocks still need to be synchronized.
+    assert_eq!(
+        import_storage.get_available_block_range(),
+        AvailableBlockRange::RANGE_0_0
+    );
+
+    // A truncated file is rejected.
+    let mut truncated = &exported[..exported.len() - 1];
+    assert!(matches!(
+        import_storage.import_blocks(&mut truncated),
+        Err(BlockExportError::Io(_))
+    ));
+    let mut not_an_export: &[u8] = b"not a block export file";
+    assert!(matches!(
+        import_storage.import_blocks(&mut not_an_export),
+        Err(BlockExportError::InvalidMagic)
+    ));
+}
+
+#[test]
+fn should_get_deploy_hashes_by_account() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy_1 = Arc::new(Deploy::random(&mut harness.rng));
+    let deploy_2 = Arc::new(Deploy::random(&mut harness.rng));
+    put_deploy(&mut harness, &mut storage, deploy_1.clone());
+    put_deploy(&mut harness, &mut storage, deploy_2.clone());
+
+    let account_1 = deploy_1.header().account().clone();
+    assert_eq!(
+        get_deploy_hashes_by_account(&mut harness, &mut storage, account_1),
+        vec![*deploy_1.hash()]
+    );
+    let account_2 = deploy_2.header().account().clone();
+    assert_eq!(
+        get_deploy_hashes_by_account(&mut harness, &mut storage, account_2),
+        vec![*deploy_2.hash()]
+    );
+    let unknown_account = PublicKey::random(&mut harness.rng);
+    assert!(get_deploy_hashes_by_account(&mut harness, &mut storage, unknown_account).is_empty());
+}
+
+#[test]
+fn should_index_existing_deploys_by_account_on_first_use() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Store a deploy the way storage did before the index existed.
+    let deploy = Deploy::random(&mut harness.rng);
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    txn.put_value(storage.deploy_db, deploy.hash(), &deploy, false)
+        .unwrap();
+    txn.commit().unwrap();
+    assert!(!storage.deploys_by_account_indexed);
+
+    let account = deploy.header().account().clone();
+    assert_eq!(
+        get_deploy_hashes_by_account(&mut harness, &mut storage, account),
+        vec![*deploy.hash()]
+    );
+    assert!(storage.deploys_by_account_indexed);
+
+    // The index is only built once.
+    drop(storage);
+    let storage = storage_fixture(&harness);
+    assert!(storage.deploys_by_account_indexed);
+}
+
+#[test]
+fn should_get_transfers_by_account() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let account_1: AccountHash = harness.rng.gen();
+    let account_2: AccountHash = harness.rng.gen();
+    let account_3: AccountHash = harness.rng.gen();
+    let transfer_1 = put_block_with_transfer(&mut harness, &mut storage, 5, account_1, account_2);
+    let transfer_2 = put_block_with_transfer(&mut harness, &mut storage, 7, account_2, account_3);
+
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_1, None, 10),
+        vec![transfer_1]
+    );
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_3, None, 10),
+        vec![transfer_2]
+    );
+    // The transfers of an account are returned most recent first, both outgoing and incoming.
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_2, None, 10),
+        vec![transfer_2, transfer_1]
+    );
+
+    // Paginate through the transfers of an account.
+    let after = TransferPosition {
+        block_height: 7,
+        deploy_hash: transfer_2.deploy_hash,
+        index: 0,
+    };
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_2, None, 1),
+        vec![transfer_2]
+    );
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_2, Some(after), 1),
+        vec![transfer_1]
+    );
+    let after = TransferPosition {
+        block_height: 5,
+        deploy_hash: transfer_1.deploy_hash,
+        index: 0,
+    };
+    assert!(
+        get_transfers_by_account(&mut harness, &mut storage, account_2, Some(after), 1).is_empty()
+    );
+
+    let unknown_account: AccountHash = harness.rng.gen();
+    assert!(
+        get_transfers_by_account(&mut harness, &mut storage, unknown_account, None, 10).is_empty()
+    );
+}
+
+#[test]
+fn should_index_existing_transfers_by_account_on_first_use() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let account_1: AccountHash = harness.rng.gen();
+    let account_2: AccountHash = harness.rng.gen();
+    let transfer = put_block_with_transfer(&mut harness, &mut storage, 3, account_1, account_2);
+
+    // Remove the transfer from the index, as if it had been stored before the index existed.
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    txn.clear_db(storage.transfers_by_account_db).unwrap();
+    txn.commit().unwrap();
+    assert!(!storage.transfers_by_account_indexed);
+
+    assert_eq!(
+        get_transfers_by_account(&mut harness, &mut storage, account_2, None, 10),
+        vec![transfer]
+    );
+    assert!(storage.transfers_by_account_indexed);
+
+    // The index is only built once.
+    drop(storage);
+    let storage = storage_fixture(&harness);
+    assert!(storage.transfers_by_account_indexed);
+}
+
+#[test]
+fn should_store_equivocations_ordered_by_era() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    let alice = PublicKey::from(&SecretKey::ed25519_from_bytes([1; 32]).unwrap());
+    let bob = PublicKey::from(&SecretKey::ed25519_from_bytes([2; 32]).unwrap());
+    let record = |era_id: u64, validator: &PublicKey| EquivocationRecord {
+        era_id: EraId::new(era_id),
+        validator: validator.clone(),
+        detected_at: Timestamp::from(era_id),
+        conflicting_messages: vec![],
+    };
+
+    assert!(storage.put_equivocation(&record(7, &alice)).unwrap());
+    assert!(storage.put_equivocation(&record(3, &bob)).unwrap());
+    assert!(storage.put_equivocation(&record(5, &alice)).unwrap());
+    // The first record of an equivocation is kept.
+    let mut duplicate = record(5, &alice);
+    duplicate.detected_at = Timestamp::from(1000);
+    assert!(!storage.put_equivocation(&duplicate).unwrap());
+
+    assert_eq!(
+        storage.get_equivocations(EraId::new(0), None).unwrap(),
+        vec![record(3, &bob), record(5, &alice), record(7, &alice)]
+    );
+    assert_eq!(
+        storage.get_equivocations(EraId::new(4), None).unwrap(),
+        vec![record(5, &alice), record(7, &alice)]
+    );
+    assert_eq!(
+        storage
+            .get_equivocations(EraId::new(0), Some(&bob))
+            .unwrap(),
+        vec![record(3, &bob)]
+    );
+    assert!(storage
+        .get_equivocations(EraId::new(8), None)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_replace_validator_performance_report() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    let alice = PublicKey::from(&SecretKey::ed25519_from_bytes([1; 32]).unwrap());
+    let era_id = EraId::new(3);
+    let report = |proposed_blocks: u64| ValidatorPerformanceReport {
+        era_id,
+        validators: iter::once((
+            alice.clone(),
+            ValidatorPerformance {
+                proposed_blocks,
+                finality_signatures: 10,
+                rounds_missed: 1,
+            },
+        ))
+        .collect(),
+    };
+
+    assert!(storage.get_validator_performance(era_id).unwrap().is_none());
+    storage.put_validator_performance(&report(2)).unwrap();
+    assert_eq!(
+        storage.get_validator_performance(era_id).unwrap(),
+        Some(report(2))
+    );
+    // A later report for the same era replaces the earlier one.
+    storage.put_validator_performance(&report(4)).unwrap();
+    assert_eq!(
+        storage.get_validator_performance(era_id).unwrap(),
+        Some(report(4))
+    );
+    assert!(storage
+        .get_validator_performance(EraId::new(4))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn should_put_and_get_era_transition_proofs() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    let proof = |era_id: u64| EraTransitionProof {
+        switch_block_hash: Digest::hash([1]),
+        era_id: EraId::new(era_id),
+        era_end_hash: Digest::hash([2]),
+        proof_system: ProofSystem::Groth16Bn254,
+        circuit_id: "era-transition-v1-l20916".to_string(),
+        proof: Proof::new(Bytes::from(vec![3; 128])),
+    };
+
+    assert!(storage
+        .get_era_transition_proof(EraId::new(3))
+        .unwrap()
+        .is_none());
+    storage.put_era_transition_proof(&proof(3)).unwrap();
+    storage.put_era_transition_proof(&proof(4)).unwrap();
+    assert_eq!(
+        storage.get_era_transition_proof(EraId::new(3)).unwrap(),
+        Some(proof(3))
+    );
+    assert_eq!(
+        storage.get_era_transition_proof(EraId::new(4)).unwrap(),
+        Some(proof(4))
+    );
+}
+
+#[test]
+fn should_put_read_and_delete_proving_jobs() {
+    let mut harness = ComponentHarness::default();
+
+    let mut job = |era_id: u64| {
+        let block = Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::new(era_id),
+            10 * era_id,
+            ProtocolVersion::V1_0_0,
+            true,
+            None,
+        );
+        let circuit = EraTransition::new(block.header(), 100).unwrap();
+        PersistedJob {
+            job: Arc::new(ProvingJob::from(circuit)),
+            priority: Priority::Node,
+        }
+    };
+    let jobs = [job(1), job(2)];
+    let job_ids: Vec<_> = jobs
+        .iter()
+        .map(|persisted| persisted.job.job_id())
+        .collect();
+
+    let storage = storage_fixture(&harness);
+    assert!(storage.read_proving_jobs().unwrap().is_empty());
+    for persisted in &jobs {
+        storage.put_proving_job(persisted).unwrap();
+    }
+    let mut read_job_ids: Vec<_> = storage
+        .read_proving_jobs()
+        .unwrap()
+        .iter()
+        .map(|persisted| persisted.job.job_id())
+        .collect();
+    read_job_ids.sort();
+    let mut expected_job_ids = job_ids.clone();
+    expected_job_ids.sort();
+    assert_eq!(read_job_ids, expected_job_ids);
+
+    storage.delete_proving_job(job_ids[0]).unwrap();
+    // Deleting a job which is not stored is a no-op.
+    storage.delete_proving_job(job_ids[0]).unwrap();
+    let read_jobs = storage.read_proving_jobs().unwrap();
+    assert_eq!(read_jobs.len(), 1);
+    assert_eq!(read_jobs[0].job.job_id(), job_ids[1]);
+    assert_eq!(read_jobs[0].priority, Priority::Node);
+}
+
+#[test]
+fn should_put_get_and_delete_trie_chunks() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    // A trie of two chunks, and one small enough to be a single chunk.
+    let data = vec![7u8; ChunkWithProof::CHUNK_SIZE_BYTES + 1];
+    let chunks: Vec<_> = (0..2)
+        .map(|index| ChunkWithProof::new(&data, index).unwrap())
+        .collect();
+    let trie_hash = chunks[0].proof().root_hash();
+    let other_chunk = ChunkWithProof::new(&[1, 2, 3], 0).unwrap();
+    let other_trie_hash = other_chunk.proof().root_hash();
+
+    assert!(storage.get_trie_chunks(&trie_hash).unwrap().is_empty());
+    storage.put_trie_chunk(&chunks[1]).unwrap();
+    storage.put_trie_chunk(&other_chunk).unwrap();
+    storage.put_trie_chunk(&chunks[0]).unwrap();
+    // Storing a chunk again is not an error.
+    storage.put_trie_chunk(&chunks[0]).unwrap();
+
+    assert_eq!(storage.get_trie_chunks(&trie_hash).unwrap(), chunks);
+    assert_eq!(
+        storage.get_trie_chunks(&other_trie_hash).unwrap(),
+        vec![other_chunk.clone()]
+    );
+
+    storage.delete_trie_chunks(&trie_hash).unwrap();
+    assert!(storage.get_trie_chunks(&trie_hash).unwrap().is_empty());
+    assert_eq!(
+        storage.get_trie_chunks(&other_trie_hash).unwrap(),
+        vec![other_chunk]
+    );
+}
+
+#[test]
+fn should_read_legacy_unbonding_purse() {
+    // These bytes represent the `UnbondingPurse` struct with the `new_validator` field removed
+    // and serialized with `bincode`.
+    // In theory, we can generate these bytes by serializing the `WithdrawPurse`, but at some point,
+    // these two structs may diverge and it's a safe bet to rely on the bytes
+    // that are consistent with what we keep in the current storage.
+    const LEGACY_BYTES: &str = "0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e07010000002000000000000000197f6b23e16c8532c6abc838facd5ea789be0c76b2920334039bfa8b3d368d610100000020000000000000004508a07aa941707f3eb2db94c8897a80b2c1197476b6de213ac273df7d86c4ffffffffffffffffff40feffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+    let decoded = base16::decode(LEGACY_BYTES).expect("decode");
+    let deserialized: UnbondingPurse = deserialize_internal(&decoded)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+
+    // Make sure the new field is set to default.
+    assert_eq!(*deserialized.new_validator(), Option::default())
+}
+
+#[test]
+fn unbonding_purse_serialization_roundtrip() {
+    let original = UnbondingPurse::new(
+        URef::new([14; 32], AccessRights::READ_ADD_WRITE),
+        {
             let secret_key =
                 SecretKey::ed25519_from_bytes([42; SecretKey::ED25519_LENGTH]).unwrap();
             PublicKey::from(&secret_key)
@@ -2158,12 +3338,12 @@ fn should_initialize_block_metadata_db() {
 
     let block_4 = Block::random(&mut harness.rng);
 
-    let _ = storage.put_finality_signature(Box::new(fs_1_1.clone()));
-    let _ = storage.put_finality_signature(Box::new(fs_1_2.clone()));
-    let _ = storage.put_finality_signature(Box::new(fs_2_1.clone()));
-    let _ = storage.put_finality_signature(Box::new(fs_2_2.clone()));
-    let _ = storage.put_finality_signature(Box::new(fs_3_1.clone()));
-    let _ = storage.put_finality_signature(Box::new(fs_3_2.clone()));
+    let _ = storage.put_finality_signature(&fs_1_1);
+    let _ = storage.put_finality_signature(&fs_1_2);
+    let _ = storage.put_finality_signature(&fs_2_1);
+    let _ = storage.put_finality_signature(&fs_2_2);
+    let _ = storage.put_finality_signature(&fs_3_1);
+    let _ = storage.put_finality_signature(&fs_3_2);
 
     assert_signatures(
         &storage,
@@ -2235,3 +3415,243 @@ fn should_initialize_block_metadata_db() {
     assert_signatures(&storage, *block_3.hash(), vec![]);
     assert_signatures(&storage, *block_4.hash(), vec![]);
 }
+
+#[test]
+fn should_grow_map_when_full() {
+    const MIB: usize = 1024 * 1024;
+
+    let mut harness = ComponentHarness::default();
+    let cfg = Config {
+        max_block_store_size: MIB,
+        max_deploy_store_size: 0,
+        max_deploy_metadata_store_size: 0,
+        map_size_increment: MIB,
+        ..new_config(&harness)
+    };
+    let mut storage = storage_fixture_with_config(&harness, cfg);
+
+    // Keep storing deploys until the initial map is full and had to be grown.
+    let mut deploys = vec![];
+    while storage.map_size == MIB {
+        assert!(deploys.len() < 10_000, "memory map should have been grown");
+        let deploy = Arc::new(Deploy::random(&mut harness.rng));
+        assert!(put_deploy(&mut harness, &mut storage, deploy.clone()));
+        deploys.push(deploy);
+    }
+    assert_eq!(storage.map_size, 2 * MIB);
+
+    for deploy in deploys {
+        assert_eq!(
+            storage.get_deploy_by_hash(*deploy.hash()).as_ref(),
+            Some(&*deploy)
+        );
+    }
+}
+
+#[test]
+fn should_keep_read_cache_consistent_with_writes() {
+    const MIB: usize = 1024 * 1024;
+
+    let mut harness = ComponentHarness::default();
+    let cfg = Config {
+        read_cache_size: MIB,
+        ..new_config(&harness)
+    };
+    let mut storage = storage_fixture_with_config(&harness, cfg);
+
+    // Blocks are two days apart, so the first one can be pruned.
+    let two_days_millis = 2 * MAX_TTL.millis();
+    let mut blocks = vec![];
+    for height in 0..2 {
+        let deploy = Deploy::random(&mut harness.rng);
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .height(height)
+                .timestamp(Timestamp::from(height * two_days_millis))
+                .deploys(iter::once(&deploy))
+                .build(&mut harness.rng),
+        );
+        put_deploy(&mut harness, &mut storage, Arc::new(deploy));
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        blocks.push(block);
+    }
+    let block = &blocks[0];
+
+    // Reads are served from the cache once read from the database.
+    for _ in 0..2 {
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&**block)
+        );
+    }
+    assert!(storage
+        .read_cache
+        .borrow_mut()
+        .get_block(block.hash())
+        .is_some());
+
+    // Newly stored signatures are not hidden by cached ones.
+    let signatures_1 = random_signatures(&mut harness.rng, block);
+    let signatures_2 = random_signatures(&mut harness.rng, block);
+    put_block_signatures(&mut harness, &mut storage, signatures_1.clone());
+    assert_eq!(
+        get_block_signatures(&mut storage, *block.hash()),
+        Some(signatures_1.clone())
+    );
+    put_block_signatures(&mut harness, &mut storage, signatures_2.clone());
+    let mut merged_signatures = signatures_1;
+    merged_signatures.proofs.extend(signatures_2.proofs);
+    assert_eq!(
+        get_block_signatures(&mut storage, *block.hash()),
+        Some(merged_signatures)
+    );
+
+    // Pruned blocks are no longer served from the cache.
+    let outcome = harness.send_request(&mut storage, |responder| {
+        StorageRequest::PruneBlocks {
+            below_height: 1,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(outcome.pruned_block_count, 1);
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_none());
+    assert!(get_block_header_at_height(&mut storage, block.height(), false).is_some());
+}
+
+#[test]
+fn should_migrate_old_blocks_to_cold_storage() {
+    let mut harness = ComponentHarness::default();
+    let cfg = Config {
+        cold_storage_path: Some(harness.tmp.path().join("cold_storage")),
+        ..new_config(&harness)
+    };
+    let mut storage = storage_fixture_with_config(&harness, cfg.clone());
+
+    // One block per era; with an era depth of 1, the blocks of the first two eras are old.
+    let mut blocks = vec![];
+    let mut execution_results = vec![];
+    for height in 0..4 {
+        let deploy = Deploy::random(&mut harness.rng);
+        let block = Arc::new(
+            TestBlockBuilder::new()
+                .era(height)
+                .height(height)
+                .deploys(iter::once(&deploy))
+                .build(&mut harness.rng),
+        );
+        put_deploy(&mut harness, &mut storage, Arc::new(deploy));
+        put_complete_block(&mut harness, &mut storage, block.clone());
+        let block_execution_results: Vec<(DeployHash, ExecutionResult)> = block
+            .deploy_and_transfer_hashes()
+            .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+            .collect();
+        put_execution_results(
+            &mut harness,
+            &mut storage,
+            *block.hash(),
+            block_execution_results.iter().cloned().collect(),
+        );
+        blocks.push(block);
+        execution_results.push(block_execution_results);
+    }
+
+    assert_eq!(storage.migrate_to_cold_storage(u64::MAX).unwrap(), 2);
+    assert_eq!(storage.migrated_below_height, 2);
+    // Nothing else is old enough yet.
+    assert_eq!(storage.migrate_to_cold_storage(u64::MAX).unwrap(), 0);
+
+    let is_in_hot_tier = |storage: &Storage, block: &Block| {
+        let mut txn = storage.env.begin_ro_txn().unwrap();
+        txn.value_exists(storage.block_body_db, block.header().body_hash())
+            .unwrap()
+    };
+    for (index, block) in blocks.iter().enumerate() {
+        assert_eq!(is_in_hot_tier(&storage, block), index >= 2);
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&**block)
+        );
+        assert_eq!(
+            get_execution_results_for_block(&mut harness, &mut storage, *block.hash()),
+            Some(execution_results[index].clone())
+        );
+    }
+
+    // Migrated blocks are still read through after a restart, which requires the cold tier.
+    drop(storage);
+    let mut storage = storage_fixture_with_config(&harness, cfg);
+    assert_eq!(storage.migrated_below_height, 2);
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *blocks[0].hash()).as_ref(),
+        Some(&*blocks[0])
+    );
+    drop(storage);
+    let result = Storage::new(
+        &WithDir::new(harness.tmp.path(), new_config(&harness)),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    );
+    assert!(matches!(
+        result,
+        Err(FatalStorageError::MissingColdStorage {
+            migrated_below_height: 2
+        })
+    ));
+}
+
+#[test]
+fn should_inspect_storage_of_stopped_node() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+    let blocks: Vec<_> = (0..3)
+        .map(|height| {
+            let block = Arc::new(
+                TestBlockBuilder::new()
+                    .height(height)
+                    .build(&mut harness.rng),
+            );
+            put_complete_block(&mut harness, &mut storage, block.clone());
+            block
+        })
+        .collect();
+    drop(storage);
+
+    let cfg = WithDir::new(harness.tmp.path(), new_config(&harness));
+    let inspector = StorageInspector::open(&cfg, "test").unwrap();
+
+    let stored_block = inspector.get_block(blocks[1].hash()).unwrap().unwrap();
+    assert_eq!(&stored_block.header, blocks[1].header());
+    assert_eq!(stored_block.body.as_ref(), Some(blocks[1].body()));
+    assert_eq!(
+        inspector.get_block_by_height(1).unwrap().as_ref(),
+        Some(&stored_block)
+    );
+    assert!(inspector.get_block_by_height(3).unwrap().is_none());
+    assert_eq!(
+        inspector.get_highest_block().unwrap().unwrap().hash,
+        *blocks[2].hash()
+    );
+    assert_eq!(inspector.get_deploy(deploy.hash()).unwrap(), Some(deploy));
+
+    let stats = inspector.stats().unwrap();
+    let block_headers = stats
+        .databases
+        .iter()
+        .find(|database| database.name == "block_header")
+        .unwrap();
+    assert_eq!(block_headers.entry_count, 3);
+
+    // Storage which was never created is not created by the inspector.
+    assert!(StorageInspector::open(&cfg, "other").is_err());
+    assert!(!harness.tmp.path().join("storage").join("other").exists());
+}
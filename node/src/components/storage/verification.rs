@@ -0,0 +1,146 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+
+use casper_types::PublicKey;
+
+use crate::types::BlockHash;
+
+/// An inconsistency found while verifying the integrity of storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum StorageIssue {
+    /// A stored block header could not be deserialized.
+    CorruptHeader {
+        /// The hex-encoded key under which the header is stored.
+        key: String,
+    },
+    /// A block header is stored under a key other than its hash.
+    HeaderHashMismatch {
+        /// The hash under which the header is stored.
+        stored_under: BlockHash,
+        /// The actual hash of the header.
+        actual: BlockHash,
+    },
+    /// The body of a complete, unpruned block is missing.
+    MissingBody { block_hash: BlockHash, height: u64 },
+    /// The body of a block could not be deserialized.
+    CorruptBody { block_hash: BlockHash, height: u64 },
+    /// The body of a block does not hash to the body hash in its header.
+    BodyHashMismatch { block_hash: BlockHash, height: u64 },
+    /// The finality signatures of a block could not be deserialized.
+    CorruptSignatures { block_hash: BlockHash },
+    /// The finality signatures of a block are for a different block or era.
+    SignaturesMismatch { block_hash: BlockHash },
+    /// A finality signature is not a valid signature of the block hash.
+    InvalidSignature {
+        block_hash: BlockHash,
+        public_key: PublicKey,
+    },
+    /// A finality signature is by a public key which is not a validator in the block's era.
+    UnknownSigner {
+        block_hash: BlockHash,
+        public_key: PublicKey,
+    },
+}
+
+impl StorageIssue {
+    /// Returns `true` if the block this issue affects needs to be synchronized again, once the
+    /// issue has been repaired.
+    pub(super) fn requires_resync(&self) -> bool {
+        !matches!(
+            self,
+            StorageIssue::CorruptHeader { .. } | StorageIssue::HeaderHashMismatch { .. }
+        )
+    }
+}
+
+impl Display for StorageIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageIssue::CorruptHeader { key } => write!(f, "corrupt block header under {}", key),
+            StorageIssue::HeaderHashMismatch {
+                stored_under,
+                actual,
+            } => write!(
+                f,
+                "block header with hash {} stored under {}",
+                actual, stored_under
+            ),
+            StorageIssue::MissingBody { block_hash, height } => {
+                write!(
+                    f,
+                    "missing body of block {} at height {}",
+                    block_hash, height
+                )
+            }
+            StorageIssue::CorruptBody { block_hash, height } => {
+                write!(
+                    f,
+                    "corrupt body of block {} at height {}",
+                    block_hash, height
+                )
+            }
+            StorageIssue::BodyHashMismatch { block_hash, height } => write!(
+                f,
+                "body hash mismatch of block {} at height {}",
+                block_hash, height
+            ),
+            StorageIssue::CorruptSignatures { block_hash } => {
+                write!(f, "corrupt finality signatures of block {}", block_hash)
+            }
+            StorageIssue::SignaturesMismatch { block_hash } => write!(
+                f,
+                "finality signatures stored for block {} are for another block or era",
+                block_hash
+            ),
+            StorageIssue::InvalidSignature {
+                block_hash,
+                public_key,
+            } => write!(
+                f,
+                "invalid finality signature of block {} by {}",
+                block_hash, public_key
+            ),
+            StorageIssue::UnknownSigner {
+                block_hash,
+                public_key,
+            } => write!(
+                f,
+                "finality signature of block {} by {}, who is not a validator in its era",
+                block_hash, public_key
+            ),
+        }
+    }
+}
+
+/// The result of verifying the integrity of storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct StorageVerification {
+    /// The number of block headers checked.
+    pub(crate) checked_block_count: u64,
+    /// The inconsistencies found.
+    pub(crate) issues: Vec<StorageIssue>,
+    /// Whether the issues which can be repaired were repaired.
+    ///
+    /// Corrupt headers are never repaired; all other issues are repaired by discarding the
+    /// affected data and marking the block as incomplete, so that it is synchronized again.
+    pub(crate) repaired: bool,
+}
+
+impl Display for StorageVerification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checked {} block(s), found {} issue(s)",
+            self.checked_block_count,
+            self.issues.len()
+        )?;
+        if self.repaired && !self.issues.is_empty() {
+            write!(f, ", repaired")?;
+        }
+        for issue in &self.issues {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
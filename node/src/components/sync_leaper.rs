@@ -1,4 +1,5 @@
 //! The Sync Leaper
+mod config;
 mod error;
 mod event;
 mod leap_activity;
@@ -26,6 +27,7 @@ use crate::{
     },
     NodeRng,
 };
+pub use config::Config;
 pub(crate) use error::LeapActivityError;
 pub(crate) use event::Event;
 pub(crate) use leap_state::LeapState;
@@ -73,6 +75,7 @@ enum Error {
 #[derive(Debug, DataSize)]
 pub(crate) struct SyncLeaper {
     leap_activity: Option<LeapActivity>,
+    config: Config,
     chainspec: Arc<Chainspec>,
     #[data_size(skip)]
     metrics: Metrics,
@@ -80,11 +83,13 @@ pub(crate) struct SyncLeaper {
 
 impl SyncLeaper {
     pub(crate) fn new(
+        config: Config,
         chainspec: Arc<Chainspec>,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
         Ok(SyncLeaper {
             leap_activity: None,
+            config,
             chainspec,
             metrics: Metrics::new(registry)?,
         })
@@ -191,6 +196,7 @@ impl SyncLeaper {
                 .map(|peer| (*peer, PeerState::RequestSent))
                 .collect(),
             Instant::now(),
+            self.config.quorum as usize,
         ));
         RegisterLeapAttemptOutcome::FetchSyncLeapFromPeers(peers_to_ask)
     }
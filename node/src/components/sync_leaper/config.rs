@@ -0,0 +1,20 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_QUORUM: u8 = 3;
+
+/// Configuration options for the sync leaper.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+pub struct Config {
+    /// Number of peers which need to respond with the same sync leap before it is accepted while
+    /// requests to other peers are still in flight.
+    pub quorum: u8,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            quorum: DEFAULT_QUORUM,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, collections::HashMap, time::Instant};
+use std::{collections::HashMap, time::Instant};
 
 use datasize::DataSize;
 
@@ -11,6 +11,7 @@ pub(crate) struct LeapActivity {
     sync_leap_identifier: SyncLeapIdentifier,
     peers: HashMap<NodeId, PeerState>,
     leap_start: Instant,
+    quorum: usize,
 }
 
 impl LeapActivity {
@@ -18,11 +19,13 @@ impl LeapActivity {
         sync_leap_identifier: SyncLeapIdentifier,
         peers: HashMap<NodeId, PeerState>,
         leap_start: Instant,
+        quorum: usize,
     ) -> Self {
         Self {
             sync_leap_identifier,
             peers,
             leap_start,
+            quorum,
         }
     }
 
@@ -49,11 +52,21 @@ impl LeapActivity {
                 in_flight,
             };
         }
+        // a single peer could feed us a bogus ancestry, so while other requests are still in
+        // flight we hold out for a response corroborated by a quorum of peers; once all of them
+        // are done we settle for the best attested response.
+        let quorum = self.quorum.min(self.peers.len());
         match self.best_response() {
-            Ok((best_available, from_peers)) => LeapState::Received {
+            Ok((best_available, from_peers)) if from_peers.len() >= quorum || in_flight == 0 => {
+                LeapState::Received {
+                    in_flight,
+                    best_available: Box::new(best_available),
+                    from_peers,
+                }
+            }
+            Ok(_) => LeapState::Awaiting {
+                sync_leap_identifier,
                 in_flight,
-                best_available: Box::new(best_available),
-                from_peers,
             },
             // `Unobtainable` means we couldn't download it from any peer so far - don't treat it
             // as a failure if there are still requests in flight
@@ -70,6 +83,8 @@ impl LeapActivity {
         }
     }
 
+    /// Returns the sync leap received from the most peers along with those peers; among equally
+    /// attested responses the one with the highest block is preferred.
     fn best_response(&self) -> Result<(SyncLeap, Vec<NodeId>), LeapActivityError> {
         let reject_count = self
             .peers
@@ -77,44 +92,41 @@ impl LeapActivity {
             .filter(|peer_state| matches!(peer_state, PeerState::Rejected))
             .count();
 
-        let mut peers = vec![];
-        let mut maybe_ret = None;
+        let mut responses: Vec<(&SyncLeap, Vec<NodeId>)> = vec![];
         for (peer, peer_state) in &self.peers {
             match peer_state {
-                PeerState::Fetched(sync_leap) => match &maybe_ret {
-                    None => {
-                        maybe_ret = Some(sync_leap);
-                        peers.push(*peer);
+                PeerState::Fetched(sync_leap) => {
+                    match responses
+                        .iter_mut()
+                        .find(|(response, _)| **response == **sync_leap)
+                    {
+                        Some((_, peers)) => peers.push(*peer),
+                        None => responses.push((&**sync_leap, vec![*peer])),
                     }
-                    Some(current_ret) => {
-                        match current_ret
-                            .highest_block_height()
-                            .cmp(&sync_leap.highest_block_height())
-                        {
-                            Ordering::Less => {
-                                maybe_ret = Some(sync_leap);
-                                peers = vec![*peer];
-                            }
-                            Ordering::Equal => {
-                                peers.push(*peer);
-                            }
-                            Ordering::Greater => {}
-                        }
-                    }
-                },
+                }
                 PeerState::RequestSent | PeerState::Rejected | PeerState::CouldntFetch => {}
             }
         }
 
+        let maybe_ret = responses
+            .into_iter()
+            .max_by(|(leap_a, peers_a), (leap_b, peers_b)| {
+                peers_a.len().cmp(&peers_b.len()).then_with(|| {
+                    leap_a
+                        .highest_block_height()
+                        .cmp(&leap_b.highest_block_height())
+                })
+            });
+
         match maybe_ret {
-            Some(sync_leap) => Ok((*sync_leap.clone(), peers)),
+            Some((sync_leap, peers)) => Ok((sync_leap.clone(), peers)),
             None => {
                 if reject_count > 0 {
-                    Err(LeapActivityError::TooOld(self.sync_leap_identifier, peers))
+                    Err(LeapActivityError::TooOld(self.sync_leap_identifier, vec![]))
                 } else {
                     Err(LeapActivityError::Unobtainable(
                         self.sync_leap_identifier,
-                        peers,
+                        vec![],
                     ))
                 }
             }
@@ -213,6 +225,7 @@ mod tests {
             sync_leap_identifier,
             peers: [peer_1.clone()].iter().cloned().collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
 
         let (actual_sync_leap, actual_peers) = leap_activity.best_response().unwrap();
@@ -270,6 +283,7 @@ mod tests {
             sync_leap_identifier,
             peers: peers_with_sync_leaps.clone(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
 
         let (actual_sync_leap, actual_peers) = leap_activity.best_response().unwrap();
@@ -376,6 +390,7 @@ mod tests {
                 .cloned()
                 .collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
 
         let best_response_error = leap_activity.best_response().unwrap_err();
@@ -404,6 +419,7 @@ mod tests {
             sync_leap_identifier,
             peers: HashMap::new(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
         assert!(matches!(
             leap_activity.status(),
@@ -415,6 +431,7 @@ mod tests {
             sync_leap_identifier,
             peers: [peer_1].iter().cloned().collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
         assert!(matches!(
             leap_activity.status(),
@@ -434,6 +451,7 @@ mod tests {
             sync_leap_identifier,
             peers: [peer_1, peer_2].iter().cloned().collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
         assert!(matches!(leap_activity.status(), LeapState::Awaiting { .. }));
 
@@ -458,6 +476,7 @@ mod tests {
             sync_leap_identifier,
             peers: [peer_1].iter().cloned().collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
         assert!(matches!(leap_activity.status(), LeapState::Received { .. }));
 
@@ -474,6 +493,122 @@ mod tests {
         assert!(matches!(leap_activity.status(), LeapState::Received { .. }));
     }
 
+    #[test]
+    fn leap_activity_status_awaits_quorum() {
+        let mut rng = TestRng::new();
+
+        let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(BlockHash::random(&mut rng));
+
+        // A bogus sync leap claiming a higher block than the honest one.
+        let block = make_random_block_with_height(&mut rng, 1000);
+        let bogus_sync_leap = make_sync_leap_with_trusted_block_header(block.header().clone());
+        let block = make_random_block_with_height(&mut rng, 10);
+        let honest_sync_leap = make_sync_leap_with_trusted_block_header(block.header().clone());
+
+        let bogus_peer = NodeId::random(&mut rng);
+        let honest_peer_1 = NodeId::random(&mut rng);
+        let honest_peer_2 = NodeId::random(&mut rng);
+        let mut leap_activity = LeapActivity {
+            sync_leap_identifier,
+            peers: [
+                (
+                    bogus_peer,
+                    PeerState::Fetched(Box::new(bogus_sync_leap.clone())),
+                ),
+                (
+                    honest_peer_1,
+                    PeerState::Fetched(Box::new(honest_sync_leap.clone())),
+                ),
+                (honest_peer_2, PeerState::RequestSent),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            leap_start: Instant::now(),
+            quorum: 2,
+        };
+
+        // No response is corroborated by a quorum yet, so we keep waiting.
+        assert!(matches!(leap_activity.status(), LeapState::Awaiting { .. }));
+
+        // The second honest response reaches the quorum and wins over the higher bogus one.
+        leap_activity.peers.insert(
+            honest_peer_2,
+            PeerState::Fetched(Box::new(honest_sync_leap.clone())),
+        );
+        match leap_activity.status() {
+            LeapState::Received {
+                best_available,
+                mut from_peers,
+                ..
+            } => {
+                let mut expected_peers = vec![honest_peer_1, honest_peer_2];
+                from_peers.sort_unstable();
+                expected_peers.sort_unstable();
+                assert_eq!(*best_available, honest_sync_leap);
+                assert_eq!(from_peers, expected_peers);
+            }
+            status => panic!("unexpected leap status: {}", status),
+        }
+    }
+
+    #[test]
+    fn leap_activity_status_falls_back_to_best_attested() {
+        let mut rng = TestRng::new();
+
+        let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(BlockHash::random(&mut rng));
+
+        let block = make_random_block_with_height(&mut rng, 1000);
+        let bogus_sync_leap = make_sync_leap_with_trusted_block_header(block.header().clone());
+        let block = make_random_block_with_height(&mut rng, 10);
+        let honest_sync_leap = make_sync_leap_with_trusted_block_header(block.header().clone());
+
+        let mut leap_activity = LeapActivity {
+            sync_leap_identifier,
+            peers: [
+                (
+                    NodeId::random(&mut rng),
+                    PeerState::Fetched(Box::new(bogus_sync_leap)),
+                ),
+                (
+                    NodeId::random(&mut rng),
+                    PeerState::Fetched(Box::new(honest_sync_leap.clone())),
+                ),
+                (NodeId::random(&mut rng), PeerState::RequestSent),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            leap_start: Instant::now(),
+            quorum: 3,
+        };
+        assert!(matches!(leap_activity.status(), LeapState::Awaiting { .. }));
+
+        // Once no requests are in flight, the response attested by the most peers is used even
+        // though it falls short of the quorum.
+        leap_activity.peers.insert(
+            NodeId::random(&mut rng),
+            PeerState::Fetched(Box::new(honest_sync_leap.clone())),
+        );
+        for peer_state in leap_activity.peers.values_mut() {
+            if matches!(peer_state, PeerState::RequestSent) {
+                *peer_state = PeerState::CouldntFetch;
+            }
+        }
+        match leap_activity.status() {
+            LeapState::Received {
+                best_available,
+                from_peers,
+                in_flight,
+            } => {
+                assert_eq!(in_flight, 0);
+                assert_eq!(*best_available, honest_sync_leap);
+                assert_eq!(from_peers.len(), 2);
+            }
+            status => panic!("unexpected leap status: {}", status),
+        }
+    }
+
     #[test]
     fn register_peer() {
         let mut rng = TestRng::new();
@@ -486,6 +621,7 @@ mod tests {
             sync_leap_identifier,
             peers: [peer_1.clone()].iter().cloned().collect(),
             leap_start: Instant::now(),
+            quorum: 1,
         };
 
         // Expect the single peer specified on creation.
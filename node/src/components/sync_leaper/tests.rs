@@ -11,7 +11,7 @@ use crate::{
     types::{Block, BlockHash, Chainspec, NodeId, SyncLeap, SyncLeapIdentifier},
 };
 
-use super::{Error, SyncLeaper};
+use super::{Config, Error, SyncLeaper};
 
 pub(crate) fn make_test_sync_leap(rng: &mut TestRng) -> SyncLeap {
     let block = Block::random(rng);
@@ -26,7 +26,7 @@ pub(crate) fn make_test_sync_leap(rng: &mut TestRng) -> SyncLeap {
 fn make_sync_leaper(rng: &mut TestRng) -> SyncLeaper {
     let chainspec = Chainspec::random(rng);
     let registry = Registry::new();
-    SyncLeaper::new(Arc::new(chainspec), &registry).unwrap()
+    SyncLeaper::new(Config::default(), Arc::new(chainspec), &registry).unwrap()
 }
 
 fn assert_peers(expected: &[NodeId], actual: &Vec<(NodeId, PeerState)>) {
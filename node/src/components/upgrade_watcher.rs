@@ -7,6 +7,8 @@
 //! <https://casperlabs.atlassian.net/wiki/spaces/EN/pages/135528449/Genesis+Process+Specification>
 //! for full details.
 
+mod pre_download;
+
 use std::{
     fmt::{self, Display, Formatter},
     fs, io,
@@ -24,7 +26,7 @@ use tracing::{debug, error, info, trace, warn};
 
 use casper_types::{
     file_utils::{self, ReadFileError},
-    EraId, ProtocolVersion, TimeDiff,
+    EraId, ProtocolVersion, PublicKey, TimeDiff,
 };
 
 use crate::{
@@ -40,21 +42,36 @@ use crate::{
     },
     NodeRng,
 };
+use pre_download::PreDownloader;
 
 const COMPONENT_NAME: &str = "upgrade_watcher";
 
 const DEFAULT_UPGRADE_CHECK_INTERVAL: &str = "30sec";
+const DEFAULT_DOWNLOAD_TIMEOUT: &str = "5min";
+
+/// The subdir of the chainspec's parent dir in which pre-downloaded upgrade packages are staged.
+const STAGING_DIR_NAME: &str = "staged_upgrades";
 
-#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     /// How often to scan file system for available upgrades.
     pub upgrade_check_interval: TimeDiff,
+    /// The URL of the manifest describing the package of the next protocol version.
+    ///
+    /// If set, the package is downloaded, verified against the upgrade signing keys of the current
+    /// chainspec and staged for installation as soon as it is published.
+    #[serde(default)]
+    pub upgrade_manifest_url: Option<String>,
+    /// The maximum time downloading a single file of an upgrade package may take.
+    pub download_timeout: TimeDiff,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             upgrade_check_interval: DEFAULT_UPGRADE_CHECK_INTERVAL.parse().unwrap(),
+            upgrade_manifest_url: None,
+            download_timeout: DEFAULT_DOWNLOAD_TIMEOUT.parse().unwrap(),
         }
     }
 }
@@ -70,6 +87,8 @@ pub(crate) enum Event {
     CheckForNextUpgrade,
     /// If the result of checking for an upgrade is successful, it is passed here.
     GotNextUpgrade(NextUpgrade),
+    /// The result of pre-downloading the package of the next upgrade, if one was staged.
+    PreDownloadFinished(Option<ProtocolVersion>),
 }
 
 impl Display for Event {
@@ -87,6 +106,12 @@ impl Display for Event {
             Event::GotNextUpgrade(next_upgrade) => {
                 write!(formatter, "got {}", next_upgrade)
             }
+            Event::PreDownloadFinished(Some(version)) => {
+                write!(formatter, "staged upgrade package for {}", version)
+            }
+            Event::PreDownloadFinished(None) => {
+                write!(formatter, "no upgrade package staged")
+            }
         }
     }
 }
@@ -175,6 +200,12 @@ pub(crate) struct UpgradeWatcher {
     root_dir: PathBuf,
     state: ComponentState,
     next_upgrade: Option<NextUpgrade>,
+    /// Set if upgrade packages are to be pre-downloaded.
+    pre_downloader: Option<PreDownloader>,
+    pre_download_in_progress: bool,
+    /// The version of the most recently staged upgrade package.
+    #[data_size(skip)]
+    staged_version: Option<ProtocolVersion>,
 }
 
 impl UpgradeWatcher {
@@ -191,6 +222,11 @@ impl UpgradeWatcher {
 
         let current_version = chainspec.protocol_config.version;
         let next_upgrade = next_upgrade(root_dir.clone(), current_version);
+        let pre_downloader = pre_downloader(
+            &config,
+            &chainspec.protocol_config.upgrade_signing_keys,
+            &root_dir,
+        );
 
         let upgrade_watcher = UpgradeWatcher {
             current_version,
@@ -198,6 +234,9 @@ impl UpgradeWatcher {
             root_dir,
             state: ComponentState::Uninitialized,
             next_upgrade,
+            pre_downloader,
+            pre_download_in_progress: false,
+            staged_version: None,
         };
 
         Ok(upgrade_watcher)
@@ -223,7 +262,7 @@ impl UpgradeWatcher {
         self.check_for_next_upgrade(effect_builder)
     }
 
-    fn check_for_next_upgrade<REv>(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
+    fn check_for_next_upgrade<REv>(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
     where
         REv: From<UpgradeWatcherAnnouncement> + Send,
     {
@@ -246,6 +285,7 @@ impl UpgradeWatcher {
         }
         .ignore();
 
+        effects.extend(self.pre_download_next_upgrade());
         effects.extend(
             effect_builder
                 .set_timeout(self.config.upgrade_check_interval.into())
@@ -255,6 +295,54 @@ impl UpgradeWatcher {
         effects
     }
 
+    /// Downloads and stages the package of the next upgrade, unless pre-downloading is disabled,
+    /// already running, or the next upgrade is already staged or installed.
+    fn pre_download_next_upgrade(&mut self) -> Effects<Event> {
+        let pre_downloader = match &self.pre_downloader {
+            Some(pre_downloader) => pre_downloader.clone(),
+            None => return Effects::new(),
+        };
+        if self.pre_download_in_progress
+            || self.staged_version.is_some()
+            || self.next_upgrade.is_some()
+        {
+            return Effects::new();
+        }
+
+        self.pre_download_in_progress = true;
+        let current_version = self.current_version;
+        async move {
+            pre_downloader
+                .run(current_version)
+                .await
+                .unwrap_or_else(|error| {
+                    warn!(%error, "failed to pre-download upgrade package");
+                    None
+                })
+        }
+        .event(Event::PreDownloadFinished)
+    }
+
+    fn handle_pre_download_finished(
+        &mut self,
+        maybe_staged_version: Option<ProtocolVersion>,
+    ) -> Effects<Event> {
+        self.pre_download_in_progress = false;
+        if let Some(version) = maybe_staged_version {
+            info!(
+                %version,
+                staged_dir = %self
+                    .root_dir
+                    .join(STAGING_DIR_NAME)
+                    .join(dir_name_from_version(&version))
+                    .display(),
+                "upgrade package staged, install it before its activation point"
+            );
+            self.staged_version = Some(version);
+        }
+        Effects::new()
+    }
+
     fn handle_got_next_upgrade(&mut self, next_upgrade: NextUpgrade) -> Effects<Event> {
         debug!("got {}", next_upgrade);
         if let Some(ref current_point) = self.next_upgrade {
@@ -303,7 +391,10 @@ where
             }
             ComponentState::Initializing => match event {
                 Event::Initialize => self.start_checking_for_upgrades(effect_builder),
-                Event::Request(_) | Event::CheckForNextUpgrade | Event::GotNextUpgrade(_) => {
+                Event::Request(_)
+                | Event::CheckForNextUpgrade
+                | Event::GotNextUpgrade(_)
+                | Event::PreDownloadFinished(_) => {
                     warn!(
                         ?event,
                         name = <Self as Component<MainEvent>>::name(self),
@@ -324,6 +415,9 @@ where
                 Event::Request(request) => request.0.respond(self.next_upgrade.clone()).ignore(),
                 Event::CheckForNextUpgrade => self.check_for_next_upgrade(effect_builder),
                 Event::GotNextUpgrade(next_upgrade) => self.handle_got_next_upgrade(next_upgrade),
+                Event::PreDownloadFinished(maybe_staged_version) => {
+                    self.handle_pre_download_finished(maybe_staged_version)
+                }
             },
         }
     }
@@ -369,6 +463,29 @@ impl UpgradePoint {
     }
 }
 
+/// Returns a `PreDownloader` if an upgrade manifest URL is configured and the chainspec lists keys
+/// to verify the packages with.
+fn pre_downloader(
+    config: &Config,
+    upgrade_signing_keys: &[PublicKey],
+    root_dir: &Path,
+) -> Option<PreDownloader> {
+    let manifest_url = config.upgrade_manifest_url.clone()?;
+    if upgrade_signing_keys.is_empty() {
+        warn!(
+            %manifest_url,
+            "chainspec lists no upgrade signing keys, not pre-downloading upgrade packages"
+        );
+        return None;
+    }
+    Some(PreDownloader::new(
+        manifest_url,
+        upgrade_signing_keys.to_vec(),
+        root_dir.join(STAGING_DIR_NAME),
+        config.download_timeout,
+    ))
+}
+
 fn dir_name_from_version(version: &ProtocolVersion) -> PathBuf {
     PathBuf::from(version.to_string().replace('.', "_"))
 }
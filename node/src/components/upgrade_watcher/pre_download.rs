@@ -0,0 +1,398 @@
+//! Downloading, verifying and staging the package of the next protocol version ahead of its
+//! activation point.
+//!
+//! The package is described by a manifest published at the configured URL, which lists the files
+//! of the package along with their checksums, and which must be signed by one of the upgrade
+//! signing keys listed in the current chainspec.  Verified packages are staged in a versioned
+//! subdir of the staging dir, from where the operator installs them.
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task;
+use tracing::debug;
+
+use casper_hashing::Digest;
+use casper_types::{bytesrepr, crypto, ProtocolVersion, PublicKey, Signature, TimeDiff};
+
+use super::{dir_name_from_version, UpgradePoint};
+use crate::types::chainspec::CHAINSPEC_FILENAME;
+
+/// An error pre-downloading an upgrade package.
+#[derive(Debug, Error)]
+pub(super) enum Error {
+    /// Failed to download a file.
+    #[error("failed to download {url}: {error}")]
+    Download {
+        /// The URL of the file.
+        url: String,
+        /// The underlying error.
+        error: reqwest::Error,
+    },
+
+    /// The manifest could not be decoded.
+    #[error("decoding manifest from JSON error: {0}")]
+    DecodingManifest(#[from] serde_json::Error),
+
+    /// The signed contents of the manifest could not be serialized.
+    #[error("serializing manifest error: {0}")]
+    Bytesrepr(bytesrepr::Error),
+
+    /// A file name in the manifest is not a plain file name.
+    #[error("invalid file name in manifest: {0:?}")]
+    InvalidFileName(String),
+
+    /// The manifest does not list a chainspec.
+    #[error("manifest does not list a {}", CHAINSPEC_FILENAME)]
+    MissingChainspec,
+
+    /// The manifest is not signed by any of the upgrade signing keys.
+    #[error("manifest has no valid signature by an upgrade signing key")]
+    NoValidSignature,
+
+    /// A downloaded file does not match its checksum.
+    #[error("checksum mismatch for {name}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// The name of the file.
+        name: String,
+        /// The checksum listed in the manifest.
+        expected: Digest,
+        /// The checksum of the downloaded file.
+        actual: Digest,
+    },
+
+    /// The downloaded chainspec could not be decoded.
+    #[error("decoding chainspec from TOML error: {0}")]
+    DecodingChainspec(#[from] toml::de::Error),
+
+    /// The downloaded chainspec is for a different version than the manifest.
+    #[error("manifest is for version {manifest_version}, but chainspec for {chainspec_version}")]
+    VersionMismatch {
+        /// The version given in the manifest.
+        manifest_version: ProtocolVersion,
+        /// The version given in the chainspec.
+        chainspec_version: ProtocolVersion,
+    },
+
+    /// Failed to write the staged package.
+    #[error("failed to stage package in {}: {error}", dir.display())]
+    Stage {
+        /// The directory which could not be written.
+        dir: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+
+    /// The task staging the package failed.
+    #[error("failed to join staging task: {0}")]
+    Join(#[from] task::JoinError),
+}
+
+/// A file of an upgrade package.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackageFile {
+    /// The name under which the file is staged.
+    name: String,
+    /// The URL from which the file is downloaded.
+    url: String,
+    /// The hash of the file's contents.
+    checksum: Digest,
+}
+
+/// A signature over the contents of a manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestSignature {
+    public_key: PublicKey,
+    signature: Signature,
+}
+
+/// The manifest describing the package of an upcoming protocol version.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    protocol_version: ProtocolVersion,
+    files: Vec<PackageFile>,
+    signatures: Vec<ManifestSignature>,
+}
+
+impl Manifest {
+    /// Returns the hash signed by the manifest's signatures.
+    ///
+    /// It covers the protocol version and the names and checksums of the files, but not their
+    /// URLs, so that the same signed package can be served from different locations.
+    fn signed_hash(&self) -> Result<Digest, Error> {
+        let files: Vec<(String, Digest)> = self
+            .files
+            .iter()
+            .map(|file| (file.name.clone(), file.checksum))
+            .collect();
+        let bytes =
+            bytesrepr::serialize((self.protocol_version, files)).map_err(Error::Bytesrepr)?;
+        Ok(Digest::hash(bytes))
+    }
+
+    /// Checks that the files are plain, distinct file names including a chainspec, and that the
+    /// manifest is signed by at least one of `signing_keys`.
+    fn verify(&self, signing_keys: &[PublicKey]) -> Result<(), Error> {
+        let mut names = BTreeSet::new();
+        for file in &self.files {
+            let is_plain_name = !file.name.is_empty()
+                && file.name != "."
+                && file.name != ".."
+                && !file.name.contains(|c| c == '/' || c == '\\');
+            if !is_plain_name || !names.insert(file.name.as_str()) {
+                return Err(Error::InvalidFileName(file.name.clone()));
+            }
+        }
+        if !names.contains(CHAINSPEC_FILENAME) {
+            return Err(Error::MissingChainspec);
+        }
+
+        let signed_hash = self.signed_hash()?;
+        let is_signed = self.signatures.iter().any(|manifest_signature| {
+            signing_keys.contains(&manifest_signature.public_key)
+                && crypto::verify(
+                    signed_hash,
+                    &manifest_signature.signature,
+                    &manifest_signature.public_key,
+                )
+                .is_ok()
+        });
+        if !is_signed {
+            return Err(Error::NoValidSignature);
+        }
+        Ok(())
+    }
+}
+
+/// Downloads the package of the next protocol version and stages it once verified.
+#[derive(Clone, DataSize, Debug)]
+pub(super) struct PreDownloader {
+    manifest_url: String,
+    signing_keys: Vec<PublicKey>,
+    staging_dir: PathBuf,
+    download_timeout: TimeDiff,
+}
+
+impl PreDownloader {
+    pub(super) fn new(
+        manifest_url: String,
+        signing_keys: Vec<PublicKey>,
+        staging_dir: PathBuf,
+        download_timeout: TimeDiff,
+    ) -> Self {
+        PreDownloader {
+            manifest_url,
+            signing_keys,
+            staging_dir,
+            download_timeout,
+        }
+    }
+
+    /// Stages the package described by the manifest if it is for a version after
+    /// `current_version`, returning the version of the staged package.
+    ///
+    /// Returns `None` if there is no such package, and does nothing if it was already staged.
+    pub(super) async fn run(
+        self,
+        current_version: ProtocolVersion,
+    ) -> Result<Option<ProtocolVersion>, Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from(self.download_timeout))
+            .build()
+            .map_err(|error| Error::Download {
+                url: self.manifest_url.clone(),
+                error,
+            })?;
+
+        let manifest: Manifest =
+            serde_json::from_slice(&download(&client, &self.manifest_url).await?)?;
+        let version = manifest.protocol_version;
+        if version <= current_version {
+            debug!(%version, "no upgrade package newer than the current version");
+            return Ok(None);
+        }
+        let staged_dir = self.staging_dir.join(dir_name_from_version(&version));
+        if staged_dir.exists() {
+            debug!(%version, "upgrade package already staged");
+            return Ok(Some(version));
+        }
+        manifest.verify(&self.signing_keys)?;
+
+        let mut files = Vec::with_capacity(manifest.files.len());
+        for file in manifest.files {
+            let contents = download(&client, &file.url).await?;
+            let actual = Digest::hash(&contents);
+            if actual != file.checksum {
+                return Err(Error::ChecksumMismatch {
+                    name: file.name,
+                    expected: file.checksum,
+                    actual,
+                });
+            }
+            if file.name == CHAINSPEC_FILENAME {
+                let upgrade_point: UpgradePoint = toml::from_slice(&contents)?;
+                if upgrade_point.protocol_config.version != version {
+                    return Err(Error::VersionMismatch {
+                        manifest_version: version,
+                        chainspec_version: upgrade_point.protocol_config.version,
+                    });
+                }
+            }
+            files.push((file.name, contents));
+        }
+
+        let staging_dir = self.staging_dir;
+        task::spawn_blocking(move || stage(&staging_dir, &staged_dir, files)).await??;
+        Ok(Some(version))
+    }
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, Error> {
+    let to_error = |error| Error::Download {
+        url: url.to_string(),
+        error,
+    };
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(to_error)?;
+    Ok(response.bytes().await.map_err(to_error)?.to_vec())
+}
+
+/// Writes `files` to a temporary dir in `staging_dir`, then moves it to `staged_dir`, so that a
+/// partially written package is never mistaken for a staged one.
+fn stage(
+    staging_dir: &Path,
+    staged_dir: &Path,
+    files: Vec<(String, Vec<u8>)>,
+) -> Result<(), Error> {
+    let to_error = |error| Error::Stage {
+        dir: staged_dir.to_path_buf(),
+        error,
+    };
+    fs::create_dir_all(staging_dir).map_err(to_error)?;
+    let temp_dir = tempfile::tempdir_in(staging_dir).map_err(to_error)?;
+    for (name, contents) in files {
+        fs::write(temp_dir.path().join(name), contents).map_err(to_error)?;
+    }
+    fs::rename(temp_dir.into_path(), staged_dir).map_err(to_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::SecretKey;
+
+    use super::*;
+
+    fn signed_manifest(secret_key: &SecretKey, names: &[&str]) -> Manifest {
+        let files = names
+            .iter()
+            .map(|name| PackageFile {
+                name: name.to_string(),
+                url: format!("https://example.com/{}", name),
+                checksum: Digest::hash(name.as_bytes()),
+            })
+            .collect();
+        let mut manifest = Manifest {
+            protocol_version: ProtocolVersion::from_parts(2, 0, 0),
+            files,
+            signatures: vec![],
+        };
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(manifest.signed_hash().unwrap(), secret_key, &public_key);
+        manifest.signatures.push(ManifestSignature {
+            public_key,
+            signature,
+        });
+        manifest
+    }
+
+    #[test]
+    fn should_verify_manifest() {
+        let mut rng = crate::new_rng();
+        let secret_key = SecretKey::random(&mut rng);
+        let signing_keys = vec![PublicKey::from(&secret_key)];
+
+        let manifest = signed_manifest(&secret_key, &[CHAINSPEC_FILENAME, "casper-node"]);
+        assert!(manifest.verify(&signing_keys).is_ok());
+
+        // Not signed by an upgrade signing key.
+        let other_keys = vec![PublicKey::random(&mut rng)];
+        assert!(matches!(
+            manifest.verify(&other_keys),
+            Err(Error::NoValidSignature)
+        ));
+
+        // Tampered with after signing.
+        let mut tampered = manifest.clone();
+        tampered.files[1].checksum = Digest::hash(b"malicious");
+        assert!(matches!(
+            tampered.verify(&signing_keys),
+            Err(Error::NoValidSignature)
+        ));
+
+        // Served from a different location.
+        let mut mirrored = manifest;
+        mirrored.files[1].url = "https://mirror.example.com/casper-node".to_string();
+        assert!(mirrored.verify(&signing_keys).is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_file_names() {
+        let mut rng = crate::new_rng();
+        let secret_key = SecretKey::random(&mut rng);
+        let signing_keys = vec![PublicKey::from(&secret_key)];
+
+        for name in ["", "..", "../casper-node", "bin/casper-node"] {
+            let manifest = signed_manifest(&secret_key, &[CHAINSPEC_FILENAME, name]);
+            assert!(matches!(
+                manifest.verify(&signing_keys),
+                Err(Error::InvalidFileName(_))
+            ));
+        }
+
+        let manifest = signed_manifest(&secret_key, &[CHAINSPEC_FILENAME, CHAINSPEC_FILENAME]);
+        assert!(matches!(
+            manifest.verify(&signing_keys),
+            Err(Error::InvalidFileName(_))
+        ));
+
+        let manifest = signed_manifest(&secret_key, &["casper-node"]);
+        assert!(matches!(
+            manifest.verify(&signing_keys),
+            Err(Error::MissingChainspec)
+        ));
+    }
+
+    #[test]
+    fn should_stage_package() {
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let staging_dir = tempdir.path().join("staged");
+        let staged_dir = staging_dir.join("2_0_0");
+        let files = vec![
+            (CHAINSPEC_FILENAME.to_string(), b"chainspec".to_vec()),
+            ("casper-node".to_string(), b"binary".to_vec()),
+        ];
+
+        stage(&staging_dir, &staged_dir, files).unwrap();
+
+        assert_eq!(
+            fs::read(staged_dir.join(CHAINSPEC_FILENAME)).unwrap(),
+            b"chainspec"
+        );
+        assert_eq!(fs::read(staged_dir.join("casper-node")).unwrap(), b"binary");
+        assert_eq!(fs::read_dir(&staging_dir).unwrap().count(), 1);
+    }
+}
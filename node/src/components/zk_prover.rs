@@ -0,0 +1,724 @@
+//! Zero-knowledge prover component.
+//!
+//! Runs proving jobs submitted by other components on a dedicated pool of threads, and announces
+//! each finished job.  Proving a statement can take minutes, so jobs never run on the reactor's
+//! threads: handling an event only ever queues a job or hands it to an idle worker thread.
+//!
+//! Jobs wait in a bounded queue, from which the job with the highest priority is started first,
+//! so that proofs the node needs itself are not held up by proofs requested by third parties.
+//! Third parties may attach a priority hint to their requests, up to the limit set in the
+//! chainspec, to order their requests among themselves.  If the queue is full, a job evicts the
+//! queued job of the lowest priority if that is lower than its own.  Running jobs are never
+//! interrupted, so a job of higher priority may still have to wait for idle worker threads.
+//!
+//! Circuits are proven by the `ProvingBackend` of the configured proof system, using keys from the
+//! configured keys directory.  Jobs proving a circuit whose keys are missing fail, unless the node
+//! is configured to generate keys itself, which is only fit for testing.
+//!
+//! If configured to, the component also proves the validators set by each switch block for the next
+//! era, and stores the proofs.
+//!
+//! Queued and running jobs are persisted in storage until they finish, so that jobs interrupted by
+//! a restart of the node are run again from the start once it is back up.
+
+mod backend;
+mod circuit;
+mod config;
+mod era_transition;
+mod gadgets;
+mod job_queue;
+mod keys;
+mod state_inclusion;
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::{self, Display, Formatter},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+use datasize::DataSize;
+use derive_more::From;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use casper_hashing::Digest;
+use casper_types::bytesrepr::{Bytes, ToBytes};
+
+use crate::{
+    components::Component,
+    effect::{
+        announcements::ZkProverAnnouncement,
+        requests::{StorageRequest, ZkProverRequest},
+        EffectBuilder, EffectExt, Effects,
+    },
+    types::{chainspec::ProofRequestsConfig, Block},
+    utils::{thread_pool::ThreadPool, WithDir},
+    NodeRng,
+};
+pub(crate) use backend::ProofSystem;
+use backend::{Backend, ProvingBackend};
+pub(crate) use circuit::{Circuit, Statement};
+pub(crate) use config::Config;
+pub(crate) use era_transition::{EraTransition, EraTransitionProof};
+use job_queue::JobQueue;
+use keys::KeyStore;
+pub(crate) use state_inclusion::{StateInclusion, StateInclusionError};
+
+const COMPONENT_NAME: &str = "zk_prover";
+
+/// The number of finished jobs whose results are kept for status queries.
+const MAX_FINISHED_JOBS: usize = 1024;
+
+/// Identifies a proving job.
+///
+/// Two jobs with the same ID prove the same statement, so only one of them is ever run.
+#[derive(
+    Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, DataSize,
+)]
+pub(crate) struct JobId(Digest);
+
+impl JobId {
+    pub(crate) fn new(digest: Digest) -> Self {
+        JobId(digest)
+    }
+
+    /// Returns the digest identifying the job.
+    pub(crate) fn digest(&self) -> &Digest {
+        &self.0
+    }
+}
+
+impl Display for JobId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "proving job {}", self.0)
+    }
+}
+
+impl FromStr for JobId {
+    type Err = casper_hashing::Error;
+
+    /// Parses the hex-encoded digest of a job ID.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Digest::from_hex(value).map(JobId)
+    }
+}
+
+/// The priority of a proving job.
+///
+/// Queued jobs of higher priority are always started before those of lower priority.  Proofs the
+/// node generates for itself have a higher priority than any requested by a third party.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, DataSize)]
+pub(crate) enum Priority {
+    /// A proof requested by a third party, e.g. via the RPC server, with the priority hint given
+    /// in the request.
+    ThirdParty { hint: u64 },
+    /// A proof the node generates for itself.
+    Node,
+}
+
+/// A serialized proof.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, DataSize)]
+pub(crate) struct Proof(Bytes);
+
+impl Proof {
+    pub(crate) fn new(bytes: Bytes) -> Self {
+        Proof(bytes)
+    }
+
+    /// Returns the serialized proof.
+    pub(crate) fn bytes(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+/// An error generating a proof.
+#[derive(Clone, PartialEq, Eq, Debug, Error, Serialize)]
+pub(crate) enum ProvingError {
+    /// The job failed.
+    #[error("proving failed: {0}")]
+    Failed(String),
+    /// The job panicked.
+    #[error("proving aborted")]
+    Aborted,
+    /// The job was evicted from the full queue by a job of higher priority before it started.
+    #[error("evicted from the proving queue by a job of higher priority")]
+    Evicted,
+}
+
+/// An error submitting a proving job.
+#[derive(Clone, PartialEq, Eq, Debug, Error, Serialize)]
+pub(crate) enum SubmitJobError {
+    /// The queue is full of jobs of at least the same priority.
+    #[error("proving queue is full")]
+    QueueFull,
+    /// The priority hint is higher than the chainspec allows.
+    #[error("priority hint {hint} exceeds the maximum of {max_priority_hint}")]
+    PriorityHintTooHigh { hint: u64, max_priority_hint: u64 },
+}
+
+/// The status of a proving job.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub(crate) enum JobStatus {
+    /// The job is waiting for a worker thread.
+    Queued,
+    /// The job is running.
+    Running,
+    /// The job finished.
+    Finished(Result<Proof, ProvingError>),
+}
+
+/// A statement to be proven, along with everything needed to prove it.
+#[derive(Debug, From, Serialize, Deserialize)]
+pub(crate) enum ProvingJob {
+    /// Proves that the global state holds a leaf.
+    StateInclusion(StateInclusion),
+    /// Proves the validators set by a switch block for the next era.
+    EraTransition(EraTransition),
+}
+
+impl ProvingJob {
+    /// Returns the ID of the job.
+    pub(crate) fn job_id(&self) -> JobId {
+        match self {
+            ProvingJob::StateInclusion(circuit) => circuit_job_id(circuit),
+            ProvingJob::EraTransition(circuit) => circuit_job_id(circuit),
+        }
+    }
+
+    /// Generates the proof.  This is called on a worker thread and may take a long time.
+    fn run(&self, prover: &Prover) -> Result<Proof, ProvingError> {
+        match self {
+            ProvingJob::StateInclusion(circuit) => prover.prove(circuit),
+            ProvingJob::EraTransition(circuit) => prover.prove(circuit),
+        }
+    }
+}
+
+impl Display for ProvingJob {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProvingJob::StateInclusion(circuit) => write!(
+                formatter,
+                "inclusion of leaf {} under state root hash {}",
+                circuit.statement().leaf_hash,
+                circuit.statement().state_root_hash
+            ),
+            ProvingJob::EraTransition(circuit) => write!(
+                formatter,
+                "validators set by the switch block of era {}",
+                circuit.statement().era_id
+            ),
+        }
+    }
+}
+
+/// Returns the ID of the job proving the statement of `circuit`.
+fn circuit_job_id<C: Circuit>(circuit: &C) -> JobId {
+    let mut bytes = circuit.id().into_bytes();
+    match circuit.statement().to_bytes() {
+        Ok(statement_bytes) => bytes.extend(statement_bytes),
+        Err(error) => warn!(%error, "failed to serialize statement"),
+    }
+    JobId::new(Digest::hash(bytes))
+}
+
+/// A queued or running proving job, as persisted in storage until it finishes.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PersistedJob {
+    pub(crate) job: Arc<ProvingJob>,
+    pub(crate) priority: Priority,
+}
+
+/// A queued or running proving job, as listed by the diagnostics port.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct JobInfo {
+    pub(crate) job_id: JobId,
+    pub(crate) description: String,
+    pub(crate) priority: Priority,
+    pub(crate) status: JobStatus,
+}
+
+/// The queued and running proving jobs: the running ones first, then the queued ones in the order
+/// they will be started.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ProvingJobs {
+    pub(crate) jobs: Vec<JobInfo>,
+}
+
+impl Display for ProvingJobs {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        if self.jobs.is_empty() {
+            return write!(formatter, "no proving jobs");
+        }
+        for (index, info) in self.jobs.iter().enumerate() {
+            if index > 0 {
+                writeln!(formatter)?;
+            }
+            write!(
+                formatter,
+                "{:x}: {} ({:?}, {:?} priority)",
+                info.job_id.digest(),
+                info.description,
+                info.status,
+                info.priority
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A job running on a worker thread.
+#[derive(Debug)]
+struct RunningJob {
+    job: Arc<ProvingJob>,
+    priority: Priority,
+    /// Whether the job was cancelled.  Proving cannot be interrupted, so a cancelled job keeps its
+    /// worker thread busy until it finishes, and its result is discarded.
+    cancelled: bool,
+}
+
+/// Proves circuits with the configured proof system.
+#[derive(Debug)]
+pub(crate) struct Prover {
+    backend: Backend,
+    key_store: KeyStore,
+}
+
+impl Prover {
+    fn new(proof_system: ProofSystem, keys_path: &Path, allow_insecure_keygen: bool) -> Self {
+        let backend = Backend::new(proof_system);
+        let key_store = KeyStore::new(keys_path, &backend, allow_insecure_keygen);
+        Prover { backend, key_store }
+    }
+
+    /// Proves the statement of `circuit`, generating the circuit's keys first if they are missing
+    /// and allowed to.
+    pub(crate) fn prove<C: Circuit>(&self, circuit: &C) -> Result<Proof, ProvingError> {
+        let mut rng = crate::new_rng();
+        let keys = self
+            .key_store
+            .get_or_generate(&self.backend, circuit, &mut rng)
+            .map_err(|error| ProvingError::Failed(error.to_string()))?;
+        let proof = self
+            .backend
+            .prove(&keys.proving_key, circuit, &mut rng)
+            .map_err(|error| ProvingError::Failed(error.to_string()))?;
+        // The backend does not check that the witness satisfies the circuit, in which case the
+        // proof is simply invalid.
+        let is_valid = self
+            .backend
+            .verify(&keys.verifying_key, circuit.statement(), &proof)
+            .map_err(|error| ProvingError::Failed(error.to_string()))?;
+        if !is_valid {
+            return Err(ProvingError::Failed(format!(
+                "witness does not satisfy circuit {}",
+                circuit.id()
+            )));
+        }
+        Ok(Proof::new(Bytes::from(proof)))
+    }
+}
+
+/// The zero-knowledge prover's event.
+#[derive(Debug, From, Serialize)]
+pub(crate) enum Event {
+    #[from]
+    Request(#[serde(skip_serializing)] ZkProverRequest),
+    /// A block was added to the linear chain.
+    BlockAdded(#[serde(skip_serializing)] Arc<Block>),
+    /// A job finished running.
+    JobFinished {
+        job_id: JobId,
+        result: Result<Proof, ProvingError>,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Request(request) => write!(formatter, "zk prover request: {}", request),
+            Event::BlockAdded(block) => write!(formatter, "block added: {}", block.hash()),
+            Event::JobFinished { job_id, result } => match result {
+                Ok(_) => write!(formatter, "{} finished", job_id),
+                Err(error) => write!(formatter, "{} failed: {}", job_id, error),
+            },
+        }
+    }
+}
+
+/// Zero-knowledge prover component.
+#[derive(DataSize)]
+pub(crate) struct ZkProver {
+    #[data_size(skip)]
+    thread_pool: ThreadPool,
+    #[data_size(skip)]
+    prover: Arc<Prover>,
+    worker_threads: usize,
+    #[data_size(skip)]
+    queue: JobQueue<Arc<ProvingJob>>,
+    #[data_size(skip)]
+    running: BTreeMap<JobId, RunningJob>,
+    finished: BTreeMap<JobId, Result<Proof, ProvingError>>,
+    /// The IDs of the finished jobs, oldest first.
+    finished_order: VecDeque<JobId>,
+    prove_era_transitions: bool,
+    validator_slots: u32,
+    max_priority_hint: u64,
+}
+
+impl ZkProver {
+    pub(crate) fn new(
+        config: WithDir<Config>,
+        validator_slots: u32,
+        proof_requests_config: ProofRequestsConfig,
+    ) -> Self {
+        let keys_path = config.with_dir(config.value().keys_path.clone());
+        let config = config.value();
+        let worker_threads = config.worker_threads.max(1);
+        ZkProver {
+            thread_pool: ThreadPool::new(COMPONENT_NAME, worker_threads),
+            prover: Arc::new(Prover::new(
+                config.proof_system,
+                &keys_path,
+                config.allow_insecure_keygen,
+            )),
+            worker_threads,
+            queue: JobQueue::new(config.max_queued_jobs),
+            running: BTreeMap::new(),
+            finished: BTreeMap::new(),
+            finished_order: VecDeque::new(),
+            prove_era_transitions: config.prove_era_transitions,
+            validator_slots,
+            max_priority_hint: proof_requests_config.max_priority_hint,
+        }
+    }
+
+    /// Queues the jobs which were persisted in storage when the node stopped, starting them from
+    /// scratch.
+    pub(crate) fn resume_jobs<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        persisted_jobs: Vec<PersistedJob>,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let mut effects = Effects::new();
+        if persisted_jobs.is_empty() {
+            return effects;
+        }
+        info!(
+            count = persisted_jobs.len(),
+            "resuming persisted proving jobs"
+        );
+        for PersistedJob { job, priority } in persisted_jobs {
+            let job_id = job.job_id();
+            let dropped_job_id = match self.queue.push(job_id, job, priority) {
+                Ok(None) => continue,
+                Ok(Some((evicted_job_id, _))) => evicted_job_id,
+                Err(_) => job_id,
+            };
+            warn!(job_id = %dropped_job_id, "proving queue full, dropping persisted job");
+            effects.extend(
+                effect_builder
+                    .delete_proving_job_from_storage(dropped_job_id)
+                    .ignore(),
+            );
+        }
+        effects.extend(self.start_jobs());
+        effects
+    }
+
+    fn job_status(&self, job_id: &JobId) -> Option<JobStatus> {
+        if self.queue.contains(job_id) {
+            Some(JobStatus::Queued)
+        } else if let Some(running_job) = self.running.get(job_id) {
+            (!running_job.cancelled).then_some(JobStatus::Running)
+        } else {
+            self.finished
+                .get(job_id)
+                .map(|result| JobStatus::Finished(result.clone()))
+        }
+    }
+
+    fn handle_submit_job<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        job: ProvingJob,
+        priority: Priority,
+    ) -> (Result<JobStatus, SubmitJobError>, Effects<Event>)
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        if let Priority::ThirdParty { hint } = priority {
+            if hint > self.max_priority_hint {
+                let error = SubmitJobError::PriorityHintTooHigh {
+                    hint,
+                    max_priority_hint: self.max_priority_hint,
+                };
+                return (Err(error), Effects::new());
+            }
+        }
+        let job_id = job.job_id();
+        if let Some(running_job) = self.running.get_mut(&job_id) {
+            if running_job.cancelled {
+                // The job is still running, so it only needs to be persisted again.
+                running_job.cancelled = false;
+                let effects = effect_builder
+                    .put_proving_job_to_storage(PersistedJob {
+                        job: Arc::clone(&running_job.job),
+                        priority: running_job.priority,
+                    })
+                    .ignore();
+                return (Ok(JobStatus::Running), effects);
+            }
+        }
+        match self.job_status(&job_id) {
+            // An evicted job is queued again when it is resubmitted.
+            None | Some(JobStatus::Finished(Err(ProvingError::Evicted))) => {}
+            Some(status) => return (Ok(status), Effects::new()),
+        }
+        let job = Arc::new(job);
+        let mut effects = Effects::new();
+        match self.queue.push(job_id, Arc::clone(&job), priority) {
+            Ok(None) => {}
+            Ok(Some((evicted_job_id, _))) => {
+                debug!(%evicted_job_id, %job_id, "evicted proving job from full queue");
+                self.record_finished(evicted_job_id, Err(ProvingError::Evicted));
+                effects.extend(
+                    effect_builder
+                        .delete_proving_job_from_storage(evicted_job_id)
+                        .ignore(),
+                );
+            }
+            Err(_) => {
+                debug!(%job_id, ?priority, "proving queue full, rejecting job");
+                return (Err(SubmitJobError::QueueFull), Effects::new());
+            }
+        }
+        debug!(%job_id, ?priority, queued = self.queue.len(), "queued proving job");
+        effects.extend(
+            effect_builder
+                .put_proving_job_to_storage(PersistedJob { job, priority })
+                .ignore(),
+        );
+        effects.extend(self.start_jobs());
+        let status = self.job_status(&job_id).unwrap_or(JobStatus::Queued);
+        (Ok(status), effects)
+    }
+
+    /// Submits a job proving the validators set by `block` for the next era, if it is a switch
+    /// block.
+    fn handle_block_added<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block: &Block,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        if !self.prove_era_transitions || !block.header().is_switch_block() {
+            return Effects::new();
+        }
+        let era_id = block.header().era_id();
+        let circuit = match EraTransition::new(block.header(), self.validator_slots) {
+            Ok(circuit) => circuit,
+            Err(error) => {
+                warn!(%era_id, %error, "cannot prove era transition");
+                return Effects::new();
+            }
+        };
+        let (result, effects) =
+            self.handle_submit_job(effect_builder, ProvingJob::from(circuit), Priority::Node);
+        if let Err(error) = result {
+            warn!(%era_id, %error, "cannot prove era transition");
+        }
+        effects
+    }
+
+    /// Returns the queued and running jobs.
+    fn list_jobs(&self) -> ProvingJobs {
+        let running = self
+            .running
+            .iter()
+            .filter(|(_, running_job)| !running_job.cancelled)
+            .map(|(job_id, running_job)| JobInfo {
+                job_id: *job_id,
+                description: running_job.job.to_string(),
+                priority: running_job.priority,
+                status: JobStatus::Running,
+            });
+        let queued = self.queue.iter().map(|(job_id, job, priority)| JobInfo {
+            job_id,
+            description: job.to_string(),
+            priority,
+            status: JobStatus::Queued,
+        });
+        ProvingJobs {
+            jobs: running.chain(queued).collect(),
+        }
+    }
+
+    /// Cancels a queued or running job, returning whether there was such a job.
+    fn handle_cancel_job<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        job_id: JobId,
+    ) -> (bool, Effects<Event>)
+    where
+        REv: From<StorageRequest> + Send,
+    {
+        let cancelled = if self.queue.remove(&job_id).is_some() {
+            true
+        } else if let Some(running_job) = self.running.get_mut(&job_id) {
+            !std::mem::replace(&mut running_job.cancelled, true)
+        } else {
+            false
+        };
+        if !cancelled {
+            return (false, Effects::new());
+        }
+        info!(%job_id, "cancelled proving job");
+        let effects = effect_builder
+            .delete_proving_job_from_storage(job_id)
+            .ignore();
+        (true, effects)
+    }
+
+    /// Keeps the result of a finished job for status queries, forgetting the oldest results.
+    fn record_finished(&mut self, job_id: JobId, result: Result<Proof, ProvingError>) {
+        if self.finished.insert(job_id, result).is_none() {
+            self.finished_order.push_back(job_id);
+        }
+        while self.finished_order.len() > MAX_FINISHED_JOBS {
+            if let Some(oldest) = self.finished_order.pop_front() {
+                self.finished.remove(&oldest);
+            }
+        }
+    }
+
+    /// Starts queued jobs while there are idle worker threads.
+    fn start_jobs(&mut self) -> Effects<Event> {
+        let mut effects = Effects::new();
+        while self.running.len() < self.worker_threads {
+            let (job_id, job, priority) = match self.queue.pop() {
+                Some(queued) => queued,
+                None => break,
+            };
+            self.running.insert(
+                job_id,
+                RunningJob {
+                    job: Arc::clone(&job),
+                    priority,
+                    cancelled: false,
+                },
+            );
+            let prover = Arc::clone(&self.prover);
+            let result_receiver = self.thread_pool.run(move || job.run(&prover));
+            effects.extend(
+                async move { result_receiver.await.unwrap_or(Err(ProvingError::Aborted)) }
+                    .event(move |result| Event::JobFinished { job_id, result }),
+            );
+        }
+        effects
+    }
+
+    fn handle_job_finished<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        job_id: JobId,
+        result: Result<Proof, ProvingError>,
+    ) -> Effects<Event>
+    where
+        REv: From<ZkProverAnnouncement> + From<StorageRequest> + Send,
+    {
+        let running_job = match self.running.remove(&job_id) {
+            Some(running_job) => running_job,
+            None => {
+                warn!(%job_id, "finished proving job was not running");
+                return self.start_jobs();
+            }
+        };
+        if running_job.cancelled {
+            debug!(%job_id, "cancelled proving job finished, discarding result");
+            return self.start_jobs();
+        }
+        if let Err(error) = &result {
+            warn!(%job_id, %error, "proving job failed");
+        }
+        self.record_finished(job_id, result.clone());
+
+        let mut effects = effect_builder
+            .delete_proving_job_from_storage(job_id)
+            .ignore();
+        if let (ProvingJob::EraTransition(circuit), Ok(proof)) = (&*running_job.job, &result) {
+            let proof =
+                circuit.era_transition_proof(self.prover.backend.proof_system(), proof.clone());
+            effects.extend(
+                effect_builder
+                    .put_era_transition_proof_to_storage(proof.clone())
+                    .ignore(),
+            );
+            effects.extend(
+                effect_builder
+                    .announce_era_transition_proven(proof)
+                    .ignore(),
+            );
+        }
+        effects.extend(
+            effect_builder
+                .announce_proving_job_finished(job_id, result)
+                .ignore(),
+        );
+        effects.extend(self.start_jobs());
+        effects
+    }
+}
+
+impl<REv> Component<REv> for ZkProver
+where
+    REv: From<ZkProverAnnouncement> + From<StorageRequest> + Send,
+{
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Request(ZkProverRequest::SubmitJob {
+                job,
+                priority,
+                responder,
+            }) => {
+                let (result, mut effects) = self.handle_submit_job(effect_builder, *job, priority);
+                effects.extend(responder.respond(result).ignore());
+                effects
+            }
+            Event::Request(ZkProverRequest::GetJobStatus { job_id, responder }) => {
+                responder.respond(self.job_status(&job_id)).ignore()
+            }
+            Event::Request(ZkProverRequest::ListJobs { responder }) => {
+                responder.respond(self.list_jobs()).ignore()
+            }
+            Event::Request(ZkProverRequest::CancelJob { job_id, responder }) => {
+                let (cancelled, mut effects) = self.handle_cancel_job(effect_builder, job_id);
+                effects.extend(responder.respond(cancelled).ignore());
+                effects
+            }
+            Event::BlockAdded(block) => self.handle_block_added(effect_builder, &block),
+            Event::JobFinished { job_id, result } => {
+                self.handle_job_finished(effect_builder, job_id, result)
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
@@ -0,0 +1,260 @@
+//! Proving backends, the proof systems circuits are proven with.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
+
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_groth16::{Groth16 as ArkGroth16, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate,
+};
+use ark_snark::SNARK;
+use datasize::DataSize;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::circuit::{Circuit, Statement, Synthesizer};
+
+/// A proof system.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, DataSize)]
+pub enum ProofSystem {
+    /// Groth16 over the BN254 curve, whose proofs can be verified cheaply on Ethereum.
+    #[serde(rename = "groth16-bn254")]
+    Groth16Bn254,
+    /// Groth16 over the BLS12-381 curve.
+    #[serde(rename = "groth16-bls12-381")]
+    Groth16Bls12_381,
+}
+
+impl Display for ProofSystem {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofSystem::Groth16Bn254 => formatter.write_str("groth16-bn254"),
+            ProofSystem::Groth16Bls12_381 => formatter.write_str("groth16-bls12-381"),
+        }
+    }
+}
+
+/// An error generating keys, or generating or verifying a proof.
+#[derive(Debug, Error)]
+pub(crate) enum BackendError {
+    /// The circuit could not be synthesized, e.g. because the witness doesn't satisfy it.
+    #[error("failed to synthesize circuit: {0}")]
+    Synthesis(#[from] SynthesisError),
+    /// A key or proof could not be serialized or deserialized.
+    #[error("malformed key or proof: {0}")]
+    Serialization(#[from] SerializationError),
+}
+
+/// The serialized proving and verifying key of a circuit.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Keys {
+    pub(crate) proving_key: Vec<u8>,
+    pub(crate) verifying_key: Vec<u8>,
+}
+
+/// A proof system which can prove any `Circuit`.
+///
+/// Keys and proofs are passed in serialized form, so that callers need not know which proof system
+/// they deal with.
+pub(crate) trait ProvingBackend {
+    /// Returns the proof system.
+    fn proof_system(&self) -> ProofSystem;
+
+    /// Generates the keys for circuits with the same ID as `circuit`.
+    ///
+    /// Whoever knows the randomness drawn from `rng` can forge proofs, so proofs checked with keys
+    /// generated locally only convince the party who generated them.
+    fn keygen<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Keys, BackendError>;
+
+    /// Proves the statement of `circuit`, using its witness.
+    fn prove<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        proving_key: &[u8],
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, BackendError>;
+
+    /// Verifies `proof` of `statement`.
+    fn verify<S: Statement>(
+        &self,
+        verifying_key: &[u8],
+        statement: &S,
+        proof: &[u8],
+    ) -> Result<bool, BackendError>;
+}
+
+/// The Groth16 proof system over the pairing-friendly curve `E`.
+#[derive(Debug)]
+pub(crate) struct Groth16<E> {
+    proof_system: ProofSystem,
+    _curve: PhantomData<E>,
+}
+
+impl<E: Pairing> Groth16<E> {
+    fn new(proof_system: ProofSystem) -> Self {
+        Groth16 {
+            proof_system,
+            _curve: PhantomData,
+        }
+    }
+}
+
+impl<E: Pairing> ProvingBackend for Groth16<E> {
+    fn proof_system(&self) -> ProofSystem {
+        self.proof_system
+    }
+
+    fn keygen<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Keys, BackendError> {
+        let (proving_key, verifying_key) =
+            ArkGroth16::<E>::circuit_specific_setup(Synthesizer(circuit), rng)?;
+        // Proving keys can be large, and are only ever read back by this node, so they are stored
+        // uncompressed for faster loading.
+        let mut proving_key_bytes = vec![];
+        proving_key.serialize_uncompressed(&mut proving_key_bytes)?;
+        let mut verifying_key_bytes = vec![];
+        verifying_key.serialize_compressed(&mut verifying_key_bytes)?;
+        Ok(Keys {
+            proving_key: proving_key_bytes,
+            verifying_key: verifying_key_bytes,
+        })
+    }
+
+    fn prove<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        proving_key: &[u8],
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, BackendError> {
+        let proving_key =
+            ProvingKey::<E>::deserialize_with_mode(proving_key, Compress::No, Validate::No)?;
+        let proof = ArkGroth16::<E>::prove(&proving_key, Synthesizer(circuit), rng)?;
+        let mut proof_bytes = vec![];
+        proof.serialize_compressed(&mut proof_bytes)?;
+        Ok(proof_bytes)
+    }
+
+    fn verify<S: Statement>(
+        &self,
+        verifying_key: &[u8],
+        statement: &S,
+        proof: &[u8],
+    ) -> Result<bool, BackendError> {
+        let verifying_key = VerifyingKey::<E>::deserialize_compressed(verifying_key)?;
+        let proof = ark_groth16::Proof::<E>::deserialize_compressed(proof)?;
+        let public_inputs = statement.public_inputs::<E::ScalarField>();
+        if public_inputs.len() + 1 != verifying_key.gamma_abc_g1.len() {
+            return Ok(false);
+        }
+        Ok(ArkGroth16::<E>::verify(
+            &verifying_key,
+            &public_inputs,
+            &proof,
+        )?)
+    }
+}
+
+/// The backend of the configured proof system.
+#[derive(Debug)]
+pub(crate) enum Backend {
+    Groth16Bn254(Groth16<Bn254>),
+    Groth16Bls12_381(Groth16<Bls12_381>),
+}
+
+impl Backend {
+    pub(crate) fn new(proof_system: ProofSystem) -> Self {
+        match proof_system {
+            ProofSystem::Groth16Bn254 => Backend::Groth16Bn254(Groth16::new(proof_system)),
+            ProofSystem::Groth16Bls12_381 => Backend::Groth16Bls12_381(Groth16::new(proof_system)),
+        }
+    }
+}
+
+impl ProvingBackend for Backend {
+    fn proof_system(&self) -> ProofSystem {
+        match self {
+            Backend::Groth16Bn254(backend) => backend.proof_system(),
+            Backend::Groth16Bls12_381(backend) => backend.proof_system(),
+        }
+    }
+
+    fn keygen<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Keys, BackendError> {
+        match self {
+            Backend::Groth16Bn254(backend) => backend.keygen(circuit, rng),
+            Backend::Groth16Bls12_381(backend) => backend.keygen(circuit, rng),
+        }
+    }
+
+    fn prove<C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        proving_key: &[u8],
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, BackendError> {
+        match self {
+            Backend::Groth16Bn254(backend) => backend.prove(proving_key, circuit, rng),
+            Backend::Groth16Bls12_381(backend) => backend.prove(proving_key, circuit, rng),
+        }
+    }
+
+    fn verify<S: Statement>(
+        &self,
+        verifying_key: &[u8],
+        statement: &S,
+        proof: &[u8],
+    ) -> Result<bool, BackendError> {
+        match self {
+            Backend::Groth16Bn254(backend) => backend.verify(verifying_key, statement, proof),
+            Backend::Groth16Bls12_381(backend) => backend.verify(verifying_key, statement, proof),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::circuit::tests::*, *};
+
+    #[test]
+    fn should_prove_and_verify_with_every_proof_system() {
+        let mut rng = crate::new_rng();
+        let circuit = SquareRoot {
+            square: Square(49),
+            root: 7,
+        };
+        for proof_system in [ProofSystem::Groth16Bn254, ProofSystem::Groth16Bls12_381] {
+            let backend = Backend::new(proof_system);
+            assert_eq!(backend.proof_system(), proof_system);
+            let keys = backend.keygen(&circuit, &mut rng).unwrap();
+            let proof = backend
+                .prove(&keys.proving_key, &circuit, &mut rng)
+                .unwrap();
+            assert!(backend
+                .verify(&keys.verifying_key, &Square(49), &proof)
+                .unwrap());
+            assert!(!backend
+                .verify(&keys.verifying_key, &Square(36), &proof)
+                .unwrap());
+            assert!(backend
+                .verify(&keys.verifying_key, &Square(49), &proof[1..])
+                .is_err());
+        }
+    }
+}
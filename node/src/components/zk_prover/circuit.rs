@@ -0,0 +1,114 @@
+//! Circuits, which define the statements the prover can prove.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use casper_types::bytesrepr::ToBytes;
+
+/// The public part of what a circuit proves, which a verifier needs to check a proof.
+pub(crate) trait Statement: ToBytes {
+    /// Returns the statement encoded as field elements, the public inputs of the circuit.
+    ///
+    /// The encoding must be injective, and must not depend on the field beyond its size, which is
+    /// at least 253 bits for every supported proof system.
+    fn public_inputs<F: PrimeField>(&self) -> Vec<F>;
+}
+
+/// A circuit, together with the witness proving its statement.
+///
+/// Circuits are written once against R1CS constraint systems over any prime field, so that they can
+/// be proven by any `ProvingBackend`.
+pub(crate) trait Circuit: Send + Sync {
+    /// The type of the statement.
+    type Statement: Statement;
+
+    /// Returns an identifier of the circuit and its shape, e.g. `state-inclusion-v1-d8`.
+    ///
+    /// Circuits with the same identifier must generate the same constraints for any witness, as
+    /// they share their proving and verifying keys.
+    fn id(&self) -> String;
+
+    /// Returns the statement.
+    fn statement(&self) -> &Self::Statement;
+
+    /// Generates the constraints and assigns the witness.
+    ///
+    /// `public_inputs` holds the statement, allocated as public inputs in the order returned by
+    /// `Statement::public_inputs`.
+    fn generate_constraints<F: PrimeField>(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        public_inputs: &[FpVar<F>],
+    ) -> Result<(), SynthesisError>;
+}
+
+/// Adapts a `Circuit` to the constraint synthesizer expected by arkworks' proof systems.
+pub(super) struct Synthesizer<'a, C>(pub(super) &'a C);
+
+impl<'a, C: Circuit, F: PrimeField> ConstraintSynthesizer<F> for Synthesizer<'a, C> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let public_inputs = self
+            .0
+            .statement()
+            .public_inputs::<F>()
+            .into_iter()
+            .map(|input| FpVar::new_input(cs.clone(), || Ok(input)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.0.generate_constraints(cs, &public_inputs)
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use ark_r1cs_std::eq::EqGadget;
+
+    use casper_types::bytesrepr;
+
+    use super::*;
+
+    /// The statement "I know a square root of `square`".
+    pub(crate) struct Square(pub(crate) u64);
+
+    impl ToBytes for Square {
+        fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+            self.0.to_bytes()
+        }
+
+        fn serialized_length(&self) -> usize {
+            self.0.serialized_length()
+        }
+    }
+
+    impl Statement for Square {
+        fn public_inputs<F: PrimeField>(&self) -> Vec<F> {
+            vec![F::from(self.0)]
+        }
+    }
+
+    pub(crate) struct SquareRoot {
+        pub(crate) square: Square,
+        pub(crate) root: u64,
+    }
+
+    impl Circuit for SquareRoot {
+        type Statement = Square;
+
+        fn id(&self) -> String {
+            "square-root".to_string()
+        }
+
+        fn statement(&self) -> &Square {
+            &self.square
+        }
+
+        fn generate_constraints<F: PrimeField>(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            public_inputs: &[FpVar<F>],
+        ) -> Result<(), SynthesisError> {
+            let root = FpVar::new_witness(cs, || Ok(F::from(self.root)))?;
+            (&root * &root).enforce_equal(&public_inputs[0])
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use super::ProofSystem;
+
+const DEFAULT_PROOF_SYSTEM: ProofSystem = ProofSystem::Groth16Bn254;
+const DEFAULT_KEYS_PATH: &str = "zk_keys";
+const DEFAULT_ALLOW_INSECURE_KEYGEN: bool = false;
+const DEFAULT_WORKER_THREADS: usize = 1;
+const DEFAULT_MAX_QUEUED_JOBS: usize = 64;
+const DEFAULT_PROVE_ERA_TRANSITIONS: bool = false;
+const DEFAULT_RPC_ENABLED: bool = false;
+
+/// Configuration options for generating zero-knowledge proofs.
+#[derive(Clone, Serialize, Deserialize, Debug, DataSize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The proof system to generate proofs with.
+    pub proof_system: ProofSystem,
+    /// The directory holding the proving and verifying keys of the circuits.
+    ///
+    /// If relative, it is relative to the directory of the config file.
+    pub keys_path: PathBuf,
+    /// Whether to generate the keys of circuits missing from `keys_path`, rather than failing to
+    /// prove them.
+    ///
+    /// The node can forge proofs checked against keys it generated itself, so this is only meant
+    /// for testing.
+    pub allow_insecure_keygen: bool,
+    /// The number of threads dedicated to proving, each running one job at a time.
+    pub worker_threads: usize,
+    /// The maximum number of jobs waiting for a thread.
+    ///
+    /// Once full, a further job evicts the queued job of the lowest priority if that is lower than
+    /// its own, or is rejected otherwise.
+    pub max_queued_jobs: usize,
+    /// Whether to prove the validators set by each switch block for the next era.
+    pub prove_era_transitions: bool,
+    /// Whether clients can request proofs via the `state_get_item_zk` RPC.
+    pub rpc_enabled: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            proof_system: DEFAULT_PROOF_SYSTEM,
+            keys_path: PathBuf::from(DEFAULT_KEYS_PATH),
+            allow_insecure_keygen: DEFAULT_ALLOW_INSECURE_KEYGEN,
+            worker_threads: DEFAULT_WORKER_THREADS,
+            max_queued_jobs: DEFAULT_MAX_QUEUED_JOBS,
+            prove_era_transitions: DEFAULT_PROVE_ERA_TRANSITIONS,
+            rpc_enabled: DEFAULT_RPC_ENABLED,
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! The circuit proving which validators a switch block sets for the next era.
+//!
+//! The witness is the serialized header of the switch block ending an era.  The circuit hashes it
+//! with BLAKE2b to the switch block's hash, and hashes the era end it holds, which lists the
+//! validators of the next era along with their weights, to the proven era end hash.  Knowing the
+//! hash of a switch block, a verifier can thus take the next era's validators from any era end
+//! matching the proven hash, without the rest of the header.
+//!
+//! The fields before and after the era end have a fixed length, so the era end is found at a fixed
+//! distance from either end of the header, without parsing it.
+//!
+//! The statement does not cover the finality signatures of the switch block by the validators of
+//! the era it ends: neither Ed25519 nor secp256k1 is defined over the field of a supported proof
+//! system, so verifying a single signature would take millions of constraints.  Verifiers need to
+//! establish that the switch block is finalized by other means.
+//!
+//! Circuits have a fixed size, so the header is padded up to the length of an era end listing as
+//! many validators as there are validator slots.  Proving costs about 170 thousand constraints per
+//! validator slot, i.e. 17 million on a network with 100 slots.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{
+        self, FromBytes, ToBytes, BOOL_SERIALIZED_LENGTH, OPTION_SOME_TAG, U32_SERIALIZED_LENGTH,
+        U512_SERIALIZED_LENGTH, U64_SERIALIZED_LENGTH, U8_SERIALIZED_LENGTH,
+    },
+    EraId, PublicKey, SEM_VER_SERIALIZED_LENGTH,
+};
+
+use super::{
+    circuit::{Circuit, Statement},
+    gadgets::{
+        blake2b::blake2b_256,
+        bytes::{byte_to_fp, VarBytesVar},
+        digest::{digest_to_field_elements, DigestVar, DIGEST_LENGTH},
+    },
+    Proof, ProofSystem,
+};
+use crate::types::{BlockHash, BlockHeader};
+
+/// The index of the era end in a serialized switch block header, after the parent hash, state
+/// root hash, body hash, random bit, accumulated seed and the option tag of the era end.
+const ERA_END_START: usize =
+    3 * DIGEST_LENGTH + BOOL_SERIALIZED_LENGTH + DIGEST_LENGTH + U8_SERIALIZED_LENGTH;
+/// The length of the fields of a serialized block header after the era end: the timestamp, era
+/// ID, height and protocol version.
+const TAIL_LENGTH: usize = 3 * U64_SERIALIZED_LENGTH + SEM_VER_SERIALIZED_LENGTH;
+/// The index of the era ID relative to the end of the era end, after the timestamp.
+const ERA_ID_OFFSET: usize = U64_SERIALIZED_LENGTH;
+
+/// The maximum length of a serialized public key: its tag followed by a secp256k1 key.
+const MAX_PUBLIC_KEY_LENGTH: usize = U8_SERIALIZED_LENGTH + PublicKey::SECP256K1_LENGTH;
+/// The maximum length of a serialized `U512`: its length followed by its bytes.
+const MAX_U512_LENGTH: usize = U8_SERIALIZED_LENGTH + U512_SERIALIZED_LENGTH;
+
+/// Returns the maximum length of a serialized era end if neither the era it ends nor the next one
+/// has more validators than `validator_slots`.
+fn max_era_end_length(validator_slots: u32) -> usize {
+    // The equivocators, rewards and inactive validators of the era report, and the next era's
+    // validator weights, each prefixed by their number.
+    let max_validator_length = MAX_PUBLIC_KEY_LENGTH
+        + (MAX_PUBLIC_KEY_LENGTH + U64_SERIALIZED_LENGTH)
+        + MAX_PUBLIC_KEY_LENGTH
+        + (MAX_PUBLIC_KEY_LENGTH + MAX_U512_LENGTH);
+    4 * U32_SERIALIZED_LENGTH + validator_slots as usize * max_validator_length
+}
+
+/// An error creating an `EraTransition` circuit.
+#[derive(Debug, Error)]
+pub(crate) enum EraTransitionError {
+    /// The block is not a switch block.
+    #[error("{0} is not a switch block")]
+    NotSwitchBlock(BlockHash),
+    /// The era end is longer than the circuit supports.
+    #[error("era end of {length} bytes is longer than the supported {max_length}")]
+    TooLong { length: usize, max_length: usize },
+    /// The block header could not be serialized.
+    #[error("failed to serialize block header: {0}")]
+    Serialization(bytesrepr::Error),
+}
+
+impl From<bytesrepr::Error> for EraTransitionError {
+    fn from(error: bytesrepr::Error) -> Self {
+        EraTransitionError::Serialization(error)
+    }
+}
+
+/// The statement that the switch block with the given hash ends the given era, with the era end
+/// with the given hash.
+///
+/// The era end is serialized as `EraEnd`, and lists the validators of the next era.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(crate) struct EraTransitionStatement {
+    pub(crate) switch_block_hash: Digest,
+    pub(crate) era_id: EraId,
+    pub(crate) era_end_hash: Digest,
+}
+
+impl ToBytes for EraTransitionStatement {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = self.switch_block_hash.to_bytes()?;
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.era_end_hash.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.switch_block_hash.serialized_length()
+            + self.era_id.serialized_length()
+            + self.era_end_hash.serialized_length()
+    }
+}
+
+impl Statement for EraTransitionStatement {
+    fn public_inputs<F: PrimeField>(&self) -> Vec<F> {
+        let mut inputs = digest_to_field_elements::<F>(&self.switch_block_hash.value()).to_vec();
+        inputs.push(F::from(self.era_id.value()));
+        inputs.extend(digest_to_field_elements::<F>(&self.era_end_hash.value()));
+        inputs
+    }
+}
+
+/// A proof of an `EraTransitionStatement`, as stored for each switch block.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, DataSize)]
+pub(crate) struct EraTransitionProof {
+    /// The hash of the switch block.
+    pub(crate) switch_block_hash: Digest,
+    /// The era ended by the switch block.
+    pub(crate) era_id: EraId,
+    /// The hash of the switch block's serialized era end.
+    pub(crate) era_end_hash: Digest,
+    /// The proof system the proof was generated with.
+    pub(crate) proof_system: ProofSystem,
+    /// The ID of the circuit, whose verifying key checks the proof.
+    pub(crate) circuit_id: String,
+    pub(crate) proof: Proof,
+}
+
+/// The circuit proving an `EraTransitionStatement`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct EraTransition {
+    statement: EraTransitionStatement,
+    header_bytes: Vec<u8>,
+    max_era_end_length: usize,
+}
+
+impl EraTransition {
+    /// Creates the circuit proving the era end of the switch block with the given header, on a
+    /// network with `validator_slots` validator slots.
+    pub(crate) fn new(
+        header: &BlockHeader,
+        validator_slots: u32,
+    ) -> Result<Self, EraTransitionError> {
+        if !header.is_switch_block() {
+            return Err(EraTransitionError::NotSwitchBlock(header.block_hash()));
+        }
+        Self::from_header_bytes(header.to_bytes()?, max_era_end_length(validator_slots))
+    }
+
+    /// Creates the circuit from the serialized header of a switch block.
+    fn from_header_bytes(
+        header_bytes: Vec<u8>,
+        max_era_end_length: usize,
+    ) -> Result<Self, EraTransitionError> {
+        let era_end_end = header_bytes.len() - TAIL_LENGTH;
+        let era_end = &header_bytes[ERA_END_START..era_end_end];
+        if era_end.len() > max_era_end_length {
+            return Err(EraTransitionError::TooLong {
+                length: era_end.len(),
+                max_length: max_era_end_length,
+            });
+        }
+        let (era_id, _) = EraId::from_bytes(&header_bytes[era_end_end + ERA_ID_OFFSET..])?;
+        let statement = EraTransitionStatement {
+            switch_block_hash: Digest::hash(&header_bytes),
+            era_id,
+            era_end_hash: Digest::hash(era_end),
+        };
+        Ok(EraTransition {
+            statement,
+            header_bytes,
+            max_era_end_length,
+        })
+    }
+
+    /// Returns the proof of the statement, given the proof generated for it.
+    pub(crate) fn era_transition_proof(
+        &self,
+        proof_system: ProofSystem,
+        proof: Proof,
+    ) -> EraTransitionProof {
+        EraTransitionProof {
+            switch_block_hash: self.statement.switch_block_hash,
+            era_id: self.statement.era_id,
+            era_end_hash: self.statement.era_end_hash,
+            proof_system,
+            circuit_id: self.id(),
+            proof,
+        }
+    }
+}
+
+impl Circuit for EraTransition {
+    type Statement = EraTransitionStatement;
+
+    fn id(&self) -> String {
+        format!("era-transition-v1-l{}", self.max_era_end_length)
+    }
+
+    fn statement(&self) -> &EraTransitionStatement {
+        &self.statement
+    }
+
+    fn generate_constraints<F: PrimeField>(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        public_inputs: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let switch_block_hash =
+            DigestVar::from_halves([public_inputs[0].clone(), public_inputs[1].clone()]);
+        let era_id = &public_inputs[2];
+        let era_end_hash =
+            DigestVar::from_halves([public_inputs[3].clone(), public_inputs[4].clone()]);
+
+        let header = VarBytesVar::new_witness(
+            cs.clone(),
+            &self.header_bytes,
+            ERA_END_START + self.max_era_end_length + TAIL_LENGTH,
+        )?;
+        blake2b_256(&header)?.enforce_equal(&switch_block_hash)?;
+        // The header holds an era end.
+        byte_to_fp(&header.bytes[ERA_END_START - 1])?
+            .enforce_equal(&FpVar::constant(F::from(OPTION_SOME_TAG)))?;
+
+        // The era end is what is left of the header between the fields of fixed length.
+        let era_end = VarBytesVar::new_witness(
+            cs,
+            self.header_bytes
+                .get(ERA_END_START..self.header_bytes.len().saturating_sub(TAIL_LENGTH))
+                .unwrap_or_default(),
+            self.max_era_end_length,
+        )?;
+        (&era_end.len + F::from((ERA_END_START + TAIL_LENGTH) as u64))
+            .enforce_equal(&header.len)?;
+        for (index, byte) in era_end.bytes.iter().enumerate() {
+            (byte_to_fp(byte)? - byte_to_fp(&header.bytes[ERA_END_START + index])?).mul_equals(
+                &FpVar::from(era_end.in_range[index].clone()),
+                &FpVar::zero(),
+            )?;
+        }
+        blake2b_256(&era_end)?.enforce_equal(&era_end_hash)?;
+
+        // The era ID follows the timestamp after the era end.
+        let mut selected_era_ids = vec![];
+        for length in 0..=self.max_era_end_length {
+            let start = ERA_END_START + length + ERA_ID_OFFSET;
+            let value = Boolean::le_bits_to_fp_var(
+                &header.bytes[start..start + U64_SERIALIZED_LENGTH].to_bits_le()?,
+            )?;
+            selected_era_ids.push(era_end.is_end(length) * value);
+        }
+        selected_era_ids
+            .iter()
+            .sum::<FpVar<F>>()
+            .enforce_equal(era_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    use super::{super::circuit::Synthesizer, *};
+
+    const ERA_ID: u64 = 5;
+
+    /// Returns a serialized switch block header with the given era end.
+    fn header_bytes(era_end: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![1; ERA_END_START - 1];
+        bytes.push(OPTION_SOME_TAG);
+        bytes.extend(era_end);
+        bytes.extend([2; U64_SERIALIZED_LENGTH]);
+        bytes.extend(ERA_ID.to_le_bytes());
+        bytes.extend([3; U64_SERIALIZED_LENGTH + SEM_VER_SERIALIZED_LENGTH]);
+        bytes
+    }
+
+    fn is_satisfied(circuit: &EraTransition) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        Synthesizer(circuit)
+            .generate_constraints(cs.clone())
+            .unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn should_prove_era_end_of_switch_block() {
+        let era_end = [4; 100];
+        let circuit = EraTransition::from_header_bytes(header_bytes(&era_end), 200).unwrap();
+        assert_eq!(circuit.statement.era_id, EraId::new(ERA_ID));
+        assert_eq!(circuit.statement.era_end_hash, Digest::hash(era_end));
+        assert!(is_satisfied(&circuit));
+
+        // An era end filling the circuit.
+        let circuit = EraTransition::from_header_bytes(header_bytes(&[4; 200]), 200).unwrap();
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn should_not_prove_other_statements() {
+        let circuit = || EraTransition::from_header_bytes(header_bytes(&[4; 100]), 200).unwrap();
+
+        let mut wrong_block = circuit();
+        wrong_block.statement.switch_block_hash = Digest::hash([1]);
+        assert!(!is_satisfied(&wrong_block));
+
+        let mut wrong_era = circuit();
+        wrong_era.statement.era_id = EraId::new(ERA_ID + 1);
+        assert!(!is_satisfied(&wrong_era));
+
+        let mut wrong_era_end = circuit();
+        wrong_era_end.statement.era_end_hash = Digest::hash([4; 99]);
+        assert!(!is_satisfied(&wrong_era_end));
+
+        // A header without an era end.
+        let mut not_switch_block = circuit();
+        not_switch_block.header_bytes[ERA_END_START - 1] = 0;
+        not_switch_block.statement.switch_block_hash = Digest::hash(&not_switch_block.header_bytes);
+        assert!(!is_satisfied(&not_switch_block));
+    }
+
+    #[test]
+    fn should_reject_era_ends_longer_than_supported() {
+        assert!(matches!(
+            EraTransition::from_header_bytes(header_bytes(&[4; 201]), 200),
+            Err(EraTransitionError::TooLong {
+                length: 201,
+                max_length: 200
+            })
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+//! Constraint gadgets shared by the circuits.
+
+pub(crate) mod blake2b;
+pub(crate) mod bytes;
+pub(crate) mod digest;
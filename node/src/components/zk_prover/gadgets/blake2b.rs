@@ -0,0 +1,205 @@
+//! BLAKE2b-256, the hash function identifying blocks and global state trie nodes.
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*, uint64::UInt64, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+
+use super::{bytes::VarBytesVar, digest::DigestVar};
+
+const BLOCK_BYTES: usize = 128;
+const ROUNDS: usize = 12;
+const COUNTER_BITS: usize = 32;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Returns the number of blocks compressed to hash a message of up to `capacity` bytes.
+fn block_count(capacity: usize) -> usize {
+    ((capacity + BLOCK_BYTES - 1) / BLOCK_BYTES).max(1)
+}
+
+/// Computes the 32-byte BLAKE2b hash of `message`.
+///
+/// All blocks up to the capacity of `message` are compressed, and the chaining value after the
+/// block holding the last byte is selected as the hash, so the cost only depends on the capacity.
+/// `message` must not be empty.
+pub(crate) fn blake2b_256<F: PrimeField>(
+    message: &VarBytesVar<F>,
+) -> Result<DigestVar<F>, SynthesisError> {
+    let cs = message.len.cs();
+    // An empty message is hashed as a single block, which isn't supported here.
+    message.is_in_range(0).enforce_equal(&Boolean::TRUE)?;
+
+    let mut state: Vec<UInt64<F>> = IV.iter().map(|word| UInt64::constant(*word)).collect();
+    // Parameter block: 32-byte digest, no key, fanout and depth 1.
+    state[0] = UInt64::constant(IV[0] ^ 0x0101_0000 ^ 32);
+
+    let mut hash = [FpVar::zero(), FpVar::zero()];
+    for block_index in 0..block_count(message.capacity()) {
+        let start = block_index * BLOCK_BYTES;
+        let end = start + BLOCK_BYTES;
+
+        // The block is the last one if the message ends within it.
+        let is_last = message
+            .is_in_range(start)
+            .and(&message.is_in_range(end).not())?;
+
+        // The number of bytes hashed so far: the whole message if this is the last block.
+        let full_counter = F::from(end as u64);
+        let counter_value =
+            FpVar::from(is_last.clone()) * (&message.len - full_counter) + full_counter;
+        let counter_bits = counter_value
+            .value()
+            .map(|value| value.into_bigint().to_bits_le())
+            .unwrap_or_default();
+        let counter_bits = (0..COUNTER_BITS)
+            .map(|bit| {
+                Boolean::new_witness(cs.clone(), || {
+                    Ok(counter_bits.get(bit).copied().unwrap_or_default())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Boolean::le_bits_to_fp_var(&counter_bits)?.enforce_equal(&counter_value)?;
+        let mut counter_word = counter_bits;
+        counter_word.resize(64, Boolean::FALSE);
+
+        let block_bytes: Vec<UInt8<F>> = (start..end)
+            .map(|index| {
+                message
+                    .bytes
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| UInt8::constant(0))
+            })
+            .collect();
+        let words: Vec<UInt64<F>> = block_bytes
+            .chunks(8)
+            .map(|word_bytes| {
+                let bits = word_bytes
+                    .iter()
+                    .map(|byte| byte.to_bits_le())
+                    .collect::<Result<Vec<_>, _>>()?
+                    .concat();
+                Ok(UInt64::from_bits_le(&bits))
+            })
+            .collect::<Result<_, SynthesisError>>()?;
+
+        state = compress(
+            &state,
+            &words,
+            &UInt64::from_bits_le(&counter_word),
+            &is_last,
+        )?;
+
+        for (half, words) in hash.iter_mut().zip(state[..4].chunks(2)) {
+            let bits = [words[0].to_bits_le(), words[1].to_bits_le()].concat();
+            *half += FpVar::from(is_last.clone()) * Boolean::le_bits_to_fp_var(&bits)?;
+        }
+    }
+
+    Ok(DigestVar::from_halves(hash))
+}
+
+/// The BLAKE2b compression function.
+fn compress<F: PrimeField>(
+    state: &[UInt64<F>],
+    message: &[UInt64<F>],
+    counter: &UInt64<F>,
+    is_last: &Boolean<F>,
+) -> Result<Vec<UInt64<F>>, SynthesisError> {
+    let mut v: Vec<UInt64<F>> = state
+        .iter()
+        .cloned()
+        .chain(IV.iter().map(|word| UInt64::constant(*word)))
+        .collect();
+    v[12] = v[12].xor(counter)?;
+    v[14] = v[14].xor(&UInt64::from_bits_le(&vec![is_last.clone(); 64]))?;
+
+    for round in 0..ROUNDS {
+        let s = &SIGMA[round % 10];
+        mix(&mut v, [0, 4, 8, 12], &message[s[0]], &message[s[1]])?;
+        mix(&mut v, [1, 5, 9, 13], &message[s[2]], &message[s[3]])?;
+        mix(&mut v, [2, 6, 10, 14], &message[s[4]], &message[s[5]])?;
+        mix(&mut v, [3, 7, 11, 15], &message[s[6]], &message[s[7]])?;
+        mix(&mut v, [0, 5, 10, 15], &message[s[8]], &message[s[9]])?;
+        mix(&mut v, [1, 6, 11, 12], &message[s[10]], &message[s[11]])?;
+        mix(&mut v, [2, 7, 8, 13], &message[s[12]], &message[s[13]])?;
+        mix(&mut v, [3, 4, 9, 14], &message[s[14]], &message[s[15]])?;
+    }
+
+    state
+        .iter()
+        .enumerate()
+        .map(|(index, word)| word.xor(&v[index])?.xor(&v[index + 8]))
+        .collect()
+}
+
+/// The BLAKE2b mixing function G.
+fn mix<F: PrimeField>(
+    v: &mut [UInt64<F>],
+    [a, b, c, d]: [usize; 4],
+    x: &UInt64<F>,
+    y: &UInt64<F>,
+) -> Result<(), SynthesisError> {
+    v[a] = UInt64::addmany(&[v[a].clone(), v[b].clone(), x.clone()])?;
+    v[d] = v[d].xor(&v[a])?.rotr(32);
+    v[c] = UInt64::addmany(&[v[c].clone(), v[d].clone()])?;
+    v[b] = v[b].xor(&v[c])?.rotr(24);
+    v[a] = UInt64::addmany(&[v[a].clone(), v[b].clone(), y.clone()])?;
+    v[d] = v[d].xor(&v[a])?.rotr(16);
+    v[c] = UInt64::addmany(&[v[c].clone(), v[d].clone()])?;
+    v[b] = v[b].xor(&v[c])?.rotr(63);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use casper_hashing::Digest;
+
+    use super::{super::digest::digest_to_field_elements, *};
+
+    #[test]
+    fn should_match_native_blake2b() {
+        // Lengths around the block boundaries, with messages shorter than the capacity.
+        for (length, capacity) in [(1, 128), (127, 256), (128, 256), (129, 256), (256, 256)] {
+            let message: Vec<u8> = (0..length).map(|index| (index * 7 + 3) as u8).collect();
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let message_var = VarBytesVar::new_witness(cs.clone(), &message, capacity).unwrap();
+            let hash = blake2b_256(&message_var).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            let expected = digest_to_field_elements::<Fr>(&Digest::hash(&message).value());
+            let halves = hash.halves();
+            assert_eq!(
+                [halves[0].value().unwrap(), halves[1].value().unwrap()],
+                expected,
+                "hash of {} bytes",
+                length
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Byte strings of variable length.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*, uint8::UInt8};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+/// A byte string of variable length up to a fixed capacity, allocated as a witness.
+///
+/// The bytes beyond the length are constrained to be zero.
+pub(crate) struct VarBytesVar<F: PrimeField> {
+    /// The bytes, padded with zeros up to the capacity.
+    pub(crate) bytes: Vec<UInt8<F>>,
+    /// `in_range[i]` is set if and only if `i` is less than the length.
+    pub(crate) in_range: Vec<Boolean<F>>,
+    /// The length.
+    pub(crate) len: FpVar<F>,
+}
+
+impl<F: PrimeField> VarBytesVar<F> {
+    /// Allocates `bytes` as a witness of a byte string of at most `capacity` bytes.
+    pub(crate) fn new_witness(
+        cs: ConstraintSystemRef<F>,
+        bytes: &[u8],
+        capacity: usize,
+    ) -> Result<Self, SynthesisError> {
+        if bytes.len() > capacity {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut padded = bytes.to_vec();
+        padded.resize(capacity, 0);
+        let byte_vars = UInt8::new_witness_vec(cs.clone(), &padded)?;
+        let in_range = (0..capacity)
+            .map(|index| Boolean::new_witness(cs.clone(), || Ok(index < bytes.len())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The flags are a run of ones followed by zeros, and all bytes out of range are zero.
+        let mut previous = Boolean::TRUE;
+        for (byte, flag) in byte_vars.iter().zip(&in_range) {
+            FpVar::from(flag.clone()).mul_equals(&FpVar::from(previous.not()), &FpVar::zero())?;
+            byte_to_fp(byte)?.mul_equals(&FpVar::from(flag.not()), &FpVar::zero())?;
+            previous = flag.clone();
+        }
+        let len = in_range
+            .iter()
+            .map(|flag| FpVar::from(flag.clone()))
+            .collect::<Vec<_>>()
+            .iter()
+            .sum();
+
+        Ok(VarBytesVar {
+            bytes: byte_vars,
+            in_range,
+            len,
+        })
+    }
+
+    /// Returns the capacity.
+    pub(crate) fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether `index` is less than the length, which is never the case beyond the
+    /// capacity.
+    pub(crate) fn is_in_range(&self, index: usize) -> Boolean<F> {
+        self.in_range.get(index).cloned().unwrap_or(Boolean::FALSE)
+    }
+
+    /// Returns one if the length is `index`, zero otherwise.
+    pub(crate) fn is_end(&self, index: usize) -> FpVar<F> {
+        let before = match index.checked_sub(1) {
+            Some(previous) => FpVar::from(self.is_in_range(previous)),
+            None => FpVar::one(),
+        };
+        before - FpVar::from(self.is_in_range(index))
+    }
+}
+
+/// Returns the value of `byte` as a field element.
+pub(crate) fn byte_to_fp<F: PrimeField>(byte: &UInt8<F>) -> Result<FpVar<F>, SynthesisError> {
+    Boolean::le_bits_to_fp_var(&byte.to_bits_le()?)
+}
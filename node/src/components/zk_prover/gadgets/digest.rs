@@ -0,0 +1,55 @@
+//! 32-byte digests.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::SynthesisError;
+
+/// The number of bytes of a digest.
+pub(crate) const DIGEST_LENGTH: usize = 32;
+const HALF_LENGTH: usize = DIGEST_LENGTH / 2;
+
+/// Returns the two field elements representing `digest`: its first and second half, each read as
+/// a little-endian integer.
+pub(crate) fn digest_to_field_elements<F: PrimeField>(digest: &[u8; DIGEST_LENGTH]) -> [F; 2] {
+    [
+        F::from_le_bytes_mod_order(&digest[..HALF_LENGTH]),
+        F::from_le_bytes_mod_order(&digest[HALF_LENGTH..]),
+    ]
+}
+
+/// A 32-byte digest, represented by two field elements as by `digest_to_field_elements`.
+#[derive(Clone)]
+pub(crate) struct DigestVar<F: PrimeField> {
+    halves: [FpVar<F>; 2],
+}
+
+impl<F: PrimeField> DigestVar<F> {
+    /// Creates the digest represented by the two field elements.
+    pub(crate) fn from_halves(halves: [FpVar<F>; 2]) -> Self {
+        DigestVar { halves }
+    }
+
+    /// Returns the two field elements representing the digest.
+    pub(crate) fn halves(&self) -> &[FpVar<F>; 2] {
+        &self.halves
+    }
+
+    /// Enforces that `self` and `other` are the same digest.
+    pub(crate) fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.halves.enforce_equal(&other.halves)
+    }
+
+    /// Returns `if_true` if `condition` holds, `if_false` otherwise.
+    pub(crate) fn select(
+        condition: &Boolean<F>,
+        if_true: &Self,
+        if_false: &Self,
+    ) -> Result<Self, SynthesisError> {
+        Ok(DigestVar {
+            halves: [
+                condition.select(&if_true.halves[0], &if_false.halves[0])?,
+                condition.select(&if_true.halves[1], &if_false.halves[1])?,
+            ],
+        })
+    }
+}
@@ -0,0 +1,186 @@
+//! A bounded queue of proving jobs, ordered by priority.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use super::{JobId, Priority};
+
+/// The position of a job in the queue: jobs of higher priority come first, and jobs of equal
+/// priority in the order they were queued.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Position {
+    priority: Reverse<Priority>,
+    sequence_number: u64,
+}
+
+impl Position {
+    fn new(priority: Priority, sequence_number: u64) -> Self {
+        Position {
+            priority: Reverse(priority),
+            sequence_number,
+        }
+    }
+}
+
+/// A queued job.
+#[derive(Debug)]
+struct QueuedJob<J> {
+    id: JobId,
+    job: J,
+    priority: Priority,
+}
+
+/// A queue holding at most a fixed number of jobs, from which the job with the highest priority
+/// is taken first.
+#[derive(Debug)]
+pub(super) struct JobQueue<J> {
+    jobs: BTreeMap<Position, QueuedJob<J>>,
+    positions: BTreeMap<JobId, Position>,
+    capacity: usize,
+    next_sequence_number: u64,
+}
+
+impl<J> JobQueue<J> {
+    pub(super) fn new(capacity: usize) -> Self {
+        JobQueue {
+            jobs: BTreeMap::new(),
+            positions: BTreeMap::new(),
+            capacity,
+            next_sequence_number: 0,
+        }
+    }
+
+    /// Returns the number of queued jobs.
+    pub(super) fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Returns whether the job with the given ID is queued.
+    pub(super) fn contains(&self, id: &JobId) -> bool {
+        self.positions.contains_key(id)
+    }
+
+    /// Queues `job`, or returns it if the queue is full of jobs of at least the same priority.
+    ///
+    /// If the queue is full, the job taken off last, i.e. the most recently queued job of the
+    /// lowest priority, is evicted to make room if its priority is lower than that of `job`, and
+    /// returned.  A job which is queued already keeps its place.
+    pub(super) fn push(
+        &mut self,
+        id: JobId,
+        job: J,
+        priority: Priority,
+    ) -> Result<Option<(JobId, J)>, J> {
+        if self.positions.contains_key(&id) {
+            return Ok(None);
+        }
+        let mut evicted = None;
+        if self.jobs.len() >= self.capacity {
+            match self.jobs.keys().next_back() {
+                Some(&last) if last.priority > Reverse(priority) => {
+                    if let Some(QueuedJob { id, job, .. }) = self.jobs.remove(&last) {
+                        self.positions.remove(&id);
+                        evicted = Some((id, job));
+                    }
+                }
+                _ => return Err(job),
+            }
+        }
+        let position = Position::new(priority, self.next_sequence_number);
+        self.next_sequence_number += 1;
+        self.positions.insert(id, position);
+        self.jobs.insert(position, QueuedJob { id, job, priority });
+        Ok(evicted)
+    }
+
+    /// Takes the job with the highest priority off the queue.
+    pub(super) fn pop(&mut self) -> Option<(JobId, J, Priority)> {
+        let position = *self.jobs.keys().next()?;
+        let QueuedJob { id, job, priority } = self.jobs.remove(&position)?;
+        self.positions.remove(&id);
+        Some((id, job, priority))
+    }
+
+    /// Removes the job with the given ID from the queue, returning it if it was queued.
+    pub(super) fn remove(&mut self, id: &JobId) -> Option<J> {
+        let position = self.positions.remove(id)?;
+        self.jobs.remove(&position).map(|queued| queued.job)
+    }
+
+    /// Returns the queued jobs in the order they will be taken off the queue.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (JobId, &J, Priority)> {
+        self.jobs
+            .values()
+            .map(|queued| (queued.id, &queued.job, queued.priority))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_hashing::Digest;
+
+    use super::*;
+
+    fn job_id(index: u8) -> JobId {
+        JobId::new(Digest::hash([index]))
+    }
+
+    fn third_party(hint: u64) -> Priority {
+        Priority::ThirdParty { hint }
+    }
+
+    #[test]
+    fn should_pop_by_priority_then_age_and_stay_bounded() {
+        let mut queue = JobQueue::new(4);
+        queue.push(job_id(0), 0, third_party(0)).unwrap();
+        queue.push(job_id(1), 1, Priority::Node).unwrap();
+        queue.push(job_id(2), 2, third_party(0)).unwrap();
+        queue.push(job_id(3), 3, third_party(5)).unwrap();
+        // Queuing a job twice keeps it in its place.
+        assert_eq!(queue.push(job_id(0), 0, Priority::Node), Ok(None));
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.push(job_id(4), 4, third_party(0)), Err(4));
+
+        let listed: Vec<_> = queue.iter().map(|(_, job, _)| *job).collect();
+        assert_eq!(listed, vec![1, 3, 0, 2]);
+        let popped: Vec<_> = std::iter::from_fn(|| queue.pop().map(|(_, job, _)| job)).collect();
+        assert_eq!(popped, vec![1, 3, 0, 2]);
+        assert!(!queue.contains(&job_id(0)));
+    }
+
+    #[test]
+    fn should_remove_jobs() {
+        let mut queue = JobQueue::new(2);
+        queue.push(job_id(0), 0, third_party(0)).unwrap();
+        queue.push(job_id(1), 1, third_party(0)).unwrap();
+
+        assert_eq!(queue.remove(&job_id(0)), Some(0));
+        assert_eq!(queue.remove(&job_id(0)), None);
+        assert!(!queue.contains(&job_id(0)));
+        // Removing a job makes room for another.
+        queue.push(job_id(2), 2, Priority::Node).unwrap();
+        assert_eq!(queue.pop(), Some((job_id(2), 2, Priority::Node)));
+    }
+
+    #[test]
+    fn should_evict_newest_job_of_lowest_priority_when_full() {
+        let mut queue = JobQueue::new(3);
+        queue.push(job_id(0), 0, third_party(1)).unwrap();
+        queue.push(job_id(1), 1, third_party(0)).unwrap();
+        queue.push(job_id(2), 2, third_party(0)).unwrap();
+
+        assert_eq!(
+            queue.push(job_id(3), 3, third_party(1)),
+            Ok(Some((job_id(2), 2)))
+        );
+        assert!(!queue.contains(&job_id(2)));
+        assert_eq!(
+            queue.push(job_id(4), 4, Priority::Node),
+            Ok(Some((job_id(1), 1)))
+        );
+        // Jobs of the same priority as the lowest queued one are rejected.
+        assert_eq!(queue.push(job_id(5), 5, third_party(1)), Err(5));
+
+        let popped: Vec<_> = std::iter::from_fn(|| queue.pop().map(|(_, job, _)| job)).collect();
+        assert_eq!(popped, vec![4, 0, 3]);
+    }
+}
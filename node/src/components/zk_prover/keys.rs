@@ -0,0 +1,218 @@
+//! Storage of the proving and verifying keys of circuits.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use once_cell::sync::OnceCell;
+use rand::{CryptoRng, RngCore};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::{
+    backend::{BackendError, Keys, ProvingBackend},
+    circuit::Circuit,
+};
+
+const PROVING_KEY_EXTENSION: &str = "pk";
+const VERIFYING_KEY_EXTENSION: &str = "vk";
+
+/// An error loading, generating or storing keys.
+#[derive(Debug, Error)]
+pub(crate) enum KeyStoreError {
+    /// Reading or writing a key file failed.
+    #[error("could not access {}: {error}", path.display())]
+    Io { path: PathBuf, error: io::Error },
+    /// The keys of a circuit are missing, and generating them is not allowed.
+    #[error(
+        "keys of circuit {circuit_id} missing from {}; put keys from a trusted setup there, or set \
+        zk_prover.allow_insecure_keygen to generate them",
+        dir.display()
+    )]
+    Missing { circuit_id: String, dir: PathBuf },
+    /// Generating keys failed.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// A directory holding the keys of each circuit, for one proof system.
+///
+/// The keys of a circuit are read from `<circuit id>.pk` and `<circuit id>.vk` in a subdirectory
+/// named after the proof system.  Keys meant to convince third parties must be taken from a trusted
+/// setup ceremony and put in place before starting the node.
+///
+/// Missing keys are an error, unless `allow_insecure_keygen` is set, in which case they are
+/// generated and written there.  The node then knows the toxic waste of the setup, and so can
+/// forge proofs checked against the keys, which only makes them fit for testing.
+#[derive(Debug)]
+pub(crate) struct KeyStore {
+    dir: PathBuf,
+    allow_insecure_keygen: bool,
+    /// The keys loaded or being loaded so far, by circuit ID.
+    cache: Mutex<HashMap<String, Arc<OnceCell<Arc<Keys>>>>>,
+}
+
+impl KeyStore {
+    /// Creates a key store in the subdirectory of `keys_path` for the proof system of `backend`.
+    pub(crate) fn new<B: ProvingBackend>(
+        keys_path: &Path,
+        backend: &B,
+        allow_insecure_keygen: bool,
+    ) -> Self {
+        KeyStore {
+            dir: keys_path.join(backend.proof_system().to_string()),
+            allow_insecure_keygen,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the keys for `circuit`, loading them, or generating and storing them if missing and
+    /// allowed to.
+    ///
+    /// Concurrent calls for the same circuit wait for the first one to load the keys, rather than
+    /// loading or generating them twice, while those for other circuits proceed.
+    pub(crate) fn get_or_generate<B: ProvingBackend, C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        backend: &B,
+        circuit: &C,
+        rng: &mut R,
+    ) -> Result<Arc<Keys>, KeyStoreError> {
+        let circuit_id = circuit.id();
+        let cell = Arc::clone(
+            self.cache
+                .lock()
+                .expect("key cache mutex poisoned")
+                .entry(circuit_id.clone())
+                .or_default(),
+        );
+        cell.get_or_try_init(|| {
+            self.load_or_generate(backend, circuit, &circuit_id, rng)
+                .map(Arc::new)
+        })
+        .map(Arc::clone)
+    }
+
+    fn load_or_generate<B: ProvingBackend, C: Circuit, R: RngCore + CryptoRng>(
+        &self,
+        backend: &B,
+        circuit: &C,
+        circuit_id: &str,
+        rng: &mut R,
+    ) -> Result<Keys, KeyStoreError> {
+        let proving_key_path = self.key_path(circuit_id, PROVING_KEY_EXTENSION);
+        let verifying_key_path = self.key_path(circuit_id, VERIFYING_KEY_EXTENSION);
+        if proving_key_path.exists() {
+            return Ok(Keys {
+                proving_key: read(&proving_key_path)?,
+                verifying_key: read(&verifying_key_path)?,
+            });
+        }
+        if !self.allow_insecure_keygen {
+            return Err(KeyStoreError::Missing {
+                circuit_id: circuit_id.to_string(),
+                dir: self.dir.clone(),
+            });
+        }
+
+        warn!(
+            %circuit_id,
+            dir = %self.dir.display(),
+            "generating insecure keys for circuit, proofs checked against them can be forged"
+        );
+        let keys = backend.keygen(circuit, rng)?;
+        fs::create_dir_all(&self.dir).map_err(|error| KeyStoreError::Io {
+            path: self.dir.clone(),
+            error,
+        })?;
+        // The proving key is written last, as its presence marks the keys as complete.
+        write(&verifying_key_path, &keys.verifying_key)?;
+        write(&proving_key_path, &keys.proving_key)?;
+        info!(%circuit_id, "generated keys for circuit");
+        Ok(keys)
+    }
+
+    fn key_path(&self, circuit_id: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", circuit_id, extension))
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, KeyStoreError> {
+    fs::read(path).map_err(|error| KeyStoreError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Writes `contents` to a temporary file next to `path`, then renames it, so that an interrupted
+/// write never leaves a truncated key behind.
+fn write(path: &Path, contents: &[u8]) -> Result<(), KeyStoreError> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)
+        .and_then(|()| fs::rename(&temp_path, path))
+        .map_err(|error| KeyStoreError::Io {
+            path: path.to_path_buf(),
+            error,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            backend::Backend,
+            circuit::tests::{Square, SquareRoot},
+            ProofSystem,
+        },
+        *,
+    };
+
+    const CIRCUIT: SquareRoot = SquareRoot {
+        square: Square(49),
+        root: 7,
+    };
+
+    #[test]
+    fn should_fail_on_missing_keys_unless_allowed_to_generate_them() {
+        let mut rng = crate::new_rng();
+        let keys_dir = tempfile::tempdir().unwrap();
+        let backend = Backend::new(ProofSystem::Groth16Bn254);
+
+        let key_store = KeyStore::new(keys_dir.path(), &backend, false);
+        let error = key_store
+            .get_or_generate(&backend, &CIRCUIT, &mut rng)
+            .unwrap_err();
+        assert!(matches!(error, KeyStoreError::Missing { .. }));
+
+        let key_store = KeyStore::new(keys_dir.path(), &backend, true);
+        let generated = key_store
+            .get_or_generate(&backend, &CIRCUIT, &mut rng)
+            .unwrap();
+
+        // The generated keys are stored, so they are found even if generating keys is not allowed.
+        let key_store = KeyStore::new(keys_dir.path(), &backend, false);
+        let loaded = key_store
+            .get_or_generate(&backend, &CIRCUIT, &mut rng)
+            .unwrap();
+        assert_eq!(loaded, generated);
+    }
+
+    #[test]
+    fn should_cache_keys() {
+        let mut rng = crate::new_rng();
+        let keys_dir = tempfile::tempdir().unwrap();
+        let backend = Backend::new(ProofSystem::Groth16Bn254);
+        let key_store = KeyStore::new(keys_dir.path(), &backend, true);
+
+        let first = key_store
+            .get_or_generate(&backend, &CIRCUIT, &mut rng)
+            .unwrap();
+        fs::remove_dir_all(keys_dir.path()).unwrap();
+        let second = key_store
+            .get_or_generate(&backend, &CIRCUIT, &mut rng)
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
@@ -0,0 +1,452 @@
+//! The circuit proving that the global state trie under a state root hash holds a given leaf.
+//!
+//! The witness is the path of serialized nodes and extensions from the leaf's parent up to the
+//! root, as recomputed from a Merkle proof.  For each of them, the circuit checks that it holds a
+//! pointer to the hash computed so far, and hashes it with BLAKE2b.  The hash computed for the last
+//! of them must be the state root hash.
+//!
+//! Pointers to a child are only accepted where a parser of the serialized node or extension would
+//! find them, rather than anywhere in its bytes, so that a prover cannot pass off a hash embedded
+//! in a sibling pointer as a child.  A pointer to a leaf is only accepted as the first step, so the
+//! proven hash is always that of a leaf.
+//!
+//! Circuits have a fixed size, so the path is padded up to `MAX_DEPTH` steps, and each step up to
+//! the length of a full node.  Proving costs about 3.7 million constraints per step.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, ToBytes, OPTION_SOME_TAG},
+    merkle_proof::{
+        self, MerkleProofError, TrieProofPointer, TrieProofStep, LEAF_POINTER_TAG,
+        NODE_POINTER_TAG, TRIE_EXTENSION_TAG, TRIE_LEAF_TAG, TRIE_NODE_TAG, TRIE_RADIX,
+    },
+};
+
+use super::{
+    circuit::{Circuit, Statement},
+    gadgets::{
+        blake2b::blake2b_256,
+        bytes::{byte_to_fp, VarBytesVar},
+        digest::{digest_to_field_elements, DigestVar, DIGEST_LENGTH},
+    },
+};
+
+/// The maximum number of nodes and extensions between a leaf and the root.
+pub(crate) const MAX_DEPTH: usize = 8;
+
+/// The length of a serialized pointer: its tag followed by the hash of the child.
+const POINTER_LENGTH: usize = 1 + DIGEST_LENGTH;
+/// The length of a slot of a serialized node holding a pointer: the option tag and the pointer.
+const POINTER_SLOT_LENGTH: usize = 1 + POINTER_LENGTH;
+/// The length of a serialized node with all of its slots holding a pointer.
+///
+/// Serialized extensions are always shorter.
+const MAX_STEP_LENGTH: usize = 1 + TRIE_RADIX * POINTER_SLOT_LENGTH;
+/// The number of bytes of the first of the two field elements a pointer is packed into.
+const POINTER_LOW_LENGTH: usize = 1 + DIGEST_LENGTH / 2;
+
+/// The bytes hashed in place of a step beyond the root, which are ignored.
+const PADDING_STEP: &[u8] = &[TRIE_LEAF_TAG];
+
+/// An error creating a `StateInclusion` circuit.
+#[derive(Debug, Error)]
+pub(crate) enum StateInclusionError {
+    /// The Merkle proof has more steps than the circuit supports.
+    #[error("Merkle proof has {depth} steps, more than the supported {max_depth}")]
+    TooDeep { depth: usize, max_depth: usize },
+    /// The Merkle proof doesn't lead to the state root hash.
+    #[error("invalid Merkle proof: {0}")]
+    InvalidMerkleProof(MerkleProofError),
+}
+
+impl From<bytesrepr::Error> for StateInclusionError {
+    fn from(error: bytesrepr::Error) -> Self {
+        StateInclusionError::InvalidMerkleProof(MerkleProofError::from(error))
+    }
+}
+
+/// The statement that the global state trie with the given root hash holds the leaf with the
+/// given hash.
+///
+/// The hash of the leaf holding a value under a key is computed by
+/// `casper_types::merkle_proof::compute_trie_leaf_hash`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(crate) struct StateInclusionStatement {
+    pub(crate) state_root_hash: Digest,
+    pub(crate) leaf_hash: Digest,
+}
+
+impl ToBytes for StateInclusionStatement {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = self.state_root_hash.to_bytes()?;
+        buffer.extend(self.leaf_hash.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.state_root_hash.serialized_length() + self.leaf_hash.serialized_length()
+    }
+}
+
+impl Statement for StateInclusionStatement {
+    fn public_inputs<F: PrimeField>(&self) -> Vec<F> {
+        let mut inputs = digest_to_field_elements::<F>(&self.state_root_hash.value()).to_vec();
+        inputs.extend(digest_to_field_elements::<F>(&self.leaf_hash.value()));
+        inputs
+    }
+}
+
+/// A step of the path from a leaf to the root of the global state trie.
+#[derive(Debug, Serialize, Deserialize)]
+struct PathStep {
+    /// The serialized node or extension.
+    bytes: Vec<u8>,
+    /// The index of the pointer to the previous step's node or extension, or to the leaf.
+    pointer_index: usize,
+}
+
+/// The circuit proving a `StateInclusionStatement`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct StateInclusion {
+    statement: StateInclusionStatement,
+    path: Vec<PathStep>,
+    max_depth: usize,
+    max_step_length: usize,
+}
+
+impl StateInclusion {
+    /// Creates the circuit proving that the global state trie with root hash `state_root_hash`
+    /// holds `value` under `key`, given the steps of a Merkle proof ordered from the leaf up.
+    pub(crate) fn new<'a, K, V, I>(
+        state_root_hash: Digest,
+        key: &K,
+        value: &V,
+        proof_steps: I,
+    ) -> Result<Self, StateInclusionError>
+    where
+        K: ToBytes,
+        V: ToBytes,
+        I: IntoIterator<Item = &'a TrieProofStep>,
+    {
+        let leaf_hash = merkle_proof::compute_trie_leaf_hash(key, value)?;
+        let mut hash = leaf_hash;
+        let mut path = vec![];
+        for (index, proof_step) in proof_steps.into_iter().enumerate() {
+            let pointer = if index == 0 {
+                TrieProofPointer::Leaf(hash)
+            } else {
+                TrieProofPointer::Node(hash)
+            };
+            let bytes = merkle_proof::trie_proof_step_bytes(proof_step, &pointer)?;
+            let pointer_index = match proof_step {
+                TrieProofStep::Node { hole_index, .. } => {
+                    let mut slot_index = 1;
+                    for _ in 0..*hole_index {
+                        slot_index += if bytes[slot_index] == OPTION_SOME_TAG {
+                            POINTER_SLOT_LENGTH
+                        } else {
+                            1
+                        };
+                    }
+                    slot_index + 1
+                }
+                TrieProofStep::Extension { .. } => bytes.len() - POINTER_LENGTH,
+            };
+            hash = Digest::hash(&bytes).value();
+            path.push(PathStep {
+                bytes,
+                pointer_index,
+            });
+        }
+
+        if path.len() > MAX_DEPTH {
+            return Err(StateInclusionError::TooDeep {
+                depth: path.len(),
+                max_depth: MAX_DEPTH,
+            });
+        }
+        if hash != state_root_hash.value() {
+            return Err(StateInclusionError::InvalidMerkleProof(
+                MerkleProofError::RootHashMismatch {
+                    expected: state_root_hash.value(),
+                    computed: hash,
+                },
+            ));
+        }
+
+        Ok(StateInclusion {
+            statement: StateInclusionStatement {
+                state_root_hash,
+                leaf_hash: Digest::from(leaf_hash),
+            },
+            path,
+            max_depth: MAX_DEPTH,
+            max_step_length: MAX_STEP_LENGTH,
+        })
+    }
+}
+
+impl Circuit for StateInclusion {
+    type Statement = StateInclusionStatement;
+
+    fn id(&self) -> String {
+        format!(
+            "state-inclusion-v1-d{}-l{}",
+            self.max_depth, self.max_step_length
+        )
+    }
+
+    fn statement(&self) -> &StateInclusionStatement {
+        &self.statement
+    }
+
+    fn generate_constraints<F: PrimeField>(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        public_inputs: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let state_root_hash =
+            DigestVar::from_halves([public_inputs[0].clone(), public_inputs[1].clone()]);
+        let mut hash = DigestVar::from_halves([public_inputs[2].clone(), public_inputs[3].clone()]);
+
+        let mut was_active = Boolean::TRUE;
+        for depth in 0..self.max_depth {
+            let step = self.path.get(depth);
+            // The steps beyond the root are inactive, and must all follow the active ones.
+            let is_active = Boolean::new_witness(cs.clone(), || Ok(step.is_some()))?;
+            FpVar::from(is_active.clone())
+                .mul_equals(&FpVar::from(was_active.not()), &FpVar::zero())?;
+
+            let bytes = VarBytesVar::new_witness(
+                cs.clone(),
+                step.map_or(PADDING_STEP, |step| step.bytes.as_slice()),
+                self.max_step_length,
+            )?;
+            let pointer_tag = if depth == 0 {
+                LEAF_POINTER_TAG
+            } else {
+                NODE_POINTER_TAG
+            };
+            enforce_holds_pointer(
+                cs.clone(),
+                &bytes,
+                step.map(|step| step.pointer_index),
+                &is_active,
+                pointer_tag,
+                &hash,
+            )?;
+
+            hash = DigestVar::select(&is_active, &blake2b_256(&bytes)?, &hash)?;
+            was_active = is_active;
+        }
+
+        hash.enforce_equal(&state_root_hash)
+    }
+}
+
+/// Enforces that, if `is_active` is set, `parent` is a serialized node or extension holding a
+/// pointer with the tag `pointer_tag` to the child with hash `child_hash`.
+///
+/// `pointer_index` is the index of the pointer in `parent`, part of the witness.
+fn enforce_holds_pointer<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    parent: &VarBytesVar<F>,
+    pointer_index: Option<usize>,
+    is_active: &Boolean<F>,
+    pointer_tag: u8,
+    child_hash: &DigestVar<F>,
+) -> Result<(), SynthesisError> {
+    let values = parent
+        .bytes
+        .iter()
+        .map(byte_to_fp)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // An active parent is a node or an extension, as its tag says.
+    let is_node = Boolean::new_witness(cs.clone(), || {
+        Ok(R1CSVar::value(&parent.bytes[0])? == TRIE_NODE_TAG)
+    })?;
+    let is_extension = Boolean::new_witness(cs.clone(), || {
+        Ok(R1CSVar::value(&parent.bytes[0])? == TRIE_EXTENSION_TAG)
+    })?;
+    (FpVar::from(is_node.clone()) + FpVar::from(is_extension.clone()))
+        .enforce_equal(&FpVar::from(is_active.clone()))?;
+    (FpVar::from(is_node.clone()) * F::from(TRIE_NODE_TAG)
+        + FpVar::from(is_extension.clone()) * F::from(TRIE_EXTENSION_TAG))
+    .enforce_equal(&values[0])?;
+
+    // Parse the slots of a node: the first one starts after the tag, a slot holding no pointer is a
+    // single byte, and one holding a pointer starts with the option tag for `Some`.
+    // `pointer_slot_starts[i]` is one if a slot holding a pointer starts at index `i`.
+    let mut slot_starts: Vec<FpVar<F>> = Vec::with_capacity(values.len());
+    let mut pointer_slot_starts: Vec<FpVar<F>> = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        let slot_start = match index {
+            0 => FpVar::zero(),
+            1 => FpVar::from(is_node.clone()),
+            _ => {
+                let mut slot_start = &slot_starts[index - 1] - &pointer_slot_starts[index - 1];
+                if let Some(pointer_slot_index) = index.checked_sub(POINTER_SLOT_LENGTH) {
+                    slot_start += &pointer_slot_starts[pointer_slot_index];
+                }
+                // Allocated, so that the linear combinations don't grow with the index.
+                let allocated = FpVar::new_witness(cs.clone(), || slot_start.value())?;
+                allocated.enforce_equal(&slot_start)?;
+                allocated
+            }
+        };
+        // Slots start with an option tag, which is zero or one.
+        let pointer_slot_start = &slot_start * value;
+        pointer_slot_start.mul_equals(&(value - F::one()), &FpVar::zero())?;
+        slot_starts.push(slot_start);
+        pointer_slot_starts.push(pointer_slot_start);
+    }
+
+    // Select the pointer at `pointer_index`, which must be a pointer of a node's slot, or the
+    // pointer an extension ends with.
+    let mut selections = vec![];
+    let mut selected_lows = vec![];
+    let mut selected_highs = vec![];
+    for index in 1..=parent.capacity().saturating_sub(POINTER_LENGTH) {
+        let is_selected = Boolean::new_witness(cs.clone(), || Ok(pointer_index == Some(index)))?;
+        let is_slot_pointer = &pointer_slot_starts[index - 1]
+            * FpVar::from(parent.is_in_range(index + POINTER_LENGTH - 1));
+        let is_extension_pointer =
+            FpVar::from(is_extension.clone()) * parent.is_end(index + POINTER_LENGTH);
+        FpVar::from(is_selected.clone()).mul_equals(
+            &(FpVar::one() - is_slot_pointer - is_extension_pointer),
+            &FpVar::zero(),
+        )?;
+
+        let pointer = &parent.bytes[index..index + POINTER_LENGTH];
+        let low = Boolean::le_bits_to_fp_var(&pointer[..POINTER_LOW_LENGTH].to_bits_le()?)?;
+        let high = Boolean::le_bits_to_fp_var(&pointer[POINTER_LOW_LENGTH..].to_bits_le()?)?;
+        selected_lows.push(FpVar::from(is_selected.clone()) * low);
+        selected_highs.push(FpVar::from(is_selected.clone()) * high);
+        selections.push(FpVar::from(is_selected));
+    }
+    selections
+        .iter()
+        .sum::<FpVar<F>>()
+        .enforce_equal(&FpVar::from(is_active.clone()))?;
+
+    // The selected pointer has the expected tag, followed by the child's hash.
+    let [child_low, child_high] = child_hash.halves();
+    let expected_low = child_low * F::from(256u64) + F::from(pointer_tag);
+    selected_lows
+        .iter()
+        .sum::<FpVar<F>>()
+        .enforce_equal(&is_active.select(&expected_low, &FpVar::zero())?)?;
+    selected_highs
+        .iter()
+        .sum::<FpVar<F>>()
+        .enforce_equal(&is_active.select(child_high, &FpVar::zero())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    use casper_types::bytesrepr::Bytes;
+
+    use super::{super::circuit::Synthesizer, *};
+
+    const KEY: u64 = 7;
+    const VALUE: u64 = 42;
+
+    /// Returns the steps of a proof through a node holding the leaf and a sibling, then an
+    /// extension, along with the resulting state root hash.
+    fn proof_steps(sibling: TrieProofPointer) -> (Vec<TrieProofStep>, Digest) {
+        let steps = vec![
+            TrieProofStep::Node {
+                hole_index: 3,
+                indexed_pointers_with_hole: vec![(200, sibling)],
+            },
+            TrieProofStep::Extension {
+                affix: Bytes::from(vec![1, 2]),
+            },
+        ];
+        let root = merkle_proof::compute_trie_root_hash(&KEY, &VALUE, &steps).unwrap();
+        (steps, Digest::from(root))
+    }
+
+    /// Returns the circuit shrunk to `max_depth` steps of up to 384 bytes, enough for a node with
+    /// two pointers.
+    fn small_circuit(
+        state_root_hash: Digest,
+        steps: &[TrieProofStep],
+        max_depth: usize,
+    ) -> StateInclusion {
+        let mut circuit = StateInclusion::new(state_root_hash, &KEY, &VALUE, steps).unwrap();
+        circuit.max_depth = max_depth;
+        circuit.max_step_length = 384;
+        circuit
+    }
+
+    fn is_satisfied(circuit: &StateInclusion) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        Synthesizer(circuit)
+            .generate_constraints(cs.clone())
+            .unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn should_prove_inclusion_through_node_and_extension() {
+        let (steps, root) = proof_steps(TrieProofPointer::Leaf([9; DIGEST_LENGTH]));
+        assert!(is_satisfied(&small_circuit(root, &steps, 2)));
+        // Unused steps are padding.
+        assert!(is_satisfied(&small_circuit(root, &steps, 3)));
+
+        let mut circuit = small_circuit(root, &steps, 2);
+        circuit.statement.state_root_hash = Digest::hash([1]);
+        assert!(!is_satisfied(&circuit));
+
+        let mut circuit = small_circuit(root, &steps, 2);
+        circuit.statement.leaf_hash = Digest::hash([1]);
+        assert!(!is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn should_only_accept_pointers_where_a_parser_finds_them() {
+        // The hash of the sibling, followed by the option tag of the next slot, which holds no
+        // pointer, reads like a pointer to a leaf with hash `forged`.
+        let mut forged = [5; DIGEST_LENGTH];
+        forged[DIGEST_LENGTH - 1] = 0;
+        let mut sibling = [LEAF_POINTER_TAG; DIGEST_LENGTH];
+        sibling[1..].copy_from_slice(&forged[..DIGEST_LENGTH - 1]);
+        let (steps, root) = proof_steps(TrieProofPointer::Node(sibling));
+        let mut circuit = small_circuit(root, &steps, 2);
+        assert!(is_satisfied(&circuit));
+
+        let sibling_index = circuit.path[0]
+            .bytes
+            .windows(DIGEST_LENGTH)
+            .position(|window| window == sibling)
+            .unwrap();
+        circuit.statement.leaf_hash = Digest::from(forged);
+        circuit.path[0].pointer_index = sibling_index;
+        assert!(!is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn should_reject_proofs_deeper_than_supported() {
+        let steps: Vec<_> = (0..=MAX_DEPTH)
+            .map(|_| TrieProofStep::Extension {
+                affix: Bytes::from(vec![0]),
+            })
+            .collect();
+        let root = merkle_proof::compute_trie_root_hash(&KEY, &VALUE, &steps).unwrap();
+        assert!(matches!(
+            StateInclusion::new(Digest::from(root), &KEY, &VALUE, &steps),
+            Err(StateInclusionError::TooDeep { .. })
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+//! Reloading selected settings of the config while the node is running.
+//!
+//! A reload is requested by sending `SIGHUP` to the node, or via the `reload-config` command of
+//! the diagnostics port.  The main reactor then reads the config file again, along with any
+//! overrides passed on the command line at startup, and applies the settings listed in
+//! `RELOADABLE_SETTINGS`.  Changes to any other setting are reported, and only take effect once
+//! the node is restarted.
+
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::anyhow;
+use once_cell::sync::{Lazy, OnceCell};
+use toml::Value;
+
+use crate::{
+    cli::{self, ConfigExt},
+    reactor::main_reactor::Config,
+};
+
+/// The settings applied on a reload, as `section.key`, or as `section` to cover a whole section.
+const RELOADABLE_SETTINGS: [&str; 5] = [
+    "logging.filter",
+    "gossip",
+    "rpc_server.qps_limit",
+    "speculative_exec_server.qps_limit",
+    "network.known_addresses",
+];
+
+/// Global flag set when a reload of the config is requested.
+pub(crate) static RELOAD_REQUESTED: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+
+/// The config file the node was started with, and the overrides passed on the command line.
+static CONFIG_SOURCE: OnceCell<ConfigSource> = OnceCell::new();
+
+#[derive(Debug)]
+struct ConfigSource {
+    path: PathBuf,
+    config_ext: Vec<ConfigExt>,
+}
+
+/// Records where the config was read from on startup, so that it can be read again on a reload.
+pub(crate) fn set_config_source(path: PathBuf, config_ext: Vec<ConfigExt>) {
+    drop(CONFIG_SOURCE.set(ConfigSource { path, config_ext }));
+}
+
+/// Requests a reload of the config, to be carried out by the main reactor.
+pub(crate) fn request_reload() {
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether a reload was requested since the last call.
+pub(crate) fn take_reload_request() -> bool {
+    RELOAD_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+/// Reads the config again from the file and overrides the node was started with.
+pub(crate) fn read_config() -> anyhow::Result<Config> {
+    let source = CONFIG_SOURCE
+        .get()
+        .ok_or_else(|| anyhow!("node was not started from a config file"))?;
+    Ok(cli::read_config(&source.path, &source.config_ext)?.into_inner())
+}
+
+/// Returns the names of the settings which differ between `old` and `new`, as `section.key`.
+pub(crate) fn changed_settings(old: &Config, new: &Config) -> anyhow::Result<Vec<String>> {
+    let old = Value::try_from(old)?;
+    let new = Value::try_from(new)?;
+    let (old, new) = match (old.as_table(), new.as_table()) {
+        (Some(old), Some(new)) => (old, new),
+        _ => return Err(anyhow!("config is not a table")),
+    };
+
+    let mut changed = Vec::new();
+    let sections: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    for section in sections {
+        match (old.get(section), new.get(section)) {
+            (Some(Value::Table(old_section)), Some(Value::Table(new_section))) => {
+                let keys: BTreeSet<_> = old_section.keys().chain(new_section.keys()).collect();
+                for key in keys {
+                    if old_section.get(key) != new_section.get(key) {
+                        changed.push(format!("{}.{}", section, key));
+                    }
+                }
+            }
+            (old_value, new_value) => {
+                if old_value != new_value {
+                    changed.push(section.clone());
+                }
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Returns whether the given setting, as `section.key`, is applied on a reload.
+pub(crate) fn is_reloadable(setting: &str) -> bool {
+    RELOADABLE_SETTINGS.iter().any(|reloadable| {
+        setting == *reloadable
+            || setting
+                .strip_prefix(reloadable)
+                .map_or(false, |key| key.starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_find_changed_settings() {
+        let old = Config::default();
+        let mut new = Config::default();
+        assert!(changed_settings(&old, &new).unwrap().is_empty());
+
+        new.logging.filter = Some("debug".to_string());
+        new.gossip.infection_target += 1;
+        new.rpc_server.qps_limit += 1;
+        new.storage.max_block_store_size += 1;
+        let changed = changed_settings(&old, &new).unwrap();
+        assert_eq!(
+            changed,
+            vec![
+                "gossip.infection_target",
+                "logging.filter",
+                "rpc_server.qps_limit",
+                "storage.max_block_store_size",
+            ]
+        );
+
+        let non_reloadable: Vec<_> = changed
+            .iter()
+            .filter(|setting| !is_reloadable(setting))
+            .collect();
+        assert_eq!(non_reloadable, vec!["storage.max_block_store_size"]);
+        assert!(!is_reloadable("gossiper.infection_target"));
+        assert!(!is_reloadable("rpc_server.qps_limit_extra"));
+    }
+}
@@ -98,10 +98,11 @@ pub(crate) mod requests;
 use std::{
     any::type_name,
     borrow::Cow,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
     mem,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -116,44 +117,65 @@ use tracing::{debug, error, warn};
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult, GetBidsRequest,
-        GetBidsResult, QueryRequest, QueryResult,
+        self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult, GasProfile,
+        GetBidsRequest, GetBidsResult, QueryRequest, QueryResult, StateDiffResult,
     },
     shared::execution_journal::ExecutionJournal,
-    storage::trie::TrieRaw,
+    storage::{trie::TrieRaw, trie_store::gc::TrieGcOutcome},
 };
-use casper_hashing::Digest;
+use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{
-    account::Account, bytesrepr::Bytes, system::auction::EraValidators, Contract, ContractPackage,
-    EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff, Timestamp, Transfer, URef,
-    U512,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators,
+    Contract, ContractPackage, EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff,
+    Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
     components::{
+        block_accumulator::{BlockAccumulatorDump, BlocksInRange},
         block_synchronizer::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange},
-        contract_runtime::{ContractRuntimeError, EraValidatorsRequest},
+        consensus::{
+            ClContext, EquivocationRecord, EraDump, ProposedBlock, ScheduledRound, ValidatorChange,
+            ValidatorPerformanceReport,
+        },
+        contract_runtime::{ContractRuntimeError, EraValidatorsRequest, TrieGcError},
         deploy_acceptor,
+        deploy_buffer::StuckDeploy,
         diagnostics_port::StopAtSpec,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
-        network::{blocklist::BlocklistJustification, FromIncoming, NetworkInsights},
+        network::{
+            blocklist::BlocklistJustification, FromIncoming, NetworkInsights, PeerReputation,
+            PeerTraffic,
+        },
+        randomness_beacon::BlockRandomness,
+        speculative_executor::SpeculativeExecutionError,
+        storage::{
+            AccountTransfer, CreateSnapshotError, PruneOutcome, StorageSnapshot,
+            StorageVerification, TransferPosition,
+        },
         upgrade_watcher::NextUpgrade,
+        zk_prover::{
+            EraTransitionProof, JobId, JobStatus, PersistedJob, Priority, Proof, ProvingError,
+            ProvingJob, ProvingJobs, SubmitJobError,
+        },
     },
     contract_runtime::SpeculativeExecutionState,
     failpoints::FailpointActivation,
-    reactor::{main_reactor::ReactorState, EventQueueHandle, QueueKind},
+    reactor::{main_reactor::ReactorState, DispatchTimesSummary, EventQueueHandle, QueueKind},
     types::{
         appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
+        BlockBodyOrChunk, BlockBodyOrChunkId, BlockExecutionResultsOrChunk,
+        BlockExecutionResultsOrChunkId, BlockHash, BlockHeader, BlockSignatures, BlockWithMetadata,
+        ChainspecRawBytes, Deploy, DeployHash, DeployHeader, DeployId, DeployMetadataExt,
+        DeployWithFinalizedApprovals, FinalitySignature, FinalitySignatureId, FinalizedApprovals,
+        FinalizedBlock, LegacyDeploy, MetaBlock, MetaBlockState, NodeId, TrieOrChunk,
+        TrieOrChunkId,
     },
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
 };
@@ -162,15 +184,16 @@ use announcements::{
     ControlAnnouncement, DeployAcceptorAnnouncement, DeployBufferAnnouncement, FatalAnnouncement,
     FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement,
     MetaBlockAnnouncement, PeerBehaviorAnnouncement, QueueDumpFormat, UnexecutedBlockAnnouncement,
-    UpgradeWatcherAnnouncement,
+    UpgradeWatcherAnnouncement, ZkProverAnnouncement,
 };
 use diagnostics_port::DumpConsensusStateRequest;
 use requests::{
     AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest, BlockSynchronizerRequest,
     BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
     DeployBufferRequest, FetcherRequest, MakeBlockExecutableRequest, MarkBlockCompletedRequest,
-    MetricsRequest, NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, SetNodeStopRequest,
-    StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest,
+    MetricsRequest, NetworkInfoRequest, NetworkRequest, RandomnessBeaconRequest,
+    ReactorStatusRequest, SetNodeStopRequest, SpeculativeExecutionRequest, StorageRequest,
+    SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest, ZkProverRequest,
 };
 
 /// A resource that will never be available, thus trying to acquire it will wait forever.
@@ -774,6 +797,61 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the traffic exchanged with each peer, busiest peers first.
+    pub(crate) async fn get_peer_traffic(self) -> Vec<PeerTraffic>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::PeerTraffic { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the reputations of all peers which committed offenses or were banned, worst first.
+    pub(crate) async fn get_peer_reputations(self) -> Vec<PeerReputation>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::PeerReputations { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Bans a peer by node ID for the given duration, disconnecting it.
+    ///
+    /// Returns the time at which the ban expires, which may be later if the peer was already
+    /// banned.
+    pub(crate) async fn ban_peer(self, peer_id: NodeId, duration: TimeDiff) -> Timestamp
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::BanPeer {
+                peer_id,
+                duration,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Lifts the ban of a peer, returning whether it was banned.
+    pub(crate) async fn unban_peer(self, peer_id: NodeId) -> bool
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::UnbanPeer { peer_id, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets a map of the current network peers to their socket addresses.
     pub(crate) async fn network_peers(self) -> BTreeMap<NodeId, String>
     where
@@ -811,6 +889,25 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces which pending deploys were replaced by a deploy with a higher payment.
+    pub(crate) async fn announce_replaced_deploys(
+        self,
+        replaced: Vec<DeployHash>,
+        replacement: DeployHash,
+    ) where
+        REv: From<DeployBufferAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                DeployBufferAnnouncement::DeploysReplaced {
+                    replaced,
+                    replacement,
+                },
+                QueueKind::Validation,
+            )
+            .await;
+    }
+
     /// Announces an incoming network message.
     pub(crate) async fn announce_incoming<P>(self, sender: NodeId, payload: P)
     where
@@ -874,6 +971,40 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces that the block accumulator has held a block without knowing its parent for
+    /// longer than the orphan timeout.
+    pub(crate) async fn announce_orphan_detected(
+        self,
+        block_hash: BlockHash,
+        parent_hash: BlockHash,
+    ) where
+        REv: From<BlockAccumulatorAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                BlockAccumulatorAnnouncement::OrphanDetected {
+                    block_hash,
+                    parent_hash,
+                },
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
+    /// Announces that a block held by the block accumulator lost strict finality after signatures
+    /// invalidated by a validator matrix update were dropped.
+    pub(crate) async fn announce_sufficient_finality_lost(self, block_hash: BlockHash)
+    where
+        REv: From<BlockAccumulatorAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                BlockAccumulatorAnnouncement::SufficientFinalityLost { block_hash },
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
     /// Request that a block be made executable (i.e. produce a FinalizedBlock plus any Deploys),
     /// if able to.
     ///
@@ -1093,6 +1224,29 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Puts the given block, its approvals hashes if provided and its finality signatures into
+    /// the linear block store, all in a single transaction.
+    pub(crate) async fn put_block_and_approvals_and_signatures_to_storage(
+        self,
+        block: Arc<Block>,
+        approvals_hashes: Option<Box<ApprovalsHashes>>,
+        signatures: BlockSignatures,
+    ) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutBlockAndApprovalsAndSignatures {
+                block,
+                approvals_hashes,
+                signatures,
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
     /// Puts the given block and approvals hashes into the linear block store.
     pub(crate) async fn put_executed_block_to_storage(
         self,
@@ -1201,6 +1355,22 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Reconstructs the validator weights of the given era from the stored switch block of the era
+    /// before it.
+    pub(crate) async fn get_era_validator_weights_from_storage(
+        self,
+        era_id: EraId,
+    ) -> Option<BTreeMap<PublicKey, U512>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetEraValidatorWeights { era_id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Gets the requested signature for a given block hash.
     pub(crate) async fn get_signature_from_storage(
         self,
@@ -1238,6 +1408,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the execution results of all deploys and transfers of a block from storage, in the
+    /// order they are listed in the block's body.
+    pub(crate) async fn get_execution_results_for_block_from_storage(
+        self,
+        block_hash: BlockHash,
+    ) -> Option<Vec<(DeployHash, ExecutionResult)>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetExecutionResultsForBlock {
+                block_hash,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Puts a block header to storage.
     pub(crate) async fn put_block_header_to_storage(self, block_header: Box<BlockHeader>) -> bool
     where
@@ -1361,6 +1550,97 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the height of the lowest stored block with the given state root hash as its
+    /// post-state.
+    pub(crate) async fn get_block_height_for_state_root_hash_from_storage(
+        self,
+        state_root_hash: Digest,
+    ) -> Option<u64>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeightForStateRootHash {
+                state_root_hash,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Requests the state root hashes of all stored blocks at or above the given height.
+    pub(crate) async fn get_state_root_hashes_from_height_from_storage(
+        self,
+        height: u64,
+    ) -> BTreeSet<Digest>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetStateRootHashesFromHeight { height, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Prunes the bodies, deploys and execution results of blocks below the given height, subject
+    /// to the storage component's safety limits.
+    pub(crate) async fn prune_blocks_below(self, below_height: u64) -> PruneOutcome
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PruneBlocks {
+                below_height,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Records that the global state of blocks below the given height may be garbage collected,
+    /// removing them from the available block range.  Returns the resulting height.
+    pub(crate) async fn mark_global_state_pruned_below(self, height: u64) -> u64
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::MarkGlobalStatePrunedBelow { height, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Creates a consistent copy of all storage databases in the directory `path`, which must not
+    /// exist.
+    pub(crate) async fn create_storage_snapshot(
+        self,
+        path: PathBuf,
+    ) -> Result<StorageSnapshot, CreateSnapshotError>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::CreateSnapshot { path, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Verifies the integrity of all stored blocks, optionally repairing the inconsistencies found.
+    pub(crate) async fn verify_storage_integrity(self, repair: bool) -> StorageVerification
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::VerifyIntegrity { repair, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Synchronize global state under the given root hash.
     pub(crate) async fn sync_global_state(
         self,
@@ -1580,65 +1860,73 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the requested block and its finality signatures.
-    pub(crate) async fn get_block_at_height_with_metadata_from_storage(
+    /// Gets the hashes of all deploys sent by the given account from the storage component.
+    pub(crate) async fn get_deploy_hashes_by_account_from_storage(
         self,
-        block_height: u64,
-        only_from_available_block_range: bool,
-    ) -> Option<BlockWithMetadata>
+        account: PublicKey,
+    ) -> Vec<DeployHash>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetBlockAndMetadataByHeight {
-                block_height,
-                only_from_available_block_range,
-                responder,
-            },
+            |responder| StorageRequest::GetDeployHashesByAccount { account, responder },
             QueueKind::FromStorage,
         )
         .await
     }
 
-    /// Gets the requested finality signature from storage.
-    pub(crate) async fn get_finality_signature_from_storage(
+    /// Gets at most `max_count` transfers from or to the given account from the storage
+    /// component, most recent first, starting after the transfer at `after` if given.
+    pub(crate) async fn get_transfers_by_account_from_storage(
         self,
-        id: Box<FinalitySignatureId>,
-    ) -> Option<FinalitySignature>
+        account_hash: AccountHash,
+        after: Option<TransferPosition>,
+        max_count: usize,
+    ) -> Vec<AccountTransfer>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetFinalitySignature { id, responder },
+            |responder| StorageRequest::GetTransfersByAccount {
+                account_hash,
+                after,
+                max_count,
+                responder,
+            },
             QueueKind::FromStorage,
         )
         .await
     }
 
-    pub(crate) async fn is_finality_signature_stored(self, id: Box<FinalitySignatureId>) -> bool
+    /// Puts the record of an observed equivocation into the storage component.
+    pub(crate) async fn put_equivocation_to_storage(self, record: EquivocationRecord) -> bool
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::IsFinalitySignatureStored { id, responder },
-            QueueKind::FromStorage,
+            |responder| StorageRequest::PutEquivocation {
+                record: Box::new(record),
+                responder,
+            },
+            QueueKind::ToStorage,
         )
         .await
     }
 
-    /// Gets the requested block by hash with its associated metadata.
-    pub(crate) async fn get_block_with_metadata_from_storage(
+    /// Gets the records of all stored equivocations in `from_era_id` or later, optionally only
+    /// those by the given validator, from the storage component.
+    pub(crate) async fn get_equivocations_from_storage(
         self,
-        block_hash: BlockHash,
-        only_from_available_block_range: bool,
-    ) -> Option<BlockWithMetadata>
+        from_era_id: EraId,
+        validator: Option<PublicKey>,
+    ) -> Vec<EquivocationRecord>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetBlockAndMetadataByHash {
-                block_hash,
-                only_from_available_block_range,
+            |responder| StorageRequest::GetEquivocations {
+                from_era_id,
+                validator,
                 responder,
             },
             QueueKind::FromStorage,
@@ -1646,68 +1934,271 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the highest block with its associated metadata.
-    pub(crate) async fn get_highest_block_with_metadata_from_storage(
+    /// Puts the report of the validators' performance in an era into the storage component.
+    pub(crate) async fn put_validator_performance_to_storage(
         self,
-        only_from_available_block_range: bool,
-    ) -> Option<BlockWithMetadata>
-    where
+        report: ValidatorPerformanceReport,
+    ) where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetHighestBlockWithMetadata {
-                only_from_available_block_range,
+            |responder| StorageRequest::PutValidatorPerformance {
+                report: Box::new(report),
                 responder,
             },
-            QueueKind::FromStorage,
+            QueueKind::ToStorage,
         )
         .await
     }
 
-    /// Fetches an item from a fetcher.
-    pub(crate) async fn fetch<T>(
+    /// Gets the report of the validators' performance in the given era from the storage
+    /// component.
+    pub(crate) async fn get_validator_performance_from_storage(
         self,
-        id: T::Id,
-        peer: NodeId,
-        validation_metadata: Box<T::ValidationMetadata>,
-    ) -> FetchResult<T>
+        era_id: EraId,
+    ) -> Option<ValidatorPerformanceReport>
     where
-        REv: From<FetcherRequest<T>>,
-        T: FetchItem + 'static,
+        REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| FetcherRequest {
-                id,
-                peer,
-                validation_metadata,
-                responder,
-            },
-            QueueKind::Fetch,
+            |responder| StorageRequest::GetValidatorPerformance { era_id, responder },
+            QueueKind::FromStorage,
         )
         .await
     }
 
-    pub(crate) async fn fetch_trie(
-        self,
-        hash: Digest,
-        peers: Vec<NodeId>,
-    ) -> Result<TrieAccumulatorResponse, TrieAccumulatorError>
+    /// Puts a chunk of a partially fetched trie into the storage component.
+    pub(crate) async fn put_trie_chunk_to_storage(self, chunk: ChunkWithProof)
     where
-        REv: From<TrieAccumulatorRequest>,
+        REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| TrieAccumulatorRequest {
-                hash,
-                peers,
+            |responder| StorageRequest::PutTrieChunk {
+                chunk: Box::new(chunk),
                 responder,
             },
-            QueueKind::SyncGlobalState,
+            QueueKind::ToStorage,
         )
         .await
     }
 
-    /// Passes the timestamp of a future block for which deploys are to be proposed.
-    pub(crate) async fn request_appendable_block(self, timestamp: Timestamp) -> AppendableBlock
+    /// Gets the stored chunks of the trie with the given hash from the storage component.
+    pub(crate) async fn get_trie_chunks_from_storage(self, trie_hash: Digest) -> Vec<ChunkWithProof>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetTrieChunks {
+                trie_hash,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Deletes the stored chunks of the trie with the given hash from the storage component.
+    pub(crate) async fn delete_trie_chunks_from_storage(self, trie_hash: Digest)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::DeleteTrieChunks {
+                trie_hash,
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Puts the proof of the validators set by the switch block of an era into the storage
+    /// component.
+    pub(crate) async fn put_era_transition_proof_to_storage(self, proof: EraTransitionProof)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutEraTransitionProof {
+                proof: Box::new(proof),
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Gets the proof of the validators set by the switch block of the given era from the storage
+    /// component.
+    pub(crate) async fn get_era_transition_proof_from_storage(
+        self,
+        era_id: EraId,
+    ) -> Option<EraTransitionProof>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetEraTransitionProof { era_id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Puts a queued or running proving job into the storage component.
+    pub(crate) async fn put_proving_job_to_storage(self, job: PersistedJob)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutProvingJob {
+                job: Box::new(job),
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Deletes a proving job from the storage component.
+    pub(crate) async fn delete_proving_job_from_storage(self, job_id: JobId)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::DeleteProvingJob { job_id, responder },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Gets the requested block and its finality signatures.
+    pub(crate) async fn get_block_at_height_with_metadata_from_storage(
+        self,
+        block_height: u64,
+        only_from_available_block_range: bool,
+    ) -> Option<BlockWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockAndMetadataByHeight {
+                block_height,
+                only_from_available_block_range,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the requested finality signature from storage.
+    pub(crate) async fn get_finality_signature_from_storage(
+        self,
+        id: Box<FinalitySignatureId>,
+    ) -> Option<FinalitySignature>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetFinalitySignature { id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    pub(crate) async fn is_finality_signature_stored(self, id: Box<FinalitySignatureId>) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsFinalitySignatureStored { id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the requested block by hash with its associated metadata.
+    pub(crate) async fn get_block_with_metadata_from_storage(
+        self,
+        block_hash: BlockHash,
+        only_from_available_block_range: bool,
+    ) -> Option<BlockWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockAndMetadataByHash {
+                block_hash,
+                only_from_available_block_range,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the highest block with its associated metadata.
+    pub(crate) async fn get_highest_block_with_metadata_from_storage(
+        self,
+        only_from_available_block_range: bool,
+    ) -> Option<BlockWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetHighestBlockWithMetadata {
+                only_from_available_block_range,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Fetches an item from a fetcher.
+    pub(crate) async fn fetch<T>(
+        self,
+        id: T::Id,
+        peer: NodeId,
+        validation_metadata: Box<T::ValidationMetadata>,
+    ) -> FetchResult<T>
+    where
+        REv: From<FetcherRequest<T>>,
+        T: FetchItem + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest {
+                id,
+                peer,
+                validation_metadata,
+                responder,
+            },
+            QueueKind::Fetch,
+        )
+        .await
+    }
+
+    pub(crate) async fn fetch_trie(
+        self,
+        hash: Digest,
+        peers: Vec<NodeId>,
+    ) -> Result<TrieAccumulatorResponse, TrieAccumulatorError>
+    where
+        REv: From<TrieAccumulatorRequest>,
+    {
+        self.make_request(
+            |responder| TrieAccumulatorRequest {
+                hash,
+                peers,
+                responder,
+            },
+            QueueKind::SyncGlobalState,
+        )
+        .await
+    }
+
+    /// Passes the timestamp of a future block for which deploys are to be proposed.
+    pub(crate) async fn request_appendable_block(self, timestamp: Timestamp) -> AppendableBlock
     where
         REv: From<DeployBufferRequest>,
     {
@@ -1721,6 +2212,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the buffered deploys which can't be proposed because of unexecuted dependencies.
+    pub(crate) async fn get_stuck_deploys(self) -> Vec<StuckDeploy>
+    where
+        REv: From<DeployBufferRequest>,
+    {
+        self.make_request(
+            |responder| DeployBufferRequest::GetStuckDeploys { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Enqueues a finalized block execution.
     pub(crate) async fn enqueue_block_for_execution(
         self,
@@ -1897,6 +2400,29 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a query be executed against the global state at the given, possibly historical,
+    /// state root, returning the value along with its Merkle proof.
+    pub(crate) async fn query_global_state_with_proof_at_root(
+        self,
+        state_root: Digest,
+        key: Key,
+        path: Vec<String>,
+    ) -> Result<QueryResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::QueryWithProofAtRoot {
+                state_root,
+                key,
+                path,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
     /// Retrieves an `Account` from global state if present.
     pub(crate) async fn get_account_from_global_state(
         self,
@@ -2065,6 +2591,17 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns the current era and the leaders of its upcoming rounds, if consensus is running.
+    pub(crate) async fn get_consensus_leader_schedule(
+        self,
+    ) -> Option<(EraId, Vec<ScheduledRound<PublicKey>>)>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::LeaderSchedule, QueueKind::Consensus)
+            .await
+    }
+
     /// Dump consensus state for a specific era, using the supplied function to serialize the
     /// output.
     pub(crate) async fn diagnostics_port_dump_consensus_state(
@@ -2101,6 +2638,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Retrieves the time spent dispatching each kind of event since the node started.
+    pub(crate) async fn get_dispatch_times(self) -> DispatchTimesSummary
+    where
+        REv: From<ControlAnnouncement>,
+    {
+        self.make_request(
+            |responder| ControlAnnouncement::DispatchTimesRequest { responder },
+            QueueKind::Control,
+        )
+        .await
+    }
+
     /// Activates/deactivates a failpoint from a given activation.
     pub(crate) async fn activate_failpoint(self, activation: FailpointActivation)
     where
@@ -2202,16 +2751,171 @@ impl<REv> EffectBuilder<REv> {
         self,
         execution_prestate: SpeculativeExecutionState,
         deploy: Arc<Deploy>,
-    ) -> Result<Option<ExecutionResult>, engine_state::Error>
+    ) -> Result<Option<ExecutionResult>, SpeculativeExecutionError>
     where
-        REv: From<ContractRuntimeRequest>,
+        REv: From<SpeculativeExecutionRequest>,
     {
         self.make_request(
-            |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
+            |responder| SpeculativeExecutionRequest {
                 execution_prestate,
                 deploy,
                 responder,
             },
+            QueueKind::SpeculativeExecution,
+        )
+        .await
+    }
+
+    /// Submits a proving job to the zero-knowledge prover, returning the status of the job.
+    ///
+    /// If the same job was submitted before, its current status is returned instead.
+    pub(crate) async fn submit_proving_job(
+        self,
+        job: ProvingJob,
+        priority: Priority,
+    ) -> Result<JobStatus, SubmitJobError>
+    where
+        REv: From<ZkProverRequest>,
+    {
+        self.make_request(
+            |responder| ZkProverRequest::SubmitJob {
+                job: Box::new(job),
+                priority,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests the status of a proving job from the zero-knowledge prover.
+    pub(crate) async fn get_proving_job_status(self, job_id: JobId) -> Option<JobStatus>
+    where
+        REv: From<ZkProverRequest>,
+    {
+        self.make_request(
+            |responder| ZkProverRequest::GetJobStatus { job_id, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests the queued and running proving jobs from the zero-knowledge prover.
+    pub(crate) async fn list_proving_jobs(self) -> ProvingJobs
+    where
+        REv: From<ZkProverRequest>,
+    {
+        self.make_request(
+            |responder| ZkProverRequest::ListJobs { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Cancels a queued or running proving job, returning whether there was such a job.
+    pub(crate) async fn cancel_proving_job(self, job_id: JobId) -> bool
+    where
+        REv: From<ZkProverRequest>,
+    {
+        self.make_request(
+            |responder| ZkProverRequest::CancelJob { job_id, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests the random output derived for a block by the randomness beacon.
+    ///
+    /// Returns `None` if the beacon is disabled.
+    pub(crate) async fn get_block_randomness(self, block_hash: BlockHash) -> Option<BlockRandomness>
+    where
+        REv: From<RandomnessBeaconRequest>,
+    {
+        self.make_request(
+            |responder| RandomnessBeaconRequest::GetBlockRandomness {
+                block_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Announces that a proving job finished.
+    pub(crate) async fn announce_proving_job_finished(
+        self,
+        job_id: JobId,
+        result: Result<Proof, ProvingError>,
+    ) where
+        REv: From<ZkProverAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ZkProverAnnouncement::JobFinished { job_id, result },
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
+    /// Announces that the validators a switch block sets for the next era were proven.
+    pub(crate) async fn announce_era_transition_proven(self, proof: EraTransitionProof)
+    where
+        REv: From<ZkProverAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ZkProverAnnouncement::EraTransitionProven(Box::new(proof)),
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
+    /// Requests the gas profile of the most recent execution of a deploy.
+    pub(crate) async fn get_deploy_gas_profile(self, deploy_hash: DeployHash) -> Option<GasProfile>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetDeployGasProfile {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests the changes of global state between two state root hashes.
+    pub(crate) async fn get_state_diff(
+        self,
+        from_root: Digest,
+        to_root: Digest,
+    ) -> Result<StateDiffResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetStateDiff {
+                from_root,
+                to_root,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests a garbage collection of the global state tries no longer reachable from the state
+    /// of any retained block.
+    pub(crate) async fn collect_trie_garbage(
+        self,
+        dry_run: bool,
+    ) -> Result<TrieGcOutcome, TrieGcError>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::CollectTrieGarbage { dry_run, responder },
             QueueKind::ContractRuntime,
         )
         .await
@@ -2232,6 +2936,21 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Reads a block body (or chunk) from Storage component.
+    pub(crate) async fn get_block_body_or_chunk_from_storage(
+        self,
+        id: BlockBodyOrChunkId,
+    ) -> Option<BlockBodyOrChunk>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockBodyOrChunk { id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Gets peers for a given block from the block accumulator.
     pub(crate) async fn get_block_accumulated_peers(
         self,
@@ -2250,6 +2969,69 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the quality scores of the given peers from the block accumulator.
+    ///
+    /// Peers which sent the accumulator items failing validation have negative scores.
+    pub(crate) async fn get_block_accumulator_peer_quality(
+        self,
+        peers: Vec<NodeId>,
+    ) -> BTreeMap<NodeId, i64>
+    where
+        REv: From<BlockAccumulatorRequest>,
+    {
+        self.make_request(
+            |responder| BlockAccumulatorRequest::GetPeerQuality { peers, responder },
+            QueueKind::NetworkInfo,
+        )
+        .await
+    }
+
+    /// Gets the hashes and finality status of the blocks held by the block accumulator with heights
+    /// in `[start_height, end_height]`.
+    pub(crate) async fn get_block_accumulator_blocks_in_range(
+        self,
+        start_height: u64,
+        end_height: u64,
+    ) -> BlocksInRange
+    where
+        REv: From<BlockAccumulatorRequest>,
+    {
+        self.make_request(
+            |responder| BlockAccumulatorRequest::GetBlocksInRange {
+                start_height,
+                end_height,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets a snapshot of the block accumulator's state, for debugging.
+    pub(crate) async fn get_block_accumulator_dump(self) -> BlockAccumulatorDump
+    where
+        REv: From<BlockAccumulatorRequest>,
+    {
+        self.make_request(
+            |responder| BlockAccumulatorRequest::GetDump { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the height of the highest block known to the block accumulator with sufficient
+    /// finality, whether or not it is stored locally.
+    pub(crate) async fn get_highest_usable_block_height(self) -> Option<u64>
+    where
+        REv: From<BlockAccumulatorRequest>,
+    {
+        self.make_request(
+            |responder| BlockAccumulatorRequest::GetHighestUsableBlockHeight { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Set a new stopping point for the node.
     ///
     /// Returns a potentially previously set stop-at spec.
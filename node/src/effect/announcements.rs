@@ -24,10 +24,14 @@ use crate::{
         gossiper::GossipItem,
         network::blocklist::BlocklistJustification,
         upgrade_watcher::NextUpgrade,
+        zk_prover::{EraTransitionProof, JobId, Proof, ProvingError},
     },
     effect::Responder,
     failpoints::FailpointActivation,
-    types::{Block, Deploy, DeployHash, FinalitySignature, FinalizedBlock, MetaBlock, NodeId},
+    reactor::DispatchTimesSummary,
+    types::{
+        Block, BlockHash, Deploy, DeployHash, FinalitySignature, FinalizedBlock, MetaBlock, NodeId,
+    },
     utils::Source,
 };
 
@@ -64,6 +68,11 @@ pub(crate) enum ControlAnnouncement {
         /// Responder called when the dump has been finished.
         finished: Responder<()>,
     },
+    /// The time spent dispatching each kind of event has been requested.
+    DispatchTimesRequest {
+        /// Responder to call with the time spent per component and event.
+        responder: Responder<DispatchTimesSummary>,
+    },
     /// Activates/deactivates a failpoint.
     ActivateFailpoint {
         /// The failpoint activation to process.
@@ -85,6 +94,9 @@ impl Debug for ControlAnnouncement {
             ControlAnnouncement::QueueDumpRequest { .. } => {
                 f.debug_struct("QueueDump").finish_non_exhaustive()
             }
+            ControlAnnouncement::DispatchTimesRequest { .. } => f
+                .debug_struct("DispatchTimesRequest")
+                .finish_non_exhaustive(),
             ControlAnnouncement::ActivateFailpoint { activation } => f
                 .debug_struct("ActivateFailpoint")
                 .field("activation", activation)
@@ -106,6 +118,9 @@ impl Display for ControlAnnouncement {
             ControlAnnouncement::QueueDumpRequest { .. } => {
                 write!(f, "dump event queue")
             }
+            ControlAnnouncement::DispatchTimesRequest { .. } => {
+                write!(f, "dump event dispatch times")
+            }
             ControlAnnouncement::ActivateFailpoint { activation } => {
                 write!(f, "failpoint activation: {}", activation)
             }
@@ -223,6 +238,13 @@ impl Display for DeployAcceptorAnnouncement {
 pub(crate) enum DeployBufferAnnouncement {
     /// Hashes of the deploys that expired.
     DeploysExpired(Vec<DeployHash>),
+    /// Pending deploys were replaced by a conflicting deploy with a higher payment.
+    DeploysReplaced {
+        /// Hashes of the replaced deploys.
+        replaced: Vec<DeployHash>,
+        /// Hash of the deploy that replaced them.
+        replacement: DeployHash,
+    },
 }
 
 impl Display for DeployBufferAnnouncement {
@@ -231,6 +253,15 @@ impl Display for DeployBufferAnnouncement {
             DeployBufferAnnouncement::DeploysExpired(hashes) => {
                 write!(f, "pruned hashes: {}", hashes.iter().join(", "))
             }
+            DeployBufferAnnouncement::DeploysReplaced {
+                replaced,
+                replacement,
+            } => write!(
+                f,
+                "replaced hashes: {} by {}",
+                replaced.iter().join(", "),
+                replacement
+            ),
         }
     }
 }
@@ -395,6 +426,15 @@ pub(crate) enum BlockAccumulatorAnnouncement {
     AcceptedNewFinalitySignature {
         finality_signature: Box<FinalitySignature>,
     },
+    /// A block has been held without its parent being known for longer than the configured
+    /// orphan timeout.
+    OrphanDetected {
+        block_hash: BlockHash,
+        parent_hash: BlockHash,
+    },
+    /// A block which had sufficient finality no longer has strict finality after signatures
+    /// invalidated by a validator matrix update were dropped.
+    SufficientFinalityLost { block_hash: BlockHash },
 }
 
 impl Display for BlockAccumulatorAnnouncement {
@@ -407,6 +447,19 @@ impl Display for BlockAccumulatorAnnouncement {
                     finality_signature.gossip_id()
                 )
             }
+            BlockAccumulatorAnnouncement::OrphanDetected {
+                block_hash,
+                parent_hash,
+            } => {
+                write!(
+                    f,
+                    "block {} is orphaned, missing parent {}",
+                    block_hash, parent_hash
+                )
+            }
+            BlockAccumulatorAnnouncement::SufficientFinalityLost { block_hash } => {
+                write!(f, "block {} lost sufficient finality", block_hash)
+            }
         }
     }
 }
@@ -446,3 +499,31 @@ impl Display for FetchedNewFinalitySignatureAnnouncement {
         )
     }
 }
+
+/// A zero-knowledge prover announcement.
+#[derive(Debug, Serialize)]
+pub(crate) enum ZkProverAnnouncement {
+    /// A proving job finished.
+    JobFinished {
+        /// The ID of the job.
+        job_id: JobId,
+        /// The generated proof, or the reason the job failed.
+        result: Result<Proof, ProvingError>,
+    },
+    /// The validators a switch block sets for the next era were proven.
+    EraTransitionProven(Box<EraTransitionProof>),
+}
+
+impl Display for ZkProverAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkProverAnnouncement::JobFinished { job_id, result } => match result {
+                Ok(_) => write!(f, "{} finished", job_id),
+                Err(error) => write!(f, "{} failed: {}", job_id, error),
+            },
+            ZkProverAnnouncement::EraTransitionProven(proof) => {
+                write!(f, "proved era transition of {}", proof.era_id)
+            }
+        }
+    }
+}
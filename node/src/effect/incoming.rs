@@ -97,6 +97,8 @@ pub(crate) enum NetRequest {
     SyncLeap(Vec<u8>),
     ApprovalsHashes(Vec<u8>),
     BlockExecutionResults(Vec<u8>),
+    LightClientUpdate(Vec<u8>),
+    BlockBodyOrChunk(Vec<u8>),
 }
 
 impl Display for NetRequest {
@@ -114,6 +116,8 @@ impl Display for NetRequest {
             NetRequest::BlockExecutionResults(_) => {
                 f.write_str("request for block execution results")
             }
+            NetRequest::LightClientUpdate(_) => f.write_str("request for light client update"),
+            NetRequest::BlockBodyOrChunk(_) => f.write_str("request for block body or chunk"),
         }
     }
 }
@@ -129,7 +133,9 @@ impl NetRequest {
             | NetRequest::FinalitySignature(ref id)
             | NetRequest::SyncLeap(ref id)
             | NetRequest::ApprovalsHashes(ref id)
-            | NetRequest::BlockExecutionResults(ref id) => id,
+            | NetRequest::BlockExecutionResults(ref id)
+            | NetRequest::LightClientUpdate(ref id)
+            | NetRequest::BlockBodyOrChunk(ref id) => id,
         };
         let mut unique_id = Vec::with_capacity(id.len() + 1);
         unique_id.push(self.tag() as u8);
@@ -149,6 +155,8 @@ impl NetRequest {
             NetRequest::SyncLeap(_) => Tag::SyncLeap,
             NetRequest::ApprovalsHashes(_) => Tag::ApprovalsHashes,
             NetRequest::BlockExecutionResults(_) => Tag::BlockExecutionResults,
+            NetRequest::LightClientUpdate(_) => Tag::LightClientUpdate,
+            NetRequest::BlockBodyOrChunk(_) => Tag::BlockBodyOrChunk,
         }
     }
 }
@@ -166,6 +174,8 @@ pub(crate) enum NetResponse {
     SyncLeap(Arc<[u8]>),
     ApprovalsHashes(Arc<[u8]>),
     BlockExecutionResults(Arc<[u8]>),
+    LightClientUpdate(Arc<[u8]>),
+    BlockBodyOrChunk(Arc<[u8]>),
 }
 
 // `NetResponse` uses `Arcs`, so we count all data as 0.
@@ -192,6 +202,8 @@ impl Display for NetResponse {
             NetResponse::BlockExecutionResults(_) => {
                 f.write_str("response for block execution results")
             }
+            NetResponse::LightClientUpdate(_) => f.write_str("response for light client update"),
+            NetResponse::BlockBodyOrChunk(_) => f.write_str("response for block body or chunk"),
         }
     }
 }
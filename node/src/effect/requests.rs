@@ -4,9 +4,10 @@
 //! top-level module documentation for details.
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
+    path::PathBuf,
     sync::Arc,
 };
 
@@ -14,6 +15,7 @@ use datasize::DataSize;
 use serde::Serialize;
 use smallvec::SmallVec;
 use static_assertions::const_assert;
+use tokio::sync::{broadcast, OwnedSemaphorePermit};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -22,29 +24,47 @@ use casper_execution_engine::{
         era_validators::GetEraValidatorsError,
         get_bids::{GetBidsRequest, GetBidsResult},
         query::{QueryRequest, QueryResult},
+        state_diff::StateDiffResult,
+        GasProfile,
     },
-    storage::trie::TrieRaw,
+    storage::{trie::TrieRaw, trie_store::gc::TrieGcOutcome},
 };
-use casper_hashing::Digest;
+use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{
-    bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Timestamp, Transfer, URef,
+    account::AccountHash, bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult,
+    Key, ProtocolVersion, PublicKey, TimeDiff, Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
     components::{
+        block_accumulator::{BlockAccumulatorDump, BlocksInRange},
         block_synchronizer::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, ProposedBlock, ValidatorChange},
-        contract_runtime::EraValidatorsRequest,
+        consensus::{
+            ClContext, EquivocationRecord, ProposedBlock, ScheduledRound, ValidatorChange,
+            ValidatorPerformanceReport,
+        },
+        contract_runtime::{EraValidatorsRequest, TrieGcError},
         deploy_acceptor,
+        deploy_buffer::StuckDeploy,
         diagnostics_port::StopAtSpec,
+        event_stream_server::SseData,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
-        network::NetworkInsights,
+        network::{NetworkInsights, PeerReputation, PeerTraffic},
+        randomness_beacon::BlockRandomness,
+        speculative_executor::SpeculativeExecutionError,
+        storage::{
+            AccountTransfer, CreateSnapshotError, PruneOutcome, StorageSnapshot,
+            StorageVerification, TransferPosition,
+        },
         upgrade_watcher::NextUpgrade,
+        zk_prover::{
+            EraTransitionProof, JobId, JobStatus, PersistedJob, Priority, ProvingJob, ProvingJobs,
+            SubmitJobError,
+        },
     },
     contract_runtime::{ContractRuntimeError, SpeculativeExecutionState},
     effect::{AutoClosingResponder, Responder},
@@ -52,11 +72,12 @@ use crate::{
     rpcs::docs::OpenRpcSchema,
     types::{
         appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlockState,
-        NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
+        BlockBodyOrChunk, BlockBodyOrChunkId, BlockExecutionResultsOrChunk,
+        BlockExecutionResultsOrChunkId, BlockHash, BlockHeader, BlockSignatures, BlockWithMetadata,
+        ChainspecRawBytes, Deploy, DeployHash, DeployHeader, DeployId, DeployMetadataExt,
+        DeployWithFinalizedApprovals, FinalitySignature, FinalitySignatureId, FinalizedApprovals,
+        FinalizedBlock, LegacyDeploy, MetaBlockState, NodeId, StatusFeed, TrieOrChunk,
+        TrieOrChunkId,
     },
     utils::{DisplayIter, Source},
 };
@@ -213,6 +234,28 @@ pub(crate) enum NetworkInfoRequest {
     Insight {
         responder: Responder<NetworkInsights>,
     },
+    /// Get the traffic exchanged with each peer.
+    PeerTraffic {
+        responder: Responder<Vec<PeerTraffic>>,
+    },
+    /// Get the reputations of all peers which committed offenses or were banned.
+    PeerReputations {
+        responder: Responder<Vec<PeerReputation>>,
+    },
+    /// Ban a peer by node ID.
+    BanPeer {
+        peer_id: NodeId,
+        /// How long the peer should be banned for.
+        duration: TimeDiff,
+        /// Responder to be called with the time at which the ban expires.
+        responder: Responder<Timestamp>,
+    },
+    /// Lift the ban of a peer.
+    UnbanPeer {
+        peer_id: NodeId,
+        /// Responder to be called with whether the peer was banned.
+        responder: Responder<bool>,
+    },
 }
 
 impl Display for NetworkInfoRequest {
@@ -230,6 +273,21 @@ impl Display for NetworkInfoRequest {
             NetworkInfoRequest::Insight { responder: _ } => {
                 formatter.write_str("get networking insights")
             }
+            NetworkInfoRequest::PeerTraffic { responder: _ } => {
+                formatter.write_str("get per-peer traffic")
+            }
+            NetworkInfoRequest::PeerReputations { responder: _ } => {
+                formatter.write_str("get peer reputations")
+            }
+            NetworkInfoRequest::BanPeer {
+                peer_id,
+                duration,
+                responder: _,
+            } => write!(formatter, "ban peer {} for {}", peer_id, duration),
+            NetworkInfoRequest::UnbanPeer {
+                peer_id,
+                responder: _,
+            } => write!(formatter, "unban peer {}", peer_id),
         }
     }
 }
@@ -279,6 +337,19 @@ pub(crate) enum StorageRequest {
         approvals_hashes: Box<ApprovalsHashes>,
         responder: Responder<bool>,
     },
+    /// Store a block along with its approvals hashes, if provided, and finality signatures, all
+    /// in a single transaction.
+    PutBlockAndApprovalsAndSignatures {
+        /// Block to be stored.
+        block: Arc<Block>,
+        /// Approvals hashes to store, if any.
+        approvals_hashes: Option<Box<ApprovalsHashes>>,
+        /// Finality signatures of the block to store.
+        signatures: BlockSignatures,
+        /// Responder to call with the result.  Returns true if all items were stored or merged
+        /// into the already stored ones.
+        responder: Responder<bool>,
+    },
     /// Store the block and approvals hashes.
     PutExecutedBlock {
         /// Block to be stored.
@@ -344,6 +415,15 @@ pub(crate) enum StorageRequest {
         /// local storage.
         responder: Responder<Option<BlockHeader>>,
     },
+    /// Reconstruct the validator weights of an era from the stored switch block of the era before
+    /// it.
+    GetEraValidatorWeights {
+        /// The era to get the validator weights of.
+        era_id: EraId,
+        /// Responder to call with the result.  Returns `None` if the weights can't be
+        /// reconstructed from local storage.
+        responder: Responder<Option<BTreeMap<PublicKey, U512>>>,
+    },
     /// Retrieve all transfers in a block with given hash.
     GetBlockTransfers {
         /// Hash of block to get transfers of.
@@ -372,6 +452,107 @@ pub(crate) enum StorageRequest {
         deploy_hash: DeployHash,
         responder: Responder<Option<LegacyDeploy>>,
     },
+    /// Retrieve the hashes of all stored deploys sent by the given account.
+    GetDeployHashesByAccount {
+        /// The public key of the account.
+        account: PublicKey,
+        /// Responder to call with the result.
+        responder: Responder<Vec<DeployHash>>,
+    },
+    /// Retrieve the stored transfers from or to the given account, most recent first.
+    GetTransfersByAccount {
+        /// The hash of the account.
+        account_hash: AccountHash,
+        /// The position of the transfer after which to start, or `None` to start with the most
+        /// recent one.
+        after: Option<TransferPosition>,
+        /// The maximum number of transfers to retrieve.
+        max_count: usize,
+        /// Responder to call with the result.
+        responder: Responder<Vec<AccountTransfer>>,
+    },
+    /// Store a record of an observed equivocation.
+    PutEquivocation {
+        /// The equivocation record.
+        record: Box<EquivocationRecord>,
+        /// Responder to call with the result.  Contains `false` if the validator's equivocation in
+        /// that era was already recorded.
+        responder: Responder<bool>,
+    },
+    /// Retrieve the records of all stored equivocations, optionally filtered.
+    GetEquivocations {
+        /// Only records of equivocations in this era or later are returned.
+        from_era_id: EraId,
+        /// If set, only records of equivocations by this validator are returned.
+        validator: Option<PublicKey>,
+        /// Responder to call with the result.
+        responder: Responder<Vec<EquivocationRecord>>,
+    },
+    /// Store the report of the validators' performance in an era, replacing any earlier report
+    /// for that era.
+    PutValidatorPerformance {
+        /// The performance report.
+        report: Box<ValidatorPerformanceReport>,
+        /// Responder to call with the result.
+        responder: Responder<()>,
+    },
+    /// Retrieve the report of the validators' performance in an era.
+    GetValidatorPerformance {
+        /// The era.
+        era_id: EraId,
+        /// Responder to call with the result.  Returns `None` if there is no report for that era.
+        responder: Responder<Option<ValidatorPerformanceReport>>,
+    },
+    /// Store a chunk of a partially fetched trie.
+    PutTrieChunk {
+        /// The chunk, along with the proof identifying its trie and index.
+        chunk: Box<ChunkWithProof>,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Retrieve the stored chunks of a partially fetched trie.
+    GetTrieChunks {
+        /// The hash of the trie.
+        trie_hash: Digest,
+        /// Responder to call with the result.  The chunks are ordered by index.
+        responder: Responder<Vec<ChunkWithProof>>,
+    },
+    /// Delete the stored chunks of a trie.
+    DeleteTrieChunks {
+        /// The hash of the trie.
+        trie_hash: Digest,
+        /// Responder to call when done deleting.
+        responder: Responder<()>,
+    },
+    /// Store the proof of the validators set by the switch block of an era, replacing any earlier
+    /// proof for that era.
+    PutEraTransitionProof {
+        /// The proof.
+        proof: Box<EraTransitionProof>,
+        /// Responder to call with the result.
+        responder: Responder<()>,
+    },
+    /// Retrieve the proof of the validators set by the switch block of an era.
+    GetEraTransitionProof {
+        /// The era.
+        era_id: EraId,
+        /// Responder to call with the result.  Returns `None` if there is no proof for that era.
+        responder: Responder<Option<EraTransitionProof>>,
+    },
+    /// Store a queued or running proving job, so that it is resumed after a restart.
+    PutProvingJob {
+        /// The job.
+        job: Box<PersistedJob>,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Delete a stored proving job.
+    DeleteProvingJob {
+        /// The ID of the job.
+        job_id: JobId,
+        /// Responder to call when done deleting.
+        responder: Responder<()>,
+    },
     /// Retrieve deploy with given ID.
     GetDeploy {
         deploy_id: DeployId,
@@ -400,6 +581,17 @@ pub(crate) enum StorageRequest {
         block_hash: BlockHash,
         responder: Responder<Option<Vec<(DeployHash, DeployHeader, ExecutionResult)>>>,
     },
+    /// Retrieve the execution results of all deploys and transfers of a block, in the order they
+    /// are listed in the block's body.
+    ///
+    /// Unlike `GetExecutionResults`, this does not require the deploys themselves to be stored.
+    GetExecutionResultsForBlock {
+        /// Hash of the block.
+        block_hash: BlockHash,
+        /// Responder to call with the result.  Returns `None` if the block or the execution
+        /// result of any of its deploys is not stored.
+        responder: Responder<Option<Vec<(DeployHash, ExecutionResult)>>>,
+    },
     GetBlockExecutionResultsOrChunk {
         /// Request ID.
         id: BlockExecutionResultsOrChunkId,
@@ -407,6 +599,14 @@ pub(crate) enum StorageRequest {
         /// None is returned when we don't have the block in the storage.
         responder: Responder<Option<BlockExecutionResultsOrChunk>>,
     },
+    /// Retrieve the body of a block, or a chunk of it if the body is too large to be sent whole.
+    GetBlockBodyOrChunk {
+        /// Request ID.
+        id: BlockBodyOrChunkId,
+        /// Responder to call with the block body or chunk.
+        /// None is returned when we don't have the block in the storage.
+        responder: Responder<Option<BlockBodyOrChunk>>,
+    },
     /// Retrieve deploy and its metadata.
     GetDeployAndMetadata {
         /// Hash of deploy to be retrieved.
@@ -486,6 +686,22 @@ pub(crate) enum StorageRequest {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Retrieve the height of the lowest stored block with the given state root hash as its
+    /// post-state.
+    GetBlockHeightForStateRootHash {
+        /// The state root hash.
+        state_root_hash: Digest,
+        /// Responder to call with the result.  Returns `None` if no such block header exists in
+        /// local storage.
+        responder: Responder<Option<u64>>,
+    },
+    /// Retrieve the state root hashes of all stored blocks at or above the given height.
+    GetStateRootHashesFromHeight {
+        /// The lowest block height to include.
+        height: u64,
+        /// Responder to call with the result.
+        responder: Responder<BTreeSet<Digest>>,
+    },
     /// Store a set of finalized approvals for a specific deploy.
     StoreFinalizedApprovals {
         /// The deploy hash to store the finalized approvals for.
@@ -498,6 +714,35 @@ pub(crate) enum StorageRequest {
     },
     /// Retrieve the height of the final block of the previous protocol version, if known.
     GetKeyBlockHeightForActivationPoint { responder: Responder<Option<u64>> },
+    /// Prune the bodies, deploys and execution results of blocks below the given height.
+    PruneBlocks {
+        /// The height below which to prune.
+        below_height: u64,
+        /// Responder to call with the outcome.
+        responder: Responder<PruneOutcome>,
+    },
+    /// Record that the global state of blocks below the given height may be garbage collected,
+    /// removing them from the available block range.
+    MarkGlobalStatePrunedBelow {
+        /// The height below which global state may be garbage collected.
+        height: u64,
+        /// Responder to call with the resulting height, which never decreases.
+        responder: Responder<u64>,
+    },
+    /// Create a consistent copy of all storage databases in a new directory.
+    CreateSnapshot {
+        /// The directory to create the snapshot in.
+        path: PathBuf,
+        /// Responder to call with the snapshot, or the reason it could not be created.
+        responder: Responder<Result<StorageSnapshot, CreateSnapshotError>>,
+    },
+    /// Verify the integrity of all stored blocks.
+    VerifyIntegrity {
+        /// Whether to repair the inconsistencies found.
+        repair: bool,
+        /// Responder to call with the inconsistencies found.
+        responder: Responder<StorageVerification>,
+    },
 }
 
 impl Display for StorageRequest {
@@ -533,6 +778,9 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockHeaderByHeight { block_height, .. } => {
                 write!(formatter, "get header for height {}", block_height)
             }
+            StorageRequest::GetEraValidatorWeights { era_id, .. } => {
+                write!(formatter, "get validator weights for era {}", era_id)
+            }
             StorageRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers for {}", block_hash)
             }
@@ -543,6 +791,50 @@ impl Display for StorageRequest {
             StorageRequest::GetLegacyDeploy { deploy_hash, .. } => {
                 write!(formatter, "get legacy deploy {}", deploy_hash)
             }
+            StorageRequest::GetDeployHashesByAccount { account, .. } => {
+                write!(formatter, "get deploy hashes sent by {}", account)
+            }
+            StorageRequest::GetTransfersByAccount { account_hash, .. } => {
+                write!(formatter, "get transfers from or to {}", account_hash)
+            }
+            StorageRequest::PutEquivocation { record, .. } => write!(
+                formatter,
+                "put equivocation by {} in {}",
+                record.validator, record.era_id
+            ),
+            StorageRequest::GetEquivocations { from_era_id, .. } => {
+                write!(formatter, "get equivocations from {}", from_era_id)
+            }
+            StorageRequest::PutValidatorPerformance { report, .. } => {
+                write!(formatter, "put validator performance in {}", report.era_id)
+            }
+            StorageRequest::GetValidatorPerformance { era_id, .. } => {
+                write!(formatter, "get validator performance in {}", era_id)
+            }
+            StorageRequest::PutTrieChunk { chunk, .. } => write!(
+                formatter,
+                "put chunk {} of trie {}",
+                chunk.proof().index(),
+                chunk.proof().root_hash()
+            ),
+            StorageRequest::GetTrieChunks { trie_hash, .. } => {
+                write!(formatter, "get chunks of trie {}", trie_hash)
+            }
+            StorageRequest::DeleteTrieChunks { trie_hash, .. } => {
+                write!(formatter, "delete chunks of trie {}", trie_hash)
+            }
+            StorageRequest::PutEraTransitionProof { proof, .. } => {
+                write!(formatter, "put era transition proof for {}", proof.era_id)
+            }
+            StorageRequest::GetEraTransitionProof { era_id, .. } => {
+                write!(formatter, "get era transition proof for {}", era_id)
+            }
+            StorageRequest::PutProvingJob { job, .. } => {
+                write!(formatter, "put {}", job.job.job_id())
+            }
+            StorageRequest::DeleteProvingJob { job_id, .. } => {
+                write!(formatter, "delete {}", job_id)
+            }
             StorageRequest::GetDeploy { deploy_id, .. } => {
                 write!(formatter, "get deploy {}", deploy_id)
             }
@@ -555,9 +847,19 @@ impl Display for StorageRequest {
             StorageRequest::GetExecutionResults { block_hash, .. } => {
                 write!(formatter, "get execution results for {}", block_hash)
             }
+            StorageRequest::GetExecutionResultsForBlock { block_hash, .. } => {
+                write!(
+                    formatter,
+                    "get all execution results for block {}",
+                    block_hash
+                )
+            }
             StorageRequest::GetBlockExecutionResultsOrChunk { id, .. } => {
                 write!(formatter, "get block execution results or chunk for {}", id)
             }
+            StorageRequest::GetBlockBodyOrChunk { id, .. } => {
+                write!(formatter, "get {}", id)
+            }
 
             StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
                 write!(formatter, "get deploy and metadata for {}", deploy_hash)
@@ -608,18 +910,53 @@ impl Display for StorageRequest {
             StorageRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range",)
             }
+            StorageRequest::GetBlockHeightForStateRootHash {
+                state_root_hash, ..
+            } => {
+                write!(
+                    formatter,
+                    "get block height for state root {}",
+                    state_root_hash
+                )
+            }
+            StorageRequest::GetStateRootHashesFromHeight { height, .. } => {
+                write!(formatter, "get state root hashes from height {}", height)
+            }
             StorageRequest::StoreFinalizedApprovals { deploy_hash, .. } => {
                 write!(formatter, "finalized approvals for deploy {}", deploy_hash)
             }
             StorageRequest::PutExecutedBlock { block, .. } => {
                 write!(formatter, "put executed block {}", block.hash(),)
             }
+            StorageRequest::PutBlockAndApprovalsAndSignatures { block, .. } => {
+                write!(
+                    formatter,
+                    "put block {} with approvals hashes and signatures",
+                    block.hash()
+                )
+            }
             StorageRequest::GetKeyBlockHeightForActivationPoint { .. } => {
                 write!(
                     formatter,
                     "get key block height for current activation point"
                 )
             }
+            StorageRequest::PruneBlocks { below_height, .. } => {
+                write!(formatter, "prune blocks below height {}", below_height)
+            }
+            StorageRequest::MarkGlobalStatePrunedBelow { height, .. } => {
+                write!(
+                    formatter,
+                    "mark global state pruned below height {}",
+                    height
+                )
+            }
+            StorageRequest::CreateSnapshot { path, .. } => {
+                write!(formatter, "create snapshot in {}", path.display())
+            }
+            StorageRequest::VerifyIntegrity { repair, .. } => {
+                write!(formatter, "verify integrity (repair: {})", repair)
+            }
         }
     }
 }
@@ -664,6 +1001,9 @@ pub(crate) enum DeployBufferRequest {
         timestamp: Timestamp,
         responder: Responder<AppendableBlock>,
     },
+    GetStuckDeploys {
+        responder: Responder<Vec<StuckDeploy>>,
+    },
 }
 
 impl Display for DeployBufferRequest {
@@ -676,6 +1016,9 @@ impl Display for DeployBufferRequest {
                     timestamp
                 )
             }
+            DeployBufferRequest::GetStuckDeploys { .. } => {
+                write!(formatter, "request for stuck deploys")
+            }
         }
     }
 }
@@ -755,6 +1098,13 @@ pub(crate) enum RpcRequest {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Subscribe to the events pushed to WebSocket and gRPC subscribers, for a request waiting for
+    /// a deploy to be processed.
+    SubscribeNotifications {
+        /// Responder to call with the receiver of the events, along with a permit to be held while
+        /// waiting, or `None` if the maximum number of waiting requests has been reached.
+        responder: Responder<Option<(broadcast::Receiver<Arc<SseData>>, OwnedSemaphorePermit)>>,
+    },
 }
 
 impl Display for RpcRequest {
@@ -805,6 +1155,9 @@ impl Display for RpcRequest {
             RpcRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range")
             }
+            RpcRequest::SubscribeNotifications { .. } => {
+                write!(formatter, "subscribe notifications")
+            }
         }
     }
 }
@@ -865,6 +1218,18 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the query result.
         responder: Responder<Result<QueryResult, engine_state::Error>>,
     },
+    /// A query request against the global state at an arbitrary, possibly historical, state root.
+    QueryWithProofAtRoot {
+        /// The state root hash to query against.
+        state_root: Digest,
+        /// The key to query.
+        key: Key,
+        /// The path of named keys to follow from the key.
+        path: Vec<String>,
+        /// Responder to call with the query result, including the Merkle proof of the value.
+        /// Returns `QueryResult::RootNotFound` if the state root is not retained in global state.
+        responder: Responder<Result<QueryResult, engine_state::Error>>,
+    },
     /// A balance request.
     GetBalance {
         /// Balance request.
@@ -916,14 +1281,28 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result. Contains the hash of the stored trie.
         responder: Responder<Result<Digest, engine_state::Error>>,
     },
-    /// Execute deploys without commiting results
-    SpeculativeDeployExecution {
-        /// Hash of a block on top of which to execute the deploy.
-        execution_prestate: SpeculativeExecutionState,
-        /// Deploy to execute.
-        deploy: Arc<Deploy>,
-        /// Results
-        responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
+    /// Get the gas profile of the most recent execution of a deploy.
+    GetDeployGasProfile {
+        /// Hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result.  Contains `None` if no gas profile is kept.
+        responder: Responder<Option<GasProfile>>,
+    },
+    /// Get the changes of global state between two state root hashes.
+    GetStateDiff {
+        /// The state root hash to diff from.
+        from_root: Digest,
+        /// The state root hash to diff to.
+        to_root: Digest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, engine_state::Error>>,
+    },
+    /// Collect the global state tries no longer reachable from the state of any retained block.
+    CollectTrieGarbage {
+        /// Whether to only report what would be collected, without deleting anything.
+        dry_run: bool,
+        /// Responder to call with the result.
+        responder: Responder<Result<TrieGcOutcome, TrieGcError>>,
     },
 }
 
@@ -938,6 +1317,16 @@ impl Display for ContractRuntimeRequest {
             ContractRuntimeRequest::Query { query_request, .. } => {
                 write!(formatter, "query request: {:?}", query_request)
             }
+            ContractRuntimeRequest::QueryWithProofAtRoot {
+                state_root,
+                key,
+                path,
+                ..
+            } => write!(
+                formatter,
+                "query {} at path {:?} with proof at state root {}",
+                key, path, state_root
+            ),
             ContractRuntimeRequest::GetBalance {
                 balance_request, ..
             } => write!(formatter, "balance request: {:?}", balance_request),
@@ -967,17 +1356,18 @@ impl Display for ContractRuntimeRequest {
             ContractRuntimeRequest::PutTrie { trie_bytes, .. } => {
                 write!(formatter, "trie: {:?}", trie_bytes)
             }
-            ContractRuntimeRequest::SpeculativeDeployExecution {
-                execution_prestate,
-                deploy,
-                ..
-            } => {
-                write!(
-                    formatter,
-                    "Execute {} on {}",
-                    deploy.hash(),
-                    execution_prestate.state_root_hash
-                )
+            ContractRuntimeRequest::GetDeployGasProfile { deploy_hash, .. } => {
+                write!(formatter, "get gas profile of deploy {}", deploy_hash)
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                from_root, to_root, ..
+            } => write!(
+                formatter,
+                "get state diff from {} to {}",
+                from_root, to_root
+            ),
+            ContractRuntimeRequest::CollectTrieGarbage { dry_run, .. } => {
+                write!(formatter, "collect trie garbage, dry run: {}", dry_run)
             }
         }
     }
@@ -1071,6 +1461,8 @@ pub(crate) enum ConsensusRequest {
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
     /// Request for a list of validator status changes, by public key.
     ValidatorChanges(Responder<BTreeMap<PublicKey, Vec<(EraId, ValidatorChange)>>>),
+    /// Request for the current era and the leaders of its upcoming rounds.
+    LeaderSchedule(Responder<Option<(EraId, Vec<ScheduledRound<PublicKey>>)>>),
 }
 
 /// ChainspecLoader component requests.
@@ -1117,6 +1509,21 @@ pub(crate) enum BlockAccumulatorRequest {
         block_hash: BlockHash,
         responder: Responder<Option<Vec<NodeId>>>,
     },
+    GetPeerQuality {
+        peers: Vec<NodeId>,
+        responder: Responder<BTreeMap<NodeId, i64>>,
+    },
+    GetBlocksInRange {
+        start_height: u64,
+        end_height: u64,
+        responder: Responder<BlocksInRange>,
+    },
+    GetDump {
+        responder: Responder<BlockAccumulatorDump>,
+    },
+    GetHighestUsableBlockHeight {
+        responder: Responder<Option<u64>>,
+    },
 }
 
 impl Display for BlockAccumulatorRequest {
@@ -1125,6 +1532,20 @@ impl Display for BlockAccumulatorRequest {
             BlockAccumulatorRequest::GetPeersForBlock { block_hash, .. } => {
                 write!(f, "get peers for {}", block_hash)
             }
+            BlockAccumulatorRequest::GetPeerQuality { peers, .. } => {
+                write!(f, "get quality of {} peers", peers.len())
+            }
+            BlockAccumulatorRequest::GetBlocksInRange {
+                start_height,
+                end_height,
+                ..
+            } => {
+                write!(f, "get blocks in range [{}, {}]", start_height, end_height)
+            }
+            BlockAccumulatorRequest::GetDump { .. } => write!(f, "get block accumulator dump"),
+            BlockAccumulatorRequest::GetHighestUsableBlockHeight { .. } => {
+                write!(f, "get highest usable block height")
+            }
         }
     }
 }
@@ -1199,3 +1620,96 @@ impl Display for AcceptDeployRequest {
         }
     }
 }
+
+/// A request to execute a deploy without committing its effects.
+#[derive(Debug, Serialize)]
+#[must_use]
+pub(crate) struct SpeculativeExecutionRequest {
+    /// The state on top of which to execute the deploy.
+    pub(crate) execution_prestate: SpeculativeExecutionState,
+    /// The deploy to execute.
+    pub(crate) deploy: Arc<Deploy>,
+    /// Responder to call with the execution result.
+    pub(crate) responder: Responder<Result<Option<ExecutionResult>, SpeculativeExecutionError>>,
+}
+
+impl Display for SpeculativeExecutionRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "execute {} on {}",
+            self.deploy.hash(),
+            self.execution_prestate.state_root_hash
+        )
+    }
+}
+
+/// A request to the zero-knowledge prover.
+#[derive(Debug)]
+#[must_use]
+pub(crate) enum ZkProverRequest {
+    /// Queue a proving job, unless the same job is queued, running or finished already.
+    SubmitJob {
+        /// The job to run.
+        job: Box<ProvingJob>,
+        /// The priority of the job.
+        priority: Priority,
+        /// Responder to call with the status of the job.
+        responder: Responder<Result<JobStatus, SubmitJobError>>,
+    },
+    /// Return the status of a job, or `None` if it is unknown.
+    GetJobStatus {
+        /// The ID of the job.
+        job_id: JobId,
+        /// Responder to call with the status of the job.
+        responder: Responder<Option<JobStatus>>,
+    },
+    /// Return the queued and running jobs.
+    ListJobs {
+        /// Responder to call with the jobs.
+        responder: Responder<ProvingJobs>,
+    },
+    /// Cancel a queued or running job, and remove it from storage.
+    CancelJob {
+        /// The ID of the job.
+        job_id: JobId,
+        /// Responder to call with whether there was a queued or running job with that ID.
+        responder: Responder<bool>,
+    },
+}
+
+impl Display for ZkProverRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkProverRequest::SubmitJob { job, priority, .. } => {
+                write!(f, "submit {} with priority {:?}", job.job_id(), priority)
+            }
+            ZkProverRequest::GetJobStatus { job_id, .. } => write!(f, "get status of {}", job_id),
+            ZkProverRequest::ListJobs { .. } => write!(f, "list jobs"),
+            ZkProverRequest::CancelJob { job_id, .. } => write!(f, "cancel {}", job_id),
+        }
+    }
+}
+
+/// A request to the randomness beacon.
+#[derive(Debug)]
+#[must_use]
+pub(crate) enum RandomnessBeaconRequest {
+    /// Return the random output derived for a block, or `None` if the beacon is disabled.
+    GetBlockRandomness {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// Responder to call with the random output.
+        responder: Responder<Option<BlockRandomness>>,
+    },
+}
+
+impl Display for RandomnessBeaconRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RandomnessBeaconRequest::GetBlockRandomness { block_hash, .. } => {
+                write!(f, "get randomness of {}", block_hash)
+            }
+        }
+    }
+}
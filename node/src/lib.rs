@@ -25,6 +25,7 @@
 pub mod cli;
 pub(crate) mod components;
 mod config_migration;
+mod config_reload;
 mod data_migration;
 pub(crate) mod effect;
 #[cfg_attr(not(feature = "failpoints"), path = "failpoints_disabled.rs")]
@@ -33,6 +34,7 @@ pub(crate) mod failpoints;
 pub mod logging;
 pub(crate) mod protocol;
 pub(crate) mod reactor;
+pub mod telemetry;
 #[cfg(test)]
 pub(crate) mod testing;
 pub(crate) mod tls;
@@ -48,7 +50,10 @@ use ansi_term::Color::Red;
 use once_cell::sync::Lazy;
 #[cfg(not(test))]
 use rand::SeedableRng;
-use signal_hook::{consts::TERM_SIGNALS, flag};
+use signal_hook::{
+    consts::{SIGHUP, TERM_SIGNALS},
+    flag,
+};
 use tracing::warn;
 
 pub(crate) use components::{
@@ -64,9 +69,14 @@ pub(crate) use components::{
     fetcher::Config as FetcherConfig,
     gossiper::Config as GossipConfig,
     network::Config as NetworkConfig,
+    proof_relayer::Config as ProofRelayerConfig,
+    randomness_beacon::Config as RandomnessBeaconConfig,
     rest_server::Config as RestServerConfig,
-    rpc_server::{Config as RpcServerConfig, SpeculativeExecConfig},
+    rpc_server::{Config as RpcServerConfig, GrpcConfig, SpeculativeExecConfig},
+    speculative_executor::Config as SpeculativeExecutorConfig,
+    sync_leaper::Config as SyncLeaperConfig,
     upgrade_watcher::Config as UpgradeWatcherConfig,
+    zk_prover::Config as ZkProverConfig,
 };
 pub use components::{
     consensus, contract_runtime,
@@ -116,6 +126,8 @@ pub(crate) static TERMINATION_REQUESTED: Lazy<Arc<AtomicUsize>> =
     Lazy::new(|| Arc::new(AtomicUsize::new(0)));
 
 /// Setup UNIX signal hooks for current application.
+///
+/// `SIGHUP` requests a reload of the config rather than termination.
 pub(crate) fn setup_signal_hooks() {
     for signal in TERM_SIGNALS {
         flag::register_usize(
@@ -125,6 +137,8 @@ pub(crate) fn setup_signal_hooks() {
         )
         .unwrap_or_else(|error| panic!("failed to register signal {}: {}", signal, error));
     }
+    flag::register(SIGHUP, Arc::clone(&*config_reload::RELOAD_REQUESTED))
+        .unwrap_or_else(|error| panic!("failed to register signal {}: {}", SIGHUP, error));
 }
 
 /// Constructs a new `NodeRng`.
@@ -6,32 +6,53 @@ use ansi_term::{Color, Style};
 use anyhow::anyhow;
 use datasize::DataSize;
 use once_cell::sync::OnceCell;
+use opentelemetry::sdk::trace::Tracer;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
 use smallvec::SmallVec;
 use tracing::{
     field::{Field, Visit},
     Event, Level, Subscriber,
 };
+use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{
     fmt::{
-        format::{self, FieldFn, Format, Json, JsonFields, Writer},
+        format::{self, DefaultFields, FieldFn, Format, Json, JsonFields, Writer},
         time::{FormatTime, SystemTime},
         FmtContext, FormatEvent, FormatFields, FormattedFields, Layer,
     },
-    layer::Layered,
+    layer::{Layered, SubscriberExt},
     registry::LookupSpan,
     reload::{self, Handle},
+    util::SubscriberInitExt,
     EnvFilter, Registry,
 };
 
+use crate::{
+    reactor,
+    telemetry::{self, TelemetryConfig},
+};
+
 const LOG_CONFIGURATION_ENVVAR: &str = "RUST_LOG";
 
+const DEFAULT_LOG_FILTER: &str = "warn,casper_node=info";
+
 const LOG_FIELD_MESSAGE: &str = "message";
 const LOG_FIELD_TARGET: &str = "log.target";
 const LOG_FIELD_MODULE: &str = "log.module_path";
 const LOG_FIELD_FILE: &str = "log.file";
 const LOG_FIELD_LINE: &str = "log.line";
 
+const LOG_FIELD_BLOCK_HASH: &str = "block_hash";
+const LOG_FIELD_DEPLOY_HASH: &str = "deploy_hash";
+const LOG_FIELD_PEER_ID: &str = "peer_id";
+/// Fields whose values are normalized in the structured JSON format.
+const LOG_ID_FIELDS: [&str; 3] = [
+    LOG_FIELD_BLOCK_HASH,
+    LOG_FIELD_DEPLOY_HASH,
+    LOG_FIELD_PEER_ID,
+];
+
 /// Global reload handle.
 ///
 /// We use a static variable for the reload handle since our logger instance is also global.
@@ -55,6 +76,13 @@ pub struct LoggingConfig {
     /// If set, human-readable formats will abbreviate module names, `foo::bar::baz::bizz` will
     /// turn into `f:b:b:bizz`.
     pub abbreviate_modules: bool,
+
+    /// Log filter directives, e.g. `warn,casper_node=info`.
+    ///
+    /// Ignored if the `RUST_LOG` environment variable is set.  Applied again when the config is
+    /// reloaded.
+    #[serde(default)]
+    pub filter: Option<String>,
 }
 
 impl LoggingConfig {
@@ -65,8 +93,18 @@ impl LoggingConfig {
             format,
             color,
             abbreviate_modules,
+            filter: None,
         }
     }
+
+    /// Returns the log filter directives, with those set in the `RUST_LOG` environment variable
+    /// taking precedence over the configured ones.
+    fn filter_directives(&self) -> String {
+        env::var(LOG_CONFIGURATION_ENVVAR)
+            .ok()
+            .or_else(|| self.filter.clone())
+            .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string())
+    }
 }
 
 /// Logging output format.
@@ -79,6 +117,13 @@ pub enum LoggingFormat {
     Text,
     /// JSON format.
     Json,
+    /// Flat JSON format for log aggregation systems.
+    ///
+    /// Each line carries the correlation ID of the reactor event it was logged for, and the fields
+    /// holding block hashes, deploy hashes and peer IDs are given consistent names and values, so
+    /// that the journey of e.g. a deploy can be followed across components.
+    #[serde(rename = "structured_json")]
+    StructuredJson,
 }
 
 impl Default for LoggingFormat {
@@ -148,6 +193,146 @@ impl Visit for FieldVisitor {
     fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
 }
 
+/// Fields named differently across components which hold the same kind of ID, along with the name
+/// they are logged under in the structured JSON format.
+const NORMALIZED_FIELD_NAMES: [(&str, &str); 4] = [
+    ("block_header_hash", LOG_FIELD_BLOCK_HASH),
+    ("node_id", LOG_FIELD_PEER_ID),
+    ("peer", LOG_FIELD_PEER_ID),
+    ("sender", LOG_FIELD_PEER_ID),
+];
+
+/// Prefixes and suffixes added by the `Display` and `Debug` implementations of the ID types, which
+/// are stripped from the values of ID fields in the structured JSON format.
+const ID_VALUE_PREFIXES: [&str; 6] = [
+    "block hash ",
+    "BlockHash(",
+    "deploy-hash(",
+    "DeployHash(",
+    "tls:",
+    "NodeId(",
+];
+
+/// Returns the name a field is logged under in the structured JSON format.
+fn normalize_field_name(name: &str) -> &str {
+    NORMALIZED_FIELD_NAMES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, normalized)| normalized)
+}
+
+/// Strips the type names from the value of an ID field, leaving the hex-encoded ID.
+fn normalize_id_value(value: &str) -> &str {
+    ID_VALUE_PREFIXES
+        .iter()
+        .find_map(|prefix| value.strip_prefix(prefix))
+        .map_or(value, |stripped| stripped.trim_end_matches(')'))
+}
+
+/// Formats tracing events as flat JSON objects, see `LoggingFormat::StructuredJson`.
+pub struct StructuredJsonEvent;
+
+// Collects the fields of an event, normalizing their names and the values of ID fields.
+#[derive(Default)]
+struct StructuredFieldVisitor {
+    message: Option<String>,
+    target: Option<String>,
+    fields: Map<String, JsonValue>,
+}
+
+impl StructuredFieldVisitor {
+    fn insert(&mut self, field: &Field, value: JsonValue) {
+        match field.name() {
+            LOG_FIELD_MESSAGE => self.message = value.as_str().map(str::to_string),
+            LOG_FIELD_TARGET => self.target = value.as_str().map(str::to_string),
+            LOG_FIELD_MODULE | LOG_FIELD_FILE | LOG_FIELD_LINE => (),
+            name => {
+                let normalized = normalize_field_name(name);
+                let value = match value {
+                    JsonValue::String(string) if LOG_ID_FIELDS.contains(&normalized) => {
+                        JsonValue::from(normalize_id_value(&string))
+                    }
+                    value => value,
+                };
+                // Keep the original name if another field was already normalized to the same one.
+                let name = if self.fields.contains_key(normalized) {
+                    name
+                } else {
+                    normalized
+                };
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for StructuredFieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, JsonValue::from(value))
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, JsonValue::from(value))
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, JsonValue::from(value))
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, JsonValue::from(value))
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, JsonValue::from(value))
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, JsonValue::from(format!("{:?}", value)))
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for StructuredJsonEvent
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+
+        let mut visitor = StructuredFieldVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+
+        let mut line = Map::new();
+        line.insert("timestamp".to_string(), JsonValue::from(timestamp));
+        line.insert("level".to_string(), JsonValue::from(meta.level().as_str()));
+        line.insert(
+            "target".to_string(),
+            JsonValue::from(visitor.target.as_deref().unwrap_or(meta.target())),
+        );
+        if let Some(message) = visitor.message {
+            line.insert("message".to_string(), JsonValue::from(message));
+        }
+        if let Some(correlation_id) = reactor::current_correlation_id() {
+            line.insert(
+                "correlation_id".to_string(),
+                JsonValue::from(correlation_id.get()),
+            );
+        }
+        line.insert("fields".to_string(), JsonValue::Object(visitor.fields));
+
+        let encoded = serde_json::to_string(&line).map_err(|_| fmt::Error)?;
+        writeln!(writer, "{}", encoded)
+    }
+}
+
 impl<S, N> FormatEvent<S, N> for FmtEvent
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
@@ -275,6 +460,10 @@ pub enum ReloadHandle {
     Text(Handle<EnvFilter, Layered<Layer<Registry, FieldFn<FormatDebugFn>, FmtEvent>, Registry>>),
     /// JSON-logger reload handle.
     Json(Handle<EnvFilter, Layered<Layer<Registry, JsonFields, Format<Json>>, Registry>>),
+    /// Structured JSON-logger reload handle.
+    StructuredJson(
+        Handle<EnvFilter, Layered<Layer<Registry, DefaultFields, StructuredJsonEvent>, Registry>>,
+    ),
 }
 
 impl ReloadHandle {
@@ -283,6 +472,7 @@ impl ReloadHandle {
         match self {
             ReloadHandle::Text(handle) => handle.reload(new_filter),
             ReloadHandle::Json(handle) => handle.reload(new_filter),
+            ReloadHandle::StructuredJson(handle) => handle.reload(new_filter),
         }
     }
 
@@ -291,6 +481,9 @@ impl ReloadHandle {
         match self {
             ReloadHandle::Text(handle) => handle.with_current(|env_filter| env_filter.to_string()),
             ReloadHandle::Json(handle) => handle.with_current(|env_filter| env_filter.to_string()),
+            ReloadHandle::StructuredJson(handle) => {
+                handle.with_current(|env_filter| env_filter.to_string())
+            }
         }
     }
 }
@@ -305,6 +498,20 @@ pub fn reload_global_env_filter(new_filter: EnvFilter) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Swaps out the global [`EnvFilter`] for the one given in a reloaded config.
+///
+/// Fails without changing the filter if the `RUST_LOG` environment variable is set, or if the
+/// configured directives are invalid.
+pub fn reload_global_env_filter_from_config(config: &LoggingConfig) -> anyhow::Result<()> {
+    if env::var_os(LOG_CONFIGURATION_ENVVAR).is_some() {
+        return Err(anyhow!(
+            "log filter is set by the {} environment variable",
+            LOG_CONFIGURATION_ENVVAR
+        ));
+    }
+    reload_global_env_filter(EnvFilter::try_new(config.filter_directives())?)
+}
+
 /// Returns a string representation of the current global [`EnvFilter`], if set.
 pub fn display_global_env_filter() -> anyhow::Result<String> {
     let handle = RELOAD_HANDLE
@@ -330,7 +537,23 @@ fn format_into_debug_writer(
     }
 }
 
-/// Initializes the logging system.
+/// Returns a layer exporting spans via the given tracer, if any.
+fn telemetry_layer<S>(tracer: Option<Tracer>) -> Option<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Initializes the logging system without exporting spans to an OpenTelemetry collector.
+///
+/// See `init_with_telemetry` for details.
+pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
+    init_with_telemetry(config, &TelemetryConfig::default())
+}
+
+/// Initializes the logging system, along with the export of spans if enabled in
+/// `telemetry_config`.
 ///
 /// This function should only be called once during the lifetime of the application. Do not call
 /// this outside of the application or testing code, the installed logger is global.
@@ -338,14 +561,14 @@ fn format_into_debug_writer(
 /// See the `README.md` for hints on how to configure logging at runtime.
 // The `io::stdout as fn()...` casts are necessary, as is the `FormatDebugFn` cast.
 #[allow(trivial_casts)]
-pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
+pub fn init_with_telemetry(
+    config: &LoggingConfig,
+    telemetry_config: &TelemetryConfig,
+) -> anyhow::Result<()> {
+    let tracer = telemetry::install_tracer(telemetry_config)?;
     let formatter = format::debug_fn(format_into_debug_writer as FormatDebugFn);
 
-    let filter = EnvFilter::new(
-        env::var(LOG_CONFIGURATION_ENVVAR)
-            .as_deref()
-            .unwrap_or("warn,casper_node=info"),
-    );
+    let filter = EnvFilter::new(config.filter_directives());
 
     match config.format {
         // Setup a new tracing-subscriber writing to `stdout` for logging.
@@ -357,7 +580,11 @@ pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
                 .event_format(FmtEvent::new(config.color, config.abbreviate_modules))
                 .with_filter_reloading();
             let handle = ReloadHandle::Text(builder.reload_handle());
-            builder.try_init().map_err(|error| anyhow!(error))?;
+            builder
+                .finish()
+                .with(telemetry_layer(tracer))
+                .try_init()
+                .map_err(|error| anyhow!(error))?;
             drop(RELOAD_HANDLE.set(handle));
             Ok(())
         }
@@ -370,9 +597,48 @@ pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
                 .json()
                 .with_filter_reloading();
             let handle = ReloadHandle::Json(builder.reload_handle());
-            builder.try_init().map_err(|error| anyhow!(error))?;
+            builder
+                .finish()
+                .with(telemetry_layer(tracer))
+                .try_init()
+                .map_err(|error| anyhow!(error))?;
             drop(RELOAD_HANDLE.set(handle));
             Ok(())
         }
+
+        // Structured JSON logging writes flat JSON objects to `stdout`.
+        LoggingFormat::StructuredJson => {
+            let builder = tracing_subscriber::fmt()
+                .with_writer(io::stdout as fn() -> std::io::Stdout)
+                .with_env_filter(filter)
+                .event_format(StructuredJsonEvent)
+                .with_filter_reloading();
+            let handle = ReloadHandle::StructuredJson(builder.reload_handle());
+            builder
+                .finish()
+                .with(telemetry_layer(tracer))
+                .try_init()
+                .map_err(|error| anyhow!(error))?;
+            drop(RELOAD_HANDLE.set(handle));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_normalize_id_fields() {
+        assert_eq!(normalize_field_name("sender"), LOG_FIELD_PEER_ID);
+        assert_eq!(normalize_field_name("block_hash"), LOG_FIELD_BLOCK_HASH);
+        assert_eq!(normalize_field_name("era_id"), "era_id");
+
+        assert_eq!(normalize_id_value("block hash 0123..cdef"), "0123..cdef");
+        assert_eq!(normalize_id_value("DeployHash(0123abcd)"), "0123abcd");
+        assert_eq!(normalize_id_value("deploy-hash(0123..cdef)"), "0123..cdef");
+        assert_eq!(normalize_id_value("tls:0123..cdef"), "0123..cdef");
+        assert_eq!(normalize_id_value("0123abcd"), "0123abcd");
     }
 }
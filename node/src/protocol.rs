@@ -88,6 +88,8 @@ impl Payload for Message {
                 Tag::SyncLeap => MessageKind::BlockTransfer,
                 Tag::ApprovalsHashes => MessageKind::BlockTransfer,
                 Tag::BlockExecutionResults => MessageKind::BlockTransfer,
+                Tag::LightClientUpdate => MessageKind::BlockTransfer,
+                Tag::BlockBodyOrChunk => MessageKind::BlockTransfer,
             },
             Message::FinalitySignature(_) => MessageKind::Consensus,
             Message::FinalitySignatureGossiper(_) => MessageKind::FinalitySignatureGossip,
@@ -130,6 +132,8 @@ impl Payload for Message {
                 Tag::SyncLeap => weights.sync_leap_requests,
                 Tag::ApprovalsHashes => weights.approvals_hashes_requests,
                 Tag::BlockExecutionResults => weights.execution_results_requests,
+                Tag::LightClientUpdate => weights.light_client_update_requests,
+                Tag::BlockBodyOrChunk => weights.block_requests,
             },
             Message::GetResponse { tag, .. } => match tag {
                 Tag::Deploy => weights.deploy_responses,
@@ -141,6 +145,8 @@ impl Payload for Message {
                 Tag::SyncLeap => weights.sync_leap_responses,
                 Tag::ApprovalsHashes => weights.approvals_hashes_responses,
                 Tag::BlockExecutionResults => weights.execution_results_responses,
+                Tag::LightClientUpdate => weights.light_client_update_responses,
+                Tag::BlockBodyOrChunk => weights.block_responses,
             },
             Message::FinalitySignature(_) => weights.finality_signature_broadcasts,
         }
@@ -161,6 +167,10 @@ impl Payload for Message {
             Message::FinalitySignature(_) => false,
         }
     }
+
+    fn is_light_client_request(&self) -> bool {
+        matches!(self, Message::GetRequest { tag, .. } if *tag == Tag::LightClientUpdate)
+    }
 }
 
 impl Message {
@@ -386,6 +396,16 @@ where
                     message: Box::new(NetRequest::BlockExecutionResults(serialized_id)),
                 }
                 .into(),
+                Tag::LightClientUpdate => NetRequestIncoming {
+                    sender,
+                    message: Box::new(NetRequest::LightClientUpdate(serialized_id)),
+                }
+                .into(),
+                Tag::BlockBodyOrChunk => NetRequestIncoming {
+                    sender,
+                    message: Box::new(NetRequest::BlockBodyOrChunk(serialized_id)),
+                }
+                .into(),
             },
             Message::GetResponse {
                 tag,
@@ -436,6 +456,16 @@ where
                     message: Box::new(NetResponse::BlockExecutionResults(serialized_item)),
                 }
                 .into(),
+                Tag::LightClientUpdate => NetResponseIncoming {
+                    sender,
+                    message: Box::new(NetResponse::LightClientUpdate(serialized_item)),
+                }
+                .into(),
+                Tag::BlockBodyOrChunk => NetResponseIncoming {
+                    sender,
+                    message: Box::new(NetResponse::BlockBodyOrChunk(serialized_item)),
+                }
+                .into(),
             },
             Message::FinalitySignature(message) => {
                 FinalitySignatureIncoming { sender, message }.into()
@@ -29,6 +29,7 @@
 //! With all these set up, a reactor can be executed using a [`Runner`], either in a step-wise
 //! manner using [`Runner::crank`] or indefinitely using [`Runner::run`].
 
+mod dispatch_times;
 mod event_queue_metrics;
 pub(crate) mod main_reactor;
 mod queue_kind;
@@ -57,7 +58,7 @@ use serde::Serialize;
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
 use stats_alloc::{Stats, INSTRUMENTED_SYSTEM};
 use tokio::time::{Duration, Instant};
-use tracing::{debug_span, error, info, instrument, trace, warn, Span};
+use tracing::{debug, debug_span, error, info, instrument, trace, warn, Span};
 use tracing_futures::Instrument;
 
 #[cfg(test)]
@@ -81,14 +82,16 @@ use crate::{
     },
     failpoints::FailpointActivation,
     types::{
-        ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Chainspec,
-        ChainspecRawBytes, Deploy, ExitCode, FinalitySignature, LegacyDeploy, NodeId, SyncLeap,
-        TrieOrChunk,
+        ApprovalsHashes, Block, BlockBodyOrChunk, BlockExecutionResultsOrChunk, BlockHeader,
+        Chainspec, ChainspecRawBytes, Deploy, ExitCode, FinalitySignature, LegacyDeploy, NodeId,
+        SyncLeap, TrieOrChunk,
     },
     unregister_metric,
-    utils::{self, SharedFlag, WeightedRoundRobin},
+    utils::{self, round_robin::QueueInspection, SharedFlag, WeightedRoundRobin},
     NodeRng, TERMINATION_REQUESTED,
 };
+use dispatch_times::DispatchTimes;
+pub(crate) use dispatch_times::DispatchTimesSummary;
 pub(crate) use queue_kind::QueueKind;
 
 /// Default threshold for when an event is considered slow.  Can be overridden by setting the env
@@ -98,6 +101,19 @@ const DISPATCH_EVENT_THRESHOLD_ENV_VAR: &str = "CL_EVENT_MAX_MICROSECS";
 #[cfg(test)]
 const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
+/// The longest time spent dispatching the remaining events on shutdown.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the event queues must stay empty on shutdown for draining to be complete.
+const SHUTDOWN_QUIET_PERIOD: Duration = Duration::from_millis(200);
+/// How often to check for new events while the event queues are empty on shutdown.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// The queues holding events initiated by peers, which no longer accept events on shutdown.
+const NETWORK_INPUT_QUEUES: [QueueKind; 3] = [
+    QueueKind::NetworkIncoming,
+    QueueKind::NetworkLowPriority,
+    QueueKind::NetworkDemand,
+];
+
 static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
     env::var(DISPATCH_EVENT_THRESHOLD_ENV_VAR)
         .map(|threshold_str| {
@@ -167,9 +183,35 @@ fn adjust_open_files_limit() {
 ///
 /// Components rarely use this, but use a bound `EventQueueHandle` instead.
 ///
-/// Schedule tuples contain an optional ancestor ID and the actual event. The ancestor ID indicates
-/// which potential previous event resulted in the event being created.
-pub(crate) type Scheduler<Ev> = WeightedRoundRobin<(Option<NonZeroU64>, Ev), QueueKind>;
+/// Schedule tuples contain the origin of the event and the actual event.
+pub(crate) type Scheduler<Ev> = WeightedRoundRobin<(EventOrigin, Ev), QueueKind>;
+
+tokio::task_local! {
+    /// The correlation ID of the event being dispatched, or whose effects are being run.
+    static CORRELATION_ID: NonZeroU64;
+}
+
+/// Where a scheduled event originates from.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(crate) struct EventOrigin {
+    /// The ID of the event whose effects created this event, if any.
+    pub(crate) ancestor: Option<NonZeroU64>,
+    /// The ID shared by all events which resulted from the same event scheduled from outside the
+    /// reactor, e.g. an incoming network message or client request.
+    ///
+    /// Passed on both to the events created by effects and to the requests made while running
+    /// them, so that a deploy or block can be followed across components in the logs.  `None` for
+    /// events scheduled from outside the reactor, which start a new correlation.
+    pub(crate) correlation_id: Option<NonZeroU64>,
+}
+
+/// Returns the correlation ID of the event currently being dispatched, or whose effects are
+/// currently being run on this task.
+pub(crate) fn current_correlation_id() -> Option<NonZeroU64> {
+    CORRELATION_ID
+        .try_with(|correlation_id| *correlation_id)
+        .ok()
+}
 
 /// Event queue handle
 ///
@@ -217,25 +259,30 @@ impl<REv> EventQueueHandle<REv> {
 
     /// Schedule an event on a specific queue.
     ///
-    /// The scheduled event will not have an ancestor.
+    /// The scheduled event will not have an ancestor, but keeps the correlation ID of the effect
+    /// scheduling it, if any.
     pub(crate) async fn schedule<Ev>(self, event: Ev, queue_kind: QueueKind)
     where
         REv: From<Ev>,
     {
-        self.schedule_with_ancestor(None, event, queue_kind).await
+        let origin = EventOrigin {
+            ancestor: None,
+            correlation_id: current_correlation_id(),
+        };
+        self.schedule_with_origin(origin, event, queue_kind).await
     }
 
     /// Schedule an event on a specific queue.
-    pub(crate) async fn schedule_with_ancestor<Ev>(
+    pub(crate) async fn schedule_with_origin<Ev>(
         self,
-        ancestor: Option<NonZeroU64>,
+        origin: EventOrigin,
         event: Ev,
         queue_kind: QueueKind,
     ) where
         REv: From<Ev>,
     {
         self.scheduler
-            .push((ancestor, event.into()), queue_kind)
+            .push((origin, event.into()), queue_kind)
             .await
     }
 
@@ -244,6 +291,18 @@ impl<REv> EventQueueHandle<REv> {
         self.scheduler.event_queues_counts()
     }
 
+    /// Returns the depths of the scheduler's queues, along with the events dispatched from them
+    /// within the last `window`.
+    ///
+    /// Does not go through the reactor, so it also works while the reactor is stuck.
+    pub(crate) fn inspect_queues(
+        &self,
+        window: Duration,
+        top: usize,
+    ) -> QueueInspection<QueueKind> {
+        self.scheduler.inspect(window, top)
+    }
+
     /// Returns whether the associated reactor is currently shutting down.
     pub(crate) fn shutdown_flag(&self) -> SharedFlag {
         self.is_shutting_down
@@ -304,6 +363,19 @@ pub(crate) trait Reactor: Sized {
         // Default is to ignore the failpoint. If failpoint support is enabled for a reactor, route
         // the activation to the respective components here.
     }
+
+    /// Prepares the reactor for a graceful shutdown.
+    ///
+    /// Called once the node is about to exit, before the remaining events are dispatched. The
+    /// reactor should stop receiving input from peers, stop starting new work, and flush any
+    /// state held in memory to storage via the returned effects.
+    fn prepare_for_shutdown(
+        &mut self,
+        _effect_builder: EffectBuilder<Self::Event>,
+        _rng: &mut NodeRng,
+    ) -> Effects<Self::Event> {
+        Effects::new()
+    }
 }
 
 /// A reactor event type.
@@ -320,6 +392,13 @@ pub(crate) trait ReactorEvent: Send + Debug + From<ControlAnnouncement> + 'stati
     fn description(&self) -> &'static str {
         "anonymous event"
     }
+
+    /// Returns the name of the component the time spent dispatching the event is attributed to.
+    ///
+    /// Defaults to the description of the event.
+    fn component(&self) -> &'static str {
+        self.description()
+    }
 }
 
 /// A drop-like trait for `async` compatible drop-and-wait.
@@ -369,6 +448,9 @@ where
     /// Metrics for the runner.
     metrics: RunnerMetrics,
 
+    /// Time spent dispatching events, per component and event.
+    dispatch_times: DispatchTimes,
+
     /// Check if we need to update reactor metrics every this many events.
     event_metrics_threshold: u64,
 
@@ -528,9 +610,14 @@ where
             initial_effects.len()
         );
         // Run all effects from component instantiation.
-        process_effects(None, scheduler, initial_effects, QueueKind::Regular)
-            .instrument(debug_span!("process initial effects"))
-            .await;
+        process_effects(
+            EventOrigin::default(),
+            scheduler,
+            initial_effects,
+            QueueKind::Regular,
+        )
+        .instrument(debug_span!("process initial effects"))
+        .await;
 
         info!("reactor main loop is ready");
 
@@ -539,6 +626,7 @@ where
             reactor,
             current_event_id: 1,
             metrics: RunnerMetrics::new(registry)?,
+            dispatch_times: DispatchTimes::new(registry)?,
             last_metrics: Instant::now(),
             event_metrics_min_delay: Duration::from_secs(30),
             event_metrics_threshold: 1000,
@@ -550,7 +638,12 @@ where
     /// Processes a single event on the event queue.
     ///
     /// Returns `Some(exit_code)` if processing should stop.
-    #[instrument("dispatch", level = "debug", fields(a, ev = self.current_event_id), skip(self, rng))]
+    #[instrument(
+        "dispatch",
+        level = "debug",
+        fields(a, c, ev = self.current_event_id),
+        skip(self, rng)
+    )]
     pub(crate) async fn crank(&mut self, rng: &mut NodeRng) -> Option<ExitCode> {
         self.metrics.events.inc();
 
@@ -582,18 +675,27 @@ where
             }
         }
 
-        let ((ancestor, event), queue_kind) = self.scheduler.pop().await;
+        let ((origin, event), queue_kind) = self.scheduler.pop().await;
         trace!(%event, %queue_kind, "current");
         let event_desc = event.description();
+        let event_component = event.component();
+        self.scheduler.record_dequeue(queue_kind, event_desc);
 
         // Create another span for tracing the processing of one event.
         Span::current().record("ev", self.current_event_id);
 
         // If we know the ancestor of an event, record it.
-        if let Some(ancestor) = ancestor {
+        if let Some(ancestor) = origin.ancestor {
             Span::current().record("a", ancestor.get());
         }
 
+        // Events scheduled from outside the reactor start a new correlation.
+        let event_id = NonZeroU64::new(self.current_event_id);
+        let correlation_id = origin.correlation_id.or(event_id);
+        if let Some(correlation_id) = correlation_id {
+            Span::current().record("c", correlation_id.get());
+        }
+
         // Dispatch the event, then execute the resulting effect.
         let start = self.clock.start();
 
@@ -669,6 +771,11 @@ where
                     // Do nothing on queue dump otherwise.
                     (Default::default(), None, QueueKind::Control)
                 }
+                Some(ControlAnnouncement::DispatchTimesRequest { responder }) => {
+                    responder.respond(self.dispatch_times.summary()).await;
+
+                    (Effects::new(), None, QueueKind::Control)
+                }
                 Some(ControlAnnouncement::ActivateFailpoint { activation }) => {
                     self.reactor.activate_failpoint(&activation);
 
@@ -677,11 +784,11 @@ where
                 }
             }
         } else {
-            (
-                self.reactor.dispatch_event(effect_builder, rng, event),
-                None,
-                queue_kind,
-            )
+            let reactor = &mut self.reactor;
+            let effects = with_correlation_id(correlation_id, || {
+                reactor.dispatch_event(effect_builder, rng, event)
+            });
+            (effects, None, queue_kind)
         };
 
         let end = self.clock.end();
@@ -694,16 +801,17 @@ where
         self.metrics
             .event_dispatch_duration
             .observe(delta.into_nanos() as f64);
+        self.dispatch_times
+            .record(event_component, event_desc, delta.into_nanos());
 
         // Run effects, with the current event ID as the ancestor for resulting set of events.
-        process_effects(
-            NonZeroU64::new(self.current_event_id),
-            self.scheduler,
-            effects,
-            queue_kind,
-        )
-        .in_current_span()
-        .await;
+        let origin = EventOrigin {
+            ancestor: event_id,
+            correlation_id,
+        };
+        process_effects(origin, self.scheduler, effects, queue_kind)
+            .in_current_span()
+            .await;
 
         self.current_event_id += 1;
 
@@ -742,29 +850,74 @@ where
 
     /// Runs the reactor until `self.crank` returns `Some` or we get interrupted by a termination
     /// signal.
+    ///
+    /// Unless exiting due to a fatal error or `SIGQUIT`, the remaining events are drained before
+    /// returning.
     pub(crate) async fn run(&mut self, rng: &mut NodeRng) -> ExitCode {
-        loop {
+        let exit_code = loop {
             match TERMINATION_REQUESTED.load(Ordering::SeqCst) as i32 {
                 0 => {
                     if let Some(exit_code) = self.crank(rng).await {
-                        self.is_shutting_down.set();
                         break exit_code;
                     }
                 }
-                SIGINT => {
-                    self.is_shutting_down.set();
-                    break ExitCode::SigInt;
-                }
-                SIGQUIT => {
-                    self.is_shutting_down.set();
-                    break ExitCode::SigQuit;
-                }
-                SIGTERM => {
-                    self.is_shutting_down.set();
-                    break ExitCode::SigTerm;
-                }
+                SIGINT => break ExitCode::SigInt,
+                SIGQUIT => break ExitCode::SigQuit,
+                SIGTERM => break ExitCode::SigTerm,
                 _ => error!("should be unreachable - bug in signal handler"),
             }
+        };
+        self.is_shutting_down.set();
+        if !matches!(exit_code, ExitCode::Abort | ExitCode::SigQuit) {
+            self.drain(rng).await;
+        }
+        exit_code
+    }
+
+    /// Drains the event queues ahead of a shutdown.
+    ///
+    /// Stops accepting events initiated by peers and lets the reactor prepare for the shutdown,
+    /// then dispatches events until the queues have stayed empty for `SHUTDOWN_QUIET_PERIOD`, or
+    /// `SHUTDOWN_DRAIN_TIMEOUT` has passed.  Exit codes returned while draining are ignored.
+    async fn drain(&mut self, rng: &mut NodeRng) {
+        info!("draining event queues before shutting down");
+        for queue_kind in NETWORK_INPUT_QUEUES.iter() {
+            self.scheduler.seal_queue(*queue_kind);
+        }
+        let event_queue = EventQueueHandle::new(self.scheduler, self.is_shutting_down);
+        let effects = self
+            .reactor
+            .prepare_for_shutdown(EffectBuilder::new(event_queue), rng);
+        process_effects(
+            EventOrigin::default(),
+            self.scheduler,
+            effects,
+            QueueKind::Control,
+        )
+        .await;
+
+        let deadline = Instant::now() + SHUTDOWN_DRAIN_TIMEOUT;
+        let mut quiet_since = Instant::now();
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                warn!(
+                    remaining_events = self.scheduler.item_count(),
+                    "timed out draining event queues"
+                );
+                break;
+            }
+            if self.scheduler.item_count() > 0 {
+                if let Some(exit_code) = self.crank(rng).await {
+                    debug!(?exit_code, "ignoring exit code while draining event queues");
+                }
+                quiet_since = Instant::now();
+            } else if now.duration_since(quiet_since) >= SHUTDOWN_QUIET_PERIOD {
+                info!("drained event queues");
+                break;
+            } else {
+                tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+            }
         }
     }
 }
@@ -827,12 +980,17 @@ where
 
         let effects = create_effects(effect_builder);
 
-        process_effects(None, self.scheduler, effects, QueueKind::Regular)
-            .instrument(debug_span!(
-                "process injected effects",
-                ev = self.current_event_id
-            ))
-            .await;
+        process_effects(
+            EventOrigin::default(),
+            self.scheduler,
+            effects,
+            QueueKind::Regular,
+        )
+        .instrument(debug_span!(
+            "process injected effects",
+            ev = self.current_event_id
+        ))
+        .await;
     }
 
     /// Processes a single event if there is one and we haven't previously handled an exit code.
@@ -866,8 +1024,8 @@ where
     pub(crate) async fn drain_into_inner(self) -> R {
         self.is_shutting_down.set();
         self.scheduler.seal();
-        for (ancestor, event) in self.scheduler.drain_queues().await {
-            tracing::debug!(?ancestor, %event, "drained event");
+        for (origin, event) in self.scheduler.drain_queues().await {
+            tracing::debug!(?origin, %event, "drained event");
         }
         self.reactor
     }
@@ -927,9 +1085,10 @@ where
 
 /// Spawns tasks that will process the given effects.
 ///
-/// Result events from processing the events will be scheduled with the given ancestor.
+/// Result events from processing the events will be scheduled with the given origin.  The tasks
+/// run with its correlation ID set, so that it is also passed on to any requests they make.
 async fn process_effects<Ev>(
-    ancestor: Option<NonZeroU64>,
+    origin: EventOrigin,
     scheduler: &'static Scheduler<Ev>,
     effects: Effects<Ev>,
     queue_kind: QueueKind,
@@ -937,11 +1096,30 @@ async fn process_effects<Ev>(
     Ev: Send + 'static,
 {
     for effect in effects {
-        tokio::spawn(async move {
+        let task = async move {
             for event in effect.await {
-                scheduler.push((ancestor, event), queue_kind).await
+                scheduler.push((origin, event), queue_kind).await
             }
-        });
+        };
+        match origin.correlation_id {
+            Some(correlation_id) => {
+                tokio::spawn(CORRELATION_ID.scope(correlation_id, task));
+            }
+            None => {
+                tokio::spawn(task);
+            }
+        }
+    }
+}
+
+/// Calls `f` with the given correlation ID set, if any.
+fn with_correlation_id<T, F>(correlation_id: Option<NonZeroU64>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    match correlation_id {
+        Some(correlation_id) => CORRELATION_ID.sync_scope(correlation_id, f),
+        None => f(),
     }
 }
 
@@ -1012,6 +1190,7 @@ where
         + From<fetcher::Event<Block>>
         + From<fetcher::Event<BlockHeader>>
         + From<fetcher::Event<BlockExecutionResultsOrChunk>>
+        + From<fetcher::Event<BlockBodyOrChunk>>
         + From<fetcher::Event<LegacyDeploy>>
         + From<fetcher::Event<Deploy>>
         + From<fetcher::Event<SyncLeap>>
@@ -1079,5 +1258,19 @@ where
                 serialized_item,
             )
         }
+        NetResponse::BlockBodyOrChunk(ref serialized_item) => {
+            handle_fetch_response::<R, BlockBodyOrChunk>(
+                reactor,
+                effect_builder,
+                rng,
+                sender,
+                serialized_item,
+            )
+        }
+        NetResponse::LightClientUpdate(_) => {
+            // Light client updates are only served, never fetched by the node itself.
+            debug!(%sender, "ignoring unsolicited light client update");
+            Effects::new()
+        }
     }
 }
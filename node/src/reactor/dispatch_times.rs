@@ -0,0 +1,211 @@
+//! Accounting of the time spent dispatching events.
+//!
+//! The time is recorded per component and per event, both as metrics and as a summary which can be
+//! dumped via the diagnostics port, so that slow event handlers can be identified.
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+use serde::Serialize;
+
+use crate::unregister_metric;
+
+/// Buckets of the per-component dispatch time histogram, in nanoseconds.
+const DISPATCH_DURATION_BUCKETS: [f64; 12] = [
+    1_000.0,
+    10_000.0,
+    50_000.0,
+    100_000.0,
+    500_000.0,
+    1_000_000.0,
+    5_000_000.0,
+    10_000_000.0,
+    50_000_000.0,
+    100_000_000.0,
+    500_000_000.0,
+    1_000_000_000.0,
+];
+
+/// Time spent dispatching a single kind of event.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(crate) struct DispatchTime {
+    /// Number of events dispatched.
+    pub(crate) count: u64,
+    /// Total time spent dispatching them, in nanoseconds.
+    pub(crate) total_nanos: u64,
+    /// Longest time spent dispatching a single one, in nanoseconds.
+    pub(crate) max_nanos: u64,
+}
+
+impl DispatchTime {
+    fn record(&mut self, nanos: u64) {
+        self.count += 1;
+        self.total_nanos = self.total_nanos.saturating_add(nanos);
+        self.max_nanos = self.max_nanos.max(nanos);
+    }
+
+    /// Returns the mean time spent dispatching a single event, in nanoseconds.
+    fn mean_nanos(&self) -> u64 {
+        self.total_nanos.checked_div(self.count).unwrap_or_default()
+    }
+}
+
+/// Time spent dispatching events of a single kind handled by a single component.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct DispatchTimeEntry {
+    /// The component the events are attributed to.
+    pub(crate) component: &'static str,
+    /// The description of the events.
+    pub(crate) event: &'static str,
+    /// The time spent dispatching them.
+    #[serde(flatten)]
+    pub(crate) time: DispatchTime,
+}
+
+/// Time spent dispatching each kind of event since the node started, largest total first.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct DispatchTimesSummary {
+    pub(crate) entries: Vec<DispatchTimeEntry>,
+}
+
+impl Display for DispatchTimesSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<28}{:<44}{:>12}{:>14}{:>12}{:>12}",
+            "component", "event", "count", "total ms", "mean us", "max us"
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<28}{:<44}{:>12}{:>14}{:>12}{:>12}",
+                entry.component,
+                entry.event,
+                entry.time.count,
+                entry.time.total_nanos / 1_000_000,
+                entry.time.mean_nanos() / 1_000,
+                entry.time.max_nanos / 1_000
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Time spent dispatching events, per component and per event.
+#[derive(Debug)]
+pub(super) struct DispatchTimes {
+    /// Time spent per component and event.
+    times: BTreeMap<(&'static str, &'static str), DispatchTime>,
+    /// Histogram of the time spent dispatching a single event, labeled by component.
+    duration_by_component: HistogramVec,
+    /// Total time spent dispatching events, labeled by component and event.
+    total_nanos_by_event: IntCounterVec,
+    /// Instance of registry to unregister from when being dropped.
+    registry: Registry,
+}
+
+impl DispatchTimes {
+    /// Creates and registers the dispatch time metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let duration_by_component = HistogramVec::new(
+            HistogramOpts::new(
+                "event_dispatch_duration_by_component",
+                "time in nanoseconds to dispatch an event, by component",
+            )
+            .buckets(DISPATCH_DURATION_BUCKETS.to_vec()),
+            &["component"],
+        )?;
+        let total_nanos_by_event = IntCounterVec::new(
+            Opts::new(
+                "event_dispatch_total_nanos",
+                "total time in nanoseconds spent dispatching events, by component and event",
+            ),
+            &["component", "event"],
+        )?;
+        registry.register(Box::new(duration_by_component.clone()))?;
+        registry.register(Box::new(total_nanos_by_event.clone()))?;
+
+        Ok(DispatchTimes {
+            times: BTreeMap::new(),
+            duration_by_component,
+            total_nanos_by_event,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Records the time spent dispatching a single event.
+    pub(super) fn record(&mut self, component: &'static str, event: &'static str, nanos: u64) {
+        self.times
+            .entry((component, event))
+            .or_default()
+            .record(nanos);
+        self.duration_by_component
+            .with_label_values(&[component])
+            .observe(nanos as f64);
+        self.total_nanos_by_event
+            .with_label_values(&[component, event])
+            .inc_by(nanos);
+    }
+
+    /// Returns the time spent dispatching each kind of event, largest total first.
+    pub(super) fn summary(&self) -> DispatchTimesSummary {
+        let mut entries: Vec<_> = self
+            .times
+            .iter()
+            .map(|(&(component, event), &time)| DispatchTimeEntry {
+                component,
+                event,
+                time,
+            })
+            .collect();
+        entries.sort_by_key(|entry| Reverse(entry.time.total_nanos));
+        DispatchTimesSummary { entries }
+    }
+}
+
+impl Drop for DispatchTimes {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.duration_by_component);
+        unregister_metric!(self.registry, self.total_nanos_by_event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_summarize_dispatch_times() {
+        let mut dispatch_times = DispatchTimes::new(&Registry::new()).unwrap();
+        dispatch_times.record("Storage", "StorageRequest", 2_000);
+        dispatch_times.record("Storage", "StorageRequest", 4_000);
+        dispatch_times.record("Consensus", "ConsensusMessageIncoming", 10_000);
+
+        let summary = dispatch_times.summary();
+        let entries: Vec<_> = summary
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.component,
+                    entry.event,
+                    entry.time.count,
+                    entry.time.total_nanos,
+                    entry.time.max_nanos,
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("Consensus", "ConsensusMessageIncoming", 1, 10_000, 10_000),
+                ("Storage", "StorageRequest", 2, 6_000, 4_000),
+            ]
+        );
+        assert_eq!(summary.entries[1].time.mean_nanos(), 3_000);
+    }
+}
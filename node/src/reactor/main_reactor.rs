@@ -12,6 +12,7 @@ mod catch_up;
 mod genesis_instruction;
 mod keep_up;
 mod reactor_state;
+mod reload;
 #[cfg(test)]
 mod tests;
 mod upgrade_shutdown;
@@ -43,12 +44,16 @@ use crate::{
         gossiper::{self, GossipItem, Gossiper},
         metrics::Metrics,
         network::{self, GossipedAddress, Identity as NetworkIdentity, Network},
+        proof_relayer::{self, ProofRelayer},
+        randomness_beacon::RandomnessBeacon,
         rest_server::RestServer,
-        rpc_server::RpcServer,
+        rpc_server::{self, RpcServer},
         shutdown_trigger::{self, ShutdownTrigger},
+        speculative_executor::SpeculativeExecutor,
         storage::Storage,
         sync_leaper::SyncLeaper,
         upgrade_watcher::{self, UpgradeWatcher},
+        zk_prover::{self, ZkProver},
         Component, ValidatorBoundComponent,
     },
     effect::{
@@ -57,10 +62,10 @@ use crate::{
             ControlAnnouncement, DeployAcceptorAnnouncement, DeployBufferAnnouncement,
             FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement,
             GossiperAnnouncement, MetaBlockAnnouncement, PeerBehaviorAnnouncement,
-            UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
+            UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement, ZkProverAnnouncement,
         },
         incoming::{NetResponseIncoming, TrieResponseIncoming},
-        requests::{AcceptDeployRequest, ChainspecRawBytesRequest},
+        requests::{AcceptDeployRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest},
         EffectBuilder, EffectExt, Effects, GossipTarget,
     },
     failpoints::FailpointActivation,
@@ -73,8 +78,8 @@ use crate::{
         EventQueueHandle, QueueKind,
     },
     types::{
-        Block, BlockHash, Chainspec, ChainspecRawBytes, Deploy, FinalitySignature, MetaBlock,
-        MetaBlockState, SyncHandling, TrieOrChunk, ValidatorMatrix,
+        Block, BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, Checkpoints, Deploy,
+        FinalitySignature, MetaBlock, MetaBlockState, SyncHandling, TrieOrChunk, ValidatorMatrix,
     },
     utils::{Source, WithDir},
     NodeRng,
@@ -135,6 +140,10 @@ pub(crate) struct MainReactor {
     //   i/o bound components
     storage: Storage,
     contract_runtime: ContractRuntime,
+    speculative_executor: SpeculativeExecutor,
+    zk_prover: ZkProver,
+    proof_relayer: ProofRelayer,
+    randomness_beacon: RandomnessBeacon,
     upgrade_watcher: UpgradeWatcher,
     rpc_server: RpcServer,
     rest_server: RestServer,
@@ -173,6 +182,8 @@ pub(crate) struct MainReactor {
     event_queue_metrics: EventQueueMetrics,
 
     //   ambient settings / data / load-bearing config
+    /// The config, with the reloadable settings as last reloaded.
+    config: Config,
     validator_matrix: ValidatorMatrix,
     trusted_hash: Option<BlockHash>,
     chainspec: Arc<Chainspec>,
@@ -190,7 +201,11 @@ pub(crate) struct MainReactor {
     switched_to_shutdown_for_upgrade: Timestamp,
     upgrade_timeout: TimeDiff,
     sync_handling: SyncHandling,
+    back_fill_depth: Option<u64>,
+    header_back_fill_tip: Option<BlockHeader>,
     signature_gossip_tracker: SignatureGossipTracker,
+    /// Whether the node is shutting down, in which case the control logic stops.
+    shutting_down: bool,
 }
 
 impl reactor::Reactor for MainReactor {
@@ -503,6 +518,29 @@ impl reactor::Reactor for MainReactor {
                     ),
                 );
 
+                effects.extend(reactor::wrap_effects(
+                    MainEvent::Consensus,
+                    self.consensus.handle_event(
+                        effect_builder,
+                        rng,
+                        consensus::Event::FinalitySignatureAccepted {
+                            era_id: finality_signature.era_id,
+                            public_key: finality_signature.public_key.clone(),
+                        },
+                    ),
+                ));
+
+                if self.rpc_server.has_subscribers() {
+                    effects.extend(reactor::wrap_effects(
+                        MainEvent::RpcServer,
+                        self.rpc_server.handle_event(
+                            effect_builder,
+                            rng,
+                            rpc_server::Event::FinalitySignature(finality_signature.clone()),
+                        ),
+                    ));
+                }
+
                 effects.extend(reactor::wrap_effects(
                     MainEvent::EventStreamServer,
                     self.event_stream_server.handle_event(
@@ -514,6 +552,38 @@ impl reactor::Reactor for MainReactor {
 
                 effects
             }
+            MainEvent::BlockAccumulatorAnnouncement(
+                BlockAccumulatorAnnouncement::OrphanDetected {
+                    block_hash,
+                    parent_hash,
+                },
+            ) => {
+                // only fetch the missing parent when keeping up and not already syncing another
+                // block, so we don't abandon work in progress.
+                if self.state == ReactorState::KeepUp
+                    && !self.block_synchronizer.forward_progress().is_active()
+                    && self
+                        .block_synchronizer
+                        .register_block_by_hash(parent_hash, false)
+                {
+                    info!(%block_hash, %parent_hash, "MainReactor: syncing parent of orphaned block");
+                    effect_builder.immediately().event(|_| {
+                        MainEvent::BlockSynchronizerRequest(BlockSynchronizerRequest::NeedNext)
+                    })
+                } else {
+                    debug!(%block_hash, %parent_hash, "MainReactor: not syncing parent of orphaned block");
+                    Effects::new()
+                }
+            }
+            MainEvent::BlockAccumulatorAnnouncement(
+                BlockAccumulatorAnnouncement::SufficientFinalityLost { block_hash },
+            ) => {
+                warn!(
+                    %block_hash,
+                    "MainReactor: block lost sufficient finality after validator matrix update"
+                );
+                Effects::new()
+            }
             MainEvent::BlockGossiper(event) => reactor::wrap_effects(
                 MainEvent::BlockGossiper,
                 self.block_gossiper.handle_event(effect_builder, rng, event),
@@ -807,6 +877,17 @@ impl reactor::Reactor for MainReactor {
                 );
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
+            MainEvent::DeployBufferAnnouncement(DeployBufferAnnouncement::DeploysReplaced {
+                replaced,
+                replacement,
+            }) => {
+                let reactor_event =
+                    MainEvent::EventStreamServer(event_stream_server::Event::DeploysReplaced {
+                        replaced,
+                        replacement,
+                    });
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
 
             // CONTRACT RUNTIME & GLOBAL STATE
             MainEvent::ContractRuntime(event) => reactor::wrap_effects(
@@ -819,6 +900,41 @@ impl reactor::Reactor for MainReactor {
                 self.contract_runtime
                     .handle_event(effect_builder, rng, req.into()),
             ),
+            MainEvent::SpeculativeExecutor(event) => reactor::wrap_effects(
+                MainEvent::SpeculativeExecutor,
+                self.speculative_executor
+                    .handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::SpeculativeExecutionRequest(req) => reactor::wrap_effects(
+                MainEvent::SpeculativeExecutor,
+                self.speculative_executor
+                    .handle_event(effect_builder, rng, req.into()),
+            ),
+            MainEvent::ZkProver(event) => reactor::wrap_effects(
+                MainEvent::ZkProver,
+                self.zk_prover.handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::ZkProverRequest(req) => reactor::wrap_effects(
+                MainEvent::ZkProver,
+                self.zk_prover.handle_event(effect_builder, rng, req.into()),
+            ),
+            MainEvent::ZkProverAnnouncement(ZkProverAnnouncement::JobFinished { .. }) => {
+                Effects::new()
+            }
+            MainEvent::ZkProverAnnouncement(ZkProverAnnouncement::EraTransitionProven(proof)) => {
+                let reactor_event =
+                    MainEvent::ProofRelayer(proof_relayer::Event::EraTransitionProven(proof));
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
+            MainEvent::ProofRelayer(event) => reactor::wrap_effects(
+                MainEvent::ProofRelayer,
+                self.proof_relayer.handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::RandomnessBeaconRequest(req) => reactor::wrap_effects(
+                MainEvent::RandomnessBeaconRequest,
+                self.randomness_beacon
+                    .handle_event(effect_builder, rng, req),
+            ),
             MainEvent::ContractRuntimeAnnouncement(
                 ContractRuntimeAnnouncement::CommitStepSuccess {
                     era_id,
@@ -977,7 +1093,9 @@ impl reactor::Reactor for MainReactor {
             | MainEvent::FinalitySignatureFetcher(..)
             | MainEvent::FinalitySignatureFetcherRequest(..)
             | MainEvent::BlockExecutionResultsOrChunkFetcher(..)
-            | MainEvent::BlockExecutionResultsOrChunkFetcherRequest(..) => self
+            | MainEvent::BlockExecutionResultsOrChunkFetcherRequest(..)
+            | MainEvent::BlockBodyOrChunkFetcher(..)
+            | MainEvent::BlockBodyOrChunkFetcherRequest(..) => self
                 .fetchers
                 .dispatch_fetcher_event(effect_builder, rng, event),
         }
@@ -1004,8 +1122,9 @@ impl reactor::Reactor for MainReactor {
 
         let trusted_hash = config.value().node.trusted_hash;
         let (root_dir, config) = config.into_parts();
+        let reloadable_config = config.clone();
         let (our_secret_key, our_public_key) = config.consensus.load_keys(&root_dir)?;
-        let validator_matrix = ValidatorMatrix::new(
+        let mut validator_matrix = ValidatorMatrix::new(
             chainspec.core_config.finality_threshold_fraction,
             chainspec
                 .protocol_config
@@ -1016,8 +1135,31 @@ impl reactor::Reactor for MainReactor {
             our_secret_key.clone(),
             our_public_key.clone(),
             chainspec.core_config.auction_delay,
+            config.node.retained_validator_eras,
         );
 
+        // checkpoints are pre-verified by the operator, so the validator sets of their eras need
+        // not be proven by a sync leap and the highest one can serve as the trust anchor
+        let checkpoints: Checkpoints = match config.node.checkpoints.clone() {
+            Some(checkpoints) => checkpoints.load(&root_dir)?,
+            None => Checkpoints::default(),
+        };
+        for checkpoint in checkpoints.iter() {
+            validator_matrix
+                .register_validator_weights(checkpoint.era_id(), checkpoint.validators().clone());
+        }
+        let trusted_hash = match (trusted_hash, checkpoints.highest()) {
+            (None, Some(checkpoint)) => {
+                info!(
+                    block_hash = %checkpoint.block_hash(),
+                    height = checkpoint.height(),
+                    "MainReactor: using highest checkpoint as trusted hash"
+                );
+                Some(checkpoint.block_hash())
+            }
+            (trusted_hash, _) => trusted_hash,
+        };
+
         let storage_config = WithDir::new(&root_dir, config.storage.clone());
 
         let hard_reset_to_start_of_era = chainspec.hard_reset_to_start_of_era();
@@ -1060,6 +1202,26 @@ impl reactor::Reactor for MainReactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.features_config.clone(),
+        )?;
+        let speculative_executor = SpeculativeExecutor::new(
+            config.speculative_executor,
+            contract_runtime.speculative_execution_handle(),
+        );
+        let zk_prover = ZkProver::new(
+            WithDir::new(&root_dir, config.zk_prover.clone()),
+            chainspec.core_config.validator_slots,
+            chainspec.proof_requests_config,
+        );
+        let proof_relayer = ProofRelayer::new(
+            WithDir::new(&root_dir, config.proof_relayer.clone()),
+            protocol_version,
+            registry,
+        )?;
+        let randomness_beacon = RandomnessBeacon::new(
+            config.randomness_beacon.clone(),
+            &our_secret_key,
+            our_public_key.clone(),
         )?;
 
         let network = Network::new(
@@ -1069,17 +1231,21 @@ impl reactor::Reactor for MainReactor {
             registry,
             chainspec.as_ref(),
             validator_matrix.clone(),
+            Some(storage.root_path()),
         )?;
 
         let address_gossiper = Gossiper::<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, _>::new(
             "address_gossiper",
             config.gossip,
+            None,
             registry,
         )?;
 
         let rpc_server = RpcServer::new(
             config.rpc_server.clone(),
             config.speculative_exec_server.clone(),
+            config.grpc_server.clone(),
+            config.zk_prover.rpc_enabled,
             protocol_version,
             chainspec.network_config.name.clone(),
             node_startup_instant,
@@ -1100,24 +1266,27 @@ impl reactor::Reactor for MainReactor {
         let shutdown_trigger = ShutdownTrigger::new();
 
         // local / remote data management
-        let sync_leaper = SyncLeaper::new(chainspec.clone(), registry)?;
+        let sync_leaper = SyncLeaper::new(config.sync_leaper, chainspec.clone(), registry)?;
         let fetchers = Fetchers::new(&config.fetcher, registry)?;
 
         // gossipers
         let block_gossiper = Gossiper::<{ Block::ID_IS_COMPLETE_ITEM }, _>::new(
             "block_gossiper",
             config.gossip,
+            Some(storage.root_path()),
             registry,
         )?;
         let deploy_gossiper = Gossiper::<{ Deploy::ID_IS_COMPLETE_ITEM }, _>::new(
             "deploy_gossiper",
             config.gossip,
+            Some(storage.root_path()),
             registry,
         )?;
         let finality_signature_gossiper =
             Gossiper::<{ FinalitySignature::ID_IS_COMPLETE_ITEM }, _>::new(
                 "finality_signature_gossiper",
                 config.gossip,
+                Some(storage.root_path()),
                 registry,
             )?;
 
@@ -1149,8 +1318,11 @@ impl reactor::Reactor for MainReactor {
             registry,
         )?;
         let block_validator = BlockValidator::new(Arc::clone(&chainspec), config.block_validator);
-        let upgrade_watcher =
-            UpgradeWatcher::new(chainspec.as_ref(), config.upgrade_watcher, &root_dir)?;
+        let upgrade_watcher = UpgradeWatcher::new(
+            chainspec.as_ref(),
+            config.upgrade_watcher.clone(),
+            &root_dir,
+        )?;
         let deploy_acceptor =
             DeployAcceptor::new(config.deploy_acceptor, chainspec.as_ref(), registry)?;
         let deploy_buffer =
@@ -1161,6 +1333,10 @@ impl reactor::Reactor for MainReactor {
             chainspec_raw_bytes,
             storage,
             contract_runtime,
+            speculative_executor,
+            zk_prover,
+            proof_relayer,
+            randomness_beacon,
             upgrade_watcher,
             net: network,
             address_gossiper,
@@ -1194,17 +1370,38 @@ impl reactor::Reactor for MainReactor {
             idle_tolerance: config.node.idle_tolerance,
             control_logic_default_delay: config.node.control_logic_default_delay,
             trusted_hash,
+            config: reloadable_config,
             validator_matrix,
             sync_handling: config.node.sync_handling,
+            back_fill_depth: config.node.back_fill_depth,
+            header_back_fill_tip: None,
             signature_gossip_tracker: SignatureGossipTracker::new(),
+            shutting_down: false,
             shutdown_for_upgrade_timeout: config.node.shutdown_for_upgrade_timeout,
             switched_to_shutdown_for_upgrade: Timestamp::from(0),
             upgrade_timeout: config.node.upgrade_timeout,
         };
         info!("MainReactor: instantiated");
-        let effects = effect_builder
+        let mut effects = effect_builder
             .immediately()
             .event(|()| MainEvent::ReactorCrank);
+        effects.extend(reactor::wrap_effects(
+            MainEvent::Storage,
+            reactor.storage.start_pruning(effect_builder),
+        ));
+        effects.extend(reactor::wrap_effects(
+            MainEvent::Storage,
+            reactor.storage.start_cold_storage_migration(effect_builder),
+        ));
+        effects.extend(reactor::wrap_effects(
+            MainEvent::ContractRuntime,
+            reactor.contract_runtime.start_trie_gc(effect_builder),
+        ));
+        let proving_jobs = reactor.storage.read_proving_jobs()?;
+        effects.extend(reactor::wrap_effects(
+            MainEvent::ZkProver,
+            reactor.zk_prover.resume_jobs(effect_builder, proving_jobs),
+        ));
         Ok((reactor, effects))
     }
 
@@ -1222,6 +1419,20 @@ impl reactor::Reactor for MainReactor {
             );
         }
     }
+
+    fn prepare_for_shutdown(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        _rng: &mut NodeRng,
+    ) -> Effects<MainEvent> {
+        self.shutting_down = true;
+        self.net.close_incoming();
+        reactor::wrap_effects(
+            MainEvent::BlockAccumulator,
+            self.block_accumulator
+                .flush_finality_signature_batches(effect_builder),
+        )
+    }
 }
 
 impl MainReactor {
@@ -1508,7 +1719,44 @@ impl MainReactor {
             ),
         ));
 
+        // Only notify the RPC server if WebSocket or gRPC clients, or RPC requests waiting for a
+        // deploy, could be subscribed, to avoid cloning the execution results needlessly.
+        let notify_rpc_server = self.rpc_server.has_subscribers();
+        if notify_rpc_server {
+            effects.extend(reactor::wrap_effects(
+                MainEvent::RpcServer,
+                self.rpc_server.handle_event(
+                    effect_builder,
+                    rng,
+                    rpc_server::Event::BlockAdded(Arc::clone(&block)),
+                ),
+            ));
+        }
+
+        if block.header().is_switch_block() {
+            effects.extend(reactor::wrap_effects(
+                MainEvent::ZkProver,
+                self.zk_prover.handle_event(
+                    effect_builder,
+                    rng,
+                    zk_prover::Event::BlockAdded(Arc::clone(&block)),
+                ),
+            ));
+        }
+
         for (deploy_hash, deploy_header, execution_result) in execution_results {
+            if notify_rpc_server {
+                let event = rpc_server::Event::DeployProcessed {
+                    deploy_hash,
+                    deploy_header: Box::new(deploy_header.clone()),
+                    block_hash: *block.hash(),
+                    execution_result: Box::new(execution_result.clone()),
+                };
+                effects.extend(reactor::wrap_effects(
+                    MainEvent::RpcServer,
+                    self.rpc_server.handle_event(effect_builder, rng, event),
+                ));
+            }
             let event = event_stream_server::Event::DeployProcessed {
                 deploy_hash,
                 deploy_header: Box::new(deploy_header),
@@ -6,7 +6,7 @@ use casper_types::{TimeDiff, Timestamp};
 
 use crate::{
     components::{
-        block_accumulator::{SyncIdentifier, SyncInstruction},
+        block_accumulator::{HighestUsableBlock, SyncIdentifier, SyncInstruction},
         block_synchronizer::BlockSynchronizerProgress,
         sync_leaper,
         sync_leaper::{LeapActivityError, LeapState},
@@ -259,11 +259,14 @@ impl MainReactor {
         sync_instruction: SyncInstruction,
     ) -> Option<CatchUpInstruction> {
         match sync_instruction {
-            SyncInstruction::Leap { block_hash }
-            | SyncInstruction::LeapIntervalElapsed { block_hash } => {
-                Some(self.catch_up_leap(effect_builder, rng, block_hash))
+            SyncInstruction::Leap {
+                block_hash,
+                highest_usable_block,
+            } => Some(self.catch_up_leap(effect_builder, rng, block_hash, highest_usable_block)),
+            SyncInstruction::LeapIntervalElapsed { block_hash } => {
+                Some(self.catch_up_leap(effect_builder, rng, block_hash, None))
             }
-            SyncInstruction::BlockSync { block_hash } => {
+            SyncInstruction::BlockSync { block_hash, .. } => {
                 Some(self.catch_up_block_sync(effect_builder, block_hash))
             }
             SyncInstruction::CaughtUp { .. } => self.catch_up_check_transition(),
@@ -275,6 +278,7 @@ impl MainReactor {
         effect_builder: EffectBuilder<MainEvent>,
         rng: &mut NodeRng,
         block_hash: BlockHash,
+        highest_usable_block: Option<HighestUsableBlock>,
     ) -> CatchUpInstruction {
         // register block builder so that control logic can tell that block is Syncing,
         // otherwise block_synchronizer detects as Idle which can cause unnecessary churn
@@ -284,7 +288,9 @@ impl MainReactor {
         let leap_status = self.sync_leaper.leap_status();
         info!(%block_hash, %leap_status, "CatchUp: status");
         match leap_status {
-            LeapState::Idle => self.catch_up_leaper_idle(effect_builder, rng, block_hash),
+            LeapState::Idle => {
+                self.catch_up_leaper_idle(effect_builder, rng, block_hash, highest_usable_block)
+            }
             LeapState::Awaiting { .. } => CatchUpInstruction::CheckLater(
                 "sync leaper is awaiting response".to_string(),
                 self.control_logic_default_delay.into(),
@@ -294,9 +300,13 @@ impl MainReactor {
                 from_peers,
                 ..
             } => self.catch_up_leap_received(effect_builder, rng, *best_available, from_peers),
-            LeapState::Failed { error, .. } => {
-                self.catch_up_leap_failed(effect_builder, rng, block_hash, error)
-            }
+            LeapState::Failed { error, .. } => self.catch_up_leap_failed(
+                effect_builder,
+                rng,
+                block_hash,
+                highest_usable_block,
+                error,
+            ),
         }
     }
 
@@ -305,6 +315,7 @@ impl MainReactor {
         effect_builder: EffectBuilder<MainEvent>,
         rng: &mut NodeRng,
         block_hash: BlockHash,
+        highest_usable_block: Option<HighestUsableBlock>,
         error: LeapActivityError,
     ) -> CatchUpInstruction {
         self.attempts += 1;
@@ -313,7 +324,7 @@ impl MainReactor {
             remaining_attempts = %self.max_attempts.saturating_sub(self.attempts),
             "CatchUp: failed leap",
         );
-        self.catch_up_leaper_idle(effect_builder, rng, block_hash)
+        self.catch_up_leaper_idle(effect_builder, rng, block_hash, highest_usable_block)
     }
 
     fn catch_up_leaper_idle(
@@ -321,6 +332,7 @@ impl MainReactor {
         effect_builder: EffectBuilder<MainEvent>,
         rng: &mut NodeRng,
         block_hash: BlockHash,
+        highest_usable_block: Option<HighestUsableBlock>,
     ) -> CatchUpInstruction {
         // we get a random sampling of peers to ask.
         let peers_to_ask = self.net.fully_connected_peers_random(
@@ -337,7 +349,22 @@ impl MainReactor {
         // latch accumulator progress to allow sync-leap time to do work
         self.block_accumulator.reset_last_progress();
 
-        let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(block_hash);
+        // if the accumulator has seen a sufficiently finalized block, it is at least as trustworthy
+        // as the one we were asked to sync to, so leap from it to start closer to the tip.
+        let trusted_block_hash = match highest_usable_block {
+            Some(highest_usable_block) => {
+                info!(
+                    %block_hash,
+                    highest_usable_block_hash = %highest_usable_block.block_hash,
+                    highest_usable_era_id = %highest_usable_block.era_id,
+                    highest_usable_block_height = highest_usable_block.block_height,
+                    "CatchUp: leaping from highest usable block known to the accumulator"
+                );
+                highest_usable_block.block_hash
+            }
+            None => block_hash,
+        };
+        let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(trusted_block_hash);
         let effects = effect_builder.immediately().event(move |_| {
             MainEvent::SyncLeaper(sync_leaper::Event::AttemptLeap {
                 sync_leap_identifier,
@@ -4,11 +4,14 @@ use tracing::error;
 
 use crate::{
     logging::LoggingConfig,
+    telemetry::TelemetryConfig,
     types::{Chainspec, NodeConfig},
     BlockAccumulatorConfig, BlockSynchronizerConfig, BlockValidatorConfig, ConsensusConfig,
     ContractRuntimeConfig, DeployAcceptorConfig, DeployBufferConfig, DiagnosticsPortConfig,
-    EventStreamServerConfig, FetcherConfig, GossipConfig, NetworkConfig, RestServerConfig,
-    RpcServerConfig, SpeculativeExecConfig, StorageConfig, UpgradeWatcherConfig,
+    EventStreamServerConfig, FetcherConfig, GossipConfig, GrpcConfig, NetworkConfig,
+    ProofRelayerConfig, RandomnessBeaconConfig, RestServerConfig, RpcServerConfig,
+    SpeculativeExecConfig, SpeculativeExecutorConfig, StorageConfig, SyncLeaperConfig,
+    UpgradeWatcherConfig, ZkProverConfig,
 };
 
 /// Root configuration.
@@ -20,6 +23,8 @@ pub struct Config {
     pub node: NodeConfig,
     /// Config values for logging.
     pub logging: LoggingConfig,
+    /// Config values for exporting spans to an OpenTelemetry collector.
+    pub telemetry: TelemetryConfig,
     /// Config values for consensus.
     pub consensus: ConsensusConfig,
     /// Config values for network.
@@ -32,6 +37,10 @@ pub struct Config {
     pub rpc_server: RpcServerConfig,
     /// Config values for speculative execution.
     pub speculative_exec_server: SpeculativeExecConfig,
+    /// Config values for the speculative executor.
+    pub speculative_executor: SpeculativeExecutorConfig,
+    /// Config values for the gRPC server.
+    pub grpc_server: GrpcConfig,
     /// Config values for storage.
     pub storage: StorageConfig,
     /// Config values for gossip.
@@ -52,8 +61,16 @@ pub struct Config {
     pub block_synchronizer: BlockSynchronizerConfig,
     /// Config values for the block validator.
     pub block_validator: BlockValidatorConfig,
+    /// Config values for the sync leaper.
+    pub sync_leaper: SyncLeaperConfig,
     /// Config values for the upgrade watcher.
     pub upgrade_watcher: UpgradeWatcherConfig,
+    /// Config values for the zero-knowledge prover.
+    pub zk_prover: ZkProverConfig,
+    /// Config values for the proof relayer.
+    pub proof_relayer: ProofRelayerConfig,
+    /// Config values for the randomness beacon.
+    pub randomness_beacon: RandomnessBeaconConfig,
 }
 
 impl Config {
@@ -10,6 +10,7 @@ use crate::{
         contract_runtime::ExecutionPreState, diagnostics_port, event_stream_server, network,
         rest_server, rpc_server, upgrade_watcher,
     },
+    config_reload,
     effect::{EffectBuilder, EffectExt, Effects},
     fatal,
     reactor::main_reactor::{
@@ -28,10 +29,20 @@ impl MainReactor {
         effect_builder: EffectBuilder<MainEvent>,
         rng: &mut NodeRng,
     ) -> Effects<MainEvent> {
+        // Stop the control logic, and with it the crank loop, once the node is shutting down.
+        if self.shutting_down {
+            return Effects::new();
+        }
         if self.attempts > self.max_attempts {
             return fatal!(effect_builder, "exceeded reattempt tolerance").ignore();
         }
-        let (delay, mut effects) = self.do_crank(effect_builder, rng);
+        let mut effects = if config_reload::take_reload_request() {
+            self.reload_config()
+        } else {
+            Effects::new()
+        };
+        let (delay, crank_effects) = self.do_crank(effect_builder, rng);
+        effects.extend(crank_effects);
         effects.extend(
             async move {
                 if !delay.is_zero() {
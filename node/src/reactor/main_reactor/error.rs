@@ -6,8 +6,9 @@ use casper_types::{bytesrepr, crypto::ErrorExt as CryptoError};
 use crate::{
     components::{
         contract_runtime, contract_runtime::BlockExecutionError, diagnostics_port, network,
-        storage, upgrade_watcher,
+        proof_relayer, storage, upgrade_watcher,
     },
+    types::CheckpointsError,
     utils::{ListeningError, LoadError},
 };
 
@@ -54,6 +55,10 @@ pub(crate) enum Error {
     #[error("bytesrepr error: {0}")]
     BytesRepr(bytesrepr::Error),
 
+    /// `ProofRelayer` component error.
+    #[error("proof relayer config error: {0}")]
+    ProofRelayer(#[from] proof_relayer::ConfigError),
+
     /// `DiagnosticsPort` component error.
     #[error("diagnostics port: {0}")]
     DiagnosticsPort(#[from] diagnostics_port::Error),
@@ -61,6 +66,14 @@ pub(crate) enum Error {
     /// Error while loading the signing key pair.
     #[error("signing key pair load error: {0}")]
     LoadSigningKeyPair(#[from] LoadError<CryptoError>),
+
+    /// Error while loading the checkpoints file.
+    #[error("checkpoints load error: {0}")]
+    LoadCheckpoints(#[from] LoadError<CheckpointsError>),
+
+    /// Error while deriving the randomness beacon's VRF key.
+    #[error("randomness beacon key derivation error: {0}")]
+    RandomnessBeaconKey(#[from] CryptoError),
 }
 
 impl From<bytesrepr::Error> for Error {
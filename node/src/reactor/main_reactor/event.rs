@@ -16,7 +16,8 @@ use crate::{
         block_validator, consensus, contract_runtime, deploy_acceptor, deploy_buffer,
         diagnostics_port, event_stream_server, fetcher, gossiper,
         network::{self, GossipedAddress},
-        rest_server, rpc_server, shutdown_trigger, storage, sync_leaper, upgrade_watcher,
+        proof_relayer, rest_server, rpc_server, shutdown_trigger, speculative_executor, storage,
+        sync_leaper, upgrade_watcher, zk_prover,
     },
     effect::{
         announcements::{
@@ -25,6 +26,7 @@ use crate::{
             FatalAnnouncement, FetchedNewBlockAnnouncement,
             FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement, MetaBlockAnnouncement,
             PeerBehaviorAnnouncement, UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
+            ZkProverAnnouncement,
         },
         diagnostics_port::DumpConsensusStateRequest,
         incoming::{
@@ -37,16 +39,17 @@ use crate::{
             BlockSynchronizerRequest, BlockValidationRequest, ChainspecRawBytesRequest,
             ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, FetcherRequest,
             MakeBlockExecutableRequest, MarkBlockCompletedRequest, MetricsRequest,
-            NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, RestRequest, RpcRequest,
-            SetNodeStopRequest, StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest,
-            UpgradeWatcherRequest,
+            NetworkInfoRequest, NetworkRequest, RandomnessBeaconRequest, ReactorStatusRequest,
+            RestRequest, RpcRequest, SetNodeStopRequest, SpeculativeExecutionRequest,
+            StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest,
+            ZkProverRequest,
         },
     },
     protocol::Message,
     reactor::ReactorEvent,
     types::{
-        ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Deploy,
-        FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
+        ApprovalsHashes, Block, BlockBodyOrChunk, BlockExecutionResultsOrChunk, BlockHeader,
+        Deploy, FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
     },
 };
 
@@ -215,6 +218,20 @@ pub(crate) enum MainEvent {
     #[from]
     ContractRuntimeAnnouncement(#[serde(skip_serializing)] ContractRuntimeAnnouncement),
     #[from]
+    SpeculativeExecutor(#[serde(skip_serializing)] speculative_executor::Event),
+    #[from]
+    SpeculativeExecutionRequest(#[serde(skip_serializing)] SpeculativeExecutionRequest),
+    #[from]
+    ZkProver(#[serde(skip_serializing)] zk_prover::Event),
+    #[from]
+    ZkProverRequest(#[serde(skip_serializing)] ZkProverRequest),
+    #[from]
+    ZkProverAnnouncement(#[serde(skip_serializing)] ZkProverAnnouncement),
+    #[from]
+    ProofRelayer(#[serde(skip_serializing)] proof_relayer::Event),
+    #[from]
+    RandomnessBeaconRequest(#[serde(skip_serializing)] RandomnessBeaconRequest),
+    #[from]
     TrieOrChunkFetcher(#[serde(skip_serializing)] fetcher::Event<TrieOrChunk>),
     #[from]
     TrieOrChunkFetcherRequest(#[serde(skip_serializing)] FetcherRequest<TrieOrChunk>),
@@ -227,6 +244,10 @@ pub(crate) enum MainEvent {
         #[serde(skip_serializing)] FetcherRequest<BlockExecutionResultsOrChunk>,
     ),
     #[from]
+    BlockBodyOrChunkFetcher(#[serde(skip_serializing)] fetcher::Event<BlockBodyOrChunk>),
+    #[from]
+    BlockBodyOrChunkFetcherRequest(#[serde(skip_serializing)] FetcherRequest<BlockBodyOrChunk>),
+    #[from]
     TrieRequestIncoming(TrieRequestIncoming),
     #[from]
     TrieDemand(TrieDemand),
@@ -284,11 +305,19 @@ impl ReactorEvent for MainEvent {
             MainEvent::AddressGossiper(_) => "AddressGossiper",
             MainEvent::BlockValidator(_) => "BlockValidator",
             MainEvent::ContractRuntimeRequest(_) => "ContractRuntimeRequest",
+            MainEvent::SpeculativeExecutor(_) => "SpeculativeExecutor",
+            MainEvent::SpeculativeExecutionRequest(_) => "SpeculativeExecutionRequest",
+            MainEvent::ZkProver(_) => "ZkProver",
+            MainEvent::ZkProverRequest(_) => "ZkProverRequest",
+            MainEvent::ZkProverAnnouncement(_) => "ZkProverAnnouncement",
+            MainEvent::ProofRelayer(_) => "ProofRelayer",
+            MainEvent::RandomnessBeaconRequest(_) => "RandomnessBeaconRequest",
             MainEvent::BlockHeaderFetcher(_) => "BlockHeaderFetcher",
             MainEvent::TrieOrChunkFetcher(_) => "TrieOrChunkFetcher",
             MainEvent::BlockExecutionResultsOrChunkFetcher(_) => {
                 "BlockExecutionResultsOrChunkFetcher"
             }
+            MainEvent::BlockBodyOrChunkFetcher(_) => "BlockBodyOrChunkFetcher",
             MainEvent::FinalitySignatureFetcher(_) => "FinalitySignatureFetcher",
             MainEvent::SyncLeapFetcher(_) => "SyncLeapFetcher",
             MainEvent::ApprovalsHashesFetcher(_) => "ApprovalsHashesFetcher",
@@ -301,6 +330,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::BlockExecutionResultsOrChunkFetcherRequest(_) => {
                 "BlockExecutionResultsOrChunkFetcherRequest"
             }
+            MainEvent::BlockBodyOrChunkFetcherRequest(_) => "BlockBodyOrChunkFetcherRequest",
             MainEvent::LegacyDeployFetcherRequest(_) => "LegacyDeployFetcherRequest",
             MainEvent::DeployFetcherRequest(_) => "DeployFetcherRequest",
             MainEvent::FinalitySignatureFetcherRequest(_) => "FinalitySignatureFetcherRequest",
@@ -364,6 +394,114 @@ impl ReactorEvent for MainEvent {
             }
         }
     }
+
+    /// Events are attributed to the component handling them.  Announcements, which may be routed
+    /// to several components, are attributed to the component making them.
+    fn component(&self) -> &'static str {
+        match self {
+            MainEvent::ReactorCrank
+            | MainEvent::MainReactorRequest(_)
+            | MainEvent::ChainspecRawBytesRequest(_)
+            | MainEvent::ControlAnnouncement(_)
+            | MainEvent::FatalAnnouncement(_)
+            | MainEvent::MetaBlockAnnouncement(_)
+            | MainEvent::UnexecutedBlockAnnouncement(_)
+            | MainEvent::GotBlockAfterUpgradeEraValidators(_, _, _) => "MainReactor",
+            MainEvent::UpgradeWatcher(_)
+            | MainEvent::UpgradeWatcherRequest(_)
+            | MainEvent::UpgradeWatcherAnnouncement(_) => "UpgradeWatcher",
+            MainEvent::RpcServer(_) => "RpcServer",
+            MainEvent::RestServer(_) => "RestServer",
+            MainEvent::MetricsRequest(_) => "Metrics",
+            MainEvent::EventStreamServer(_) => "EventStreamServer",
+            MainEvent::ShutdownTrigger(_) | MainEvent::SetNodeStopRequest(_) => "ShutdownTrigger",
+            MainEvent::DiagnosticsPort(_) => "DiagnosticsPort",
+            MainEvent::Network(_)
+            | MainEvent::NetworkRequest(_)
+            | MainEvent::NetworkInfoRequest(_)
+            | MainEvent::NetworkPeerBehaviorAnnouncement(_) => "Network",
+            MainEvent::NetworkPeerProvidingData(_) => "Fetchers",
+            MainEvent::AddressGossiper(_)
+            | MainEvent::AddressGossiperCrank(_)
+            | MainEvent::AddressGossiperIncoming(_)
+            | MainEvent::AddressGossiperAnnouncement(_) => "AddressGossiper",
+            MainEvent::SyncLeaper(_) => "SyncLeaper",
+            MainEvent::SyncLeapFetcher(_) | MainEvent::SyncLeapFetcherRequest(_) => {
+                "SyncLeapFetcher"
+            }
+            MainEvent::Consensus(_)
+            | MainEvent::ConsensusMessageIncoming(_)
+            | MainEvent::ConsensusDemand(_)
+            | MainEvent::ConsensusAnnouncement(_)
+            | MainEvent::DumpConsensusStateRequest(_) => "Consensus",
+            MainEvent::BlockHeaderFetcher(_) | MainEvent::BlockHeaderFetcherRequest(_) => {
+                "BlockHeaderFetcher"
+            }
+            MainEvent::BlockValidator(_) | MainEvent::BlockValidatorRequest(_) => "BlockValidator",
+            MainEvent::BlockAccumulator(_)
+            | MainEvent::BlockAccumulatorRequest(_)
+            | MainEvent::BlockAccumulatorAnnouncement(_)
+            | MainEvent::FinalitySignatureIncoming(_) => "BlockAccumulator",
+            MainEvent::BlockSynchronizer(_) | MainEvent::BlockSynchronizerRequest(_) => {
+                "BlockSynchronizer"
+            }
+            MainEvent::ApprovalsHashesFetcher(_) | MainEvent::ApprovalsHashesFetcherRequest(_) => {
+                "ApprovalsHashesFetcher"
+            }
+            MainEvent::BlockGossiper(_)
+            | MainEvent::BlockGossiperIncoming(_)
+            | MainEvent::BlockGossiperAnnouncement(_) => "BlockGossiper",
+            MainEvent::BlockFetcher(_)
+            | MainEvent::BlockFetcherRequest(_)
+            | MainEvent::BlockFetcherAnnouncement(_) => "BlockFetcher",
+            MainEvent::FinalitySignatureGossiper(_)
+            | MainEvent::FinalitySignatureGossiperIncoming(_)
+            | MainEvent::FinalitySignatureGossiperAnnouncement(_) => "FinalitySignatureGossiper",
+            MainEvent::FinalitySignatureFetcher(_)
+            | MainEvent::FinalitySignatureFetcherRequest(_)
+            | MainEvent::FinalitySignatureFetcherAnnouncement(_) => "FinalitySignatureFetcher",
+            MainEvent::DeployAcceptor(_)
+            | MainEvent::AcceptDeployRequest(_)
+            | MainEvent::DeployAcceptorAnnouncement(_) => "DeployAcceptor",
+            MainEvent::DeployGossiper(_)
+            | MainEvent::DeployGossiperIncoming(_)
+            | MainEvent::DeployGossiperAnnouncement(_) => "DeployGossiper",
+            MainEvent::DeployBuffer(_)
+            | MainEvent::DeployBufferRequest(_)
+            | MainEvent::DeployBufferAnnouncement(_) => "DeployBuffer",
+            MainEvent::LegacyDeployFetcher(_) | MainEvent::LegacyDeployFetcherRequest(_) => {
+                "LegacyDeployFetcher"
+            }
+            MainEvent::DeployFetcher(_) | MainEvent::DeployFetcherRequest(_) => "DeployFetcher",
+            MainEvent::ContractRuntime(_)
+            | MainEvent::ContractRuntimeRequest(_)
+            | MainEvent::ContractRuntimeAnnouncement(_)
+            | MainEvent::TrieRequestIncoming(_)
+            | MainEvent::TrieDemand(_) => "ContractRuntime",
+            MainEvent::SpeculativeExecutor(_) | MainEvent::SpeculativeExecutionRequest(_) => {
+                "SpeculativeExecutor"
+            }
+            MainEvent::ZkProver(_)
+            | MainEvent::ZkProverRequest(_)
+            | MainEvent::ZkProverAnnouncement(_) => "ZkProver",
+            MainEvent::ProofRelayer(_) => "ProofRelayer",
+            MainEvent::RandomnessBeaconRequest(_) => "RandomnessBeacon",
+            MainEvent::TrieOrChunkFetcher(_)
+            | MainEvent::TrieOrChunkFetcherRequest(_)
+            | MainEvent::TrieResponseIncoming(_) => "TrieOrChunkFetcher",
+            MainEvent::BlockExecutionResultsOrChunkFetcher(_)
+            | MainEvent::BlockExecutionResultsOrChunkFetcherRequest(_) => {
+                "BlockExecutionResultsOrChunkFetcher"
+            }
+            MainEvent::BlockBodyOrChunkFetcher(_)
+            | MainEvent::BlockBodyOrChunkFetcherRequest(_) => "BlockBodyOrChunkFetcher",
+            MainEvent::Storage(_)
+            | MainEvent::StorageRequest(_)
+            | MainEvent::NetworkPeerRequestingData(_)
+            | MainEvent::MakeBlockExecutableRequest(_)
+            | MainEvent::MarkBlockCompletedRequest(_) => "Storage",
+        }
+    }
 }
 
 impl Display for MainEvent {
@@ -403,6 +541,9 @@ impl Display for MainEvent {
             MainEvent::BlockExecutionResultsOrChunkFetcher(event) => {
                 write!(f, "block execution results or chunk fetcher: {}", event)
             }
+            MainEvent::BlockBodyOrChunkFetcher(event) => {
+                write!(f, "block body or chunk fetcher: {}", event)
+            }
             MainEvent::FinalitySignatureFetcher(event) => {
                 write!(f, "finality signature fetcher: {}", event)
             }
@@ -456,6 +597,9 @@ impl Display for MainEvent {
                     request
                 )
             }
+            MainEvent::BlockBodyOrChunkFetcherRequest(request) => {
+                write!(f, "block body or chunk fetcher request: {}", request)
+            }
             MainEvent::LegacyDeployFetcherRequest(request) => {
                 write!(f, "legacy deploy fetcher request: {}", request)
             }
@@ -528,6 +672,13 @@ impl Display for MainEvent {
             MainEvent::TrieResponseIncoming(inner) => Display::fmt(inner, f),
             MainEvent::FinalitySignatureIncoming(inner) => Display::fmt(inner, f),
             MainEvent::ContractRuntime(inner) => Display::fmt(inner, f),
+            MainEvent::SpeculativeExecutor(inner) => Display::fmt(inner, f),
+            MainEvent::SpeculativeExecutionRequest(inner) => Display::fmt(inner, f),
+            MainEvent::ZkProver(inner) => Display::fmt(inner, f),
+            MainEvent::ZkProverRequest(inner) => Display::fmt(inner, f),
+            MainEvent::ZkProverAnnouncement(inner) => Display::fmt(inner, f),
+            MainEvent::ProofRelayer(inner) => Display::fmt(inner, f),
+            MainEvent::RandomnessBeaconRequest(inner) => Display::fmt(inner, f),
             MainEvent::BlockGossiper(inner) => Display::fmt(inner, f),
             MainEvent::BlockGossiperIncoming(inner) => Display::fmt(inner, f),
             MainEvent::BlockGossiperAnnouncement(inner) => Display::fmt(inner, f),
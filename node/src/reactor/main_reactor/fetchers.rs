@@ -7,8 +7,8 @@ use crate::{
     reactor,
     reactor::main_reactor::MainEvent,
     types::{
-        ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Deploy,
-        FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
+        ApprovalsHashes, Block, BlockBodyOrChunk, BlockExecutionResultsOrChunk, BlockHeader,
+        Deploy, FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
     },
     utils::Source,
     FetcherConfig, NodeRng,
@@ -25,6 +25,7 @@ pub(super) struct Fetchers {
     deploy_fetcher: Fetcher<Deploy>,
     trie_or_chunk_fetcher: Fetcher<TrieOrChunk>,
     block_execution_results_or_chunk_fetcher: Fetcher<BlockExecutionResultsOrChunk>,
+    block_body_or_chunk_fetcher: Fetcher<BlockBodyOrChunk>,
 }
 
 impl Fetchers {
@@ -50,6 +51,11 @@ impl Fetchers {
                 config,
                 metrics_registry,
             )?,
+            block_body_or_chunk_fetcher: Fetcher::new(
+                "block_body_or_chunk_fetcher",
+                config,
+                metrics_registry,
+            )?,
         })
     }
 
@@ -156,6 +162,16 @@ impl Fetchers {
                     ),
                 )
             }
+            MainEvent::BlockBodyOrChunkFetcher(event) => reactor::wrap_effects(
+                MainEvent::BlockBodyOrChunkFetcher,
+                self.block_body_or_chunk_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::BlockBodyOrChunkFetcherRequest(request) => reactor::wrap_effects(
+                MainEvent::BlockBodyOrChunkFetcher,
+                self.block_body_or_chunk_fetcher
+                    .handle_event(effect_builder, rng, request.into()),
+            ),
 
             // MISC DISPATCHING
             MainEvent::DeployAcceptorAnnouncement(
@@ -10,7 +10,7 @@ use casper_types::{EraId, Timestamp};
 
 use crate::{
     components::{
-        block_accumulator::{SyncIdentifier, SyncInstruction},
+        block_accumulator::{self, SyncIdentifier, SyncInstruction},
         block_synchronizer::BlockSynchronizerProgress,
         contract_runtime::EraValidatorsRequest,
         storage::HighestOrphanedBlockResult,
@@ -43,6 +43,10 @@ enum SyncBackInstruction {
         sync_hash: BlockHash,
         sync_era: EraId,
     },
+    SyncHeader {
+        sync_hash: BlockHash,
+        sync_era: EraId,
+    },
     Syncing,
     TtlSynced,
     GenesisSynced,
@@ -55,6 +59,9 @@ impl Display for SyncBackInstruction {
             SyncBackInstruction::Sync { sync_hash, .. } => {
                 write!(f, "attempt to sync {}", sync_hash)
             }
+            SyncBackInstruction::SyncHeader { sync_hash, .. } => {
+                write!(f, "attempt to sync header of {}", sync_hash)
+            }
             SyncBackInstruction::Syncing => write!(f, "syncing"),
             SyncBackInstruction::TtlSynced => write!(f, "ttl reached"),
             SyncBackInstruction::GenesisSynced => write!(f, "genesis reached"),
@@ -250,19 +257,32 @@ impl MainReactor {
                 // when / if it occurs.
                 Some(KeepUpInstruction::CatchUp)
             }
-            SyncInstruction::BlockSync { block_hash } => {
-                debug!("KeepUp: BlockSync: {:?}", block_hash);
+            SyncInstruction::BlockSync {
+                block_hash,
+                has_strict_finality,
+            } => {
+                debug!(has_strict_finality, "KeepUp: BlockSync: {:?}", block_hash);
                 if self
                     .block_synchronizer
                     .register_block_by_hash(block_hash, false)
                 {
                     info!(%block_hash, "KeepUp: BlockSync: registered block by hash");
-                    Some(KeepUpInstruction::Do(
-                        Duration::ZERO,
-                        effect_builder.immediately().event(|_| {
-                            MainEvent::BlockSynchronizerRequest(BlockSynchronizerRequest::NeedNext)
-                        }),
-                    ))
+                    let mut effects = effect_builder.immediately().event(|_| {
+                        MainEvent::BlockSynchronizerRequest(BlockSynchronizerRequest::NeedNext)
+                    });
+                    if !has_strict_finality {
+                        // the block is only accepted once the accumulator holds strict finality
+                        // for it, so rather than waiting for the next scheduled fetch, request
+                        // the missing signatures while the block is being synced.
+                        effects.extend(effect_builder.immediately().event(move |_| {
+                            MainEvent::BlockAccumulator(
+                                block_accumulator::Event::SyncingBlockWithWeakFinality {
+                                    block_hash,
+                                },
+                            )
+                        }));
+                    }
+                    Some(KeepUpInstruction::Do(Duration::ZERO, effects))
                 } else {
                     // this block has already been registered and is being worked on
                     None
@@ -309,8 +329,19 @@ impl MainReactor {
                     sync_era,
                 } => {
                     debug!(%sync_hash, ?sync_era, validator_matrix_eras=?self.validator_matrix.eras(), "KeepUp: historical sync back instruction");
-                    if self.validator_matrix.has_era(&sync_era) {
-                        Some(self.sync_back_register(effect_builder, rng, sync_hash))
+                    if self.has_or_restore_era_validators(sync_era) {
+                        Some(self.sync_back_register(effect_builder, rng, sync_hash, false))
+                    } else {
+                        Some(self.sync_back_leap(effect_builder, rng, sync_hash))
+                    }
+                }
+                SyncBackInstruction::SyncHeader {
+                    sync_hash,
+                    sync_era,
+                } => {
+                    debug!(%sync_hash, ?sync_era, "KeepUp: historical header back fill instruction");
+                    if self.has_or_restore_era_validators(sync_era) {
+                        Some(self.sync_back_register(effect_builder, rng, sync_hash, true))
                     } else {
                         Some(self.sync_back_leap(effect_builder, rng, sync_hash))
                     }
@@ -413,6 +444,26 @@ impl MainReactor {
         KeepUpInstruction::Do(Duration::ZERO, effects)
     }
 
+    // Validators of eras evicted from the validator matrix can be reconstructed from our own
+    // stored switch blocks, sparing us a leap to acquire them from peers.
+    fn has_or_restore_era_validators(&mut self, era_id: EraId) -> bool {
+        if self.validator_matrix.has_era(&era_id) {
+            return true;
+        }
+        match self.storage.read_era_validator_weights(era_id) {
+            Ok(Some(validator_weights)) => {
+                debug!(%era_id, "KeepUp: restored historical validators for era from storage");
+                self.validator_matrix
+                    .register_validator_weights(era_id, validator_weights);
+            }
+            Ok(None) => {}
+            Err(error) => {
+                warn!(%error, %era_id, "KeepUp: failed to read historical validators for era");
+            }
+        }
+        self.validator_matrix.has_era(&era_id)
+    }
+
     fn sync_back_leap(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
@@ -550,11 +601,16 @@ impl MainReactor {
         effect_builder: EffectBuilder<MainEvent>,
         rng: &mut NodeRng,
         parent_hash: BlockHash,
+        headers_only: bool,
     ) -> KeepUpInstruction {
-        if self
-            .block_synchronizer
-            .register_block_by_hash(parent_hash, true)
-        {
+        let registered = if headers_only {
+            self.block_synchronizer
+                .register_block_header_by_hash(parent_hash)
+        } else {
+            self.block_synchronizer
+                .register_block_by_hash(parent_hash, true)
+        };
+        if registered {
             // sync the parent_hash block; we get a random sampling of peers to ask.
             // it is possible that we may get a random sampling that do not have the data
             // we need, but the synchronizer should (eventually) detect that and ask for
@@ -612,6 +668,9 @@ impl MainReactor {
                 if let Some(synched) = self.synched(&highest_orphaned_block_header)? {
                     return Ok(Some(synched));
                 }
+                if self.reached_back_fill_depth(&highest_orphaned_block_header) {
+                    return self.header_back_fill_instruction(&highest_orphaned_block_header);
+                }
                 let (sync_hash, sync_era) =
                     self.sync_hash_and_era(&highest_orphaned_block_header)?;
                 debug!(?sync_era, %sync_hash, "KeepUp: historical sync target era and block hash");
@@ -637,6 +696,75 @@ impl MainReactor {
         }
     }
 
+    /// Returns `true` if a back fill depth is configured and the historical blocks synced in full
+    /// already reach down to it, or if the blocks below have been pruned from storage or their
+    /// global state may have been garbage collected.
+    fn reached_back_fill_depth(&self, highest_orphaned_block_header: &BlockHeader) -> bool {
+        let pruned_below_height = self
+            .storage
+            .pruned_below_height()
+            .max(self.storage.global_state_pruned_below_height());
+        if highest_orphaned_block_header.height() <= pruned_below_height {
+            return true;
+        }
+        match (
+            self.back_fill_depth,
+            self.storage.highest_complete_block_height(),
+        ) {
+            (Some(back_fill_depth), Some(highest_complete_block_height)) => {
+                highest_orphaned_block_header.height()
+                    <= highest_complete_block_height.saturating_sub(back_fill_depth)
+            }
+            _ => false,
+        }
+    }
+
+    fn header_back_fill_instruction(
+        &mut self,
+        highest_orphaned_block_header: &BlockHeader,
+    ) -> Result<Option<SyncBackInstruction>, String> {
+        // below the back fill depth we only acquire block headers and their finality signatures.
+        // we walk down from the lowest header we already know to be back filled to find the
+        // lowest contiguous one; its parent is the next header we need.
+        let mut header_back_fill_tip = match self.header_back_fill_tip.take() {
+            Some(tip) if tip.height() <= highest_orphaned_block_header.height() => tip,
+            _ => highest_orphaned_block_header.clone(),
+        };
+        while !header_back_fill_tip.is_genesis() {
+            match self
+                .storage
+                .read_block_header(header_back_fill_tip.parent_hash())
+                .map_err(|err| err.to_string())?
+            {
+                Some(parent_block_header)
+                    if self
+                        .storage
+                        .get_finality_signatures_for_block(parent_block_header.block_hash())
+                        .is_some() =>
+                {
+                    header_back_fill_tip = parent_block_header;
+                }
+                _ => break,
+            }
+        }
+
+        let instruction = match self.synched(&header_back_fill_tip)? {
+            Some(synched) => synched,
+            None => {
+                let (sync_hash, sync_era) = self.sync_hash_and_era(&header_back_fill_tip)?;
+                debug!(?sync_era, %sync_hash, "KeepUp: historical header back fill target era and block hash");
+                self.validator_matrix
+                    .register_retrograde_latch(Some(sync_era));
+                SyncBackInstruction::SyncHeader {
+                    sync_hash,
+                    sync_era,
+                }
+            }
+        };
+        self.header_back_fill_tip = Some(header_back_fill_tip);
+        Ok(Some(instruction))
+    }
+
     fn synched(
         &self,
         highest_orphaned_block_header: &BlockHeader,
@@ -0,0 +1,84 @@
+use tracing::{error, info, warn};
+
+use crate::{
+    config_reload,
+    effect::Effects,
+    logging,
+    reactor::{
+        self,
+        main_reactor::{MainEvent, MainReactor},
+    },
+};
+
+impl MainReactor {
+    /// Reads the config file again and applies the changed settings which can be reloaded.
+    ///
+    /// Changes to any other setting are logged and otherwise ignored, and the config is kept as it
+    /// was if the file cannot be read or is invalid.
+    pub(super) fn reload_config(&mut self) -> Effects<MainEvent> {
+        let mut config = match config_reload::read_config() {
+            Ok(config) => config,
+            Err(error) => {
+                error!(%error, "failed to reload config");
+                return Effects::new();
+            }
+        };
+        config.ensure_valid(&self.chainspec);
+        let changed = match config_reload::changed_settings(&self.config, &config) {
+            Ok(changed) => changed,
+            Err(error) => {
+                error!(%error, "failed to compare reloaded config");
+                return Effects::new();
+            }
+        };
+        let (reloaded, ignored): (Vec<_>, Vec<_>) = changed
+            .into_iter()
+            .partition(|setting| config_reload::is_reloadable(setting));
+        if !ignored.is_empty() {
+            warn!(
+                settings = %ignored.join(", "),
+                "changed settings which cannot be reloaded only take effect after a restart"
+            );
+        }
+        if reloaded.is_empty() {
+            info!("reloaded config, no changes to apply");
+            return Effects::new();
+        }
+
+        let has_changed = |prefix: &str| reloaded.iter().any(|setting| setting.starts_with(prefix));
+        let mut effects = Effects::new();
+        if has_changed("logging.filter") {
+            if let Err(error) = logging::reload_global_env_filter_from_config(&config.logging) {
+                warn!(%error, "could not apply reloaded log filter");
+            }
+        }
+        if has_changed("gossip.") {
+            self.address_gossiper.update_config(config.gossip);
+            self.deploy_gossiper.update_config(config.gossip);
+            self.block_gossiper.update_config(config.gossip);
+            self.finality_signature_gossiper
+                .update_config(config.gossip);
+        }
+        self.rpc_server.update_qps_limits(
+            config.rpc_server.qps_limit,
+            config.speculative_exec_server.qps_limit,
+        );
+        if has_changed("network.known_addresses") {
+            effects.extend(reactor::wrap_effects(
+                MainEvent::Network,
+                self.net
+                    .update_known_addresses(config.network.known_addresses.clone()),
+            ));
+        }
+
+        // Only the reloaded settings are taken over, so that changes to the others are reported
+        // again on the next reload.
+        self.config.logging.filter = config.logging.filter;
+        self.config.gossip = config.gossip;
+        self.config.rpc_server.qps_limit = config.rpc_server.qps_limit;
+        self.config.speculative_exec_server.qps_limit = config.speculative_exec_server.qps_limit;
+        self.config.network.known_addresses = config.network.known_addresses;
+        info!(settings = %reloaded.join(", "), "reloaded config");
+        effects
+    }
+}
@@ -46,6 +46,11 @@ pub enum QueueKind {
     ToStorage,
     /// Contract runtime events.
     ContractRuntime,
+    /// Speculative execution events.
+    ///
+    /// Kept apart from the contract runtime events, so that a flood of speculative execution
+    /// requests cannot delay block execution.
+    SpeculativeExecution,
     /// Consensus events.
     Consensus,
     /// Validation events.
@@ -72,6 +77,7 @@ impl Display for QueueKind {
             QueueKind::FromStorage => "FromStorage",
             QueueKind::ToStorage => "ToStorage",
             QueueKind::ContractRuntime => "ContractRuntime",
+            QueueKind::SpeculativeExecution => "SpeculativeExecution",
             QueueKind::SyncGlobalState => "SyncGlobalState",
             QueueKind::FinalitySignature => "FinalitySignature",
             QueueKind::Consensus => "Consensus",
@@ -106,6 +112,7 @@ impl QueueKind {
             QueueKind::FromStorage => 4,
             QueueKind::ToStorage => 4,
             QueueKind::ContractRuntime => 4,
+            QueueKind::SpeculativeExecution => 1,
             QueueKind::SyncGlobalState => 4,
             QueueKind::Consensus => 4,
             QueueKind::FinalitySignature => 4,
@@ -138,6 +145,7 @@ impl QueueKind {
             QueueKind::FromStorage => "from_storage",
             QueueKind::ToStorage => "to_storage",
             QueueKind::ContractRuntime => "contract_runtime",
+            QueueKind::SpeculativeExecution => "speculative_execution",
             QueueKind::Consensus => "consensus",
             QueueKind::Validation => "validation",
             QueueKind::FinalitySignature => "finality_signature",
@@ -0,0 +1,98 @@
+//! Exporting tracing spans to an OpenTelemetry collector.
+//!
+//! If enabled, the spans of block execution, sync cycles and RPC requests, along with any other
+//! span passing the log filter, are sent to a collector via OTLP over gRPC.
+
+use std::{str::FromStr, time::Duration};
+
+use anyhow::anyhow;
+use datasize::DataSize;
+use opentelemetry::{
+    global,
+    sdk::{
+        trace::{self, Sampler, Tracer},
+        Resource,
+    },
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+const DEFAULT_SERVICE_NAME: &str = "casper-node";
+const DEFAULT_SAMPLING_RATIO: f64 = 1.0;
+const DEFAULT_EXPORT_TIMEOUT: &str = "10 seconds";
+
+/// OpenTelemetry configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct TelemetryConfig {
+    /// Whether to export spans to an OpenTelemetry collector.
+    pub enabled: bool,
+    /// The gRPC endpoint of the OTLP collector.
+    pub otlp_endpoint: String,
+    /// The service name the spans are reported under.
+    pub service_name: String,
+    /// The fraction of traces which are exported, between 0.0 and 1.0.
+    pub sampling_ratio: f64,
+    /// The timeout for exporting a batch of spans.
+    pub export_timeout: TimeDiff,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        TelemetryConfig {
+            enabled: false,
+            otlp_endpoint: DEFAULT_OTLP_ENDPOINT.to_string(),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            sampling_ratio: DEFAULT_SAMPLING_RATIO,
+            export_timeout: TimeDiff::from_str(DEFAULT_EXPORT_TIMEOUT).unwrap(),
+        }
+    }
+}
+
+/// Installs the global tracer provider exporting spans to the configured collector, and returns
+/// its tracer.
+///
+/// Returns `None` if exporting is disabled.  Must be called from within a tokio runtime, which
+/// runs the batch exporter.
+pub(crate) fn install_tracer(config: &TelemetryConfig) -> anyhow::Result<Option<Tracer>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    if !(0.0..=1.0).contains(&config.sampling_ratio) {
+        return Err(anyhow!(
+            "telemetry sampling ratio must be between 0.0 and 1.0, got {}",
+            config.sampling_ratio
+        ));
+    }
+
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(config.otlp_endpoint.clone())
+        .with_timeout(Duration::from(config.export_timeout));
+    let trace_config = trace::config()
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sampling_ratio,
+        ))))
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]));
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(trace_config)
+        .install_batch(opentelemetry::runtime::Tokio)?;
+    Ok(Some(tracer))
+}
+
+/// Exports all remaining spans and shuts down the global tracer provider.
+///
+/// Does nothing if exporting is disabled.
+pub(crate) fn shutdown() {
+    global::shutdown_tracer_provider();
+}
@@ -5,11 +5,13 @@ mod available_block_range;
 mod block;
 mod block_hash_height_and_era;
 pub mod chainspec;
+mod checkpoints;
 mod chunkable;
 mod deploy;
 pub mod error;
 mod exit_code;
 pub mod json_compatibility;
+pub(crate) mod light_client_update;
 mod max_ttl;
 mod node_config;
 mod node_id;
@@ -33,13 +35,15 @@ pub(crate) use block::{
 };
 pub use block::{
     json_compatibility::{JsonBlock, JsonBlockHeader},
-    Block, BlockAndDeploys, BlockBody, BlockExecutionResultsOrChunk,
-    BlockExecutionResultsOrChunkId, BlockExecutionResultsOrChunkIdDisplay, BlockHash, BlockHeader,
-    BlockSignatures, FinalitySignature, FinalizedBlock,
+    Block, BlockAndDeploys, BlockBody, BlockBodyOrChunk, BlockBodyOrChunkId,
+    BlockBodyOrChunkIdDisplay, BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId,
+    BlockExecutionResultsOrChunkIdDisplay, BlockHash, BlockHeader, BlockSignatures,
+    FinalitySignature, FinalizedBlock,
 };
 pub(crate) use block_hash_height_and_era::BlockHashHeightAndEra;
 pub use chainspec::Chainspec;
 pub(crate) use chainspec::{ActivationPoint, ChainspecRawBytes};
+pub(crate) use checkpoints::{Checkpoints, CheckpointsError};
 pub use chunkable::Chunkable;
 pub use datasize::DataSize;
 pub use deploy::{
@@ -52,6 +56,7 @@ pub(crate) use deploy::{
 };
 pub use error::BlockValidationError;
 pub use exit_code::ExitCode;
+pub(crate) use light_client_update::LightClientUpdate;
 pub(crate) use max_ttl::MaxTtl;
 pub use node_config::{NodeConfig, SyncHandling};
 pub(crate) use node_id::NodeId;
@@ -833,8 +833,9 @@ pub(crate) mod specimen_support {
     };
 
     use super::{
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHeader,
-        BlockHeaderWithMetadata, BlockSignatures, EraEnd,
+        BlockBodyOrChunk, BlockBodyOrChunkId, BlockExecutionResultsOrChunk,
+        BlockExecutionResultsOrChunkId, BlockHeader, BlockHeaderWithMetadata, BlockSignatures,
+        EraEnd,
     };
     use once_cell::sync::OnceCell;
 
@@ -911,6 +912,15 @@ pub(crate) mod specimen_support {
         }
     }
 
+    impl LargestSpecimen for BlockBodyOrChunkId {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            BlockBodyOrChunkId {
+                chunk_index: u64::MAX,
+                block_hash: LargestSpecimen::largest_specimen(estimator, cache),
+            }
+        }
+    }
+
     impl LargestSpecimen for BlockHeaderWithMetadata {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
             BlockHeaderWithMetadata {
@@ -939,6 +949,15 @@ pub(crate) mod specimen_support {
             }
         }
     }
+
+    impl LargestSpecimen for BlockBodyOrChunk {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            BlockBodyOrChunk {
+                block_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                value: LargestSpecimen::largest_specimen(estimator, cache),
+            }
+        }
+    }
 }
 
 impl BlockHeader {
@@ -2069,6 +2088,142 @@ impl<'a> Display for BlockExecutionResultsOrChunkIdDisplay<'a> {
     }
 }
 
+/// Represents the body of a single block or a chunk of it, allowing bodies too large for a single
+/// network message to be fetched piecewise.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, DataSize)]
+pub struct BlockBodyOrChunk {
+    /// Block to which this value or chunk refers to.
+    block_hash: BlockHash,
+    /// Complete body of the block or a chunk of it.
+    value: ValueOrChunk<BlockBody>,
+}
+
+impl BlockBodyOrChunk {
+    /// Consumes `self` and returns inner `ValueOrChunk` field.
+    pub fn into_value(self) -> ValueOrChunk<BlockBody> {
+        self.value
+    }
+
+    /// Returns the hash of the block this body belongs to.
+    pub fn block_hash(&self) -> &BlockHash {
+        &self.block_hash
+    }
+
+    #[cfg(test)]
+    pub(crate) fn new_value(block: &Block) -> Self {
+        Self {
+            block_hash: *block.hash(),
+            value: ValueOrChunk::Value(block.body().clone()),
+        }
+    }
+}
+
+impl FetchItem for BlockBodyOrChunk {
+    type Id = BlockBodyOrChunkId;
+    type ValidationError = ChunkWithProofVerificationError;
+    type ValidationMetadata = EmptyValidationMetadata;
+
+    const TAG: Tag = Tag::BlockBodyOrChunk;
+
+    fn fetch_id(&self) -> Self::Id {
+        let chunk_index = match &self.value {
+            ValueOrChunk::Value(_) => 0,
+            ValueOrChunk::ChunkWithProof(chunk) => chunk.proof().index(),
+        };
+        BlockBodyOrChunkId {
+            chunk_index,
+            block_hash: self.block_hash,
+        }
+    }
+
+    fn validate(&self, _metadata: &EmptyValidationMetadata) -> Result<(), Self::ValidationError> {
+        // The body hash in the block header is not a Merkle root, so a whole body can only be
+        // checked against it once it is combined with its header.
+        match &self.value {
+            ValueOrChunk::Value(_) => Ok(()),
+            ValueOrChunk::ChunkWithProof(chunk_with_proof) => chunk_with_proof.verify(),
+        }
+    }
+}
+
+impl Display for BlockBodyOrChunk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} for block {}", self.value, self.block_hash.inner())
+    }
+}
+
+/// ID of the request for a block body or chunk.
+#[derive(DataSize, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BlockBodyOrChunkId {
+    /// Index of the chunk being requested.
+    chunk_index: u64,
+    /// Hash of the block.
+    block_hash: BlockHash,
+}
+
+impl BlockBodyOrChunkId {
+    /// Returns a request for the body of the given block.
+    /// The `chunk_index` is set to 0 as the starting point of the fetch cycle.
+    /// If the body is small enough not to be chunked the index will be 0 as well.
+    pub fn new(block_hash: BlockHash) -> Self {
+        BlockBodyOrChunkId {
+            chunk_index: 0,
+            block_hash,
+        }
+    }
+
+    /// Given a serialized ID, deserializes it for display purposes.
+    fn fmt_serialized(f: &mut Formatter, serialized_id: &[u8]) -> fmt::Result {
+        match bincode::deserialize::<Self>(serialized_id) {
+            Ok(ref body_or_chunk_id) => fmt::Display::fmt(body_or_chunk_id, f),
+            Err(_) => f.write_str("<invalid>"),
+        }
+    }
+
+    /// Returns the request for the `next_chunk` retaining the original request's block hash.
+    pub fn next_chunk(&self, next_chunk: u64) -> Self {
+        BlockBodyOrChunkId {
+            chunk_index: next_chunk,
+            block_hash: self.block_hash,
+        }
+    }
+
+    pub(crate) fn block_hash(&self) -> &BlockHash {
+        &self.block_hash
+    }
+
+    pub(crate) fn chunk_index(&self) -> u64 {
+        self.chunk_index
+    }
+
+    /// Constructs a response for the request, retaining the request's `block_hash`.
+    pub(crate) fn response(&self, value: ValueOrChunk<BlockBody>) -> BlockBodyOrChunk {
+        BlockBodyOrChunk {
+            block_hash: self.block_hash,
+            value,
+        }
+    }
+}
+
+impl Display for BlockBodyOrChunkId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "body of {} or chunk #{}",
+            self.block_hash, self.chunk_index
+        )
+    }
+}
+
+/// Helper struct to on-demand deserialize a block body or chunk ID for display purposes.
+pub struct BlockBodyOrChunkIdDisplay<'a>(pub &'a [u8]);
+
+impl<'a> Display for BlockBodyOrChunkIdDisplay<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        BlockBodyOrChunkId::fmt_serialized(f, self.0)
+    }
+}
+
 pub(crate) mod json_compatibility {
     use super::*;
 
@@ -2303,6 +2458,11 @@ pub(crate) mod json_compatibility {
         pub fn transfer_hashes(&self) -> &Vec<DeployHash> {
             &self.body.transfer_hashes
         }
+
+        /// Returns the public key of the validator which proposed the `Block`.
+        pub fn proposer(&self) -> &PublicKey {
+            &self.body.proposer
+        }
     }
 
     impl DocExample for JsonBlock {
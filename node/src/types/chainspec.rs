@@ -7,10 +7,12 @@ mod chainspec_raw_bytes;
 mod core_config;
 mod deploy_config;
 mod error;
+mod features_config;
 mod global_state_update;
 mod highway_config;
 mod network_config;
 mod parse_toml;
+mod proof_requests_config;
 mod protocol_config;
 
 use std::{fmt::Debug, path::Path, sync::Arc};
@@ -43,9 +45,13 @@ pub use self::{
     core_config::{ConsensusProtocolName, CoreConfig, LegacyRequiredFinality},
     deploy_config::DeployConfig,
     error::Error,
-    global_state_update::GlobalStateUpdate,
+    features_config::{Feature, FeaturesConfig},
+    global_state_update::{
+        GlobalStateUpdate, GlobalStateUpdateConfig, GLOBAL_STATE_UPDATE_FILENAME,
+    },
     highway_config::HighwayConfig,
     network_config::NetworkConfig,
+    proof_requests_config::ProofRequestsConfig,
     protocol_config::ProtocolConfig,
 };
 use crate::{components::network::generate_largest_serialized_message, utils::Loadable};
@@ -87,6 +93,14 @@ pub struct Chainspec {
     /// System costs config.
     #[serde(rename = "system_costs")]
     pub system_costs_config: SystemConfig,
+
+    /// Features config.
+    #[serde(rename = "features")]
+    pub features_config: FeaturesConfig,
+
+    /// Proof requests config.
+    #[serde(rename = "proof_requests")]
+    pub proof_requests_config: ProofRequestsConfig,
 }
 
 impl Chainspec {
@@ -140,6 +154,7 @@ impl Chainspec {
         self.protocol_config.is_valid()
             && self.core_config.is_valid()
             && self.deploy_config.is_valid()
+            && self.features_config.is_valid()
     }
 
     /// Serializes `self` and hashes the resulting bytes.
@@ -151,6 +166,11 @@ impl Chainspec {
         Digest::hash(serialized_chainspec)
     }
 
+    /// Returns whether `feature` is active in the era `era_id`.
+    pub fn is_feature_active(&self, feature: Feature, era_id: EraId) -> bool {
+        self.features_config.is_active(feature, era_id)
+    }
+
     /// Returns the protocol version of the chainspec.
     pub fn protocol_version(&self) -> ProtocolVersion {
         self.protocol_config.version
@@ -209,6 +229,8 @@ impl Chainspec {
         let deploy_config = DeployConfig::random(rng);
         let wasm_costs_config = rng.gen();
         let system_costs_config = rng.gen();
+        let features_config = FeaturesConfig::random(rng);
+        let proof_requests_config = ProofRequestsConfig::random(rng);
 
         Chainspec {
             protocol_config,
@@ -218,6 +240,8 @@ impl Chainspec {
             deploy_config,
             wasm_config: wasm_costs_config,
             system_costs_config,
+            features_config,
+            proof_requests_config,
         }
     }
 }
@@ -232,6 +256,8 @@ impl ToBytes for Chainspec {
         buffer.extend(self.deploy_config.to_bytes()?);
         buffer.extend(self.wasm_config.to_bytes()?);
         buffer.extend(self.system_costs_config.to_bytes()?);
+        buffer.extend(self.features_config.to_bytes()?);
+        buffer.extend(self.proof_requests_config.to_bytes()?);
         Ok(buffer)
     }
 
@@ -243,6 +269,8 @@ impl ToBytes for Chainspec {
             + self.deploy_config.serialized_length()
             + self.wasm_config.serialized_length()
             + self.system_costs_config.serialized_length()
+            + self.features_config.serialized_length()
+            + self.proof_requests_config.serialized_length()
     }
 }
 
@@ -255,6 +283,8 @@ impl FromBytes for Chainspec {
         let (deploy_config, remainder) = DeployConfig::from_bytes(remainder)?;
         let (wasm_config, remainder) = WasmConfig::from_bytes(remainder)?;
         let (system_costs_config, remainder) = SystemConfig::from_bytes(remainder)?;
+        let (features_config, remainder) = FeaturesConfig::from_bytes(remainder)?;
+        let (proof_requests_config, remainder) = ProofRequestsConfig::from_bytes(remainder)?;
         let chainspec = Chainspec {
             protocol_config,
             network_config,
@@ -263,6 +293,8 @@ impl FromBytes for Chainspec {
             deploy_config,
             wasm_config,
             system_costs_config,
+            features_config,
+            proof_requests_config,
         };
         Ok((chainspec, remainder))
     }
@@ -363,6 +395,7 @@ mod tests {
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
             random_bytes: HostFunction::new(123, [0, 1]),
             enable_contract_version: HostFunction::new(142, [0, 1, 2, 3]),
+            verify_groth16: HostFunction::new(143, [0, 1, 2, 3, 4, 5, 6]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -0,0 +1,137 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+#[cfg(test)]
+use casper_types::testing::TestRng;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    EraId,
+};
+
+/// A protocol change which is activated at the start of the era given in the chainspec, rather
+/// than by a node version check, so that all nodes switch over deterministically.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Feature {
+    /// Host functions for verifying zero-knowledge proofs, i.e. `casper_verify_groth16`.
+    ZkHostFunctions,
+}
+
+impl Feature {
+    /// All known features.
+    pub const ALL: [Feature; 1] = [Feature::ZkHostFunctions];
+
+    /// Returns the name of the feature, as used in the `[features]` section of the chainspec.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::ZkHostFunctions => "zk_host_functions",
+        }
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+/// The eras in which features are activated, keyed by feature name.
+///
+/// A feature not listed is inactive.
+#[derive(Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+#[serde(transparent)]
+pub struct FeaturesConfig {
+    activation_eras: BTreeMap<String, EraId>,
+}
+
+impl FeaturesConfig {
+    /// Checks whether the values set in the config make sense and returns `false` if they don't.
+    pub fn is_valid(&self) -> bool {
+        let mut is_valid = true;
+        for name in self.activation_eras.keys() {
+            if !Feature::ALL.iter().any(|feature| feature.name() == name) {
+                error!(%name, "unknown feature in chainspec [features] section");
+                is_valid = false;
+            }
+        }
+        is_valid
+    }
+
+    /// Returns the era in which `feature` is activated, or `None` if it is never activated.
+    pub fn activation_era(&self, feature: Feature) -> Option<EraId> {
+        self.activation_eras.get(feature.name()).copied()
+    }
+
+    /// Returns whether `feature` is active in the era `era_id`.
+    pub fn is_active(&self, feature: Feature, era_id: EraId) -> bool {
+        self.activation_era(feature)
+            .map_or(false, |activation_era| era_id >= activation_era)
+    }
+}
+
+#[cfg(test)]
+impl FeaturesConfig {
+    /// Generates a random instance using a `TestRng`.
+    pub fn random(rng: &mut TestRng) -> Self {
+        let mut activation_eras = BTreeMap::new();
+        for feature in Feature::ALL {
+            if rng.gen() {
+                let activation_era = EraId::new(rng.gen_range(0..1_000));
+                activation_eras.insert(feature.name().to_string(), activation_era);
+            }
+        }
+        FeaturesConfig { activation_eras }
+    }
+}
+
+impl ToBytes for FeaturesConfig {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.activation_eras.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.activation_eras.serialized_length()
+    }
+}
+
+impl FromBytes for FeaturesConfig {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (activation_eras, remainder) = BTreeMap::from_bytes(bytes)?;
+        Ok((FeaturesConfig { activation_eras }, remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_activate_features_from_their_era() {
+        let features_config: FeaturesConfig = toml::from_str("zk_host_functions = 10").unwrap();
+        assert!(features_config.is_valid());
+        assert_eq!(
+            features_config.activation_era(Feature::ZkHostFunctions),
+            Some(EraId::new(10))
+        );
+        assert!(!features_config.is_active(Feature::ZkHostFunctions, EraId::new(9)));
+        assert!(features_config.is_active(Feature::ZkHostFunctions, EraId::new(10)));
+        assert!(!FeaturesConfig::default().is_active(Feature::ZkHostFunctions, EraId::new(10)));
+
+        let features_config: FeaturesConfig = toml::from_str("unknown_feature = 1").unwrap();
+        assert!(!features_config.is_valid());
+    }
+
+    #[test]
+    fn bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let features_config = FeaturesConfig::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&features_config);
+    }
+}
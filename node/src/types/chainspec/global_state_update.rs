@@ -14,7 +14,8 @@ use casper_types::{
 
 use super::error::GlobalStateUpdateLoadError;
 
-const GLOBAL_STATE_UPDATE_FILENAME: &str = "global_state.toml";
+/// The name of the file holding the global state update, alongside the chainspec.
+pub const GLOBAL_STATE_UPDATE_FILENAME: &str = "global_state.toml";
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -38,6 +39,22 @@ pub struct GlobalStateUpdateConfig {
 }
 
 impl GlobalStateUpdateConfig {
+    /// Returns a config replacing the validator set with `validators`, without modifying any other
+    /// global state entries.
+    pub fn from_validators(validators: &BTreeMap<PublicKey, U512>) -> Self {
+        let validators = validators
+            .iter()
+            .map(|(public_key, weight)| GlobalStateUpdateValidatorInfo {
+                public_key: public_key.to_hex(),
+                weight: weight.to_string(),
+            })
+            .collect();
+        GlobalStateUpdateConfig {
+            validators: Some(validators),
+            entries: vec![],
+        }
+    }
+
     /// Returns `Self` and the raw bytes of the file.
     ///
     /// If the file doesn't exist, returns `Ok(None)`.
@@ -171,4 +188,20 @@ mod tests {
         let update = GlobalStateUpdate::random(&mut rng);
         bytesrepr::test_serialization_roundtrip(&update);
     }
+
+    #[test]
+    fn should_load_validators_from_config() {
+        let mut rng = crate::new_rng();
+        let validators: BTreeMap<PublicKey, U512> = (0..5)
+            .map(|_| (PublicKey::random(&mut rng), rng.gen()))
+            .collect();
+        let config = GlobalStateUpdateConfig::from_validators(&validators);
+
+        let encoded = toml::to_string_pretty(&toml::Value::try_from(&config).unwrap()).unwrap();
+        let decoded: GlobalStateUpdateConfig = toml::from_str(&encoded).unwrap();
+        let update = GlobalStateUpdate::try_from(decoded).unwrap();
+
+        assert_eq!(update.validators, Some(validators));
+        assert!(update.entries.is_empty());
+    }
 }
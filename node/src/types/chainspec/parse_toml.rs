@@ -11,12 +11,12 @@ use std::{convert::TryFrom, path::Path};
 use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
-use casper_types::{bytesrepr::Bytes, file_utils, ProtocolVersion};
+use casper_types::{bytesrepr::Bytes, file_utils, ProtocolVersion, PublicKey};
 
 use super::{
     accounts_config::AccountsConfig, global_state_update::GlobalStateUpdateConfig, ActivationPoint,
-    Chainspec, ChainspecRawBytes, CoreConfig, DeployConfig, Error, GlobalStateUpdate,
-    HighwayConfig, NetworkConfig, ProtocolConfig,
+    Chainspec, ChainspecRawBytes, CoreConfig, DeployConfig, Error, FeaturesConfig,
+    GlobalStateUpdate, HighwayConfig, NetworkConfig, ProofRequestsConfig, ProtocolConfig,
 };
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -34,6 +34,8 @@ struct TomlProtocol {
     version: ProtocolVersion,
     hard_reset: bool,
     activation_point: ActivationPoint,
+    #[serde(default)]
+    upgrade_signing_keys: Vec<PublicKey>,
 }
 
 /// A chainspec configuration as laid out in the TOML-encoded configuration file.
@@ -48,6 +50,10 @@ pub(super) struct TomlChainspec {
     highway: HighwayConfig,
     wasm: WasmConfig,
     system_costs: SystemConfig,
+    #[serde(default)]
+    features: FeaturesConfig,
+    #[serde(default)]
+    proof_requests: ProofRequestsConfig,
 }
 
 impl From<&Chainspec> for TomlChainspec {
@@ -56,6 +62,7 @@ impl From<&Chainspec> for TomlChainspec {
             version: chainspec.protocol_config.version,
             hard_reset: chainspec.protocol_config.hard_reset,
             activation_point: chainspec.protocol_config.activation_point,
+            upgrade_signing_keys: chainspec.protocol_config.upgrade_signing_keys.clone(),
         };
         let network = TomlNetwork {
             name: chainspec.network_config.name.clone(),
@@ -67,6 +74,8 @@ impl From<&Chainspec> for TomlChainspec {
         let highway = chainspec.highway_config;
         let wasm = chainspec.wasm_config;
         let system_costs = chainspec.system_costs_config;
+        let features = chainspec.features_config.clone();
+        let proof_requests = chainspec.proof_requests_config;
 
         TomlChainspec {
             protocol,
@@ -76,6 +85,8 @@ impl From<&Chainspec> for TomlChainspec {
             highway,
             wasm,
             system_costs,
+            features,
+            proof_requests,
         }
     }
 }
@@ -112,6 +123,7 @@ pub(super) fn parse_toml<P: AsRef<Path>>(
         version: toml_chainspec.protocol.version,
         hard_reset: toml_chainspec.protocol.hard_reset,
         activation_point: toml_chainspec.protocol.activation_point,
+        upgrade_signing_keys: toml_chainspec.protocol.upgrade_signing_keys,
         global_state_update,
     };
 
@@ -123,6 +135,8 @@ pub(super) fn parse_toml<P: AsRef<Path>>(
         highway_config: toml_chainspec.highway,
         wasm_config: toml_chainspec.wasm,
         system_costs_config: toml_chainspec.system_costs,
+        features_config: toml_chainspec.features,
+        proof_requests_config: toml_chainspec.proof_requests,
     };
     let chainspec_raw_bytes = ChainspecRawBytes::new(
         Bytes::from(chainspec_bytes),
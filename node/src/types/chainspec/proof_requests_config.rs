@@ -0,0 +1,59 @@
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+#[cfg(test)]
+use casper_types::testing::TestRng;
+
+/// Limits on the proofs third parties can request from the zero-knowledge prover, e.g. via the RPC
+/// server.
+///
+/// Proofs the node needs itself always take precedence over those requested by third parties.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProofRequestsConfig {
+    /// The highest priority hint a proof request may carry.  Among the requests of third parties,
+    /// those with a higher hint are proven first.
+    pub max_priority_hint: u64,
+}
+
+#[cfg(test)]
+impl ProofRequestsConfig {
+    /// Generates a random instance using a `TestRng`.
+    pub fn random(rng: &mut TestRng) -> Self {
+        ProofRequestsConfig {
+            max_priority_hint: rng.gen(),
+        }
+    }
+}
+
+impl ToBytes for ProofRequestsConfig {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.max_priority_hint.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.max_priority_hint.serialized_length()
+    }
+}
+
+impl FromBytes for ProofRequestsConfig {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (max_priority_hint, remainder) = u64::from_bytes(bytes)?;
+        Ok((ProofRequestsConfig { max_priority_hint }, remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let proof_requests_config = ProofRequestsConfig::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&proof_requests_config);
+    }
+}
@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use casper_types::testing::TestRng;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    Key, ProtocolVersion, StoredValue,
+    Key, ProtocolVersion, PublicKey, StoredValue,
 };
 
 use super::{ActivationPoint, GlobalStateUpdate};
@@ -29,6 +29,10 @@ pub struct ProtocolConfig {
     pub hard_reset: bool,
     /// This protocol config applies starting at the era specified in the activation point.
     pub activation_point: ActivationPoint,
+    /// The keys trusted to sign the packages of upcoming protocol versions, which the node may
+    /// download ahead of their activation point.
+    #[serde(default)]
+    pub upgrade_signing_keys: Vec<PublicKey>,
     /// Any arbitrary updates we might want to make to the global state at the start of the era
     /// specified in the activation point.
     pub global_state_update: Option<GlobalStateUpdate>,
@@ -74,11 +78,15 @@ impl ProtocolConfig {
             rng.gen::<u8>() as u32,
         );
         let activation_point = ActivationPoint::random(rng);
+        let upgrade_signing_keys = (0..rng.gen_range(0..3))
+            .map(|_| PublicKey::random(rng))
+            .collect();
 
         ProtocolConfig {
             version: protocol_version,
             hard_reset: rng.gen(),
             activation_point,
+            upgrade_signing_keys,
             global_state_update: None,
         }
     }
@@ -91,6 +99,7 @@ impl ToBytes for ProtocolConfig {
         buffer.extend(self.hard_reset.to_bytes()?);
         buffer.extend(self.activation_point.to_bytes()?);
         buffer.extend(self.global_state_update.to_bytes()?);
+        buffer.extend(self.upgrade_signing_keys.to_bytes()?);
         Ok(buffer)
     }
 
@@ -99,6 +108,7 @@ impl ToBytes for ProtocolConfig {
             + self.hard_reset.serialized_length()
             + self.activation_point.serialized_length()
             + self.global_state_update.serialized_length()
+            + self.upgrade_signing_keys.serialized_length()
     }
 }
 
@@ -110,10 +120,12 @@ impl FromBytes for ProtocolConfig {
         let (hard_reset, remainder) = bool::from_bytes(remainder)?;
         let (activation_point, remainder) = ActivationPoint::from_bytes(remainder)?;
         let (global_state_update, remainder) = Option::<GlobalStateUpdate>::from_bytes(remainder)?;
+        let (upgrade_signing_keys, remainder) = Vec::<PublicKey>::from_bytes(remainder)?;
         let protocol_config = ProtocolConfig {
             version,
             hard_reset,
             activation_point,
+            upgrade_signing_keys,
             global_state_update,
         };
         Ok((protocol_config, remainder))
@@ -187,6 +199,7 @@ mod tests {
             version: current_version,
             hard_reset: false,
             activation_point: ActivationPoint::EraId(upgrade_era),
+            upgrade_signing_keys: vec![],
             global_state_update: None,
         };
 
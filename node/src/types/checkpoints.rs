@@ -0,0 +1,192 @@
+use std::{collections::BTreeMap, path::Path};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::{
+    file_utils::{self, ReadFileError},
+    EraId, PublicKey, U512,
+};
+
+use crate::{types::BlockHash, utils::Loadable};
+
+/// Error loading the checkpoints file.
+#[derive(Debug, Error)]
+pub(crate) enum CheckpointsError {
+    /// Error reading the checkpoints file.
+    #[error(transparent)]
+    ReadFile(#[from] ReadFileError),
+
+    /// Error decoding the checkpoints file.
+    #[error("decoding from toml error: {0}")]
+    DecodingFromToml(#[from] toml::de::Error),
+
+    /// A checkpoint without any validators.
+    #[error("checkpoint for block {0} has no validators")]
+    NoValidators(BlockHash),
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct CheckpointValidatorInfo {
+    public_key: PublicKey,
+    weight: U512,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct CheckpointConfig {
+    block_hash: BlockHash,
+    height: u64,
+    era_id: EraId,
+    validators: Vec<CheckpointValidatorInfo>,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+struct CheckpointsConfig {
+    checkpoints: Vec<CheckpointConfig>,
+}
+
+/// A block provided by the node operator as a pre-verified trust anchor, along with the validator
+/// set of its era.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+    block_hash: BlockHash,
+    height: u64,
+    era_id: EraId,
+    validators: BTreeMap<PublicKey, U512>,
+}
+
+impl Checkpoint {
+    pub(crate) fn block_hash(&self) -> BlockHash {
+        self.block_hash
+    }
+
+    pub(crate) fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub(crate) fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    pub(crate) fn validators(&self) -> &BTreeMap<PublicKey, U512> {
+        &self.validators
+    }
+}
+
+/// The checkpoints read from an operator-provided file, ordered by block height.
+#[derive(Clone, DataSize, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Checkpoints {
+    checkpoints: BTreeMap<u64, Checkpoint>,
+}
+
+impl Checkpoints {
+    /// Returns the checkpoint with the highest block, if any.
+    pub(crate) fn highest(&self) -> Option<&Checkpoint> {
+        self.checkpoints.values().next_back()
+    }
+
+    /// Returns an iterator over the checkpoints in ascending order of block height.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Checkpoint> {
+        self.checkpoints.values()
+    }
+
+    fn from_toml(bytes: &[u8]) -> Result<Self, CheckpointsError> {
+        let config: CheckpointsConfig = toml::from_slice(bytes)?;
+        let mut checkpoints = BTreeMap::new();
+        for checkpoint in config.checkpoints {
+            if checkpoint.validators.is_empty() {
+                return Err(CheckpointsError::NoValidators(checkpoint.block_hash));
+            }
+            let validators = checkpoint
+                .validators
+                .into_iter()
+                .map(|info| (info.public_key, info.weight))
+                .collect();
+            checkpoints.insert(
+                checkpoint.height,
+                Checkpoint {
+                    block_hash: checkpoint.block_hash,
+                    height: checkpoint.height,
+                    era_id: checkpoint.era_id,
+                    validators,
+                },
+            );
+        }
+        Ok(Checkpoints { checkpoints })
+    }
+}
+
+impl Loadable for Checkpoints {
+    type Error = CheckpointsError;
+
+    fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Self::Error> {
+        let bytes = file_utils::read_file(path)?;
+        Self::from_toml(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    fn checkpoint_config(
+        rng: &mut TestRng,
+        height: u64,
+        validators: Vec<CheckpointValidatorInfo>,
+    ) -> CheckpointConfig {
+        CheckpointConfig {
+            block_hash: BlockHash::random(rng),
+            height,
+            era_id: EraId::from(height / 100),
+            validators,
+        }
+    }
+
+    #[test]
+    fn should_parse_checkpoints_ordered_by_height() {
+        let mut rng = TestRng::new();
+        let public_key = PublicKey::random(&mut rng);
+        let validators = vec![CheckpointValidatorInfo {
+            public_key: public_key.clone(),
+            weight: U512::from(100),
+        }];
+        let higher = checkpoint_config(&mut rng, 2000, validators.clone());
+        let lower = checkpoint_config(&mut rng, 1000, validators);
+        let config = CheckpointsConfig {
+            checkpoints: vec![higher.clone(), lower],
+        };
+        let toml = toml::to_string(&config).unwrap();
+
+        let checkpoints = Checkpoints::from_toml(toml.as_bytes()).unwrap();
+        let heights: Vec<u64> = checkpoints.iter().map(Checkpoint::height).collect();
+        assert_eq!(heights, vec![1000, 2000]);
+
+        let highest = checkpoints.highest().unwrap();
+        assert_eq!(highest.block_hash(), higher.block_hash);
+        assert_eq!(highest.era_id(), EraId::from(20));
+        assert_eq!(
+            highest.validators().get(&public_key),
+            Some(&U512::from(100))
+        );
+    }
+
+    #[test]
+    fn should_reject_checkpoint_without_validators() {
+        let mut rng = TestRng::new();
+        let config = CheckpointsConfig {
+            checkpoints: vec![checkpoint_config(&mut rng, 1000, vec![])],
+        };
+        let toml = toml::to_string(&config).unwrap();
+
+        assert!(matches!(
+            Checkpoints::from_toml(toml.as_bytes()),
+            Err(CheckpointsError::NoValidators(_))
+        ));
+    }
+}
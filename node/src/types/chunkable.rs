@@ -6,7 +6,7 @@ use casper_types::{
     ExecutionResult,
 };
 
-use super::value_or_chunk::HashingTrieRaw;
+use super::{value_or_chunk::HashingTrieRaw, BlockBody};
 
 /// Implemented for types that are chunked when sending over the wire and/or before storing the
 /// trie store.
@@ -69,3 +69,11 @@ impl Chunkable for Vec<ExecutionResult> {
         Ok(Cow::Owned(self.to_bytes()?))
     }
 }
+
+impl Chunkable for BlockBody {
+    type Error = bytesrepr::Error;
+
+    fn as_bytes(&self) -> Result<Cow<Vec<u8>>, Self::Error> {
+        Ok(Cow::Owned(self.to_bytes()?))
+    }
+}
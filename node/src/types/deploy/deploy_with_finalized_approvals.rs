@@ -50,6 +50,11 @@ impl DeployWithFinalizedApprovals {
         self.deploy
     }
 
+    /// Splits into the original deploy and the finalized approvals, if any.
+    pub(crate) fn into_parts(self) -> (Deploy, Option<FinalizedApprovals>) {
+        (self.deploy, self.finalized_approvals)
+    }
+
     #[cfg(test)]
     pub(crate) fn original_approvals(&self) -> &BTreeSet<Approval> {
         self.deploy.approvals()
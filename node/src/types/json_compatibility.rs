@@ -8,7 +8,7 @@ mod stored_value;
 use casper_types::{contracts::NamedKeys, NamedKey};
 
 pub use account::Account;
-pub use auction_state::AuctionState;
+pub use auction_state::{AuctionState, JsonBidSummary, JsonDelegator};
 pub use contracts::{Contract, ContractPackage};
 pub use stored_value::StoredValue;
 
@@ -95,6 +95,18 @@ pub struct JsonDelegator {
     delegatee: PublicKey,
 }
 
+impl JsonDelegator {
+    /// Creates a new `JsonDelegator` from the delegator with the given public key.
+    pub fn new(public_key: PublicKey, delegator: &Delegator) -> Self {
+        JsonDelegator {
+            public_key,
+            staked_amount: *delegator.staked_amount(),
+            bonding_purse: *delegator.bonding_purse(),
+            delegatee: delegator.validator_public_key().clone(),
+        }
+    }
+}
+
 /// An entry in a founding validator map representing a bid.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -115,12 +127,7 @@ impl From<Bid> for JsonBid {
     fn from(bid: Bid) -> Self {
         let mut json_delegators: Vec<JsonDelegator> = Vec::with_capacity(bid.delegators().len());
         for (public_key, delegator) in bid.delegators().iter() {
-            json_delegators.push(JsonDelegator {
-                public_key: public_key.clone(),
-                staked_amount: *delegator.staked_amount(),
-                bonding_purse: *delegator.bonding_purse(),
-                delegatee: delegator.validator_public_key().clone(),
-            });
+            json_delegators.push(JsonDelegator::new(public_key.clone(), delegator));
         }
         JsonBid {
             bonding_purse: *bid.bonding_purse(),
@@ -132,6 +139,38 @@ impl From<Bid> for JsonBid {
     }
 }
 
+/// A bid without its delegators, which can be retrieved separately.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBidSummary {
+    /// The public key of the validator.
+    public_key: PublicKey,
+    /// The purse that was used for bonding.
+    bonding_purse: URef,
+    /// The amount of tokens staked by a validator (not including delegators).
+    staked_amount: U512,
+    /// The delegation rate.
+    delegation_rate: DelegationRate,
+    /// The number of delegators.
+    delegator_count: u64,
+    /// Is this an inactive validator.
+    inactive: bool,
+}
+
+impl JsonBidSummary {
+    /// Creates a new `JsonBidSummary` from the bid of the validator with the given public key.
+    pub fn new(public_key: PublicKey, bid: &Bid) -> Self {
+        JsonBidSummary {
+            public_key,
+            bonding_purse: *bid.bonding_purse(),
+            staked_amount: *bid.staked_amount(),
+            delegation_rate: *bid.delegation_rate(),
+            delegator_count: bid.delegators().len() as u64,
+            inactive: bid.inactive(),
+        }
+    }
+}
+
 /// A Json representation of a single bid.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -0,0 +1,161 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::{crypto, EraId, PublicKey, U512};
+
+use crate::{
+    components::fetcher::{FetchItem, Tag},
+    types::{error::BlockHeaderWithMetadataValidationError, BlockHash, BlockHeaderWithMetadata},
+    utils::{self, BlockSignatureError},
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum LightClientUpdateValidationError {
+    #[error("Too many signed block headers: an update may span at most {0} eras.")]
+    TooManySignedBlockHeaders(u64),
+    #[error("Block {block_hash} is not in the expected era {expected_era_id}.")]
+    UnexpectedEra {
+        block_hash: BlockHash,
+        expected_era_id: EraId,
+    },
+    #[error("Only the last signed block header is allowed not to be a switch block.")]
+    MissingSwitchBlock,
+    #[error(transparent)]
+    HeadersNotSufficientlySigned(BlockSignatureError),
+    #[error("The block signatures are not cryptographically valid: {0}")]
+    Crypto(crypto::Error),
+    #[error(transparent)]
+    BlockWithMetadata(BlockHeaderWithMetadataValidationError),
+}
+
+/// What the requester of a light client update already trusts.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize)]
+pub(crate) struct LightClientUpdateValidationMetadata {
+    /// The era following the trusted switch block.
+    pub(crate) era_id: EraId,
+    /// The validator weights of that era.
+    pub(crate) validator_weights: BTreeMap<PublicKey, U512>,
+    /// The maximum number of eras an update may span.
+    pub(crate) recent_era_count: u64,
+    #[data_size(skip)]
+    pub(crate) finality_threshold_fraction: Ratio<u64>,
+}
+
+/// Headers and signatures allowing a light client which trusts the validators of the era after a
+/// given switch block to follow the chain to a later block, without downloading block bodies.
+///
+/// The validator set changes are contained in the switch block headers, so a light client only
+/// needs to check the signatures of each header against the validators it learned from the
+/// previous one.  An update spans at most `recent_era_count` eras; if its last header is a switch
+/// block, the sender has further blocks, which can be requested with an update from that switch
+/// block.
+///
+/// Updates across an emergency upgrade which replaced the validators cannot be validated, as the
+/// new validators are only known from the chainspec.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, DataSize)]
+pub(crate) struct LightClientUpdate {
+    /// The hash of the switch block trusted by the requester.
+    pub(crate) trusted_switch_block_hash: BlockHash,
+    /// The headers of the subsequent switch blocks known to the sender, in ascending order and
+    /// with signatures, followed by their signed highest block unless the update was truncated.
+    pub(crate) signed_block_headers: Vec<BlockHeaderWithMetadata>,
+}
+
+impl Display for LightClientUpdate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "light client update from {} with {} signed block header(s)",
+            self.trusted_switch_block_hash,
+            self.signed_block_headers.len()
+        )
+    }
+}
+
+impl FetchItem for LightClientUpdate {
+    type Id = BlockHash;
+    type ValidationError = LightClientUpdateValidationError;
+    type ValidationMetadata = LightClientUpdateValidationMetadata;
+
+    const TAG: Tag = Tag::LightClientUpdate;
+
+    fn fetch_id(&self) -> Self::Id {
+        self.trusted_switch_block_hash
+    }
+
+    fn validate(
+        &self,
+        validation_metadata: &LightClientUpdateValidationMetadata,
+    ) -> Result<(), Self::ValidationError> {
+        let max_len = validation_metadata.recent_era_count.saturating_add(1);
+        if self.signed_block_headers.len() as u64 > max_len {
+            return Err(LightClientUpdateValidationError::TooManySignedBlockHeaders(
+                validation_metadata.recent_era_count,
+            ));
+        }
+
+        let mut era_id = validation_metadata.era_id;
+        let mut validator_weights = &validation_metadata.validator_weights;
+        let mut signed_headers = self.signed_block_headers.iter().peekable();
+        while let Some(signed_header) = signed_headers.next() {
+            signed_header
+                .validate()
+                .map_err(LightClientUpdateValidationError::BlockWithMetadata)?;
+            let header = &signed_header.block_header;
+            if header.era_id() != era_id {
+                return Err(LightClientUpdateValidationError::UnexpectedEra {
+                    block_hash: header.block_hash(),
+                    expected_era_id: era_id,
+                });
+            }
+            utils::check_sufficient_block_signatures(
+                validator_weights,
+                validation_metadata.finality_threshold_fraction,
+                Some(&signed_header.block_signatures),
+            )
+            .map_err(LightClientUpdateValidationError::HeadersNotSufficientlySigned)?;
+            match header.next_era_validator_weights() {
+                Some(next_era_validator_weights) => {
+                    era_id = header.next_block_era_id();
+                    validator_weights = next_era_validator_weights;
+                }
+                None if signed_headers.peek().is_some() => {
+                    return Err(LightClientUpdateValidationError::MissingSwitchBlock);
+                }
+                None => {}
+            }
+        }
+
+        // defer cryptographic verification until last to avoid unnecessary computation
+        for signed_header in &self.signed_block_headers {
+            signed_header
+                .block_signatures
+                .verify()
+                .map_err(LightClientUpdateValidationError::Crypto)?;
+        }
+
+        Ok(())
+    }
+}
+
+mod specimen_support {
+    use crate::utils::specimen::{vec_prop_specimen, Cache, LargestSpecimen, SizeEstimator};
+
+    use super::LightClientUpdate;
+
+    impl LargestSpecimen for LightClientUpdate {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            LightClientUpdate {
+                trusted_switch_block_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                signed_block_headers: vec_prop_specimen(estimator, "recent_era_count", cache),
+            }
+        }
+    }
+}
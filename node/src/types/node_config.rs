@@ -1,7 +1,10 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use crate::types::BlockHash;
+use crate::{
+    types::{validator_matrix::DEFAULT_RETAINED_ERAS, BlockHash},
+    utils::External,
+};
 
 use casper_types::TimeDiff;
 
@@ -49,12 +52,26 @@ pub struct NodeConfig {
     /// Hash used as a trust anchor when joining, if any.
     pub trusted_hash: Option<BlockHash>,
 
+    /// Path to a file of operator-provided checkpoints, if any. Each checkpoint is a block along
+    /// with the validator set of its era, treated as a pre-verified trust anchor. The highest one
+    /// is used as the trusted hash if none is configured.
+    pub checkpoints: Option<External>,
+
     /// Which historical sync option?
     ///  Genesis: sync all the way back to genesis
     ///  Ttl: sync the necessary number of historical blocks to satisfy TTL requirement.
     ///  NoSync: don't attempt to get any historical records; i.e. go forward only.
     pub sync_handling: SyncHandling,
 
+    /// Number of blocks below the highest complete block which are synced in full when syncing
+    /// historical blocks. Older blocks only have their headers and finality signatures acquired,
+    /// skipping their bodies and global state. If unset, all historical blocks are synced in full.
+    pub back_fill_depth: Option<u64>,
+
+    /// Number of most recent eras whose validator weights are kept in memory. Older ones are
+    /// reconstructed from stored switch blocks, or acquired from peers, when needed.
+    pub retained_validator_eras: usize,
+
     /// Idle time after which the syncing process is considered stalled.
     pub idle_tolerance: TimeDiff,
 
@@ -80,7 +97,10 @@ impl Default for NodeConfig {
     fn default() -> NodeConfig {
         NodeConfig {
             trusted_hash: None,
+            checkpoints: None,
             sync_handling: SyncHandling::default(),
+            back_fill_depth: None,
+            retained_validator_eras: DEFAULT_RETAINED_ERAS,
             idle_tolerance: DEFAULT_IDLE_TOLERANCE.parse().unwrap(),
             max_attempts: DEFAULT_MAX_ATTEMPTS,
             control_logic_default_delay: DEFAULT_CONTROL_LOGIC_DEFAULT_DELAY.parse().unwrap(),
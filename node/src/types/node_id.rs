@@ -1,4 +1,7 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
 
 use datasize::DataSize;
 use hex_fmt::HexFmt;
@@ -6,6 +9,7 @@ use once_cell::sync::Lazy;
 #[cfg(test)]
 use rand::Rng;
 use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
 
 #[cfg(test)]
 use casper_types::testing::TestRng;
@@ -58,15 +62,7 @@ impl<'de> Deserialize<'de> for NodeId {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         if deserializer.is_human_readable() {
             let NodeIdAsString::Tls(hex_value) = NodeIdAsString::deserialize(deserializer)?;
-
-            let bytes = base16::decode(hex_value.as_bytes()).map_err(D::Error::custom)?;
-            if bytes.len() != KeyFingerprint::LENGTH {
-                return Err(SerdeError::custom("wrong length"));
-            }
-            let mut array = [0_u8; KeyFingerprint::LENGTH];
-            array.copy_from_slice(bytes.as_slice());
-
-            Ok(NodeId(KeyFingerprint::from(array)))
+            hex_value.parse().map_err(D::Error::custom)
         } else {
             let NodeIdAsBytes::Tls(key_fingerprint) = NodeIdAsBytes::deserialize(deserializer)?;
             Ok(NodeId(key_fingerprint))
@@ -74,6 +70,34 @@ impl<'de> Deserialize<'de> for NodeId {
     }
 }
 
+/// Error parsing a `NodeId` from a string.
+#[derive(Debug, Error)]
+pub enum ParseNodeIdError {
+    /// The string is not valid hex.
+    #[error(transparent)]
+    Hex(#[from] base16::DecodeError),
+    /// The decoded bytes have the wrong length.
+    #[error("wrong length")]
+    WrongLength,
+}
+
+/// Parses the full hex-encoded fingerprint of a node ID, optionally prefixed with `tls:`.
+impl FromStr for NodeId {
+    type Err = ParseNodeIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let hex_value = value.strip_prefix("tls:").unwrap_or(value);
+        let bytes = base16::decode(hex_value.as_bytes())?;
+        if bytes.len() != KeyFingerprint::LENGTH {
+            return Err(ParseNodeIdError::WrongLength);
+        }
+        let mut array = [0_u8; KeyFingerprint::LENGTH];
+        array.copy_from_slice(bytes.as_slice());
+
+        Ok(NodeId(KeyFingerprint::from(array)))
+    }
+}
+
 static NODE_ID: Lazy<NodeId> =
     Lazy::new(|| NodeId(KeyFingerprint::from([1u8; KeyFingerprint::LENGTH])));
 
@@ -182,4 +206,19 @@ mod tests {
         let decoded = serde_json::from_str(&json_string).unwrap();
         assert_eq!(node_id, decoded);
     }
+
+    #[test]
+    fn parse_full_hex() {
+        let mut rng = crate::new_rng();
+        let node_id = NodeId::random(&mut rng);
+        let hex_value = base16::encode_lower(&node_id.0);
+
+        assert_eq!(hex_value.parse::<NodeId>().unwrap(), node_id);
+        assert_eq!(
+            format!("tls:{}", hex_value).parse::<NodeId>().unwrap(),
+            node_id
+        );
+        assert!(hex_value[..10].parse::<NodeId>().is_err());
+        assert!("not hex".parse::<NodeId>().is_err());
+    }
 }
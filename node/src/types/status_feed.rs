@@ -17,6 +17,7 @@ use casper_types::{EraId, ProtocolVersion, PublicKey, TimeDiff, Timestamp};
 use crate::{
     components::{
         block_synchronizer::BlockSynchronizerStatus,
+        deploy_buffer::StuckDeploy,
         rpc_server::rpcs::docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
         upgrade_watcher::NextUpgrade,
     },
@@ -55,6 +56,7 @@ static GET_STATUS_RESULT: Lazy<GetStatusResult> = Lazy::new(|| {
         available_block_range: AvailableBlockRange::RANGE_0_0,
         block_sync: BlockSynchronizerStatus::doc_example().clone(),
         starting_state_root_hash: Digest::default(),
+        stuck_deploys: vec![],
     };
     GetStatusResult::new(status_feed, DOCS_EXAMPLE_PROTOCOL_VERSION)
 });
@@ -109,6 +111,8 @@ pub struct StatusFeed {
     pub block_sync: BlockSynchronizerStatus,
     /// The state root hash of the lowest block in the available block range.
     pub starting_state_root_hash: Digest,
+    /// The buffered deploys which can't be proposed because of unexecuted dependencies.
+    pub stuck_deploys: Vec<StuckDeploy>,
 }
 
 impl StatusFeed {
@@ -124,6 +128,7 @@ impl StatusFeed {
         available_block_range: AvailableBlockRange,
         block_sync: BlockSynchronizerStatus,
         starting_state_root_hash: Digest,
+        stuck_deploys: Vec<StuckDeploy>,
     ) -> Self {
         let (our_public_signing_key, round_length) = match consensus_status {
             Some((public_key, round_length)) => (Some(public_key), round_length),
@@ -142,6 +147,7 @@ impl StatusFeed {
             available_block_range,
             block_sync,
             starting_state_root_hash,
+            stuck_deploys,
         }
     }
 }
@@ -204,6 +210,9 @@ pub struct GetStatusResult {
     pub available_block_range: AvailableBlockRange,
     /// The status of the block synchronizer builders.
     pub block_sync: BlockSynchronizerStatus,
+    /// The buffered deploys which can't be proposed because some of their dependencies have not
+    /// been executed yet.
+    pub stuck_deploys: Vec<StuckDeploy>,
 }
 
 impl GetStatusResult {
@@ -223,6 +232,7 @@ impl GetStatusResult {
             last_progress: status_feed.last_progress,
             available_block_range: status_feed.available_block_range,
             block_sync: status_feed.block_sync,
+            stuck_deploys: status_feed.stuck_deploys,
             #[cfg(not(test))]
             build_version: crate::VERSION_STRING.clone(),
 
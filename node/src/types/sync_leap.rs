@@ -1696,6 +1696,7 @@ mod tests {
             version: protocol_version,
             global_state_update: None,
             activation_point: ActivationPoint::EraId(rng.gen()),
+            upgrade_signing_keys: vec![],
             hard_reset: rng.gen(),
         };
 
@@ -1889,6 +1890,7 @@ mod tests {
             global_state_update: None,
             hard_reset: false,
             activation_point: ActivationPoint::EraId(rng.gen()),
+            upgrade_signing_keys: vec![],
         };
 
         let actual_eras: BTreeSet<u64> = sync_leap
@@ -1946,6 +1948,7 @@ mod tests {
             }),
             hard_reset: false,
             activation_point: ActivationPoint::EraId(rng.gen()),
+            upgrade_signing_keys: vec![],
         };
 
         let actual_eras: BTreeSet<u64> = sync_leap
@@ -1966,6 +1969,7 @@ mod tests {
             global_state_update: None,
             hard_reset: rng.gen(),
             activation_point: ActivationPoint::EraId(rng.gen()),
+            upgrade_signing_keys: vec![],
         };
 
         let actual_eras: BTreeSet<u64> = sync_leap
@@ -2022,6 +2026,7 @@ mod tests {
             global_state_update: None,
             hard_reset: false,
             activation_point: ActivationPoint::EraId(rng.gen()),
+            upgrade_signing_keys: vec![],
         };
 
         let actual_eras: BTreeSet<u64> = sync_leap
@@ -10,15 +10,14 @@ use datasize::DataSize;
 use itertools::Itertools;
 use num_rational::Ratio;
 use serde::Serialize;
-use static_assertions::const_assert;
-use tracing::info;
+use tracing::{debug, info};
 
 use casper_types::{EraId, PublicKey, SecretKey, U512};
 
 use super::{BlockHeader, FinalitySignature};
 
-const MAX_VALIDATOR_MATRIX_ENTRIES: usize = 6;
-const_assert!(MAX_VALIDATOR_MATRIX_ENTRIES % 2 == 0);
+/// The default number of most recent eras whose validator weights are kept in memory.
+pub(crate) const DEFAULT_RETAINED_ERAS: usize = 6;
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone, DataSize)]
 pub(crate) enum SignatureWeight {
@@ -50,6 +49,7 @@ pub(crate) struct ValidatorMatrix {
     secret_signing_key: Arc<SecretKey>,
     public_signing_key: PublicKey,
     auction_delay: u64,
+    retained_eras: usize,
     retrograde_latch: Option<EraId>,
 }
 
@@ -61,6 +61,7 @@ impl ValidatorMatrix {
         secret_signing_key: Arc<SecretKey>,
         public_signing_key: PublicKey,
         auction_delay: u64,
+        retained_eras: usize,
     ) -> Self {
         let inner = Arc::new(RwLock::new(BTreeMap::new()));
         ValidatorMatrix {
@@ -71,6 +72,8 @@ impl ValidatorMatrix {
             secret_signing_key,
             public_signing_key,
             auction_delay,
+            // the current and upcoming eras are needed to tell active and upcoming validators
+            retained_eras: retained_eras.max(auction_delay as usize + 1),
             retrograde_latch: None,
         }
     }
@@ -94,6 +97,7 @@ impl ValidatorMatrix {
             public_signing_key,
             secret_signing_key,
             auction_delay: 1,
+            retained_eras: DEFAULT_RETAINED_ERAS,
             retrograde_latch: None,
         }
     }
@@ -136,6 +140,10 @@ impl ValidatorMatrix {
         was_present
     }
 
+    // Only the validator weights of the `retained_eras` most recent eras are kept, along with
+    // those of the eras at or below the retrograde latch, which historical sync is still working
+    // its way down to. Evicted weights can be reconstructed from the stored switch blocks, or
+    // acquired from peers, if they are needed again.
     fn register_era_validator_weights_bounded(&mut self, validators: EraValidatorWeights) -> bool {
         let era_id = validators.era_id;
         let mut guard = self
@@ -144,31 +152,25 @@ impl ValidatorMatrix {
             .expect("poisoned lock on validator matrix");
         let is_new = guard.insert(era_id, validators).is_none();
 
-        let latch_era = if let Some(era) = self.retrograde_latch.as_ref() {
-            *era
-        } else {
+        let latch_era = self.retrograde_latch;
+        let evicted_eras = guard
+            .keys()
+            .rev()
+            .skip(self.retained_eras)
+            .filter(|era| latch_era.map_or(true, |latch_era| **era > latch_era))
+            .copied()
+            .collect_vec();
+        if evicted_eras.is_empty() {
             return is_new;
-        };
-
-        let mut removed = false;
-        let excess_entry_count = guard.len().saturating_sub(MAX_VALIDATOR_MATRIX_ENTRIES);
-        for _ in 0..excess_entry_count {
-            let median_era = guard
-                .keys()
-                .rev()
-                .nth(MAX_VALIDATOR_MATRIX_ENTRIES / 2)
-                .copied()
-                .unwrap();
-            if median_era <= latch_era {
-                break;
-            } else {
-                guard.remove(&median_era);
-                if median_era == era_id {
-                    removed = true;
-                }
-            }
         }
-        is_new && !removed
+        for evicted_era in &evicted_eras {
+            guard.remove(evicted_era);
+        }
+        debug!(
+            ?evicted_eras,
+            "ValidatorMatrix: evicted validator weights of old eras"
+        );
+        is_new && !evicted_eras.contains(&era_id)
     }
 
     pub(crate) fn register_validator_weights(
@@ -423,7 +425,7 @@ mod tests {
         components::consensus::tests::utils::{
             ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY, CAROL_PUBLIC_KEY,
         },
-        types::{validator_matrix::MAX_VALIDATOR_MATRIX_ENTRIES, SignatureWeight},
+        types::{validator_matrix::DEFAULT_RETAINED_ERAS, SignatureWeight},
     };
 
     use super::{EraValidatorWeights, ValidatorMatrix};
@@ -542,14 +544,14 @@ mod tests {
         let mut validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
         let mut era_validator_weights = vec![validator_matrix.validator_weights(0.into()).unwrap()];
         era_validator_weights.extend(
-            (1..MAX_VALIDATOR_MATRIX_ENTRIES as u64)
+            (1..DEFAULT_RETAINED_ERAS as u64)
                 .into_iter()
                 .map(EraId::from)
                 .map(empty_era_validator_weights),
         );
         for evw in era_validator_weights
             .iter()
-            .take(MAX_VALIDATOR_MATRIX_ENTRIES)
+            .take(DEFAULT_RETAINED_ERAS)
             .skip(1)
             .cloned()
         {
@@ -558,7 +560,7 @@ mod tests {
                 "register_era_validator_weights"
             );
         }
-        // For a `MAX_VALIDATOR_MATRIX_ENTRIES` value of 6, the validator
+        // For a `DEFAULT_RETAINED_ERAS` value of 6, the validator
         // matrix should contain eras 0 through 5 inclusive.
         assert_eq!(
             vec![0u64, 1, 2, 3, 4, 5],
@@ -570,40 +572,27 @@ mod tests {
                 .collect::<Vec<u64>>()
         );
 
-        // Now that we have 6 entries in the validator matrix, try adding more.
-        // We should have an entry for era 3 (we have eras 0 through 5
-        // inclusive).
-        let median = MAX_VALIDATOR_MATRIX_ENTRIES as u64 / 2;
-        assert!(
-            validator_matrix.has_era(&(median).into()),
-            "should have median era {}",
-            median
-        );
         // Add era 7, which would be the 7th entry in the matrix. Skipping era
         // 6 should have no effect on the pruning.
         era_validator_weights.push(empty_era_validator_weights(
-            (MAX_VALIDATOR_MATRIX_ENTRIES as u64 + 1).into(),
+            (DEFAULT_RETAINED_ERAS as u64 + 1).into(),
         ));
 
-        // set retrograde latch to simulate a fully sync'd node
-        validator_matrix.register_retrograde_latch(Some(EraId::new(0)));
-
-        // Now the entry for era 3 should be dropped and we should be left with
-        // the 3 lowest eras [0, 1, 2] and 3 highest eras [4, 5, 7].
+        // Now the entry for era 0 should be dropped as the oldest one, and we
+        // should be left with the 6 most recent eras [1, 2, 3, 4, 5, 7].
         assert!(validator_matrix
             .register_era_validator_weights(era_validator_weights.last().cloned().unwrap()));
         assert!(
-            !validator_matrix.has_era(&(median).into()),
-            "should not have median era {}",
-            median
+            !validator_matrix.has_era(&EraId::from(0)),
+            "should not have oldest era"
         );
         let len = validator_matrix.read_inner().len();
         assert_eq!(
-            len, MAX_VALIDATOR_MATRIX_ENTRIES,
+            len, DEFAULT_RETAINED_ERAS,
             "expected entries {} actual entries: {}",
-            MAX_VALIDATOR_MATRIX_ENTRIES, len
+            DEFAULT_RETAINED_ERAS, len
         );
-        let expected = vec![0u64, 1, 2, 4, 5, 7];
+        let expected = vec![1u64, 2, 3, 4, 5, 7];
         let actual = validator_matrix
             .read_inner()
             .keys()
@@ -624,6 +613,14 @@ mod tests {
         );
         let new_state: Vec<EraId> = validator_matrix.read_inner().keys().copied().collect();
         assert_eq!(old_state, new_state, "state should be unchanged");
+
+        // Re-adding an era older than all retained ones shouldn't change the state either.
+        assert!(
+            !validator_matrix.register_era_validator_weights(era_validator_weights[0].clone()),
+            "should not retain era older than the retained ones"
+        );
+        let new_state: Vec<EraId> = validator_matrix.read_inner().keys().copied().collect();
+        assert_eq!(old_state, new_state, "state should be unchanged");
     }
 
     #[test]
@@ -635,75 +632,55 @@ mod tests {
         validator_matrix.register_retrograde_latch(Some(EraId::from(10)));
         let mut era_validator_weights = vec![validator_matrix.validator_weights(0.into()).unwrap()];
         era_validator_weights.extend(
-            (1..=MAX_VALIDATOR_MATRIX_ENTRIES as u64)
+            (1..=10)
                 .into_iter()
                 .map(EraId::from)
                 .map(empty_era_validator_weights),
         );
-        for evw in era_validator_weights
-            .iter()
-            .take(MAX_VALIDATOR_MATRIX_ENTRIES + 1)
-            .skip(1)
-            .cloned()
-        {
+        for evw in era_validator_weights.iter().take(10).skip(1).cloned() {
             assert!(
                 validator_matrix.register_era_validator_weights(evw),
                 "register_era_validator_weights"
             );
         }
-
-        // Register eras [7, 8, 9].
-        era_validator_weights.extend(
-            (7..=9)
-                .into_iter()
-                .map(EraId::from)
-                .map(empty_era_validator_weights),
-        );
-        for evw in era_validator_weights.iter().rev().take(3).cloned() {
-            assert!(
-                validator_matrix.register_era_validator_weights(evw),
-                "register_era_validator_weights"
-            );
+        // All eras [0, ..., 9] are at or below the latch, so none were purged.
+        for era in 0..=9 {
+            assert!(validator_matrix.has_era(&EraId::from(era)));
         }
 
-        // Set the retrograde latch to era 5.
-        validator_matrix.register_retrograde_latch(Some(EraId::from(5)));
-        // Add era 10 to the weights.
-        era_validator_weights.push(empty_era_validator_weights(EraId::from(10)));
-        assert_eq!(era_validator_weights.len(), 11);
+        // Set the retrograde latch to era 3.
+        validator_matrix.register_retrograde_latch(Some(EraId::from(3)));
         // As the current weights in the matrix are [0, ..., 9], register era
         // 10. This should succeed anyway since it's the highest weight.
         assert!(
             validator_matrix.register_era_validator_weights(era_validator_weights[10].clone()),
             "register_era_validator_weights"
         );
-        // The latch was previously set to 5, so now all weights which are
-        // neither the lowest 3, highest 3 or higher than the latched era
+        // The latch was previously set to 3, so now all weights which are
+        // neither among the 6 most recent nor at or below the latched era
         // should have been purged.
-        // Given we had weights [0, ..., 10] and the latch is 5, we should
-        // be left with [0, 1, 2, 3, 4, 5, 8, 9, 10].
-        for era in 0..=5 {
+        // Given we had weights [0, ..., 10] and the latch is 3, we should
+        // be left with [0, 1, 2, 3, 5, 6, 7, 8, 9, 10].
+        for era in 0..=3 {
             assert!(validator_matrix.has_era(&EraId::from(era)));
         }
-        for era in 6..=7 {
-            assert!(!validator_matrix.has_era(&EraId::from(era)));
-        }
-        for era in 8..=10 {
+        assert!(!validator_matrix.has_era(&EraId::from(4)));
+        for era in 5..=10 {
             assert!(validator_matrix.has_era(&EraId::from(era)));
         }
 
-        // Make sure era 6, which was previously purged, is not registered as
-        // it is greater than the latch, which is 5.
+        // Make sure era 4, which was previously purged, is not registered as
+        // it is greater than the latch, which is 3.
         assert!(
-            !validator_matrix.register_era_validator_weights(era_validator_weights[6].clone()),
+            !validator_matrix.register_era_validator_weights(era_validator_weights[4].clone()),
             "register_era_validator_weights"
         );
 
-        // Set the retrograde latch to era 6.
-        validator_matrix.register_retrograde_latch(Some(EraId::from(6)));
-        // Make sure era 6 is now registered.
+        // Set the retrograde latch to era 4.
+        validator_matrix.register_retrograde_latch(Some(EraId::from(4)));
+        // Make sure era 4 is now registered.
         assert!(
-            validator_matrix.register_era_validator_weights(era_validator_weights[6].clone()),
+            validator_matrix.register_era_validator_weights(era_validator_weights[4].clone()),
             "register_era_validator_weights"
         );
 
@@ -715,18 +692,42 @@ mod tests {
             "register_era_validator_weights"
         );
         // The latch was previously set to 1, so now all weights which are
-        // neither the lowest 3, highest 3 or higher than the latched era
+        // neither among the 6 most recent nor at or below the latched era
         // should have been purged.
-        // Given we had weights [0, 1, 2, 3, 4, 5, 6, 8, 9, 10] and the latch
-        // is 1, we should be left with [0, 1, 2, 8, 9, 10].
-        for era in 0..=2 {
+        // Given we had weights [0, ..., 10] and the latch is 1, we should be
+        // left with [0, 1, 5, 6, 7, 8, 9, 10].
+        for era in 0..=1 {
             assert!(validator_matrix.has_era(&EraId::from(era)));
         }
-        for era in 3..=7 {
+        for era in 2..=4 {
             assert!(!validator_matrix.has_era(&EraId::from(era)));
         }
-        for era in 8..=10 {
+        for era in 5..=10 {
             assert!(validator_matrix.has_era(&EraId::from(era)));
         }
     }
+
+    #[test]
+    fn retains_at_least_current_and_upcoming_eras() {
+        let auction_delay = 3;
+        let mut validator_matrix = ValidatorMatrix::new(
+            Ratio::new(1, 3),
+            None,
+            EraId::from(0),
+            ALICE_SECRET_KEY.clone(),
+            ALICE_PUBLIC_KEY.clone(),
+            auction_delay,
+            1,
+        );
+        for era in 0..10 {
+            validator_matrix
+                .register_era_validator_weights(empty_era_validator_weights(EraId::from(era)));
+        }
+        // The current era and the `auction_delay` upcoming ones are retained, even though only
+        // a single era was configured to be.
+        assert_eq!(
+            validator_matrix.eras(),
+            (6..10).map(EraId::from).collect::<Vec<_>>()
+        );
+    }
 }
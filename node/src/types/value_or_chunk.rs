@@ -12,7 +12,7 @@ use casper_hashing::{
     ChunkWithProof, ChunkWithProofVerificationError, Digest, MerkleConstructionError,
 };
 
-use super::Chunkable;
+use super::{BlockBody, Chunkable};
 use crate::{
     components::fetcher::{EmptyValidationMetadata, FetchItem, Tag},
     utils::ds,
@@ -107,6 +107,20 @@ impl Display for ValueOrChunk<Vec<ExecutionResult>> {
     }
 }
 
+impl Display for ValueOrChunk<BlockBody> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ValueOrChunk::Value(body) => write!(f, "value: {}", body),
+            ValueOrChunk::ChunkWithProof(chunk) => write!(
+                f,
+                "chunk #{} with proof, root hash {}",
+                chunk.proof().index(),
+                chunk.proof().root_hash()
+            ),
+        }
+    }
+}
+
 /// Error type simply conveying that chunk validation failed.
 #[derive(Debug, Error)]
 #[error("Chunk validation failed")]
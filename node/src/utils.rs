@@ -11,6 +11,7 @@ pub(crate) mod opt_display;
 pub(crate) mod rlimit;
 pub(crate) mod round_robin;
 pub(crate) mod specimen;
+pub(crate) mod thread_pool;
 pub(crate) mod umask;
 pub mod work_queue;
 
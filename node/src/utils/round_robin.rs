@@ -5,17 +5,28 @@
 //! synchronization primitives under the hood.
 
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, HashMap, VecDeque},
-    fmt::{Debug, Display},
+    fmt::{self, Debug, Display, Formatter},
     hash::Hash,
     num::NonZeroUsize,
-    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    sync::{
+        self,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use enum_iterator::IntoEnumIterator;
 use serde::Serialize;
 use tokio::sync::{Mutex, MutexGuard, Semaphore};
-use tracing::{debug, warn};
+use tracing::{debug, error, warn};
+
+/// How long items taken from the queues are remembered for inspection.
+const DEQUEUE_HISTORY_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// The maximum number of items taken from the queues which are remembered for inspection.
+const DEQUEUE_HISTORY_MAX_LEN: usize = 100_000;
 
 /// Weighted round-robin scheduler.
 ///
@@ -47,6 +58,22 @@ pub struct WeightedRoundRobin<I, K> {
     /// Dump count of events only when there is a 10%+ increase of events compared to the previous
     /// report. Setting to `None` disables the dump function.
     recent_event_count_peak: Option<AtomicUsize>,
+
+    /// Items recently taken from the queues, oldest first.
+    ///
+    /// Uses a blocking lock, so that it can be inspected without waiting for the queues.
+    dequeue_history: sync::Mutex<VecDeque<Dequeued<K>>>,
+}
+
+/// An item taken from a queue, remembered for inspection.
+#[derive(Debug)]
+struct Dequeued<K> {
+    /// When the item was taken from the queue.
+    at: Instant,
+    /// The queue the item was taken from.
+    queue: K,
+    /// A description of the item.
+    label: &'static str,
 }
 
 /// State that wraps queue and its event count.
@@ -57,6 +84,8 @@ struct QueueState<I> {
     /// Do not modify this unless you are holding the `queue` lock.
     event_count: AtomicUsize,
     queue: Mutex<VecDeque<I>>,
+    /// Whether or not the queue is sealed (not accepting any more items).
+    sealed: AtomicBool,
 }
 
 impl<I> QueueState<I> {
@@ -64,6 +93,7 @@ impl<I> QueueState<I> {
         QueueState {
             event_count: AtomicUsize::new(0),
             queue: Mutex::new(VecDeque::new()),
+            sealed: AtomicBool::new(false),
         }
     }
 
@@ -131,6 +161,63 @@ where
     queues: BTreeMap<K, &'a VecDeque<I>>,
 }
 
+/// The current depth of a queue, along with the rate at which items were taken from it recently.
+#[derive(Debug, Serialize)]
+pub(crate) struct QueueActivity<K> {
+    /// The queue.
+    pub(crate) queue: K,
+    /// Number of items currently in the queue.
+    pub(crate) depth: usize,
+    /// Number of items taken from the queue within the inspected window.
+    pub(crate) dequeued: usize,
+    /// Average number of items taken from the queue per second within the inspected window.
+    pub(crate) dequeue_rate: f64,
+}
+
+/// Number of items with the same description taken from the queues.
+#[derive(Debug, Serialize)]
+pub(crate) struct LabelCount {
+    /// The description of the items.
+    pub(crate) label: &'static str,
+    /// Number of items taken within the inspected window.
+    pub(crate) count: usize,
+}
+
+/// A snapshot of the depths of all queues, along with the items taken from them recently.
+#[derive(Debug, Serialize)]
+pub(crate) struct QueueInspection<K> {
+    /// Length of the inspected window in seconds.
+    ///
+    /// May be shorter than requested if more items were taken from the queues than are
+    /// remembered.
+    pub(crate) window_secs: f64,
+    /// The activity of each queue, in scheduling order.
+    pub(crate) queues: Vec<QueueActivity<K>>,
+    /// The most frequent descriptions of the items taken within the window, most frequent first.
+    pub(crate) top_labels: Vec<LabelCount>,
+}
+
+impl<K: Display> Display for QueueInspection<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "queues (last {:.1} seconds):", self.window_secs)?;
+        for activity in &self.queues {
+            writeln!(
+                f,
+                "  {:<24}{:>10} queued{:>10} dequeued{:>12.1}/s",
+                activity.queue.to_string(),
+                activity.depth,
+                activity.dequeued,
+                activity.dequeue_rate
+            )?;
+        }
+        writeln!(f, "top events:")?;
+        for label_count in &self.top_labels {
+            writeln!(f, "  {:<40}{:>10}", label_count.label, label_count.count)?;
+        }
+        Ok(())
+    }
+}
+
 impl<I, K> WeightedRoundRobin<I, K>
 where
     I: Debug,
@@ -169,6 +256,7 @@ where
             total: Semaphore::new(0),
             sealed: AtomicBool::new(false),
             recent_event_count_peak: initial_event_count_threshold.map(AtomicUsize::new),
+            dequeue_history: sync::Mutex::new(VecDeque::new()),
         }
     }
 
@@ -226,11 +314,15 @@ where
             return;
         }
 
-        self.queues
+        let queue_state = self
+            .queues
             .get(&queue)
-            .expect("tried to push to non-existent queue")
-            .push_back(item)
-            .await;
+            .expect("tried to push to non-existent queue");
+        if queue_state.sealed.load(Ordering::SeqCst) {
+            debug!(%queue, "queue sealed, dropping item");
+            return;
+        }
+        queue_state.push_back(item).await;
 
         // NOTE: Count may be off by one b/c of the way locking works when elements are popped.
         // It's fine for its purposes.
@@ -332,8 +424,17 @@ where
         self.sealed.store(true, Ordering::SeqCst);
     }
 
+    /// Seals a single queue, preventing it from accepting any more items.
+    ///
+    /// Items pushed into the queue via `push` will be dropped immediately, while the items already
+    /// in it are still returned by `pop`.
+    pub(crate) fn seal_queue(&self, queue: K) {
+        if let Some(queue_state) = self.queues.get(&queue) {
+            queue_state.sealed.store(true, Ordering::SeqCst);
+        }
+    }
+
     /// Returns the number of events currently in the queue.
-    #[cfg(test)]
     pub(crate) fn item_count(&self) -> usize {
         self.total.available_permits()
     }
@@ -345,6 +446,96 @@ where
             .map(|(key, queue)| (*key, queue.event_count()))
             .collect()
     }
+
+    /// Remembers that an item described by `label` was taken from `queue`, for inspection.
+    ///
+    /// Items are forgotten once they are older than `DEQUEUE_HISTORY_MAX_AGE`, or once too many
+    /// newer ones were recorded.
+    pub(crate) fn record_dequeue(&self, queue: K, label: &'static str) {
+        let mut history = match self.dequeue_history.lock() {
+            Ok(history) => history,
+            Err(_) => {
+                error!("could not record dequeued item, lock poisoned");
+                return;
+            }
+        };
+        let now = Instant::now();
+        while history.front().map_or(false, |oldest| {
+            history.len() >= DEQUEUE_HISTORY_MAX_LEN
+                || now.duration_since(oldest.at) > DEQUEUE_HISTORY_MAX_AGE
+        }) {
+            history.pop_front();
+        }
+        history.push_back(Dequeued {
+            at: now,
+            queue,
+            label,
+        });
+    }
+
+    /// Returns the current depth of every queue, the rate at which items were taken from each
+    /// within the last `window`, and the `top` most frequent descriptions of those items.
+    ///
+    /// The window is capped at `DEQUEUE_HISTORY_MAX_AGE`.
+    pub(crate) fn inspect(&self, window: Duration, top: usize) -> QueueInspection<K> {
+        let now = Instant::now();
+        let mut window = window.min(DEQUEUE_HISTORY_MAX_AGE);
+        let mut dequeued: HashMap<K, usize> = HashMap::new();
+        let mut labels: HashMap<&'static str, usize> = HashMap::new();
+        match self.dequeue_history.lock() {
+            Ok(history) => {
+                // If the history is full, older items within the window have been forgotten.
+                if history.len() >= DEQUEUE_HISTORY_MAX_LEN {
+                    if let Some(oldest) = history.front() {
+                        window = window.min(now.duration_since(oldest.at));
+                    }
+                }
+                for item in history
+                    .iter()
+                    .rev()
+                    .take_while(|item| now.duration_since(item.at) <= window)
+                {
+                    *dequeued.entry(item.queue).or_default() += 1;
+                    *labels.entry(item.label).or_default() += 1;
+                }
+            }
+            Err(_) => error!("could not inspect dequeued items, lock poisoned"),
+        }
+
+        let window_secs = window.as_secs_f64();
+        let queues = self
+            .slots
+            .iter()
+            .map(|slot| {
+                let count = dequeued.get(&slot.key).copied().unwrap_or_default();
+                QueueActivity {
+                    queue: slot.key,
+                    depth: self
+                        .queues
+                        .get(&slot.key)
+                        .map_or(0, |queue| queue.event_count()),
+                    dequeued: count,
+                    dequeue_rate: if window_secs > 0.0 {
+                        count as f64 / window_secs
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        let mut top_labels: Vec<_> = labels
+            .into_iter()
+            .map(|(label, count)| LabelCount { label, count })
+            .collect();
+        top_labels.sort_by_key(|label_count| (Reverse(label_count.count), label_count.label));
+        top_labels.truncate(top);
+
+        QueueInspection {
+            window_secs,
+            queues,
+            top_labels,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -427,6 +618,59 @@ mod tests {
         assert!(scheduler.drain_queues().await.is_empty());
     }
 
+    #[tokio::test]
+    async fn should_inspect_queues() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights(), None);
+        scheduler.push('a', QueueKind::One).await;
+        scheduler.push('b', QueueKind::Two).await;
+        scheduler.push('c', QueueKind::Two).await;
+
+        scheduler.record_dequeue(QueueKind::One, "first");
+        scheduler.record_dequeue(QueueKind::Two, "second");
+        scheduler.record_dequeue(QueueKind::Two, "second");
+        scheduler.record_dequeue(QueueKind::Two, "third");
+
+        let inspection = scheduler.inspect(Duration::from_secs(60), 2);
+        assert_eq!(inspection.window_secs, 60.0);
+        let activity: Vec<_> = inspection
+            .queues
+            .iter()
+            .map(|activity| (activity.queue, activity.depth, activity.dequeued))
+            .collect();
+        assert_eq!(
+            activity,
+            vec![(QueueKind::One, 1, 1), (QueueKind::Two, 2, 3)]
+        );
+        let top_labels: Vec<_> = inspection
+            .top_labels
+            .iter()
+            .map(|label_count| (label_count.label, label_count.count))
+            .collect();
+        assert_eq!(top_labels, vec![("second", 2), ("first", 1)]);
+
+        // The window is capped at the maximum age of remembered items.
+        let inspection = scheduler.inspect(Duration::from_secs(3_600), 2);
+        assert_eq!(
+            inspection.window_secs,
+            DEQUEUE_HISTORY_MAX_AGE.as_secs_f64()
+        );
+    }
+
+    #[tokio::test]
+    async fn can_seal_single_queue() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights(), None);
+        scheduler.push('a', QueueKind::One).await;
+
+        scheduler.seal_queue(QueueKind::One);
+        scheduler.push('b', QueueKind::One).await;
+        scheduler.push('c', QueueKind::Two).await;
+        assert_eq!(scheduler.item_count(), 2);
+
+        assert_eq!(('a', QueueKind::One), scheduler.pop().await);
+        assert_eq!(('c', QueueKind::Two), scheduler.pop().await);
+        assert_eq!(scheduler.item_count(), 0);
+    }
+
     #[test]
     fn should_calculate_dump_threshold() {
         let total = 0;
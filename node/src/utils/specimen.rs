@@ -31,9 +31,10 @@ use crate::{
     },
     protocol::Message,
     types::{
-        ApprovalsHash, ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHash,
-        BlockHeader, BlockPayload, Deploy, DeployHashWithApprovals, DeployId, FinalitySignature,
-        FinalitySignatureId, FinalizedBlock, LegacyDeploy, SyncLeap, TrieOrChunk,
+        ApprovalsHash, ApprovalsHashes, Block, BlockBodyOrChunk, BlockExecutionResultsOrChunk,
+        BlockHash, BlockHeader, BlockPayload, Deploy, DeployHashWithApprovals, DeployId,
+        FinalitySignature, FinalitySignatureId, FinalizedBlock, LegacyDeploy, LightClientUpdate,
+        SyncLeap, TrieOrChunk,
     },
 };
 
@@ -418,9 +419,9 @@ where
                 //
                 // 1. The required seed bytes for Ed25519 and Secp256k1 are both the same length of
                 //    32 bytes.
-                // 2. While Secp256k1 does not allow the most trivial seed bytes of 0x00..0001, a
-                //    a hash function output seems to satisfy it, and our current hashing scheme
-                //    also output 32 bytes.
+                // 2. While Secp256k1 does not allow the most trivial seed bytes of 0x00..0001, a a
+                //    hash function output seems to satisfy it, and our current hashing scheme also
+                //    output 32 bytes.
                 let seed_bytes = Digest::hash(seed.to_be_bytes()).value();
 
                 match variant {
@@ -771,6 +772,12 @@ pub(crate) fn largest_get_request<E: SizeEstimator>(estimator: &E, cache: &mut C
             Tag::BlockExecutionResults => Message::new_get_request::<BlockExecutionResultsOrChunk>(
                 &LargestSpecimen::largest_specimen(estimator, cache),
             ),
+            Tag::LightClientUpdate => Message::new_get_request::<LightClientUpdate>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
+            Tag::BlockBodyOrChunk => Message::new_get_request::<BlockBodyOrChunk>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
         }
         .expect("did not expect new_get_request from largest deploy to fail")
     })
@@ -809,6 +816,12 @@ pub(crate) fn largest_get_response<E: SizeEstimator>(estimator: &E, cache: &mut
                     &LargestSpecimen::largest_specimen(estimator, cache),
                 )
             }
+            Tag::LightClientUpdate => Message::new_get_response::<LightClientUpdate>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
+            Tag::BlockBodyOrChunk => Message::new_get_response::<BlockBodyOrChunk>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
         }
         .expect("did not expect new_get_response from largest deploy to fail")
     })
@@ -0,0 +1,89 @@
+//! A fixed-size pool of threads running blocking tasks.
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// A handle to a pool of threads, which exit once all handles are dropped.
+#[derive(Clone, Debug)]
+pub(crate) struct ThreadPool {
+    sender: mpsc::Sender<Task>,
+}
+
+impl ThreadPool {
+    /// Spawns `thread_count` threads, named after `name`.
+    pub(crate) fn new(name: &str, thread_count: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Task>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..thread_count.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let spawn_result = thread::Builder::new()
+                .name(format!("{}-{}", name, index))
+                .spawn(move || loop {
+                    // Only hold the lock while waiting for the next task, not while running it.
+                    let maybe_task = receiver.lock().expect("receiver mutex poisoned").recv();
+                    let task = match maybe_task {
+                        Ok(task) => task,
+                        Err(mpsc::RecvError) => {
+                            debug!("thread pool closed, exiting");
+                            return;
+                        }
+                    };
+                    // A panicking task drops its result sender, which is reported to the caller,
+                    // so the thread can carry on with the next task.
+                    let _ = panic::catch_unwind(AssertUnwindSafe(task));
+                });
+            if let Err(error) = spawn_result {
+                warn!(%error, %name, index, "failed to spawn thread pool thread");
+            }
+        }
+        ThreadPool { sender }
+    }
+
+    /// Runs `task` on the pool, returning a receiver for its result.
+    ///
+    /// The receiver fails if the task panics.
+    pub(crate) fn run<T, V>(&self, task: T) -> oneshot::Receiver<V>
+    where
+        T: 'static + Send + FnOnce() -> V,
+        V: 'static + Send,
+    {
+        let (result_sender, result_receiver) = oneshot::channel();
+        let task = Box::new(move || {
+            // The caller may have stopped waiting for the result.
+            let _ = result_sender.send(task());
+        });
+        if self.sender.send(task).is_err() {
+            // Cannot happen, as the threads only exit once all senders are dropped.
+            warn!("thread pool closed, dropping task");
+        }
+        result_receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_run_tasks_and_survive_panics() {
+        let thread_pool = ThreadPool::new("test", 1);
+
+        let panicking = thread_pool.run(|| -> u32 { panic!("task panicked") });
+        assert!(panicking.await.is_err());
+
+        let results: Vec<_> = (0..5u32)
+            .map(|value| thread_pool.run(move || value * 2))
+            .collect();
+        for (value, result) in results.into_iter().enumerate() {
+            assert_eq!(result.await.unwrap(), value as u32 * 2);
+        }
+    }
+}
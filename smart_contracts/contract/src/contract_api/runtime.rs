@@ -359,6 +359,28 @@ pub fn random_bytes() -> [u8; RANDOM_BYTES_COUNT] {
     ret
 }
 
+/// Returns whether `proof` is a valid Groth16 proof over the BN254 curve for `public_inputs`.
+///
+/// All arguments are in the compressed canonical serialization of arkworks, the public inputs as a
+/// vector of scalar field elements. Reverts with [`ApiError::Deserialize`] or
+/// [`ApiError::LeftOverBytes`] if any of them is malformed.
+pub fn verify_groth16(verifying_key: &[u8], proof: &[u8], public_inputs: &[u8]) -> bool {
+    let mut is_valid = 0u8;
+    let result = unsafe {
+        ext_ffi::casper_verify_groth16(
+            verifying_key.as_ptr(),
+            verifying_key.len(),
+            proof.as_ptr(),
+            proof.len(),
+            public_inputs.as_ptr(),
+            public_inputs.len(),
+            &mut is_valid,
+        )
+    };
+    api_error::result_from(result).unwrap_or_revert();
+    is_valid != 0
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, ApiError> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
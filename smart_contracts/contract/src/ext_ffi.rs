@@ -805,4 +805,29 @@ extern "C" {
         contract_hash_ptr: *const u8,
         contract_hash_size: usize,
     ) -> i32;
+    /// Verifies a Groth16 proof over the BN254 curve. Returns non-zero standard error for a
+    /// failure, otherwise a zero indicates success.
+    ///
+    /// Only available once the `zk_host_functions` feature is activated in the chainspec; before
+    /// that, modules importing it are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifying_key_ptr` - pointer to the verifying key, in the compressed canonical
+    ///   serialization of arkworks.
+    /// * `verifying_key_size` - size of the verifying key.
+    /// * `proof_ptr` - pointer to the proof, in the same serialization.
+    /// * `proof_size` - size of the proof, which must be 128 bytes.
+    /// * `public_inputs_ptr` - pointer to the vector of public inputs, in the same serialization.
+    /// * `public_inputs_size` - size of the public inputs.
+    /// * `out_ptr` - pointer to a byte which is set to 1 if the proof is valid and 0 otherwise.
+    pub fn casper_verify_groth16(
+        verifying_key_ptr: *const u8,
+        verifying_key_size: usize,
+        proof_ptr: *const u8,
+        proof_size: usize,
+        public_inputs_ptr: *const u8,
+        public_inputs_size: usize,
+        out_ptr: *mut u8,
+    ) -> i32;
 }
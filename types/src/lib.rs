@@ -42,6 +42,7 @@ mod gas;
 pub mod gens;
 mod json_pretty_printer;
 mod key;
+pub mod merkle_proof;
 mod motes;
 mod named_key;
 mod phase;
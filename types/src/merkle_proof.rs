@@ -0,0 +1,351 @@
+//! Verification of Merkle proofs of global state entries.
+//!
+//! Global state is held in a Merkle trie, each node of which is identified by the BLAKE2b hash of
+//! its serialized form.  A proof that an entry is held under a given state root hash consists of
+//! the entry along with one step per ancestor of its leaf, each step holding the ancestor's other
+//! children.  Recomputing the hashes from the leaf up to the root and comparing the result to the
+//! state root hash verifies the entry.
+//!
+//! The hashing is implemented here rather than in the storage layer, so that the node and any
+//! external tooling verifying proofs share a single implementation.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+
+use crate::{
+    blake2b,
+    bytesrepr::{self, Bytes, ToBytes, OPTION_NONE_TAG, OPTION_SOME_TAG},
+    BLAKE2B_DIGEST_LENGTH,
+};
+
+/// The number of children of a node of the global state trie.
+pub const TRIE_RADIX: usize = 256;
+/// The size of the chunks a serialized trie larger than this is split into for hashing.
+pub const TRIE_CHUNK_SIZE_BYTES: usize = 8 * 1024 * 1024;
+
+/// The tag a serialized leaf of the global state trie starts with.
+pub const TRIE_LEAF_TAG: u8 = 0;
+/// The tag a serialized node of the global state trie starts with.
+pub const TRIE_NODE_TAG: u8 = 1;
+/// The tag a serialized extension of the global state trie starts with.
+pub const TRIE_EXTENSION_TAG: u8 = 2;
+/// The tag of a serialized pointer to a leaf.
+pub const LEAF_POINTER_TAG: u8 = 0;
+/// The tag of a serialized pointer to a node or extension.
+pub const NODE_POINTER_TAG: u8 = 1;
+
+/// A pointer from a node or extension of the global state trie to one of its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrieProofPointer {
+    /// A pointer to a leaf, identified by its hash.
+    Leaf([u8; BLAKE2B_DIGEST_LENGTH]),
+    /// A pointer to a node or extension, identified by its hash.
+    Node([u8; BLAKE2B_DIGEST_LENGTH]),
+}
+
+impl TrieProofPointer {
+    fn write_bytes(&self, writer: &mut Vec<u8>) {
+        let (tag, hash) = match self {
+            TrieProofPointer::Leaf(hash) => (LEAF_POINTER_TAG, hash),
+            TrieProofPointer::Node(hash) => (NODE_POINTER_TAG, hash),
+        };
+        writer.push(tag);
+        writer.extend_from_slice(hash);
+    }
+}
+
+/// A step of a proof, from a child in the global state trie to its parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrieProofStep {
+    /// The parent is a node.
+    Node {
+        /// The index of the child the proof passes through.
+        hole_index: u8,
+        /// The pointers to the node's other children, along with their indices.
+        indexed_pointers_with_hole: Vec<(u8, TrieProofPointer)>,
+    },
+    /// The parent is an extension.
+    Extension {
+        /// The extension's affix.
+        affix: Bytes,
+    },
+}
+
+/// Error verifying a Merkle proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MerkleProofError {
+    /// The entry the proof is for couldn't be serialized.
+    Serialization(bytesrepr::Error),
+    /// The root hash computed from the proof differs from the expected one.
+    RootHashMismatch {
+        /// The expected root hash.
+        expected: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The root hash computed from the proof.
+        computed: [u8; BLAKE2B_DIGEST_LENGTH],
+    },
+}
+
+impl Display for MerkleProofError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            MerkleProofError::Serialization(error) => {
+                write!(formatter, "failed to serialize proven entry: {}", error)
+            }
+            MerkleProofError::RootHashMismatch { expected, computed } => write!(
+                formatter,
+                "Merkle proof root hash mismatch: expected {}, computed {}",
+                base16::encode_lower(expected),
+                base16::encode_lower(computed)
+            ),
+        }
+    }
+}
+
+impl From<bytesrepr::Error> for MerkleProofError {
+    fn from(error: bytesrepr::Error) -> Self {
+        MerkleProofError::Serialization(error)
+    }
+}
+
+/// Computes the root hash of the global state trie holding `value` under `key`, given the steps
+/// of a proof ordered from the leaf up to the root.
+///
+/// Without any steps, this is the hash of the leaf itself.
+pub fn compute_trie_root_hash<'a, K, V, I>(
+    key: &K,
+    value: &V,
+    proof_steps: I,
+) -> Result<[u8; BLAKE2B_DIGEST_LENGTH], bytesrepr::Error>
+where
+    K: ToBytes,
+    V: ToBytes,
+    I: IntoIterator<Item = &'a TrieProofStep>,
+{
+    let mut hash = compute_trie_leaf_hash(key, value)?;
+    for (index, proof_step) in proof_steps.into_iter().enumerate() {
+        let pointer = if index == 0 {
+            TrieProofPointer::Leaf(hash)
+        } else {
+            TrieProofPointer::Node(hash)
+        };
+        hash = blake2b(trie_proof_step_bytes(proof_step, &pointer)?);
+    }
+    Ok(hash)
+}
+
+/// Computes the hash of the leaf of the global state trie holding `value` under `key`.
+pub fn compute_trie_leaf_hash<K: ToBytes, V: ToBytes>(
+    key: &K,
+    value: &V,
+) -> Result<[u8; BLAKE2B_DIGEST_LENGTH], bytesrepr::Error> {
+    let mut leaf_bytes =
+        Vec::with_capacity(1 + key.serialized_length() + value.serialized_length());
+    leaf_bytes.push(TRIE_LEAF_TAG);
+    key.write_bytes(&mut leaf_bytes)?;
+    value.write_bytes(&mut leaf_bytes)?;
+    Ok(hash_trie_bytes(&leaf_bytes))
+}
+
+/// Returns the serialized node or extension a proof step passes through, given the pointer to the
+/// child the proof comes from.
+///
+/// A node or extension is hashed with plain BLAKE2b, as it is never larger than a single chunk.
+pub fn trie_proof_step_bytes(
+    proof_step: &TrieProofStep,
+    child: &TrieProofPointer,
+) -> Result<Vec<u8>, bytesrepr::Error> {
+    let mut bytes = Vec::new();
+    match proof_step {
+        TrieProofStep::Node {
+            hole_index,
+            indexed_pointers_with_hole,
+        } => {
+            let mut pointer_block = [None; TRIE_RADIX];
+            for (child_index, sibling) in indexed_pointers_with_hole {
+                pointer_block[*child_index as usize] = Some(sibling);
+            }
+            pointer_block[*hole_index as usize] = Some(child);
+            bytes.push(TRIE_NODE_TAG);
+            for maybe_pointer in pointer_block.iter() {
+                match maybe_pointer {
+                    None => bytes.push(OPTION_NONE_TAG),
+                    Some(pointer) => {
+                        bytes.push(OPTION_SOME_TAG);
+                        pointer.write_bytes(&mut bytes);
+                    }
+                }
+            }
+        }
+        TrieProofStep::Extension { affix } => {
+            bytes.push(TRIE_EXTENSION_TAG);
+            affix.write_bytes(&mut bytes)?;
+            child.write_bytes(&mut bytes);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Verifies that the global state trie with root hash `state_root_hash` holds `value` under
+/// `key`, given the steps of a proof ordered from the leaf up to the root.
+pub fn verify_trie_proof<'a, K, V, I>(
+    key: &K,
+    value: &V,
+    proof_steps: I,
+    state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+) -> Result<(), MerkleProofError>
+where
+    K: ToBytes,
+    V: ToBytes,
+    I: IntoIterator<Item = &'a TrieProofStep>,
+{
+    let computed = compute_trie_root_hash(key, value, proof_steps)?;
+    if computed != *state_root_hash {
+        return Err(MerkleProofError::RootHashMismatch {
+            expected: *state_root_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Hashes a serialized trie.
+///
+/// Tries larger than a single chunk are split into chunks, whose hashes form the leaves of a
+/// Merkle tree.  The root of the tree is hashed along with the number of chunks, prefixed with a
+/// zeroed chunk so that no serialized trie small enough to be hashed directly can collide with it.
+fn hash_trie_bytes(bytes: &[u8]) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    if bytes.len() <= TRIE_CHUNK_SIZE_BYTES {
+        return blake2b(bytes);
+    }
+
+    let mut hashes: Vec<_> = bytes.chunks(TRIE_CHUNK_SIZE_BYTES).map(blake2b).collect();
+    let chunk_count = hashes.len() as u64;
+    while hashes.len() > 1 {
+        hashes = hashes
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_pair(left, right),
+                _ => pair[0],
+            })
+            .collect();
+    }
+
+    let mut hasher = VarBlake2b::new(BLAKE2B_DIGEST_LENGTH).expect("should create hasher");
+    let zeroes = [0u8; 1024];
+    for _ in 0..TRIE_CHUNK_SIZE_BYTES / zeroes.len() {
+        hasher.update(zeroes);
+    }
+    hasher.update(chunk_count.to_le_bytes());
+    hasher.update(hashes[0]);
+    let mut result = [0; BLAKE2B_DIGEST_LENGTH];
+    hasher.finalize_variable(|slice| result.copy_from_slice(slice));
+    result
+}
+
+fn hash_pair(left: &[u8], right: &[u8]) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    let mut hasher = VarBlake2b::new(BLAKE2B_DIGEST_LENGTH).expect("should create hasher");
+    hasher.update(left);
+    hasher.update(right);
+    let mut result = [0; BLAKE2B_DIGEST_LENGTH];
+    hasher.finalize_variable(|slice| result.copy_from_slice(slice));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CLValue, Key, StoredValue};
+
+    fn leaf_bytes(key: &Key, value: &StoredValue) -> Vec<u8> {
+        let mut bytes = vec![TRIE_LEAF_TAG];
+        bytes.extend(key.to_bytes().unwrap());
+        bytes.extend(value.to_bytes().unwrap());
+        bytes
+    }
+
+    #[test]
+    fn should_verify_proof_through_node_and_extension() {
+        let key = Key::Hash([1; 32]);
+        let value = StoredValue::CLValue(CLValue::from_t(7_u64).unwrap());
+        let leaf_hash = blake2b(leaf_bytes(&key, &value));
+        let sibling = TrieProofPointer::Node([9; 32]);
+
+        // A node with the leaf at index 3 and a sibling at index 200, under an extension.
+        let mut node_bytes = vec![TRIE_NODE_TAG];
+        for index in 0..TRIE_RADIX {
+            match index {
+                3 => {
+                    node_bytes.extend([OPTION_SOME_TAG, LEAF_POINTER_TAG]);
+                    node_bytes.extend(leaf_hash);
+                }
+                200 => {
+                    node_bytes.extend([OPTION_SOME_TAG, NODE_POINTER_TAG]);
+                    node_bytes.extend([9; 32]);
+                }
+                _ => node_bytes.push(OPTION_NONE_TAG),
+            }
+        }
+        let node_hash = blake2b(&node_bytes);
+        let affix = Bytes::from(vec![4, 5]);
+        let mut extension_bytes = vec![TRIE_EXTENSION_TAG];
+        extension_bytes.extend(affix.to_bytes().unwrap());
+        extension_bytes.push(NODE_POINTER_TAG);
+        extension_bytes.extend(node_hash);
+        let root_hash = blake2b(&extension_bytes);
+
+        let proof_steps = vec![
+            TrieProofStep::Node {
+                hole_index: 3,
+                indexed_pointers_with_hole: vec![(200, sibling)],
+            },
+            TrieProofStep::Extension { affix },
+        ];
+        assert_eq!(
+            compute_trie_root_hash(&key, &value, &proof_steps),
+            Ok(root_hash)
+        );
+        assert_eq!(
+            verify_trie_proof(&key, &value, &proof_steps, &root_hash),
+            Ok(())
+        );
+
+        let other_value = StoredValue::CLValue(CLValue::from_t(8_u64).unwrap());
+        assert!(matches!(
+            verify_trie_proof(&key, &other_value, &proof_steps, &root_hash),
+            Err(MerkleProofError::RootHashMismatch { expected, .. }) if expected == root_hash
+        ));
+    }
+
+    #[test]
+    fn should_hash_leaf_without_proof_steps() {
+        let key = Key::Hash([2; 32]);
+        let value = StoredValue::CLValue(CLValue::unit());
+        assert_eq!(
+            compute_trie_root_hash(&key, &value, &[]),
+            Ok(blake2b(leaf_bytes(&key, &value)))
+        );
+    }
+
+    #[test]
+    fn should_hash_large_trie_as_merkle_tree_of_chunks() {
+        let bytes: Vec<u8> = (0..2 * TRIE_CHUNK_SIZE_BYTES + 1)
+            .map(|index| index as u8)
+            .collect();
+        let chunk_hashes: Vec<_> = bytes.chunks(TRIE_CHUNK_SIZE_BYTES).map(blake2b).collect();
+        let root = hash_pair(
+            &hash_pair(&chunk_hashes[0], &chunk_hashes[1]),
+            &chunk_hashes[2],
+        );
+        let mut preimage = vec![0u8; TRIE_CHUNK_SIZE_BYTES];
+        preimage.extend(3_u64.to_le_bytes());
+        preimage.extend(root);
+        assert_eq!(hash_trie_bytes(&bytes), blake2b(preimage));
+        assert_eq!(hash_trie_bytes(&bytes[..10]), blake2b(&bytes[..10]));
+    }
+}